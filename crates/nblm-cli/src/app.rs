@@ -1,31 +1,73 @@
+use std::io::IsTerminal;
+use std::sync::Arc;
 use std::time::Duration;
 
-use anyhow::{anyhow, bail, Result};
+use anyhow::{anyhow, bail, Context, Result};
+use colored::Colorize;
+use reqwest::StatusCode;
 use tracing_subscriber::EnvFilter;
 
-use nblm_core::env::profile_experiment_enabled;
+use nblm_core::env::{profile_experiment_enabled, validate_base_url};
 use nblm_core::{
-    ApiProfile, EnvironmentConfig, NblmClient, ProfileParams, RetryConfig, PROFILE_EXPERIMENT_FLAG,
+    client_options_from_env, language_tag_from_locale, ApiProfile, ClientOptions,
+    EnvironmentConfig, HostFailureConfig, NblmClient, NotebookId, ProfileParams, RetryConfig,
+    TokenProject, TraceContext, PROFILE_EXPERIMENT_FLAG,
 };
 
 use crate::args::{Cli, Command, GlobalArgs};
-use crate::ops::{audio, doctor, notebooks, sources};
-use crate::util::auth::build_token_provider;
+use crate::ops::{api, audio, doctor, notebooks, queue, sources};
+use crate::util::auth::{build_token_provider, ProviderFactory};
+use crate::util::curl;
+use crate::util::deprecation::Deprecations;
+use crate::util::interrupt::{Interrupt, Interrupted};
+use crate::util::output::{OutputSink, StdStreams};
+use crate::util::parse;
+use crate::util::rate_limit::{self, RateLimits};
+use crate::util::redact::{RedactingSink, Redactor};
+use crate::util::status;
+use crate::util::timings::Timings;
 
 pub struct NblmApp {
     cli: Cli,
-    client: NblmClient,
+    environment: EnvironmentConfig,
+    options: ClientOptions,
+    trace_context: Option<TraceContext>,
+    provider_factory: ProviderFactory,
+    output: Box<dyn OutputSink>,
+    /// Set by `--redact`; also applied to the error `main` prints.
+    redactor: Option<Arc<Redactor>>,
+    rate_limits: Arc<RateLimits>,
 }
 
+const TRACEPARENT_ENV: &str = "TRACEPARENT";
+const TRACESTATE_ENV: &str = "TRACESTATE";
+
 impl NblmApp {
     pub fn new(cli: Cli) -> Result<Self> {
+        Self::with_provider_factory(cli, Box::new(build_token_provider))
+    }
+
+    /// Validate the global settings without touching credentials. `provider_factory` runs only
+    /// when a command is about to call the API (see [`NblmApp::run`]).
+    pub fn with_provider_factory(cli: Cli, provider_factory: ProviderFactory) -> Result<Self> {
         init_logging();
 
         if cli.global.debug_http {
-            std::env::set_var("NBLM_DEBUG_HTTP", "1");
+            nblm_core::set_debug_http(true);
         }
 
-        let provider = build_token_provider(&cli.global)?;
+        // Checked before anything else so a bad override is reported against its source rather
+        // than as a URL parse failure deep in the client.
+        let base_url = match &cli.global.base_url {
+            Some(base) => Some(validate_base_url(base).with_context(|| {
+                format!(
+                    "{} is not a valid base URL",
+                    base_url_source(std::env::args())
+                )
+            })?),
+            None => None,
+        };
+
         let profile: ApiProfile = cli.global.profile.into();
         if profile.requires_experimental_flag() && !profile_experiment_enabled() {
             bail!(
@@ -37,45 +79,443 @@ impl NblmApp {
 
         // TODO(profile-docs): Document profile selection once additional SKUs are available publicly.
         let params = resolve_profile_params(&cli.global, profile)?;
-        let environment = EnvironmentConfig::from_profile(profile, params)?;
-        let mut client = NblmClient::new(provider, environment)?;
+        let mut environment = EnvironmentConfig::from_profile(profile, params)?;
+        if let Some(version) = cli.global.api_version {
+            environment = environment.with_api_version(version);
+        }
+        parse::check_notebook_names(environment.parent_path())?;
 
+        // NBLM_MAX_RETRIES, NBLM_RETRY_*_DELAY and NBLM_TIMEOUT first, then the flags on top.
+        let mut options = client_options_from_env()?;
         if let Some(timeout) = cli.global.timeout {
-            client = client.with_timeout(timeout);
+            options.timeout = Some(timeout);
+        }
+        if let Some(delay) = cli.global.retry_min_delay {
+            options.retry = options.retry.with_min_delay(delay);
         }
-
         // Use fast retry config for tests to avoid slow retries
-        let retry_config = if std::env::var_os("NBLM_RETRY_FAST").is_some() {
-            RetryConfig::default()
+        if std::env::var_os("NBLM_RETRY_FAST").is_some() {
+            options.retry = RetryConfig::default()
                 .with_min_delay(Duration::from_millis(5))
                 .with_max_delay(Duration::from_millis(20))
-                .with_max_retries(2)
-        } else {
-            RetryConfig::default()
-        };
-        client = client.with_retry_config(retry_config);
+                .with_max_retries(2);
+        }
+        // --no-retry wins over every retry setting above.
+        if cli.global.no_retry {
+            options.retry = RetryConfig::disabled();
+        }
+        let inline_status = !cli.global.quiet && std::io::stderr().is_terminal();
+        let report_retry = status::retry_reporter(inline_status);
+        let rate_limits = RateLimits::new();
+        let record_rate_limit = rate_limits.hook();
+        options.retry = options.retry.with_on_retry(move |event| {
+            record_rate_limit(event);
+            report_retry(event);
+        });
 
-        if let Some(base) = &cli.global.base_url {
-            client = client.with_base_url(base)?;
+        let redactor = cli.global.redact.map(|mode| {
+            let project_number = cli.global.project_number.as_deref().map(str::trim);
+            Arc::new(Redactor::new(mode, project_number.map(str::to_string)))
+        });
+        let output: Box<dyn OutputSink> = match &redactor {
+            Some(redactor) => Box::new(RedactingSink::new(
+                Box::new(StdStreams),
+                Arc::clone(redactor),
+            )),
+            None => Box::new(StdStreams),
+        };
+        if cli.global.insecure_skip_tls_verify {
+            output.err(
+                &"WARNING: TLS certificate verification is disabled (--insecure-skip-tls-verify). \
+                  Responses can be intercepted or forged; use this only with local mock servers."
+                    .red()
+                    .bold()
+                    .to_string(),
+            );
+            ensure_insecure_tls_allowed(base_url.as_deref().unwrap_or(environment.base_url()))?;
         }
+        let trace_context = trace_context_from_env(output.as_ref());
 
-        Ok(Self { cli, client })
+        Ok(Self {
+            cli,
+            environment,
+            options,
+            trace_context,
+            provider_factory,
+            output,
+            redactor,
+            rate_limits,
+        })
     }
 
+    /// Run the command. The token provider and client are built just before an API command is
+    /// dispatched, so auth failures still surface before the command prints anything.
     pub async fn run(self) -> Result<()> {
-        let NblmApp { cli, client } = self;
+        let NblmApp {
+            cli,
+            environment,
+            options,
+            trace_context,
+            provider_factory,
+            output,
+            redactor,
+            rate_limits,
+        } = self;
+        let out = output.as_ref();
+        let timings = cli.global.timings.then(Timings::start);
+        let fans_out = fans_out(&cli.command);
+        let reads_repeatedly = reads_repeatedly(&cli.command);
+        let deprecations = Deprecations::new();
+        let connect = || {
+            let client = build_client(
+                &cli.global,
+                &provider_factory,
+                environment.clone(),
+                options.clone(),
+            )?;
+            let client = match &trace_context {
+                Some(context) => client.with_trace_context(context.clone()),
+                None => client,
+            }
+            .with_deprecation_observer(deprecations.hook());
+            let client = if fans_out {
+                client.with_host_failure_cache(HostFailureConfig::default())
+            } else {
+                client
+            };
+            let client = if reads_repeatedly {
+                client.with_request_cache()
+            } else {
+                client
+            };
+            let client = if cli.global.show_curl {
+                let redactor = redactor.clone();
+                client.with_request_observer(move |request| {
+                    let command = curl::curl_command(request);
+                    match &redactor {
+                        Some(redactor) => status::show(&redactor.text(&command), false),
+                        None => status::show(&command, false),
+                    }
+                })
+            } else {
+                client
+            };
+            Ok::<_, anyhow::Error>(match &timings {
+                Some(timings) => client.with_observer(timings.observer()),
+                None => client,
+            })
+        };
+
+        // One client per invocation: the token check, the command and the explanation of a
+        // failure all share its provider.
+        let client = uses_api(&cli.command).then(connect).transpose()?;
+        let api = || {
+            client
+                .as_ref()
+                .expect("uses_api covers every command that calls the API")
+        };
+        if let (true, Some(client)) = (cli.global.verify_token_project, &client) {
+            verify_token_project(client, out).await;
+        }
 
         let json_mode = cli.global.json;
-        match cli.command {
-            Command::Notebooks(cmd) => notebooks::run(cmd, &client, json_mode).await,
-            Command::Sources(cmd) => sources::run(cmd, &client, json_mode).await,
-            Command::Audio(cmd) => audio::run(cmd, &client, json_mode).await,
-            Command::Auth(cmd) => crate::ops::auth::run(cmd).await,
-            Command::Doctor(cmd) => doctor::run(cmd).await,
+        let no_retry = cli.global.no_retry;
+        let adds_sources = matches!(cli.command, Command::Sources(sources::Command::Add(_)));
+        let notebook = notebook_scope(&cli.command);
+        let interactive = cli.global.is_interactive();
+        let interrupt = Interrupt::listen();
+        let result = match cli.command {
+            Command::Notebooks(cmd) => {
+                notebooks::run(cmd, api(), out, json_mode, interactive, &interrupt).await
+            }
+            Command::Sources(cmd) => {
+                sources::run(cmd, api(), out, json_mode, interactive, &interrupt).await
+            }
+            Command::Audio(cmd) => audio::run(cmd, api(), out, json_mode, &interrupt).await,
+            Command::Queue(cmd) => queue::run(cmd, client.as_ref(), out, json_mode).await,
+            Command::Api(args) => api::run(args, api(), out).await,
+            Command::Auth(cmd) => crate::ops::auth::run(cmd, json_mode).await,
+            Command::Doctor(cmd) => doctor::run(cmd, json_mode).await,
+            Command::Init(args) => crate::ops::init::run(args, out).await,
+            Command::Man(args) => crate::ops::man::run(args, out),
+            Command::Commands(args) => crate::ops::commands::run(args, out, json_mode),
+            Command::Validate(args) => crate::ops::validate::run(args, out, json_mode),
+            Command::Fixtures(cmd) => crate::ops::fixtures::run(cmd, api(), out).await,
+        };
+        let result = match (result, notebook, &client) {
+            (Err(err), Some(notebook_id), Some(client)) => {
+                Err(explain_notebook_error(client, notebook_id, err).await)
+            }
+            (result, _, _) => result,
+        };
+        // A failed command's error is printed by main, after any retry status line.
+        status::clear();
+        if let Some(timings) = &timings {
+            timings.report(out, json_mode);
+        }
+        rate_limits.report(out, json_mode);
+        deprecations.report(out, json_mode);
+        let result = match result {
+            Err(err) if adds_sources && rate_limit::is_rate_limited(&err) => Err(anyhow!(
+                "{err:#}\n{}",
+                rate_limit::rerun_hint(&rate_limits.tally(), std::env::args())
+            )),
+            Err(err) => match timeout_hint(&err) {
+                Some(hint) => Err(anyhow!("{err:#}\n{hint}")),
+                None => Err(err),
+            },
+            result => result,
+        };
+        let result = match result {
+            Err(err) if no_retry && would_retry(&err) => Err(anyhow!("{err:#}\n{NO_RETRY_NOTE}")),
+            result => result,
+        };
+        match (result, &redactor) {
+            (Err(err), Some(redactor)) if !err.is::<Interrupted>() => {
+                Err(anyhow!(redactor.text(&format!("{err:#}"))))
+            }
+            (result, _) => result,
+        }
+    }
+}
+
+/// Warn on stderr when the client's token was issued for another project. Never fails the
+/// command: a token whose project cannot be read gets a note, a failed lookup a warning.
+async fn verify_token_project(client: &NblmClient, out: &dyn OutputSink) {
+    match client.check_token_project(None).await {
+        Ok(result) => {
+            if let Some(report) = token_project_report(&result, client.environment().parent_path())
+            {
+                out.err(&report);
+            }
+        }
+        Err(err) => out.err(&format!(
+            "warning: could not verify the access token's project: {err}"
+        )),
+    }
+}
+
+fn token_project_report(result: &TokenProject, parent_path: &str) -> Option<String> {
+    let project_number = parent_path
+        .strip_prefix("projects/")
+        .and_then(|rest| rest.split('/').next())
+        .unwrap_or(parent_path);
+    match result {
+        TokenProject::Matches(_) => None,
+        TokenProject::Mismatch { found, expected } => Some(format!(
+            "{}\n  Requests will fail or act on the wrong project. To switch:\n    \
+             gcloud config set project {project_number}\n    \
+             gcloud auth application-default set-quota-project {project_number}\n  \
+             then refresh the token (with --auth env: export NBLM_ACCESS_TOKEN=$(gcloud auth print-access-token)).",
+            format!("WARNING: the access token was issued for {found}, not {expected}.")
+                .red()
+                .bold()
+        )),
+        TokenProject::Unknown(reason) => {
+            Some(format!("note: token project not verified: {reason}"))
+        }
+    }
+}
+
+/// Commands that talk to the API and so build a client. The other queue commands only touch the
+/// local queue, so they work offline and without credentials.
+fn uses_api(command: &Command) -> bool {
+    matches!(
+        command,
+        Command::Notebooks(_)
+            | Command::Sources(_)
+            | Command::Audio(_)
+            | Command::Queue(queue::Command::Flush)
+            | Command::Api(_)
+            | Command::Fixtures(_)
+    )
+}
+
+/// Advice appended to a request that ran out of time: a longer timeout to rerun with, at least
+/// two minutes and at least double the one that expired.
+fn timeout_hint(err: &anyhow::Error) -> Option<String> {
+    let timeout = err.chain().find_map(|cause| match cause.downcast_ref() {
+        Some(nblm_core::Error::Timeout { timeout, .. }) => Some(*timeout),
+        _ => None,
+    })?;
+    let suggested = (timeout * 2).max(Duration::from_secs(120));
+    let suggested = humantime::format_duration(Duration::from_secs(suggested.as_secs()));
+    Some(format!(
+        "The API did not answer in time. To allow more time per request, rerun with \
+         `nblm --timeout {suggested} ...` or set NBLM_TIMEOUT={suggested}."
+    ))
+}
+
+const NO_RETRY_NOTE: &str = "Retries are disabled (--no-retry or NBLM_NO_RETRY): this is the \
+     first attempt's error, which would otherwise have been retried.";
+
+/// Whether `err` came from a failure the client retries unless `--no-retry` is given.
+fn would_retry(err: &anyhow::Error) -> bool {
+    err.chain()
+        .filter_map(|cause| cause.downcast_ref::<nblm_core::Error>())
+        .any(nblm_core::Error::is_retryable)
+}
+
+/// Build the API client for `args`, creating its token provider with `provider_factory`.
+fn build_client(
+    args: &GlobalArgs,
+    provider_factory: &ProviderFactory,
+    environment: EnvironmentConfig,
+    options: ClientOptions,
+) -> Result<NblmClient> {
+    let provider = provider_factory(args)?;
+    let mut client = NblmClient::new(provider, environment)?.with_options(options);
+    if let Some(base) = &args.base_url {
+        client = client.with_base_url(base)?;
+    }
+    if args.insecure_skip_tls_verify {
+        client = client.with_danger_accept_invalid_certs(true);
+    }
+    if let Some(language) = accept_language(args.lang.as_deref(), |name| std::env::var(name).ok()) {
+        client = client.with_accept_language(&language)?;
+    }
+    Ok(client)
+}
+
+/// Commands that send many independent requests, where an unreachable endpoint would otherwise
+/// cost a connect timeout per item.
+fn fans_out(command: &Command) -> bool {
+    matches!(
+        command,
+        Command::Audio(audio::Command::CreateBatch(_))
+            | Command::Sources(sources::Command::Upload(_))
+            | Command::Sources(sources::Command::Reingest(sources::ReingestArgs {
+                all_web: true,
+                ..
+            }))
+            | Command::Queue(queue::Command::Flush)
+    )
+}
+
+/// The one notebook a command works on, whose 403s and 404s are worth a closer look (see
+/// `NblmClient::explain_notebook_error`). Commands on several notebooks report each failure
+/// themselves.
+fn notebook_scope(command: &Command) -> Option<NotebookId> {
+    let id = match command {
+        Command::Notebooks(notebooks::Command::Update(args)) => &args.notebook_id,
+        Command::Notebooks(notebooks::Command::Budget(args)) => &args.notebook_id,
+        Command::Notebooks(notebooks::Command::Activity(args)) => &args.notebook_id,
+        Command::Notebooks(notebooks::Command::Query(args)) => &args.notebook_id,
+        Command::Notebooks(notebooks::Command::SourcesCount(args)) => {
+            match &args.notebook_ids[..] {
+                [id] => id,
+                _ => return None,
+            }
+        }
+        Command::Sources(sources::Command::Add(args)) => &args.notebook_id,
+        Command::Sources(sources::Command::List(args)) => &args.notebook_id,
+        Command::Sources(sources::Command::Delete(args)) => &args.notebook_id,
+        Command::Sources(sources::Command::Upload(args)) => &args.notebook_id,
+        Command::Sources(sources::Command::Get(args)) => &args.notebook_id,
+        Command::Sources(sources::Command::Search(args)) => args.notebook_id.as_ref()?,
+        Command::Sources(sources::Command::Reingest(args)) => &args.notebook_id,
+        Command::Audio(audio::Command::Create(args)) => &args.notebook_id,
+        Command::Audio(audio::Command::Download(args)) => &args.notebook_id,
+        Command::Audio(audio::Command::Delete(args)) => &args.notebook_id,
+        _ => return None,
+    };
+    Some(id.clone())
+}
+
+/// `err` replaced by what `NblmClient::explain_notebook_error` made of it, for a command on
+/// `notebook_id` that failed with a 403 or 404. Any other error is returned untouched, without
+/// a request.
+async fn explain_notebook_error(
+    client: &NblmClient,
+    notebook_id: NotebookId,
+    err: anyhow::Error,
+) -> anyhow::Error {
+    let Some(api_error) = err.chain().find_map(|cause| match cause.downcast_ref() {
+        Some(api_error @ nblm_core::Error::Http { status, .. })
+            if matches!(*status, StatusCode::FORBIDDEN | StatusCode::NOT_FOUND) =>
+        {
+            Some(api_error)
+        }
+        _ => None,
+    }) else {
+        return err;
+    };
+    match client.explain_notebook_error(notebook_id, api_error).await {
+        Some(explained) => explained.into(),
+        None => err,
+    }
+}
+
+/// Commands that read the same notebooks or sources more than once, whose GETs are answered
+/// from a per-invocation cache (see `NblmClient::with_request_cache`). Commands that poll are
+/// left out, since they need every read to reach the API.
+fn reads_repeatedly(command: &Command) -> bool {
+    matches!(
+        command,
+        Command::Notebooks(notebooks::Command::Budget(_))
+            | Command::Notebooks(notebooks::Command::SourcesCount(_))
+            | Command::Sources(sources::Command::Search(_))
+            | Command::Sources(sources::Command::Reingest(_))
+    )
+}
+
+/// `--lang`, or else the language of the first set locale variable (`LC_ALL`, then `LANG`).
+/// A locale that names no language, such as `C.UTF-8`, sends no `Accept-Language`.
+fn accept_language(flag: Option<&str>, env: impl Fn(&str) -> Option<String>) -> Option<String> {
+    if let Some(tag) = flag {
+        return Some(tag.to_string());
+    }
+    ["LC_ALL", "LANG"]
+        .into_iter()
+        .filter_map(env)
+        .find(|value| !value.trim().is_empty())
+        .and_then(|locale| language_tag_from_locale(&locale))
+}
+
+/// Trace context from `TRACEPARENT` and `TRACESTATE`, as exported by tracing-aware parent
+/// processes. Malformed values are reported and ignored rather than failing the command.
+fn trace_context_from_env(out: &dyn OutputSink) -> Option<TraceContext> {
+    let traceparent = std::env::var(TRACEPARENT_ENV)
+        .ok()
+        .filter(|value| !value.trim().is_empty())?;
+    let tracestate = std::env::var(TRACESTATE_ENV).unwrap_or_default();
+    match TraceContext::parse(&traceparent).and_then(|context| context.with_tracestate(&tracestate))
+    {
+        Ok(context) => Some(context),
+        Err(err) => {
+            out.err(&format!(
+                "warning: ignoring {TRACEPARENT_ENV}/{TRACESTATE_ENV}: {err}"
+            ));
+            None
         }
     }
 }
 
+/// Name of the setting `--base-url` was read from, for error messages.
+fn base_url_source(args: impl IntoIterator<Item = String>) -> &'static str {
+    if args
+        .into_iter()
+        .any(|arg| arg == "--base-url" || arg.starts_with("--base-url="))
+    {
+        "--base-url"
+    } else {
+        "NBLM_BASE_URL"
+    }
+}
+
+/// Refuse to skip certificate checks against the production Google APIs.
+fn ensure_insecure_tls_allowed(base_url: &str) -> Result<()> {
+    let url = url::Url::parse(base_url)?;
+    let host = url.host_str().unwrap_or_default().to_ascii_lowercase();
+    let host = host.trim_end_matches('.');
+    if host == "googleapis.com" || host.ends_with(".googleapis.com") {
+        bail!(
+            "--insecure-skip-tls-verify cannot be used with production host '{host}'; point --base-url at a local mock server"
+        );
+    }
+    Ok(())
+}
+
 fn resolve_profile_params(args: &GlobalArgs, profile: ApiProfile) -> Result<ProfileParams> {
     match profile {
         ApiProfile::Enterprise => {
@@ -115,7 +555,9 @@ fn resolve_profile_params(args: &GlobalArgs, profile: ApiProfile) -> Result<Prof
 }
 
 fn init_logging() {
-    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    // Retries are reported through the status line instead of the retry module's warnings.
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new("info,nblm_core::client::retry=error"));
     let _ = tracing_subscriber::fmt()
         .with_env_filter(filter)
         .with_target(false)
@@ -127,8 +569,16 @@ fn init_logging() {
 mod tests {
     use super::*;
     use crate::args::ProfileArg;
+    use crate::ops::test_support::PROJECT;
+    use clap::Parser;
+    use nblm_core::auth::TOKENINFO_ENDPOINT_ENV;
+    use nblm_core::StaticTokenProvider;
     use rstest::rstest;
     use serial_test::serial;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
 
     struct ExperimentFlagGuard {
         original: Option<String>,
@@ -166,11 +616,207 @@ mod tests {
             auth: crate::args::AuthMethod::Gcloud,
             token: None,
             json: false,
+            redact: None,
+            lang: None,
             debug_http: false,
+            quiet: false,
+            timings: false,
+            show_curl: false,
             timeout: None,
+            verify_token_project: false,
+            retry_min_delay: None,
+            no_retry: false,
+            non_interactive: false,
+            interactive: false,
+            insecure_skip_tls_verify: false,
             env_token: None,
             base_url: None,
+            api_version: None,
+        }
+    }
+
+    /// Provider factory that counts how often it is asked for a provider.
+    fn counting_factory() -> (Arc<AtomicUsize>, ProviderFactory) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let factory: ProviderFactory = Box::new(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(Arc::new(StaticTokenProvider::new("DUMMY_TOKEN")))
+        });
+        (calls, factory)
+    }
+
+    #[test]
+    #[serial]
+    fn missing_project_number_is_reported_without_building_a_provider() {
+        let original = std::env::var("NBLM_PROJECT_NUMBER").ok();
+        std::env::remove_var("NBLM_PROJECT_NUMBER");
+        let cli = Cli::try_parse_from(["nblm", "--auth", "user-oauth", "notebooks", "recent"]);
+        if let Some(value) = original {
+            std::env::set_var("NBLM_PROJECT_NUMBER", value);
+        }
+
+        let (calls, factory) = counting_factory();
+        let err = NblmApp::with_provider_factory(cli.unwrap(), factory)
+            .err()
+            .expect("missing project number must fail");
+        assert!(
+            err.to_string().contains("requires --project-number"),
+            "{err}"
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn api_command_builds_the_provider_once() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/v1alpha/projects/{PROJECT}/locations/global/notebooks:listRecentlyViewed"
+            )))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"notebooks": []})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let base_url = format!("{}/v1alpha", server.uri());
+        let cli = Cli::try_parse_from([
+            "nblm",
+            "--auth",
+            "env",
+            "--project-number",
+            PROJECT,
+            "--base-url",
+            &base_url,
+            "notebooks",
+            "recent",
+        ])
+        .unwrap();
+        let (calls, factory) = counting_factory();
+        let app = NblmApp::with_provider_factory(cli, factory).unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        app.run().await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn verify_token_project_shares_the_commands_provider() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/v1alpha/projects/{PROJECT}/locations/global/notebooks:listRecentlyViewed"
+            )))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"notebooks": []})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/tokeninfo"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let base_url = format!("{}/v1alpha", server.uri());
+        let cli = Cli::try_parse_from([
+            "nblm",
+            "--auth",
+            "env",
+            "--project-number",
+            PROJECT,
+            "--base-url",
+            &base_url,
+            "--verify-token-project",
+            "notebooks",
+            "recent",
+        ])
+        .unwrap();
+        let (calls, factory) = counting_factory();
+        let app = NblmApp::with_provider_factory(cli, factory).unwrap();
+
+        let original = std::env::var(TOKENINFO_ENDPOINT_ENV).ok();
+        std::env::set_var(
+            TOKENINFO_ENDPOINT_ENV,
+            format!("{}/tokeninfo", server.uri()),
+        );
+        let result = app.run().await;
+        match original {
+            Some(value) => std::env::set_var(TOKENINFO_ENDPOINT_ENV, value),
+            None => std::env::remove_var(TOKENINFO_ENDPOINT_ENV),
         }
+
+        result.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn a_notebook_error_is_explained_with_the_commands_client() {
+        let server = MockServer::start().await;
+        let notebook = format!("/v1alpha/projects/{PROJECT}/locations/global/notebooks/nb-1");
+        Mock::given(method("POST"))
+            .and(path(format!("{notebook}/audioOverviews")))
+            .respond_with(ResponseTemplate::new(403))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(notebook))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"notebookId": "nb-1"})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let base_url = format!("{}/v1alpha", server.uri());
+        let cli = Cli::try_parse_from([
+            "nblm",
+            "--auth",
+            "env",
+            "--project-number",
+            PROJECT,
+            "--base-url",
+            &base_url,
+            "audio",
+            "create",
+            "--notebook-id",
+            "nb-1",
+        ])
+        .unwrap();
+        let (calls, factory) = counting_factory();
+        let err = NblmApp::with_provider_factory(cli, factory)
+            .unwrap()
+            .run()
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(
+                err.downcast_ref(),
+                Some(nblm_core::Error::NotebookForbidden { .. })
+            ),
+            "{err:#}"
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[rstest]
+    #[case::default_endpoint("https://global-discoveryengine.googleapis.com/v1alpha", false)]
+    #[case::regional("https://us-discoveryengine.googleapis.com/v1alpha", false)]
+    #[case::uppercase("https://DISCOVERYENGINE.GOOGLEAPIS.COM/v1alpha", false)]
+    #[case::trailing_dot("https://discoveryengine.googleapis.com./v1alpha", false)]
+    #[case::localhost("https://localhost:8443/v1alpha", true)]
+    #[case::lookalike("https://googleapis.com.example.test/v1alpha", true)]
+    #[test]
+    fn insecure_tls_allowed_only_off_googleapis(#[case] base_url: &str, #[case] allowed: bool) {
+        assert_eq!(ensure_insecure_tls_allowed(base_url).is_ok(), allowed);
     }
 
     // Tests for ApiProfile::requires_experimental_flag()
@@ -264,4 +910,130 @@ mod tests {
         let params = resolve_profile_params(&args, ApiProfile::Workspace).unwrap();
         assert_eq!(params.expected_profile(), ApiProfile::Workspace);
     }
+
+    #[test]
+    fn only_batch_commands_fail_fast_on_unreachable_hosts() {
+        let command = |args: &[&str]| {
+            let mut argv = vec!["nblm", "--project-number", PROJECT];
+            argv.extend_from_slice(args);
+            Cli::try_parse_from(argv).unwrap().command
+        };
+        assert!(fans_out(&command(&[
+            "audio",
+            "create-batch",
+            "--notebook-id",
+            "nb1",
+            "--notebook-id",
+            "nb2"
+        ])));
+        assert!(fans_out(&command(&["queue", "flush"])));
+        assert!(fans_out(&command(&[
+            "sources",
+            "reingest",
+            "--notebook-id",
+            "nb",
+            "--all-web"
+        ])));
+        assert!(!fans_out(&command(&["notebooks", "recent"])));
+        assert!(!fans_out(&command(&[
+            "audio",
+            "create",
+            "--notebook-id",
+            "nb1"
+        ])));
+    }
+
+    #[test]
+    fn accept_language_prefers_the_flag_then_lc_all_then_lang() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        let locales = &[("LC_ALL", "de_DE.UTF-8"), ("LANG", "fr_FR.UTF-8")];
+        assert_eq!(
+            accept_language(Some("ja"), env(locales)).as_deref(),
+            Some("ja")
+        );
+        assert_eq!(
+            accept_language(None, env(locales)).as_deref(),
+            Some("de-DE")
+        );
+        assert_eq!(
+            accept_language(None, env(&[("LC_ALL", ""), ("LANG", "pt_BR.UTF-8")])).as_deref(),
+            Some("pt-BR")
+        );
+        // A set LC_ALL wins even when it names no language.
+        assert_eq!(
+            accept_language(None, env(&[("LC_ALL", "C.UTF-8"), ("LANG", "fr_FR")])),
+            None
+        );
+        assert_eq!(accept_language(None, env(&[])), None);
+    }
+
+    #[test]
+    fn token_project_report_warns_only_on_mismatch() {
+        let parent = "projects/123456/locations/global";
+        assert_eq!(
+            token_project_report(&TokenProject::Matches("project 123456".into()), parent),
+            None
+        );
+        let mismatch = TokenProject::Mismatch {
+            found: "project 987654".into(),
+            expected: "project 123456".into(),
+        };
+        let report = token_project_report(&mismatch, parent).unwrap();
+        assert!(
+            report.contains("issued for project 987654, not project 123456"),
+            "{report}"
+        );
+        assert!(
+            report.contains("gcloud config set project 123456"),
+            "{report}"
+        );
+        assert!(report.contains("set-quota-project 123456"), "{report}");
+        let report =
+            token_project_report(&TokenProject::Unknown("no project".into()), parent).unwrap();
+        assert_eq!(report, "note: token project not verified: no project");
+    }
+
+    #[test]
+    fn timeout_hint_suggests_a_longer_timeout() {
+        let timed_out = |timeout| {
+            anyhow::Error::new(nblm_core::Error::Timeout {
+                method: "GET".into(),
+                path: "/v1alpha/projects/{project}".into(),
+                timeout,
+                elapsed: timeout,
+            })
+            .context("failed to list notebooks")
+        };
+
+        let hint = timeout_hint(&timed_out(Duration::from_secs(30))).unwrap();
+        assert!(hint.contains("`nblm --timeout 2m ...`"), "{hint}");
+        assert!(hint.contains("NBLM_TIMEOUT=2m"), "{hint}");
+        let hint = timeout_hint(&timed_out(Duration::from_secs(300))).unwrap();
+        assert!(hint.contains("--timeout 10m"), "{hint}");
+        assert_eq!(timeout_hint(&anyhow!("other failure")), None);
+    }
+
+    #[test]
+    fn would_retry_looks_through_context() {
+        let failed = |err: nblm_core::Error| anyhow::Error::new(err).context("failed to list");
+
+        assert!(would_retry(&failed(nblm_core::Error::http(
+            reqwest::StatusCode::TOO_MANY_REQUESTS,
+            "{}"
+        ))));
+        assert!(!would_retry(&failed(nblm_core::Error::http(
+            reqwest::StatusCode::NOT_FOUND,
+            "{}"
+        ))));
+        assert!(!would_retry(&failed(nblm_core::Error::validation(
+            "bad id"
+        ))));
+        assert!(!would_retry(&anyhow!("other failure")));
+    }
 }
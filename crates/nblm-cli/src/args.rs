@@ -2,16 +2,62 @@ use std::time::Duration;
 
 use clap::{Args, Parser, Subcommand, ValueEnum};
 
-use nblm_core::ApiProfile;
+use nblm_core::{ApiProfile, ApiVersion};
 
 use crate::ops;
+use crate::util::help::api_help;
+use crate::util::parse;
+use crate::util::prompt;
+use crate::util::redact::RedactMode;
+
+const LONG_ABOUT: &str = "NotebookLM Enterprise CLI
+
+Manage NotebookLM Enterprise notebooks, sources and audio overviews. Settings come from flags \
+first, then environment variables, then the config file written by `nblm init`.";
+
+const AFTER_HELP: &str = api_help!(
+    "Examples:\n",
+    "  nblm init\n",
+    "  nblm notebooks recent\n",
+    "  nblm sources add --notebook-id abc123 --web-url https://example.com\n",
+    "  nblm help sources add";
+    "  NBLM_CONFIG_DIR         Directory for config.toml, OAuth credentials and the queue",
+    "  NBLM_TIMEOUT            Request timeout, e.g. 30s (--timeout)",
+    "  NBLM_MAX_RETRIES        How many times a failed request is retried",
+    "  NBLM_DEBUG_HTTP         Log HTTP requests and responses when set to 1 (--debug-http)",
+    "  NBLM_GCLOUD_PATH        gcloud binary used by --auth gcloud",
+);
+
+const AUTH_LOGIN_HELP: &str = "Notes:
+  - Runs `gcloud auth login` from PATH; its output and prompts are passed through.
+  - Google Drive sources need --drive-access, which adds --enable-gdrive-access so the token
+    carries the Drive scope.
+
+Examples:
+  nblm auth login
+  nblm auth login --drive-access
+
+Environment:
+  None. Commands run with --auth env use NBLM_ACCESS_TOKEN instead of gcloud credentials.";
+
+const AUTH_STATUS_HELP: &str = "Notes:
+  - Asks `gcloud auth print-access-token` for a token and prints the active account. Exits
+    non-zero when gcloud has no credentials.
+
+Examples:
+  nblm auth status
+  nblm --json auth status
+
+Environment:
+  None. Commands run with --auth env use NBLM_ACCESS_TOKEN instead of gcloud credentials.";
 
 #[derive(Parser)]
 #[command(
     name = "nblm",
     version,
     about = "NotebookLM Enterprise CLI",
-    disable_help_subcommand = true
+    long_about = LONG_ABOUT,
+    after_long_help = AFTER_HELP
 )]
 pub struct Cli {
     #[command(flatten)]
@@ -23,25 +69,36 @@ pub struct Cli {
 
 #[derive(Args)]
 pub struct GlobalArgs {
+    /// Google Cloud project number (not the project ID)
     #[arg(long, env = "NBLM_PROJECT_NUMBER")]
     pub project_number: Option<String>,
 
+    /// Notebook location
     #[arg(long, env = "NBLM_LOCATION", default_value = "global")]
     pub location: String,
 
-    #[arg(long, env = "NBLM_ENDPOINT_LOCATION", default_value = "global")]
+    /// Multi-region of the API endpoint (not a Google Cloud region such as us-central1)
+    #[arg(
+        long,
+        env = "NBLM_ENDPOINT_LOCATION",
+        default_value = "global",
+        value_parser = parse::EndpointLocation
+    )]
     pub endpoint_location: String,
 
     /// (hidden) API profile selector. Defaults to Enterprise until additional SKUs are public.
     #[arg(long, value_enum, default_value_t = ProfileArg::Enterprise, hide = true)]
     pub profile: ProfileArg,
 
+    /// Where the access token comes from
     #[arg(long, value_enum, default_value_t = AuthMethod::Gcloud)]
     pub auth: AuthMethod,
 
+    /// Access token for --auth env, instead of NBLM_ACCESS_TOKEN
     #[arg(long)]
     pub token: Option<String>,
 
+    /// Print machine-readable JSON on stdout
     #[arg(long, global = true)]
     pub json: bool,
 
@@ -49,30 +106,134 @@ pub struct GlobalArgs {
     #[arg(long, global = true)]
     pub debug_http: bool,
 
-    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    /// Report retries as plain stderr lines instead of a single updating status line
+    #[arg(long, global = true)]
+    pub quiet: bool,
+
+    /// Replace project numbers and email addresses in all output, for sharing it in bug reports;
+    /// --redact=aggressive also replaces titles in JSON output
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "LEVEL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "standard"
+    )]
+    pub redact: Option<RedactMode>,
+
+    /// Print where the time went (token fetch, API calls, retry waits, file reads) to stderr
+    #[arg(long, global = true)]
+    pub timings: bool,
+
+    /// Print each request to stderr as a curl command (the token is left as $TOKEN)
+    #[arg(long, global = true, alias = "show-request")]
+    pub show_curl: bool,
+
+    /// Language for API messages and generated content, as a BCP 47 tag (e.g. fr, pt-BR).
+    /// Defaults to the LC_ALL or LANG locale when it names a language
+    #[arg(long, global = true, value_name = "BCP47", value_parser = parse::language_tag)]
+    pub lang: Option<String>,
+
+    /// Timeout for each HTTP request (e.g. 30s, 2m)
+    #[arg(long, value_name = "DURATION", value_parser = parse::positive_duration)]
     pub timeout: Option<Duration>,
 
+    /// Before an API command, warn if the access token was issued for another project than
+    /// --project-number
+    #[arg(long, global = true)]
+    pub verify_token_project: bool,
+
+    /// Minimum backoff delay between retries of a failed request (e.g. 250ms, 2s)
+    #[arg(long, value_name = "DURATION", value_parser = parse::duration)]
+    pub retry_min_delay: Option<Duration>,
+
+    /// Send each request once and fail on the first error, ignoring every other retry setting.
+    /// Useful for seeing the raw failure while debugging
+    #[arg(
+        long,
+        global = true,
+        env = "NBLM_NO_RETRY",
+        value_parser = clap::builder::FalseyValueParser::new()
+    )]
+    pub no_retry: bool,
+
+    /// Fail (exit code 4) instead of opening a browser to sign in, asking for confirmation or
+    /// letting gcloud prompt. The default when stdin is not a terminal
+    #[arg(long, global = true, conflicts_with = "interactive")]
+    pub non_interactive: bool,
+
+    /// Allow browser sign-in, confirmations and gcloud prompts even when stdin is not a terminal
+    #[arg(long, global = true)]
+    pub interactive: bool,
+
+    /// Access token for --auth env
     #[arg(long, env = "NBLM_ACCESS_TOKEN", hide_env_values = true)]
     pub env_token: Option<String>,
 
+    /// (hidden) Skip TLS certificate verification. Only for local HTTPS mock servers;
+    /// refused for googleapis.com hosts.
+    #[arg(long, hide = true)]
+    pub insecure_skip_tls_verify: bool,
+
     /// (hidden) Override Discovery Engine API base URL. For tests only.
     /// Also configurable via env NBLM_BASE_URL.
     #[arg(long, hide = true, env = "NBLM_BASE_URL")]
     pub base_url: Option<String>,
+
+    /// (hidden) Discovery Engine API version (v1alpha or v1beta). Defaults to v1alpha.
+    /// Also configurable via env NBLM_API_VERSION.
+    #[arg(long, hide = true, env = "NBLM_API_VERSION", value_parser = parse::api_version)]
+    pub api_version: Option<ApiVersion>,
+}
+
+impl GlobalArgs {
+    /// Whether the command may wait for someone at the terminal: `--interactive` and
+    /// `--non-interactive` decide, and without either, whether stdin is a terminal.
+    pub fn is_interactive(&self) -> bool {
+        self.interactive || (!self.non_interactive && prompt::can_prompt(false))
+    }
 }
 
 #[derive(Subcommand)]
 #[allow(clippy::large_enum_variant)]
 pub enum Command {
-    #[command(subcommand)]
+    /// Create, list and delete notebooks
+    #[command(subcommand, after_long_help = ops::notebooks::HELP)]
     Notebooks(ops::notebooks::Command),
-    #[command(subcommand)]
+    /// Add, list, upload and delete notebook sources
+    #[command(subcommand, after_long_help = ops::sources::HELP)]
     Sources(ops::sources::Command),
-    #[command(subcommand)]
+    /// Create and delete audio overviews
+    #[command(subcommand, after_long_help = ops::audio::HELP)]
     Audio(ops::audio::Command),
+    /// Queue source operations locally and send them later
+    #[command(subcommand, after_long_help = ops::queue::HELP)]
+    Queue(ops::queue::Command),
     /// Manage authentication using Google Cloud SDK (gcloud)
     Auth(AuthCommand),
+    /// Check the environment, credentials and API connectivity
+    #[command(after_long_help = ops::doctor::HELP)]
     Doctor(ops::doctor::DoctorArgs),
+    /// Set up gcloud, the project and location, and write the config file
+    #[command(after_long_help = ops::init::HELP)]
+    Init(ops::init::InitArgs),
+    /// Check a config or queue file offline, without credentials
+    #[command(after_long_help = ops::validate::HELP)]
+    Validate(ops::validate::ValidateArgs),
+    /// Call an API endpoint directly, for endpoints nblm does not wrap yet
+    #[command(hide = true)]
+    Api(ops::api::ApiArgs),
+    /// Write man pages for every command into a directory
+    #[command(hide = true)]
+    Man(ops::man::ManArgs),
+    /// Describe every command and flag as JSON, for tools that generate wrappers
+    #[command(hide = true, after_long_help = ops::commands::HELP)]
+    Commands(ops::commands::CommandsArgs),
+    /// Capture redacted API responses for the model fixture tests
+    #[command(subcommand, hide = true)]
+    Fixtures(ops::fixtures::Command),
 }
 
 #[derive(Args)]
@@ -84,8 +245,10 @@ pub struct AuthCommand {
 #[derive(Subcommand)]
 pub enum AuthSubcommand {
     /// Log in via Google Cloud SDK (gcloud auth login)
+    #[command(after_long_help = AUTH_LOGIN_HELP)]
     Login(LoginArgs),
     /// Check current authentication status
+    #[command(after_long_help = AUTH_STATUS_HELP)]
     Status,
 }
 
@@ -96,12 +259,13 @@ pub struct LoginArgs {
     pub drive_access: bool,
 }
 
-#[derive(Copy, Clone, ValueEnum)]
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum)]
 pub enum AuthMethod {
     Gcloud,
     Env,
     #[value(name = "user-oauth", hide = true)]
     UserOauth,
+    Metadata,
 }
 
 impl AuthMethod {
@@ -110,10 +274,6 @@ impl AuthMethod {
     }
 }
 
-fn parse_duration(input: &str) -> std::result::Result<Duration, String> {
-    humantime::parse_duration(input).map_err(|err| err.to_string())
-}
-
 #[derive(Copy, Clone, ValueEnum)]
 pub enum ProfileArg {
     Enterprise,
@@ -138,20 +298,38 @@ impl From<ProfileArg> for ApiProfile {
 }
 
 pub enum SpecialCommand {
-    Doctor(crate::ops::doctor::DoctorArgs),
-    Auth(AuthCommand),
+    Doctor {
+        args: crate::ops::doctor::DoctorArgs,
+        json_mode: bool,
+    },
+    Auth {
+        command: AuthCommand,
+        json_mode: bool,
+    },
+    Init(crate::ops::init::InitArgs),
+    Man(crate::ops::man::ManArgs),
+    Commands {
+        args: crate::ops::commands::CommandsArgs,
+        json_mode: bool,
+    },
+    Validate {
+        args: crate::ops::validate::ValidateArgs,
+        json_mode: bool,
+    },
 }
 
 pub fn parse_pre_command(args: &[String]) -> Option<SpecialCommand> {
-    if args.len() <= 1 {
-        return None;
-    }
+    // `--json` is global, so it may come before the command name (`nblm --json auth status`).
+    let command = args.iter().skip(1).find(|arg| arg.as_str() != "--json")?;
 
-    match args[1].as_str() {
+    match command.as_str() {
         "doctor" => {
             #[derive(Parser)]
             #[command(name = "nblm")]
             struct DoctorCli {
+                #[arg(long, global = true)]
+                json: bool,
+
                 #[command(subcommand)]
                 command: DoctorCommand,
             }
@@ -174,12 +352,18 @@ pub fn parse_pre_command(args: &[String]) -> Option<SpecialCommand> {
             // For exact behavior preservation:
             let cli = DoctorCli::parse_from(args);
             let DoctorCommand::Doctor(args) = cli.command;
-            Some(SpecialCommand::Doctor(args))
+            Some(SpecialCommand::Doctor {
+                args,
+                json_mode: cli.json,
+            })
         }
         "auth" => {
             #[derive(Parser)]
             #[command(name = "nblm")]
             struct AuthCli {
+                #[arg(long, global = true)]
+                json: bool,
+
                 #[command(subcommand)]
                 command: AuthCommandWrapper,
             }
@@ -190,8 +374,91 @@ pub fn parse_pre_command(args: &[String]) -> Option<SpecialCommand> {
             }
 
             let cli = AuthCli::parse_from(args);
-            let AuthCommandWrapper::Auth(cmd) = cli.command;
-            Some(SpecialCommand::Auth(cmd))
+            let AuthCommandWrapper::Auth(command) = cli.command;
+            Some(SpecialCommand::Auth {
+                command,
+                json_mode: cli.json,
+            })
+        }
+        "init" => {
+            #[derive(Parser)]
+            #[command(name = "nblm")]
+            struct InitCli {
+                #[command(subcommand)]
+                command: InitCommand,
+            }
+
+            #[derive(Subcommand)]
+            enum InitCommand {
+                Init(crate::ops::init::InitArgs),
+            }
+
+            let cli = InitCli::parse_from(args);
+            let InitCommand::Init(args) = cli.command;
+            Some(SpecialCommand::Init(args))
+        }
+        "man" => {
+            #[derive(Parser)]
+            #[command(name = "nblm")]
+            struct ManCli {
+                #[command(subcommand)]
+                command: ManCommand,
+            }
+
+            #[derive(Subcommand)]
+            enum ManCommand {
+                Man(crate::ops::man::ManArgs),
+            }
+
+            let cli = ManCli::parse_from(args);
+            let ManCommand::Man(args) = cli.command;
+            Some(SpecialCommand::Man(args))
+        }
+        "commands" => {
+            #[derive(Parser)]
+            #[command(name = "nblm")]
+            struct CommandsCli {
+                #[arg(long, global = true)]
+                json: bool,
+
+                #[command(subcommand)]
+                command: CommandsCommand,
+            }
+
+            #[derive(Subcommand)]
+            enum CommandsCommand {
+                Commands(crate::ops::commands::CommandsArgs),
+            }
+
+            let cli = CommandsCli::parse_from(args);
+            let CommandsCommand::Commands(args) = cli.command;
+            Some(SpecialCommand::Commands {
+                args,
+                json_mode: cli.json,
+            })
+        }
+        "validate" => {
+            #[derive(Parser)]
+            #[command(name = "nblm")]
+            struct ValidateCli {
+                #[arg(long, global = true)]
+                json: bool,
+
+                #[command(subcommand)]
+                command: ValidateCommand,
+            }
+
+            #[derive(Subcommand)]
+            enum ValidateCommand {
+                Validate(crate::ops::validate::ValidateArgs),
+            }
+
+            let cli = ValidateCli::parse_from(args);
+            let ValidateCommand::Validate(args) = cli.command;
+            Some(SpecialCommand::Validate {
+                args,
+                json_mode: cli.json,
+            })
         }
         _ => None,
     }
@@ -206,20 +473,66 @@ mod tests {
         // Test doctor
         let args = vec!["nblm".to_string(), "doctor".to_string()];
         match parse_pre_command(&args) {
-            Some(SpecialCommand::Doctor(_)) => {}
+            Some(SpecialCommand::Doctor { json_mode, .. }) => assert!(!json_mode),
             _ => panic!("expected Doctor command"),
         }
 
+        // Test doctor with --json before and after the command
+        for args in [
+            vec!["nblm", "--json", "doctor", "--skip-api-check"],
+            vec!["nblm", "doctor", "--skip-api-check", "--json"],
+        ] {
+            let args: Vec<String> = args.into_iter().map(String::from).collect();
+            match parse_pre_command(&args) {
+                Some(SpecialCommand::Doctor { args, json_mode }) => {
+                    assert!(json_mode);
+                    assert!(args.skip_api_check);
+                }
+                _ => panic!("expected Doctor command"),
+            }
+        }
+
         // Test auth
         let args = vec!["nblm".to_string(), "auth".to_string(), "login".to_string()];
         match parse_pre_command(&args) {
-            Some(SpecialCommand::Auth(cmd)) => match cmd.command {
-                AuthSubcommand::Login(_) => {}
-                _ => panic!("expected Login subcommand"),
-            },
+            Some(SpecialCommand::Auth { command, json_mode }) => {
+                assert!(!json_mode);
+                match command.command {
+                    AuthSubcommand::Login(_) => {}
+                    _ => panic!("expected Login subcommand"),
+                }
+            }
             _ => panic!("expected Auth command"),
         }
 
+        // Test auth with --json before and after the command
+        for args in [
+            vec!["nblm", "--json", "auth", "status"],
+            vec!["nblm", "auth", "status", "--json"],
+        ] {
+            let args: Vec<String> = args.into_iter().map(String::from).collect();
+            match parse_pre_command(&args) {
+                Some(SpecialCommand::Auth { command, json_mode }) => {
+                    assert!(json_mode);
+                    assert!(matches!(command.command, AuthSubcommand::Status));
+                }
+                _ => panic!("expected Auth command"),
+            }
+        }
+
+        // Test init
+        let args: Vec<String> = ["nblm", "init", "--yes", "--project-number", "123"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        match parse_pre_command(&args) {
+            Some(SpecialCommand::Init(args)) => {
+                assert!(args.yes);
+                assert_eq!(args.project_number.as_deref(), Some("123"));
+            }
+            _ => panic!("expected Init command"),
+        }
+
         // Test normal command
         let args = vec!["nblm".to_string(), "notebooks".to_string()];
         assert!(parse_pre_command(&args).is_none());
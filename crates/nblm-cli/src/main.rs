@@ -8,21 +8,59 @@ mod util;
 
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> Result<()> {
-    // Check if this is the doctor command before requiring global args
+    let result = run().await;
+    // Everything written to stdout must reach the pipe before the error below is printed or
+    // `process::exit` skips the buffers.
+    util::output::flush_stdout();
+    if let Err(err) = &result {
+        if err.is::<util::interrupt::Interrupted>() {
+            eprintln!("Error: {err:#}");
+            std::process::exit(util::interrupt::INTERRUPTED_EXIT_CODE);
+        }
+        if let Some(code) = util::wait::exit_code(err).or_else(|| util::prompt::exit_code(err)) {
+            eprintln!("Error: {err:#}");
+            std::process::exit(code);
+        }
+    }
+    result
+}
+
+async fn run() -> Result<()> {
     let args: Vec<String> = std::env::args().collect();
-    let has_doctor = args.iter().any(|arg| arg == "doctor");
-    let has_json = args.iter().any(|arg| arg == "--json");
 
-    // If both doctor and --json are present, bail immediately
-    if has_doctor && has_json {
-        anyhow::bail!("The --json flag is not supported for the 'doctor' command");
+    // Defaults written by `nblm init`; a broken file should not block every command.
+    match util::config::CliConfig::default_path()
+        .and_then(|path| util::config::CliConfig::load(&path))
+    {
+        Ok(Some(config)) => {
+            config.apply_env_defaults();
+            config.install_mime_overrides();
+        }
+        Ok(None) => {}
+        Err(err) => eprintln!("warning: ignoring config file: {err:#}"),
     }
 
     // Check for special commands that need to bypass NblmApp initialization
     if let Some(cmd) = args::parse_pre_command(&args) {
         match cmd {
-            args::SpecialCommand::Doctor(args) => return ops::doctor::run(args).await,
-            args::SpecialCommand::Auth(cmd) => return ops::auth::run(cmd).await,
+            args::SpecialCommand::Doctor { args, json_mode } => {
+                return ops::doctor::run(args, json_mode).await
+            }
+            args::SpecialCommand::Auth { command, json_mode } => {
+                return ops::auth::run(command, json_mode).await
+            }
+            args::SpecialCommand::Init(args) => {
+                return ops::init::run(args, &util::output::StdStreams).await
+            }
+            args::SpecialCommand::Man(args) => {
+                return ops::man::run(args, &util::output::StdStreams)
+            }
+            args::SpecialCommand::Commands { args, json_mode } => {
+                return ops::commands::run(args, &util::output::StdStreams, json_mode)
+            }
+            args::SpecialCommand::Validate { args, json_mode } => {
+                return ops::validate::run(args, &util::output::StdStreams, json_mode)
+            }
         }
     }
 
@@ -0,0 +1,77 @@
+use std::fs;
+use std::io::{self, Read};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use nblm_core::{NblmClient, TraceContext};
+use reqwest::Method;
+use serde_json::Value;
+
+use crate::util::output::OutputSink;
+use crate::util::parse;
+
+#[derive(Args)]
+pub struct ApiArgs {
+    /// HTTP method, e.g. GET, POST, PATCH or DELETE
+    #[arg(value_name = "METHOD", value_parser = parse::http_method)]
+    pub method: Method,
+
+    /// Path under the project/location parent, or under the base URL when it starts with '/'
+    #[arg(value_name = "PATH")]
+    pub path: String,
+
+    /// JSON request body, or @FILE to read it from a file (@- for stdin)
+    #[arg(long, value_name = "JSON|@FILE")]
+    pub body: Option<String>,
+
+    /// Query parameter to append (can be repeated)
+    #[arg(long = "query", value_name = "KEY=VALUE", value_parser = parse::query_pair)]
+    pub query: Vec<(String, String)>,
+
+    /// W3C traceparent for this request only, replacing the one from TRACEPARENT
+    #[arg(long, value_name = "TRACEPARENT", value_parser = parse::traceparent)]
+    pub traceparent: Option<TraceContext>,
+}
+
+pub async fn run(args: ApiArgs, client: &NblmClient, out: &dyn OutputSink) -> Result<()> {
+    let body = args.body.as_deref().map(read_body).transpose()?;
+    let query: Vec<(&str, &str)> = args
+        .query
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+        .collect();
+
+    let response = match &args.traceparent {
+        Some(trace) => {
+            client
+                .raw_request_with_trace(args.method, &args.path, body, &query, trace)
+                .await?
+        }
+        None => {
+            client
+                .raw_request(args.method, &args.path, body, &query)
+                .await?
+        }
+    };
+    // The response is printed as returned, whether or not --json is set.
+    if !response.is_null() {
+        out.out(&serde_json::to_string_pretty(&response)?);
+    }
+    Ok(())
+}
+
+fn read_body(arg: &str) -> Result<Value> {
+    let text = match arg.strip_prefix('@') {
+        Some("-") => {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .context("failed to read --body from stdin")?;
+            text
+        }
+        Some(path) => fs::read_to_string(path)
+            .with_context(|| format!("failed to read --body file {path}"))?,
+        None => arg.to_string(),
+    };
+    serde_json::from_str(&text).context("--body is not valid JSON")
+}
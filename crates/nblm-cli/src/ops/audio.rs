@@ -1,20 +1,112 @@
-use anyhow::Result;
+use std::fs::{self, File};
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
 use clap::{Args, Subcommand};
-use nblm_core::{models::enterprise::audio::AudioOverviewRequest, NblmClient};
-use serde_json::json;
+use nblm_core::{
+    models::enterprise::audio::{AudioOverviewRequest, AudioOverviewResponse},
+    ArtifactDownload, NblmClient, NotebookId, PollConfig,
+};
+use tokio::time::Instant;
 
+use crate::util::batch::{run_batch, BatchArgs, FailureMode};
+use crate::util::help::api_help;
+use crate::util::interrupt::Interrupt;
 use crate::util::io::emit_json;
+use crate::util::output::OutputSink;
+use crate::util::parse;
+use crate::util::payload::{
+    AudioBatch, AudioBatchEntry, AudioCreated, AudioDownloaded, Sorted, Status,
+};
+use crate::util::status;
+use crate::util::wait;
+
+pub const HELP: &str = api_help!(
+    "Examples:\n",
+    "  nblm audio create --notebook-id abc123\n",
+    "  nblm audio create-batch --notebook-id abc123 --notebook-id def456 --wait\n",
+    "  nblm audio download --notebook-id abc123 --out overview.mp3\n",
+    "  nblm audio delete --notebook-id abc123",
+);
+
+const CREATE_HELP: &str = api_help!(
+    "Notes:\n",
+    "  - The notebook needs at least one source, and can have only one audio overview.\n",
+    "  - Generation takes several minutes; the command returns once it has started.\n",
+    "  - The API accepts no options (language, focus, sources) as of 2025-10-25. Change them\n",
+    "    in the NotebookLM web UI.\n",
+    "\n",
+    "Examples:\n",
+    "  nblm audio create --notebook-id abc123\n",
+    "  nblm --json audio create --notebook-id abc123",
+);
+
+const CREATE_BATCH_HELP: &str = api_help!(
+    "Notes:\n",
+    "  - A notebook that fails does not stop the others; the exit status is non-zero if any\n",
+    "    failed (use --allow-partial to ignore failures, or --fail-fast to stop at the first).\n",
+    "  - With --wait, each overview is polled until it is ready, fails, or --wait-timeout\n",
+    "    passes. DURATION is the time from the create request to that point. STATE is\n",
+    "    TIMED_OUT for a notebook still generating at --wait-timeout.\n",
+    "  - Polling starts every --poll-interval and slows down by half each time the status is\n",
+    "    unchanged, up to once a minute; a new status starts again at --poll-interval.\n",
+    "  - Without --wait, STATE is the status returned by the create request.\n",
+    "\n",
+    "Examples:\n",
+    "  nblm audio create-batch --notebook-id abc123 --notebook-id def456\n",
+    "  nblm audio create-batch --notebook-id abc123 --notebook-id def456 --wait --concurrency 4\n",
+    "  nblm --json audio create-batch --notebook-id abc123 --wait --wait-timeout 30m",
+);
+
+const DOWNLOAD_HELP: &str = api_help!(
+    "Notes:\n",
+    "  - The overview must have finished generating. With --wait, it is polled until it is\n",
+    "    ready, fails, or --wait-timeout passes. Polling starts every --poll-interval and slows\n",
+    "    down by half each time the status is unchanged, up to once a minute.\n",
+    "  - Exit status with --wait: 124 if the overview was still generating at --wait-timeout,\n",
+    "    3 if generation failed.\n",
+    "  - The file is written next to FILE and renamed into place once complete, so a failed\n",
+    "    download never leaves a truncated file. An existing FILE is kept unless --force is given.\n",
+    "  - The access token is sent only to googleapis.com hosts. Signed URLs and other hosts\n",
+    "    are fetched without credentials.\n",
+    "\n",
+    "Examples:\n",
+    "  nblm audio download --notebook-id abc123 --out overview.mp3\n",
+    "  nblm audio download --notebook-id abc123 --out overview.mp3 --wait --wait-timeout 30m",
+);
+
+const DELETE_HELP: &str = api_help!(
+    "Notes:\n",
+    "  - Deletion cannot be undone. The notebook and its sources are kept, and a new overview\n",
+    "    can be created afterwards.\n",
+    "\n",
+    "Examples:\n",
+    "  nblm audio delete --notebook-id abc123",
+);
 
 #[derive(Subcommand)]
 pub enum Command {
+    /// Start generating the audio overview of a notebook
+    #[command(after_long_help = CREATE_HELP)]
     Create(CreateArgs),
+    /// Start generating audio overviews for several notebooks, optionally waiting for them
+    #[command(after_long_help = CREATE_BATCH_HELP)]
+    CreateBatch(CreateBatchArgs),
+    /// Download the generated audio of a notebook's audio overview
+    #[command(after_long_help = DOWNLOAD_HELP)]
+    Download(DownloadArgs),
+    /// Delete the audio overview of a notebook
+    #[command(after_long_help = DELETE_HELP)]
     Delete(DeleteArgs),
 }
 
 #[derive(Args)]
 pub struct CreateArgs {
-    #[arg(long, value_name = "ID")]
-    pub notebook_id: String,
+    /// Notebook ID (the last segment of the notebook resource name)
+    #[arg(long, value_name = "ID", value_parser = parse::notebook_id)]
+    pub notebook_id: NotebookId,
     // TODO: Uncomment when API supports these fields (as of 2025-10-19, they return "Unknown name" errors)
     // /// Source IDs to include in the audio overview
     // #[arg(long = "source-id", value_name = "SOURCE_ID")]
@@ -29,13 +121,78 @@ pub struct CreateArgs {
     // pub language_code: Option<String>,
 }
 
+#[derive(Args)]
+pub struct CreateBatchArgs {
+    /// Notebook ID (can be repeated)
+    #[arg(
+        long = "notebook-id",
+        value_name = "ID",
+        required = true,
+        value_parser = parse::notebook_id
+    )]
+    pub notebook_ids: Vec<NotebookId>,
+
+    /// Poll each audio overview until it is ready or has failed
+    #[arg(long)]
+    pub wait: bool,
+
+    /// Number of notebooks processed at the same time
+    #[arg(long, value_name = "N", default_value_t = 2, value_parser = clap::value_parser!(u32).range(1..))]
+    pub concurrency: u32,
+
+    /// First wait between status checks with --wait; later ones grow up to 1m (e.g. 10s)
+    #[arg(long, value_name = "DURATION", default_value = "10s", value_parser = parse::positive_duration, requires = "wait")]
+    pub poll_interval: Duration,
+
+    /// Give up waiting for a notebook after this long and report it as TIMED_OUT (e.g. 20m)
+    #[arg(long, value_name = "DURATION", default_value = "20m", value_parser = parse::positive_duration, requires = "wait")]
+    pub wait_timeout: Duration,
+
+    #[command(flatten)]
+    pub batch: BatchArgs,
+}
+
+#[derive(Args)]
+pub struct DownloadArgs {
+    /// Notebook ID (the last segment of the notebook resource name)
+    #[arg(long, value_name = "ID", value_parser = parse::notebook_id)]
+    pub notebook_id: NotebookId,
+
+    /// File to write the audio to
+    #[arg(long, value_name = "FILE")]
+    pub out: PathBuf,
+
+    /// Overwrite FILE if it already exists
+    #[arg(long)]
+    pub force: bool,
+
+    /// Poll the audio overview until it is ready instead of failing while it is generated
+    #[arg(long)]
+    pub wait: bool,
+
+    /// Time between status checks with --wait (e.g. 10s, 1m)
+    #[arg(long, value_name = "DURATION", default_value = "10s", value_parser = parse::positive_duration, requires = "wait")]
+    pub poll_interval: Duration,
+
+    /// Give up waiting after this long and exit with status 124 (e.g. 20m)
+    #[arg(long, value_name = "DURATION", default_value = "20m", value_parser = parse::positive_duration, requires = "wait")]
+    pub wait_timeout: Duration,
+}
+
 #[derive(Args)]
 pub struct DeleteArgs {
-    #[arg(long, value_name = "ID")]
-    pub notebook_id: String,
+    /// Notebook ID (the last segment of the notebook resource name)
+    #[arg(long, value_name = "ID", value_parser = parse::notebook_id)]
+    pub notebook_id: NotebookId,
 }
 
-pub async fn run(cmd: Command, client: &NblmClient, json_mode: bool) -> Result<()> {
+pub async fn run(
+    cmd: Command,
+    client: &NblmClient,
+    out: &dyn OutputSink,
+    json_mode: bool,
+    interrupt: &Interrupt,
+) -> Result<()> {
     match cmd {
         Command::Create(args) => {
             // TODO: Uncomment when API supports configuration fields
@@ -64,28 +221,308 @@ pub async fn run(cmd: Command, client: &NblmClient, json_mode: bool) -> Result<(
 
             if json_mode {
                 // In CLI json mode, wrap with audioOverview to match original format
-                emit_json(json!({"audioOverview": response}), json_mode);
+                emit_json(
+                    out,
+                    &AudioCreated {
+                        audio_overview: Sorted(&response),
+                    },
+                    json_mode,
+                )?;
             } else {
-                println!("Audio overview created successfully:");
+                out.out("Audio overview created successfully:");
                 if let Some(id) = &response.audio_overview_id {
-                    println!("  Audio Overview ID: {}", id);
+                    out.out(&format!("  Audio Overview ID: {}", id));
                 }
                 if let Some(name) = &response.name {
-                    println!("  Name: {}", name);
+                    out.out(&format!("  Name: {}", name));
                 }
                 if let Some(status) = &response.status {
-                    println!("  Status: {}", status);
+                    out.out(&format!("  Status: {}", status));
                 }
             }
         }
+        Command::CreateBatch(args) => create_batch(args, client, out, json_mode, interrupt).await?,
+        Command::Download(args) => download(args, client, out, json_mode).await?,
         Command::Delete(args) => {
             client.delete_audio_overview(&args.notebook_id).await?;
             if !json_mode {
-                println!("Audio overview deleted successfully");
+                out.out("Audio overview deleted successfully");
             } else {
-                emit_json(json!({"status": "deleted"}), json_mode);
+                emit_json(out, &Status { status: "deleted" }, json_mode)?;
             }
         }
     }
     Ok(())
 }
+
+/// Where one notebook of `audio create-batch` ended up.
+struct Generated {
+    state: String,
+    elapsed: Duration,
+}
+
+async fn create_batch(
+    args: CreateBatchArgs,
+    client: &NblmClient,
+    out: &dyn OutputSink,
+    json_mode: bool,
+    interrupt: &Interrupt,
+) -> Result<()> {
+    let mode = args.batch.mode(FailureMode::KeepGoing);
+    let wait = args
+        .wait
+        .then(|| PollConfig::new(args.poll_interval, args.wait_timeout));
+    let outcome = run_batch(
+        args.notebook_ids.clone(),
+        args.concurrency as usize,
+        mode,
+        interrupt,
+        |notebook_id| async move { generate(client, &notebook_id, wait).await },
+    )
+    .await;
+
+    let label = |index: usize| args.notebook_ids[index].to_string();
+    for (index, err) in &outcome.failed {
+        out.err(&format!("Failed for notebook {}: {err:#}", label(*index)));
+    }
+    for index in &outcome.skipped {
+        out.err(&format!("Skipped notebook {}", label(*index)));
+    }
+    outcome.report_interruption(out, "finished", label);
+
+    // One row per notebook, in the order given.
+    let mut rows: Vec<(String, Option<&Generated>)> =
+        vec![("SKIPPED".to_string(), None); outcome.total];
+    for (index, generated) in &outcome.succeeded {
+        rows[*index] = (generated.state.clone(), Some(generated));
+    }
+    for (index, err) in &outcome.failed {
+        rows[*index].0 = if wait::exit_code(err) == Some(wait::WAIT_TIMED_OUT_EXIT_CODE) {
+            "TIMED_OUT".to_string()
+        } else {
+            "FAILED".to_string()
+        };
+    }
+    for index in &outcome.abandoned {
+        rows[*index].0 = "INTERRUPTED".to_string();
+    }
+
+    if json_mode {
+        let payload = AudioBatch {
+            notebooks: rows
+                .iter()
+                .enumerate()
+                .map(|(index, (state, generated))| AudioBatchEntry {
+                    notebook_id: args.notebook_ids[index].as_str(),
+                    state,
+                    duration_secs: generated.map(|generated| generated.elapsed.as_secs_f64()),
+                    error: outcome
+                        .failed
+                        .iter()
+                        .find(|(failed, _)| *failed == index)
+                        .map(|(_, err)| format!("{err:#}")),
+                })
+                .collect(),
+        };
+        emit_json(out, &payload, json_mode)?;
+    } else {
+        let width = args
+            .notebook_ids
+            .iter()
+            .map(|id| id.as_str().len())
+            .max()
+            .unwrap_or(0)
+            .max("NOTEBOOK".len());
+        out.out(&format!(
+            "{:<width$}  {:<36}  DURATION",
+            "NOTEBOOK", "STATE"
+        ));
+        for (index, (state, generated)) in rows.iter().enumerate() {
+            let duration = generated
+                .map(|generated| wait::format_elapsed(generated.elapsed))
+                .unwrap_or_else(|| "-".to_string());
+            out.out(&format!(
+                "{:<width$}  {state:<36}  {duration}",
+                args.notebook_ids[index].as_str()
+            ));
+        }
+    }
+    outcome.finish(args.batch.allow_partial, "notebook")
+}
+
+/// Start generation for one notebook and, with `wait`, poll it until it is ready. A failed
+/// generation or a timeout is an error.
+async fn generate(
+    client: &NblmClient,
+    notebook_id: &NotebookId,
+    wait: Option<PollConfig>,
+) -> Result<Generated> {
+    let started = Instant::now();
+    let created = client
+        .create_audio_overview(notebook_id, AudioOverviewRequest::default())
+        .await?;
+    let Some(config) = wait else {
+        return Ok(Generated {
+            state: state_of(&created),
+            elapsed: started.elapsed(),
+        });
+    };
+
+    let ready = client
+        .wait_for_audio_overview(notebook_id.clone(), created, config)
+        .await?;
+    Ok(Generated {
+        state: state_of(&ready),
+        elapsed: started.elapsed(),
+    })
+}
+
+async fn download(
+    args: DownloadArgs,
+    client: &NblmClient,
+    out: &dyn OutputSink,
+    json_mode: bool,
+) -> Result<()> {
+    if args.out.exists() && !args.force {
+        bail!(
+            "{} already exists; re-run with --force to overwrite it",
+            args.out.display()
+        );
+    }
+    let mut overview = client.get_audio_overview(&args.notebook_id).await?;
+    if args.wait {
+        let config = PollConfig::new(args.poll_interval, args.wait_timeout);
+        overview = client
+            .wait_for_audio_overview(args.notebook_id.clone(), overview, config)
+            .await?;
+    } else if overview.is_failed() {
+        bail!("audio generation ended with {}", state_of(&overview));
+    } else if !overview.is_ready() {
+        bail!(
+            "the audio overview is not ready yet ({}); re-run with --wait to poll until it is",
+            state_of(&overview)
+        );
+    }
+    let Some(uri) = overview.audio_uri() else {
+        bail!(
+            "the audio overview is {} but the API returned no download URI for it",
+            state_of(&overview)
+        );
+    };
+
+    let artifact = client.download_artifact(&uri).await?;
+    let content_type = artifact.content_type();
+    if let Some(media_type) = content_type
+        .as_deref()
+        .filter(|media| !is_audio_type(media))
+    {
+        bail!("{uri} returned {media_type} instead of audio; the link may have expired");
+    }
+    let progress = std::io::stderr().is_terminal();
+    let bytes = write_atomically(&args.out, artifact, progress).await?;
+
+    if json_mode {
+        emit_json(
+            out,
+            &AudioDownloaded {
+                notebook_id: args.notebook_id.as_str(),
+                path: &args.out.to_string_lossy(),
+                bytes,
+                content_type: content_type.as_deref(),
+            },
+            json_mode,
+        )?;
+    } else {
+        out.out(&format!(
+            "Downloaded audio overview to {} ({}, {})",
+            args.out.display(),
+            format_size(bytes),
+            content_type.as_deref().unwrap_or("unknown type")
+        ));
+    }
+    Ok(())
+}
+
+/// Media types accepted as the audio file. Servers that do not know the type send
+/// `application/octet-stream`; some containers are labelled as video.
+fn is_audio_type(media_type: &str) -> bool {
+    media_type.starts_with("audio/")
+        || media_type.starts_with("video/")
+        || media_type == "application/octet-stream"
+}
+
+/// Stream `artifact` into a `.part` file next to `path` and rename it into place once the whole
+/// body arrived. Returns the number of bytes written.
+async fn write_atomically(path: &Path, artifact: ArtifactDownload, progress: bool) -> Result<u64> {
+    let mut part = path.as_os_str().to_owned();
+    part.push(".part");
+    let part = PathBuf::from(part);
+    let result = stream_to(&part, artifact, progress).await;
+    if progress {
+        status::clear();
+    }
+    match result {
+        Ok(bytes) => {
+            fs::rename(&part, path)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+            Ok(bytes)
+        }
+        Err(err) => {
+            let _ = fs::remove_file(&part);
+            Err(err)
+        }
+    }
+}
+
+async fn stream_to(path: &Path, mut artifact: ArtifactDownload, progress: bool) -> Result<u64> {
+    let mut file =
+        File::create(path).with_context(|| format!("failed to create {}", path.display()))?;
+    let expected = artifact.content_length();
+    let mut written = 0u64;
+    let mut reported = 0u64;
+    while let Some(chunk) = artifact.chunk().await? {
+        file.write_all(&chunk)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        written += chunk.len() as u64;
+        // Redraw about every 256 KiB rather than on each small chunk.
+        if progress && written - reported >= 256 * 1024 {
+            reported = written;
+            status::show(&progress_line(written, expected), true);
+        }
+    }
+    file.sync_all()
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    if let Some(expected) = expected.filter(|expected| *expected != written) {
+        bail!("download ended after {written} of {expected} bytes");
+    }
+    if written == 0 {
+        bail!("the audio download was empty");
+    }
+    Ok(written)
+}
+
+fn progress_line(written: u64, expected: Option<u64>) -> String {
+    match expected.filter(|expected| *expected > 0) {
+        Some(expected) => format!(
+            "Downloading audio: {} of {} ({}%)",
+            format_size(written),
+            format_size(expected),
+            written * 100 / expected
+        ),
+        None => format!("Downloading audio: {}", format_size(written)),
+    }
+}
+
+/// Bytes below 1 KiB, otherwise KiB or MiB with one decimal.
+fn format_size(bytes: u64) -> String {
+    const KIB: f64 = 1024.0;
+    match bytes as f64 {
+        size if size < KIB => format!("{bytes} B"),
+        size if size < KIB * KIB => format!("{:.1} KiB", size / KIB),
+        size => format!("{:.1} MiB", size / (KIB * KIB)),
+    }
+}
+
+fn state_of(response: &AudioOverviewResponse) -> String {
+    response.status.clone().unwrap_or_else(|| "-".to_string())
+}
@@ -1,21 +1,31 @@
 use anyhow::{Context, Result};
 use colored::Colorize;
+use nblm_core::{ensure_drive_scope, FileRefreshTokenStore, StaticTokenProvider};
 use std::process::Stdio;
 use tokio::process::Command;
 
 use crate::args::{AuthCommand, AuthSubcommand};
+use crate::util::gcloud;
+use crate::util::io::emit_json;
+use crate::util::output::StdStreams;
+use crate::util::payload::{AuthLogin, AuthStatus};
 
-pub async fn run(cmd: AuthCommand) -> Result<()> {
+pub async fn run(cmd: AuthCommand, json_mode: bool) -> Result<()> {
     match cmd.command {
+        AuthSubcommand::Login(args) if json_mode => login_json(args).await,
         AuthSubcommand::Login(args) => login(args).await,
+        AuthSubcommand::Status if json_mode => status_json().await,
         AuthSubcommand::Status => status().await,
     }
 }
 
-async fn login(args: crate::args::LoginArgs) -> Result<()> {
+pub(crate) async fn login(args: crate::args::LoginArgs) -> Result<()> {
     println!("{}", "Starting Google Cloud authentication...".cyan());
     println!("This will open your browser to authenticate with Google.");
 
+    if args.drive_access {
+        println!("(Requesting Google Drive access)");
+    }
     let mut command = build_login_command(&args);
 
     println!("(Executing: {:?})\n", command);
@@ -26,7 +36,7 @@ async fn login(args: crate::args::LoginArgs) -> Result<()> {
         .stderr(Stdio::inherit())
         .status()
         .await
-        .context("Failed to execute 'gcloud'. Please ensure Google Cloud SDK is installed and in your PATH.")?;
+        .context(GCLOUD_MISSING)?;
 
     if status.success() {
         println!("\n{}", "Authentication successful!".green().bold());
@@ -80,12 +90,105 @@ async fn status() -> Result<()> {
     Ok(())
 }
 
+const GCLOUD_MISSING: &str =
+    "Failed to execute 'gcloud'. Please ensure Google Cloud SDK is installed and in your PATH.";
+
+/// `auth login --json`: gcloud's own output goes to stderr so stdout stays a single JSON object.
+async fn login_json(args: crate::args::LoginArgs) -> Result<()> {
+    let status = build_login_command(&args)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::from(std::io::stderr()))
+        .stderr(Stdio::inherit())
+        .status()
+        .await
+        .context(GCLOUD_MISSING)?;
+
+    emit_json(
+        &StdStreams,
+        &AuthLogin {
+            method: "gcloud",
+            drive_access: args.drive_access,
+            success: status.success(),
+            exit_code: status.code(),
+        },
+        true,
+    )?;
+    if !status.success() {
+        anyhow::bail!("gcloud auth login failed");
+    }
+    Ok(())
+}
+
+async fn status_json() -> Result<()> {
+    let gcloud_token = Command::new("gcloud")
+        .arg("auth")
+        .arg("print-access-token")
+        .output()
+        .await;
+    let gcloud_available = gcloud_token.is_ok();
+    let gcloud_token = gcloud_token
+        .ok()
+        .filter(|out| out.status.success())
+        .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+        .filter(|token| !token.is_empty());
+
+    let gcloud_account = if gcloud_available {
+        gcloud::account().await
+    } else {
+        None
+    };
+
+    let env_token = std::env::var("NBLM_ACCESS_TOKEN")
+        .ok()
+        .filter(|token| !token.trim().is_empty());
+    let oauth_tokens_stored = match FileRefreshTokenStore::new() {
+        Ok(store) => store.has_entries().await.unwrap_or(false),
+        Err(_) => false,
+    };
+
+    let (auth_method, token) = match (&gcloud_token, &env_token) {
+        (Some(token), _) => (Some("gcloud"), Some(token.clone())),
+        (None, Some(token)) => (Some("env"), Some(token.clone())),
+        (None, None) if oauth_tokens_stored => (Some("user-oauth"), None),
+        (None, None) => (None, None),
+    };
+
+    let drive_scope = match token {
+        Some(token) => match ensure_drive_scope(&StaticTokenProvider::new(token)).await {
+            Ok(()) => Some(true),
+            Err(err)
+                if err
+                    .to_string()
+                    .contains("missing the required drive.file scope") =>
+            {
+                Some(false)
+            }
+            Err(_) => None,
+        },
+        None => None,
+    };
+
+    let status = AuthStatus {
+        auth_method,
+        gcloud_account,
+        gcloud_available,
+        has_env_token: env_token.is_some(),
+        oauth_tokens_stored,
+        drive_scope,
+    };
+    emit_json(&StdStreams, &status, true)?;
+
+    if status.auth_method.is_none() {
+        anyhow::bail!("Not authenticated");
+    }
+    Ok(())
+}
+
 fn build_login_command(args: &crate::args::LoginArgs) -> Command {
     let mut command = Command::new("gcloud");
     command.arg("auth").arg("login");
 
     if args.drive_access {
-        println!("(Requesting Google Drive access)");
         command.arg("--enable-gdrive-access");
     }
     command
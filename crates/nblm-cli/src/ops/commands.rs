@@ -0,0 +1,47 @@
+use anyhow::Result;
+use clap::{Args, CommandFactory, ValueEnum};
+
+use crate::args::{AuthMethod, Cli, ProfileArg};
+use crate::util::inventory;
+use crate::util::io::emit_json;
+use crate::util::output::OutputSink;
+
+pub const HELP: &str = "Notes:
+  - With --json, prints every command (hidden ones included) with its arguments: long and
+    short names, value type, whether it repeats or is required, default, environment
+    variable and possible values. `schema_version` changes only when a key is removed or
+    changes meaning.
+  - Without --json, prints one command path per line.
+
+Examples:
+  nblm commands --json
+  nblm commands";
+
+#[derive(Args)]
+pub struct CommandsArgs {}
+
+pub fn run(_args: CommandsArgs, out: &dyn OutputSink, json_mode: bool) -> Result<()> {
+    let inventory = inventory::inventory(&Cli::command(), &experimental);
+    if json_mode {
+        return emit_json(out, &inventory, true);
+    }
+    for command in &inventory.commands {
+        if command.hidden {
+            out.out(&format!("{} (hidden)", command.path));
+        } else {
+            out.out(&command.path);
+        }
+    }
+    Ok(())
+}
+
+/// Values of `--auth` and `--profile` that need NBLM_PROFILE_EXPERIMENT=1.
+fn experimental(arg: &str, value: &str) -> bool {
+    match arg {
+        "auth" => AuthMethod::from_str(value, false)
+            .is_ok_and(|method| method.requires_experimental_flag()),
+        "profile" => ProfileArg::from_str(value, false)
+            .is_ok_and(|profile| profile.requires_experimental_flag()),
+        _ => false,
+    }
+}
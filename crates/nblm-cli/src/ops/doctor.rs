@@ -3,41 +3,91 @@ use clap::Args;
 use colored::Colorize;
 use nblm_core::doctor::{
     check_api_connectivity, check_commands, check_drive_access_token, check_environment_variables,
-    DiagnosticsSummary,
+    check_oauth_endpoints, check_token_project, DiagnosticsSummary,
 };
 
+use crate::util::io::emit_json;
+use crate::util::output::StdStreams;
+use crate::util::payload::DoctorReport;
+
+pub const HELP: &str = "Notes:
+  - Checks the NBLM_* variables, gcloud, the token's Google Drive access and, unless
+    --skip-api-check is given, the project the token was issued for and a request to the API.
+  - The API check lists up to 50 recently viewed notebooks and reports how many there are
+    and the latest one's title (--hide-titles leaves it out). It warns when there are none,
+    which usually means a wrong project number or location.
+  - Exits 0 when every check passes, 1 when some only warn and 2 when any fails.
+  - With --json, prints every check and the exit code as one object; the API check's entry
+    carries the notebook counts in its notebooks field.
+  - Runs without credentials or a valid configuration, so it can explain what is missing.
+
+Examples:
+  nblm doctor
+  nblm doctor --skip-api-check
+  nblm doctor --hide-titles
+  nblm doctor --json
+
+Environment:
+  NBLM_PROJECT_NUMBER     Google Cloud project number
+  NBLM_LOCATION           Notebook location
+  NBLM_ENDPOINT_LOCATION  API endpoint multi-region: global, us or eu
+  NBLM_ACCESS_TOKEN       Access token used with --auth env";
+
 #[derive(Args)]
 pub struct DoctorArgs {
     /// Skip the API connectivity check
     #[arg(long)]
     pub skip_api_check: bool,
+
+    /// Leave notebook titles out of the API check, e.g. when sharing the output
+    #[arg(long, conflicts_with = "skip_api_check")]
+    pub hide_titles: bool,
 }
 
-pub async fn run(args: DoctorArgs) -> Result<()> {
-    println!("Running NotebookLM environment diagnostics...\n");
+pub async fn run(args: DoctorArgs, json_mode: bool) -> Result<()> {
+    if !json_mode {
+        println!("Running NotebookLM environment diagnostics...\n");
+    }
 
     // Run all checks
     let mut all_checks = Vec::new();
     all_checks.extend(check_environment_variables());
+    all_checks.extend(check_oauth_endpoints());
     all_checks.extend(check_drive_access_token().await);
     all_checks.extend(check_commands());
 
     // Only run API connectivity check if not skipped
     if !args.skip_api_check {
-        all_checks.extend(check_api_connectivity().await);
+        all_checks.extend(check_token_project().await);
+        let api_checks = check_api_connectivity().await.into_iter();
+        all_checks.extend(api_checks.map(|check| match args.hide_titles {
+            true => check.without_titles(),
+            false => check,
+        }));
+    }
+
+    let summary = DiagnosticsSummary::new(all_checks);
+    let exit_code = summary.exit_code();
+
+    if json_mode {
+        let report = DoctorReport {
+            checks: &summary.checks,
+            exit_code,
+        };
+        emit_json(&StdStreams, &report, true)?;
+        crate::util::output::flush_stdout();
+        std::process::exit(exit_code);
     }
 
     // Print individual check results
-    for check in &all_checks {
+    for check in &summary.checks {
         println!("{}", check.format_colored());
     }
 
     // Print summary
-    let summary = DiagnosticsSummary::new(all_checks);
     println!("{}", summary.format_summary_colored());
 
     // Determine exit behavior
-    let exit_code = summary.exit_code();
     if exit_code == 0 {
         println!(
             "\n{}",
@@ -45,5 +95,6 @@ pub async fn run(args: DoctorArgs) -> Result<()> {
         );
     }
 
+    crate::util::output::flush_stdout();
     std::process::exit(exit_code);
 }
@@ -0,0 +1,338 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use nblm_core::{NblmClient, NotebookId, SourceId};
+use reqwest::Method;
+use serde_json::{json, Value};
+
+use crate::util::output::OutputSink;
+use crate::util::parse;
+use crate::util::redact::redact_emails;
+
+/// Project number the fixtures use in place of the real one.
+const PROJECT_PLACEHOLDER: &str = "123456";
+
+const SCRATCH_TITLE: &str = "nblm fixture capture";
+
+/// Fixed, so recapturing gives the same email placeholders and a small diff.
+const EMAIL_SALT: &[u8] = b"nblm-fixtures";
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Save redacted API responses as model test fixtures
+    Capture(CaptureArgs),
+}
+
+#[derive(Args)]
+pub struct CaptureArgs {
+    /// Directory to write the fixtures into (created if missing)
+    #[arg(
+        long,
+        value_name = "DIR",
+        default_value = "crates/nblm-core/tests/fixtures/responses"
+    )]
+    pub dir: PathBuf,
+
+    /// Notebook to capture as get_notebook.json
+    #[arg(long, value_name = "ID", value_parser = parse::notebook_id)]
+    pub notebook_id: Option<NotebookId>,
+
+    /// YouTube source in --notebook-id to capture as get_source_youtube.json
+    #[arg(
+        long,
+        value_name = "ID",
+        value_parser = parse::source_id,
+        requires = "notebook_id"
+    )]
+    pub youtube_source_id: Option<SourceId>,
+
+    /// Also create a scratch notebook, add sources and an audio overview to it, then delete it
+    #[arg(long)]
+    pub scratch: bool,
+
+    /// Replace TEXT with PLACEHOLDER in every fixture, on top of the IDs, project number and
+    /// email addresses (can be repeated)
+    #[arg(long = "replace", value_name = "TEXT=PLACEHOLDER", value_parser = parse::redaction)]
+    pub redactions: Vec<(String, String)>,
+}
+
+pub async fn run(cmd: Command, client: &NblmClient, out: &dyn OutputSink) -> Result<()> {
+    match cmd {
+        Command::Capture(args) => capture(args, client, out).await,
+    }
+}
+
+async fn capture(args: CaptureArgs, client: &NblmClient, out: &dyn OutputSink) -> Result<()> {
+    let mut capture = Capture::new(client, &args);
+
+    capture
+        .get(
+            "list_recently_viewed",
+            "notebooks:listRecentlyViewed",
+            &[("pageSize", "10")],
+        )
+        .await?;
+    if let Some(notebook_id) = &args.notebook_id {
+        capture
+            .get("get_notebook", &format!("notebooks/{notebook_id}"), &[])
+            .await?;
+        if let Some(source_id) = &args.youtube_source_id {
+            capture
+                .get(
+                    "get_source_youtube",
+                    &format!("notebooks/{notebook_id}/sources/{source_id}"),
+                    &[],
+                )
+                .await?;
+        }
+    }
+    if args.scratch {
+        capture_scratch(&mut capture, client, out).await?;
+    }
+
+    for path in capture.write(&args.dir)? {
+        out.out(&path.display().to_string());
+    }
+    out.err(
+        "Only IDs and the project number were redacted; review titles, URLs and channel names before committing.",
+    );
+    Ok(())
+}
+
+/// Capture the write endpoints against a throwaway notebook, deleting it even if a step fails.
+async fn capture_scratch(
+    capture: &mut Capture<'_>,
+    client: &NblmClient,
+    out: &dyn OutputSink,
+) -> Result<()> {
+    let created = capture
+        .send(
+            "notebook_create",
+            Method::POST,
+            "notebooks",
+            json!({ "title": SCRATCH_TITLE }),
+        )
+        .await?;
+    let notebook_id = created["notebookId"]
+        .as_str()
+        .context("create notebook response has no notebookId")?
+        .to_string();
+
+    let result = async {
+        capture
+            .send(
+                "batch_create_sources_with_errors",
+                Method::POST,
+                &format!("notebooks/{notebook_id}/sources:batchCreate"),
+                json!({
+                    "userContents": [
+                        { "webContent": { "url": "https://example.com/", "sourceName": "Example Domain" } },
+                        { "webContent": { "url": "https://unreachable.example.invalid/" } },
+                    ]
+                }),
+            )
+            .await?;
+        capture
+            .send(
+                "audio_overview_create",
+                Method::POST,
+                &format!("notebooks/{notebook_id}/audioOverviews"),
+                json!({}),
+            )
+            .await
+    }
+    .await;
+
+    let name = format!(
+        "{}/notebooks/{notebook_id}",
+        client.environment().parent_path()
+    );
+    if let Err(err) = client.delete_notebooks(vec![name.clone()]).await {
+        out.err(&format!(
+            "Failed to delete scratch notebook {name}: {err}; delete it by hand"
+        ));
+    }
+    result.map(drop)
+}
+
+/// Responses captured so far and the replacements that redact them.
+struct Capture<'a> {
+    client: &'a NblmClient,
+    responses: Vec<(&'static str, Value)>,
+    redactor: Redactor,
+}
+
+impl<'a> Capture<'a> {
+    fn new(client: &'a NblmClient, args: &CaptureArgs) -> Self {
+        let mut redactor = Redactor::default();
+        let parent = client.environment().parent_path();
+        if let Some(project) = parent.split('/').nth(1) {
+            redactor.add(project, PROJECT_PLACEHOLDER);
+        }
+        for (text, placeholder) in &args.redactions {
+            redactor.add(text, placeholder);
+        }
+        Self {
+            client,
+            responses: Vec::new(),
+            redactor,
+        }
+    }
+
+    async fn get(&mut self, name: &'static str, path: &str, query: &[(&str, &str)]) -> Result<()> {
+        let response = self
+            .client
+            .raw_request(Method::GET, path, None, query)
+            .await
+            .with_context(|| format!("failed to capture {name}"))?;
+        self.keep(name, response);
+        Ok(())
+    }
+
+    async fn send(
+        &mut self,
+        name: &'static str,
+        method: Method,
+        path: &str,
+        body: Value,
+    ) -> Result<Value> {
+        let response = self
+            .client
+            .raw_request(method, path, Some(body), &[])
+            .await
+            .with_context(|| format!("failed to capture {name}"))?;
+        self.keep(name, response.clone());
+        Ok(response)
+    }
+
+    fn keep(&mut self, name: &'static str, response: Value) {
+        self.redactor.collect_ids(&response);
+        self.responses.push((name, response));
+    }
+
+    /// Write `NAME.json` per response, redacted, and return the paths.
+    fn write(&self, dir: &Path) -> Result<Vec<PathBuf>> {
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+        let mut written = Vec::new();
+        for (name, response) in &self.responses {
+            let path = dir.join(format!("{name}.json"));
+            let mut content = self
+                .redactor
+                .apply(&serde_json::to_string_pretty(response)?);
+            content.push('\n');
+            fs::write(&path, content)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+}
+
+/// Replaces IDs with numbered placeholders (`notebook-1`, `source-1`, ...) in the order they are
+/// first seen, plus any fixed replacements.
+#[derive(Default)]
+struct Redactor {
+    replacements: Vec<(String, String)>,
+    counts: HashMap<&'static str, usize>,
+}
+
+impl Redactor {
+    fn add(&mut self, text: &str, placeholder: &str) {
+        let known = self.replacements.iter().any(|(seen, _)| seen == text);
+        if !text.is_empty() && !known {
+            self.replacements
+                .push((text.to_string(), placeholder.to_string()));
+        }
+    }
+
+    fn add_id(&mut self, kind: &'static str, id: &str) {
+        if id.is_empty() || self.replacements.iter().any(|(seen, _)| seen == id) {
+            return;
+        }
+        let count = self.counts.entry(kind).or_default();
+        *count += 1;
+        let placeholder = format!("{kind}-{count}");
+        self.add(id, &placeholder);
+    }
+
+    /// Register the notebook, source and audio overview IDs found anywhere in `value`.
+    fn collect_ids(&mut self, value: &Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, field) in map {
+                    match (key.as_str(), field) {
+                        ("notebookId" | "notebook_id", Value::String(id)) => {
+                            self.add_id("notebook", id)
+                        }
+                        ("audioOverviewId" | "audio_overview_id", Value::String(id)) => {
+                            self.add_id("audio-overview", id)
+                        }
+                        ("sourceId" | "source_id", Value::Object(source)) => {
+                            if let Some(Value::String(id)) = source.get("id") {
+                                self.add_id("source", id);
+                            }
+                        }
+                        _ => {}
+                    }
+                    self.collect_ids(field);
+                }
+            }
+            Value::Array(items) => items.iter().for_each(|item| self.collect_ids(item)),
+            _ => {}
+        }
+    }
+
+    /// Longest texts first, so an ID that contains another is replaced whole. Email addresses
+    /// are replaced last, like `--redact` does.
+    fn apply(&self, text: &str) -> String {
+        let mut replacements: Vec<_> = self.replacements.iter().collect();
+        replacements.sort_by_key(|(seen, _)| std::cmp::Reverse(seen.len()));
+        let text = replacements
+            .into_iter()
+            .fold(text.to_string(), |text, (seen, placeholder)| {
+                text.replace(seen.as_str(), placeholder)
+            });
+        redact_emails(&text, EMAIL_SALT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redactor_numbers_ids_in_order_and_replaces_them_everywhere() {
+        let mut redactor = Redactor::default();
+        redactor.add("987654321", PROJECT_PLACEHOLDER);
+        let response = json!({
+            "notebookId": "f3a1",
+            "name": "projects/987654321/locations/global/notebooks/f3a1",
+            "sources": [
+                { "sourceId": { "id": "s-77" }, "name": "projects/987654321/locations/global/notebooks/f3a1/sources/s-77" },
+                { "sourceId": { "id": "s-7" } },
+            ],
+        });
+        redactor.collect_ids(&response);
+        redactor.collect_ids(&json!({ "notebookId": "f3a1", "audioOverviewId": "ao" }));
+
+        let redacted: Value = serde_json::from_str(&redactor.apply(&response.to_string())).unwrap();
+        assert_eq!(
+            redacted,
+            json!({
+                "notebookId": "notebook-1",
+                "name": "projects/123456/locations/global/notebooks/notebook-1",
+                "sources": [
+                    { "sourceId": { "id": "source-1" }, "name": "projects/123456/locations/global/notebooks/notebook-1/sources/source-1" },
+                    { "sourceId": { "id": "source-2" } },
+                ],
+            })
+        );
+        assert_eq!(redactor.apply("ao"), "audio-overview-1");
+        assert!(redactor
+            .apply("shared by owner@example.com")
+            .ends_with("@redacted.invalid"));
+    }
+}
@@ -0,0 +1,258 @@
+use std::io::{self, IsTerminal};
+
+use anyhow::{bail, Result};
+use clap::Args;
+use colored::Colorize;
+use nblm_core::doctor::{
+    check_api_connectivity, check_commands, check_drive_access_token, check_environment_variables,
+    CheckStatus, DiagnosticsSummary,
+};
+use nblm_core::EnvironmentConfig;
+
+use crate::args::LoginArgs;
+use crate::util::interrupt::Interrupt;
+use crate::util::prompt::{self, Reply};
+use crate::util::{config::CliConfig, gcloud, output::OutputSink};
+
+pub const HELP: &str = "Notes:
+  - Writes config.toml under NBLM_CONFIG_DIR (default: the platform config directory, e.g.
+    ~/.config/nblm-rs). An existing file is kept unless --force is given.
+  - Values from the config file apply only when neither the flag nor the environment
+    variable is set.
+  - Prompts for missing values when run in a terminal; --yes accepts detected values.
+
+Examples:
+  nblm init
+  nblm init --yes --project-number 123456 --location global --drive-access
+
+Environment:
+  NBLM_CONFIG_DIR         Directory the config file is written to";
+
+#[derive(Args)]
+pub struct InitArgs {
+    /// Accept detected values and defaults without prompting
+    #[arg(long, short = 'y')]
+    pub yes: bool,
+
+    /// Google Cloud project number (inferred from the active gcloud project if omitted)
+    #[arg(long, value_name = "NUMBER")]
+    pub project_number: Option<String>,
+
+    /// Notebook location (default: global)
+    #[arg(long)]
+    pub location: Option<String>,
+
+    /// API endpoint location: us, eu or global (default: same as --location)
+    #[arg(long)]
+    pub endpoint_location: Option<String>,
+
+    /// Run `gcloud auth login --enable-gdrive-access` so Drive documents can be added
+    #[arg(long)]
+    pub drive_access: bool,
+
+    /// Overwrite an existing config file
+    #[arg(long)]
+    pub force: bool,
+
+    /// Skip the API connectivity check
+    #[arg(long)]
+    pub skip_api_check: bool,
+}
+
+pub async fn run(args: InitArgs, out: &dyn OutputSink) -> Result<()> {
+    let path = CliConfig::default_path()?;
+    if path.exists() && !args.force {
+        bail!(
+            "{} already exists; re-run with --force to overwrite it",
+            path.display()
+        );
+    }
+    let interactive = !args.yes && io::stdin().is_terminal();
+    let prompt = Prompter {
+        interactive,
+        // Ctrl-C at a question exits with 130 like everywhere else, also between questions.
+        interrupt: interactive.then(Interrupt::listen),
+    };
+
+    step(out, 1, "Google Cloud CLI");
+    let command_checks = check_commands();
+    for check in &command_checks {
+        out.out(&check.format_colored());
+    }
+    let gcloud_available = command_checks
+        .iter()
+        .all(|check| check.status == CheckStatus::Pass);
+    if gcloud_available {
+        match gcloud::account().await {
+            Some(account) => out.out(&format!("Active account: {}", account.cyan())),
+            None => out.out(&format!(
+                "No active gcloud account; run {} before using nblm",
+                "nblm auth login".bold()
+            )),
+        }
+    }
+
+    step(out, 2, "Project number");
+    let detected = match args
+        .project_number
+        .clone()
+        .or_else(|| env_value("NBLM_PROJECT_NUMBER"))
+    {
+        Some(value) => Some(value),
+        None if gcloud_available => gcloud::project_number().await,
+        None => None,
+    };
+    let project_number = loop {
+        let value = prompt
+            .ask("Project number", detected.as_deref())?
+            .unwrap_or_default();
+        if !value.is_empty() && value.chars().all(|c| c.is_ascii_digit()) {
+            break value;
+        }
+        if !prompt.interactive {
+            if value.is_empty() {
+                bail!("could not infer the project number from gcloud; pass --project-number");
+            }
+            bail!("project number must be numeric (got '{value}')");
+        }
+        out.err(
+            "The project number is numeric; find it with `gcloud projects describe <PROJECT_ID>`.",
+        );
+    };
+    out.out(&format!("Using project number {}", project_number.cyan()));
+
+    step(out, 3, "Location and endpoint");
+    let (location, endpoint_location) = loop {
+        let location_default = args
+            .location
+            .clone()
+            .or_else(|| env_value("NBLM_LOCATION"))
+            .unwrap_or_else(|| "global".to_string());
+        let location = prompt
+            .ask("Location (global, us, eu)", Some(&location_default))?
+            .unwrap_or(location_default);
+        let endpoint_default = args
+            .endpoint_location
+            .clone()
+            .unwrap_or_else(|| location.clone());
+        let endpoint_location = prompt
+            .ask(
+                "Endpoint location (global, us, eu)",
+                Some(&endpoint_default),
+            )?
+            .unwrap_or(endpoint_default);
+        match EnvironmentConfig::enterprise(&project_number, &location, &endpoint_location) {
+            Ok(_) => break (location, endpoint_location),
+            Err(err) if prompt.interactive => out.err(&err.to_string()),
+            Err(err) => return Err(err.into()),
+        }
+    };
+    out.out(&format!(
+        "Using location {} with the {} endpoint",
+        location.cyan(),
+        endpoint_location.cyan()
+    ));
+
+    step(out, 4, "Google Drive access");
+    let drive_access = prompt.confirm(
+        "Log in again with Google Drive access (needed to add Drive documents)?",
+        args.drive_access,
+    )?;
+    if !drive_access {
+        out.out("Skipped; run `nblm auth login --drive-access` later if you need it.");
+    } else if !gcloud_available {
+        out.err("Skipping Drive access: gcloud is not installed.");
+    } else {
+        crate::ops::auth::login(LoginArgs { drive_access: true }).await?;
+    }
+
+    step(out, 5, "Diagnostics");
+    // The doctor checks read the environment, so point them at the chosen values.
+    std::env::set_var("NBLM_PROJECT_NUMBER", &project_number);
+    std::env::set_var("NBLM_LOCATION", &location);
+    std::env::set_var("NBLM_ENDPOINT_LOCATION", &endpoint_location);
+    let mut checks = check_environment_variables();
+    checks.extend(check_drive_access_token().await);
+    if !args.skip_api_check {
+        checks.extend(check_api_connectivity().await);
+    }
+    for check in &checks {
+        out.out(&check.format_colored());
+    }
+    let summary = DiagnosticsSummary::new(checks);
+    out.out(&summary.format_summary_colored());
+    if summary.exit_code() != 0 {
+        out.err("Some checks reported problems; the config is written anyway. Re-run `nblm doctor` after fixing them.");
+    }
+
+    // Keep settings init does not ask about when overwriting an existing file.
+    let mime_overrides = CliConfig::load(&path)
+        .ok()
+        .flatten()
+        .map(|existing| existing.mime_overrides)
+        .unwrap_or_default();
+    let config = CliConfig {
+        project_number: Some(project_number),
+        location: Some(location),
+        endpoint_location: Some(endpoint_location),
+        mime_overrides,
+    };
+    config.write(&path)?;
+    out.out(&format!("\nWrote {}", path.display()));
+    out.out("Try it:");
+    out.out(&format!("  {}", "nblm notebooks recent".bold()));
+    Ok(())
+}
+
+fn step(out: &dyn OutputSink, number: usize, title: &str) {
+    out.out(&format!("\n{}", format!("[{number}/5] {title}").bold()));
+}
+
+fn env_value(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+}
+
+/// Asks on stderr when attached to a terminal; otherwise answers with the defaults.
+struct Prompter {
+    interactive: bool,
+    interrupt: Option<Interrupt>,
+}
+
+impl Prompter {
+    /// An empty answer keeps `default`.
+    fn ask(&self, question: &str, default: Option<&str>) -> Result<Option<String>> {
+        if !self.interactive {
+            return Ok(default.map(str::to_string));
+        }
+        let answer = match default {
+            Some(default) => self.read_line(&format!("{question} [{default}]: "))?,
+            None => self.read_line(&format!("{question}: "))?,
+        };
+        Ok(match answer.trim() {
+            "" => default.map(str::to_string),
+            value => Some(value.to_string()),
+        })
+    }
+
+    fn confirm(&self, question: &str, default: bool) -> Result<bool> {
+        if !self.interactive {
+            return Ok(default);
+        }
+        let hint = if default { "Y/n" } else { "y/N" };
+        let answer = self.read_line(&format!("{question} [{hint}]: "))?;
+        Ok(match answer.trim().to_ascii_lowercase().as_str() {
+            "" => default,
+            value => value.starts_with('y'),
+        })
+    }
+
+    fn read_line(&self, question: &str) -> Result<String> {
+        match prompt::ask(question, self.interrupt.as_ref())? {
+            Reply::Line(line) => Ok(line),
+            Reply::Closed => bail!("input closed before setup finished"),
+            Reply::Cancelled => Err(prompt::cancelled()),
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Args, CommandFactory};
+
+use crate::args::Cli;
+use crate::util::{man, output::OutputSink};
+
+#[derive(Args)]
+pub struct ManArgs {
+    /// Directory to write the pages into (created if missing)
+    #[arg(long, value_name = "DIR", default_value = "man")]
+    pub dir: PathBuf,
+}
+
+pub fn run(args: ManArgs, out: &dyn OutputSink) -> Result<()> {
+    let written = man::write_pages(Cli::command(), &args.dir)?;
+    for path in &written {
+        out.out(&path.display().to_string());
+    }
+    Ok(())
+}
@@ -1,5 +1,46 @@
+pub mod api;
 pub mod audio;
 pub mod auth;
+pub mod commands;
 pub mod doctor;
+pub mod fixtures;
+pub mod init;
+pub mod man;
 pub mod notebooks;
+pub mod queue;
 pub mod sources;
+pub mod validate;
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use std::sync::Arc;
+
+    use nblm_core::test_support::InMemoryBackend;
+    use nblm_core::{EnvironmentConfig, NblmClient, RetryConfig, StaticTokenProvider};
+
+    pub const PROJECT: &str = "123456";
+
+    fn environment() -> EnvironmentConfig {
+        EnvironmentConfig::enterprise(PROJECT, "global", "global").unwrap()
+    }
+
+    /// Client for project [`PROJECT`] in `global`, pointed at a mock server.
+    pub fn client(server_uri: &str) -> NblmClient {
+        NblmClient::new(
+            Arc::new(StaticTokenProvider::new("DUMMY_TOKEN")),
+            environment(),
+        )
+        .unwrap()
+        .with_retry_config(RetryConfig::disabled())
+        .with_base_url(format!("{server_uri}/v1alpha"))
+        .unwrap()
+    }
+
+    /// Client for project [`PROJECT`] backed by an empty [`InMemoryBackend`], which is returned
+    /// alongside it for seeding and inspection.
+    pub fn in_memory() -> (InMemoryBackend, NblmClient) {
+        let backend = InMemoryBackend::new(environment());
+        let client = NblmClient::with_backends_for_testing(backend.clone());
+        (backend, client)
+    }
+}
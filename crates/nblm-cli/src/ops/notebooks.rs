@@ -1,20 +1,234 @@
-use anyhow::Result;
-use clap::{Args, Subcommand};
-use nblm_core::NblmClient;
+use std::collections::HashMap;
+use std::path::PathBuf;
 
-use crate::util::io::{emit_notebook, emit_recent};
+use anyhow::{Context, Result};
+use clap::{ArgGroup, Args, Subcommand};
+use nblm_core::models::enterprise::budget::DEFAULT_WORD_BUDGET;
+use nblm_core::models::enterprise::notebook::{
+    BatchDeleteNotebooksRequest, ListRecentlyViewedResponse, Notebook, NOTEBOOK_FIELDS_MINIMAL,
+    NOTEBOOK_SOURCE_COUNT_FIELDS, RECENT_NOTEBOOKS_FIELDS_MINIMAL,
+};
+use nblm_core::models::enterprise::query::QueryRequest;
+use nblm_core::models::enterprise::stats::SourceCounts;
+use nblm_core::{NblmClient, NotebookId, ResponseEnvelope, SourceId};
+use serde_json::Value;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use crate::util::batch::{run_batch, BatchArgs, FailureMode};
+use crate::util::bulk::{confirm_bulk_destruction, emit_dry_run, terminal_prompt, BulkDeleteArgs};
+use crate::util::diff::{diff, emit_diff, NotebookState};
+use crate::util::gcloud;
+use crate::util::help::api_help;
+use crate::util::interrupt::Interrupt;
+use crate::util::io::{
+    emit_activity, emit_json, emit_notebook, emit_query_answer, emit_recent, emit_source_counts,
+    emit_word_budget, source_counts_csv,
+};
+use crate::util::marks::RecentMarks;
+use crate::util::output::{FieldSelection, OutputSink};
+use crate::util::parse;
+use crate::util::payload::{Deleted, FailedItem, Sorted};
+use crate::util::template::{Template, Values};
+use crate::util::validate::ensure_same_parent;
+
+pub const HELP: &str = api_help!(
+    "Examples:\n",
+    "  nblm notebooks create --title \"Research\"\n",
+    "  nblm notebooks update --notebook-id abc123 --title \"Research 2025\" --show-diff\n",
+    "  nblm notebooks recent\n",
+    "  nblm notebooks budget --notebook-id abc123\n",
+    "  nblm notebooks sources-count --all --out counts.csv\n",
+    "  nblm notebooks activity --notebook-id abc123\n",
+    "  nblm notebooks query --notebook-id abc123 --question \"What are the key findings?\"",
+);
+
+const CREATE_HELP: &str = api_help!(
+    "Notes:\n",
+    "  - --emoji takes a single emoji (📘, or a sequence such as 👩‍🔬 that shows as one) or a\n",
+    "    shortcode such as :rocket:, :books: or :microscope:; anything else is rejected.\n",
+    "  - --expand-title fills in placeholders before the notebook is created: {date}\n",
+    "    (2025-03-04), {date:FORMAT} with strftime conversions such as %a %d %b, {time} (09:05),\n",
+    "    {user} (gcloud account, else $USER) and {uuid}. Write {{ and }} for literal braces.\n",
+    "\n",
+    "Examples:\n",
+    "  nblm notebooks create --title \"Research\"\n",
+    "  nblm notebooks create --title \"Launch plan\" --emoji :rocket:\n",
+    "  nblm notebooks create --expand-title --title \"Standup {date:%a %d %b}\"\n",
+    "  nblm --json notebooks create --title \"Research\"",
+);
+
+const UPDATE_HELP: &str = api_help!(
+    "Notes:\n",
+    "  - Only the fields given are changed; the others keep their current values.\n",
+    "  - --emoji takes a single emoji or a shortcode such as :rocket: (see `notebooks create`).\n",
+    "  - --show-diff fetches the notebook first and prints what will change (on stderr with\n",
+    "    --json). When nothing would change it prints \"no changes\" and sends no update.\n",
+    "\n",
+    "Examples:\n",
+    "  nblm notebooks update --notebook-id abc123 --title \"Research 2025\"\n",
+    "  nblm notebooks update --notebook-id abc123 --emoji 📘 --show-diff",
+);
+
+const RECENT_HELP: &str = api_help!(
+    "Notes:\n",
+    "  - Only notebooks the authenticated account has opened are listed, most recent first.\n",
+    "  - Text output asks the API only for each notebook's name, ID, title and emoji; --json\n",
+    "    gets the full notebooks. --fields overrides either: minimal, full, or a partial\n",
+    "    response mask such as 'notebooks(title,metadata),nextPageToken'.\n",
+    "  - Generated notebook summaries are long, so text output leaves them out. --show-summary\n",
+    "    fetches the full notebooks and prints each summary, when the API has one, under a\n",
+    "    Summary: heading wrapped to the terminal width.\n",
+    "  - --created-after keeps notebooks created at or after the given time: an RFC 3339\n",
+    "    timestamp, a date (midnight local time) or a duration before now such as 7d or 24h.\n",
+    "    It needs metadata.createTime, so it fetches the full notebooks unless --fields is\n",
+    "    given; notebooks without a creation time are left out.\n",
+    "  - --since-last-run NAME lists only notebooks created after the latest creation time\n",
+    "    seen by the previous successful run with the same NAME (all of them on the first\n",
+    "    run), then records the new latest time. Each NAME is an independent mark, kept in\n",
+    "    recent-marks.json in the nblm config directory. The mark is not moved when the\n",
+    "    command fails or with --dry-run-mark. Only the first page of the listing is read.\n",
+    "\n",
+    "Examples:\n",
+    "  nblm notebooks recent\n",
+    "  nblm notebooks recent --show-summary\n",
+    "  nblm notebooks recent --created-after 7d\n",
+    "  nblm --json notebooks recent --since-last-run slack\n",
+    "  nblm --json notebooks recent --page-size 50\n",
+    "  nblm --json notebooks recent --fields minimal",
+);
+
+const DELETE_HELP: &str = api_help!(
+    "Notes:\n",
+    "  - Takes full resource names (projects/PROJECT_NUMBER/locations/LOCATION/notebooks/ID),\n",
+    "    not bare notebook IDs.\n",
+    "  - The API deletes one notebook per request, so several names are deleted in turn.\n",
+    "  - Names outside --project-number/--location are refused unless --allow-cross-project\n",
+    "    is given.\n",
+    "  - Deleting more than --max-delete notebooks (default 25) asks for confirmation, or\n",
+    "    fails with exit code 4 when stdin is not a terminal; --yes skips the question.\n",
+    "    --dry-run only lists what would be deleted.\n",
+    "\n",
+    "Examples:\n",
+    "  nblm notebooks delete \\\n",
+    "    --notebook-name projects/123456/locations/global/notebooks/abc123\n",
+    "  nblm notebooks delete --dry-run --notebook-name NAME_1 --notebook-name NAME_2",
+);
+
+const BUDGET_HELP: &str = api_help!(
+    "Notes:\n",
+    "  - Adds up the word counts the API reports for each source, largest first. Sources\n",
+    "    still being processed have no word count yet; they count as 0 and are noted.\n",
+    "  - The default budget, 500,000 words, is roughly where audio overview quality starts to\n",
+    "    degrade.\n",
+    "\n",
+    "Examples:\n",
+    "  nblm notebooks budget --notebook-id abc123\n",
+    "  nblm --json notebooks budget --notebook-id abc123 --budget 200000",
+);
+
+const SOURCES_COUNT_HELP: &str = api_help!(
+    "Notes:\n",
+    "  - --all pages through every recently viewed notebook, asking only for notebook IDs,\n",
+    "    titles and source names, so it is much faster than `notebooks recent --json`.\n",
+    "  - A notebook listed on more than one page is counted once. Rows are sorted by source\n",
+    "    count, largest first, then by title.\n",
+    "  - --out also writes the rows as CSV (notebook_id,title,sources) to FILE.\n",
+    "\n",
+    "Examples:\n",
+    "  nblm notebooks sources-count --all\n",
+    "  nblm notebooks sources-count --all --out counts.csv\n",
+    "  nblm --json notebooks sources-count --notebook-id abc123 --notebook-id def456",
+);
+
+const ACTIVITY_HELP: &str = api_help!(
+    "Notes:\n",
+    "  - A best-effort timeline built from timestamps the API already returns: when the\n",
+    "    notebook was created, when each source was added, when the audio overview was\n",
+    "    created and when the notebook was last viewed. It is not an audit log; deletions,\n",
+    "    renames and who made a change are not shown.\n",
+    "  - Events the API gives no timestamp for are left out and counted in a note. A\n",
+    "    notebook without an audio overview simply has no audio event.\n",
+    "  - --json prints the events with RFC 3339 times and no relative times.\n",
+    "\n",
+    "Examples:\n",
+    "  nblm notebooks activity --notebook-id abc123\n",
+    "  nblm --json notebooks activity --notebook-id abc123",
+);
+
+const QUERY_HELP: &str = api_help!(
+    "Notes:\n",
+    "  - The answer is printed first, followed by the numbered passages it cites. --json\n",
+    "    prints the whole response, including fields nblm does not know about.\n",
+    "  - --source-id limits the answer to those sources; by default every source is used.\n",
+    "  - Projects or API profiles without the query endpoint get a \"not available\" error.\n",
+    "\n",
+    "Examples:\n",
+    "  nblm notebooks query --notebook-id abc123 --question \"What are the key findings?\"\n",
+    "  nblm --json notebooks query --notebook-id abc123 --question \"Summarize\" --source-id s1",
+);
 
 #[derive(Subcommand)]
 pub enum Command {
+    /// Create a notebook
+    #[command(after_long_help = CREATE_HELP)]
     Create(CreateArgs),
+    /// Change a notebook's title or emoji
+    #[command(after_long_help = UPDATE_HELP)]
+    Update(UpdateArgs),
+    /// List recently viewed notebooks
+    #[command(after_long_help = RECENT_HELP)]
     Recent(RecentArgs),
+    /// Delete notebooks (stops at the first failure unless --keep-going is given)
+    #[command(after_long_help = DELETE_HELP)]
     Delete(DeleteArgs),
+    /// Report source word counts against the audio overview budget
+    #[command(after_long_help = BUDGET_HELP)]
+    Budget(BudgetArgs),
+    /// Count the sources in each notebook, most first
+    #[command(after_long_help = SOURCES_COUNT_HELP)]
+    SourcesCount(SourcesCountArgs),
+    /// Show when the notebook, its sources and its audio overview were created, oldest first
+    #[command(after_long_help = ACTIVITY_HELP)]
+    Activity(ActivityArgs),
+    /// Ask a question and get an answer cited from the notebook's sources
+    #[command(after_long_help = QUERY_HELP)]
+    Query(QueryArgs),
 }
 
 #[derive(Args)]
 pub struct CreateArgs {
+    /// Notebook title
     #[arg(long)]
     pub title: String,
+
+    /// Expand {date}, {date:FORMAT}, {time}, {user} and {uuid} in --title
+    #[arg(long)]
+    pub expand_title: bool,
+
+    /// Emoji shown next to the title (a single emoji or a shortcode such as :books:)
+    #[arg(long, value_parser = parse::emoji)]
+    pub emoji: Option<String>,
+}
+
+#[derive(Args)]
+#[command(group(ArgGroup::new("change").required(true).multiple(true).args(["title", "emoji"])))]
+pub struct UpdateArgs {
+    /// Notebook ID (the last segment of the notebook resource name)
+    #[arg(long, value_name = "ID", value_parser = parse::notebook_id)]
+    pub notebook_id: NotebookId,
+
+    /// New title
+    #[arg(long)]
+    pub title: Option<String>,
+
+    /// New emoji (a single emoji or a shortcode such as :books:)
+    #[arg(long, value_parser = parse::emoji)]
+    pub emoji: Option<String>,
+
+    /// Print the changes before applying them, and skip the update when there are none
+    #[arg(long)]
+    pub show_diff: bool,
 }
 
 #[derive(Args)]
@@ -22,6 +236,34 @@ pub struct RecentArgs {
     /// Page size for pagination (1-500, default: 500)
     #[arg(long)]
     pub page_size: Option<u32>,
+
+    /// Start at the page this token names (`nextPageToken` from the previous page's --json output)
+    #[arg(long, value_name = "TOKEN", value_parser = clap::builder::NonEmptyStringValueParser::new())]
+    pub page_token: Option<String>,
+
+    /// Fields to request: minimal, full or a custom mask (default: minimal, full with --json)
+    #[arg(long, value_name = "FIELDS", value_parser = parse::field_selection)]
+    pub fields: Option<FieldSelection>,
+
+    /// Print each notebook's generated summary in text output
+    #[arg(long, overrides_with = "no_summary")]
+    pub show_summary: bool,
+
+    /// Leave summaries out of text output (the default)
+    #[arg(long, overrides_with = "show_summary")]
+    pub no_summary: bool,
+
+    /// Only list notebooks created at or after this time (2025-01-31T09:30:00Z, 2025-01-31 or 7d)
+    #[arg(long, value_name = "WHEN", value_parser = parse::time_or_duration)]
+    pub created_after: Option<OffsetDateTime>,
+
+    /// Only list notebooks created since the last run that used this mark name, then move the mark
+    #[arg(long, value_name = "NAME", value_parser = parse::mark_name)]
+    pub since_last_run: Option<String>,
+
+    /// With --since-last-run, list the new notebooks but leave the mark where it is
+    #[arg(long, requires = "since_last_run")]
+    pub dry_run_mark: bool,
 }
 
 #[derive(Args)]
@@ -30,37 +272,552 @@ pub struct DeleteArgs {
     /// Can be specified multiple times. Note: API limitation requires sequential deletion (one at a time).
     #[arg(long = "notebook-name", value_name = "NAME", required = true)]
     pub notebook_names: Vec<String>,
+
+    /// Allow deleting notebooks whose project/location differs from --project-number/--location
+    #[arg(long)]
+    pub allow_cross_project: bool,
+
+    #[command(flatten)]
+    pub batch: BatchArgs,
+
+    #[command(flatten)]
+    pub bulk: BulkDeleteArgs,
+}
+
+#[derive(Args)]
+#[command(group(ArgGroup::new("scope").required(true).args(["all", "notebook_ids"])))]
+pub struct SourcesCountArgs {
+    /// Count every recently viewed notebook, across all pages
+    #[arg(long)]
+    pub all: bool,
+
+    /// Count only this notebook (can be repeated)
+    #[arg(long = "notebook-id", value_name = "ID", value_parser = parse::notebook_id)]
+    pub notebook_ids: Vec<NotebookId>,
+
+    /// Page size for --all (1-500, default: 500)
+    #[arg(long, requires = "all")]
+    pub page_size: Option<u32>,
+
+    /// Also write the counts to FILE as CSV
+    #[arg(long, value_name = "FILE")]
+    pub out: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct BudgetArgs {
+    /// Notebook ID (the last segment of the notebook resource name)
+    #[arg(long, value_name = "ID", value_parser = parse::notebook_id)]
+    pub notebook_id: NotebookId,
+
+    /// Word budget to compare against
+    #[arg(long, value_name = "WORDS", default_value_t = DEFAULT_WORD_BUDGET)]
+    pub budget: u64,
 }
 
-pub async fn run(cmd: Command, client: &NblmClient, json_mode: bool) -> Result<()> {
+#[derive(Args)]
+pub struct ActivityArgs {
+    /// Notebook ID (the last segment of the notebook resource name)
+    #[arg(long, value_name = "ID", value_parser = parse::notebook_id)]
+    pub notebook_id: NotebookId,
+}
+
+#[derive(Args)]
+pub struct QueryArgs {
+    /// Notebook ID (the last segment of the notebook resource name)
+    #[arg(long, value_name = "ID", value_parser = parse::notebook_id)]
+    pub notebook_id: NotebookId,
+
+    /// The question to answer
+    #[arg(long, value_name = "TEXT")]
+    pub question: String,
+
+    /// Only answer from this source (can be repeated)
+    #[arg(long = "source-id", value_name = "ID", value_parser = parse::source_id)]
+    pub source_ids: Vec<SourceId>,
+}
+
+pub async fn run(
+    cmd: Command,
+    client: &NblmClient,
+    out: &dyn OutputSink,
+    json_mode: bool,
+    interactive: bool,
+    interrupt: &Interrupt,
+) -> Result<()> {
     match cmd {
         Command::Create(args) => {
-            let notebook = client.create_notebook(args.title).await?;
-            emit_notebook(&notebook, json_mode);
+            let title = if args.expand_title {
+                expand_title(&args.title).await?
+            } else {
+                args.title
+            };
+            let notebook = client.create_notebook_with_emoji(title, args.emoji).await?;
+            emit_notebook(out, &notebook, json_mode)?;
+        }
+        Command::Update(args) => {
+            if args.show_diff {
+                let current = client
+                    .get_notebook_with_fields(&args.notebook_id, Some(NOTEBOOK_FIELDS_MINIMAL))
+                    .await?;
+                let before = NotebookState::from_notebook(&current);
+                let after = NotebookState {
+                    title: args.title.clone().unwrap_or_else(|| before.title.clone()),
+                    emoji: args.emoji.clone().or_else(|| before.emoji.clone()),
+                    ..before.clone()
+                };
+                let changes = diff(&before, &after);
+                emit_diff(out, &changes, json_mode);
+                if changes.is_empty() {
+                    if json_mode {
+                        emit_notebook(out, &current, json_mode)?;
+                    }
+                    return Ok(());
+                }
+            }
+            let notebook = client
+                .update_notebook(&args.notebook_id, args.title, args.emoji)
+                .await?;
+            emit_notebook(out, &notebook, json_mode)?;
         }
         Command::Recent(args) => {
-            let response = client.list_recently_viewed(args.page_size).await?;
-            emit_recent(&response, json_mode)?;
+            let show_summary = args.show_summary && !json_mode;
+            // The minimal mask has no summary or creation time, so ask for everything unless
+            // --fields says otherwise.
+            let needs_full =
+                show_summary || args.created_after.is_some() || args.since_last_run.is_some();
+            let fields = match (args.fields, needs_full) {
+                (None, true) => FieldSelection::Full,
+                (fields, _) => FieldSelection::resolve(fields, json_mode),
+            };
+            let response = client
+                .list_recently_viewed_page_raw(
+                    args.page_size,
+                    args.page_token.as_deref(),
+                    fields.mask(RECENT_NOTEBOOKS_FIELDS_MINIMAL),
+                )
+                .await?;
+            let response = match args.created_after {
+                Some(cutoff) => retain_created(response, |created| created >= cutoff)?,
+                None => response,
+            };
+            let Some(name) = args.since_last_run else {
+                return emit_recent(out, &response, json_mode, show_summary);
+            };
+            let file = RecentMarks::file()?;
+            let mut marks = file.load()?;
+            let (response, latest) = since_mark(response, marks.get(&name))?;
+            emit_recent(out, &response, json_mode, show_summary)?;
+            if let (Some(latest), false) = (latest, args.dry_run_mark) {
+                marks.advance(&name, latest)?;
+                file.save(&marks)?;
+            }
         }
         Command::Delete(args) => {
-            let response = client.delete_notebooks(args.notebook_names.clone()).await?;
+            let parent = client.environment().parent_path();
+            ensure_same_parent(&args.notebook_names, parent, args.allow_cross_project)?;
+            if args.bulk.dry_run {
+                return emit_dry_run(out, "notebook", &args.notebook_names, json_mode);
+            }
+            // The API cannot list every notebook in a project, so only the count limit applies.
+            confirm_bulk_destruction(
+                &args.bulk,
+                "notebook",
+                &args.notebook_names,
+                false,
+                terminal_prompt(false, interactive, interrupt).as_deref(),
+            )?;
+            if !json_mode && args.notebook_names.len() > 1 {
+                out.err(&format!(
+                    "Deleting {} notebook(s) from {}",
+                    args.notebook_names.len(),
+                    parent
+                ));
+            }
+            let mode = args.batch.mode(FailureMode::FailFast);
+            // The API only accepts one name per batchDelete call, so delete sequentially.
+            let outcome = run_batch(
+                args.notebook_names.clone(),
+                1,
+                mode,
+                interrupt,
+                |name| async move {
+                    let request = BatchDeleteNotebooksRequest { names: vec![name] };
+                    Ok(client.batch_delete_notebooks(request).await?)
+                },
+            )
+            .await;
+
+            for (index, err) in &outcome.failed {
+                out.err(&format!(
+                    "Failed to delete {}: {err:#}",
+                    args.notebook_names[*index]
+                ));
+            }
+            for index in &outcome.skipped {
+                out.err(&format!("Skipped {}", args.notebook_names[*index]));
+            }
+            outcome.report_interruption(out, "deleted", |index| args.notebook_names[index].clone());
+
             if !json_mode {
-                println!(
-                    "Deleted {} notebook(s) successfully",
-                    args.notebook_names.len()
-                );
+                if !outcome.succeeded.is_empty() || outcome.failed.is_empty() {
+                    out.out(&format!(
+                        "Deleted {} notebook(s) successfully",
+                        outcome.succeeded.len()
+                    ));
+                }
             } else {
-                use serde_json::json;
-                crate::util::io::emit_json(
-                    json!({
-                        "status": "deleted",
-                        "count": args.notebook_names.len(),
-                        "response": response
+                let complete = outcome.is_complete();
+                // One call per notebook; their responses are merged in request order, so a
+                // single delete prints the API's response as is.
+                let response: HashMap<String, Value> = outcome
+                    .succeeded
+                    .iter()
+                    .flat_map(|(_, response)| response.extra.clone())
+                    .collect();
+                let payload = Deleted {
+                    status: match (complete, outcome.interrupted) {
+                        (true, _) => "deleted",
+                        (false, false) => "partial",
+                        (false, true) => "interrupted",
+                    },
+                    count: outcome.succeeded.len(),
+                    response: Sorted(response),
+                    failed: (!complete).then(|| {
+                        outcome
+                            .failed
+                            .iter()
+                            .map(|(index, err)| FailedItem {
+                                file: None,
+                                name: Some(args.notebook_names[*index].clone()),
+                                error: format!("{err:#}"),
+                            })
+                            .collect()
                     }),
-                    json_mode,
-                );
+                    skipped: (!complete).then(|| {
+                        outcome
+                            .skipped
+                            .iter()
+                            .map(|index| args.notebook_names[*index].clone())
+                            .collect()
+                    }),
+                    in_flight: outcome.interrupted.then(|| {
+                        outcome
+                            .abandoned
+                            .iter()
+                            .map(|index| args.notebook_names[*index].clone())
+                            .collect()
+                    }),
+                };
+                emit_json(out, &payload, json_mode)?;
             }
+            outcome.finish(args.batch.allow_partial, "notebook")?;
+        }
+        Command::Budget(args) => {
+            let summary = client.notebook_word_budget(&args.notebook_id).await?;
+            emit_word_budget(out, &summary, args.budget, json_mode)?;
+        }
+        Command::SourcesCount(args) => {
+            let counts = if args.all {
+                client.notebook_source_counts(args.page_size).await?
+            } else {
+                let mut entries = Vec::with_capacity(args.notebook_ids.len());
+                for notebook_id in &args.notebook_ids {
+                    let notebook = client
+                        .get_notebook_with_fields(notebook_id, Some(NOTEBOOK_SOURCE_COUNT_FIELDS))
+                        .await?;
+                    entries.extend(SourceCounts::entry(&notebook));
+                }
+                SourceCounts::from_refs(entries)
+            };
+            if let Some(path) = &args.out {
+                std::fs::write(path, source_counts_csv(&counts))
+                    .with_context(|| format!("failed to write {}", path.display()))?;
+            }
+            emit_source_counts(out, &counts, json_mode)?;
+        }
+        Command::Activity(args) => {
+            let activity = client.notebook_activity(&args.notebook_id).await?;
+            emit_activity(out, &activity, OffsetDateTime::now_utc(), json_mode)?;
+        }
+        Command::Query(args) => {
+            let request = QueryRequest {
+                query: args.question.clone(),
+                source_ids: (!args.source_ids.is_empty()).then(|| {
+                    args.source_ids
+                        .iter()
+                        .map(|id| id.as_str().to_string())
+                        .collect()
+                }),
+            };
+            let response = client.query_notebook(&args.notebook_id, request).await?;
+            emit_query_answer(
+                out,
+                args.notebook_id.as_str(),
+                &args.question,
+                &response,
+                json_mode,
+            )?;
         }
     }
     Ok(())
 }
+
+/// `--expand-title`: fill in the placeholders of `template`. The gcloud account is only looked
+/// up when the template has `{user}`.
+async fn expand_title(template: &str) -> Result<String> {
+    let template = Template::parse(template)?;
+    let mut values = Values::now()?;
+    if template.needs_user() {
+        values.user = match gcloud::account().await {
+            Some(account) => Some(account),
+            None => std::env::var("USER").ok().filter(|user| !user.is_empty()),
+        };
+    }
+    template.render(&values)
+}
+
+/// Keep the notebooks whose creation time passes `keep`, in both the typed response and the raw
+/// body `--json` prints. Notebooks without a creation time are left out.
+fn retain_created(
+    envelope: ResponseEnvelope<ListRecentlyViewedResponse>,
+    keep: impl Fn(OffsetDateTime) -> bool,
+) -> Result<ResponseEnvelope<ListRecentlyViewedResponse>> {
+    if envelope.value().notebooks.is_empty() {
+        return Ok(envelope);
+    }
+    let kept = |create_time: Option<&str>| parse_create_time(create_time).is_some_and(&keep);
+    let (mut response, raw) = envelope.into_parts();
+    response
+        .notebooks
+        .retain(|notebook| kept(notebook_create_time(notebook)));
+    let mut body: Value = serde_json::from_slice(&raw)?;
+    if let Some(Value::Array(notebooks)) = body.get_mut("notebooks") {
+        notebooks.retain(|notebook| kept(notebook["metadata"]["createTime"].as_str()));
+    }
+    Ok(ResponseEnvelope::new(
+        response,
+        serde_json::to_vec(&body)?.into(),
+    ))
+}
+
+/// For `--since-last-run`: keep the notebooks created after `mark` (every notebook with a
+/// creation time when there is no mark yet), and return the latest creation time in the whole
+/// listing, which becomes the next mark.
+fn since_mark(
+    envelope: ResponseEnvelope<ListRecentlyViewedResponse>,
+    mark: Option<OffsetDateTime>,
+) -> Result<(
+    ResponseEnvelope<ListRecentlyViewedResponse>,
+    Option<OffsetDateTime>,
+)> {
+    let latest = envelope
+        .value()
+        .notebooks
+        .iter()
+        .filter_map(|notebook| parse_create_time(notebook_create_time(notebook)))
+        .max();
+    let envelope = retain_created(envelope, |created| mark.is_none_or(|mark| created > mark))?;
+    Ok((envelope, latest))
+}
+
+fn notebook_create_time(notebook: &Notebook) -> Option<&str> {
+    notebook
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.create_time.as_deref())
+}
+
+fn parse_create_time(create_time: Option<&str>) -> Option<OffsetDateTime> {
+    create_time.and_then(|value| OffsetDateTime::parse(value, &Rfc3339).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::test_support::{in_memory, PROJECT};
+    use crate::util::output::CapturedOutput;
+    use nblm_core::test_support::InMemoryBackend;
+
+    fn delete_command() -> Command {
+        let names = ["nb1", "nb2"]
+            .iter()
+            .map(|id| format!("projects/{PROJECT}/locations/global/notebooks/{id}"))
+            .collect();
+        Command::Delete(DeleteArgs {
+            notebook_names: names,
+            allow_cross_project: false,
+            batch: BatchArgs::default(),
+            bulk: BulkDeleteArgs::default(),
+        })
+    }
+
+    /// Backend holding the two notebooks [`delete_command`] deletes, plus one it keeps.
+    fn seeded() -> (InMemoryBackend, NblmClient) {
+        let (backend, client) = in_memory();
+        for id in ["nb1", "nb2", "keep"] {
+            backend.insert_notebook(id, id);
+        }
+        (backend, client)
+    }
+
+    #[tokio::test]
+    async fn delete_reports_progress_on_stderr() {
+        let (backend, client) = seeded();
+        let out = CapturedOutput::default();
+
+        run(
+            delete_command(),
+            &client,
+            &out,
+            false,
+            false,
+            &Interrupt::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            out.stderr(),
+            format!("Deleting 2 notebook(s) from projects/{PROJECT}/locations/global\n")
+        );
+        assert_eq!(out.stdout(), "Deleted 2 notebook(s) successfully\n");
+        let left: Vec<_> = backend
+            .notebooks()
+            .into_iter()
+            .filter_map(|notebook| notebook.notebook_id)
+            .collect();
+        assert_eq!(left, ["keep"]);
+    }
+
+    #[tokio::test]
+    async fn delete_json_mode_keeps_stdout_parseable() {
+        let (backend, client) = seeded();
+        let out = CapturedOutput::default();
+
+        run(
+            delete_command(),
+            &client,
+            &out,
+            true,
+            false,
+            &Interrupt::default(),
+        )
+        .await
+        .unwrap();
+
+        let payload: serde_json::Value = serde_json::from_str(&out.stdout()).unwrap();
+        assert_eq!(payload["status"], "deleted");
+        assert_eq!(payload["count"], 2);
+        assert_eq!(out.stderr(), "");
+        assert!(backend.notebook("nb1").is_none());
+    }
+
+    #[tokio::test]
+    async fn delete_of_an_unknown_notebook_still_deletes_the_others() {
+        let (backend, client) = in_memory();
+        backend.insert_notebook("nb1", "Only one");
+        let out = CapturedOutput::default();
+
+        let err = run(
+            delete_command(),
+            &client,
+            &out,
+            false,
+            false,
+            &Interrupt::default(),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(err.to_string(), "1 of 2 notebook(s) failed");
+        assert!(
+            out.stderr().contains("Notebook nb2 not found"),
+            "{}",
+            out.stderr()
+        );
+        assert!(backend.notebook("nb1").is_none());
+    }
+
+    fn listing(created: &[(&str, Option<&str>)]) -> ResponseEnvelope<ListRecentlyViewedResponse> {
+        let notebooks: Vec<Value> = created
+            .iter()
+            .map(|(id, create_time)| match create_time {
+                Some(time) => {
+                    serde_json::json!({ "notebookId": id, "metadata": { "createTime": time } })
+                }
+                None => serde_json::json!({ "notebookId": id }),
+            })
+            .collect();
+        let body = serde_json::to_vec(&serde_json::json!({ "notebooks": notebooks })).unwrap();
+        ResponseEnvelope::new(serde_json::from_slice(&body).unwrap(), body.into())
+    }
+
+    fn ids(envelope: &ResponseEnvelope<ListRecentlyViewedResponse>) -> (Vec<String>, Vec<String>) {
+        let typed = envelope
+            .value()
+            .notebooks
+            .iter()
+            .filter_map(|notebook| notebook.notebook_id().map(str::to_string))
+            .collect();
+        let raw: Value = serde_json::from_slice(envelope.raw()).unwrap();
+        let raw = raw["notebooks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|notebook| notebook["notebookId"].as_str().unwrap().to_string())
+            .collect();
+        (typed, raw)
+    }
+
+    #[test]
+    fn since_mark_keeps_notebooks_created_after_the_mark_and_finds_the_next_one() {
+        let notebooks = [
+            ("newest", Some("2025-03-01T10:00:00Z")),
+            ("at-mark", Some("2025-02-01T00:00:00Z")),
+            ("new", Some("2025-02-15T08:30:00+01:00")),
+            ("old", Some("2025-01-01T00:00:00Z")),
+            ("undated", None),
+        ];
+
+        let (kept, latest) = since_mark(
+            listing(&notebooks),
+            Some(time::macros::datetime!(2025-02-01 00:00 UTC)),
+        )
+        .unwrap();
+        let expected = vec!["newest".to_string(), "new".to_string()];
+        assert_eq!(ids(&kept), (expected.clone(), expected));
+        assert_eq!(latest, Some(time::macros::datetime!(2025-03-01 10:00 UTC)));
+
+        // The first run has no mark and lists every dated notebook.
+        let (kept, _) = since_mark(listing(&notebooks), None).unwrap();
+        assert_eq!(ids(&kept).0, ["newest", "at-mark", "new", "old"]);
+    }
+
+    #[test]
+    fn since_mark_with_nothing_new_keeps_the_mark() {
+        let mark = time::macros::datetime!(2025-03-01 10:00 UTC);
+        let (kept, latest) = since_mark(
+            listing(&[("newest", Some("2025-03-01T10:00:00Z")), ("undated", None)]),
+            Some(mark),
+        )
+        .unwrap();
+        assert!(ids(&kept).0.is_empty());
+
+        let mut marks = RecentMarks::default();
+        marks.advance("slack", mark).unwrap();
+        marks.advance("slack", latest.unwrap()).unwrap();
+        assert_eq!(marks.get("slack"), Some(mark));
+
+        let (_, latest) = since_mark(listing(&[("undated", None)]), Some(mark)).unwrap();
+        assert_eq!(latest, None);
+    }
+
+    #[test]
+    fn since_mark_passes_a_body_without_notebooks_through() {
+        let envelope = ResponseEnvelope::new(Default::default(), b"{}".to_vec().into());
+        let (kept, latest) = since_mark(envelope, None).unwrap();
+        assert_eq!(kept.raw(), b"{}");
+        assert_eq!(latest, None);
+    }
+}
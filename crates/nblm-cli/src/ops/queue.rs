@@ -0,0 +1,367 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Context, Result};
+use clap::{Args, Subcommand};
+use nblm_core::{NblmClient, NotebookId};
+
+use crate::ops::sources::{build_contents, prepare_upload, source_input_notes, SourceInputArgs};
+use crate::util::{
+    help::api_help,
+    io::emit_json,
+    output::OutputSink,
+    parse,
+    payload::{
+        FailedEntry, FlushedEntry, QueueAdded, QueueCleared, QueueFlushed, QueueList, Sorted,
+    },
+    queue::{sha256_hex, verify_file, Queue, QueueEntry, QueuedOperation},
+};
+
+pub const HELP: &str = api_help!(
+    "Notes:\n",
+    "  - The queue is stored in the queue directory under NBLM_CONFIG_DIR.\n",
+    "\n",
+    "Examples:\n",
+    "  nblm queue add --notebook-id abc123 --web-url https://example.com\n",
+    "  nblm queue flush";
+    "  NBLM_CONFIG_DIR         Directory holding the queue",
+);
+
+const ADD_HELP: &str = api_help!(
+    "Notes:\n",
+    "  - Nothing is sent until `queue flush`, so no network or credentials are needed. Files\n",
+    "    are read and hashed now, and a file that changed before the flush is not uploaded.\n",
+    source_input_notes!(),
+    "\n",
+    "Examples:\n",
+    "  nblm queue add --notebook-id abc123 --web-url https://example.com\n",
+    "  nblm queue add --notebook-id abc123 --file report.pdf --max-file-size 50MB";
+    "  NBLM_CONFIG_DIR         Directory holding the queue",
+);
+
+const FLUSH_HELP: &str = api_help!(
+    "Notes:\n",
+    "  - Operations run in the order they were queued. Successful ones are removed; failed\n",
+    "    ones stay queued with their error, so running flush again retries only those.\n",
+    "\n",
+    "Examples:\n",
+    "  nblm queue flush\n",
+    "  nblm --json queue flush";
+    "  NBLM_CONFIG_DIR         Directory holding the queue",
+);
+
+const LIST_HELP: &str = api_help!(
+    "Examples:\n",
+    "  nblm queue list\n",
+    "  nblm --json queue list";
+    "  NBLM_CONFIG_DIR         Directory holding the queue",
+);
+
+const CLEAR_HELP: &str = api_help!(
+    "Examples:\n",
+    "  nblm queue clear --id ENTRY_ID\n",
+    "  nblm queue clear";
+    "  NBLM_CONFIG_DIR         Directory holding the queue",
+);
+
+#[derive(Subcommand)]
+#[allow(clippy::large_enum_variant)]
+pub enum Command {
+    /// Store source additions and file uploads to run later with `queue flush`
+    #[command(after_long_help = ADD_HELP)]
+    Add(AddArgs),
+    /// Replay queued operations in order; failed ones stay queued with their error
+    #[command(after_long_help = FLUSH_HELP)]
+    Flush,
+    /// Show queued operations
+    #[command(after_long_help = LIST_HELP)]
+    List,
+    /// Remove queued operations (all of them unless --id is given)
+    #[command(after_long_help = CLEAR_HELP)]
+    Clear(ClearArgs),
+}
+
+#[derive(Args)]
+pub struct AddArgs {
+    /// Notebook ID (the last segment of the notebook resource name)
+    #[arg(long, value_name = "ID", value_parser = parse::notebook_id)]
+    pub notebook_id: NotebookId,
+
+    #[command(flatten)]
+    pub inputs: SourceInputArgs,
+
+    /// File to upload. Can be specified multiple times.
+    #[arg(long = "file", value_name = "PATH")]
+    pub files: Vec<PathBuf>,
+
+    /// Content type of the files (guessed from the file extension by default)
+    #[arg(long = "content-type", value_name = "MEDIA_TYPE")]
+    pub content_type: Option<String>,
+
+    /// Refuse files larger than this size (e.g. 200MB, 1.5GiB)
+    #[arg(long, value_name = "SIZE", value_parser = parse::positive_size)]
+    pub max_file_size: Option<u64>,
+}
+
+#[derive(Args)]
+pub struct ClearArgs {
+    /// Queue entry ID to remove. Can be specified multiple times.
+    #[arg(long = "id", value_name = "ID")]
+    pub ids: Vec<String>,
+}
+
+/// `client` is only built for `queue flush`; the other commands never touch the API.
+pub async fn run(
+    cmd: Command,
+    client: Option<&NblmClient>,
+    out: &dyn OutputSink,
+    json_mode: bool,
+) -> Result<()> {
+    let mut queue = Queue::open(Queue::default_dir()?)?;
+    match cmd {
+        Command::Add(args) => add(&mut queue, args, out, json_mode),
+        Command::Flush => {
+            let client = client.expect("uses_api builds a client for queue flush");
+            flush(&mut queue, client, out, json_mode).await
+        }
+        Command::List => list(&queue, out, json_mode),
+        Command::Clear(args) => clear(&mut queue, args, out, json_mode),
+    }
+}
+
+fn add(queue: &mut Queue, args: AddArgs, out: &dyn OutputSink, json_mode: bool) -> Result<()> {
+    let contents = build_contents(&args.inputs)?;
+    if contents.is_empty() && args.files.is_empty() {
+        bail!(
+            "at least one source must be specified (--web-url/--text/--drive-document-id/--video-url/--file)"
+        );
+    }
+
+    let mut added = Vec::new();
+    if !contents.is_empty() {
+        added.push(QueueEntry::new(
+            args.notebook_id.as_str(),
+            QueuedOperation::AddSources { contents },
+        )?);
+    }
+    for file in &args.files {
+        let prepared =
+            prepare_upload(file, args.content_type.as_deref(), None, args.max_file_size)?;
+        let path = file
+            .canonicalize()
+            .with_context(|| format!("failed to resolve {}", file.display()))?;
+        added.push(QueueEntry::new(
+            args.notebook_id.as_str(),
+            QueuedOperation::UploadFile {
+                path,
+                file_name: prepared.file_name,
+                content_type: prepared.content_type,
+                sha256: sha256_hex(&prepared.data),
+                size: prepared.data.len() as u64,
+            },
+        )?);
+    }
+
+    queue.entries.extend(added.iter().cloned());
+    queue.save()?;
+
+    if json_mode {
+        emit_json(
+            out,
+            &QueueAdded {
+                queued: Sorted(&added),
+            },
+            true,
+        )?;
+    } else {
+        for entry in &added {
+            out.out(&format!(
+                "Queued {}: {}",
+                entry.id,
+                entry.operation.describe()
+            ));
+        }
+        out.out(&format!(
+            "{} operation(s) in the queue; run `nblm queue flush` to send them",
+            queue.entries.len()
+        ));
+    }
+    Ok(())
+}
+
+async fn flush(
+    queue: &mut Queue,
+    client: &NblmClient,
+    out: &dyn OutputSink,
+    json_mode: bool,
+) -> Result<()> {
+    let total = queue.entries.len();
+    let mut flushed = Vec::new();
+    let mut failed = Vec::new();
+
+    // Save after every entry so an interrupted flush never replays a completed call.
+    let mut index = 0;
+    while index < queue.entries.len() {
+        let entry = &mut queue.entries[index];
+        let label = format!("{} ({})", entry.id, entry.operation.describe());
+        match replay(client, entry).await {
+            Ok(result) => {
+                if !json_mode {
+                    out.err(&format!("  {label}: done ({result})"));
+                }
+                flushed.push(FlushedEntry {
+                    id: entry.id.clone(),
+                    result,
+                });
+                queue.entries.remove(index);
+            }
+            Err(err) => {
+                let message = format!("{err:#}");
+                if !json_mode {
+                    out.err(&format!("  {label}: failed ({message})"));
+                }
+                entry.attempts += 1;
+                entry.last_error = Some(message.clone());
+                failed.push(FailedEntry {
+                    id: entry.id.clone(),
+                    error: message,
+                });
+                index += 1;
+            }
+        }
+        queue.save()?;
+    }
+
+    if json_mode {
+        emit_json(
+            out,
+            &QueueFlushed {
+                flushed: &flushed,
+                failed: &failed,
+                remaining: queue.entries.len(),
+            },
+            true,
+        )?;
+    } else if total == 0 {
+        out.out("Queue is empty.");
+    } else {
+        out.out(&format!(
+            "Flushed {} of {total} queued operation(s)",
+            flushed.len()
+        ));
+    }
+
+    if !failed.is_empty() {
+        bail!(
+            "{} of {total} queued operation(s) failed and remain in the queue",
+            failed.len()
+        );
+    }
+    Ok(())
+}
+
+/// Send one entry to the API. On a partial batch failure only the failed sources stay queued.
+async fn replay(client: &NblmClient, entry: &mut QueueEntry) -> Result<String> {
+    entry.verify()?;
+    match entry.operation.clone() {
+        QueuedOperation::AddSources { contents } => {
+            let result = client
+                .add_sources_partial(&entry.notebook_id, contents.clone())
+                .await?;
+            if !result.is_complete() {
+                let still_failed = result.failed_contents();
+                let count = still_failed.len();
+                entry.set_operation(QueuedOperation::AddSources {
+                    contents: still_failed,
+                })?;
+                bail!(
+                    "{count} of {} source(s) failed to ingest; {} were added",
+                    contents.len(),
+                    result.succeeded.len()
+                );
+            }
+            Ok(format!("added {} source(s)", result.succeeded.len()))
+        }
+        QueuedOperation::UploadFile {
+            path,
+            file_name,
+            content_type,
+            sha256,
+            ..
+        } => {
+            let data = verify_file(&path, &sha256)?;
+            let response = client
+                .upload_source_file(&entry.notebook_id, &file_name, &content_type, data)
+                .await?;
+            Ok(
+                match response.source_id.as_ref().and_then(|id| id.id.as_deref()) {
+                    Some(source_id) => format!("created source {source_id}"),
+                    None => "upload accepted".to_string(),
+                },
+            )
+        }
+    }
+}
+
+fn list(queue: &Queue, out: &dyn OutputSink, json_mode: bool) -> Result<()> {
+    if json_mode {
+        return emit_json(
+            out,
+            &QueueList {
+                entries: Sorted(&queue.entries),
+            },
+            true,
+        );
+    }
+    if queue.entries.is_empty() {
+        out.out("Queue is empty.");
+        return Ok(());
+    }
+    for entry in &queue.entries {
+        out.out(&format!(
+            "{}  {}  {}  {}",
+            entry.id,
+            entry.queued_at,
+            entry.notebook_id,
+            entry.operation.describe()
+        ));
+        if let Some(error) = &entry.last_error {
+            out.out(&format!(
+                "    last error (attempt {}): {error}",
+                entry.attempts
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn clear(queue: &mut Queue, args: ClearArgs, out: &dyn OutputSink, json_mode: bool) -> Result<()> {
+    let before = queue.entries.len();
+    if args.ids.is_empty() {
+        queue.entries.clear();
+    } else {
+        if let Some(unknown) = args
+            .ids
+            .iter()
+            .find(|id| !queue.entries.iter().any(|entry| &entry.id == *id))
+        {
+            bail!("no queued operation with ID '{unknown}'");
+        }
+        queue.entries.retain(|entry| !args.ids.contains(&entry.id));
+    }
+    queue.save()?;
+
+    let removed = before - queue.entries.len();
+    if json_mode {
+        emit_json(
+            out,
+            &QueueCleared {
+                removed,
+                remaining: queue.entries.len(),
+            },
+            true,
+        )?;
+    } else {
+        out.out(&format!("Removed {removed} queued operation(s)"));
+    }
+    Ok(())
+}
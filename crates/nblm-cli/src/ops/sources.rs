@@ -1,37 +1,260 @@
-use std::{fs, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    io::{self, BufRead},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
 
 use anyhow::{anyhow, bail, Context, Result};
 use clap::{Args, Subcommand};
+use colored::Colorize;
+use nblm_core::models::enterprise::notebook::NOTEBOOK_FIELDS_MINIMAL;
 use nblm_core::models::enterprise::source::{
-    GoogleDriveContent, TextContent, UserContent, VideoContent, WebContent,
+    BatchCreateSourcesResponse, GoogleDriveContent, NotebookSource, PartialIngestResult,
+    SourceOrigin, SourceRef, TextContent, UploadSourceFileResponse, UserContent, VideoContent,
+    WebContent,
+};
+use nblm_core::{
+    infer_content_type, read_upload_file, DurationStats, Error as CoreError, NblmClient,
+    NotebookId, Phase, PollConfig, ResponseEnvelope, SourceId, UploadFileError,
 };
-use nblm_core::NblmClient;
+use url::Url;
 
 use crate::util::{
-    io::{emit_source, emit_sources, emit_uploaded_source},
-    validate::{pair_with_names, validate_url},
+    batch::{run_batch, run_batch_streaming, BatchArgs, FailureMode},
+    bulk::{confirm_bulk_destruction, emit_dry_run, terminal_prompt, BulkDeleteArgs},
+    config::mime_overrides,
+    download,
+    help::api_help,
+    interrupt::Interrupt,
+    io::{emit_json, emit_raw_json, emit_source, emit_sources, emit_uploaded_source},
+    output::{FieldSelection, OutputMode, OutputSink, WarningDeduper},
+    parse,
+    payload::{
+        Deleted, FailedItem, IngestTiming, KindTiming, ReingestedSource, RetriedSource,
+        RetryOutcome, SearchHit, SearchResults, SkippedSource, Sorted, SourceList, SourcesAdded,
+        SourcesIngested, SourcesReingested, SourcesUploaded, UploadEntry,
+    },
+    search::{snippet, Matcher, Snippet},
+    stdin_sources::StdinFormat,
+    validate::{pair_with_names, source_name_arg, validate_url},
+    wait,
 };
 
+pub const HELP: &str = api_help!(
+    "Examples:\n",
+    "  nblm sources add --notebook-id abc123 --web-url https://example.com\n",
+    "  nblm sources upload --notebook-id abc123 --file report.pdf\n",
+    "  nblm sources list --notebook-id abc123",
+);
+
+/// Constraints shared by `sources add` and `queue add`, which take the same source flags.
+macro_rules! source_input_notes {
+    () => {
+        concat!(
+            "  - Google Drive sources need a token with Drive access: run\n",
+            "    `nblm auth login --drive-access` (or `gcloud auth login --enable-gdrive-access`).\n",
+            "    The account must also be able to open the document; both are checked before the\n",
+            "    request is sent.\n",
+            "  - --drive-document-id is the ID in the Drive URL (.../file/d/<ID>/...). Give one\n",
+            "    --drive-mime-type per document, in the same order.\n",
+            "  - --web-name, --text-name and --drive-name name the sources of their kind in order.\n",
+            "    Names are trimmed; sources with no or a blank name get the API's default title.\n",
+            "    Names over 500 characters are refused.\n",
+            "  - --video-url accepts YouTube links only.\n",
+        )
+    };
+}
+
+pub(crate) use source_input_notes;
+
+const ADD_HELP: &str = api_help!(
+    "Notes:\n",
+    source_input_notes!(),
+    "  - --stdin reads one web URL per line. With --stdin-format json it reads a JSON array\n",
+    "    of URL strings or objects with a `type` (web, text, video, drive) and that type's\n",
+    "    fields: url, text, document_id, mime_type, name. The first invalid item fails the\n",
+    "    command before anything is sent.\n",
+    "  - More than 100 sources are sent in several requests of up to 100 each.\n",
+    "  - --retry-failed re-submits only the sources the API reports as failed, once.\n",
+    "  - --wait polls every added source until it is ingested, fails, or --wait-timeout\n",
+    "    passes, then prints one row per source (DURATION from the add request, POLLS made)\n",
+    "    and the median and longest DURATION of each kind. STATE is TIMED_OUT for a source\n",
+    "    still ingesting at --wait-timeout; the exit status is non-zero if any source did not\n",
+    "    finish ingesting.\n",
+    "\n",
+    "Examples:\n",
+    "  nblm sources add --notebook-id abc123 --web-url https://example.com --web-name Example\n",
+    "  nblm sources add --notebook-id abc123 --text \"Meeting notes\" --text-name Notes\n",
+    "  nblm sources add --notebook-id abc123 \\\n",
+    "    --drive-document-id FILE_ID \\\n",
+    "    --drive-mime-type application/vnd.google-apps.presentation\n",
+    "  nblm sources add --notebook-id abc123 --web-url https://a.example --web-url https://b.example --wait\n",
+    "  scraper --json | nblm sources add --notebook-id abc123 --stdin --stdin-format json",
+);
+
+const LIST_HELP: &str = api_help!(
+    "Notes:\n",
+    "  - Text and --output ids/titles ask the API only for the notebook's identity and each\n",
+    "    source's name, ID, title and status; --json gets the full notebook. --fields\n",
+    "    overrides either: minimal, full, or a partial response mask. A custom mask must keep\n",
+    "    sources(name) or the response cannot be read.\n",
+    "\n",
+    "Examples:\n",
+    "  nblm sources list --notebook-id abc123\n",
+    "  nblm sources list --notebook-id abc123 --output ids\n",
+    "  nblm --json sources list --notebook-id abc123 --fields 'sources(name,metadata)'",
+);
+
+const DELETE_HELP: &str = api_help!(
+    "Notes:\n",
+    "  - Takes full source names, as printed by `sources list --output ids`, source IDs\n",
+    "    (--source-id) or parts of source titles (--source-title), in any mix. A title must\n",
+    "    match exactly one source, or be the whole title of one; otherwise nothing is deleted.\n",
+    "    A source named more than once is deleted once.\n",
+    "  - Deleting every source of the notebook needs --allow-match-all. Deleting more than\n",
+    "    --max-delete sources (default 25) asks for confirmation, or fails with exit code 4\n",
+    "    when stdin is not a terminal; --yes skips the question. --dry-run only lists what\n",
+    "    would be deleted.\n",
+    "\n",
+    "Examples:\n",
+    "  nblm sources delete --notebook-id abc123 --source-name NAME\n",
+    "  nblm sources delete --notebook-id abc123 --source-id src1 --source-title \"Q3 report\"\n",
+    "  nblm sources list --notebook-id abc123 --output ids | \\\n",
+    "    nblm sources delete --notebook-id abc123 --stdin --allow-match-all",
+);
+
+/// Default `--max-download-size`, NotebookLM's per-source size limit.
+const DEFAULT_MAX_DOWNLOAD_SIZE: &str = "200MB";
+
+const UPLOAD_HELP: &str = api_help!(
+    "Notes:\n",
+    "  - The content type is guessed from the file extension unless --content-type is given.\n",
+    "  - Each file is uploaded in its own request. Empty files are refused.\n",
+    "  - --display-name is rejected by the API as of 2025-10-25 (HTTP 400), so the source keeps\n",
+    "    the file name. The flag is kept for when the API accepts it, and needs a single --file.\n",
+    "  - --url downloads the file first (following redirects) and names it after the last URL\n",
+    "    segment. Use it for PDFs that web sources ingest poorly; web pages are refused.\n",
+    "\n",
+    "Examples:\n",
+    "  nblm sources upload --notebook-id abc123 --file report.pdf\n",
+    "  nblm sources upload --notebook-id abc123 --url https://example.com/paper.pdf\n",
+    "  nblm sources upload --notebook-id abc123 --file a.pdf --file b.txt --fail-fast\n",
+    "  nblm sources upload --notebook-id abc123 --file notes.md --content-type text/markdown",
+);
+
+const GET_HELP: &str = api_help!(
+    "Examples:\n",
+    "  nblm sources get --notebook-id abc123 --source-id src456\n",
+    "  nblm --json sources get --notebook-id abc123 --source-id src456",
+);
+
+const SEARCH_HELP: &str = api_help!(
+    "Notes:\n",
+    "  - The API does not return source content, so only titles and URLs are searched.\n",
+    "  - --all-notebooks fetches every recently viewed notebook, which takes one request each.\n",
+    "\n",
+    "Examples:\n",
+    "  nblm sources search --notebook-id abc123 --query pricing\n",
+    "  nblm sources search --all-notebooks --query 'v[0-9]+' --regex",
+);
+
+const REINGEST_HELP: &str = api_help!(
+    "Notes:\n",
+    "  - The API has no refresh call: each source is deleted and its URL added again under the\n",
+    "    same title, so it gets a new source ID. Both IDs are printed.\n",
+    "  - Only web and YouTube sources can be reingested; the URL is read from the source's\n",
+    "    metadata. Sources without one are skipped with the reason.\n",
+    "  - If adding the URL again fails, the old source is already gone; the error names the\n",
+    "    URL so it can be added back with `nblm sources add`.\n",
+    "\n",
+    "Examples:\n",
+    "  nblm sources reingest --notebook-id abc123 --source-id src456\n",
+    "  nblm sources reingest --notebook-id abc123 --all-web --keep-going",
+);
+
 #[derive(Subcommand)]
 pub enum Command {
+    /// Add web, text, Google Drive or YouTube sources to a notebook
+    #[command(after_long_help = ADD_HELP)]
     Add(AddArgs),
+    /// List the sources in a notebook
+    #[command(after_long_help = LIST_HELP)]
+    List(ListArgs),
+    /// Delete sources from a notebook
+    #[command(after_long_help = DELETE_HELP)]
     Delete(DeleteArgs),
+    /// Upload files (processes every file unless --fail-fast is given)
+    #[command(after_long_help = UPLOAD_HELP)]
     Upload(UploadArgs),
+    /// Show one source of a notebook
+    #[command(after_long_help = GET_HELP)]
     Get(GetArgs),
+    /// Search source titles and URLs across notebooks
+    #[command(after_long_help = SEARCH_HELP)]
+    Search(SearchArgs),
+    /// Refresh web and YouTube sources by deleting them and adding their URL again
+    #[command(after_long_help = REINGEST_HELP)]
+    Reingest(ReingestArgs),
 }
 
 #[derive(Args)]
 pub struct AddArgs {
-    #[arg(long, value_name = "ID")]
-    pub notebook_id: String,
+    /// Notebook ID (the last segment of the notebook resource name)
+    #[arg(long, value_name = "ID", value_parser = parse::notebook_id)]
+    pub notebook_id: NotebookId,
+
+    #[command(flatten)]
+    pub inputs: SourceInputArgs,
+
+    /// Also read sources from stdin: one web URL per line, or JSON with --stdin-format json
+    #[arg(long)]
+    pub stdin: bool,
 
+    /// How to read --stdin
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        default_value_t,
+        requires = "stdin"
+    )]
+    pub stdin_format: StdinFormat,
+
+    /// Re-submit sources the API reports as failed, once
+    #[arg(long)]
+    pub retry_failed: bool,
+
+    /// Poll each added source until it is ingested and report how long each one took
+    #[arg(long, conflicts_with = "retry_failed")]
+    pub wait: bool,
+
+    /// First wait between status checks with --wait; later ones grow up to 1m (e.g. 5s)
+    #[arg(long, value_name = "DURATION", default_value = "5s", value_parser = parse::positive_duration, requires = "wait")]
+    pub poll_interval: Duration,
+
+    /// Give up waiting for a source after this long and report it as TIMED_OUT (e.g. 10m)
+    #[arg(long, value_name = "DURATION", default_value = "10m", value_parser = parse::positive_duration, requires = "wait")]
+    pub wait_timeout: Duration,
+}
+
+/// Source flags shared by `sources add` and `queue add`.
+#[derive(Args)]
+pub struct SourceInputArgs {
+    /// Web page to add. Can be specified multiple times.
     #[arg(long = "web-url", value_name = "URL", alias = "url")]
     pub web_urls: Vec<String>,
+    /// Display name for the --web-url at the same position
     #[arg(long = "web-name", value_name = "DISPLAY", alias = "name")]
     pub web_names: Vec<String>,
+    /// Fetcher hint applied to every --web-url, sent verbatim under `fetchOptions`. Can be repeated.
+    #[arg(long = "web-fetch-option", value_name = "KEY=VALUE", value_parser = parse::fetch_option)]
+    pub web_fetch_options: Vec<(String, serde_json::Value)>,
 
+    /// Text to add as a source. Can be specified multiple times.
     #[arg(long = "text", value_name = "TEXT")]
     pub texts: Vec<String>,
+    /// Display name for the --text at the same position
     #[arg(long = "text-name", value_name = "DISPLAY")]
     pub text_names: Vec<String>,
 
@@ -41,30 +264,78 @@ pub struct AddArgs {
     /// Google Drive MIME type.
     #[arg(long = "drive-mime-type", value_name = "MIME_TYPE")]
     pub drive_mime_types: Vec<String>,
+    /// Display name for the --drive-document-id at the same position
     #[arg(long = "drive-name", value_name = "DISPLAY")]
     pub drive_names: Vec<String>,
 
+    /// YouTube video to add. Can be specified multiple times.
     #[arg(long = "video-url", value_name = "URL")]
     pub video_urls: Vec<String>,
 }
 
+#[derive(Args)]
+pub struct ListArgs {
+    /// Notebook ID (the last segment of the notebook resource name)
+    #[arg(long, value_name = "ID", value_parser = parse::notebook_id)]
+    pub notebook_id: NotebookId,
+
+    #[arg(long, value_enum, value_name = "MODE", default_value_t)]
+    pub output: OutputMode,
+
+    /// Fields to request: minimal, full or a custom mask (default: minimal, full with --json)
+    #[arg(long, value_name = "FIELDS", value_parser = parse::field_selection)]
+    pub fields: Option<FieldSelection>,
+}
+
 #[derive(Args)]
 pub struct DeleteArgs {
-    #[arg(long, value_name = "ID")]
-    pub notebook_id: String,
+    /// Notebook ID (the last segment of the notebook resource name)
+    #[arg(long, value_name = "ID", value_parser = parse::notebook_id)]
+    pub notebook_id: NotebookId,
 
-    #[arg(long = "source-name", value_name = "NAME", required = true)]
+    /// Full source name to delete (from `sources list --output ids`). Can be specified multiple
+    /// times.
+    #[arg(
+        long = "source-name",
+        value_name = "NAME",
+        required_unless_present_any = ["stdin", "source_ids", "source_titles"]
+    )]
     pub source_names: Vec<String>,
+
+    /// Source ID to delete (the last segment of the source name). Can be specified multiple
+    /// times.
+    #[arg(long = "source-id", value_name = "ID", value_parser = parse::source_id)]
+    pub source_ids: Vec<SourceId>,
+
+    /// Delete the one source whose title contains this text (case-insensitive). Can be specified
+    /// multiple times.
+    #[arg(long = "source-title", value_name = "SUBSTRING")]
+    pub source_titles: Vec<String>,
+
+    /// Also read full source names from stdin, one per line (e.g. from `sources list --output ids`)
+    #[arg(long)]
+    pub stdin: bool,
+
+    #[command(flatten)]
+    pub bulk: BulkDeleteArgs,
 }
 
 #[derive(Args)]
 pub struct UploadArgs {
-    #[arg(long, value_name = "ID")]
-    pub notebook_id: String,
+    /// Notebook ID (the last segment of the notebook resource name)
+    #[arg(long, value_name = "ID", value_parser = parse::notebook_id)]
+    pub notebook_id: NotebookId,
+
+    /// File to upload. Can be specified multiple times.
+    #[arg(long = "file", value_name = "PATH", required_unless_present = "url")]
+    pub files: Vec<PathBuf>,
 
-    #[arg(long, value_name = "PATH")]
-    pub file: PathBuf,
+    /// Download a remote file (e.g. a PDF) and upload it, instead of adding the URL as a web
+    /// source
+    #[arg(long, value_name = "URL", value_parser = parse::web_url, conflicts_with = "files")]
+    pub url: Option<Url>,
 
+    /// Content type of the files (guessed from the file extension by default)
     #[arg(long = "content-type", value_name = "MEDIA_TYPE")]
     pub content_type: Option<String>,
 
@@ -72,187 +343,1624 @@ pub struct UploadArgs {
     /// This flag is kept for forward compatibility but currently non-functional.
     #[arg(long = "display-name", value_name = "NAME")]
     pub display_name: Option<String>,
+
+    /// Refuse files larger than this size (e.g. 200MB, 1.5GiB)
+    #[arg(long, value_name = "SIZE", value_parser = parse::positive_size)]
+    pub max_file_size: Option<u64>,
+
+    /// Stop a --url download once it exceeds this size
+    #[arg(
+        long,
+        value_name = "SIZE",
+        value_parser = parse::positive_size,
+        default_value = DEFAULT_MAX_DOWNLOAD_SIZE
+    )]
+    pub max_download_size: u64,
+
+    #[command(flatten)]
+    pub batch: BatchArgs,
 }
 
 #[derive(Args)]
 pub struct GetArgs {
-    #[arg(long, value_name = "ID", help = "Notebook ID containing the source")]
-    pub notebook_id: String,
+    #[arg(
+        long,
+        value_name = "ID",
+        value_parser = parse::notebook_id,
+        help = "Notebook ID containing the source"
+    )]
+    pub notebook_id: NotebookId,
 
-    #[arg(long, value_name = "ID", help = "Source ID to retrieve")]
-    pub source_id: String,
+    #[arg(
+        long,
+        value_name = "ID",
+        value_parser = parse::source_id,
+        help = "Source ID to retrieve"
+    )]
+    pub source_id: SourceId,
+
+    #[arg(long, value_enum, value_name = "MODE", default_value_t)]
+    pub output: OutputMode,
 }
 
-pub async fn run(cmd: Command, client: &NblmClient, json_mode: bool) -> Result<()> {
+#[derive(Args)]
+pub struct SearchArgs {
+    /// Text to search for (case-insensitive)
+    #[arg(long, value_name = "TEXT")]
+    pub query: String,
+
+    /// Treat --query as a regular expression
+    #[arg(long)]
+    pub regex: bool,
+
+    /// Notebook to search
+    #[arg(
+        long,
+        value_name = "ID",
+        value_parser = parse::notebook_id,
+        conflicts_with = "all_notebooks",
+        required_unless_present = "all_notebooks"
+    )]
+    pub notebook_id: Option<NotebookId>,
+
+    /// Search every recently viewed notebook
+    #[arg(long)]
+    pub all_notebooks: bool,
+}
+
+#[derive(Args)]
+pub struct ReingestArgs {
+    /// Notebook ID (the last segment of the notebook resource name)
+    #[arg(long, value_name = "ID", value_parser = parse::notebook_id)]
+    pub notebook_id: NotebookId,
+
+    /// Source to reingest
+    #[arg(
+        long,
+        value_name = "ID",
+        value_parser = parse::source_id,
+        conflicts_with = "all_web",
+        required_unless_present = "all_web"
+    )]
+    pub source_id: Option<SourceId>,
+
+    /// Reingest every web source in the notebook
+    #[arg(long)]
+    pub all_web: bool,
+
+    #[command(flatten)]
+    pub batch: BatchArgs,
+}
+
+pub async fn run(
+    cmd: Command,
+    client: &NblmClient,
+    out: &dyn OutputSink,
+    json_mode: bool,
+    interactive: bool,
+    interrupt: &Interrupt,
+) -> Result<()> {
     match cmd {
         Command::Add(args) => {
-            let mut contents = Vec::<UserContent>::new();
-
-            for (url, name) in pair_with_names(&args.web_urls, &args.web_names, "--web-name")? {
-                validate_url(&url)?;
-                contents.push(UserContent::Web {
-                    web_content: WebContent {
-                        url,
-                        source_name: name,
-                    },
-                });
-            }
-
-            for (text, name) in pair_with_names(&args.texts, &args.text_names, "--text-name")? {
-                if text.trim().is_empty() {
-                    bail!("--text cannot be empty");
+            let mut contents = build_contents(&args.inputs)?;
+            if args.stdin {
+                let piped = args.stdin_format.read(io::stdin().lock())?;
+                if piped.is_empty() && contents.is_empty() {
+                    bail!("no sources given (stdin was empty)");
                 }
-                contents.push(UserContent::Text {
-                    text_content: TextContent {
-                        content: text,
-                        source_name: name,
-                    },
-                });
+                contents.extend(piped);
             }
+            let includes_drive = contents
+                .iter()
+                .any(|content| matches!(content, UserContent::GoogleDrive { .. }));
 
-            let includes_drive = !args.drive_document_ids.is_empty();
-            if args.drive_document_ids.len() != args.drive_mime_types.len() {
+            if contents.is_empty() {
                 bail!(
-                    "--drive-document-id and --drive-mime-type must be specified in pairs (got {} document IDs and {} mime types)",
-                    args.drive_document_ids.len(),
-                    args.drive_mime_types.len()
+                    "at least one source must be specified (--web-url/--text/--drive-document-id/--video-url/--stdin)"
                 );
             }
-            if args.drive_names.len() > args.drive_document_ids.len() {
-                bail!("--drive-name count exceeds number of document IDs");
+
+            if includes_drive {
+                // Printed up front because --retry-failed may exit with an error.
+                out.err("NOTE: Google Drive sources require `gcloud auth login --enable-gdrive-access` and that the authenticated account has view access to the document.");
+            }
+            if args.retry_failed {
+                return add_with_retry(client, out, &args.notebook_id, contents, json_mode).await;
             }
-            for (idx, (document_id, mime_type)) in args
-                .drive_document_ids
-                .iter()
-                .zip(&args.drive_mime_types)
-                .enumerate()
-            {
-                if document_id.trim().is_empty() {
-                    bail!("--drive-document-id cannot be empty");
+            if args.wait {
+                let config = PollConfig::new(args.poll_interval, args.wait_timeout);
+                return add_and_wait(client, out, &args.notebook_id, contents, config, json_mode)
+                    .await;
+            }
+            let response = add_in_batches(client, &args.notebook_id, contents).await?;
+            emit_sources(out, args.notebook_id.as_str(), &response, json_mode)?;
+        }
+        Command::List(args) => {
+            args.output.check_json(json_mode)?;
+            let fields = FieldSelection::resolve(args.fields, json_mode);
+            let notebook = client
+                .get_notebook_with_fields(&args.notebook_id, fields.mask(NOTEBOOK_FIELDS_MINIMAL))
+                .await?;
+            let parent = client.environment().parent_path();
+            match args.output {
+                OutputMode::Ids => {
+                    for source in &notebook.sources {
+                        out.out(&source_resource_name(
+                            parent,
+                            args.notebook_id.as_str(),
+                            source,
+                        ));
+                    }
                 }
-                let source_name = args.drive_names.get(idx).and_then(|s| {
-                    let trimmed = s.trim();
-                    if trimmed.is_empty() {
-                        None
-                    } else {
-                        Some(trimmed.to_string())
+                OutputMode::Titles => {
+                    for source in &notebook.sources {
+                        out.out(source_title(source));
                     }
-                });
-                contents.push(UserContent::GoogleDrive {
-                    google_drive_content: GoogleDriveContent {
-                        document_id: document_id.clone(),
-                        mime_type: mime_type.clone(),
-                        source_name,
+                }
+                OutputMode::Details if json_mode => emit_json(
+                    out,
+                    &SourceList {
+                        notebook_id: args.notebook_id.as_str(),
+                        sources: Sorted(&notebook.sources),
                     },
-                });
+                    json_mode,
+                )?,
+                OutputMode::Details => {
+                    if notebook.sources.is_empty() {
+                        out.out(&format!("No sources in notebook {}.", args.notebook_id));
+                    }
+                    for source in &notebook.sources {
+                        let status = source
+                            .settings
+                            .as_ref()
+                            .and_then(|settings| settings.status.as_deref())
+                            .unwrap_or("-");
+                        out.out(&format!(
+                            "{}  {}  {}",
+                            source_id(source),
+                            source_title(source),
+                            status.dimmed()
+                        ));
+                    }
+                }
             }
-
-            for url in &args.video_urls {
-                validate_url(url)?;
-                contents.push(UserContent::Video {
-                    video_content: VideoContent { url: url.clone() },
-                });
+        }
+        Command::Delete(args) => {
+            let parent = client.environment().parent_path();
+            let mut source_names = args.source_names.clone();
+            if args.stdin {
+                source_names.extend(read_names(io::stdin().lock())?);
             }
-
-            if contents.is_empty() {
-                bail!(
-                    "at least one source must be specified (--web-url/--text/--drive-document-id/--video-url)"
-                );
+            source_names.extend(
+                args.source_ids
+                    .iter()
+                    .map(|id| source_name(parent, args.notebook_id.as_str(), id.as_str())),
+            );
+            let mut existing = None;
+            if !args.source_titles.is_empty() {
+                let fetched = notebook_source_refs(client, &args.notebook_id).await?;
+                source_names.extend(resolve_titles(&fetched, &args.source_titles)?);
+                existing = Some(fetched);
             }
-
-            let response = client.add_sources(&args.notebook_id, contents).await?;
-            emit_sources(&args.notebook_id, &response, json_mode)?;
-            if includes_drive {
-                eprintln!("NOTE: Google Drive sources require `gcloud auth login --enable-gdrive-access` and that the authenticated account has view access to the document.");
+            let mut seen = HashSet::new();
+            source_names.retain(|name| seen.insert(name.clone()));
+            if source_names.is_empty() {
+                bail!("no source names given (stdin was empty)");
             }
-        }
-        Command::Delete(args) => {
+            if args.bulk.dry_run {
+                return emit_dry_run(out, "source", &source_names, json_mode);
+            }
+            let (labels, matches_all) = if source_names.len() > 1 {
+                let existing = match existing {
+                    Some(existing) => existing,
+                    None => notebook_source_refs(client, &args.notebook_id).await?,
+                };
+                deletion_labels(&existing, &source_names)
+            } else {
+                (source_names.clone(), false)
+            };
+            confirm_bulk_destruction(
+                &args.bulk,
+                "source",
+                &labels,
+                matches_all,
+                terminal_prompt(args.stdin, interactive, interrupt).as_deref(),
+            )?;
             let response = client
-                .delete_sources(&args.notebook_id, args.source_names.clone())
+                .delete_sources(&args.notebook_id, source_names.clone())
                 .await?;
             if !json_mode {
-                println!("Deleted {} source(s) successfully", args.source_names.len());
+                out.out(&format!(
+                    "Deleted {} source(s) successfully",
+                    source_names.len()
+                ));
             } else {
-                use serde_json::json;
-                crate::util::io::emit_json(
-                    json!({
-                        "status": "deleted",
-                        "count": args.source_names.len(),
-                        "response": response
-                    }),
+                emit_json(
+                    out,
+                    &Deleted {
+                        status: "deleted",
+                        count: source_names.len(),
+                        response: Sorted(&response),
+                        failed: None,
+                        skipped: None,
+                        in_flight: None,
+                    },
                     json_mode,
-                );
+                )?;
             }
         }
         Command::Upload(args) => {
-            if !args.file.exists() {
-                bail!("file not found: {}", args.file.display());
+            if args.files.len() > 1 && args.display_name.is_some() {
+                bail!("--display-name can only be used with a single --file");
             }
-            if !args.file.is_file() {
-                bail!("path is not a file: {}", args.file.display());
+            if args.display_name.is_some() {
+                out.err("WARNING: NotebookLM API rejects custom display names as of 2025-10-25 (HTTP 400).");
+                out.err("The uploaded source will use the original file name instead.");
             }
 
-            let data = fs::read(&args.file)
-                .with_context(|| format!("failed to read {}", args.file.display()))?;
-            if data.is_empty() {
-                bail!("cannot upload empty files");
+            if let Some(url) = &args.url {
+                let uploaded = upload_url(
+                    client,
+                    args.notebook_id.as_str(),
+                    url,
+                    args.content_type.as_deref(),
+                    args.display_name.as_deref(),
+                    args.max_download_size,
+                )
+                .await?;
+                emit_uploaded_source(
+                    out,
+                    args.notebook_id.as_str(),
+                    &uploaded.file_name,
+                    &uploaded.content_type,
+                    &uploaded.response,
+                    json_mode,
+                )?;
+                return Ok(());
             }
 
-            let content_type = args
-                .content_type
-                .map(|value| value.trim().to_string())
-                .filter(|value| !value.is_empty())
-                .unwrap_or_else(|| {
-                    mime_guess::from_path(&args.file)
-                        .first_or_octet_stream()
-                        .essence_str()
-                        .to_string()
-                });
-
-            let inferred_name = args
-                .display_name
-                .as_ref()
-                .map(|s| s.trim())
-                .filter(|s| !s.is_empty())
-                .map(|s| s.to_string())
-                .or_else(|| {
-                    args.file
-                        .file_name()
-                        .and_then(|name| name.to_str())
-                        .map(|s| s.to_string())
-                })
-                .ok_or_else(|| anyhow!("could not determine file name; use --display-name"))?;
-
-            if args.display_name.is_some() {
-                eprintln!(
-                    "WARNING: NotebookLM API rejects custom display names as of 2025-10-25 (HTTP 400)."
-                );
-                eprintln!("The uploaded source will use the original file name instead.");
+            if let [file] = args.files.as_slice() {
+                let uploaded = upload_file(
+                    client,
+                    args.notebook_id.as_str(),
+                    file,
+                    args.content_type.as_deref(),
+                    args.display_name.as_deref(),
+                    args.max_file_size,
+                )
+                .await?;
+                emit_uploaded_source(
+                    out,
+                    args.notebook_id.as_str(),
+                    &uploaded.file_name,
+                    &uploaded.content_type,
+                    &uploaded.response,
+                    json_mode,
+                )?;
+                return Ok(());
             }
 
-            let response = client
-                .upload_source_file(&args.notebook_id, &inferred_name, &content_type, data)
-                .await?;
+            let mode = args.batch.mode(FailureMode::KeepGoing);
+            let notebook_id = args.notebook_id.as_str();
+            let content_type = args.content_type.as_deref();
+            let max_file_size = args.max_file_size;
+            let label = |index: usize| args.files[index].display().to_string();
+            let warnings = WarningDeduper::default();
+            // Text output is written as each file finishes; only --json needs the results kept.
+            let mut uploads = Vec::new();
+            let mut failed = Vec::new();
+            let summary = run_batch_streaming(
+                args.files.clone(),
+                1,
+                mode,
+                interrupt,
+                |file| async move {
+                    upload_file(
+                        client,
+                        notebook_id,
+                        &file,
+                        content_type,
+                        None,
+                        max_file_size,
+                    )
+                    .await
+                },
+                |index, result| match result {
+                    Ok(uploaded) if json_mode => uploads.push(uploaded),
+                    Ok(uploaded) => out.out(&created_source_line(&uploaded)),
+                    Err(err) => {
+                        let error = format!("{err:#}");
+                        warnings.warn(
+                            out,
+                            &error,
+                            &format!("Failed to upload {}: {error}", label(index)),
+                        );
+                        if json_mode {
+                            failed.push(FailedItem {
+                                file: Some(label(index)),
+                                name: None,
+                                error,
+                            });
+                        }
+                    }
+                },
+            )
+            .await;
 
-            emit_uploaded_source(
-                &args.notebook_id,
-                &inferred_name,
-                &content_type,
-                &response,
-                json_mode,
-            )?;
+            for index in &summary.skipped {
+                warnings.warn(out, "skipped", &format!("Skipped {}", label(*index)));
+            }
+            warnings.summarize(out);
+            summary.report_interruption(out, "uploaded", label);
+
+            if json_mode {
+                let payload = SourcesUploaded {
+                    notebook_id: args.notebook_id.as_str(),
+                    uploads: uploads
+                        .iter()
+                        .map(|uploaded| UploadEntry {
+                            file_name: &uploaded.file_name,
+                            content_type: &uploaded.content_type,
+                            source_id: Sorted(&uploaded.response.source_id),
+                            extra: Sorted(&uploaded.response.extra),
+                        })
+                        .collect(),
+                    failed,
+                    skipped: summary.skipped.iter().map(|index| label(*index)).collect(),
+                    in_flight: summary
+                        .abandoned
+                        .iter()
+                        .map(|index| label(*index))
+                        .collect(),
+                };
+                emit_json(out, &payload, json_mode)?;
+            } else {
+                out.out(&format!(
+                    "Uploaded {} of {} file(s)",
+                    summary.succeeded.len(),
+                    summary.total
+                ));
+            }
+            summary.finish(args.batch.allow_partial, "file", label)?;
         }
         Command::Get(args) => {
+            args.output.check_json(json_mode)?;
             let source = client
-                .get_source(&args.notebook_id, &args.source_id)
+                .get_source_raw(&args.notebook_id, &args.source_id)
                 .await?;
 
-            if json_mode {
-                crate::util::io::emit_json(serde_json::json!(&source), json_mode);
-            } else {
-                emit_source(&source);
+            match args.output {
+                OutputMode::Ids => out.out(&source_resource_name(
+                    client.environment().parent_path(),
+                    args.notebook_id.as_str(),
+                    source.value(),
+                )),
+                OutputMode::Titles => out.out(source_title(source.value())),
+                OutputMode::Details if json_mode => emit_raw_json(out, source.raw())?,
+                OutputMode::Details => emit_source(out, source.value()),
+            }
+        }
+        Command::Search(args) => search(client, out, args, json_mode).await?,
+        Command::Reingest(args) => reingest(client, out, args, json_mode, interrupt).await?,
+    }
+    Ok(())
+}
+
+/// Validate source flags and turn them into API contents, in flag order by kind.
+pub fn build_contents(args: &SourceInputArgs) -> Result<Vec<UserContent>> {
+    let mut contents = Vec::<UserContent>::new();
+
+    let fetch_options = web_fetch_options(args)?;
+    for (url, name) in pair_with_names(&args.web_urls, &args.web_names, "--web-name")? {
+        validate_url(&url)?;
+        contents.push(UserContent::Web {
+            web_content: WebContent {
+                url,
+                source_name: name,
+                fetch_options: fetch_options.clone(),
+            },
+        });
+    }
+
+    for (text, name) in pair_with_names(&args.texts, &args.text_names, "--text-name")? {
+        if text.trim().is_empty() {
+            bail!("--text cannot be empty");
+        }
+        contents.push(UserContent::Text {
+            text_content: TextContent {
+                content: text,
+                source_name: name,
+            },
+        });
+    }
+
+    if args.drive_document_ids.len() != args.drive_mime_types.len() {
+        bail!(
+            "--drive-document-id and --drive-mime-type must be specified in pairs (got {} document IDs and {} mime types)",
+            args.drive_document_ids.len(),
+            args.drive_mime_types.len()
+        );
+    }
+    if args.drive_names.len() > args.drive_document_ids.len() {
+        bail!("--drive-name count exceeds number of document IDs");
+    }
+    for (idx, (document_id, mime_type)) in args
+        .drive_document_ids
+        .iter()
+        .zip(&args.drive_mime_types)
+        .enumerate()
+    {
+        if document_id.trim().is_empty() {
+            bail!("--drive-document-id cannot be empty");
+        }
+        let source_name = source_name_arg(
+            args.drive_names.get(idx).map(String::as_str),
+            "--drive-name",
+        )?;
+        contents.push(UserContent::GoogleDrive {
+            google_drive_content: GoogleDriveContent {
+                document_id: document_id.clone(),
+                mime_type: mime_type.clone(),
+                source_name,
+            },
+        });
+    }
+
+    for url in &args.video_urls {
+        validate_url(url)?;
+        contents.push(UserContent::Video {
+            video_content: VideoContent { url: url.clone() },
+        });
+    }
+
+    Ok(contents)
+}
+
+/// Text-mode line for one uploaded file.
+fn created_source_line(uploaded: &UploadedFile) -> String {
+    match uploaded
+        .response
+        .source_id
+        .as_ref()
+        .and_then(|id| id.id.as_deref())
+    {
+        Some(source_id) => format!("Created source: {source_id} ({})", uploaded.file_name),
+        None => format!(
+            "Upload request accepted (source ID unavailable) ({})",
+            uploaded.file_name
+        ),
+    }
+}
+
+/// `--web-fetch-option` pairs as one JSON object, or `None` when none were given.
+fn web_fetch_options(args: &SourceInputArgs) -> Result<Option<serde_json::Value>> {
+    if args.web_fetch_options.is_empty() {
+        return Ok(None);
+    }
+    if args.web_urls.is_empty() {
+        bail!("--web-fetch-option requires at least one --web-url");
+    }
+    let mut options = serde_json::Map::new();
+    for (key, value) in &args.web_fetch_options {
+        if options.insert(key.clone(), value.clone()).is_some() {
+            bail!("--web-fetch-option {key} was given more than once");
+        }
+    }
+    Ok(Some(serde_json::Value::Object(options)))
+}
+
+/// Notebooks fetched concurrently by `sources search --all-notebooks`.
+const SEARCH_CONCURRENCY: usize = 4;
+/// Characters of context shown on each side of a search match.
+const SNIPPET_CONTEXT: usize = 40;
+
+struct SearchMatch {
+    notebook_id: String,
+    source: NotebookSource,
+    field: &'static str,
+    snippet: Snippet,
+}
+
+async fn search(
+    client: &NblmClient,
+    out: &dyn OutputSink,
+    args: SearchArgs,
+    json_mode: bool,
+) -> Result<()> {
+    let matcher = Matcher::new(&args.query, args.regex)?;
+
+    let notebook_ids = match args.notebook_id {
+        Some(id) => vec![id.into_string()],
+        None => client
+            .list_recently_viewed(None)
+            .await?
+            .notebooks
+            .iter()
+            .filter_map(|notebook| notebook.notebook_id().map(str::to_string))
+            .collect(),
+    };
+
+    // Nothing to clean up in a read-only search, so a signal ends it right away.
+    let outcome = run_batch(
+        notebook_ids.clone(),
+        SEARCH_CONCURRENCY,
+        FailureMode::KeepGoing,
+        &Interrupt::default(),
+        |id| async move { client.get_notebook(&id).await.map_err(anyhow::Error::from) },
+    )
+    .await;
+    let mut notes: Vec<String> = outcome
+        .failed
+        .iter()
+        .map(|(index, err)| format!("could not fetch notebook {}: {err}", notebook_ids[*index]))
+        .collect();
+    if outcome.succeeded.is_empty() && !notes.is_empty() {
+        bail!("{}", notes.join("; "));
+    }
+    // The API has no endpoint for source text, so only metadata is searchable.
+    notes.push(
+        "source content is not available from the API; searched titles and URLs only".to_string(),
+    );
+
+    let mut matches = Vec::new();
+    for (index, notebook) in &outcome.succeeded {
+        for source in &notebook.sources {
+            let fields = std::iter::once(("title", source.title.clone().unwrap_or_default()))
+                .chain(source_urls(source).into_iter().map(|url| ("url", url)));
+            for (field, text) in fields {
+                if let Some(range) = matcher.find(&text) {
+                    matches.push(SearchMatch {
+                        notebook_id: notebook_ids[*index].clone(),
+                        source: source.clone(),
+                        field,
+                        snippet: snippet(&text, range, SNIPPET_CONTEXT),
+                    });
+                    break;
+                }
             }
         }
     }
+
+    if json_mode {
+        let payload = SearchResults {
+            query: &args.query,
+            matches: matches
+                .iter()
+                .map(|m| SearchHit {
+                    notebook_id: &m.notebook_id,
+                    source_name: &m.source.name,
+                    source_id: source_id(&m.source),
+                    title: m.source.title.as_deref(),
+                    field: m.field,
+                    matched: &m.snippet.matched,
+                    snippet: m.snippet.to_string(),
+                })
+                .collect(),
+            notes: &notes,
+        };
+        return emit_json(out, &payload, true);
+    }
+
+    for note in &notes {
+        out.err(&format!("Note: {note}"));
+    }
+    if matches.is_empty() {
+        out.out(&format!("No matches for '{}'.", args.query));
+    }
+    for m in &matches {
+        out.out(&format!(
+            "{}/{}  {}",
+            m.notebook_id,
+            source_id(&m.source),
+            m.source.title.as_deref().unwrap_or("(untitled)")
+        ));
+        out.out(&format!(
+            "    {}: {}{}{}",
+            m.field,
+            m.snippet.before,
+            m.snippet.matched.yellow().bold(),
+            m.snippet.after
+        ));
+    }
     Ok(())
 }
+
+/// Sources reingested concurrently by `sources reingest --all-web`.
+const REINGEST_CONCURRENCY: usize = 4;
+
+async fn reingest(
+    client: &NblmClient,
+    out: &dyn OutputSink,
+    args: ReingestArgs,
+    json_mode: bool,
+    interrupt: &Interrupt,
+) -> Result<()> {
+    let notebook_id = args.notebook_id.as_str();
+    let sources = match &args.source_id {
+        Some(id) => vec![client.get_source(&args.notebook_id, id).await?],
+        None => client.get_notebook(&args.notebook_id).await?.sources,
+    };
+
+    let mut candidates = Vec::new();
+    let mut skipped = Vec::new();
+    for source in sources {
+        match source.origin() {
+            Some(SourceOrigin::Video { .. }) if args.all_web => {
+                skipped.push((source, "YouTube video; reingest it with --source-id"));
+            }
+            Some(origin) => candidates.push((source, origin)),
+            None => skipped.push((source, "no web or YouTube URL in the source's metadata")),
+        }
+    }
+    if let (Some(id), Some((_, reason))) = (&args.source_id, skipped.first()) {
+        bail!("source {id} cannot be reingested: {reason}");
+    }
+
+    let parent = client.environment().parent_path();
+    let outcome = run_batch(
+        (0..candidates.len()).collect(),
+        REINGEST_CONCURRENCY,
+        args.batch.mode(FailureMode::KeepGoing),
+        interrupt,
+        |index| {
+            let (source, origin) = &candidates[index];
+            reingest_one(client, parent, notebook_id, source, origin)
+        },
+    )
+    .await;
+
+    let label = |index: usize| source_id(&candidates[index].0).to_string();
+    for (source, reason) in &skipped {
+        out.err(&format!(
+            "Skipped {} ({}): {reason}",
+            source_id(source),
+            source_title(source)
+        ));
+    }
+    for (index, err) in &outcome.failed {
+        out.err(&format!("Failed to reingest {}: {err:#}", label(*index)));
+    }
+    for index in &outcome.skipped {
+        out.err(&format!(
+            "Skipped {} after an earlier failure",
+            label(*index)
+        ));
+    }
+    outcome.report_interruption(out, "reingested", label);
+
+    if json_mode {
+        let not_started = "not started after an earlier failure";
+        let payload = SourcesReingested {
+            notebook_id,
+            reingested: outcome
+                .succeeded
+                .iter()
+                .map(|(index, new_id)| {
+                    let (source, origin) = &candidates[*index];
+                    ReingestedSource {
+                        old_source_id: source_id(source),
+                        new_source_id: new_id,
+                        url: origin.url(),
+                        title: source.title.as_deref(),
+                    }
+                })
+                .collect(),
+            skipped: skipped
+                .iter()
+                .map(|(source, reason)| (source, *reason))
+                .chain(
+                    outcome
+                        .skipped
+                        .iter()
+                        .map(|index| (&candidates[*index].0, not_started)),
+                )
+                .map(|(source, reason)| SkippedSource {
+                    source_id: source_id(source),
+                    title: source.title.as_deref(),
+                    reason,
+                })
+                .collect(),
+            failed: outcome
+                .failed
+                .iter()
+                .map(|(index, err)| FailedItem {
+                    file: None,
+                    name: Some(label(*index)),
+                    error: format!("{err:#}"),
+                })
+                .collect(),
+            in_flight: outcome
+                .abandoned
+                .iter()
+                .map(|index| label(*index))
+                .collect(),
+        };
+        emit_json(out, &payload, json_mode)?;
+    } else {
+        for (index, new_id) in &outcome.succeeded {
+            let (source, origin) = &candidates[*index];
+            out.out(&format!(
+                "Reingested {} -> {new_id} ({})",
+                source_id(source),
+                origin.url()
+            ));
+        }
+        if args.all_web {
+            out.out(&format!(
+                "Reingested {} of {} web source(s)",
+                outcome.succeeded.len(),
+                outcome.total
+            ));
+        }
+    }
+    outcome.finish(args.batch.allow_partial, "source")
+}
+
+/// Delete `source` and add its URL again under the same title, returning the new source ID.
+async fn reingest_one(
+    client: &NblmClient,
+    parent: &str,
+    notebook_id: &str,
+    source: &NotebookSource,
+    origin: &SourceOrigin,
+) -> Result<String> {
+    client
+        .delete_sources(
+            notebook_id,
+            vec![source_resource_name(parent, notebook_id, source)],
+        )
+        .await
+        .context("failed to delete the source")?;
+    let lost = || {
+        format!(
+            "deleted the source but could not add {} again; add it back with `nblm sources add`",
+            origin.url()
+        )
+    };
+    let result = client
+        .add_sources_partial(notebook_id, vec![origin.to_content(source.title.clone())])
+        .await
+        .with_context(lost)?;
+    match result.succeeded.first() {
+        Some(ingested) => Ok(source_id(&ingested.source).to_string()),
+        None => {
+            let status = result
+                .failed
+                .first()
+                .and_then(|failed| failed.source.as_ref())
+                .and_then(|source| source.settings.as_ref())
+                .and_then(|settings| settings.status.as_deref())
+                .unwrap_or("no source returned");
+            bail!("{} ({status})", lost())
+        }
+    }
+}
+
+fn source_id(source: &NotebookSource) -> &str {
+    source.source_id().unwrap_or_default()
+}
+
+fn source_title(source: &NotebookSource) -> &str {
+    source.title.as_deref().unwrap_or("(untitled)")
+}
+
+/// Full `projects/.../sources/ID` name, which `sources delete` requires.
+fn source_resource_name(parent: &str, notebook_id: &str, source: &NotebookSource) -> String {
+    if source.name.contains('/') {
+        source.name.clone()
+    } else {
+        source_name(parent, notebook_id, source_id(source))
+    }
+}
+
+fn source_name(parent: &str, notebook_id: &str, source_id: &str) -> String {
+    format!("{parent}/notebooks/{notebook_id}/sources/{source_id}")
+}
+
+fn ref_title(source: &SourceRef) -> &str {
+    source.title.as_deref().unwrap_or("(untitled)")
+}
+
+/// References to the sources of `notebook_id`, for the title lookups of `sources delete`.
+/// Sources without an ID cannot be deleted and are left out.
+async fn notebook_source_refs(
+    client: &NblmClient,
+    notebook_id: &NotebookId,
+) -> Result<Vec<SourceRef>> {
+    let notebook = client.get_notebook(notebook_id).await?;
+    let parent = client.environment().parent_path();
+    Ok(notebook
+        .sources
+        .iter()
+        .filter_map(|source| SourceRef::in_notebook(parent, notebook_id.as_str(), source).ok())
+        .collect())
+}
+
+/// Full names of the sources picked by `--source-title`. Each text must be part of exactly one
+/// title, or the whole title of one source (ignoring case); if any is not, the error lists every
+/// text that could not be resolved so nothing is deleted.
+fn resolve_titles(sources: &[SourceRef], titles: &[String]) -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    let mut problems = Vec::new();
+    for wanted in titles {
+        let needle = wanted.to_lowercase();
+        let matches: Vec<&SourceRef> = sources
+            .iter()
+            .filter(|source| ref_title(source).to_lowercase().contains(&needle))
+            .collect();
+        let exact: Vec<&SourceRef> = matches
+            .iter()
+            .copied()
+            .filter(|source| ref_title(source).to_lowercase() == needle)
+            .collect();
+        match (matches.as_slice(), exact.as_slice()) {
+            ([source], _) | (_, [source]) => {
+                names.push(source.name.clone());
+            }
+            ([], _) => problems.push(format!(
+                "--source-title '{wanted}': no source title contains it"
+            )),
+            (candidates, _) => problems.push(format!(
+                "--source-title '{wanted}': matches {} sources: {}",
+                candidates.len(),
+                candidates
+                    .iter()
+                    .map(|source| format!("{} ({})", ref_title(source), source.source_id))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+        }
+    }
+    if !problems.is_empty() {
+        bail!(
+            "could not resolve every source to delete; nothing was deleted:\n  {}",
+            problems.join("\n  ")
+        );
+    }
+    Ok(names)
+}
+
+/// Titles of the sources named for deletion (names for unknown ones), and whether they are every
+/// source in the notebook.
+fn deletion_labels(sources: &[SourceRef], names: &[String]) -> (Vec<String>, bool) {
+    let existing: HashMap<&str, &str> = sources
+        .iter()
+        .map(|source| (source.name.as_str(), ref_title(source)))
+        .collect();
+    let labels = names
+        .iter()
+        .map(|name| match existing.get(name.as_str()) {
+            Some(title) => (*title).to_string(),
+            None => name.clone(),
+        })
+        .collect();
+    let matches_all = !existing.is_empty()
+        && existing
+            .keys()
+            .all(|name| names.iter().any(|wanted| wanted == *name));
+    (labels, matches_all)
+}
+
+/// Non-empty trimmed lines, for names piped in with `--stdin`.
+fn read_names(reader: impl BufRead) -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    for line in reader.lines() {
+        let line = line.context("failed to read source names from stdin")?;
+        let name = line.trim();
+        if !name.is_empty() {
+            names.push(name.to_string());
+        }
+    }
+    Ok(names)
+}
+
+/// URL-looking string values anywhere in the source's metadata.
+fn source_urls(source: &NotebookSource) -> Vec<String> {
+    fn collect(value: &serde_json::Value, urls: &mut Vec<String>) {
+        match value {
+            serde_json::Value::String(s)
+                if s.starts_with("http://") || s.starts_with("https://") =>
+            {
+                urls.push(s.clone())
+            }
+            serde_json::Value::Array(items) => items.iter().for_each(|item| collect(item, urls)),
+            serde_json::Value::Object(map) => map.values().for_each(|item| collect(item, urls)),
+            _ => {}
+        }
+    }
+
+    let mut urls = Vec::new();
+    if let Ok(value) = serde_json::to_value(source) {
+        collect(&value, &mut urls);
+    }
+    urls
+}
+
+/// Sources sent in one `batchCreate` request; more are split across several requests.
+const ADD_BATCH_SIZE: usize = 100;
+
+/// Add `contents` in requests of at most [`ADD_BATCH_SIZE`] sources, one after another, and
+/// merge the responses as if they were one. A failed request ends the run; the sources of the
+/// requests before it stay added.
+async fn add_in_batches(
+    client: &NblmClient,
+    notebook_id: &NotebookId,
+    contents: Vec<UserContent>,
+) -> Result<ResponseEnvelope<BatchCreateSourcesResponse>> {
+    if contents.len() <= ADD_BATCH_SIZE {
+        return Ok(client.add_sources_raw(notebook_id, contents).await?);
+    }
+    let batches = contents.len().div_ceil(ADD_BATCH_SIZE);
+    let mut merged = BatchCreateSourcesResponse::default();
+    let mut raw_sources = Vec::with_capacity(contents.len());
+    for (batch, chunk) in contents.chunks(ADD_BATCH_SIZE).enumerate() {
+        let envelope = client
+            .add_sources_raw(notebook_id, chunk.to_vec())
+            .await
+            .with_context(|| {
+                format!(
+                    "request {} of {batches} failed; the {} sources before it were added",
+                    batch + 1,
+                    batch * ADD_BATCH_SIZE
+                )
+            })?;
+        let mut raw: serde_json::Value = serde_json::from_slice(envelope.raw())?;
+        if let Some(serde_json::Value::Array(sources)) =
+            raw.get_mut("sources").map(serde_json::Value::take)
+        {
+            raw_sources.extend(sources);
+        }
+        let response = envelope.into_inner();
+        merged.sources.extend(response.sources);
+        if let Some(errors) = response.error_count {
+            merged.error_count = Some(merged.error_count.unwrap_or(0) + errors);
+        }
+    }
+    let mut raw = serde_json::json!({ "sources": raw_sources });
+    if let Some(errors) = merged.error_count {
+        raw["errorCount"] = errors.into();
+    }
+    Ok(ResponseEnvelope::new(
+        merged,
+        serde_json::to_vec(&raw)?.into(),
+    ))
+}
+
+/// [`add_in_batches`], split into created and failed sources like
+/// [`NblmClient::add_sources_partial`].
+async fn add_partial_in_batches(
+    client: &NblmClient,
+    notebook_id: &NotebookId,
+    contents: Vec<UserContent>,
+) -> Result<PartialIngestResult> {
+    let response = add_in_batches(client, notebook_id, contents.clone()).await?;
+    Ok(PartialIngestResult::from_response(
+        contents,
+        response.into_inner(),
+    ))
+}
+
+/// Add sources and re-submit the ones the API reported as failed exactly once.
+async fn add_with_retry(
+    client: &NblmClient,
+    out: &dyn OutputSink,
+    notebook_id: &NotebookId,
+    contents: Vec<UserContent>,
+    json_mode: bool,
+) -> Result<()> {
+    let first = add_partial_in_batches(client, notebook_id, contents.clone()).await?;
+    let mut sources: Vec<NotebookSource> = first
+        .succeeded
+        .iter()
+        .map(|ingested| ingested.source.clone())
+        .collect();
+    let mut retried = Vec::new();
+    let mut still_failed = Vec::new();
+
+    if !first.is_complete() {
+        if !json_mode {
+            out.err(&format!("Retrying {} failed source(s)", first.failed.len()));
+        }
+        let second = add_partial_in_batches(client, notebook_id, first.failed_contents()).await?;
+        for ingested in &second.succeeded {
+            let original = &first.failed[ingested.index];
+            let label = content_label(&original.content);
+            if !json_mode {
+                out.err(&format!("  {label}: added ({})", ingested.source.name));
+            }
+            retried.push(RetriedSource {
+                index: original.index,
+                source: label,
+                outcome: RetryOutcome::Added {
+                    name: ingested.source.name.clone(),
+                },
+            });
+            sources.push(ingested.source.clone());
+        }
+        for failed in &second.failed {
+            let original = &first.failed[failed.index];
+            let label = content_label(&original.content);
+            let status = failed
+                .source
+                .as_ref()
+                .and_then(|source| source.settings.as_ref())
+                .and_then(|settings| settings.status.clone());
+            if !json_mode {
+                out.err(&format!(
+                    "  {label}: failed ({})",
+                    status.as_deref().unwrap_or("no source returned")
+                ));
+            }
+            still_failed.push(label.clone());
+            retried.push(RetriedSource {
+                index: original.index,
+                source: label,
+                outcome: RetryOutcome::Failed { api_status: status },
+            });
+        }
+        retried.sort_by_key(|entry| entry.index);
+    }
+
+    emit_json(
+        out,
+        &SourcesAdded {
+            notebook_id: notebook_id.as_str(),
+            source_ids: None,
+            sources: Sorted(serde_json::to_value(&sources)?),
+            retried: Some(retried),
+            error_count: still_failed.len().into(),
+        },
+        json_mode,
+    )?;
+
+    if !still_failed.is_empty() {
+        bail!(
+            "{} of {} source(s) still failed after retry: {}",
+            still_failed.len(),
+            contents.len(),
+            still_failed.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Add `contents`, poll the added sources until they are ingested, and report how long each
+/// one took, with the median and longest time per kind.
+async fn add_and_wait(
+    client: &NblmClient,
+    out: &dyn OutputSink,
+    notebook_id: &NotebookId,
+    contents: Vec<UserContent>,
+    config: PollConfig,
+    json_mode: bool,
+) -> Result<()> {
+    let started = Instant::now();
+    let added = add_partial_in_batches(client, notebook_id, contents.clone()).await?;
+    let submitted = started.elapsed();
+    let sources: Vec<NotebookSource> = added
+        .succeeded
+        .iter()
+        .map(|ingested| ingested.source.clone())
+        .collect();
+    let waits = client.wait_for_sources(notebook_id, &sources, config).await;
+
+    let mut rows = Vec::with_capacity(contents.len());
+    let mut finished: Vec<(&'static str, Duration)> = Vec::new();
+    for (ingested, polled) in added.succeeded.iter().zip(waits) {
+        let kind = contents[ingested.index].kind();
+        let elapsed = submitted + polled.elapsed;
+        let (state, error) = match polled.result {
+            Ok(source) => {
+                finished.push((kind, elapsed));
+                (source.status().unwrap_or("-").to_string(), None)
+            }
+            Err(CoreError::WaitTimedOut { .. }) => ("TIMED_OUT".to_string(), None),
+            Err(CoreError::OperationFailed { state, .. }) => (state, None),
+            Err(err) => ("ERROR".to_string(), Some(err.to_string())),
+        };
+        rows.push(IngestTiming {
+            index: ingested.index,
+            source_id: ingested.source.source_id().map(str::to_string),
+            kind,
+            state,
+            duration_secs: Some(elapsed.as_secs_f64()),
+            polls: polled.polls,
+            error,
+        });
+    }
+    for failed in &added.failed {
+        let source = failed.source.as_ref();
+        rows.push(IngestTiming {
+            index: failed.index,
+            source_id: source
+                .and_then(NotebookSource::source_id)
+                .map(str::to_string),
+            kind: failed.content.kind(),
+            state: source
+                .and_then(NotebookSource::status)
+                .unwrap_or("FAILED")
+                .to_string(),
+            duration_secs: None,
+            polls: 0,
+            error: None,
+        });
+    }
+    rows.sort_by_key(|row| row.index);
+
+    let mut kinds: Vec<&'static str> = finished.iter().map(|(kind, _)| *kind).collect();
+    kinds.sort_unstable();
+    kinds.dedup();
+    let by_kind: Vec<(&'static str, DurationStats)> = kinds
+        .into_iter()
+        .filter_map(|kind| {
+            let durations = finished
+                .iter()
+                .filter(|(finished_kind, _)| *finished_kind == kind)
+                .map(|(_, elapsed)| *elapsed);
+            DurationStats::from_durations(durations).map(|stats| (kind, stats))
+        })
+        .collect();
+
+    if json_mode {
+        let payload = SourcesIngested {
+            notebook_id: notebook_id.as_str(),
+            by_kind: by_kind
+                .iter()
+                .map(|(kind, stats)| KindTiming {
+                    kind,
+                    count: stats.count,
+                    median_secs: stats.median.as_secs_f64(),
+                    max_secs: stats.max.as_secs_f64(),
+                })
+                .collect(),
+            sources: rows,
+        };
+        emit_json(out, &payload, json_mode)?;
+    } else {
+        for row in &rows {
+            if let Some(error) = &row.error {
+                let id = row.source_id.as_deref().unwrap_or("-");
+                out.err(&format!("Failed to check source {id}: {error}"));
+            }
+        }
+        for line in ingest_table(&rows, &by_kind) {
+            out.out(&line);
+        }
+    }
+
+    let unfinished = contents.len() - finished.len();
+    if unfinished > 0 {
+        bail!(
+            "{unfinished} of {} source(s) did not finish ingesting",
+            contents.len()
+        );
+    }
+    Ok(())
+}
+
+/// The `sources add --wait` summary: one row per source, then a row per kind.
+fn ingest_table(rows: &[IngestTiming], by_kind: &[(&str, DurationStats)]) -> Vec<String> {
+    let seconds = |secs: f64| wait::format_elapsed(Duration::from_secs_f64(secs));
+    let id_width = rows
+        .iter()
+        .filter_map(|row| row.source_id.as_deref())
+        .map(str::len)
+        .max()
+        .unwrap_or(0)
+        .max("SOURCE".len());
+    let mut lines = vec![format!(
+        "{:<id_width$}  {:<5}  {:<24}  {:<8}  POLLS",
+        "SOURCE", "KIND", "STATE", "DURATION"
+    )];
+    for row in rows {
+        let duration = row
+            .duration_secs
+            .map(seconds)
+            .unwrap_or_else(|| "-".to_string());
+        lines.push(format!(
+            "{:<id_width$}  {:<5}  {:<24}  {duration:<8}  {}",
+            row.source_id.as_deref().unwrap_or("-"),
+            row.kind,
+            row.state,
+            row.polls
+        ));
+    }
+    if !by_kind.is_empty() {
+        lines.push(String::new());
+        lines.push(format!(
+            "{:<5}  {:<5}  {:<8}  MAX",
+            "KIND", "COUNT", "MEDIAN"
+        ));
+        for (kind, stats) in by_kind {
+            lines.push(format!(
+                "{kind:<5}  {:<5}  {:<8}  {}",
+                stats.count,
+                wait::format_elapsed(stats.median),
+                wait::format_elapsed(stats.max)
+            ));
+        }
+    }
+    lines
+}
+
+/// Short human-readable identifier for a submitted source.
+fn content_label(content: &UserContent) -> String {
+    match content {
+        UserContent::Web { web_content } => web_content.url.clone(),
+        UserContent::Text { text_content } => text_content
+            .source_name
+            .clone()
+            .unwrap_or_else(|| "text source".to_string()),
+        UserContent::GoogleDrive {
+            google_drive_content,
+        } => google_drive_content.document_id.clone(),
+        UserContent::Video { video_content } => video_content.url.clone(),
+    }
+}
+
+struct UploadedFile {
+    file_name: String,
+    content_type: String,
+    response: UploadSourceFileResponse,
+}
+
+async fn upload_file(
+    client: &NblmClient,
+    notebook_id: &str,
+    file: &Path,
+    content_type: Option<&str>,
+    display_name: Option<&str>,
+    max_file_size: Option<u64>,
+) -> Result<UploadedFile> {
+    let started = client.is_observed().then(Instant::now);
+    let prepared = prepare_upload(file, content_type, display_name, max_file_size)?;
+    if let Some(started) = started {
+        client.report_phase(
+            Phase::FileRead {
+                path: file.to_path_buf(),
+            },
+            started.elapsed(),
+        );
+    }
+    let response = client
+        .upload_source_file(
+            notebook_id,
+            &prepared.file_name,
+            &prepared.content_type,
+            prepared.data,
+        )
+        .await?;
+
+    Ok(UploadedFile {
+        file_name: prepared.file_name,
+        content_type: prepared.content_type,
+        response,
+    })
+}
+
+/// Download `url` and upload it as a file named after the last segment of its final URL.
+async fn upload_url(
+    client: &NblmClient,
+    notebook_id: &str,
+    url: &Url,
+    content_type: Option<&str>,
+    display_name: Option<&str>,
+    max_download_size: u64,
+) -> Result<UploadedFile> {
+    let download = download::fetch(&download::http_client()?, url, max_download_size).await?;
+    if download.is_html() {
+        bail!(
+            "{url} returned a web page ({}), not a file; add it with `nblm sources add --web-url {url}` instead",
+            download.content_type.as_deref().unwrap_or_default()
+        );
+    }
+    if download.data.is_empty() {
+        bail!("cannot upload empty files");
+    }
+
+    let file_name = display_name
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .or_else(|| download.file_name())
+        .ok_or_else(|| {
+            anyhow!(
+                "could not determine a file name from {}; use --display-name",
+                download.url
+            )
+        })?;
+    // A generic binary type says nothing, so fall back to the file name as for local files.
+    let content_type = content_type
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .or_else(|| {
+            download
+                .content_type
+                .clone()
+                .filter(|value| value != "application/octet-stream")
+        })
+        .unwrap_or_else(|| infer_content_type(Path::new(&file_name), mime_overrides()));
+
+    let response = client
+        .upload_source_file(notebook_id, &file_name, &content_type, download.data)
+        .await?;
+
+    Ok(UploadedFile {
+        file_name,
+        content_type,
+        response,
+    })
+}
+
+/// A validated file ready to send to the upload endpoint.
+pub struct PreparedUpload {
+    pub file_name: String,
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+/// Check and read `file`, resolving its upload file name and content type.
+pub fn prepare_upload(
+    file: &Path,
+    content_type: Option<&str>,
+    display_name: Option<&str>,
+    max_file_size: Option<u64>,
+) -> Result<PreparedUpload> {
+    let data = read_upload_file(file, max_file_size).map_err(|err| match err {
+        UploadFileError::TooLarge { size, limit, .. } => anyhow!(
+            "{} is {size} bytes, larger than --max-file-size ({limit} bytes)",
+            file.display()
+        ),
+        err => err.into(),
+    })?;
+
+    let content_type = content_type
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .unwrap_or_else(|| infer_content_type(Path::new(file), mime_overrides()));
+
+    let file_name = display_name
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .or_else(|| {
+            file.file_name()
+                .and_then(|name| name.to_str())
+                .map(|s| s.to_string())
+        })
+        .ok_or_else(|| anyhow!("could not determine file name; use --display-name"))?;
+
+    Ok(PreparedUpload {
+        file_name,
+        content_type,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::test_support::{client, PROJECT};
+    use crate::util::output::CapturedOutput;
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn ingest_table_lists_each_source_then_each_kind() {
+        let row = |index, source_id: Option<&str>, kind, state: &str, secs| IngestTiming {
+            index,
+            source_id: source_id.map(str::to_string),
+            kind,
+            state: state.to_string(),
+            duration_secs: secs,
+            polls: if secs.is_some() { 3 } else { 0 },
+            error: None,
+        };
+        let rows = [
+            row(
+                0,
+                Some("source-1"),
+                "web",
+                "SOURCE_STATUS_COMPLETE",
+                Some(75.4),
+            ),
+            row(1, None, "text", "SOURCE_STATUS_ERROR", None),
+        ];
+        let stats = DurationStats::from_durations([Duration::from_secs_f64(75.4)]).unwrap();
+
+        assert_eq!(
+            ingest_table(&rows, &[("web", stats)]),
+            [
+                "SOURCE    KIND   STATE                     DURATION  POLLS",
+                "source-1  web    SOURCE_STATUS_COMPLETE    1m 15s    3",
+                "-         text   SOURCE_STATUS_ERROR       -         0",
+                "",
+                "KIND   COUNT  MEDIAN    MAX",
+                "web    1      1m 15s    1m 15s",
+            ]
+        );
+        assert_eq!(ingest_table(&rows[1..], &[]).len(), 2);
+    }
+
+    async fn mock_batch_delete(server: &MockServer) {
+        Mock::given(method("POST"))
+            .and(path(format!(
+                "/v1alpha/projects/{PROJECT}/locations/global/notebooks/nb1/sources:batchDelete"
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+            .mount(server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/v1alpha/projects/{PROJECT}/locations/global/notebooks/nb1"
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "title": "Notebook",
+                "sources": [
+                    {"name": "source-a", "title": "A"},
+                    {"name": "source-b", "title": "B"},
+                    {"name": "source-c", "title": "C"}
+                ]
+            })))
+            .mount(server)
+            .await;
+    }
+
+    fn delete_command() -> Command {
+        Command::Delete(DeleteArgs {
+            notebook_id: NotebookId::from("nb1"),
+            source_names: vec!["source-a".to_string(), "source-b".to_string()],
+            source_ids: Vec::new(),
+            source_titles: Vec::new(),
+            stdin: false,
+            bulk: BulkDeleteArgs::default(),
+        })
+    }
+
+    #[tokio::test]
+    async fn delete_prints_count_line() {
+        let server = MockServer::start().await;
+        mock_batch_delete(&server).await;
+        let out = CapturedOutput::default();
+
+        run(
+            delete_command(),
+            &client(&server.uri()),
+            &out,
+            false,
+            false,
+            &Interrupt::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(out.stdout(), "Deleted 2 source(s) successfully\n");
+        assert_eq!(out.stderr(), "");
+    }
+
+    #[tokio::test]
+    async fn delete_json_mode_emits_single_document() {
+        let server = MockServer::start().await;
+        mock_batch_delete(&server).await;
+        let out = CapturedOutput::default();
+
+        run(
+            delete_command(),
+            &client(&server.uri()),
+            &out,
+            true,
+            false,
+            &Interrupt::default(),
+        )
+        .await
+        .unwrap();
+
+        let payload: serde_json::Value = serde_json::from_str(&out.stdout()).unwrap();
+        assert_eq!(payload["status"], "deleted");
+        assert_eq!(payload["count"], 2);
+        assert_eq!(out.stderr(), "");
+    }
+
+    fn source_refs(sources: serde_json::Value) -> Vec<SourceRef> {
+        serde_json::from_value::<Vec<NotebookSource>>(sources)
+            .unwrap()
+            .iter()
+            .map(|source| SourceRef::try_from(source).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn deletion_labels_use_titles_and_detect_match_all() {
+        let parent = "projects/1/locations/global";
+        let sources = source_refs(json!([
+            {"name": "projects/1/locations/global/notebooks/nb1/sources/s1", "title": "First"},
+            {"name": "projects/1/locations/global/notebooks/nb1/sources/s2"}
+        ]));
+        let s1 = format!("{parent}/notebooks/nb1/sources/s1");
+        let s2 = format!("{parent}/notebooks/nb1/sources/s2");
+        let gone = format!("{parent}/notebooks/nb1/sources/gone");
+
+        let (labels, matches_all) = deletion_labels(&sources, &[s1.clone(), gone.clone()]);
+        assert_eq!(labels, vec!["First".to_string(), gone]);
+        assert!(!matches_all);
+
+        let (labels, matches_all) = deletion_labels(&sources, &[s2, s1]);
+        assert_eq!(labels, vec!["(untitled)", "First"]);
+        assert!(matches_all);
+    }
+
+    #[test]
+    fn resolve_titles_prefers_a_whole_title_and_reports_every_failure() {
+        let parent = "projects/1/locations/global";
+        let sources = source_refs(json!([
+            {"name": "projects/1/locations/global/notebooks/nb1/sources/s1", "title": "Report"},
+            {"name": "projects/1/locations/global/notebooks/nb1/sources/s2", "title": "Report 2024"},
+            {"name": "projects/1/locations/global/notebooks/nb1/sources/s3", "title": "Notes"}
+        ]));
+
+        let names = resolve_titles(&sources, &["report".into(), "NOT".into()]);
+        assert_eq!(
+            names.unwrap(),
+            vec![
+                format!("{parent}/notebooks/nb1/sources/s1"),
+                format!("{parent}/notebooks/nb1/sources/s3"),
+            ]
+        );
+
+        let err = resolve_titles(&sources, &["port".into(), "missing".into()])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("nothing was deleted"), "{err}");
+        assert!(
+            err.contains("'port': matches 2 sources: Report (s1), Report 2024 (s2)"),
+            "{err}"
+        );
+        assert!(
+            err.contains("'missing': no source title contains it"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn upload_display_name_warning_goes_to_stderr() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path(format!(
+                "/upload/v1alpha/projects/{PROJECT}/locations/global/notebooks/nb1/sources:uploadFile"
+            )))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"sourceId": {"id": "src-1"}})),
+            )
+            .mount(&server)
+            .await;
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), "hello").unwrap();
+        let out = CapturedOutput::default();
+
+        let cmd = Command::Upload(UploadArgs {
+            notebook_id: NotebookId::from("nb1"),
+            files: vec![file.path().to_path_buf()],
+            content_type: Some("text/plain".to_string()),
+            display_name: Some("Renamed.txt".to_string()),
+            url: None,
+            max_file_size: None,
+            max_download_size: 1024,
+            batch: BatchArgs::default(),
+        });
+        run(
+            cmd,
+            &client(&server.uri()),
+            &out,
+            true,
+            false,
+            &Interrupt::default(),
+        )
+        .await
+        .unwrap();
+
+        assert!(out.stderr().starts_with("WARNING: NotebookLM API rejects"));
+        let payload: serde_json::Value = serde_json::from_str(&out.stdout()).unwrap();
+        assert_eq!(payload["source_id"]["id"], "src-1");
+    }
+}
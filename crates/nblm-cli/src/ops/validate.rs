@@ -0,0 +1,633 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use clap::{Args, ValueEnum};
+use colored::Colorize;
+use nblm_core::models::enterprise::source::{normalize_source_name, UserContent};
+use toml_edit::{Document, Item};
+
+use crate::util::config::{mime_override_problem, ENV_KEYS, MIME_OVERRIDES_TABLE};
+use crate::util::io::emit_json;
+use crate::util::output::OutputSink;
+use crate::util::payload::{Problem, Validated};
+use crate::util::persisted;
+use crate::util::queue::{QueueFile, QueuedOperation, QUEUE_FORMAT_VERSION};
+use crate::util::validate::validate_url;
+
+/// Exit code when the file has problems (1 stays for failures to read it at all).
+pub const INVALID_EXIT_CODE: i32 = 2;
+
+pub const HELP: &str = "Notes:
+  - Checks a file the CLI reads, offline: no credentials, API access or NBLM_* variables
+    are needed.
+  - The kind is taken from --kind, or else from the extension: .toml is a config file
+    (config.toml) and .json a queue file (queue.json).
+  - Parses the file with the same types nblm uses at runtime, then applies the checks the
+    commands would: URLs, empty texts, Drive document ID and mime type pairs, duplicate
+    source names, source name lengths and queue checksums.
+  - Exits 0 when the file is valid and 2 when it is not.
+
+Examples:
+  nblm validate --file ~/.config/nblm-rs/config.toml
+  nblm validate --file queue.json --json";
+
+#[derive(Args)]
+pub struct ValidateArgs {
+    /// File to check
+    #[arg(long, value_name = "PATH")]
+    pub file: PathBuf,
+
+    /// What the file is (default: inferred from the extension)
+    #[arg(long, value_enum)]
+    pub kind: Option<FileKind>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FileKind {
+    /// `config.toml`, written by `nblm init`
+    Config,
+    /// `queue.json`, written by `nblm queue add`
+    Queue,
+}
+
+impl FileKind {
+    fn detect(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Ok(Self::Config),
+            Some("json") => Ok(Self::Queue),
+            _ => bail!(
+                "cannot tell what kind of file {} is; pass --kind config or --kind queue",
+                path.display()
+            ),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Config => "config",
+            Self::Queue => "queue",
+        }
+    }
+}
+
+/// Print the result and exit with [`INVALID_EXIT_CODE`] when the file has problems.
+pub fn run(args: ValidateArgs, out: &dyn OutputSink, json_mode: bool) -> Result<()> {
+    let kind = match args.kind {
+        Some(kind) => kind,
+        None => FileKind::detect(&args.file)?,
+    };
+    let raw = fs::read_to_string(&args.file)
+        .with_context(|| format!("failed to read {}", args.file.display()))?;
+    let problems = check(kind, &raw);
+    let file = args.file.display().to_string();
+
+    if json_mode {
+        let payload = Validated {
+            file,
+            kind: kind.as_str(),
+            valid: problems.is_empty(),
+            errors: &problems,
+        };
+        emit_json(out, &payload, true)?;
+    } else if problems.is_empty() {
+        out.out(&format!("{file}: {}", "valid".green()));
+    } else {
+        for problem in &problems {
+            out.out(&format_problem(&file, problem));
+        }
+        out.out(&format!(
+            "{file}: {}",
+            format!("{} problem(s)", problems.len()).red()
+        ));
+    }
+
+    if !problems.is_empty() {
+        crate::util::output::flush_stdout();
+        std::process::exit(INVALID_EXIT_CODE);
+    }
+    Ok(())
+}
+
+/// Everything wrong with `raw` as a file of `kind`; empty when it is valid.
+pub fn check(kind: FileKind, raw: &str) -> Vec<Problem> {
+    match kind {
+        FileKind::Config => check_config(raw),
+        FileKind::Queue => check_queue(raw),
+    }
+}
+
+/// `file:line:column: field: message`, leaving out the parts that are not known.
+fn format_problem(file: &str, problem: &Problem) -> String {
+    let mut line = file.to_string();
+    if let Some(number) = problem.line {
+        line.push_str(&format!(":{number}"));
+        if let Some(column) = problem.column {
+            line.push_str(&format!(":{column}"));
+        }
+    }
+    line.push_str(": ");
+    if let Some(field) = &problem.field {
+        line.push_str(&format!("{field}: "));
+    }
+    line.push_str(&problem.message);
+    line
+}
+
+fn problem(field: impl Into<String>, message: impl Into<String>) -> Problem {
+    Problem {
+        field: Some(field.into()),
+        line: None,
+        column: None,
+        message: message.into(),
+    }
+}
+
+fn check_config(raw: &str) -> Vec<Problem> {
+    let doc = match Document::parse(raw) {
+        Ok(doc) => doc,
+        Err(err) => {
+            let (line, column) = err.span().map(|span| line_column(raw, span.start)).unzip();
+            return vec![Problem {
+                field: None,
+                line,
+                column,
+                message: err.message().trim().to_string(),
+            }];
+        }
+    };
+
+    let mut problems = Vec::new();
+    for (key, item) in doc.iter() {
+        let at = |message: String| {
+            let (line, column) = item
+                .span()
+                .or_else(|| doc.as_table().get_key_value(key)?.0.span())
+                .map(|span| line_column(raw, span.start))
+                .unzip();
+            Problem {
+                field: Some(key.to_string()),
+                line,
+                column,
+                message,
+            }
+        };
+        if key == MIME_OVERRIDES_TABLE {
+            problems.extend(check_mime_overrides(raw, item));
+            continue;
+        }
+        if !ENV_KEYS.iter().any(|(known, _)| *known == key) {
+            let known: Vec<&str> = ENV_KEYS
+                .iter()
+                .map(|(known, _)| *known)
+                .chain([MIME_OVERRIDES_TABLE])
+                .collect();
+            problems.push(at(format!(
+                "unknown key (expected one of: {})",
+                known.join(", ")
+            )));
+            continue;
+        }
+        let Some(value) = item.as_str() else {
+            problems.push(at("must be a string".to_string()));
+            continue;
+        };
+        if let Some(message) = config_value_problem(key, value) {
+            problems.push(at(message));
+        }
+    }
+    problems
+}
+
+fn check_mime_overrides(raw: &str, item: &Item) -> Vec<Problem> {
+    let at = |field: String, span: Option<std::ops::Range<usize>>, message: String| {
+        let (line, column) = span.map(|span| line_column(raw, span.start)).unzip();
+        Problem {
+            field: Some(field),
+            line,
+            column,
+            message,
+        }
+    };
+    let Some(table) = item.as_table_like() else {
+        return vec![at(
+            MIME_OVERRIDES_TABLE.to_string(),
+            item.span(),
+            "must be a table of extension = \"content/type\"".to_string(),
+        )];
+    };
+    let mut problems = Vec::new();
+    for (extension, value) in table.iter() {
+        let field = format!("{MIME_OVERRIDES_TABLE}.{extension}");
+        let message = match value.as_str() {
+            None => Some("must be a string".to_string()),
+            Some(content_type) => mime_override_problem(extension, content_type),
+        };
+        if let Some(message) = message {
+            problems.push(at(field, value.span(), message));
+        }
+    }
+    problems
+}
+
+fn config_value_problem(key: &str, value: &str) -> Option<String> {
+    if value.trim().is_empty() {
+        return Some("cannot be empty".to_string());
+    }
+    match key {
+        "project_number" if !value.chars().all(|c| c.is_ascii_digit()) => Some(format!(
+            "'{value}' is not a project number (use the numeric ID, not the project name)"
+        )),
+        "endpoint_location" => nblm_core::env::normalize_endpoint_location(value.to_string())
+            .err()
+            .map(|err| err.to_string()),
+        _ => None,
+    }
+}
+
+fn check_queue(raw: &str) -> Vec<Problem> {
+    let mut deserializer = serde_json::Deserializer::from_str(raw);
+    let file: QueueFile = match serde_path_to_error::deserialize(&mut deserializer) {
+        Ok(file) => file,
+        Err(err) => {
+            let path = err.path().to_string();
+            let inner = err.inner();
+            return vec![Problem {
+                // `.` is the root and `?` a position the parser could not attribute to a field.
+                field: (path != "." && path != "?").then_some(path),
+                line: Some(inner.line()).filter(|line| *line > 0),
+                column: Some(inner.column()).filter(|column| *column > 0),
+                message: strip_position(&inner.to_string()),
+            }];
+        }
+    };
+
+    let mut problems = Vec::new();
+    // The file parsed as a queue above, so it is valid JSON.
+    let value: serde_json::Value = serde_json::from_str(raw).unwrap_or_default();
+    match persisted::stored_version(&value) {
+        Some((_, version)) if version > 0 && version <= u64::from(QUEUE_FORMAT_VERSION) => {}
+        stored => {
+            let (key, version) = stored.unwrap_or((persisted::SCHEMA_VERSION_KEY, 0));
+            problems.push(problem(
+                key,
+                format!(
+                    "unsupported queue format version {version} (this nblm supports version {QUEUE_FORMAT_VERSION})"
+                ),
+            ));
+        }
+    }
+    // Source names already used per notebook, with where they first appeared.
+    let mut names: HashMap<(&str, &str), String> = HashMap::new();
+    for (index, entry) in file.entries.iter().enumerate() {
+        let prefix = format!("entries[{index}]");
+        if let Err(err) = nblm_core::NotebookId::parse(entry.notebook_id.as_str()) {
+            problems.push(problem(format!("{prefix}.notebook_id"), err.to_string()));
+        }
+        if let Err(err) = entry.verify() {
+            problems.push(problem(format!("{prefix}.checksum"), err.to_string()));
+        }
+        match &entry.operation {
+            QueuedOperation::AddSources { contents } => {
+                if contents.is_empty() {
+                    problems.push(problem(
+                        format!("{prefix}.operation.contents"),
+                        "no sources to add",
+                    ));
+                }
+                for (position, content) in contents.iter().enumerate() {
+                    let field = format!("{prefix}.operation.contents[{position}]");
+                    problems.extend(content_problems(&field, content));
+                    if let Some(name) = source_name(content) {
+                        let key = (entry.notebook_id.as_str(), name);
+                        match names.get(&key) {
+                            Some(first) => problems.push(problem(
+                                field,
+                                format!(
+                                    "duplicate source name '{name}' for notebook {} (first used at {first})",
+                                    entry.notebook_id
+                                ),
+                            )),
+                            None => {
+                                names.insert(key, field);
+                            }
+                        }
+                    }
+                }
+            }
+            QueuedOperation::UploadFile {
+                file_name,
+                content_type,
+                ..
+            } => {
+                if file_name.trim().is_empty() {
+                    problems.push(problem(
+                        format!("{prefix}.operation.file_name"),
+                        "cannot be empty",
+                    ));
+                }
+                if content_type.trim().is_empty() {
+                    problems.push(problem(
+                        format!("{prefix}.operation.content_type"),
+                        "cannot be empty",
+                    ));
+                }
+            }
+        }
+    }
+    problems
+}
+
+/// The checks `sources add` applies to the same content given as flags.
+fn content_problems(field: &str, content: &UserContent) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    match content {
+        UserContent::Web { web_content } => {
+            if let Err(err) = validate_url(&web_content.url) {
+                problems.push(problem(format!("{field}.webContent.url"), err.to_string()));
+            }
+            problems.extend(source_name_problem(
+                &format!("{field}.webContent"),
+                &web_content.source_name,
+            ));
+        }
+        UserContent::Text { text_content } => {
+            if text_content.content.trim().is_empty() {
+                problems.push(problem(
+                    format!("{field}.textContent.content"),
+                    "text cannot be empty",
+                ));
+            }
+            problems.extend(source_name_problem(
+                &format!("{field}.textContent"),
+                &text_content.source_name,
+            ));
+        }
+        UserContent::GoogleDrive {
+            google_drive_content,
+        } => {
+            let drive = format!("{field}.googleDriveContent");
+            if google_drive_content.document_id.trim().is_empty() {
+                problems.push(problem(
+                    format!("{drive}.documentId"),
+                    "Drive document ID cannot be empty",
+                ));
+            }
+            if google_drive_content.mime_type.trim().is_empty() {
+                problems.push(problem(
+                    format!("{drive}.mimeType"),
+                    "a Drive document needs a mime type",
+                ));
+            }
+            problems.extend(source_name_problem(
+                &drive,
+                &google_drive_content.source_name,
+            ));
+        }
+        UserContent::Video { video_content } => {
+            if let Err(err) = validate_url(&video_content.url) {
+                problems.push(problem(
+                    format!("{field}.videoContent.youtubeUrl"),
+                    err.to_string(),
+                ));
+            }
+        }
+    }
+    problems
+}
+
+/// A name the API would cut off; blank names are fine, they are sent as none.
+fn source_name_problem(content_field: &str, name: &Option<String>) -> Option<Problem> {
+    match normalize_source_name(name.as_deref()) {
+        Err(nblm_core::Error::Validation(message)) => {
+            Some(problem(format!("{content_field}.sourceName"), message))
+        }
+        _ => None,
+    }
+}
+
+fn source_name(content: &UserContent) -> Option<&str> {
+    let name = match content {
+        UserContent::Web { web_content } => web_content.source_name.as_deref(),
+        UserContent::Text { text_content } => text_content.source_name.as_deref(),
+        UserContent::GoogleDrive {
+            google_drive_content,
+        } => google_drive_content.source_name.as_deref(),
+        UserContent::Video { .. } => None,
+    };
+    name.map(str::trim).filter(|name| !name.is_empty())
+}
+
+/// 1-based line and column of byte `offset` in `raw`.
+fn line_column(raw: &str, offset: usize) -> (usize, usize) {
+    let before = &raw[..offset.min(raw.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .count()
+        + 1;
+    (line, column)
+}
+
+/// serde_json appends " at line L column C" to its messages; the position is reported
+/// separately.
+fn strip_position(message: &str) -> String {
+    match message.rfind(" at line ") {
+        Some(index) => message[..index].to_string(),
+        None => message.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::queue::QueueEntry;
+    use serde_json::json;
+
+    fn queue_file(entries: Vec<QueueEntry>) -> String {
+        serde_json::to_string_pretty(&json!({
+            "schema_version": QUEUE_FORMAT_VERSION,
+            "entries": entries,
+        }))
+        .unwrap()
+    }
+
+    fn add_sources(notebook_id: &str, contents: Vec<UserContent>) -> QueueEntry {
+        QueueEntry::new(notebook_id, QueuedOperation::AddSources { contents }).unwrap()
+    }
+
+    fn fields(problems: &[Problem]) -> Vec<Option<&str>> {
+        problems.iter().map(|p| p.field.as_deref()).collect()
+    }
+
+    #[test]
+    fn kind_is_inferred_from_the_extension() {
+        assert_eq!(
+            FileKind::detect(Path::new("config.toml")).unwrap(),
+            FileKind::Config
+        );
+        assert_eq!(
+            FileKind::detect(Path::new("queue/queue.json")).unwrap(),
+            FileKind::Queue
+        );
+        assert!(FileKind::detect(Path::new("notes.txt"))
+            .unwrap_err()
+            .to_string()
+            .contains("pass --kind"));
+    }
+
+    #[test]
+    fn valid_config_has_no_problems() {
+        let raw =
+            "project_number = \"123456\"\nlocation = \"global\"\nendpoint_location = \"us\"\n";
+        assert_eq!(check(FileKind::Config, raw), []);
+    }
+
+    #[test]
+    fn invalid_config_values_are_reported_with_their_line() {
+        let raw = "project_number = \"my-project\"\nendpoint_location = \"us-central1\"\nlocaton = \"global\"\nlocation = 1\n";
+        let problems = check(FileKind::Config, raw);
+        assert_eq!(
+            fields(&problems),
+            [
+                Some("project_number"),
+                Some("endpoint_location"),
+                Some("locaton"),
+                Some("location")
+            ]
+        );
+        let lines: Vec<_> = problems.iter().map(|p| p.line).collect();
+        assert_eq!(lines, [Some(1), Some(2), Some(3), Some(4)]);
+        assert!(problems[1].message.contains("use 'us' instead"));
+        assert!(problems[2].message.starts_with("unknown key"));
+        assert_eq!(problems[3].message, "must be a string");
+    }
+
+    #[test]
+    fn mime_overrides_are_checked_per_entry() {
+        let raw = "location = \"global\"\n\n[mime_overrides]\nmd = \"text/plain\"\nsrt = \"subtitles\"\nvtt = 1\n";
+        let problems = check(FileKind::Config, raw);
+        assert_eq!(
+            fields(&problems),
+            [Some("mime_overrides.srt"), Some("mime_overrides.vtt")]
+        );
+        let lines: Vec<_> = problems.iter().map(|p| p.line).collect();
+        assert_eq!(lines, [Some(5), Some(6)]);
+        assert!(problems[0].message.contains("is not a content type"));
+
+        let problems = check(FileKind::Config, "mime_overrides = \"md\"\n");
+        assert_eq!(fields(&problems), [Some("mime_overrides")]);
+    }
+
+    #[test]
+    fn config_syntax_errors_carry_a_position() {
+        let problems = check(FileKind::Config, "project_number = \"123\"\nlocation = \n");
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].field, None);
+        assert_eq!(problems[0].line, Some(2));
+    }
+
+    #[test]
+    fn valid_queue_has_no_problems() {
+        let raw = queue_file(vec![add_sources(
+            "nb1",
+            vec![
+                UserContent::web("https://example.com".to_string(), Some("Docs".to_string())),
+                UserContent::text("notes".to_string(), None),
+            ],
+        )]);
+        assert_eq!(check(FileKind::Queue, &raw), []);
+    }
+
+    #[test]
+    fn queue_content_checks_report_field_paths() {
+        let raw = queue_file(vec![
+            add_sources(
+                "nb1",
+                vec![
+                    UserContent::web("ftp://example.com".to_string(), Some("Docs".to_string())),
+                    UserContent::text("  ".to_string(), Some("Docs".to_string())),
+                    UserContent::google_drive("doc1".to_string(), String::new(), None),
+                    UserContent::web("https://example.com".to_string(), Some("x".repeat(501))),
+                ],
+            ),
+            add_sources("notebooks/nb2", vec![]),
+        ]);
+        let problems = check(FileKind::Queue, &raw);
+        assert_eq!(
+            fields(&problems),
+            [
+                Some("entries[0].operation.contents[0].webContent.url"),
+                Some("entries[0].operation.contents[1].textContent.content"),
+                Some("entries[0].operation.contents[1]"),
+                Some("entries[0].operation.contents[2].googleDriveContent.mimeType"),
+                Some("entries[0].operation.contents[3].webContent.sourceName"),
+                Some("entries[1].notebook_id"),
+                Some("entries[1].operation.contents"),
+            ]
+        );
+        assert!(problems[2]
+            .message
+            .starts_with("duplicate source name 'Docs' for notebook nb1"));
+    }
+
+    #[test]
+    fn edited_queue_entries_fail_their_checksum() {
+        let mut value: serde_json::Value = serde_json::from_str(&queue_file(vec![add_sources(
+            "nb1",
+            vec![UserContent::text("notes".to_string(), None)],
+        )]))
+        .unwrap();
+        value["entries"][0]["notebook_id"] = json!("nb2");
+        let problems = check(FileKind::Queue, &value.to_string());
+        assert_eq!(fields(&problems), [Some("entries[0].checksum")]);
+    }
+
+    #[test]
+    fn queue_parse_errors_name_the_field_and_position() {
+        let raw = "{\n  \"version\": 1,\n  \"entries\": [\n    { \"id\": 5 }\n  ]\n}\n";
+        let problems = check(FileKind::Queue, raw);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].field.as_deref(), Some("entries[0].id"));
+        assert_eq!(problems[0].line, Some(4));
+        assert!(problems[0].message.starts_with("invalid type: integer `5`"));
+
+        let problems = check(FileKind::Queue, "{\"version\": 1,");
+        assert_eq!(problems[0].field, None);
+        assert_eq!(problems[0].line, Some(1));
+    }
+
+    #[test]
+    fn unsupported_queue_versions_are_reported() {
+        let raw = json!({ "version": 99, "entries": [] }).to_string();
+        assert_eq!(fields(&check(FileKind::Queue, &raw)), [Some("version")]);
+        let raw = json!({ "schema_version": 99, "entries": [] }).to_string();
+        assert_eq!(
+            fields(&check(FileKind::Queue, &raw)),
+            [Some("schema_version")]
+        );
+        let raw = json!({ "entries": [] }).to_string();
+        assert_eq!(
+            fields(&check(FileKind::Queue, &raw)),
+            [Some("schema_version")]
+        );
+    }
+
+    #[test]
+    fn problems_format_as_compiler_style_lines() {
+        let problem = Problem {
+            field: Some("location".to_string()),
+            line: Some(3),
+            column: Some(12),
+            message: "must be a string".to_string(),
+        };
+        assert_eq!(
+            format_problem("config.toml", &problem),
+            "config.toml:3:12: location: must be a string"
+        );
+    }
+}
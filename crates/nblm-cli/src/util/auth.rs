@@ -1,13 +1,43 @@
 use std::{env, sync::Arc};
 
-use anyhow::Result;
-use nblm_core::auth::{EnvTokenProvider, GcloudTokenProvider, StaticTokenProvider, TokenProvider};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use nblm_core::auth::{
+    EnvTokenProvider, GcloudTokenProvider, MetadataTokenProvider, ProbeStatus, ProviderKind,
+    StaticTokenProvider, TokenProvider,
+};
 use nblm_core::env::profile_experiment_enabled;
+use nblm_core::FileRefreshTokenStore;
 
 use crate::args::{AuthMethod, GlobalArgs};
 use crate::util::oauth_bootstrap::OAuthBootstrapper;
+use crate::util::prompt;
 
+/// Builds the token provider once a command is about to call the API.
+pub type ProviderFactory = Box<dyn Fn(&GlobalArgs) -> Result<Arc<dyn TokenProvider>>>;
+
+/// Token provider for `--auth`. When it cannot be built, or later fails to produce a token, the
+/// error lists every auth method's status (see [`auth_guidance`]).
+///
+/// A sign-in refused because the run is not interactive is passed through as is: its message
+/// already says how to sign in ahead of time.
 pub fn build_token_provider(args: &GlobalArgs) -> Result<Arc<dyn TokenProvider>> {
+    let inner = build_selected_provider(args).map_err(|err| {
+        if prompt::exit_code(&err).is_some() {
+            return err;
+        }
+        anyhow!(
+            "{err:#}\n\n{}",
+            auth_guidance(args.auth, &probe_auth_methods())
+        )
+    })?;
+    Ok(Arc::new(GuidedTokenProvider {
+        inner,
+        selected: args.auth,
+    }))
+}
+
+fn build_selected_provider(args: &GlobalArgs) -> Result<Arc<dyn TokenProvider>> {
     if args.auth.requires_experimental_flag() && !profile_experiment_enabled() {
         anyhow::bail!(
             "auth method '{}' is experimental and not yet available. Set {}=1 to enable experimental auth methods.",
@@ -17,7 +47,9 @@ pub fn build_token_provider(args: &GlobalArgs) -> Result<Arc<dyn TokenProvider>>
     }
 
     Ok(match args.auth {
-        AuthMethod::Gcloud => Arc::new(build_gcloud_provider()?),
+        AuthMethod::Gcloud => {
+            Arc::new(build_gcloud_provider()?.with_non_interactive(!args.is_interactive()))
+        }
         AuthMethod::Env => {
             if let Some(token) = args.token.as_ref().or(args.env_token.as_ref()) {
                 Arc::new(StaticTokenProvider::new(token.clone()))
@@ -29,6 +61,7 @@ pub fn build_token_provider(args: &GlobalArgs) -> Result<Arc<dyn TokenProvider>>
             let bootstrapper = OAuthBootstrapper::new()?;
             bootstrapper.bootstrap_provider(args)?
         }
+        AuthMethod::Metadata => Arc::new(MetadataTokenProvider::new()),
     })
 }
 
@@ -42,9 +75,114 @@ fn auth_method_label(method: AuthMethod) -> &'static str {
         AuthMethod::Gcloud => "gcloud",
         AuthMethod::Env => "env",
         AuthMethod::UserOauth => "user-oauth",
+        AuthMethod::Metadata => "metadata",
     }
 }
 
+/// Appends [`auth_guidance`] to token errors from the wrapped provider.
+struct GuidedTokenProvider {
+    inner: Arc<dyn TokenProvider>,
+    selected: AuthMethod,
+}
+
+impl GuidedTokenProvider {
+    fn guide(&self, err: nblm_core::Error) -> nblm_core::Error {
+        match err {
+            nblm_core::Error::TokenProvider(message) => nblm_core::Error::TokenProvider(format!(
+                "{message}\n\n{}",
+                auth_guidance(self.selected, &probe_auth_methods())
+            )),
+            other => other,
+        }
+    }
+}
+
+#[async_trait]
+impl TokenProvider for GuidedTokenProvider {
+    async fn access_token(&self) -> nblm_core::Result<String> {
+        self.inner
+            .access_token()
+            .await
+            .map_err(|err| self.guide(err))
+    }
+
+    async fn refresh_token(&self) -> nblm_core::Result<String> {
+        self.inner
+            .refresh_token()
+            .await
+            .map_err(|err| self.guide(err))
+    }
+
+    fn kind(&self) -> ProviderKind {
+        self.inner.kind()
+    }
+}
+
+/// Cheap status of each locally configurable auth method; nothing here fetches a token.
+fn probe_auth_methods() -> Vec<(AuthMethod, ProbeStatus)> {
+    let user_oauth = match FileRefreshTokenStore::new() {
+        Ok(store) => store.probe(),
+        Err(err) => ProbeStatus::Missing(format!("credentials file unavailable: {err}")),
+    };
+    vec![
+        (
+            AuthMethod::Gcloud,
+            build_gcloud_provider()
+                .map(|provider| provider.probe())
+                .unwrap_or_else(|err| ProbeStatus::Missing(err.to_string())),
+        ),
+        (
+            AuthMethod::Env,
+            EnvTokenProvider::new("NBLM_ACCESS_TOKEN").probe(),
+        ),
+        (AuthMethod::UserOauth, user_oauth),
+    ]
+}
+
+/// Lists each method's status and where to go next, e.g.
+///
+/// ```text
+/// Auth methods (selected: gcloud):
+///   gcloud: binary 'gcloud' not found
+///   env: NBLM_ACCESS_TOKEN unset
+///   user-oauth: experimental, no stored tokens
+/// Run `nblm doctor` to check your setup, or `nblm auth login` to sign in with gcloud.
+/// ```
+fn auth_guidance(selected: AuthMethod, probes: &[(AuthMethod, ProbeStatus)]) -> String {
+    let mut lines = vec![format!(
+        "Auth methods (selected: {}):",
+        auth_method_label(selected)
+    )];
+    for (method, status) in probes {
+        let experimental = if method.requires_experimental_flag() {
+            "experimental, "
+        } else {
+            ""
+        };
+        lines.push(format!(
+            "  {}: {experimental}{}",
+            auth_method_label(*method),
+            status.detail()
+        ));
+    }
+    let alternatives: Vec<String> = probes
+        .iter()
+        .filter(|(method, status)| *method != selected && status.is_ready())
+        .map(|(method, _)| format!("`--auth {}`", auth_method_label(*method)))
+        .collect();
+    if !alternatives.is_empty() {
+        lines.push(format!(
+            "To use another method, try {}.",
+            alternatives.join(" or ")
+        ));
+    }
+    lines.push(
+        "Run `nblm doctor` to check your setup, or `nblm auth login` to sign in with gcloud."
+            .to_string(),
+    );
+    lines.join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -83,10 +221,22 @@ mod tests {
             auth,
             token: Some("token".to_string()),
             json: false,
+            redact: None,
+            lang: None,
             debug_http: false,
+            quiet: false,
+            timings: false,
+            show_curl: false,
             timeout: None,
+            verify_token_project: false,
+            retry_min_delay: None,
+            no_retry: false,
+            non_interactive: false,
+            interactive: false,
+            insecure_skip_tls_verify: false,
             env_token: Some("token".to_string()),
             base_url: None,
+            api_version: None,
         }
     }
 
@@ -149,11 +299,117 @@ mod tests {
         env::remove_var("NBLM_OAUTH_CLIENT_ID");
     }
 
+    fn ready(detail: &str) -> ProbeStatus {
+        ProbeStatus::Ready(detail.to_string())
+    }
+
+    fn missing(detail: &str) -> ProbeStatus {
+        ProbeStatus::Missing(detail.to_string())
+    }
+
+    #[test]
+    fn auth_guidance_lists_every_method_when_nothing_is_configured() {
+        let probes = [
+            (AuthMethod::Gcloud, missing("binary 'gcloud' not found")),
+            (AuthMethod::Env, missing("NBLM_ACCESS_TOKEN unset")),
+            (AuthMethod::UserOauth, missing("no stored tokens")),
+        ];
+        assert_eq!(
+            auth_guidance(AuthMethod::Gcloud, &probes),
+            "Auth methods (selected: gcloud):\n  \
+             gcloud: binary 'gcloud' not found\n  \
+             env: NBLM_ACCESS_TOKEN unset\n  \
+             user-oauth: experimental, no stored tokens\n\
+             Run `nblm doctor` to check your setup, or `nblm auth login` to sign in with gcloud."
+        );
+    }
+
+    #[test]
+    fn auth_guidance_suggests_ready_alternatives() {
+        let probes = [
+            (AuthMethod::Gcloud, missing("binary 'gcloud' not found")),
+            (AuthMethod::Env, ready("NBLM_ACCESS_TOKEN set")),
+            (AuthMethod::UserOauth, ready("1 stored token(s)")),
+        ];
+        let guidance = auth_guidance(AuthMethod::Gcloud, &probes);
+        assert!(
+            guidance.contains("  env: NBLM_ACCESS_TOKEN set\n"),
+            "{guidance}"
+        );
+        assert!(
+            guidance.contains("try `--auth env` or `--auth user-oauth`."),
+            "{guidance}"
+        );
+    }
+
+    #[test]
+    fn auth_guidance_does_not_suggest_the_selected_method() {
+        // gcloud is installed but not logged in: the token fetch fails although the probe passes.
+        let probes = [
+            (AuthMethod::Gcloud, ready("binary found at /usr/bin/gcloud")),
+            (AuthMethod::Env, missing("NBLM_ACCESS_TOKEN unset")),
+            (AuthMethod::UserOauth, missing("no stored tokens")),
+        ];
+        let guidance = auth_guidance(AuthMethod::Gcloud, &probes);
+        assert!(
+            guidance.contains("  gcloud: binary found at /usr/bin/gcloud\n"),
+            "{guidance}"
+        );
+        assert!(!guidance.contains("To use another method"), "{guidance}");
+        assert!(guidance.ends_with("`nblm auth login` to sign in with gcloud."));
+    }
+
+    #[test]
+    fn auth_guidance_for_metadata_still_lists_local_methods() {
+        let probes = [
+            (AuthMethod::Gcloud, ready("binary found at /usr/bin/gcloud")),
+            (AuthMethod::Env, missing("NBLM_ACCESS_TOKEN unset")),
+            (AuthMethod::UserOauth, missing("no stored tokens")),
+        ];
+        let guidance = auth_guidance(AuthMethod::Metadata, &probes);
+        assert!(guidance.starts_with("Auth methods (selected: metadata):\n"));
+        assert!(guidance.contains("try `--auth gcloud`."), "{guidance}");
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn token_errors_include_guidance() {
+        let _guard = EnvGuard::new("NBLM_ACCESS_TOKEN");
+        env::remove_var("NBLM_ACCESS_TOKEN");
+        let mut args = make_args(AuthMethod::Env);
+        args.token = None;
+        args.env_token = None;
+        let provider = build_token_provider(&args).expect("expected provider");
+        let err = provider.access_token().await.unwrap_err().to_string();
+        assert!(
+            err.contains("environment variable NBLM_ACCESS_TOKEN missing"),
+            "{err}"
+        );
+        assert!(err.contains("  env: NBLM_ACCESS_TOKEN unset"), "{err}");
+        assert!(err.contains("`nblm doctor`"), "{err}");
+    }
+
+    #[test]
+    #[serial]
+    fn construction_errors_include_guidance() {
+        let _guard = EnvGuard::new(nblm_core::PROFILE_EXPERIMENT_FLAG);
+        env::remove_var(nblm_core::PROFILE_EXPERIMENT_FLAG);
+        let err = build_token_provider(&make_args(AuthMethod::UserOauth))
+            .err()
+            .expect("expected experimental auth to fail")
+            .to_string();
+        assert!(
+            err.contains("Auth methods (selected: user-oauth):"),
+            "{err}"
+        );
+    }
+
     #[test]
     fn auth_method_label_returns_correct_labels() {
         assert_eq!(auth_method_label(AuthMethod::Gcloud), "gcloud");
         assert_eq!(auth_method_label(AuthMethod::Env), "env");
         assert_eq!(auth_method_label(AuthMethod::UserOauth), "user-oauth");
+        assert_eq!(auth_method_label(AuthMethod::Metadata), "metadata");
     }
 
     #[test]
@@ -161,6 +417,15 @@ mod tests {
         assert!(!AuthMethod::Gcloud.requires_experimental_flag());
         assert!(!AuthMethod::Env.requires_experimental_flag());
         assert!(AuthMethod::UserOauth.requires_experimental_flag());
+        assert!(!AuthMethod::Metadata.requires_experimental_flag());
+    }
+
+    #[test]
+    #[serial]
+    fn metadata_auth_builds_metadata_provider() {
+        let args = make_args(AuthMethod::Metadata);
+        let provider = build_token_provider(&args).expect("expected provider");
+        assert_eq!(provider.kind(), ProviderKind::Metadata);
     }
 
     #[test]
@@ -0,0 +1,628 @@
+use std::collections::{BTreeSet, VecDeque};
+use std::future::Future;
+
+use anyhow::{anyhow, bail, Result};
+use clap::Args;
+use futures::stream::{FuturesUnordered, StreamExt};
+use tokio::time::Instant;
+
+use crate::util::interrupt::{Interrupt, Interrupted, IN_FLIGHT_GRACE};
+use crate::util::output::OutputSink;
+
+/// How a multi-item command reacts to a failed item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureMode {
+    /// Stop scheduling new items after the first failure.
+    FailFast,
+    /// Process every item and report failures at the end.
+    KeepGoing,
+}
+
+#[derive(Args, Debug, Clone, Copy, Default)]
+pub struct BatchArgs {
+    /// Stop scheduling new items after the first failure
+    #[arg(long, conflicts_with = "keep_going")]
+    pub fail_fast: bool,
+
+    /// Process every item even if some of them fail
+    #[arg(long)]
+    pub keep_going: bool,
+
+    /// Exit successfully in --keep-going mode even if some items failed
+    #[arg(long)]
+    pub allow_partial: bool,
+}
+
+impl BatchArgs {
+    pub fn mode(&self, default: FailureMode) -> FailureMode {
+        if self.fail_fast {
+            FailureMode::FailFast
+        } else if self.keep_going {
+            FailureMode::KeepGoing
+        } else {
+            default
+        }
+    }
+}
+
+/// Per-item results of [`run_batch`], each tagged with the item's input index.
+#[derive(Debug)]
+pub struct BatchOutcome<T> {
+    pub mode: FailureMode,
+    pub total: usize,
+    pub succeeded: Vec<(usize, T)>,
+    pub failed: Vec<(usize, anyhow::Error)>,
+    pub skipped: Vec<usize>,
+    /// Items still in flight when the grace period after an interrupt ran out. They may or may
+    /// not have taken effect.
+    pub abandoned: Vec<usize>,
+    /// Whether the run stopped early because of SIGINT or SIGTERM.
+    pub interrupted: bool,
+}
+
+impl<T> BatchOutcome<T> {
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty() && self.skipped.is_empty() && self.abandoned.is_empty()
+    }
+
+    /// After an interrupt, list on stderr which items were `done` and which may have been.
+    pub fn report_interruption(
+        &self,
+        out: &dyn OutputSink,
+        done: &str,
+        label: impl Fn(usize) -> String,
+    ) {
+        if self.interrupted {
+            report_interruption(
+                out,
+                done,
+                self.total,
+                self.succeeded.iter().map(|(index, _)| *index),
+                &self.abandoned,
+                label,
+            );
+        }
+    }
+
+    /// Turn the outcome into the command's exit status.
+    ///
+    /// An interrupted run is an [`Interrupted`] error. Otherwise any failure is an error, except
+    /// in keep-going mode with `allow_partial`.
+    pub fn finish(&self, allow_partial: bool, noun: &str) -> Result<()> {
+        let counts = Counts {
+            mode: self.mode,
+            total: self.total,
+            succeeded: self.succeeded.len(),
+            failed: self.failed.len(),
+            skipped: self.skipped.len(),
+            interrupted: self.interrupted,
+        };
+        finish(counts, allow_partial, noun)
+    }
+}
+
+/// Failures kept by [`run_batch_streaming`] as examples.
+pub const RECENT_FAILURES: usize = 3;
+
+/// The last `capacity` items pushed, plus how many were pushed in all.
+#[derive(Debug, Clone)]
+pub struct RingBuffer<T> {
+    items: VecDeque<T>,
+    capacity: usize,
+    pushed: usize,
+}
+
+impl<T> RingBuffer<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            items: VecDeque::with_capacity(capacity),
+            capacity,
+            pushed: 0,
+        }
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.pushed += 1;
+        if self.capacity == 0 {
+            return;
+        }
+        if self.items.len() == self.capacity {
+            self.items.pop_front();
+        }
+        self.items.push_back(item);
+    }
+
+    /// Everything ever pushed, including the items no longer kept.
+    pub fn pushed(&self) -> usize {
+        self.pushed
+    }
+
+    /// The kept items, oldest first.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.items.iter()
+    }
+}
+
+/// What [`run_batch_streaming`] keeps about a run: counters and indices, not the results.
+#[derive(Debug)]
+pub struct BatchSummary {
+    pub mode: FailureMode,
+    pub total: usize,
+    /// Input indices of the items that succeeded, in input order.
+    pub succeeded: Vec<usize>,
+    /// The most recent failures (input index and message); `failures.pushed()` counts them all.
+    pub failures: RingBuffer<(usize, String)>,
+    pub skipped: Vec<usize>,
+    pub abandoned: Vec<usize>,
+    pub interrupted: bool,
+}
+
+impl BatchSummary {
+    pub fn failed(&self) -> usize {
+        self.failures.pushed()
+    }
+
+    /// Same as [`BatchOutcome::report_interruption`].
+    pub fn report_interruption(
+        &self,
+        out: &dyn OutputSink,
+        done: &str,
+        label: impl Fn(usize) -> String,
+    ) {
+        if self.interrupted {
+            report_interruption(
+                out,
+                done,
+                self.total,
+                self.succeeded.iter().copied(),
+                &self.abandoned,
+                label,
+            );
+        }
+    }
+
+    /// Same as [`BatchOutcome::finish`], with the most recent failures appended to the error so
+    /// they survive a long, deduplicated stderr.
+    pub fn finish(
+        &self,
+        allow_partial: bool,
+        noun: &str,
+        label: impl Fn(usize) -> String,
+    ) -> Result<()> {
+        let counts = Counts {
+            mode: self.mode,
+            total: self.total,
+            succeeded: self.succeeded.len(),
+            failed: self.failed(),
+            skipped: self.skipped.len(),
+            interrupted: self.interrupted,
+        };
+        finish(counts, allow_partial, noun).map_err(|err| {
+            if self.interrupted || self.failed() == 0 {
+                return err;
+            }
+            let recent: Vec<String> = self
+                .failures
+                .iter()
+                .map(|(index, message)| format!("{}: {message}", label(*index)))
+                .collect();
+            anyhow!("{err}; most recent: {}", recent.join("; "))
+        })
+    }
+}
+
+fn report_interruption(
+    out: &dyn OutputSink,
+    done: &str,
+    total: usize,
+    succeeded: impl ExactSizeIterator<Item = usize>,
+    abandoned: &[usize],
+    label: impl Fn(usize) -> String,
+) {
+    out.err(&format!(
+        "Interrupted after {} of {total} item(s); {done}:",
+        succeeded.len()
+    ));
+    for index in succeeded {
+        out.err(&format!("  {}", label(index)));
+    }
+    for index in abandoned {
+        out.err(&format!(
+            "Still in flight when interrupted (may have completed): {}",
+            label(*index)
+        ));
+    }
+}
+
+/// The tallies [`BatchOutcome::finish`] and [`BatchSummary::finish`] decide the exit status from.
+struct Counts {
+    mode: FailureMode,
+    total: usize,
+    succeeded: usize,
+    failed: usize,
+    skipped: usize,
+    interrupted: bool,
+}
+
+fn finish(counts: Counts, allow_partial: bool, noun: &str) -> Result<()> {
+    let Counts {
+        mode,
+        total,
+        succeeded,
+        failed,
+        skipped,
+        interrupted,
+    } = counts;
+    if interrupted {
+        return Err(Interrupted {
+            summary: format!("{succeeded} of {total} {noun}(s) done, {skipped} not started"),
+        }
+        .into());
+    }
+    if failed == 0 {
+        return Ok(());
+    }
+    if allow_partial && mode == FailureMode::KeepGoing {
+        return Ok(());
+    }
+    let mut message = format!("{failed} of {total} {noun}(s) failed");
+    if skipped > 0 {
+        message.push_str(&format!(", {skipped} skipped after the first failure"));
+    }
+    bail!(message)
+}
+
+/// Run `f` over `items` with at most `concurrency` operations in flight.
+///
+/// In [`FailureMode::FailFast`] no new items are started once one has failed; items already in
+/// flight still complete. After an interrupt no new items are started either, and items in
+/// flight get [`IN_FLIGHT_GRACE`] to finish before they are abandoned. Results are sorted by
+/// input index.
+pub async fn run_batch<I, T, F, Fut>(
+    items: Vec<I>,
+    concurrency: usize,
+    mode: FailureMode,
+    interrupt: &Interrupt,
+    f: F,
+) -> BatchOutcome<T>
+where
+    F: Fn(I) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let total = items.len();
+    let mut succeeded = Vec::new();
+    let mut failed = Vec::new();
+    let run = drive(
+        items,
+        concurrency,
+        mode,
+        interrupt,
+        f,
+        |index, result| match result {
+            Ok(value) => succeeded.push((index, value)),
+            Err(err) => failed.push((index, err)),
+        },
+    )
+    .await;
+    succeeded.sort_by_key(|(index, _)| *index);
+    failed.sort_by_key(|(index, _)| *index);
+
+    BatchOutcome {
+        mode,
+        total,
+        succeeded,
+        failed,
+        skipped: run.skipped,
+        abandoned: run.abandoned,
+        interrupted: run.interrupted,
+    }
+}
+
+/// [`run_batch`] for large runs: each result goes to `on_result` as soon as its item finishes
+/// (in completion order) and is then dropped, so memory stays bounded by `concurrency` rather
+/// than the number of items. Only counters, indices and the last [`RECENT_FAILURES`] failure
+/// messages are kept.
+pub async fn run_batch_streaming<I, T, F, Fut>(
+    items: Vec<I>,
+    concurrency: usize,
+    mode: FailureMode,
+    interrupt: &Interrupt,
+    f: F,
+    mut on_result: impl FnMut(usize, Result<T>),
+) -> BatchSummary
+where
+    F: Fn(I) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let total = items.len();
+    let mut succeeded = Vec::new();
+    let mut failures = RingBuffer::new(RECENT_FAILURES);
+    let run = drive(items, concurrency, mode, interrupt, f, |index, result| {
+        match &result {
+            Ok(_) => succeeded.push(index),
+            Err(err) => failures.push((index, format!("{err:#}"))),
+        }
+        on_result(index, result);
+    })
+    .await;
+    succeeded.sort_unstable();
+
+    BatchSummary {
+        mode,
+        total,
+        succeeded,
+        failures,
+        skipped: run.skipped,
+        abandoned: run.abandoned,
+        interrupted: run.interrupted,
+    }
+}
+
+/// What is left to report once [`drive`] has handed every finished item to its callback.
+struct Run {
+    skipped: Vec<usize>,
+    abandoned: Vec<usize>,
+    interrupted: bool,
+}
+
+async fn drive<I, T, F, Fut>(
+    items: Vec<I>,
+    concurrency: usize,
+    mode: FailureMode,
+    interrupt: &Interrupt,
+    f: F,
+    mut on_result: impl FnMut(usize, Result<T>),
+) -> Run
+where
+    F: Fn(I) -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut pending = items.into_iter().enumerate();
+    let mut in_flight = FuturesUnordered::new();
+    let mut stop = false;
+    let mut running = BTreeSet::new();
+    let mut deadline = None;
+    let _graceful = interrupt.graceful();
+
+    loop {
+        if deadline.is_none() && interrupt.is_interrupted() {
+            deadline = Some(Instant::now() + IN_FLIGHT_GRACE);
+        }
+        while !stop && deadline.is_none() && in_flight.len() < concurrency.max(1) {
+            let Some((index, item)) = pending.next() else {
+                break;
+            };
+            let fut = f(item);
+            running.insert(index);
+            in_flight.push(async move { (index, fut.await) });
+        }
+
+        let next = match deadline {
+            None => tokio::select! {
+                next = in_flight.next() => next,
+                () = interrupt.interrupted() => continue,
+            },
+            Some(deadline) => match tokio::time::timeout_at(deadline, in_flight.next()).await {
+                Ok(next) => next,
+                Err(_) => break,
+            },
+        };
+        let Some((index, result)) = next else {
+            break;
+        };
+        running.remove(&index);
+        interrupt.item_completed();
+        if result.is_err() && mode == FailureMode::FailFast {
+            stop = true;
+        }
+        on_result(index, result);
+    }
+
+    Run {
+        skipped: pending.map(|(index, _)| index).collect(),
+        abandoned: running.into_iter().collect(),
+        interrupted: deadline.is_some(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    async fn fail_on_two(value: u32) -> Result<u32> {
+        if value == 2 {
+            Err(anyhow!("boom"))
+        } else {
+            Ok(value * 10)
+        }
+    }
+
+    #[tokio::test]
+    async fn keep_going_processes_every_item() {
+        let outcome = run_batch(
+            vec![1, 2, 3],
+            1,
+            FailureMode::KeepGoing,
+            &Interrupt::default(),
+            fail_on_two,
+        )
+        .await;
+        assert_eq!(outcome.succeeded, vec![(0, 10), (2, 30)]);
+        assert_eq!(outcome.failed.len(), 1);
+        assert_eq!(outcome.failed[0].0, 1);
+        assert!(outcome.skipped.is_empty());
+    }
+
+    #[tokio::test]
+    async fn fail_fast_skips_remaining_items() {
+        let outcome = run_batch(
+            vec![1, 2, 3, 4],
+            1,
+            FailureMode::FailFast,
+            &Interrupt::default(),
+            fail_on_two,
+        )
+        .await;
+        assert_eq!(outcome.succeeded, vec![(0, 10)]);
+        assert_eq!(outcome.failed.len(), 1);
+        assert_eq!(outcome.skipped, vec![2, 3]);
+    }
+
+    #[tokio::test]
+    async fn bounded_concurrency_preserves_input_order() {
+        let outcome = run_batch(
+            (1..=8).collect::<Vec<u32>>(),
+            3,
+            FailureMode::KeepGoing,
+            &Interrupt::default(),
+            |value| async move { Ok::<_, anyhow::Error>(value) },
+        )
+        .await;
+        let indices: Vec<usize> = outcome.succeeded.iter().map(|(i, _)| *i).collect();
+        assert_eq!(indices, (0..8).collect::<Vec<_>>());
+        assert!(outcome.is_complete());
+    }
+
+    #[tokio::test]
+    async fn finish_respects_allow_partial_only_in_keep_going() {
+        let keep_going = run_batch(
+            vec![1, 2],
+            1,
+            FailureMode::KeepGoing,
+            &Interrupt::default(),
+            fail_on_two,
+        )
+        .await;
+        assert!(keep_going.finish(false, "item").is_err());
+        assert!(keep_going.finish(true, "item").is_ok());
+
+        let fail_fast = run_batch(
+            vec![2, 1],
+            1,
+            FailureMode::FailFast,
+            &Interrupt::default(),
+            fail_on_two,
+        )
+        .await;
+        let err = fail_fast.finish(true, "item").unwrap_err().to_string();
+        assert!(err.contains("1 of 2 item(s) failed"));
+        assert!(err.contains("1 skipped"));
+    }
+
+    #[tokio::test]
+    async fn interrupt_stops_scheduling_and_finishes_as_interrupted() {
+        let interrupt = Interrupt::default();
+        let outcome = run_batch(
+            vec![1, 2, 3, 4],
+            1,
+            FailureMode::KeepGoing,
+            &interrupt,
+            |value| {
+                if value == 2 {
+                    interrupt.trigger();
+                }
+                async move { Ok::<_, anyhow::Error>(value) }
+            },
+        )
+        .await;
+        assert!(outcome.interrupted);
+        assert_eq!(outcome.succeeded, vec![(0, 1), (1, 2)]);
+        assert_eq!(outcome.skipped, vec![2, 3]);
+        assert!(outcome.abandoned.is_empty());
+
+        let err = outcome.finish(true, "item").unwrap_err();
+        assert!(err.is::<Interrupted>());
+        assert_eq!(
+            err.to_string(),
+            "interrupted: 2 of 4 item(s) done, 2 not started"
+        );
+    }
+
+    #[test]
+    fn ring_buffer_keeps_the_newest_items_and_counts_all() {
+        let mut buffer = RingBuffer::new(3);
+        for value in 1..=5 {
+            buffer.push(value);
+        }
+        assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), [3, 4, 5]);
+        assert_eq!(buffer.pushed(), 5);
+
+        let mut empty = RingBuffer::new(0);
+        empty.push(1);
+        assert_eq!(empty.iter().count(), 0);
+        assert_eq!(empty.pushed(), 1);
+    }
+
+    #[tokio::test]
+    async fn streaming_counts_results_without_keeping_them() {
+        let mut seen = Vec::new();
+        let summary = run_batch_streaming(
+            (0..10).collect::<Vec<u32>>(),
+            2,
+            FailureMode::KeepGoing,
+            &Interrupt::default(),
+            |value| async move {
+                if value % 2 == 1 {
+                    Err(anyhow!("odd {value}"))
+                } else {
+                    Ok(value)
+                }
+            },
+            |index, result| seen.push((index, result.is_ok())),
+        )
+        .await;
+
+        assert_eq!(seen.len(), 10);
+        assert_eq!(summary.succeeded, [0, 2, 4, 6, 8]);
+        assert_eq!(summary.failed(), 5);
+        assert_eq!(summary.failures.iter().count(), RECENT_FAILURES);
+        assert!(summary.skipped.is_empty());
+
+        let err = summary
+            .finish(false, "item", |index| format!("#{index}"))
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.starts_with("5 of 10 item(s) failed; most recent: "),
+            "{err}"
+        );
+        assert_eq!(err.matches("odd").count(), RECENT_FAILURES, "{err}");
+        assert!(summary.finish(true, "item", |_| String::new()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn streaming_fail_fast_skips_remaining_items() {
+        let summary = run_batch_streaming(
+            vec![1, 2, 3, 4],
+            1,
+            FailureMode::FailFast,
+            &Interrupt::default(),
+            fail_on_two,
+            |_, _| {},
+        )
+        .await;
+        assert_eq!(summary.succeeded, [0]);
+        assert_eq!(summary.failed(), 1);
+        assert_eq!(summary.skipped, [2, 3]);
+    }
+
+    #[test]
+    fn batch_args_mode_falls_back_to_default() {
+        let args = BatchArgs::default();
+        assert_eq!(args.mode(FailureMode::FailFast), FailureMode::FailFast);
+        assert_eq!(args.mode(FailureMode::KeepGoing), FailureMode::KeepGoing);
+
+        let args = BatchArgs {
+            keep_going: true,
+            ..Default::default()
+        };
+        assert_eq!(args.mode(FailureMode::FailFast), FailureMode::KeepGoing);
+
+        let args = BatchArgs {
+            fail_fast: true,
+            ..Default::default()
+        };
+        assert_eq!(args.mode(FailureMode::KeepGoing), FailureMode::FailFast);
+    }
+}
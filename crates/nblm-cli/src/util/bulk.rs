@@ -0,0 +1,260 @@
+use anyhow::{bail, Result};
+use clap::Args;
+use rand::seq::IndexedRandom;
+
+use crate::util::interrupt::Interrupt;
+use crate::util::io::emit_json;
+use crate::util::output::OutputSink;
+use crate::util::payload::DryRun;
+use crate::util::prompt;
+
+/// Items a delete command removes without `--max-delete` or confirmation.
+pub const DEFAULT_MAX_DELETE: usize = 25;
+/// Items echoed when asking to confirm an over-limit delete.
+const CONFIRM_SAMPLE: usize = 5;
+
+/// Asks a yes/no question and returns the answer.
+pub type Confirm<'a> = dyn Fn(&str) -> Result<bool> + 'a;
+
+/// Safety flags shared by the bulk delete commands.
+#[derive(Args, Debug, Clone, Copy)]
+pub struct BulkDeleteArgs {
+    /// Allow a delete that removes every item in scope (e.g. all sources of a notebook)
+    #[arg(long)]
+    pub allow_match_all: bool,
+
+    /// Ask for confirmation (or fail when not interactive) above this many items
+    #[arg(long, value_name = "N", default_value_t = DEFAULT_MAX_DELETE)]
+    pub max_delete: usize,
+
+    /// Delete more than --max-delete items without asking for confirmation
+    #[arg(long)]
+    pub yes: bool,
+
+    /// Print everything that would be deleted, then exit without deleting
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+impl Default for BulkDeleteArgs {
+    fn default() -> Self {
+        Self {
+            allow_match_all: false,
+            max_delete: DEFAULT_MAX_DELETE,
+            yes: false,
+            dry_run: false,
+        }
+    }
+}
+
+/// Check a bulk delete of `labels` (titles or names, one per item) against the safety flags.
+///
+/// `matches_all` says the items are everything in scope; with more than one item that needs
+/// `--allow-match-all`. More than `--max-delete` items need `--yes` or `confirm` to answer yes;
+/// `None` means there is nobody to ask, and the delete fails with
+/// [`nblm_core::Error::InteractionRequired`].
+pub fn confirm_bulk_destruction(
+    args: &BulkDeleteArgs,
+    noun: &str,
+    labels: &[String],
+    matches_all: bool,
+    confirm: Option<&Confirm<'_>>,
+) -> Result<()> {
+    let count = labels.len();
+    if matches_all && count > 1 && !args.allow_match_all {
+        bail!(
+            "refusing to delete all {count} {noun}(s) in scope; pass --allow-match-all to confirm, or --dry-run to review the list"
+        );
+    }
+    if count <= args.max_delete || args.yes {
+        return Ok(());
+    }
+    let Some(confirm) = confirm else {
+        return Err(prompt::interaction_required(
+            format!(
+                "ask to confirm deleting {count} {noun}(s) (more than --max-delete {})",
+                args.max_delete
+            ),
+            "pass --yes or raise --max-delete to delete them without asking, or use --dry-run to review the list",
+        ));
+    };
+    let sample: Vec<&str> = labels
+        .choose_multiple(&mut rand::rng(), CONFIRM_SAMPLE)
+        .map(String::as_str)
+        .collect();
+    let question = format!(
+        "About to delete {count} {noun}(s), more than --max-delete {}. Sample: {}. Delete all {count}?",
+        args.max_delete,
+        sample.join(", ")
+    );
+    if !confirm(&question)? {
+        bail!("aborted; nothing was deleted");
+    }
+    Ok(())
+}
+
+/// Confirmation prompt on stderr, or `None` when the run is not `interactive` (see
+/// [`GlobalArgs::is_interactive`]) or stdin already carries input. Ctrl-C at the prompt stops
+/// the command with exit code 130; see [`prompt::confirm`].
+///
+/// [`GlobalArgs::is_interactive`]: crate::args::GlobalArgs::is_interactive
+pub fn terminal_prompt(
+    stdin_in_use: bool,
+    interactive: bool,
+    interrupt: &Interrupt,
+) -> Option<Box<Confirm<'_>>> {
+    (interactive && !stdin_in_use)
+        .then(|| Box::new(|question: &str| prompt::confirm(question, Some(interrupt))) as Box<_>)
+}
+
+/// `--dry-run` output: every item that would be deleted, one per line.
+pub fn emit_dry_run(
+    out: &dyn OutputSink,
+    noun: &str,
+    labels: &[String],
+    json_mode: bool,
+) -> Result<()> {
+    if json_mode {
+        return emit_json(
+            out,
+            &DryRun {
+                status: "dry_run",
+                count: labels.len(),
+                would_delete: labels,
+            },
+            json_mode,
+        );
+    }
+    out.err(&format!(
+        "Dry run: would delete {} {noun}(s):",
+        labels.len()
+    ));
+    for label in labels {
+        out.out(label);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    fn labels(count: usize) -> Vec<String> {
+        (1..=count).map(|i| format!("item-{i}")).collect()
+    }
+
+    fn args(max_delete: usize, allow_match_all: bool) -> BulkDeleteArgs {
+        BulkDeleteArgs {
+            allow_match_all,
+            max_delete,
+            yes: false,
+            dry_run: false,
+        }
+    }
+
+    #[test]
+    fn within_limit_proceeds_without_asking() {
+        let asked = |_: &str| -> Result<bool> { panic!("should not ask") };
+        confirm_bulk_destruction(
+            &args(25, false),
+            "notebook",
+            &labels(25),
+            false,
+            Some(&asked),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn match_all_requires_flag() {
+        let err = confirm_bulk_destruction(&args(25, false), "source", &labels(3), true, None)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "refusing to delete all 3 source(s) in scope; pass --allow-match-all to confirm, or --dry-run to review the list"
+        );
+        confirm_bulk_destruction(&args(25, true), "source", &labels(3), true, None).unwrap();
+    }
+
+    #[test]
+    fn match_all_of_a_single_item_is_not_bulk() {
+        confirm_bulk_destruction(&args(25, false), "source", &labels(1), true, None).unwrap();
+    }
+
+    #[test]
+    fn over_limit_without_prompt_needs_an_interaction() {
+        let err = confirm_bulk_destruction(&args(25, false), "notebook", &labels(26), false, None)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "cannot ask to confirm deleting 26 notebook(s) (more than --max-delete 25) when running non-interactively; pass --yes or raise --max-delete to delete them without asking, or use --dry-run to review the list"
+        );
+        assert_eq!(
+            prompt::exit_code(&err),
+            Some(prompt::INTERACTION_REQUIRED_EXIT_CODE)
+        );
+    }
+
+    #[test]
+    fn yes_skips_the_confirmation() {
+        let asked = |_: &str| -> Result<bool> { panic!("should not ask") };
+        let yes = BulkDeleteArgs {
+            yes: true,
+            ..args(25, false)
+        };
+        confirm_bulk_destruction(&yes, "notebook", &labels(26), false, Some(&asked)).unwrap();
+        confirm_bulk_destruction(&yes, "notebook", &labels(26), false, None).unwrap();
+        // --yes answers the confirmation, not --allow-match-all.
+        confirm_bulk_destruction(&yes, "source", &labels(3), true, None).unwrap_err();
+    }
+
+    #[test]
+    fn raised_limit_allows_large_deletes() {
+        confirm_bulk_destruction(&args(100, false), "notebook", &labels(26), false, None).unwrap();
+    }
+
+    #[test]
+    fn over_limit_prompt_echoes_count_and_sample() {
+        let items = labels(30);
+        let question = RefCell::new(String::new());
+        let confirm = |q: &str| -> Result<bool> {
+            *question.borrow_mut() = q.to_string();
+            Ok(true)
+        };
+        confirm_bulk_destruction(&args(25, false), "notebook", &items, false, Some(&confirm))
+            .unwrap();
+
+        let question = question.into_inner();
+        assert!(
+            question
+                .starts_with("About to delete 30 notebook(s), more than --max-delete 25. Sample: "),
+            "{question}"
+        );
+        assert!(question.ends_with(". Delete all 30?"), "{question}");
+        let sample = question
+            .split("Sample: ")
+            .nth(1)
+            .and_then(|rest| rest.split(". Delete").next())
+            .unwrap();
+        let sample: Vec<&str> = sample.split(", ").collect();
+        assert_eq!(sample.len(), CONFIRM_SAMPLE);
+        assert!(sample
+            .iter()
+            .all(|label| items.iter().any(|item| item == label)));
+    }
+
+    #[test]
+    fn declined_prompt_aborts() {
+        let decline = |_: &str| -> Result<bool> { Ok(false) };
+        let err = confirm_bulk_destruction(
+            &args(25, false),
+            "notebook",
+            &labels(26),
+            false,
+            Some(&decline),
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "aborted; nothing was deleted");
+    }
+}
@@ -0,0 +1,284 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::{bail, Context, Result};
+use toml_edit::DocumentMut;
+
+const CONFIG_FILE: &str = "config.toml";
+
+/// Config keys and the environment variables clap reads for the matching global flags.
+pub const ENV_KEYS: [(&str, &str); 3] = [
+    ("project_number", "NBLM_PROJECT_NUMBER"),
+    ("location", "NBLM_LOCATION"),
+    ("endpoint_location", "NBLM_ENDPOINT_LOCATION"),
+];
+
+/// Table of upload content types by file extension.
+pub const MIME_OVERRIDES_TABLE: &str = "mime_overrides";
+
+/// Overrides from the config file, set once at startup by [`CliConfig::install_mime_overrides`].
+static MIME_OVERRIDES: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+/// Defaults for the global flags, stored in `config.toml` and written by `nblm init`.
+///
+/// Precedence is flag, then environment variable, then this file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CliConfig {
+    pub project_number: Option<String>,
+    pub location: Option<String>,
+    pub endpoint_location: Option<String>,
+    /// `[mime_overrides]`: upload content type by lowercase file extension (without the dot),
+    /// ahead of nblm-core's built-in table.
+    pub mime_overrides: BTreeMap<String, String>,
+}
+
+impl CliConfig {
+    /// `config.toml` under the nblm config directory (`NBLM_CONFIG_DIR` overrides it).
+    pub fn default_path() -> Result<PathBuf> {
+        Ok(nblm_core::config_dir()?.join(CONFIG_FILE))
+    }
+
+    /// Read `path`, returning `None` when it does not exist.
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        Self::parse(&raw)
+            .with_context(|| format!("failed to parse {}", path.display()))
+            .map(Some)
+    }
+
+    pub fn parse(raw: &str) -> Result<Self> {
+        let doc: DocumentMut = raw.parse()?;
+        let mut config = Self::default();
+        for (key, _) in ENV_KEYS {
+            let value = match doc.get(key) {
+                None => None,
+                Some(item) => match item.as_str() {
+                    Some(value) => Some(value.to_string()),
+                    None => bail!("`{key}` must be a string"),
+                },
+            };
+            *config.field_mut(key) = value;
+        }
+        if let Some(item) = doc.get(MIME_OVERRIDES_TABLE) {
+            let Some(table) = item.as_table_like() else {
+                bail!("`{MIME_OVERRIDES_TABLE}` must be a table");
+            };
+            for (extension, value) in table.iter() {
+                let Some(content_type) = value.as_str() else {
+                    bail!("`{MIME_OVERRIDES_TABLE}.{extension}` must be a string");
+                };
+                if let Some(problem) = mime_override_problem(extension, content_type) {
+                    bail!("`{MIME_OVERRIDES_TABLE}.{extension}`: {problem}");
+                }
+                config.mime_overrides.insert(
+                    normalize_extension(extension),
+                    content_type.trim().to_string(),
+                );
+            }
+        }
+        Ok(config)
+    }
+
+    pub fn to_toml(&self) -> String {
+        let mut body =
+            String::from("# Written by `nblm init`. Flags and NBLM_* variables take precedence.\n");
+        for (key, _) in ENV_KEYS {
+            if let Some(value) = self.field(key) {
+                // JSON string escapes are a subset of TOML basic-string escapes.
+                body.push_str(&format!("{key} = {}\n", serde_json::Value::from(value)));
+            }
+        }
+        if !self.mime_overrides.is_empty() {
+            body.push_str(&format!("\n[{MIME_OVERRIDES_TABLE}]\n"));
+            for (extension, content_type) in &self.mime_overrides {
+                body.push_str(&format!(
+                    "{} = {}\n",
+                    serde_json::Value::from(extension.as_str()),
+                    serde_json::Value::from(content_type.as_str())
+                ));
+            }
+        }
+        body
+    }
+
+    /// Write the file atomically (temp file + rename), creating the directory if needed.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let dir = path
+            .parent()
+            .with_context(|| format!("{} has no parent directory", path.display()))?;
+        let hint = format!("set {} to a writable directory", nblm_core::CONFIG_DIR_ENV);
+        fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create {}; {hint}", dir.display()))?;
+        let tmp = dir.join(format!("{CONFIG_FILE}.tmp"));
+        fs::write(&tmp, self.to_toml())
+            .with_context(|| format!("failed to write {}; {hint}", tmp.display()))?;
+        fs::rename(&tmp, path).with_context(|| format!("failed to write {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Export values as `NBLM_*` variables that are not already set, so clap picks them up.
+    pub fn apply_env_defaults(&self) {
+        for (key, var) in ENV_KEYS {
+            if let Some(value) = self.field(key) {
+                if std::env::var_os(var).is_none() {
+                    std::env::set_var(var, value);
+                }
+            }
+        }
+    }
+
+    /// Make `[mime_overrides]` the table [`mime_overrides`] returns. Only the first call counts.
+    pub fn install_mime_overrides(&self) {
+        let overrides = self
+            .mime_overrides
+            .iter()
+            .map(|(extension, content_type)| (extension.clone(), content_type.clone()))
+            .collect();
+        let _ = MIME_OVERRIDES.set(overrides);
+    }
+
+    fn field(&self, key: &str) -> Option<&str> {
+        match key {
+            "project_number" => self.project_number.as_deref(),
+            "location" => self.location.as_deref(),
+            "endpoint_location" => self.endpoint_location.as_deref(),
+            _ => unreachable!("unknown config key {key}"),
+        }
+    }
+
+    fn field_mut(&mut self, key: &str) -> &mut Option<String> {
+        match key {
+            "project_number" => &mut self.project_number,
+            "location" => &mut self.location,
+            "endpoint_location" => &mut self.endpoint_location,
+            _ => unreachable!("unknown config key {key}"),
+        }
+    }
+}
+
+/// Upload content types from the config file's `[mime_overrides]`, for
+/// [`nblm_core::infer_content_type`]. Empty when there is no config file.
+pub fn mime_overrides() -> &'static HashMap<String, String> {
+    MIME_OVERRIDES.get_or_init(HashMap::new)
+}
+
+/// Why `extension = "content_type"` is not a usable override, if it is not.
+pub fn mime_override_problem(extension: &str, content_type: &str) -> Option<String> {
+    let extension = normalize_extension(extension);
+    if extension.is_empty() || extension.contains(['/', '\\']) {
+        return Some(format!(
+            "'{extension}' is not a file extension (use e.g. md or .md)"
+        ));
+    }
+    let content_type = content_type.trim();
+    let valid = content_type.split_once('/').is_some_and(|(kind, subtype)| {
+        !kind.is_empty()
+            && !subtype.is_empty()
+            && !content_type.contains(char::is_whitespace)
+            && !subtype.contains('/')
+    });
+    (!valid).then(|| format!("'{content_type}' is not a content type such as text/plain"))
+}
+
+fn normalize_extension(extension: &str) -> String {
+    extension
+        .trim()
+        .trim_start_matches('.')
+        .to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_round_trips_through_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join(CONFIG_FILE);
+        let config = CliConfig {
+            project_number: Some("123456".to_string()),
+            location: Some("global".to_string()),
+            endpoint_location: Some("us".to_string()),
+            mime_overrides: BTreeMap::from([("md".to_string(), "text/plain".to_string())]),
+        };
+        config.write(&path).unwrap();
+
+        assert_eq!(CliConfig::load(&path).unwrap(), Some(config));
+        assert!(fs::read_to_string(&path)
+            .unwrap()
+            .contains("project_number = \"123456\"\n"));
+    }
+
+    #[test]
+    fn load_returns_none_for_missing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            CliConfig::load(&dir.path().join(CONFIG_FILE)).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_rejects_non_string_values() {
+        let err = CliConfig::parse("project_number = 123456\n").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("`project_number` must be a string"));
+    }
+
+    #[test]
+    fn to_toml_escapes_strings() {
+        let config = CliConfig {
+            location: Some("a\"b\\c".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(CliConfig::parse(&config.to_toml()).unwrap(), config);
+    }
+
+    #[test]
+    fn parse_reads_mime_overrides() {
+        let config =
+            CliConfig::parse("location = \"global\"\n\n[mime_overrides]\n\".MD\" = \"text/plain\"\nlog = \"text/plain\"\n")
+                .unwrap();
+        assert_eq!(
+            config.mime_overrides,
+            BTreeMap::from([
+                ("log".to_string(), "text/plain".to_string()),
+                ("md".to_string(), "text/plain".to_string()),
+            ])
+        );
+        assert_eq!(CliConfig::parse(&config.to_toml()).unwrap(), config);
+    }
+
+    #[test]
+    fn parse_rejects_bad_mime_overrides() {
+        let cases = [
+            (
+                "mime_overrides = \"md\"\n",
+                "`mime_overrides` must be a table",
+            ),
+            (
+                "[mime_overrides]\nmd = 1\n",
+                "`mime_overrides.md` must be a string",
+            ),
+            (
+                "[mime_overrides]\nmd = \"markdown\"\n",
+                "is not a content type",
+            ),
+            (
+                "[mime_overrides]\n\"a/b\" = \"text/plain\"\n",
+                "is not a file extension",
+            ),
+        ];
+        for (raw, expected) in cases {
+            let err = CliConfig::parse(raw).unwrap_err();
+            assert!(err.to_string().contains(expected), "{raw}: {err}");
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! `--show-curl`: each request as a curl command that can be run by hand.
+
+use nblm_core::RequestEvent;
+use reqwest::Method;
+
+use crate::util::rate_limit::shell_quote;
+
+/// Bodies up to this size are inlined with `--data-raw`; larger ones follow as a here-document.
+const INLINE_BODY_LIMIT: usize = 4 * 1024;
+
+/// Here-document delimiter for large bodies.
+const BODY_DELIMITER: &str = "NBLM_BODY";
+
+/// Headers curl sets itself, or that carry credentials.
+const SKIPPED_HEADERS: &[&str] = &["authorization", "content-length", "host"];
+
+/// A curl command repeating `request`, for the shell.
+///
+/// The access token is never included: the `Authorization` header reads the token from
+/// `$TOKEN`. Other headers are copied as sent.
+pub fn curl_command(request: &RequestEvent) -> String {
+    let mut words = vec!["curl".to_string()];
+    if request.method != Method::GET || request.body.is_some() {
+        words.extend(["-X".to_string(), request.method.to_string()]);
+    }
+    words.push(shell_quote(request.url.as_str()));
+    if request.headers.contains_key(reqwest::header::AUTHORIZATION) {
+        words.extend([
+            "-H".to_string(),
+            "\"Authorization: Bearer $TOKEN\"".to_string(),
+        ]);
+    }
+    for (name, value) in &request.headers {
+        if SKIPPED_HEADERS.contains(&name.as_str()) {
+            continue;
+        }
+        let header = format!("{name}: {}", String::from_utf8_lossy(value.as_bytes()));
+        words.extend(["-H".to_string(), shell_quote(&header)]);
+    }
+
+    let mut trailer = None;
+    match request.body.as_deref().map(std::str::from_utf8) {
+        None if request.method == Method::GET || request.method == Method::DELETE => {}
+        None => {
+            // Uploads stream the file; the caller knows which one.
+            words.extend(["--data-binary".to_string(), "@FILE".to_string()]);
+            trailer = Some("# the body was streamed from a file: replace FILE with its path");
+        }
+        Some(Ok(body)) if body.len() <= INLINE_BODY_LIMIT && !body.contains('\n') => {
+            words.extend(["--data-raw".to_string(), shell_quote(body)]);
+        }
+        Some(Ok(body)) => {
+            words.extend([
+                "--data-binary".to_string(),
+                "@-".to_string(),
+                format!("<<'{BODY_DELIMITER}'"),
+            ]);
+            return format!("{}\n{body}\n{BODY_DELIMITER}", words.join(" "));
+        }
+        Some(Err(_)) => {
+            words.extend(["--data-binary".to_string(), "@FILE".to_string()]);
+            trailer = Some("# the body is binary and not shown: replace FILE with the data");
+        }
+    }
+
+    let command = words.join(" ");
+    match trailer {
+        Some(note) => format!("{note}\n{command}"),
+        None => command,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderMap, HeaderValue};
+
+    fn event(method: Method, body: Option<&str>) -> RequestEvent {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "authorization",
+            HeaderValue::from_static("Bearer ya29.secret"),
+        );
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+        headers.insert("x-goog-user-project", HeaderValue::from_static("123456"));
+        RequestEvent {
+            method,
+            url: "https://example.com/v1alpha/projects/123456/notebooks?pageSize=5"
+                .parse()
+                .unwrap(),
+            headers,
+            body: body.map(|body| body.to_string().into()),
+        }
+    }
+
+    #[test]
+    fn small_bodies_are_inlined_and_the_token_is_replaced() {
+        let command = curl_command(&event(Method::POST, Some(r#"{"title":"it's"}"#)));
+        assert_eq!(
+            command,
+            "curl -X POST 'https://example.com/v1alpha/projects/123456/notebooks?pageSize=5' \
+             -H \"Authorization: Bearer $TOKEN\" -H 'content-type: application/json' \
+             -H 'x-goog-user-project: 123456' --data-raw '{\"title\":\"it'\\''s\"}'"
+        );
+        assert!(!command.contains("ya29"));
+    }
+
+    #[test]
+    fn gets_have_no_method_or_body() {
+        let command = curl_command(&event(Method::GET, None));
+        assert!(
+            command.starts_with("curl 'https://example.com/"),
+            "{command}"
+        );
+        assert!(!command.contains("--data"), "{command}");
+    }
+
+    #[test]
+    fn large_bodies_follow_as_a_here_document() {
+        let body = format!(r#"{{"text":"{}"}}"#, "a".repeat(INLINE_BODY_LIMIT));
+        let command = curl_command(&event(Method::POST, Some(&body)));
+        let (first, rest) = command.split_once('\n').unwrap();
+        assert!(first.ends_with("--data-binary @- <<'NBLM_BODY'"), "{first}");
+        assert_eq!(rest, format!("{body}\nNBLM_BODY"));
+    }
+
+    #[test]
+    fn streamed_bodies_are_left_to_the_reader() {
+        let command = curl_command(&event(Method::POST, None));
+        assert!(command.starts_with("# the body was streamed from a file"));
+        assert!(command.ends_with("--data-binary @FILE"), "{command}");
+    }
+}
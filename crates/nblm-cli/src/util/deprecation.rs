@@ -0,0 +1,62 @@
+use std::sync::{Arc, Mutex};
+
+use nblm_core::DeprecationNotice;
+
+use crate::util::output::OutputSink;
+use crate::util::payload::{DeprecationEntry, DeprecationReport};
+
+/// Distinct deprecation notices seen while a command ran, fed by
+/// [`nblm_core::NblmClient::with_deprecation_observer`]. Calls to the same endpoint with the same
+/// headers are kept once, so a command that lists a hundred notebooks warns once.
+#[derive(Debug, Default)]
+pub struct Deprecations {
+    notices: Mutex<Vec<DeprecationNotice>>,
+}
+
+impl Deprecations {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Callback for [`nblm_core::NblmClient::with_deprecation_observer`] that records into `self`.
+    pub fn hook(self: &Arc<Self>) -> impl Fn(&DeprecationNotice) + Send + Sync + 'static {
+        let deprecations = Arc::clone(self);
+        move |notice| deprecations.record(notice)
+    }
+
+    pub fn record(&self, notice: &DeprecationNotice) {
+        let mut notices = self.notices.lock().unwrap();
+        if !notices.contains(notice) {
+            notices.push(notice.clone());
+        }
+    }
+
+    /// Print the notices on stderr: a `warning:` line each, or a `api_deprecations` JSON object
+    /// with `--json` so stdout still holds only the command's document.
+    pub fn report(&self, out: &dyn OutputSink, json_mode: bool) {
+        let notices = self.notices.lock().unwrap();
+        if notices.is_empty() {
+            return;
+        }
+        if json_mode {
+            let report = DeprecationReport {
+                api_deprecations: notices
+                    .iter()
+                    .map(|notice| DeprecationEntry {
+                        method: notice.method.to_string(),
+                        path: notice.path.clone(),
+                        deprecated: notice.deprecated,
+                        deprecated_at: notice.deprecated_at,
+                        sunset: notice.sunset,
+                        warnings: notice.warnings.clone(),
+                    })
+                    .collect(),
+            };
+            out.err(&serde_json::to_string_pretty(&report).unwrap());
+        } else {
+            for notice in notices.iter() {
+                out.err(&format!("warning: {}", notice.describe()));
+            }
+        }
+    }
+}
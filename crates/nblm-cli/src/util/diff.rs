@@ -0,0 +1,263 @@
+use colored::Colorize;
+use nblm_core::models::enterprise::notebook::Notebook;
+
+use crate::util::output::OutputSink;
+
+/// The parts of a notebook the diff compares.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NotebookState {
+    pub title: String,
+    pub emoji: Option<String>,
+    pub sources: Vec<SourceState>,
+}
+
+/// A source, identified by its ID (or URL, for one not created yet).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceState {
+    pub key: String,
+    pub title: Option<String>,
+}
+
+impl NotebookState {
+    pub fn from_notebook(notebook: &Notebook) -> Self {
+        let sources = notebook
+            .sources
+            .iter()
+            .map(|source| SourceState {
+                key: source
+                    .source_id
+                    .as_ref()
+                    .and_then(|id| id.id.clone())
+                    .unwrap_or_else(|| source.name.clone()),
+                title: source.title.clone(),
+            })
+            .collect();
+        Self {
+            title: notebook.title.clone(),
+            emoji: notebook.emoji.clone(),
+            sources,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Title {
+        from: String,
+        to: String,
+    },
+    Emoji {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    SourceAdded(SourceState),
+    SourceRemoved(SourceState),
+    SourceRetitled {
+        key: String,
+        from: Option<String>,
+        to: Option<String>,
+    },
+}
+
+/// What turns `current` into `desired`: title and emoji first, then sources in the order they
+/// appear (removed ones in `current` order, then added and retitled ones in `desired` order).
+pub fn diff(current: &NotebookState, desired: &NotebookState) -> Vec<Change> {
+    let mut changes = Vec::new();
+    if current.title != desired.title {
+        changes.push(Change::Title {
+            from: current.title.clone(),
+            to: desired.title.clone(),
+        });
+    }
+    if current.emoji != desired.emoji {
+        changes.push(Change::Emoji {
+            from: current.emoji.clone(),
+            to: desired.emoji.clone(),
+        });
+    }
+    for source in &current.sources {
+        if !desired.sources.iter().any(|s| s.key == source.key) {
+            changes.push(Change::SourceRemoved(source.clone()));
+        }
+    }
+    for source in &desired.sources {
+        match current.sources.iter().find(|s| s.key == source.key) {
+            None => changes.push(Change::SourceAdded(source.clone())),
+            Some(existing) if existing.title != source.title => {
+                changes.push(Change::SourceRetitled {
+                    key: source.key.clone(),
+                    from: existing.title.clone(),
+                    to: source.title.clone(),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+    changes
+}
+
+/// Unified-style lines for `changes`, uncolored: `-` for what goes away, `+` for what replaces it.
+pub fn render(changes: &[Change]) -> Vec<String> {
+    if changes.is_empty() {
+        return vec!["no changes".to_string()];
+    }
+    let mut lines = Vec::new();
+    for change in changes {
+        match change {
+            Change::Title { from, to } => {
+                lines.push(format!("- title: {from}"));
+                lines.push(format!("+ title: {to}"));
+            }
+            Change::Emoji { from, to } => {
+                if let Some(from) = from {
+                    lines.push(format!("- emoji: {from}"));
+                }
+                if let Some(to) = to {
+                    lines.push(format!("+ emoji: {to}"));
+                }
+            }
+            Change::SourceRemoved(source) => lines.push(format!("- source: {}", label(source))),
+            Change::SourceAdded(source) => lines.push(format!("+ source: {}", label(source))),
+            Change::SourceRetitled { key, from, to } => {
+                lines.push(format!("- source: {}", titled(key, from.as_deref())));
+                lines.push(format!("+ source: {}", titled(key, to.as_deref())));
+            }
+        }
+    }
+    lines
+}
+
+/// Write [`render`]'s lines, red for removals and green for additions. `colored` leaves the
+/// text plain when color is turned off. With `json_mode` they go to stderr, leaving stdout for
+/// the JSON payload.
+pub fn emit_diff(out: &dyn OutputSink, changes: &[Change], json_mode: bool) {
+    for line in render(changes) {
+        let line = match line.chars().next() {
+            Some('-') => line.red().to_string(),
+            Some('+') => line.green().to_string(),
+            _ => line,
+        };
+        if json_mode {
+            out.err(&line);
+        } else {
+            out.out(&line);
+        }
+    }
+}
+
+fn label(source: &SourceState) -> String {
+    titled(&source.key, source.title.as_deref())
+}
+
+fn titled(key: &str, title: Option<&str>) -> String {
+    match title {
+        Some(title) => format!("{key} ({title})"),
+        None => key.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(key: &str, title: Option<&str>) -> SourceState {
+        SourceState {
+            key: key.to_string(),
+            title: title.map(String::from),
+        }
+    }
+
+    fn state(title: &str, sources: Vec<SourceState>) -> NotebookState {
+        NotebookState {
+            title: title.to_string(),
+            emoji: None,
+            sources,
+        }
+    }
+
+    #[test]
+    fn identical_states_render_no_changes() {
+        let current = state("Research", vec![source("s1", Some("Paper"))]);
+        let changes = diff(&current, &current.clone());
+        assert!(changes.is_empty());
+        assert_eq!(render(&changes), ["no changes"]);
+    }
+
+    #[test]
+    fn title_and_emoji_changes_show_old_and_new_values() {
+        let current = state("Research", vec![]);
+        let desired = NotebookState {
+            title: "Research 2025".to_string(),
+            emoji: Some("📘".to_string()),
+            ..current.clone()
+        };
+        assert_eq!(
+            render(&diff(&current, &desired)),
+            ["- title: Research", "+ title: Research 2025", "+ emoji: 📘"]
+        );
+    }
+
+    #[test]
+    fn sources_are_added_removed_and_retitled_by_key() {
+        let current = state(
+            "Research",
+            vec![
+                source("s1", Some("Old notes")),
+                source("s2", None),
+                source("s3", Some("Kept")),
+            ],
+        );
+        let desired = state(
+            "Research",
+            vec![
+                source("s3", Some("Kept")),
+                source("https://example.com/paper", None),
+                source("s1", Some("Notes")),
+            ],
+        );
+        let changes = diff(&current, &desired);
+        assert_eq!(
+            changes,
+            [
+                Change::SourceRemoved(source("s2", None)),
+                Change::SourceAdded(source("https://example.com/paper", None)),
+                Change::SourceRetitled {
+                    key: "s1".to_string(),
+                    from: Some("Old notes".to_string()),
+                    to: Some("Notes".to_string()),
+                },
+            ]
+        );
+        assert_eq!(
+            render(&changes),
+            [
+                "- source: s2",
+                "+ source: https://example.com/paper",
+                "- source: s1 (Old notes)",
+                "+ source: s1 (Notes)",
+            ]
+        );
+    }
+
+    #[test]
+    fn notebook_sources_are_keyed_by_id_then_name() {
+        let notebook: Notebook = serde_json::from_value(serde_json::json!({
+            "title": "Research",
+            "emoji": "📘",
+            "sources": [
+                { "name": "projects/1/locations/global/notebooks/nb/sources/s1", "sourceId": { "id": "s1" }, "title": "Paper" },
+                { "name": "projects/1/locations/global/notebooks/nb/sources/s2" },
+            ]
+        }))
+        .unwrap();
+        let state = NotebookState::from_notebook(&notebook);
+        assert_eq!(state.emoji.as_deref(), Some("📘"));
+        assert_eq!(
+            state.sources,
+            [
+                source("s1", Some("Paper")),
+                source("projects/1/locations/global/notebooks/nb/sources/s2", None),
+            ]
+        );
+    }
+}
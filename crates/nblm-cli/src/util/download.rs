@@ -0,0 +1,202 @@
+use anyhow::{anyhow, bail, Context, Result};
+use reqwest::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use reqwest::redirect::Policy;
+use url::Url;
+
+/// Redirects followed before a download gives up.
+const MAX_REDIRECTS: usize = 10;
+
+/// Media types that are web pages rather than documents.
+const HTML_TYPES: &[&str] = &["text/html", "application/xhtml+xml"];
+
+/// A remote file read into memory.
+#[derive(Debug)]
+pub struct Download {
+    /// URL after redirects.
+    pub url: Url,
+    /// Media type from the response, without parameters.
+    pub content_type: Option<String>,
+    pub data: Vec<u8>,
+}
+
+impl Download {
+    /// The last path segment of the final URL, percent-decoded.
+    pub fn file_name(&self) -> Option<String> {
+        let segment = self.url.path_segments()?.next_back()?;
+        let name = urlencoding::decode(segment).ok()?;
+        let name = name.trim();
+        (!name.is_empty()).then(|| name.to_string())
+    }
+
+    /// Whether the response is a web page, which uploads poorly as a file.
+    pub fn is_html(&self) -> bool {
+        self.content_type
+            .as_deref()
+            .is_some_and(|value| HTML_TYPES.contains(&value))
+    }
+}
+
+/// HTTP client for downloads: plain reqwest, following up to [`MAX_REDIRECTS`] redirects.
+pub fn http_client() -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .redirect(Policy::limited(MAX_REDIRECTS))
+        .build()
+        .context("failed to build HTTP client")
+}
+
+/// Download `url`, failing once the body would exceed `max_size` bytes.
+///
+/// The body is read chunk by chunk, so an oversized file is abandoned as soon as the limit is
+/// crossed (or before reading, when `Content-Length` already exceeds it).
+pub async fn fetch(http: &reqwest::Client, url: &Url, max_size: u64) -> Result<Download> {
+    let mut response = http
+        .get(url.clone())
+        .send()
+        .await
+        .with_context(|| format!("failed to download {url}"))?;
+    let status = response.status();
+    if !status.is_success() {
+        bail!("failed to download {url}: HTTP {status}");
+    }
+
+    let too_large = || anyhow!("{url} is larger than --max-download-size ({max_size} bytes)");
+    let declared = response
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    if declared.is_some_and(|length| length > max_size) {
+        return Err(too_large());
+    }
+
+    let final_url = response.url().clone();
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(media_type);
+
+    let mut data = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .with_context(|| format!("failed to download {url}"))?
+    {
+        if data.len() as u64 + chunk.len() as u64 > max_size {
+            return Err(too_large());
+        }
+        data.extend_from_slice(&chunk);
+    }
+
+    Ok(Download {
+        url: final_url,
+        content_type,
+        data,
+    })
+}
+
+/// `text/html; charset=utf-8` -> `text/html`.
+fn media_type(header: &str) -> Option<String> {
+    let essence = header.split(';').next()?.trim().to_ascii_lowercase();
+    (!essence.is_empty()).then_some(essence)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn serve(server: &MockServer, route: &str, response: ResponseTemplate) {
+        Mock::given(method("GET"))
+            .and(path(route))
+            .respond_with(response)
+            .mount(server)
+            .await;
+    }
+
+    fn url(server: &MockServer, route: &str) -> Url {
+        Url::parse(&format!("{}{route}", server.uri())).unwrap()
+    }
+
+    #[tokio::test]
+    async fn follows_redirects_and_names_the_file_after_the_final_url() {
+        let server = MockServer::start().await;
+        serve(
+            &server,
+            "/latest",
+            ResponseTemplate::new(302).insert_header("location", "/files/Annual%20Report.pdf"),
+        )
+        .await;
+        serve(
+            &server,
+            "/files/Annual%20Report.pdf",
+            ResponseTemplate::new(200)
+                .set_body_raw(b"%PDF-1.4".to_vec(), "application/pdf; qs=0.9"),
+        )
+        .await;
+
+        let download = fetch(&http_client().unwrap(), &url(&server, "/latest"), 1024)
+            .await
+            .unwrap();
+
+        assert_eq!(download.url.path(), "/files/Annual%20Report.pdf");
+        assert_eq!(download.file_name().as_deref(), Some("Annual Report.pdf"));
+        assert_eq!(download.content_type.as_deref(), Some("application/pdf"));
+        assert_eq!(download.data, b"%PDF-1.4");
+        assert!(!download.is_html());
+    }
+
+    #[tokio::test]
+    async fn aborts_once_the_body_exceeds_the_cap() {
+        let server = MockServer::start().await;
+        serve(
+            &server,
+            "/big.pdf",
+            ResponseTemplate::new(200).set_body_bytes(vec![0u8; 4096]),
+        )
+        .await;
+
+        let err = fetch(&http_client().unwrap(), &url(&server, "/big.pdf"), 1000)
+            .await
+            .unwrap_err();
+
+        assert!(
+            err.to_string()
+                .contains("larger than --max-download-size (1000 bytes)"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_html_and_error_statuses() {
+        let server = MockServer::start().await;
+        serve(
+            &server,
+            "/page",
+            ResponseTemplate::new(200).set_body_raw("<html></html>", "Text/HTML; charset=utf-8"),
+        )
+        .await;
+        serve(&server, "/missing", ResponseTemplate::new(404)).await;
+        let http = http_client().unwrap();
+
+        let page = fetch(&http, &url(&server, "/page"), 1024).await.unwrap();
+        assert_eq!(page.content_type.as_deref(), Some("text/html"));
+        assert!(page.is_html());
+
+        let err = fetch(&http, &url(&server, "/missing"), 1024)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("HTTP 404"), "{err}");
+    }
+
+    #[test]
+    fn file_name_is_none_for_a_bare_host() {
+        let download = Download {
+            url: Url::parse("https://example.com/").unwrap(),
+            content_type: None,
+            data: Vec::new(),
+        };
+        assert_eq!(download.file_name(), None);
+    }
+}
@@ -0,0 +1,34 @@
+use tokio::process::Command;
+
+/// Run `gcloud <args>` and return its trimmed stdout, or `None` if it failed or printed nothing.
+async fn output(args: &[&str]) -> Option<String> {
+    let output = Command::new("gcloud").args(args).output().await.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!value.is_empty() && value != "(unset)").then_some(value)
+}
+
+/// Active gcloud account (`gcloud config get-value account`).
+pub async fn account() -> Option<String> {
+    output(&["config", "get-value", "account"]).await
+}
+
+/// Project number of the active gcloud project.
+///
+/// NotebookLM needs the number, while gcloud is usually configured with the project ID, so the
+/// ID is resolved with `gcloud projects describe`.
+pub async fn project_number() -> Option<String> {
+    let project = output(&["config", "get-value", "project"]).await?;
+    if project.chars().all(|c| c.is_ascii_digit()) {
+        return Some(project);
+    }
+    output(&[
+        "projects",
+        "describe",
+        &project,
+        "--format=value(projectNumber)",
+    ])
+    .await
+}
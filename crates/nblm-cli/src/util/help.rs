@@ -0,0 +1,25 @@
+//! Extended `--help` text, also rendered into the man pages by `nblm man`.
+//!
+//! Commands put constraints and examples in `after_long_help`, split into sections by unindented
+//! headings ending in `:` (`Notes:`, `Examples:`, `Environment:`); [`super::man`] turns each
+//! heading into a man page section.
+
+/// `after_long_help` for a command that calls the API: the given sections, then an
+/// `Environment:` section listing the variables every API command reads followed by any
+/// command-specific ones given after `;`.
+macro_rules! api_help {
+    ($($text:expr),+ $(; $($env:expr),+)? $(,)?) => {
+        concat!(
+            $($text,)+
+            "\n\nEnvironment:\n",
+            "  NBLM_PROJECT_NUMBER     Google Cloud project number (--project-number)\n",
+            "  NBLM_LOCATION           Notebook location (--location, default global)\n",
+            "  NBLM_ENDPOINT_LOCATION  API endpoint multi-region: global, us or eu\n",
+            "  NBLM_ACCESS_TOKEN       Access token used with --auth env\n",
+            "  TRACEPARENT             W3C trace context to attach to every request",
+            $($("\n", $env,)+)?
+        )
+    };
+}
+
+pub(crate) use api_help;
@@ -0,0 +1,142 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio_util::sync::CancellationToken;
+
+/// Exit code after SIGINT or SIGTERM (128 + SIGINT, as shells report it).
+pub const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// How long in-flight requests may finish after an interrupt before they are abandoned.
+pub const IN_FLIGHT_GRACE: Duration = Duration::from_secs(5);
+
+/// Hidden test hook: interrupt after this many batch items have completed.
+const TEST_INTERRUPT_AFTER_ENV: &str = "NBLM_TEST_INTERRUPT_AFTER";
+
+/// Shared interrupt state for multi-step commands.
+///
+/// Outside a [`Interrupt::graceful`] section the first signal exits immediately. Inside one it
+/// only cancels the token, so the command can stop scheduling work and report what it finished;
+/// a second signal still exits immediately.
+#[derive(Clone, Default)]
+pub struct Interrupt {
+    token: CancellationToken,
+    graceful: Arc<AtomicBool>,
+    prompting: Arc<AtomicBool>,
+    completed: Arc<AtomicUsize>,
+    interrupt_after: Option<usize>,
+}
+
+impl Interrupt {
+    /// Listen for SIGINT and SIGTERM for the rest of the process.
+    pub fn listen() -> Self {
+        let interrupt = Self {
+            interrupt_after: std::env::var(TEST_INTERRUPT_AFTER_ENV)
+                .ok()
+                .and_then(|value| value.parse().ok()),
+            ..Self::default()
+        };
+        let listener = interrupt.clone();
+        tokio::spawn(async move {
+            if wait_for_signal().await.is_err() {
+                return;
+            }
+            if !listener.graceful.load(Ordering::SeqCst) {
+                crate::util::output::flush_stdout();
+                std::process::exit(INTERRUPTED_EXIT_CODE);
+            }
+            // An open prompt reports the signal itself.
+            if !listener.prompting.load(Ordering::SeqCst) {
+                eprintln!(
+                    "Interrupted; waiting for in-flight requests (press Ctrl-C again to quit now)"
+                );
+            }
+            listener.token.cancel();
+            if wait_for_signal().await.is_ok() {
+                crate::util::output::flush_stdout();
+                std::process::exit(INTERRUPTED_EXIT_CODE);
+            }
+        });
+        interrupt
+    }
+
+    /// Handle signals by cancelling instead of exiting until the guard is dropped.
+    pub fn graceful(&self) -> GracefulGuard {
+        self.graceful.store(true, Ordering::SeqCst);
+        GracefulGuard(vec![Arc::clone(&self.graceful)])
+    }
+
+    /// Like [`Interrupt::graceful`], for a [`prompt`](crate::util::prompt) that watches for
+    /// Ctrl-C itself: a signal is only recorded, without the in-flight requests message.
+    pub fn prompting(&self) -> GracefulGuard {
+        self.graceful.store(true, Ordering::SeqCst);
+        self.prompting.store(true, Ordering::SeqCst);
+        GracefulGuard(vec![
+            Arc::clone(&self.graceful),
+            Arc::clone(&self.prompting),
+        ])
+    }
+
+    pub fn is_interrupted(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Resolves once a signal has arrived.
+    pub async fn interrupted(&self) {
+        self.token.cancelled().await
+    }
+
+    #[cfg(test)]
+    pub fn trigger(&self) {
+        self.token.cancel();
+    }
+
+    /// Count a finished batch item; used by the test hook.
+    pub fn item_completed(&self) {
+        let completed = self.completed.fetch_add(1, Ordering::SeqCst) + 1;
+        if self.interrupt_after == Some(completed) {
+            self.token.cancel();
+        }
+    }
+}
+
+pub struct GracefulGuard(Vec<Arc<AtomicBool>>);
+
+impl Drop for GracefulGuard {
+    fn drop(&mut self) {
+        for flag in &self.0 {
+            flag.store(false, Ordering::SeqCst);
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() -> std::io::Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = signal(SignalKind::terminate())?;
+    tokio::select! {
+        result = tokio::signal::ctrl_c() => result,
+        _ = terminate.recv() => Ok(()),
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() -> std::io::Result<()> {
+    tokio::signal::ctrl_c().await
+}
+
+/// Error for a command stopped by a signal; `main` exits with [`INTERRUPTED_EXIT_CODE`].
+#[derive(Debug)]
+pub struct Interrupted {
+    pub summary: String,
+}
+
+impl fmt::Display for Interrupted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "interrupted: {}", self.summary)
+    }
+}
+
+impl std::error::Error for Interrupted {}
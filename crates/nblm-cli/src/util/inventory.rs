@@ -0,0 +1,250 @@
+//! Machine-readable inventory of the command tree, read from the clap definitions.
+
+use std::any::TypeId;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::{Arg, ArgAction, Command};
+use time::OffsetDateTime;
+
+use crate::util::payload::{
+    ArgEntry, CommandEntry, CommandInventory, PossibleValueEntry, ValueType,
+};
+
+/// Version of the [`CommandInventory`] schema. Bump it when a key is removed or changes meaning;
+/// new keys do not need a bump.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Every command under `root`, hidden ones included, in declaration order. `experimental` says
+/// whether a possible value of an argument (by id) only works behind an experiment flag.
+pub fn inventory(root: &Command, experimental: &dyn Fn(&str, &str) -> bool) -> CommandInventory {
+    let mut commands = Vec::new();
+    let mut pending = vec![(root, root.get_name().to_string())];
+    while let Some((cmd, path)) = pending.pop() {
+        commands.push(CommandEntry {
+            path: path.clone(),
+            about: cmd.get_about().map(|about| about.to_string()),
+            hidden: cmd.is_hide_set(),
+            requires_subcommand: cmd.is_subcommand_required_set(),
+            args: cmd
+                .get_arguments()
+                .filter(|arg| !is_builtin(arg))
+                .map(|arg| arg_entry(arg, experimental))
+                .collect(),
+        });
+        // Reversed so commands come out in the order they are declared.
+        for sub in cmd.get_subcommands().collect::<Vec<_>>().into_iter().rev() {
+            pending.push((sub, format!("{path} {}", sub.get_name())));
+        }
+    }
+    CommandInventory {
+        schema_version: SCHEMA_VERSION,
+        name: root.get_name().to_string(),
+        version: root.get_version().unwrap_or_default().to_string(),
+        commands,
+    }
+}
+
+/// `--help` and `--version`, which clap adds to every command.
+fn is_builtin(arg: &Arg) -> bool {
+    matches!(
+        arg.get_action(),
+        ArgAction::Help | ArgAction::HelpShort | ArgAction::HelpLong | ArgAction::Version
+    )
+}
+
+fn arg_entry(arg: &Arg, experimental: &dyn Fn(&str, &str) -> bool) -> ArgEntry {
+    let id = arg.get_id().as_str();
+    let possible_values: Vec<PossibleValueEntry> = arg
+        .get_possible_values()
+        .iter()
+        .map(|value| PossibleValueEntry {
+            name: value.get_name().to_string(),
+            hidden: value.is_hide_set(),
+            experimental: experimental(id, value.get_name()),
+        })
+        .collect();
+    let takes_values = arg.get_action().takes_values();
+    let repeated = matches!(arg.get_action(), ArgAction::Append)
+        || arg
+            .get_num_args()
+            .is_some_and(|range| range.max_values() > 1);
+    let defaults: Vec<String> = arg
+        .get_default_values()
+        .iter()
+        .map(|value| value.to_string_lossy().into_owned())
+        .collect();
+    ArgEntry {
+        id: id.to_string(),
+        long: arg.get_long().map(str::to_string),
+        short: arg.get_short(),
+        positional: arg.is_positional(),
+        value_name: arg
+            .get_value_names()
+            .and_then(|names| names.first())
+            .map(|name| name.to_string())
+            .filter(|_| takes_values),
+        value_type: value_type(arg, !possible_values.is_empty()),
+        repeated,
+        required: arg.is_required_set(),
+        // Flags default to false implicitly; only values a user would type are reported.
+        default: (takes_values && !defaults.is_empty()).then(|| defaults.join(",")),
+        env: arg.get_env().map(|env| env.to_string_lossy().into_owned()),
+        possible_values: if takes_values {
+            possible_values
+        } else {
+            Vec::new()
+        },
+        global: arg.is_global_set(),
+        hidden: arg.is_hide_set(),
+        help: arg.get_help().map(|help| help.to_string()),
+    }
+}
+
+/// What the value parser produces. Sizes are `u64`s parsed from values like `200MB`, so they
+/// are told apart from counts by their `SIZE` value name.
+fn value_type(arg: &Arg, has_possible_values: bool) -> ValueType {
+    match arg.get_action() {
+        ArgAction::SetTrue | ArgAction::SetFalse => return ValueType::Bool,
+        ArgAction::Count => return ValueType::Count,
+        _ => {}
+    }
+    if has_possible_values {
+        return ValueType::Enum;
+    }
+    let produced = arg.get_value_parser().type_id();
+    let is = |id: TypeId| produced == id;
+    let integers = [
+        TypeId::of::<u8>(),
+        TypeId::of::<u16>(),
+        TypeId::of::<u32>(),
+        TypeId::of::<u64>(),
+        TypeId::of::<usize>(),
+        TypeId::of::<i32>(),
+        TypeId::of::<i64>(),
+    ];
+    let is_size = arg
+        .get_value_names()
+        .is_some_and(|names| names.iter().any(|name| name.as_str() == "SIZE"));
+    if is(TypeId::of::<Duration>()) {
+        ValueType::Duration
+    } else if is(TypeId::of::<OffsetDateTime>()) {
+        ValueType::Time
+    } else if is(TypeId::of::<PathBuf>()) {
+        ValueType::Path
+    } else if is(TypeId::of::<url::Url>()) {
+        ValueType::Url
+    } else if is(TypeId::of::<u64>()) && is_size {
+        ValueType::Size
+    } else if integers.into_iter().any(is) {
+        ValueType::Integer
+    } else if is(TypeId::of::<f64>()) || is(TypeId::of::<f32>()) {
+        ValueType::Number
+    } else {
+        ValueType::String
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    use crate::args::Cli;
+    use crate::util::payload::CommandEntry;
+
+    fn nblm() -> CommandInventory {
+        inventory(&Cli::command(), &|arg, value| {
+            arg == "auth" && value == "user-oauth"
+        })
+    }
+
+    fn command<'a>(inventory: &'a CommandInventory, path: &str) -> &'a CommandEntry {
+        inventory
+            .commands
+            .iter()
+            .find(|command| command.path == path)
+            .unwrap_or_else(|| panic!("no command {path}"))
+    }
+
+    fn arg<'a>(command: &'a CommandEntry, long: &str) -> &'a ArgEntry {
+        command
+            .args
+            .iter()
+            .find(|arg| arg.long.as_deref() == Some(long))
+            .unwrap_or_else(|| panic!("no --{long} on {}", command.path))
+    }
+
+    #[test]
+    fn commands_are_listed_in_declaration_order_with_hidden_ones_marked() {
+        let inventory = nblm();
+        assert_eq!(inventory.schema_version, SCHEMA_VERSION);
+        assert_eq!(inventory.commands[0].path, "nblm");
+        assert_eq!(inventory.commands[1].path, "nblm notebooks");
+        assert!(command(&inventory, "nblm notebooks").requires_subcommand);
+        assert!(command(&inventory, "nblm man").hidden);
+        assert!(!command(&inventory, "nblm sources add").hidden);
+        assert!(inventory.commands.iter().all(|command| command
+            .args
+            .iter()
+            .all(|arg| arg.long.as_deref() != Some("help"))));
+    }
+
+    #[test]
+    fn web_url_is_repeatable() {
+        let inventory = nblm();
+        let web_url = arg(command(&inventory, "nblm sources add"), "web-url");
+        assert!(web_url.repeated);
+        assert_eq!(web_url.value_type, ValueType::String);
+        assert_eq!(web_url.value_name.as_deref(), Some("URL"));
+        assert!(!web_url.required);
+    }
+
+    #[test]
+    fn json_is_a_global_bool() {
+        let inventory = nblm();
+        let json = arg(command(&inventory, "nblm"), "json");
+        assert!(json.global);
+        assert_eq!(json.value_type, ValueType::Bool);
+        assert_eq!(json.default, None);
+        assert_eq!(json.value_name, None);
+        assert!(!json.repeated);
+    }
+
+    #[test]
+    fn base_url_is_hidden_with_its_env_fallback() {
+        let inventory = nblm();
+        let base_url = arg(command(&inventory, "nblm"), "base-url");
+        assert!(base_url.hidden);
+        assert_eq!(base_url.env.as_deref(), Some("NBLM_BASE_URL"));
+        assert_eq!(base_url.value_type, ValueType::String);
+    }
+
+    #[test]
+    fn enums_list_their_values_and_flag_experimental_ones() {
+        let inventory = nblm();
+        let auth = arg(command(&inventory, "nblm"), "auth");
+        assert_eq!(auth.value_type, ValueType::Enum);
+        assert_eq!(auth.default.as_deref(), Some("gcloud"));
+        let user_oauth = auth
+            .possible_values
+            .iter()
+            .find(|value| value.name == "user-oauth")
+            .unwrap();
+        assert!(user_oauth.hidden && user_oauth.experimental);
+        assert!(auth
+            .possible_values
+            .iter()
+            .filter(|value| value.name != "user-oauth")
+            .all(|value| !value.hidden && !value.experimental));
+    }
+
+    #[test]
+    fn durations_and_sizes_are_typed() {
+        let inventory = nblm();
+        let root = command(&inventory, "nblm");
+        assert_eq!(arg(root, "timeout").value_type, ValueType::Duration);
+        let upload = command(&inventory, "nblm sources upload");
+        assert_eq!(arg(upload, "max-file-size").value_type, ValueType::Size);
+    }
+}
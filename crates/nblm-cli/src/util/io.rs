@@ -1,146 +1,748 @@
+use std::collections::BTreeMap;
+
 use anyhow::Result;
+use colored::Colorize;
 use nblm_core::models::enterprise::{
+    activity::{ActivityEvent, ActivityKind, NotebookActivity},
+    budget::NotebookWordBudget,
     notebook::{ListRecentlyViewedResponse, Notebook},
+    query::QueryResponse,
     source::{BatchCreateSourcesResponse, NotebookSource, UploadSourceFileResponse},
+    stats::SourceCounts,
 };
-use serde_json::json;
+use nblm_core::ResponseEnvelope;
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+use serde_json::Value;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
 
-pub fn emit_notebook(notebook: &Notebook, json_mode: bool) {
-    let notebook_id = notebook
-        .notebook_id
-        .as_deref()
-        .or_else(|| {
-            notebook
-                .name
-                .as_deref()
-                .and_then(|name| name.rsplit('/').next())
-        })
-        .unwrap_or_default();
-    let payload = json!({
-        "notebook_id": notebook_id,
-        "notebook": notebook,
-    });
-    emit_json(payload, json_mode);
+use crate::util::output::OutputSink;
+use crate::util::payload::{
+    NotebookCreated, NotebookQueried, Sorted, SourceCountReport, SourceUploaded, SourcesAdded,
+    SourcesAddedRaw, WordBudget,
+};
+use crate::util::wrap::{terminal_width, wrap};
+
+pub fn emit_notebook(out: &dyn OutputSink, notebook: &Notebook, json_mode: bool) -> Result<()> {
+    let notebook_id = notebook.notebook_id().unwrap_or_default();
+    emit_json(
+        out,
+        &NotebookCreated {
+            notebook_id,
+            notebook: Sorted(notebook),
+        },
+        json_mode,
+    )
 }
 
-pub fn emit_recent(response: &ListRecentlyViewedResponse, json_mode: bool) -> Result<()> {
+/// Print recently viewed notebooks. In text mode each notebook's summary is left out unless
+/// `show_summary` is set, and then printed under a wrapped `Summary:` heading, and a note on
+/// stderr gives the token for the next page when there is one.
+///
+/// `--json` prints the response body as received, except that `nextPageToken` is always present
+/// at the top level: `null` on the last page.
+pub fn emit_recent(
+    out: &dyn OutputSink,
+    envelope: &ResponseEnvelope<ListRecentlyViewedResponse>,
+    json_mode: bool,
+    show_summary: bool,
+) -> Result<()> {
+    let response = envelope.value();
+    let next_page_token = response
+        .next_page_token
+        .as_deref()
+        .filter(|token| !token.is_empty());
     if json_mode {
-        emit_json(json!(response), true);
-    } else if response.notebooks.is_empty() {
-        println!("No recently viewed notebooks.");
+        return emit_listing_json(out, envelope.raw(), next_page_token);
+    }
+    if response.notebooks.is_empty() {
+        out.out("No recently viewed notebooks.");
     } else {
         for notebook in &response.notebooks {
-            println!("{}", serde_json::to_string_pretty(notebook)?);
+            let summary = notebook
+                .summary
+                .as_deref()
+                .filter(|summary| !summary.trim().is_empty());
+            let notebook = Notebook {
+                summary: None,
+                ..notebook.clone()
+            };
+            out.out(&serde_json::to_string_pretty(&notebook)?);
+            if let (true, Some(summary)) = (show_summary, summary) {
+                emit_summary(out, summary, terminal_width());
+            }
         }
     }
+    if let Some(token) = next_page_token {
+        out.err(&format!(
+            "more results available; use --page-token {token} for the next page"
+        ));
+    }
     Ok(())
 }
 
+/// Print a list response body with `nextPageToken` set to `next_page_token` (`null` when there
+/// are no more pages) and a `notebooks` array, empty when the API left it out (it answers `{}`
+/// for a project without notebooks). A body that already has both is printed unchanged; one that
+/// needs either has its top-level keys sorted, which puts them where `nblm` has always printed them.
+/// Only the top level is split up: every value in it is printed as received.
+fn emit_listing_json(
+    out: &dyn OutputSink,
+    raw: &[u8],
+    next_page_token: Option<&str>,
+) -> Result<()> {
+    let no_notebooks = RawValue::from_string("[]".to_string())?;
+    let token = serde_json::value::to_raw_value(&next_page_token)?;
+    let Ok(mut fields) = serde_json::from_slice::<BTreeMap<String, &RawValue>>(raw) else {
+        return emit_raw_json(out, raw);
+    };
+    let has_notebooks = fields.contains_key("notebooks");
+    // A token here came from this body's own nextPageToken.
+    if next_page_token.is_some() && has_notebooks {
+        return emit_raw_json(out, raw);
+    }
+    fields
+        .entry("notebooks".to_string())
+        .or_insert(&no_notebooks);
+    fields.insert("nextPageToken".to_string(), &token);
+    emit_raw_payload(out, &fields)
+}
+
+/// A `Summary:` heading followed by `summary` wrapped to `width` and indented.
+fn emit_summary(out: &dyn OutputSink, summary: &str, width: usize) {
+    out.out(&"Summary:".bold().to_string());
+    for line in wrap(summary, width, "  ") {
+        out.out(&line);
+    }
+}
+
 pub fn emit_sources(
+    out: &dyn OutputSink,
     notebook_id: &str,
-    response: &BatchCreateSourcesResponse,
+    envelope: &ResponseEnvelope<BatchCreateSourcesResponse>,
     json_mode: bool,
 ) -> Result<()> {
-    let payload = json!({
-        "notebook_id": notebook_id,
-        "sources": response.sources,
-        "error_count": response.error_count,
-    });
-    emit_json(payload, json_mode);
+    if json_mode {
+        // Take the source entries straight from the server payload so fields the models do
+        // not know about still reach stdout.
+        #[derive(Deserialize)]
+        struct Body<'a> {
+            #[serde(borrow, rename = "errorCount")]
+            error_count: Option<&'a RawValue>,
+            #[serde(borrow)]
+            sources: Option<&'a RawValue>,
+        }
+        let no_sources = RawValue::from_string("[]".to_string())?;
+        let body: Body = serde_json::from_slice(envelope.raw())?;
+        return emit_raw_payload(
+            out,
+            &SourcesAddedRaw {
+                error_count: body.error_count,
+                notebook_id,
+                sources: body.sources.unwrap_or(&no_sources),
+            },
+        );
+    }
+    let response = envelope.value();
+    let payload = SourcesAdded {
+        notebook_id,
+        source_ids: Some(response.created_ids()),
+        sources: Sorted(serde_json::to_value(&response.sources)?),
+        retried: None,
+        error_count: serde_json::to_value(response.error_count)?,
+    };
+    emit_json(out, &payload, false)?;
+    for source in response.failed() {
+        let status = source
+            .settings
+            .as_ref()
+            .and_then(|settings| settings.status.as_deref())
+            .unwrap_or_default();
+        out.err(&format!(
+            "Source {} failed to ingest ({status})",
+            source.source_id().unwrap_or(&source.name)
+        ));
+    }
     Ok(())
 }
 
 pub fn emit_uploaded_source(
+    out: &dyn OutputSink,
     notebook_id: &str,
     file_name: &str,
     content_type: &str,
     response: &UploadSourceFileResponse,
     json_mode: bool,
 ) -> Result<()> {
-    let payload = json!({
-        "notebook_id": notebook_id,
-        "file_name": file_name,
-        "content_type": content_type,
-        "source_id": response.source_id,
-        "extra": response.extra,
-    });
-    emit_json(payload, json_mode);
+    emit_json(
+        out,
+        &SourceUploaded::new(notebook_id, file_name, content_type, response),
+        json_mode,
+    )?;
     if !json_mode {
         if let Some(source_id) = response.source_id.as_ref().and_then(|id| id.id.as_deref()) {
-            println!("Created source: {source_id}");
+            out.out(&format!("Created source: {source_id}"));
+        } else {
+            out.out("Upload request accepted (source ID unavailable)");
+        }
+        if let Some(name) = response.name.as_deref() {
+            out.out(&format!("Resource name: {name}"));
+        }
+        if let Some(state) = response.state.as_deref() {
+            out.out(&format!("State: {state}"));
+        }
+    }
+    Ok(())
+}
+
+/// The answer, then its citations numbered from 1, as `[n] TITLE (SOURCE_ID)` and the passage.
+pub fn emit_query_answer(
+    out: &dyn OutputSink,
+    notebook_id: &str,
+    query: &str,
+    response: &QueryResponse,
+    json_mode: bool,
+) -> Result<()> {
+    if json_mode {
+        return emit_json(
+            out,
+            &NotebookQueried {
+                notebook_id,
+                query,
+                response: Sorted(response),
+            },
+            json_mode,
+        );
+    }
+
+    if !response.has_answer() {
+        out.out("No answer found in the notebook's sources.");
+        return Ok(());
+    }
+    out.out(response.answer.as_deref().unwrap_or_default().trim_end());
+    if response.citations.is_empty() {
+        return Ok(());
+    }
+    out.out("");
+    out.out("Citations:");
+    for (index, citation) in response.citations.iter().enumerate() {
+        let source_id = citation.source_id.as_ref().and_then(|id| id.id.as_deref());
+        let label = match (citation.title.as_deref(), source_id) {
+            (Some(title), Some(id)) => format!("{title} ({id})"),
+            (Some(title), None) => title.to_string(),
+            (None, Some(id)) => id.to_string(),
+            (None, None) => "unknown source".to_string(),
+        };
+        out.out(&format!("  [{}] {label}", index + 1));
+        if let Some(text) = citation
+            .text
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+        {
+            out.out(&format!("      {text}"));
+        }
+    }
+    Ok(())
+}
+
+/// Number of largest sources highlighted in the budget report.
+const BUDGET_TOP_OFFENDERS: usize = 3;
+
+pub fn emit_word_budget(
+    out: &dyn OutputSink,
+    summary: &NotebookWordBudget,
+    budget: u64,
+    json_mode: bool,
+) -> Result<()> {
+    let over_by = summary.over_budget(budget);
+    if json_mode {
+        return emit_json(out, &WordBudget::new(summary, budget), json_mode);
+    }
+
+    out.out(&format!(
+        "Notebook {}: {} words across {} source(s) (budget: {})",
+        summary.notebook_id,
+        summary.total_words,
+        summary.sources.len(),
+        budget
+    ));
+    for (index, source) in summary.sources.iter().enumerate() {
+        let label = source.title.as_deref().unwrap_or(&source.name);
+        let count = match source.word_count {
+            Some(words) => words.to_string(),
+            None => "n/a".to_string(),
+        };
+        let line = format!("  {count:>10}  {label}");
+        if over_by.is_some() && index < BUDGET_TOP_OFFENDERS && source.words() > 0 {
+            out.out(&line.yellow().bold().to_string());
         } else {
-            println!("Upload request accepted (source ID unavailable)");
+            out.out(&line);
         }
     }
+    if summary.missing_word_counts > 0 {
+        out.out(&format!(
+            "Note: {} source(s) have no word count yet and were counted as 0.",
+            summary.missing_word_counts
+        ));
+    }
+    match over_by {
+        Some(over) => out.out(
+            &format!("Over budget by {over} words")
+                .red()
+                .bold()
+                .to_string(),
+        ),
+        None => out.out(
+            &format!(
+                "Within budget ({} words remaining)",
+                budget - summary.total_words
+            )
+            .green()
+            .to_string(),
+        ),
+    }
+    Ok(())
+}
+
+/// Print one row per notebook, most sources first, and the totals.
+pub fn emit_source_counts(
+    out: &dyn OutputSink,
+    counts: &SourceCounts,
+    json_mode: bool,
+) -> Result<()> {
+    if json_mode {
+        return emit_json(out, &SourceCountReport::new(counts), json_mode);
+    }
+    let width = counts
+        .notebooks
+        .iter()
+        .map(|count| count.notebook_id.len())
+        .max()
+        .unwrap_or(0)
+        .max("NOTEBOOK".len());
+    out.out(&format!("{:>7}  {:<width$}  TITLE", "SOURCES", "NOTEBOOK"));
+    for count in &counts.notebooks {
+        out.out(&format!(
+            "{:>7}  {:<width$}  {}",
+            count.sources, count.notebook_id, count.title
+        ));
+    }
+    out.out(&format!(
+        "{} notebook(s), {} source(s)",
+        counts.notebooks.len(),
+        counts.total_sources
+    ));
     Ok(())
 }
 
-pub fn emit_source(source: &NotebookSource) {
-    println!("Source Details:");
-    println!("  Name: {}", source.name);
+/// Print a notebook's timeline, oldest first, each event with its time and how long before
+/// `now` it happened. `--json` prints the events as assembled.
+pub fn emit_activity(
+    out: &dyn OutputSink,
+    activity: &NotebookActivity,
+    now: OffsetDateTime,
+    json_mode: bool,
+) -> Result<()> {
+    if json_mode {
+        return emit_json(out, activity, json_mode);
+    }
+    out.out(&format!(
+        "Notebook {}: {} event(s)",
+        activity.notebook_id,
+        activity.events.len()
+    ));
+    let width = activity
+        .events
+        .iter()
+        .map(|event| relative_time(event.at, now).len())
+        .max()
+        .unwrap_or(0);
+    for event in &activity.events {
+        out.out(&format!(
+            "  {}  {:<width$}  {}",
+            event.at.format(&Rfc3339)?,
+            relative_time(event.at, now),
+            describe_event(event)
+        ));
+    }
+    if activity.missing_timestamps > 0 {
+        out.out(&format!(
+            "Note: {} event(s) lacked timestamps and are not shown.",
+            activity.missing_timestamps
+        ));
+    }
+    Ok(())
+}
+
+fn describe_event(event: &ActivityEvent) -> String {
+    match event.kind {
+        ActivityKind::NotebookCreated => "Notebook created".to_string(),
+        ActivityKind::SourceAdded => format!(
+            "Source added: {}",
+            event
+                .title
+                .as_deref()
+                .or(event.source.as_deref())
+                .unwrap_or("(untitled)")
+        ),
+        ActivityKind::AudioOverviewCreated => "Audio overview created".to_string(),
+        ActivityKind::LastViewed => "Last viewed".to_string(),
+    }
+}
+
+/// `at` relative to `now` in its largest whole unit: `just now`, `5 minutes ago`, `in 2 days`.
+fn relative_time(at: OffsetDateTime, now: OffsetDateTime) -> String {
+    let delta = now - at;
+    let seconds = delta.whole_seconds().unsigned_abs();
+    let (count, unit) = match seconds {
+        0..=59 => return "just now".to_string(),
+        60..=3_599 => (seconds / 60, "minute"),
+        3_600..=86_399 => (seconds / 3_600, "hour"),
+        86_400..=2_591_999 => (seconds / 86_400, "day"),
+        2_592_000..=31_535_999 => (seconds / 2_592_000, "month"),
+        _ => (seconds / 31_536_000, "year"),
+    };
+    let plural = if count == 1 { "" } else { "s" };
+    if delta.is_negative() {
+        format!("in {count} {unit}{plural}")
+    } else {
+        format!("{count} {unit}{plural} ago")
+    }
+}
+
+/// `counts` as CSV with a `notebook_id,title,sources` header, in the same order as the table.
+pub fn source_counts_csv(counts: &SourceCounts) -> String {
+    let mut csv = String::from("notebook_id,title,sources\n");
+    for count in &counts.notebooks {
+        csv.push_str(&format!(
+            "{},{},{}\n",
+            csv_field(&count.notebook_id),
+            csv_field(&count.title),
+            count.sources
+        ));
+    }
+    csv
+}
+
+/// Quote a CSV field when it contains a comma, quote or line break (RFC 4180).
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn emit_source(out: &dyn OutputSink, source: &NotebookSource) {
+    out.out("Source Details:");
+    out.out(&format!("  Name: {}", source.name));
     if let Some(title) = &source.title {
-        println!("  Title: {}", title);
+        out.out(&format!("  Title: {}", title));
     }
     if let Some(source_id) = &source.source_id {
         if let Some(id) = &source_id.id {
-            println!("  Source ID: {}", id);
+            out.out(&format!("  Source ID: {}", id));
         }
     }
     if let Some(metadata) = &source.metadata {
-        println!("  Metadata:");
+        out.out("  Metadata:");
         if let Some(timestamp) = &metadata.source_added_timestamp {
-            println!("    Added: {}", timestamp);
+            out.out(&format!("    Added: {}", timestamp));
         }
         if let Some(word_count) = &metadata.word_count {
-            println!("    Word Count: {}", word_count);
+            out.out(&format!("    Word Count: {}", word_count));
         }
         if let Some(youtube_metadata) = &metadata.youtube_metadata {
             if let Some(channel_name) = &youtube_metadata.channel_name {
-                println!("    YouTube Channel: {}", channel_name);
+                out.out(&format!("    YouTube Channel: {}", channel_name));
             }
             if let Some(video_id) = &youtube_metadata.video_id {
-                println!("    YouTube Video ID: {}", video_id);
+                out.out(&format!("    YouTube Video ID: {}", video_id));
             }
         }
     }
     if let Some(settings) = &source.settings {
         if let Some(status) = &settings.status {
-            println!("  Status: {}", status);
+            out.out(&format!("  Status: {}", status));
         }
     }
 }
 
-pub fn emit_json(value: serde_json::Value, json_mode: bool) {
+/// Write a server payload to stdout as received, ending in exactly one newline.
+pub fn emit_raw_json(out: &dyn OutputSink, raw: &[u8]) -> Result<()> {
+    let end = raw
+        .iter()
+        .rposition(|byte| !matches!(byte, b'\n' | b'\r'))
+        .map_or(0, |last| last + 1);
+    let mut payload = Vec::with_capacity(end + 1);
+    payload.extend_from_slice(&raw[..end]);
+    payload.push(b'\n');
+    out.out_raw(&payload)?;
+    Ok(())
+}
+
+/// Print a payload holding [`RawValue`]s as one JSON document, like [`emit_raw_json`]. The raw
+/// values are written as the API sent them, not parsed and re-indented.
+fn emit_raw_payload(out: &dyn OutputSink, payload: &impl Serialize) -> Result<()> {
+    emit_raw_json(out, &serde_json::to_vec_pretty(payload)?)
+}
+
+/// Print a [`payload`](crate::util::payload) as JSON, or as `key: value` lines in field order.
+pub fn emit_json(out: &dyn OutputSink, payload: &impl Serialize, json_mode: bool) -> Result<()> {
+    let value = serde_json::to_value(payload)?;
     if json_mode {
-        println!("{}", serde_json::to_string_pretty(&value).unwrap());
+        out.json(&value);
     } else {
         match value {
-            serde_json::Value::Object(map) => {
+            Value::Object(map) => {
                 for (key, val) in map {
-                    println!("{key}: {val}");
+                    out.out(&format!("{key}: {val}"));
                 }
             }
-            other => println!("{}", other),
+            other => out.out(&other.to_string()),
         }
     }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::util::output::CapturedOutput;
+    use crate::util::payload::Deleted;
+    use nblm_core::models::enterprise::stats::NotebookSourceCount;
+
+    fn recent_envelope(body: &str) -> ResponseEnvelope<ListRecentlyViewedResponse> {
+        let value = serde_json::from_str(body).unwrap();
+        ResponseEnvelope::new(value, body.as_bytes().to_vec().into())
+    }
+
+    #[test]
+    fn emit_recent_reports_empty_list() {
+        let out = CapturedOutput::default();
+        emit_recent(&out, &recent_envelope("{}"), false, false).unwrap();
+        assert_eq!(out.stdout(), "No recently viewed notebooks.\n");
+        assert_eq!(out.stderr(), "");
+    }
+
+    #[test]
+    fn emit_recent_json_always_has_a_next_page_token() {
+        let out = CapturedOutput::default();
+        emit_recent(&out, &recent_envelope(r#"{"notebooks":[]}"#), true, false).unwrap();
+        let printed: Value = serde_json::from_str(&out.stdout()).unwrap();
+        assert_eq!(printed["nextPageToken"], Value::Null);
+
+        let out = CapturedOutput::default();
+        let body = r#"{"notebooks":[],"nextPageToken":""}"#;
+        emit_recent(&out, &recent_envelope(body), true, false).unwrap();
+        let printed: Value = serde_json::from_str(&out.stdout()).unwrap();
+        assert_eq!(printed["nextPageToken"], Value::Null);
+
+        let out = CapturedOutput::default();
+        let body = r#"{"notebooks":[],"nextPageToken":"page-2"}"#;
+        emit_recent(&out, &recent_envelope(body), true, false).unwrap();
+        assert_eq!(out.stdout(), format!("{body}\n"));
+        assert_eq!(out.stderr(), "");
+    }
+
+    #[test]
+    fn emit_recent_json_fills_in_a_missing_notebooks_array() {
+        let out = CapturedOutput::default();
+        emit_recent(&out, &recent_envelope("{}"), true, false).unwrap();
+        let printed: Value = serde_json::from_str(&out.stdout()).unwrap();
+        assert_eq!(
+            printed,
+            serde_json::json!({"notebooks": [], "nextPageToken": null})
+        );
+
+        let out = CapturedOutput::default();
+        emit_recent(
+            &out,
+            &recent_envelope(r#"{"nextPageToken":"page-2"}"#),
+            true,
+            false,
+        )
+        .unwrap();
+        let printed: Value = serde_json::from_str(&out.stdout()).unwrap();
+        assert_eq!(printed["notebooks"], serde_json::json!([]));
+        assert_eq!(printed["nextPageToken"], "page-2");
+    }
+
+    #[test]
+    fn emit_recent_notes_more_pages_on_stderr_in_text_mode() {
+        let out = CapturedOutput::default();
+        let body =
+            r#"{"notebooks":[{"title":"First","notebookId":"nb1"}],"nextPageToken":"page-2"}"#;
+        emit_recent(&out, &recent_envelope(body), false, false).unwrap();
+        assert_eq!(
+            out.stderr(),
+            "more results available; use --page-token page-2 for the next page\n"
+        );
+        assert!(!out.stdout().contains("page-2"));
+
+        let out = CapturedOutput::default();
+        emit_recent(&out, &recent_envelope(r#"{"notebooks":[]}"#), false, false).unwrap();
+        assert_eq!(out.stderr(), "");
+    }
+
+    #[test]
+    fn emit_recent_prints_each_notebook() {
+        let out = CapturedOutput::default();
+        let body = r#"{"notebooks":[{"title":"First","notebookId":"nb1"},{"title":"Second","notebookId":"nb2"}]}"#;
+        emit_recent(&out, &recent_envelope(body), false, false).unwrap();
+
+        let stdout = out.stdout();
+        assert!(!stdout.contains("No recently viewed notebooks."));
+        assert!(stdout.contains("\"title\": \"First\""));
+        assert!(stdout.contains("\"title\": \"Second\""));
+    }
+
+    #[test]
+    fn emit_recent_prints_summaries_only_when_asked() {
+        let body = r#"{"notebooks":[
+            {"title":"First","notebookId":"nb1","summary":"Quarterly reports and analyst notes about revenue."},
+            {"title":"Second","notebookId":"nb2"}
+        ]}"#;
+
+        let out = CapturedOutput::default();
+        emit_recent(&out, &recent_envelope(body), false, false).unwrap();
+        assert!(!out.stdout().contains("Summary"));
+        assert!(!out.stdout().contains("Quarterly"));
+
+        let out = CapturedOutput::default();
+        emit_recent(&out, &recent_envelope(body), false, true).unwrap();
+        let stdout = out.stdout();
+        assert_eq!(stdout.matches("Summary:").count(), 1);
+        assert!(stdout.contains("  Quarterly reports and analyst notes about revenue.\n"));
+        assert!(!stdout.contains("\"summary\""));
+    }
+
+    #[test]
+    fn emit_raw_json_ends_with_one_newline() {
+        for raw in ["{}", "{}\n", "{}\n\n", "{}\r\n"] {
+            let out = CapturedOutput::default();
+            emit_raw_json(&out, raw.as_bytes()).unwrap();
+            assert_eq!(out.stdout(), "{}\n", "{raw:?}");
+        }
+    }
+
+    #[test]
+    fn emit_recent_json_mode_passes_raw_body_through() {
+        let out = CapturedOutput::default();
+        let body = r#"{"notebooks":[],"unknownField":1,"nextPageToken":"t"}"#;
+        emit_recent(&out, &recent_envelope(body), true, false).unwrap();
+        assert_eq!(out.stdout(), format!("{body}\n"));
+        assert_eq!(out.stderr(), "");
+
+        // Only the missing token is added; unknown fields are kept.
+        let out = CapturedOutput::default();
+        let body = r#"{"notebooks":[],"unknownField":1}"#;
+        emit_recent(&out, &recent_envelope(body), true, false).unwrap();
+        let printed: Value = serde_json::from_str(&out.stdout()).unwrap();
+        assert_eq!(
+            printed,
+            serde_json::json!({ "notebooks": [], "unknownField": 1, "nextPageToken": null })
+        );
+    }
+
+    #[test]
+    fn emit_sources_lists_created_ids_and_reports_failures() {
+        let body = r#"{"sources":[
+            {"name":"projects/1/locations/global/notebooks/nb/sources/s1","settings":{"status":"SOURCE_STATUS_COMPLETE"}},
+            {"name":"projects/1/locations/global/notebooks/nb/sources/s2","settings":{"status":"SOURCE_STATUS_ERROR"}}
+        ],"errorCount":1}"#;
+        let envelope = ResponseEnvelope::new(
+            serde_json::from_str(body).unwrap(),
+            body.as_bytes().to_vec().into(),
+        );
+
+        let out = CapturedOutput::default();
+        emit_sources(&out, "nb", &envelope, false).unwrap();
+        assert!(out.stdout().contains("source_ids: [\"s1\"]"));
+        assert_eq!(
+            out.stderr(),
+            "Source s2 failed to ingest (SOURCE_STATUS_ERROR)\n"
+        );
+
+        let json_out = CapturedOutput::default();
+        emit_sources(&json_out, "nb", &envelope, true).unwrap();
+        assert_eq!(json_out.stderr(), "");
+    }
 
+    #[test]
+    fn emit_sources_json_mode_writes_the_response_fields_as_received() {
+        let sources = r#"[{"name":"projects/1/locations/global/notebooks/nb/sources/s1","futureField":{"b":1,"a":2}}]"#;
+        let body = format!(r#"{{"sources":{sources},"errorCount":0}}"#);
+        let envelope = ResponseEnvelope::new(
+            serde_json::from_str(&body).unwrap(),
+            body.as_bytes().to_vec().into(),
+        );
+
+        let out = CapturedOutput::default();
+        emit_sources(&out, "nb", &envelope, true).unwrap();
+        assert_eq!(
+            out.stdout(),
+            format!(
+                "{{\n  \"error_count\": 0,\n  \"notebook_id\": \"nb\",\n  \"sources\": {sources}\n}}\n"
+            )
+        );
+
+        // A response without sources or errorCount still prints both keys.
+        let envelope = ResponseEnvelope::new(Default::default(), b"{}".to_vec().into());
+        let out = CapturedOutput::default();
+        emit_sources(&out, "nb", &envelope, true).unwrap();
+        let printed: Value = serde_json::from_str(&out.stdout()).unwrap();
+        assert_eq!(
+            printed,
+            serde_json::json!({ "error_count": null, "notebook_id": "nb", "sources": [] })
+        );
+    }
+
+    #[test]
+    fn relative_times_use_the_largest_whole_unit() {
+        let now = OffsetDateTime::parse("2025-03-01T12:00:00Z", &Rfc3339).unwrap();
+        let ago = |seconds: i64| relative_time(now - time::Duration::seconds(seconds), now);
+        assert_eq!(ago(30), "just now");
+        assert_eq!(ago(60), "1 minute ago");
+        assert_eq!(ago(2 * 3_600 + 59), "2 hours ago");
+        assert_eq!(ago(3 * 86_400), "3 days ago");
+        assert_eq!(ago(400 * 86_400), "1 year ago");
+        assert_eq!(ago(-90), "in 1 minute");
+    }
+
+    #[test]
+    fn emit_json_routes_by_mode() {
+        let payload = Deleted {
+            status: "deleted",
+            count: 2,
+            response: Sorted(Value::Object(Default::default())),
+            failed: None,
+            skipped: None,
+            in_flight: None,
+        };
+
+        let json_out = CapturedOutput::default();
+        emit_json(&json_out, &payload, true).unwrap();
+        assert_eq!(
+            json_out.stdout(),
+            "{\n  \"count\": 2,\n  \"response\": {},\n  \"status\": \"deleted\"\n}\n"
+        );
+
+        let text_out = CapturedOutput::default();
+        emit_json(&text_out, &payload, false).unwrap();
+        assert_eq!(
+            text_out.stdout(),
+            "count: 2\nresponse: {}\nstatus: \"deleted\"\n"
+        );
+        assert_eq!(json_out.stderr(), "");
+        assert_eq!(text_out.stderr(), "");
+    }
+
+    /// The ID [`emit_notebook`] reports.
     fn extract_notebook_id(notebook: &Notebook) -> String {
-        notebook
-            .notebook_id
-            .as_deref()
-            .or_else(|| {
-                notebook
-                    .name
-                    .as_deref()
-                    .and_then(|name| name.rsplit('/').next())
-            })
-            .unwrap_or_default()
-            .to_string()
+        notebook.notebook_id().unwrap_or_default().to_string()
     }
 
     #[test]
@@ -150,6 +752,7 @@ mod tests {
             title: "Test".to_string(),
             notebook_id: Some("nb123".to_string()),
             emoji: None,
+            summary: None,
             metadata: None,
             sources: Vec::new(),
             extra: Default::default(),
@@ -164,6 +767,7 @@ mod tests {
             title: "Test".to_string(),
             notebook_id: None,
             emoji: None,
+            summary: None,
             metadata: None,
             sources: Vec::new(),
             extra: Default::default(),
@@ -178,6 +782,7 @@ mod tests {
             title: "Test".to_string(),
             notebook_id: Some("from-field".to_string()),
             emoji: None,
+            summary: None,
             metadata: None,
             sources: Vec::new(),
             extra: Default::default(),
@@ -192,6 +797,7 @@ mod tests {
             title: "Test".to_string(),
             notebook_id: None,
             emoji: None,
+            summary: None,
             metadata: None,
             sources: Vec::new(),
             extra: Default::default(),
@@ -206,6 +812,7 @@ mod tests {
             title: "Test".to_string(),
             notebook_id: None,
             emoji: None,
+            summary: None,
             metadata: None,
             sources: Vec::new(),
             extra: Default::default(),
@@ -220,6 +827,7 @@ mod tests {
             title: "Test".to_string(),
             notebook_id: None,
             emoji: None,
+            summary: None,
             metadata: None,
             sources: Vec::new(),
             extra: Default::default(),
@@ -227,4 +835,27 @@ mod tests {
         // rsplit('/').next() will return "weird"
         assert_eq!(extract_notebook_id(&notebook), "weird");
     }
+
+    #[test]
+    fn source_counts_csv_quotes_titles_that_need_it() {
+        let counts = SourceCounts {
+            notebooks: vec![
+                NotebookSourceCount {
+                    notebook_id: "nb1".to_string(),
+                    title: "Q3, \"final\"".to_string(),
+                    sources: 4,
+                },
+                NotebookSourceCount {
+                    notebook_id: "nb2".to_string(),
+                    title: "Plain".to_string(),
+                    sources: 0,
+                },
+            ],
+            total_sources: 4,
+        };
+        assert_eq!(
+            source_counts_csv(&counts),
+            "notebook_id,title,sources\nnb1,\"Q3, \"\"final\"\"\",4\nnb2,Plain,0\n"
+        );
+    }
 }
@@ -0,0 +1,103 @@
+//! Man pages for the command tree, rendered from the clap definitions by `clap_mangen`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::Command;
+use clap_mangen::Man;
+
+/// Write one `.1` page per visible command (`nblm.1`, `nblm-sources-add.1`, ...) into `dir`,
+/// creating it if needed. Returns the written paths in tree order.
+pub fn write_pages(root: Command, dir: &Path) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    let version = root.get_version().unwrap_or_default().to_string();
+    // Building names each subcommand `parent-child`, which is also its page name.
+    let mut root = root.disable_help_subcommand(true);
+    root.build();
+
+    let mut written = Vec::new();
+    let mut pending = vec![root];
+    while let Some(cmd) = pending.pop() {
+        // Reversed so pages are written in the order the subcommands are declared.
+        let subcommands: Vec<_> = cmd
+            .get_subcommands()
+            .filter(|sub| !sub.is_hide_set())
+            .cloned()
+            .collect();
+        pending.extend(subcommands.into_iter().rev());
+        let name = cmd.get_display_name().unwrap_or(cmd.get_name()).to_string();
+        let path = Man::new(cmd)
+            .source(format!("nblm {version}"))
+            .manual("nblm manual")
+            .generate_to(dir)
+            .with_context(|| format!("failed to write the man page for {name}"))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Arg;
+
+    fn sample() -> Command {
+        Command::new("tool")
+            .version("1.2.3")
+            .about("Sample tool")
+            .subcommand(
+                Command::new("run")
+                    .about("Run it")
+                    .arg(
+                        Arg::new("count")
+                            .long("count")
+                            .value_name("N")
+                            .help("How many")
+                            .default_value("1")
+                            .env("TOOL_COUNT"),
+                    )
+                    .after_long_help(
+                        "Notes:\n.dots start this line\n'quotes start this one\n\
+                         a \\backslash stays literal",
+                    ),
+            )
+            .subcommand(Command::new("secret").hide(true))
+    }
+
+    #[test]
+    fn writes_a_page_per_visible_command() {
+        let dir = tempfile::tempdir().unwrap();
+        let written = write_pages(sample(), dir.path()).unwrap();
+        let names: Vec<_> = written
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, ["tool.1", "tool-run.1"]);
+
+        let root = fs::read_to_string(&written[0]).unwrap();
+        assert!(
+            root.contains("\n.TH tool 1  \"nblm 1.2.3\" \"nblm manual\"\n"),
+            "{root}"
+        );
+        assert!(root.contains("tool\\-run(1)\nRun it"), "{root}");
+        let run = fs::read_to_string(&written[1]).unwrap();
+        assert!(run.contains("tool\\-run \\- Run it"), "{run}");
+        assert!(run.contains("[default: 1]"), "{run}");
+        assert!(run.contains("TOOL_COUNT"), "{run}");
+    }
+
+    #[test]
+    fn text_that_looks_like_roff_stays_text() {
+        let dir = tempfile::tempdir().unwrap();
+        let written = write_pages(sample(), dir.path()).unwrap();
+        let run = fs::read_to_string(&written[1]).unwrap();
+
+        // A line starting with `.` or `'` would be read as a request, and `\` starts an escape.
+        assert!(run.contains("\n\\&.dots start this line"), "{run}");
+        assert!(run.contains("\n\\*(Aqquotes start this one"), "{run}");
+        assert!(run.contains("a \\\\backslash stays literal"), "{run}");
+        assert!(!run.contains("\n.dots"), "{run}");
+        assert!(!run.contains("\n'quotes"), "{run}");
+    }
+}
@@ -0,0 +1,76 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+use crate::util::persisted::{Persisted, PersistedFile};
+
+const MARKS_FILE: &str = "recent-marks.json";
+
+/// Named high-water marks for `notebooks recent --since-last-run`: the latest notebook creation
+/// time each consumer has seen.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RecentMarks {
+    /// Mark name to RFC 3339 creation time.
+    pub marks: BTreeMap<String, String>,
+}
+
+impl Persisted for RecentMarks {
+    const KIND: &'static str = "high-water mark file";
+    const SCHEMA_VERSION: u32 = 1;
+}
+
+impl RecentMarks {
+    /// `recent-marks.json` under the nblm config directory (`NBLM_CONFIG_DIR` overrides it).
+    pub fn file() -> Result<PersistedFile<Self>> {
+        Ok(PersistedFile::new(default_path()?))
+    }
+
+    /// The mark called `name`, or `None` when it was never set (or no longer parses).
+    pub fn get(&self, name: &str) -> Option<OffsetDateTime> {
+        self.marks
+            .get(name)
+            .and_then(|value| OffsetDateTime::parse(value, &Rfc3339).ok())
+    }
+
+    /// Move the mark called `name` forward to `seen`. A mark is never moved back.
+    pub fn advance(&mut self, name: &str, seen: OffsetDateTime) -> Result<()> {
+        if self.get(name).is_some_and(|mark| mark >= seen) {
+            return Ok(());
+        }
+        self.marks.insert(name.to_string(), seen.format(&Rfc3339)?);
+        Ok(())
+    }
+}
+
+fn default_path() -> Result<PathBuf> {
+    Ok(nblm_core::config_dir()?.join(MARKS_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    #[test]
+    fn marks_only_move_forward_and_are_independent() {
+        let mut marks = RecentMarks::default();
+        assert_eq!(marks.get("slack"), None);
+
+        marks
+            .advance("slack", datetime!(2025-02-01 00:00 UTC))
+            .unwrap();
+        marks
+            .advance("slack", datetime!(2025-01-01 00:00 UTC))
+            .unwrap();
+        marks
+            .advance("email", datetime!(2025-01-15 00:00 UTC))
+            .unwrap();
+
+        assert_eq!(marks.get("slack"), Some(datetime!(2025-02-01 00:00 UTC)));
+        assert_eq!(marks.get("email"), Some(datetime!(2025-01-15 00:00 UTC)));
+        assert_eq!(marks.marks["slack"], "2025-02-01T00:00:00Z");
+    }
+}
@@ -1,5 +1,33 @@
 pub mod auth;
+pub mod batch;
+pub mod bulk;
+pub mod config;
+pub mod curl;
+pub mod deprecation;
+pub mod diff;
+pub mod download;
+pub mod gcloud;
+pub mod help;
+pub mod interrupt;
+pub mod inventory;
 pub mod io;
+pub mod man;
+pub mod marks;
 pub mod oauth_bootstrap;
 pub mod oauth_browser;
+pub mod output;
+pub mod parse;
+pub mod payload;
+pub mod persisted;
+pub mod prompt;
+pub mod queue;
+pub mod rate_limit;
+pub mod redact;
+pub mod search;
+pub mod status;
+pub mod stdin_sources;
+pub mod template;
+pub mod timings;
 pub mod validate;
+pub mod wait;
+pub mod wrap;
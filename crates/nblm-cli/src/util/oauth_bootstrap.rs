@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{anyhow, Result};
 use reqwest::Client;
@@ -8,13 +9,17 @@ use tokio::task::block_in_place;
 
 use crate::args::GlobalArgs;
 use crate::util::oauth_browser::OAuthBrowserFlow;
+use crate::util::prompt;
 
 use nblm_core::auth::oauth::{
-    FileRefreshTokenStore, OAuthClientConfig, OAuthConfig, OAuthFlow, RefreshTokenProvider,
-    SerializedTokens, TokenStoreKey,
+    client_fingerprint, BootstrapTurn, FileRefreshTokenStore, OAuthClientConfig, OAuthConfig,
+    OAuthFlow, RefreshTokenProvider, SerializedTokens, TokenStoreKey,
 };
 use nblm_core::auth::TokenProvider;
-use nblm_core::RefreshTokenStore;
+use nblm_core::{RefreshTokenStore, RetryConfig};
+
+/// How long to wait for another process's browser flow; the same as the flow's own timeout.
+const BOOTSTRAP_WAIT: Duration = Duration::from_secs(600);
 
 pub struct OAuthBootstrapper {
     store: Arc<FileRefreshTokenStore>,
@@ -59,7 +64,7 @@ impl OAuthBootstrapper {
         let store_key = Self::build_store_key(args, project_number.clone());
         let client_config = OAuthClientConfig::from_env().map_err(|e| {
             anyhow!(
-                "OAuth configuration error: {}\nSee guide: https://github.com/K-dash/nblm-rs/blob/main/docs/guides/oauth2-authentication.md",
+                "OAuth configuration error: {}\nSet NBLM_OAUTH_CLIENT_ID, and give the client secret with NBLM_OAUTH_CLIENT_SECRET or NBLM_OAUTH_CLIENT_SECRET_FILE (a file holding the secret).\nSee guide: https://github.com/K-dash/nblm-rs/blob/main/docs/guides/oauth2-authentication.md",
                 e
             )
         })?;
@@ -73,14 +78,17 @@ impl OAuthBootstrapper {
                 Arc::clone(&http_client),
                 &project_number,
                 &store_key,
+                args.is_interactive(),
             )?;
         }
 
         let flow = OAuthFlow::new(oauth_config, Arc::clone(&http_client))
             .map_err(|e| anyhow!("failed to create OAuth flow: {}", e))?;
-        let provider: Arc<RefreshTokenProvider<FileRefreshTokenStore>> = Arc::new(
-            RefreshTokenProvider::new(flow, Arc::clone(&self.store), store_key),
-        );
+        let mut provider = RefreshTokenProvider::new(flow, Arc::clone(&self.store), store_key);
+        if args.no_retry {
+            provider = provider.with_retry_config(RetryConfig::disabled());
+        }
+        let provider: Arc<RefreshTokenProvider<FileRefreshTokenStore>> = Arc::new(provider);
 
         if !skip_bootstrap {
             block_in_place(|| {
@@ -99,23 +107,68 @@ impl OAuthBootstrapper {
         Ok(provider as Arc<dyn TokenProvider>)
     }
 
+    /// Run the browser flow unless a usable refresh token is stored. A token issued to another
+    /// OAuth client is replaced when `interactive`, and reported otherwise; with no token at all,
+    /// a run that is not `interactive` fails instead of opening the browser.
     fn ensure_tokens_blocking(
         &self,
         config: &OAuthConfig,
         http_client: Arc<Client>,
         project_number: &str,
         store_key: &TokenStoreKey,
+        interactive: bool,
     ) -> Result<()> {
         block_in_place(|| {
             let handle = Handle::try_current()
                 .map_err(|_| anyhow!("user-oauth authentication requires a Tokio runtime"))?;
             let config = config.clone();
             handle.block_on(async {
-                if self.store.load(store_key).await?.is_some() {
-                    return Ok(());
+                if let Some(stored) = self.store.load(store_key).await? {
+                    match stored.ensure_client(&config.client_id) {
+                        Ok(()) => return Ok(()),
+                        Err(err) if !interactive => return Err(err.into()),
+                        Err(_) => eprintln!(
+                            "Stored tokens for project {project_number} were issued to a different OAuth client; signing in again."
+                        ),
+                    }
+                } else if !interactive {
+                    return Err(prompt::interaction_required(
+                        format!(
+                            "open a browser to sign in with user-oauth (no tokens are stored for project {project_number})"
+                        ),
+                        "run the command once in a terminal to sign in and store a refresh token, or use --auth env with NBLM_ACCESS_TOKEN",
+                    ));
+                }
+
+                // Concurrent commands share one browser flow: the first takes the lock, the
+                // others wait for its tokens.
+                let client_id = config.client_id.clone();
+                let turn = self
+                    .store
+                    .take_bootstrap_turn(
+                        store_key,
+                        |tokens| tokens.ensure_client(&client_id).is_ok(),
+                        BOOTSTRAP_WAIT,
+                        |owner| {
+                            eprintln!(
+                                "Another nblm process (pid {}) is completing authentication; waiting for it to finish...",
+                                owner.pid
+                            )
+                        },
+                    )
+                    .await?;
+                match turn {
+                    BootstrapTurn::Stored(_) => Ok(()),
+                    BootstrapTurn::Lead(_lock) => {
+                        self.run_browser_flow(
+                            config,
+                            http_client,
+                            project_number.to_string(),
+                            store_key,
+                        )
+                        .await
+                    }
                 }
-                self.run_browser_flow(config, http_client, project_number.to_string(), store_key)
-                    .await
             })
         })
     }
@@ -127,6 +180,7 @@ impl OAuthBootstrapper {
         project_number: String,
         store_key: &TokenStoreKey,
     ) -> Result<()> {
+        let fingerprint = client_fingerprint(&config.client_id);
         let browser_flow = OAuthBrowserFlow::new(config, http_client);
         let tokens = browser_flow.run().await?;
         let refresh_token = tokens
@@ -144,6 +198,7 @@ impl OAuthBootstrapper {
             expires_at: Some(tokens.expires_at),
             token_type: tokens.token_type,
             updated_at: OffsetDateTime::now_utc(),
+            client_fingerprint: Some(fingerprint),
         };
 
         self.store.save(store_key, &serialized).await?;
@@ -169,6 +224,7 @@ pub(crate) fn is_bootstrap_disabled() -> bool {
 mod tests {
     use super::*;
     use crate::args::{AuthMethod, GlobalArgs, ProfileArg};
+    use nblm_core::auth::oauth::LockAttempt;
     use nblm_core::auth::ProviderKind;
     use serial_test::serial;
     use tokio::runtime::Runtime;
@@ -206,10 +262,22 @@ mod tests {
             auth: AuthMethod::UserOauth,
             token: None,
             json: false,
+            redact: None,
+            lang: None,
             debug_http: false,
+            quiet: false,
+            timings: false,
+            show_curl: false,
             timeout: None,
+            verify_token_project: false,
+            retry_min_delay: None,
+            no_retry: false,
+            non_interactive: false,
+            interactive: false,
+            insecure_skip_tls_verify: false,
             env_token: None,
             base_url: None,
+            api_version: None,
         }
     }
 
@@ -244,7 +312,9 @@ mod tests {
             .bootstrap_provider(&make_args())
             .err()
             .expect("expected error");
-        assert!(format!("{}", err).contains("OAuth configuration error"));
+        let message = format!("{}", err);
+        assert!(message.contains("OAuth configuration error"));
+        assert!(message.contains("NBLM_OAUTH_CLIENT_SECRET or NBLM_OAUTH_CLIENT_SECRET_FILE"));
     }
 
     #[test]
@@ -262,4 +332,212 @@ mod tests {
         let provider = bootstrapper.bootstrap_provider(&make_args()).unwrap();
         assert_eq!(provider.kind(), ProviderKind::UserOauth);
     }
+
+    /// Point the credentials store at a temp dir holding an entry for `make_args()` issued to
+    /// `issued_to`.
+    fn store_entry_for_client(runtime: &Runtime, config_dir: &std::path::Path, issued_to: &str) {
+        std::env::set_var("NBLM_CONFIG_DIR", config_dir);
+        let store = FileRefreshTokenStore::new().unwrap();
+        let key = OAuthBootstrapper::build_store_key(&make_args(), "123456".to_string());
+        let tokens = SerializedTokens {
+            refresh_token: "personal-refresh-token".to_string(),
+            scopes: vec![],
+            expires_at: None,
+            token_type: "Bearer".to_string(),
+            updated_at: OffsetDateTime::now_utc(),
+            client_fingerprint: Some(client_fingerprint(issued_to)),
+        };
+        runtime.block_on(store.save(&key, &tokens)).unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn client_mismatch_with_bootstrap_disabled_fails_on_first_token() {
+        let runtime = Runtime::new().expect("runtime for oauth bootstrap test");
+        let _guard = runtime.enter();
+        let _guard_dir = EnvGuard::new("NBLM_CONFIG_DIR");
+        let _guard_id = EnvGuard::new("NBLM_OAUTH_CLIENT_ID");
+        let _guard_disable = EnvGuard::new("NBLM_OAUTH_DISABLE_BOOTSTRAP");
+        let temp = tempfile::tempdir().unwrap();
+        store_entry_for_client(&runtime, temp.path(), "personal-test-client");
+        std::env::set_var("NBLM_OAUTH_CLIENT_ID", "org-client");
+        std::env::set_var("NBLM_OAUTH_DISABLE_BOOTSTRAP", "1");
+
+        let provider = OAuthBootstrapper::new()
+            .unwrap()
+            .bootstrap_provider(&make_args())
+            .unwrap();
+        let err = runtime.block_on(provider.access_token()).unwrap_err();
+
+        let message = err.to_string();
+        assert!(message.contains("different OAuth client"), "{message}");
+        assert!(
+            message.contains("NBLM_OAUTH_DISABLE_BOOTSTRAP"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn client_mismatch_without_terminal_is_reported_instead_of_reauthorizing() {
+        let runtime = Runtime::new().expect("runtime for oauth bootstrap test");
+        let _guard = runtime.enter();
+        let _guard_dir = EnvGuard::new("NBLM_CONFIG_DIR");
+        let temp = tempfile::tempdir().unwrap();
+        store_entry_for_client(&runtime, temp.path(), "personal-test-client");
+
+        let bootstrapper = OAuthBootstrapper::new().unwrap();
+        let config = OAuthClientConfig {
+            client_id: "org-client".to_string(),
+            client_secret: None,
+            redirect_uri: OAuthConfig::DEFAULT_REDIRECT_URI.to_string(),
+            audience: None,
+            auth_endpoint: OAuthConfig::AUTH_ENDPOINT.to_string(),
+            token_endpoint: OAuthConfig::TOKEN_ENDPOINT.to_string(),
+        }
+        .into_oauth_config();
+        let key = OAuthBootstrapper::build_store_key(&make_args(), "123456".to_string());
+        let err = bootstrapper
+            .ensure_tokens_blocking(
+                &config,
+                OAuthBootstrapper::create_http_client().unwrap(),
+                "123456",
+                &key,
+                false,
+            )
+            .unwrap_err();
+
+        assert!(err.to_string().contains("different OAuth client"), "{err}");
+        let stored = runtime
+            .block_on(bootstrapper.store.load(&key))
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.refresh_token, "personal-refresh-token");
+    }
+
+    #[test]
+    #[serial]
+    fn missing_tokens_without_terminal_fail_instead_of_opening_a_browser() {
+        let runtime = Runtime::new().expect("runtime for oauth bootstrap test");
+        let _guard = runtime.enter();
+        let _guard_dir = EnvGuard::new("NBLM_CONFIG_DIR");
+        let temp = tempfile::tempdir().unwrap();
+        std::env::set_var("NBLM_CONFIG_DIR", temp.path());
+
+        let config = OAuthClientConfig {
+            client_id: "org-client".to_string(),
+            client_secret: None,
+            redirect_uri: OAuthConfig::DEFAULT_REDIRECT_URI.to_string(),
+            audience: None,
+            auth_endpoint: OAuthConfig::AUTH_ENDPOINT.to_string(),
+            token_endpoint: OAuthConfig::TOKEN_ENDPOINT.to_string(),
+        }
+        .into_oauth_config();
+        let key = OAuthBootstrapper::build_store_key(&make_args(), "123456".to_string());
+        let bootstrapper = OAuthBootstrapper::new().unwrap();
+        let err = bootstrapper
+            .ensure_tokens_blocking(
+                &config,
+                OAuthBootstrapper::create_http_client().unwrap(),
+                "123456",
+                &key,
+                false,
+            )
+            .unwrap_err();
+
+        assert_eq!(
+            prompt::exit_code(&err),
+            Some(prompt::INTERACTION_REQUIRED_EXIT_CODE)
+        );
+        assert_eq!(
+            err.to_string(),
+            "cannot open a browser to sign in with user-oauth (no tokens are stored for project 123456) when running non-interactively; run the command once in a terminal to sign in and store a refresh token, or use --auth env with NBLM_ACCESS_TOKEN"
+        );
+        // Nobody was left holding the bootstrap lock.
+        assert!(!bootstrapper.store.bootstrap_lock_path().exists());
+    }
+
+    #[test]
+    #[serial]
+    fn matching_client_needs_no_browser_flow() {
+        let runtime = Runtime::new().expect("runtime for oauth bootstrap test");
+        let _guard = runtime.enter();
+        let _guard_dir = EnvGuard::new("NBLM_CONFIG_DIR");
+        let temp = tempfile::tempdir().unwrap();
+        store_entry_for_client(&runtime, temp.path(), "org-client");
+
+        let config = OAuthClientConfig {
+            client_id: "org-client".to_string(),
+            client_secret: None,
+            redirect_uri: OAuthConfig::DEFAULT_REDIRECT_URI.to_string(),
+            audience: None,
+            auth_endpoint: OAuthConfig::AUTH_ENDPOINT.to_string(),
+            token_endpoint: OAuthConfig::TOKEN_ENDPOINT.to_string(),
+        }
+        .into_oauth_config();
+        let key = OAuthBootstrapper::build_store_key(&make_args(), "123456".to_string());
+        OAuthBootstrapper::new()
+            .unwrap()
+            .ensure_tokens_blocking(
+                &config,
+                OAuthBootstrapper::create_http_client().unwrap(),
+                "123456",
+                &key,
+                true,
+            )
+            .unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn waits_for_another_process_instead_of_opening_a_browser() {
+        let runtime = Runtime::new().expect("runtime for oauth bootstrap test");
+        let _guard = runtime.enter();
+        let _guard_dir = EnvGuard::new("NBLM_CONFIG_DIR");
+        let temp = tempfile::tempdir().unwrap();
+        std::env::set_var("NBLM_CONFIG_DIR", temp.path());
+
+        // Stands in for the process running the browser flow.
+        let other = FileRefreshTokenStore::new().unwrap();
+        let LockAttempt::Acquired(lock) = runtime.block_on(other.try_lock_bootstrap()).unwrap()
+        else {
+            panic!("expected to take the bootstrap lock");
+        };
+        let finisher = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(300));
+            let runtime = Runtime::new().unwrap();
+            store_entry_for_client(&runtime, temp.path(), "org-client");
+            drop(lock);
+            temp
+        });
+
+        let config = OAuthClientConfig {
+            client_id: "org-client".to_string(),
+            client_secret: None,
+            redirect_uri: OAuthConfig::DEFAULT_REDIRECT_URI.to_string(),
+            audience: None,
+            auth_endpoint: OAuthConfig::AUTH_ENDPOINT.to_string(),
+            token_endpoint: OAuthConfig::TOKEN_ENDPOINT.to_string(),
+        }
+        .into_oauth_config();
+        let key = OAuthBootstrapper::build_store_key(&make_args(), "123456".to_string());
+        let bootstrapper = OAuthBootstrapper::new().unwrap();
+        // A browser flow would hang here until its ten-minute timeout.
+        bootstrapper
+            .ensure_tokens_blocking(
+                &config,
+                OAuthBootstrapper::create_http_client().unwrap(),
+                "123456",
+                &key,
+                true,
+            )
+            .unwrap();
+
+        let _temp = finisher.join().unwrap();
+        let stored = runtime
+            .block_on(bootstrapper.store.load(&key))
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.refresh_token, "personal-refresh-token");
+    }
 }
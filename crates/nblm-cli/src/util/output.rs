@@ -0,0 +1,315 @@
+use std::io::{self, Write};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use anyhow::{bail, Result};
+use clap::ValueEnum;
+use serde_json::Value;
+
+/// Where command output goes.
+///
+/// Commands write results to the stdout-like stream and progress, warnings and notes to the
+/// stderr-like stream, so `--json` consumers only ever see the payload on stdout.
+pub trait OutputSink: Send + Sync {
+    /// Write a line to the stdout-like stream.
+    fn out(&self, line: &str);
+
+    /// Write a line to the stderr-like stream.
+    fn err(&self, line: &str);
+
+    /// Write bytes to the stdout-like stream as-is.
+    fn out_raw(&self, bytes: &[u8]) -> io::Result<()>;
+
+    /// Pretty-print `value` as a single JSON document on the stdout-like stream.
+    fn json(&self, value: &Value) {
+        self.out(&serde_json::to_string_pretty(value).unwrap());
+    }
+}
+
+/// `--output` for commands whose results feed other commands.
+///
+/// `ids` and `titles` print one value per line on stdout and nothing else, so the output can be
+/// piped into commands such as `sources delete --stdin`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputMode {
+    /// Human-readable details, or the API payload with --json
+    #[default]
+    Details,
+    /// Full resource names, one per line
+    Ids,
+    /// Titles, one per line
+    Titles,
+}
+
+impl OutputMode {
+    /// `ids` and `titles` already define the stdout format, so they cannot be mixed with `--json`.
+    pub fn check_json(self, json_mode: bool) -> Result<()> {
+        if json_mode && self != Self::Details {
+            bail!(
+                "--output {} cannot be combined with --json",
+                self.to_possible_value()
+                    .expect("no variant is skipped")
+                    .get_name()
+            );
+        }
+        Ok(())
+    }
+}
+
+/// `--fields` for list commands: how much of each resource the API should send back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldSelection {
+    /// The command's preset mask, enough for its text and `--output` modes
+    Minimal,
+    /// The whole resource (no mask)
+    Full,
+    /// A partial response mask passed through as the `fields` query parameter
+    Mask(String),
+}
+
+impl FieldSelection {
+    /// Without `--fields`, text output asks for the minimal fields and `--json` for everything,
+    /// so JSON consumers keep seeing the full payload.
+    pub fn resolve(fields: Option<Self>, json_mode: bool) -> Self {
+        fields.unwrap_or(if json_mode { Self::Full } else { Self::Minimal })
+    }
+
+    /// The `fields` value to send, given the command's preset minimal mask.
+    pub fn mask<'a>(&'a self, minimal: &'a str) -> Option<&'a str> {
+        match self {
+            Self::Minimal => Some(minimal),
+            Self::Full => None,
+            Self::Mask(mask) => Some(mask),
+        }
+    }
+}
+
+/// Held for every write to the real stdout and stderr, so when both go to the same pipe a
+/// stderr line from another task never lands in the middle of a JSON document.
+static STREAMS: Mutex<()> = Mutex::new(());
+
+pub(super) fn lock_streams() -> MutexGuard<'static, ()> {
+    STREAMS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// `line` without trailing line breaks, so each line is written with exactly one.
+pub fn trim_line_end(line: &str) -> &str {
+    line.trim_end_matches(['\n', '\r'])
+}
+
+/// Flush stdout. `std::process::exit` skips destructors, so every exit path calls this first.
+pub fn flush_stdout() {
+    let _ = io::stdout().lock().flush();
+}
+
+/// Write `line` and one newline with a single call and flush, so the line reaches a pipe whole.
+fn write_line(stream: &mut impl Write, line: &str) -> io::Result<()> {
+    let line = trim_line_end(line);
+    let mut buf = Vec::with_capacity(line.len() + 1);
+    buf.extend_from_slice(line.as_bytes());
+    buf.push(b'\n');
+    stream.write_all(&buf)?;
+    stream.flush()
+}
+
+/// The process's real stdout and stderr.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdStreams;
+
+impl OutputSink for StdStreams {
+    fn out(&self, line: &str) {
+        let _streams = lock_streams();
+        super::status::clear();
+        // A closed pipe (`nblm ... | head`) is not worth a panic; the reader has what it wanted.
+        let _ = write_line(&mut io::stdout().lock(), line);
+    }
+
+    fn err(&self, line: &str) {
+        let _streams = lock_streams();
+        super::status::clear();
+        let _ = write_line(&mut io::stderr().lock(), line);
+    }
+
+    fn out_raw(&self, bytes: &[u8]) -> io::Result<()> {
+        let _streams = lock_streams();
+        super::status::clear();
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(bytes)?;
+        stdout.flush()
+    }
+}
+
+/// Repeated warnings from a bulk run, collapsed: the first `limit` of each kind are printed, the
+/// rest only counted until [`WarningDeduper::summarize`].
+#[derive(Debug)]
+pub struct WarningDeduper {
+    limit: usize,
+    /// Occurrences per kind, in the order each kind first appeared.
+    kinds: Mutex<Vec<(String, usize)>>,
+}
+
+impl Default for WarningDeduper {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_LIMIT)
+    }
+}
+
+impl WarningDeduper {
+    /// Occurrences of each kind printed before the rest are held back.
+    pub const DEFAULT_LIMIT: usize = 3;
+
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            kinds: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Write `line` to stderr unless `limit` warnings of the same `kind` already were. `kind` is
+    /// what makes two warnings "the same", such as the error message without the file name.
+    pub fn warn(&self, out: &dyn OutputSink, kind: &str, line: &str) {
+        let mut kinds = self.kinds.lock().unwrap();
+        let seen = match kinds.iter_mut().find(|(known, _)| known == kind) {
+            Some((_, count)) => count,
+            None => {
+                kinds.push((kind.to_string(), 0));
+                &mut kinds.last_mut().expect("just pushed").1
+            }
+        };
+        *seen += 1;
+        if *seen <= self.limit {
+            out.err(line);
+        }
+    }
+
+    /// Write `…and N more like this: <kind>` to stderr for every kind that was held back.
+    pub fn summarize(&self, out: &dyn OutputSink) {
+        for (kind, count) in self.kinds.lock().unwrap().iter() {
+            if *count > self.limit {
+                out.err(&format!(
+                    "…and {} more like this: {kind}",
+                    count - self.limit
+                ));
+            }
+        }
+    }
+}
+
+/// In-memory sink for asserting on command output without spawning the binary.
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct CapturedOutput {
+    stdout: std::sync::Mutex<Vec<u8>>,
+    stderr: std::sync::Mutex<String>,
+}
+
+#[cfg(test)]
+impl CapturedOutput {
+    pub fn stdout(&self) -> String {
+        String::from_utf8(self.stdout.lock().unwrap().clone()).expect("stdout is UTF-8")
+    }
+
+    pub fn stderr(&self) -> String {
+        self.stderr.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+impl OutputSink for CapturedOutput {
+    fn out(&self, line: &str) {
+        let mut stdout = self.stdout.lock().unwrap();
+        stdout.extend_from_slice(trim_line_end(line).as_bytes());
+        stdout.push(b'\n');
+    }
+
+    fn err(&self, line: &str) {
+        let mut stderr = self.stderr.lock().unwrap();
+        stderr.push_str(trim_line_end(line));
+        stderr.push('\n');
+    }
+
+    fn out_raw(&self, bytes: &[u8]) -> io::Result<()> {
+        self.stdout.lock().unwrap().extend_from_slice(bytes);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captured_output_keeps_streams_apart() {
+        let sink = CapturedOutput::default();
+        sink.out("result");
+        sink.err("progress");
+        sink.json(&serde_json::json!({"ok": true}));
+
+        assert_eq!(sink.stdout(), "result\n{\n  \"ok\": true\n}\n");
+        assert_eq!(sink.stderr(), "progress\n");
+    }
+
+    #[test]
+    fn lines_are_written_with_exactly_one_newline() {
+        let sink = CapturedOutput::default();
+        sink.out("answer\n\n");
+        sink.out("crlf\r\n");
+        sink.out("");
+        sink.err("warning\n");
+
+        assert_eq!(sink.stdout(), "answer\ncrlf\n\n");
+        assert_eq!(sink.stderr(), "warning\n");
+    }
+
+    #[test]
+    fn std_streams_write_each_line_whole() {
+        let mut buf = Vec::new();
+        write_line(&mut buf, "{\n  \"ok\": true\n}\n").unwrap();
+        assert_eq!(buf, b"{\n  \"ok\": true\n}\n");
+    }
+
+    #[test]
+    fn warning_deduper_caps_each_kind_and_counts_the_rest() {
+        let sink = CapturedOutput::default();
+        let warnings = WarningDeduper::new(2);
+        for file in ["a", "b", "c", "d"] {
+            warnings.warn(&sink, "HTTP 400", &format!("{file}: HTTP 400"));
+        }
+        warnings.warn(&sink, "HTTP 500", "e: HTTP 500");
+        warnings.warn(&sink, "skipped", "Skipped f");
+        warnings.warn(&sink, "skipped", "Skipped g");
+        warnings.warn(&sink, "skipped", "Skipped h");
+
+        warnings.summarize(&sink);
+        assert_eq!(
+            sink.stderr(),
+            concat!(
+                "a: HTTP 400\n",
+                "b: HTTP 400\n",
+                "e: HTTP 500\n",
+                "Skipped f\n",
+                "Skipped g\n",
+                "…and 2 more like this: HTTP 400\n",
+                "…and 1 more like this: skipped\n",
+            )
+        );
+    }
+
+    #[test]
+    fn warning_deduper_summary_is_empty_below_the_limit() {
+        let sink = CapturedOutput::default();
+        let warnings = WarningDeduper::default();
+        warnings.warn(&sink, "kind", "once");
+        warnings.summarize(&sink);
+        assert_eq!(sink.stderr(), "once\n");
+    }
+
+    #[test]
+    fn output_mode_rejects_json_for_line_modes() {
+        assert!(OutputMode::Details.check_json(true).is_ok());
+        assert!(OutputMode::Ids.check_json(false).is_ok());
+        assert_eq!(
+            OutputMode::Titles.check_json(true).unwrap_err().to_string(),
+            "--output titles cannot be combined with --json"
+        );
+    }
+}
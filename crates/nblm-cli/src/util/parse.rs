@@ -0,0 +1,594 @@
+use std::ffi::OsStr;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use clap::builder::{PossibleValue, StringValueParser, TypedValueParser};
+use nblm_core::models::enterprise::name::NotebookName;
+use nblm_core::{NotebookId, SourceId, ENDPOINT_LOCATIONS};
+use serde_json::Value;
+use time::format_description::well_known::Rfc3339;
+use time::macros::format_description;
+use time::{Date, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
+
+use crate::util::output::FieldSelection;
+
+const SIZE_FORMS: &str = "expected forms like 500KB, 10MiB";
+const DURATION_FORMS: &str = "expected forms like 30s, 2m, 1h30m";
+const TIME_FORMS: &str = "expected an RFC 3339 timestamp (2025-01-31T09:30:00Z), a date (2025-01-31, midnight local time) or a duration before now (7d, 24h, 2w)";
+
+/// Parse a human-friendly byte size such as `500KB`, `10MiB` or `1.5GB`.
+///
+/// Decimal suffixes (KB, MB, GB, TB) are powers of 1000, binary suffixes (KiB, MiB, GiB, TiB)
+/// powers of 1024. Suffixes are case-insensitive and a bare number is a byte count.
+pub fn parse_size(input: &str) -> Result<u64> {
+    let invalid = || anyhow!("invalid size '{input}': {SIZE_FORMS}");
+    let too_large = || anyhow!("invalid size '{input}': value is too large");
+
+    let trimmed = input.trim();
+    let split = trimmed
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+    let multiplier: u128 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "kb" => 1_000,
+        "mb" => 1_000_000,
+        "gb" => 1_000_000_000,
+        "tb" => 1_000_000_000_000,
+        "kib" => 1 << 10,
+        "mib" => 1 << 20,
+        "gib" => 1 << 30,
+        "tib" => 1 << 40,
+        _ => return Err(invalid()),
+    };
+
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return Err(invalid());
+    }
+    if fraction.contains('.') || fraction.len() > 9 {
+        return Err(invalid());
+    }
+    // Only digits remain, so a parse failure here means the number overflowed.
+    let whole: u128 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().map_err(|_| too_large())?
+    };
+    let scale = 10u128.pow(fraction.len() as u32);
+    let fraction: u128 = if fraction.is_empty() {
+        0
+    } else {
+        fraction.parse().map_err(|_| invalid())?
+    };
+
+    let bytes = whole
+        .checked_mul(multiplier)
+        .and_then(|bytes| bytes.checked_add(fraction * multiplier / scale))
+        .ok_or_else(too_large)?;
+    u64::try_from(bytes).map_err(|_| too_large())
+}
+
+/// Parse a human-friendly duration such as `30s`, `2m` or `1h30m`.
+pub fn parse_duration(input: &str) -> Result<Duration> {
+    humantime::parse_duration(input.trim())
+        .map_err(|_| anyhow!("invalid duration '{input}': {DURATION_FORMS}"))
+}
+
+/// clap value parser for sizes that must be greater than zero.
+pub fn positive_size(input: &str) -> std::result::Result<u64, String> {
+    match parse_size(input) {
+        Ok(0) => Err(format!("invalid size '{input}': must be greater than zero")),
+        Ok(bytes) => Ok(bytes),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// clap value parser for durations that may be zero.
+pub fn duration(input: &str) -> std::result::Result<Duration, String> {
+    parse_duration(input).map_err(|err| err.to_string())
+}
+
+/// clap value parser for durations that must be greater than zero.
+pub fn positive_duration(input: &str) -> std::result::Result<Duration, String> {
+    match parse_duration(input) {
+        Ok(value) if value.is_zero() => Err(format!(
+            "invalid duration '{input}': must be greater than zero"
+        )),
+        Ok(value) => Ok(value),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+/// Parse a point in time given as an RFC 3339 timestamp, a `YYYY-MM-DD` date or a duration.
+///
+/// A date means midnight at `local_offset`, so `2025-01-31` covers the user's whole day rather
+/// than starting at midnight UTC. A duration such as `7d` or `24h` counts back from `now`. The
+/// result is always in UTC.
+pub fn parse_time_or_duration(
+    input: &str,
+    now: OffsetDateTime,
+    local_offset: UtcOffset,
+) -> Result<OffsetDateTime> {
+    let trimmed = input.trim();
+    let invalid = || anyhow!("invalid time '{input}': {TIME_FORMS}");
+    if trimmed.is_empty() {
+        return Err(invalid());
+    }
+    if let Ok(timestamp) = OffsetDateTime::parse(trimmed, &Rfc3339) {
+        return Ok(timestamp.to_offset(UtcOffset::UTC));
+    }
+    if let Ok(date) = Date::parse(trimmed, format_description!("[year]-[month]-[day]")) {
+        return Ok(PrimitiveDateTime::new(date, Time::MIDNIGHT)
+            .assume_offset(local_offset)
+            .to_offset(UtcOffset::UTC));
+    }
+    let ago = humantime::parse_duration(trimmed).map_err(|_| invalid())?;
+    time::Duration::try_from(ago)
+        .ok()
+        .and_then(|ago| now.checked_sub(ago))
+        .map(|time| time.to_offset(UtcOffset::UTC))
+        .ok_or_else(|| anyhow!("invalid time '{input}': duration reaches too far back"))
+}
+
+/// The system's current UTC offset, or UTC when it cannot be determined.
+///
+/// Read through chrono because `time` refuses to look up the offset once other threads are
+/// running, which is always the case under the tokio runtime.
+pub fn local_offset() -> UtcOffset {
+    let seconds = chrono::Local::now().offset().local_minus_utc();
+    UtcOffset::from_whole_seconds(seconds).unwrap_or(UtcOffset::UTC)
+}
+
+/// clap value parser for [`parse_time_or_duration`], relative to the current time and zone.
+pub fn time_or_duration(input: &str) -> std::result::Result<OffsetDateTime, String> {
+    parse_time_or_duration(input, OffsetDateTime::now_utc(), local_offset())
+        .map_err(|err| err.to_string())
+}
+
+/// Longest key accepted by [`fetch_option`].
+const FETCH_OPTION_KEY_MAX: usize = 64;
+
+/// clap value parser for `--web-fetch-option KEY=VALUE`.
+///
+/// Keys are limited to ASCII letters, digits, `_` and `-`, starting with a letter. Values must be
+/// scalars: `true`/`false` and numbers are sent as JSON booleans and numbers, a JSON string
+/// literal (`"123"`) forces a string, and anything else is sent as a plain string.
+pub fn fetch_option(input: &str) -> std::result::Result<(String, Value), String> {
+    let Some((key, raw)) = input.split_once('=') else {
+        return Err(format!(
+            "invalid fetch option '{input}': expected KEY=VALUE"
+        ));
+    };
+    let key = key.trim();
+    let valid_key = key.len() <= FETCH_OPTION_KEY_MAX
+        && key.starts_with(|c: char| c.is_ascii_alphabetic())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid_key {
+        return Err(format!(
+            "invalid fetch option key '{key}': use letters, digits, '_' or '-', starting with a letter"
+        ));
+    }
+    let value = match serde_json::from_str::<Value>(raw.trim()) {
+        Ok(Value::Array(_) | Value::Object(_)) => {
+            return Err(format!(
+                "invalid fetch option '{key}': values must be strings, numbers or booleans"
+            ))
+        }
+        Ok(Value::Null) | Err(_) => Value::String(raw.to_string()),
+        Ok(scalar) => scalar,
+    };
+    Ok((key.to_string(), value))
+}
+
+/// clap value parser for an HTTP method; case-insensitive.
+pub fn http_method(input: &str) -> std::result::Result<reqwest::Method, String> {
+    reqwest::Method::from_bytes(input.trim().to_ascii_uppercase().as_bytes())
+        .map_err(|_| format!("invalid HTTP method '{input}'"))
+}
+
+/// clap value parser for `--query KEY=VALUE`. The value may be empty or contain `=`.
+pub fn query_pair(input: &str) -> std::result::Result<(String, String), String> {
+    match input.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!(
+            "invalid query parameter '{input}': expected KEY=VALUE"
+        )),
+    }
+}
+
+/// clap value parser for `fixtures capture --replace TEXT=PLACEHOLDER`. The placeholder may be empty.
+pub fn redaction(input: &str) -> std::result::Result<(String, String), String> {
+    match input.split_once('=') {
+        Some((text, placeholder)) if !text.is_empty() => {
+            Ok((text.to_string(), placeholder.to_string()))
+        }
+        _ => Err(format!(
+            "invalid redaction '{input}': expected TEXT=PLACEHOLDER"
+        )),
+    }
+}
+
+/// clap value parser for an `http` or `https` URL.
+pub fn web_url(input: &str) -> std::result::Result<url::Url, String> {
+    crate::util::validate::validate_url(input).map_err(|err| err.to_string())?;
+    url::Url::parse(input).map_err(|err| err.to_string())
+}
+
+/// clap value parser for a W3C `traceparent` header value.
+pub fn traceparent(input: &str) -> std::result::Result<nblm_core::TraceContext, String> {
+    nblm_core::TraceContext::parse(input).map_err(|err| err.to_string())
+}
+
+/// clap value parser for `--lang`: a BCP 47 language tag such as `fr` or `pt-BR`.
+pub fn language_tag(input: &str) -> std::result::Result<String, String> {
+    nblm_core::parse_language_tag(input).map_err(|err| err.to_string())
+}
+
+/// clap value parser for `--api-version`: `v1alpha` or `v1beta`.
+pub fn api_version(input: &str) -> std::result::Result<nblm_core::ApiVersion, String> {
+    nblm_core::ApiVersion::parse(input).map_err(|err| err.to_string())
+}
+
+/// clap value parser for `--emoji`: a single emoji, or a shortcode such as `:rocket:` turned into
+/// its emoji.
+pub fn emoji(input: &str) -> std::result::Result<String, String> {
+    nblm_core::normalize_emoji(input).map_err(|err| err.to_string())
+}
+
+/// clap value parser for `--since-last-run`: a mark name of letters, digits, `-`, `_` and `.`.
+pub fn mark_name(input: &str) -> std::result::Result<String, String> {
+    let valid = !input.is_empty()
+        && input
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        return Err(format!(
+            "invalid mark name '{input}': use letters, digits, '-', '_' and '.'"
+        ));
+    }
+    Ok(input.to_string())
+}
+
+/// Notebook resource names given where an ID was expected, for [`check_notebook_names`].
+static NOTEBOOK_NAMES: Mutex<Vec<NotebookName>> = Mutex::new(Vec::new());
+
+/// clap value parser for notebook IDs. A full notebook resource name is cut down to its ID; the
+/// name is kept so [`check_notebook_names`] can compare it with the configured project and
+/// location, which are not known yet while arguments are parsed.
+pub fn notebook_id(input: &str) -> std::result::Result<NotebookId, String> {
+    if let Ok(name) = NotebookName::parse(input) {
+        let id = NotebookId::from(name.notebook_id());
+        NOTEBOOK_NAMES.lock().unwrap().push(name);
+        return Ok(id);
+    }
+    NotebookId::parse(input).map_err(|err| err.to_string())
+}
+
+/// Fail if a notebook resource name passed as an ID belongs to a project or location other
+/// than `parent`, instead of quietly using its ID there.
+pub fn check_notebook_names(parent: &str) -> Result<()> {
+    for name in NOTEBOOK_NAMES.lock().unwrap().drain(..) {
+        NotebookId::from(name.to_string()).resolve(parent)?;
+    }
+    Ok(())
+}
+
+pub fn source_id(input: &str) -> std::result::Result<SourceId, String> {
+    SourceId::parse(input).map_err(|err| err.to_string())
+}
+
+/// clap value parser for `--fields`: `minimal`, `full` (case-insensitive) or a custom mask.
+pub fn field_selection(input: &str) -> std::result::Result<FieldSelection, String> {
+    let trimmed = input.trim();
+    if trimmed.eq_ignore_ascii_case("minimal") {
+        Ok(FieldSelection::Minimal)
+    } else if trimmed.eq_ignore_ascii_case("full") {
+        Ok(FieldSelection::Full)
+    } else if trimmed.is_empty() || trimmed.chars().any(char::is_whitespace) {
+        Err(format!(
+            "invalid field mask '{input}': expected minimal, full or a mask such as 'notebooks(title)'"
+        ))
+    } else {
+        Ok(FieldSelection::Mask(trimmed.to_string()))
+    }
+}
+
+/// clap value parser for `--endpoint-location`.
+///
+/// Lists the supported endpoint locations in `--help` but accepts any string: a bad
+/// `NBLM_ENDPOINT_LOCATION` must still let `nblm doctor` run and explain it, and API commands
+/// reject it with the same explanation when they build the client.
+#[derive(Clone, Copy, Debug)]
+pub struct EndpointLocation;
+
+impl TypedValueParser for EndpointLocation {
+    type Value = String;
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &OsStr,
+    ) -> std::result::Result<String, clap::Error> {
+        StringValueParser::new().parse_ref(cmd, arg, value)
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        Some(Box::new(
+            ENDPOINT_LOCATIONS.iter().copied().map(PossibleValue::new),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("0", 0)]
+    #[case("1024", 1024)]
+    #[case("512B", 512)]
+    #[case("500KB", 500_000)]
+    #[case("500kb", 500_000)]
+    #[case("10MB", 10_000_000)]
+    #[case("10 MB", 10_000_000)]
+    #[case("2GB", 2_000_000_000)]
+    #[case("1TB", 1_000_000_000_000)]
+    #[case("1KiB", 1024)]
+    #[case("10MiB", 10 * 1024 * 1024)]
+    #[case("10mib", 10 * 1024 * 1024)]
+    #[case("1.5GiB", 1536 * 1024 * 1024)]
+    #[case("1.5MB", 1_500_000)]
+    #[case(".5KiB", 512)]
+    #[case("2TiB", 2 << 40)]
+    #[case("  64KB  ", 64_000)]
+    fn parse_size_accepts(#[case] input: &str, #[case] expected: u64) {
+        assert_eq!(parse_size(input).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("MB")]
+    #[case("10 megs")]
+    #[case("-5MB")]
+    #[case("1.2.3MB")]
+    #[case("10M")]
+    #[case("ten MB")]
+    fn parse_size_rejects(#[case] input: &str) {
+        let err = parse_size(input).unwrap_err().to_string();
+        assert_eq!(err, format!("invalid size '{input}': {SIZE_FORMS}"));
+    }
+
+    #[rstest]
+    #[case("18446744073709551616")]
+    #[case("20000000TiB")]
+    #[case("99999999999999999999999999999999999999999")]
+    fn parse_size_reports_overflow(#[case] input: &str) {
+        let err = parse_size(input).unwrap_err().to_string();
+        assert_eq!(err, format!("invalid size '{input}': value is too large"));
+    }
+
+    #[test]
+    fn parse_size_max_u64() {
+        assert_eq!(parse_size("18446744073709551615").unwrap(), u64::MAX);
+    }
+
+    #[rstest]
+    #[case("30s", Duration::from_secs(30))]
+    #[case("2m", Duration::from_secs(120))]
+    #[case("1h30m", Duration::from_secs(5400))]
+    #[case("250ms", Duration::from_millis(250))]
+    fn parse_duration_accepts(#[case] input: &str, #[case] expected: Duration) {
+        assert_eq!(parse_duration(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn parse_duration_rejects_with_expected_forms() {
+        let err = parse_duration("5 parsecs").unwrap_err().to_string();
+        assert_eq!(
+            err,
+            format!("invalid duration '5 parsecs': {DURATION_FORMS}")
+        );
+    }
+
+    #[test]
+    fn web_url_accepts_only_http_schemes() {
+        assert_eq!(
+            web_url("https://example.com/a.pdf").unwrap().path(),
+            "/a.pdf"
+        );
+        assert!(web_url("ftp://example.com/a.pdf")
+            .unwrap_err()
+            .contains("unsupported URL scheme"));
+        assert!(web_url("not a url").unwrap_err().contains("invalid URL"));
+    }
+
+    #[test]
+    fn positive_parsers_reject_zero() {
+        assert!(positive_size("0KB")
+            .unwrap_err()
+            .contains("greater than zero"));
+        assert!(positive_duration("0s")
+            .unwrap_err()
+            .contains("greater than zero"));
+        assert_eq!(duration("0s").unwrap(), Duration::ZERO);
+    }
+
+    #[rstest]
+    #[case(
+        "userAgent=Mozilla/5.0 (X11)",
+        "userAgent",
+        Value::from("Mozilla/5.0 (X11)")
+    )]
+    #[case("waitForRender=true", "waitForRender", Value::from(true))]
+    #[case("render_timeout_ms=1500", "render_timeout_ms", Value::from(1500))]
+    #[case("scale=0.5", "scale", Value::from(0.5))]
+    #[case("label=\"123\"", "label", Value::from("123"))]
+    #[case("header=a=b", "header", Value::from("a=b"))]
+    #[case("empty=", "empty", Value::from(""))]
+    #[case("nothing=null", "nothing", Value::from("null"))]
+    fn fetch_option_accepts(#[case] input: &str, #[case] key: &str, #[case] value: Value) {
+        assert_eq!(fetch_option(input).unwrap(), (key.to_string(), value));
+    }
+
+    #[rstest]
+    #[case("waitForRender", "expected KEY=VALUE")]
+    #[case("=true", "invalid fetch option key ''")]
+    #[case("1st=true", "invalid fetch option key '1st'")]
+    #[case("user agent=x", "invalid fetch option key 'user agent'")]
+    #[case("a.b=x", "invalid fetch option key 'a.b'")]
+    #[case("headers={\"a\":1}", "values must be strings, numbers or booleans")]
+    #[case("list=[1,2]", "values must be strings, numbers or booleans")]
+    fn fetch_option_rejects(#[case] input: &str, #[case] expected: &str) {
+        let err = fetch_option(input).unwrap_err();
+        assert!(err.contains(expected), "{err}");
+    }
+
+    #[test]
+    fn http_method_is_case_insensitive() {
+        assert_eq!(http_method("patch").unwrap(), reqwest::Method::PATCH);
+        assert!(http_method("NOT A METHOD").is_err());
+    }
+
+    #[rstest]
+    #[case("pageSize=5", ("pageSize", "5"))]
+    #[case("filter=title=a b", ("filter", "title=a b"))]
+    #[case("pageToken=", ("pageToken", ""))]
+    fn query_pair_splits_on_first_equals(#[case] input: &str, #[case] expected: (&str, &str)) {
+        let (key, value) = query_pair(input).unwrap();
+        assert_eq!((key.as_str(), value.as_str()), expected);
+    }
+
+    #[rstest]
+    #[case("pageSize")]
+    #[case("=5")]
+    fn query_pair_rejects_missing_key(#[case] input: &str) {
+        assert!(query_pair(input).is_err());
+    }
+
+    #[rstest]
+    #[case("minimal", FieldSelection::Minimal)]
+    #[case("FULL", FieldSelection::Full)]
+    #[case(" notebooks(title),nextPageToken ", FieldSelection::Mask("notebooks(title),nextPageToken".into()))]
+    fn field_selection_accepts_presets_and_masks(
+        #[case] input: &str,
+        #[case] expected: FieldSelection,
+    ) {
+        assert_eq!(field_selection(input).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("title, name")]
+    fn field_selection_rejects_blank_or_spaced_masks(#[case] input: &str) {
+        assert!(field_selection(input).is_err());
+    }
+
+    const NOW: OffsetDateTime = time::macros::datetime!(2025-03-10 12:00 UTC);
+
+    fn parse_at(input: &str, offset: UtcOffset) -> Result<OffsetDateTime> {
+        parse_time_or_duration(input, NOW, offset)
+    }
+
+    #[rstest]
+    #[case("2025-01-31T09:30:00Z", time::macros::datetime!(2025-01-31 09:30 UTC))]
+    #[case("2025-01-31T09:30:00+09:00", time::macros::datetime!(2025-01-31 00:30 UTC))]
+    #[case("2025-01-31T23:30:00-05:00", time::macros::datetime!(2025-02-01 04:30 UTC))]
+    #[case(" 2025-01-31T09:30:00.250Z ", time::macros::datetime!(2025-01-31 09:30:00.25 UTC))]
+    fn timestamps_convert_to_utc(#[case] input: &str, #[case] expected: OffsetDateTime) {
+        let parsed = parse_at(input, time::macros::offset!(+9)).unwrap();
+        assert_eq!(parsed, expected);
+        assert_eq!(parsed.offset(), UtcOffset::UTC);
+    }
+
+    #[rstest]
+    #[case("2025-01-31", time::macros::offset!(UTC), time::macros::datetime!(2025-01-31 00:00 UTC))]
+    #[case("2025-01-31", time::macros::offset!(+9), time::macros::datetime!(2025-01-30 15:00 UTC))]
+    #[case("2025-01-31", time::macros::offset!(-5), time::macros::datetime!(2025-01-31 05:00 UTC))]
+    #[case("2025-01-01", time::macros::offset!(+14), time::macros::datetime!(2024-12-31 10:00 UTC))]
+    #[case("2025-01-31", time::macros::offset!(+5:30), time::macros::datetime!(2025-01-30 18:30 UTC))]
+    fn dates_mean_local_midnight(
+        #[case] input: &str,
+        #[case] offset: UtcOffset,
+        #[case] expected: OffsetDateTime,
+    ) {
+        assert_eq!(parse_at(input, offset).unwrap(), expected);
+    }
+
+    /// US daylight saving time started on 2025-03-09. The date is resolved with the offset
+    /// passed in, so the same input lands an hour apart under standard and daylight time.
+    #[rstest]
+    #[case("2025-03-08", time::macros::offset!(-8), time::macros::datetime!(2025-03-08 08:00 UTC))]
+    #[case("2025-03-09", time::macros::offset!(-8), time::macros::datetime!(2025-03-09 08:00 UTC))]
+    #[case("2025-03-09", time::macros::offset!(-7), time::macros::datetime!(2025-03-09 07:00 UTC))]
+    #[case("2025-03-10", time::macros::offset!(-7), time::macros::datetime!(2025-03-10 07:00 UTC))]
+    fn dates_around_a_dst_change_use_the_given_offset(
+        #[case] input: &str,
+        #[case] offset: UtcOffset,
+        #[case] expected: OffsetDateTime,
+    ) {
+        assert_eq!(parse_at(input, offset).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("7d", time::macros::datetime!(2025-03-03 12:00 UTC))]
+    #[case("24h", time::macros::datetime!(2025-03-09 12:00 UTC))]
+    #[case("2w", time::macros::datetime!(2025-02-24 12:00 UTC))]
+    #[case("1h30m", time::macros::datetime!(2025-03-10 10:30 UTC))]
+    #[case("0s", NOW)]
+    fn durations_count_back_from_now(#[case] input: &str, #[case] expected: OffsetDateTime) {
+        // The local offset does not affect durations.
+        assert_eq!(
+            parse_at(input, time::macros::offset!(-7)).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn leap_days() {
+        assert_eq!(
+            parse_at("2024-02-29", UtcOffset::UTC).unwrap(),
+            time::macros::datetime!(2024-02-29 00:00 UTC)
+        );
+        assert!(parse_at("2025-02-29", UtcOffset::UTC).is_err());
+        let after_leap_day = time::macros::datetime!(2024-03-01 06:00 UTC);
+        assert_eq!(
+            parse_time_or_duration("1d", after_leap_day, UtcOffset::UTC).unwrap(),
+            time::macros::datetime!(2024-02-29 06:00 UTC)
+        );
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("   ")]
+    #[case("yesterday")]
+    #[case("7 dayz")]
+    #[case("-7d")]
+    #[case("2025-13-01")]
+    #[case("2025-01-32")]
+    #[case("2025/01/31")]
+    #[case("31-01-2025")]
+    #[case("2025-01-31T25:00:00Z")]
+    #[case("2025-01-31T09:30:00")]
+    fn garbage_lists_every_accepted_form(#[case] input: &str) {
+        let message = parse_at(input, UtcOffset::UTC).unwrap_err().to_string();
+        assert_eq!(message, format!("invalid time '{input}': {TIME_FORMS}"));
+        assert!(message.contains("RFC 3339"));
+        assert!(message.contains("2025-01-31, midnight local time"));
+        assert!(message.contains("7d, 24h, 2w"));
+    }
+
+    #[test]
+    fn durations_past_the_supported_range_are_rejected() {
+        let err = parse_at("20000y", UtcOffset::UTC).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid time '20000y': duration reaches too far back"
+        );
+    }
+}
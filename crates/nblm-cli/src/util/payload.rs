@@ -0,0 +1,658 @@
+//! Payloads printed by `--json`.
+//!
+//! Every object the CLI builds itself is a struct here, so its keys are snake_case and appear in
+//! field order (serde_json is built with `preserve_order`). Payloads that used to be `json!` maps
+//! declare their fields alphabetically, the order those maps printed in. API objects and queue
+//! entries nested inside are wrapped in [`Sorted`]: they keep the API's camelCase names and their
+//! keys are sorted, so fields the models do not know about cannot reorder the output. Changing a
+//! struct here changes the CLI's JSON schema; `tests/json_output.rs` snapshots each of them.
+
+use std::collections::HashMap;
+
+use nblm_core::doctor::CheckResult;
+use nblm_core::models::enterprise::{
+    audio::AudioOverviewResponse,
+    budget::{NotebookWordBudget, SourceWordCount},
+    notebook::Notebook,
+    query::QueryResponse,
+    source::{NotebookSource, NotebookSourceId, UploadSourceFileResponse},
+    stats::{NotebookSourceCount, SourceCounts},
+};
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use serde_json::value::RawValue;
+use serde_json::Value;
+use time::OffsetDateTime;
+
+use crate::util::queue::QueueEntry;
+
+/// Serialize `T` with its object keys sorted at every level.
+#[derive(Debug)]
+pub struct Sorted<T>(pub T);
+
+impl<T: Serialize> Serialize for Sorted<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut value = serde_json::to_value(&self.0).map_err(serde::ser::Error::custom)?;
+        sort_keys(&mut value);
+        value.serialize(serializer)
+    }
+}
+
+/// Sort object keys recursively, independent of how the map preserves insertion order.
+pub fn sort_keys(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.sort_keys();
+            map.values_mut().for_each(sort_keys);
+        }
+        Value::Array(items) => items.iter_mut().for_each(sort_keys),
+        _ => {}
+    }
+}
+
+/// `notebooks create`.
+#[derive(Debug, Serialize)]
+pub struct NotebookCreated<'a> {
+    pub notebook: Sorted<&'a Notebook>,
+    pub notebook_id: &'a str,
+}
+
+/// `sources add` in text mode, and with `--retry-failed`.
+#[derive(Debug, Serialize)]
+pub struct SourcesAdded<'a> {
+    pub error_count: Value,
+    pub notebook_id: &'a str,
+    /// Present only with `--retry-failed`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retried: Option<Vec<RetriedSource>>,
+    /// Text mode only: IDs of the sources that did not fail.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_ids: Option<Vec<&'a str>>,
+    pub sources: Sorted<Value>,
+}
+
+/// `sources add --json`: `errorCount` and `sources` exactly as the API sent them.
+#[derive(Debug, Serialize)]
+pub struct SourcesAddedRaw<'a> {
+    pub error_count: Option<&'a RawValue>,
+    pub notebook_id: &'a str,
+    pub sources: &'a RawValue,
+}
+
+/// `sources add --wait`.
+#[derive(Debug, Serialize)]
+pub struct SourcesIngested<'a> {
+    pub notebook_id: &'a str,
+    /// One entry per submitted source, in request order.
+    pub sources: Vec<IngestTiming>,
+    /// Durations of the sources that finished ingesting, by kind.
+    pub by_kind: Vec<KindTiming>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IngestTiming {
+    /// Position of the source in the request.
+    pub index: usize,
+    pub source_id: Option<String>,
+    /// `web`, `text`, `drive` or `video`.
+    pub kind: &'static str,
+    /// The source's last status, or `TIMED_OUT` or `ERROR`.
+    pub state: String,
+    /// Seconds from the add request to the last status; `null` for a source the add request
+    /// already reported as failed.
+    pub duration_secs: Option<f64>,
+    /// Status checks made after the add request.
+    pub polls: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KindTiming {
+    pub kind: &'static str,
+    pub count: usize,
+    pub median_secs: f64,
+    pub max_secs: f64,
+}
+
+#[derive(Debug)]
+pub struct RetriedSource {
+    /// Position of the source in the original request.
+    pub index: usize,
+    pub source: String,
+    pub outcome: RetryOutcome,
+}
+
+#[derive(Debug)]
+pub enum RetryOutcome {
+    Added { name: String },
+    Failed { api_status: Option<String> },
+}
+
+impl Serialize for RetriedSource {
+    /// The outcome's keys sit among the others in alphabetical order.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(4))?;
+        if let RetryOutcome::Failed { api_status } = &self.outcome {
+            map.serialize_entry("api_status", api_status)?;
+        }
+        map.serialize_entry("index", &self.index)?;
+        if let RetryOutcome::Added { name } = &self.outcome {
+            map.serialize_entry("name", name)?;
+        }
+        map.serialize_entry("source", &self.source)?;
+        let status = match self.outcome {
+            RetryOutcome::Added { .. } => "added",
+            RetryOutcome::Failed { .. } => "failed",
+        };
+        map.serialize_entry("status", status)?;
+        map.end()
+    }
+}
+
+/// `sources upload` for a single file.
+#[derive(Debug, Serialize)]
+pub struct SourceUploaded<'a> {
+    pub content_type: &'a str,
+    pub extra: Sorted<&'a HashMap<String, Value>>,
+    pub file_name: &'a str,
+    pub name: Option<&'a str>,
+    pub notebook_id: &'a str,
+    pub source_id: Sorted<&'a Option<NotebookSourceId>>,
+    pub state: Option<&'a str>,
+}
+
+impl<'a> SourceUploaded<'a> {
+    pub fn new(
+        notebook_id: &'a str,
+        file_name: &'a str,
+        content_type: &'a str,
+        response: &'a UploadSourceFileResponse,
+    ) -> Self {
+        Self {
+            content_type,
+            extra: Sorted(&response.extra),
+            file_name,
+            name: response.name.as_deref(),
+            notebook_id,
+            source_id: Sorted(&response.source_id),
+            state: response.state.as_deref(),
+        }
+    }
+}
+
+/// `sources upload` with several files.
+#[derive(Debug, Serialize)]
+pub struct SourcesUploaded<'a> {
+    pub failed: Vec<FailedItem>,
+    /// Files still uploading when an interrupt's grace period ran out.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub in_flight: Vec<String>,
+    pub notebook_id: &'a str,
+    pub skipped: Vec<String>,
+    pub uploads: Vec<UploadEntry<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UploadEntry<'a> {
+    pub content_type: &'a str,
+    pub extra: Sorted<&'a HashMap<String, Value>>,
+    pub file_name: &'a str,
+    pub source_id: Sorted<&'a Option<NotebookSourceId>>,
+}
+
+/// An item of a batch command that failed; `file` for uploads, `name` for deletions.
+#[derive(Debug, Serialize)]
+pub struct FailedItem {
+    pub error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// `sources list`.
+#[derive(Debug, Serialize)]
+pub struct SourceList<'a> {
+    pub notebook_id: &'a str,
+    pub sources: Sorted<&'a [NotebookSource]>,
+}
+
+/// `notebooks delete` and `sources delete`.
+#[derive(Debug, Serialize)]
+pub struct Deleted<R> {
+    pub count: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failed: Option<Vec<FailedItem>>,
+    /// Items still being deleted when an interrupt's grace period ran out.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_flight: Option<Vec<String>>,
+    pub response: Sorted<R>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<Vec<String>>,
+    /// `deleted`, `partial` when some notebooks could not be deleted, or `interrupted`.
+    pub status: &'static str,
+}
+
+/// `notebooks delete --dry-run` and `sources delete --dry-run`.
+#[derive(Debug, Serialize)]
+pub struct DryRun<'a> {
+    pub status: &'static str,
+    pub count: usize,
+    pub would_delete: &'a [String],
+}
+
+/// `sources search`.
+#[derive(Debug, Serialize)]
+pub struct SearchResults<'a> {
+    pub matches: Vec<SearchHit<'a>>,
+    pub notes: &'a [String],
+    pub query: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchHit<'a> {
+    /// `title` or `url`.
+    pub field: &'a str,
+    #[serde(rename = "match")]
+    pub matched: &'a str,
+    pub notebook_id: &'a str,
+    pub snippet: String,
+    pub source_id: &'a str,
+    pub source_name: &'a str,
+    pub title: Option<&'a str>,
+}
+
+/// `sources reingest`.
+#[derive(Debug, Serialize)]
+pub struct SourcesReingested<'a> {
+    pub notebook_id: &'a str,
+    pub reingested: Vec<ReingestedSource<'a>>,
+    pub skipped: Vec<SkippedSource<'a>>,
+    pub failed: Vec<FailedItem>,
+    /// Sources still being reingested when an interrupt's grace period ran out.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub in_flight: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReingestedSource<'a> {
+    pub old_source_id: &'a str,
+    pub new_source_id: &'a str,
+    pub url: &'a str,
+    pub title: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SkippedSource<'a> {
+    pub source_id: &'a str,
+    pub title: Option<&'a str>,
+    pub reason: &'a str,
+}
+
+/// `notebooks query`.
+#[derive(Debug, Serialize)]
+pub struct NotebookQueried<'a> {
+    pub notebook_id: &'a str,
+    pub query: &'a str,
+    pub response: Sorted<&'a QueryResponse>,
+}
+
+/// `notebooks budget`.
+#[derive(Debug, Serialize)]
+pub struct WordBudget<'a> {
+    pub budget: u64,
+    pub missing_word_counts: usize,
+    pub notebook_id: &'a str,
+    pub over_by: u64,
+    pub sources: Sorted<&'a [SourceWordCount]>,
+    pub total_words: u64,
+    pub within_budget: bool,
+}
+
+impl<'a> WordBudget<'a> {
+    pub fn new(summary: &'a NotebookWordBudget, budget: u64) -> Self {
+        let over_by = summary.over_budget(budget);
+        Self {
+            budget,
+            missing_word_counts: summary.missing_word_counts,
+            notebook_id: &summary.notebook_id,
+            over_by: over_by.unwrap_or(0),
+            sources: Sorted(&summary.sources),
+            total_words: summary.total_words,
+            within_budget: over_by.is_none(),
+        }
+    }
+}
+
+/// `notebooks sources-count`.
+#[derive(Debug, Serialize)]
+pub struct SourceCountReport<'a> {
+    pub total_notebooks: usize,
+    pub total_sources: usize,
+    pub notebooks: &'a [NotebookSourceCount],
+}
+
+impl<'a> SourceCountReport<'a> {
+    pub fn new(counts: &'a SourceCounts) -> Self {
+        Self {
+            total_notebooks: counts.notebooks.len(),
+            total_sources: counts.total_sources,
+            notebooks: &counts.notebooks,
+        }
+    }
+}
+
+/// `audio create`. The camelCase key predates this module and is kept for compatibility.
+#[derive(Debug, Serialize)]
+pub struct AudioCreated<'a> {
+    #[serde(rename = "audioOverview")]
+    pub audio_overview: Sorted<&'a AudioOverviewResponse>,
+}
+
+/// `audio create-batch`.
+#[derive(Debug, Serialize)]
+pub struct AudioBatch<'a> {
+    pub notebooks: Vec<AudioBatchEntry<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AudioBatchEntry<'a> {
+    pub notebook_id: &'a str,
+    /// The overview's last known status, or `FAILED`, `SKIPPED` or `INTERRUPTED`.
+    pub state: &'a str,
+    /// Seconds from the create request to the last status.
+    pub duration_secs: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// `audio download`.
+#[derive(Debug, Serialize)]
+pub struct AudioDownloaded<'a> {
+    pub notebook_id: &'a str,
+    pub path: &'a str,
+    pub bytes: u64,
+    pub content_type: Option<&'a str>,
+}
+
+/// `audio delete`.
+#[derive(Debug, Serialize)]
+pub struct Status {
+    pub status: &'static str,
+}
+
+/// `auth login`.
+#[derive(Debug, Serialize)]
+pub struct AuthLogin {
+    pub drive_access: bool,
+    pub exit_code: Option<i32>,
+    pub method: &'static str,
+    pub success: bool,
+}
+
+/// `auth status`.
+#[derive(Debug, Serialize)]
+pub struct AuthStatus {
+    /// Credential nblm would pick up without extra flags, in order gcloud, env, user-oauth.
+    pub auth_method: Option<&'static str>,
+    /// `None` when the scope could not be checked (no token, or tokeninfo unreachable).
+    pub drive_scope: Option<bool>,
+    pub gcloud_account: Option<String>,
+    pub gcloud_available: bool,
+    pub has_env_token: bool,
+    pub oauth_tokens_stored: bool,
+}
+
+/// `doctor`.
+#[derive(Debug, Serialize)]
+pub struct DoctorReport<'a> {
+    pub checks: &'a [CheckResult],
+    /// What the process exits with: 0 all passed, 1 warnings only, 2 any error.
+    pub exit_code: i32,
+}
+
+/// `queue add`.
+#[derive(Debug, Serialize)]
+pub struct QueueAdded<'a> {
+    pub queued: Sorted<&'a [QueueEntry]>,
+}
+
+/// `queue flush`.
+#[derive(Debug, Serialize)]
+pub struct QueueFlushed<'a> {
+    pub failed: &'a [FailedEntry],
+    pub flushed: &'a [FlushedEntry],
+    pub remaining: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FlushedEntry {
+    pub id: String,
+    pub result: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FailedEntry {
+    pub error: String,
+    pub id: String,
+}
+
+/// `queue list`.
+#[derive(Debug, Serialize)]
+pub struct QueueList<'a> {
+    pub entries: Sorted<&'a [QueueEntry]>,
+}
+
+/// `queue clear`.
+#[derive(Debug, Serialize)]
+pub struct QueueCleared {
+    pub remaining: usize,
+    pub removed: usize,
+}
+
+/// Printed on stderr with `--json` when a request was rate limited (HTTP 429) and retried.
+#[derive(Debug, Serialize)]
+pub struct RateLimitReport {
+    pub rate_limit: RateLimitSummary,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RateLimitSummary {
+    pub rate_limited: bool,
+    pub retries: usize,
+    /// Time spent waiting before the retries.
+    pub waited_ms: u64,
+    /// Largest `Retry-After` the API sent, if any.
+    pub max_retry_after_ms: Option<u64>,
+}
+
+/// `--timings` with `--json`, printed on stderr after the command's own output.
+#[derive(Debug, Serialize)]
+pub struct TimingsReport<'a> {
+    pub timings: TimingsSummary<'a>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimingsSummary<'a> {
+    pub total_ms: f64,
+    pub token_fetch: PhaseTiming,
+    pub api: PhaseTiming,
+    pub retry_wait: PhaseTiming,
+    pub file_io: PhaseTiming,
+    pub other: PhaseTiming,
+    pub calls: Vec<CallTiming<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PhaseTiming {
+    /// Absent for `other`, which is the wall time left over rather than counted events.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+    pub ms: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CallTiming<'a> {
+    pub method: &'a str,
+    pub path: &'a str,
+    pub count: usize,
+    pub ms: f64,
+}
+
+/// `validate`.
+#[derive(Debug, Serialize)]
+pub struct Validated<'a> {
+    pub file: String,
+    pub kind: &'static str,
+    pub valid: bool,
+    pub errors: &'a [Problem],
+}
+
+/// A problem found by `validate`. `field` is a path such as `entries[0].notebook_id`; `line`
+/// and `column` are 1-based and present when the parser reported a position.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Problem {
+    pub field: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+/// Printed on stderr with `--json` when responses carried `Deprecation`, `Sunset` or `Warning`
+/// headers.
+#[derive(Debug, Serialize)]
+pub struct DeprecationReport {
+    pub api_deprecations: Vec<DeprecationEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeprecationEntry {
+    pub method: String,
+    /// Path with resource IDs replaced by placeholders.
+    pub path: String,
+    pub deprecated: bool,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub deprecated_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub sunset: Option<OffsetDateTime>,
+    pub warnings: Vec<String>,
+}
+
+/// `commands`: the command tree, for tools that generate wrappers or documentation.
+#[derive(Debug, Serialize)]
+pub struct CommandInventory {
+    /// [`crate::util::inventory::SCHEMA_VERSION`].
+    pub schema_version: u32,
+    pub name: String,
+    pub version: String,
+    pub commands: Vec<CommandEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CommandEntry {
+    /// The words that run the command, e.g. `nblm sources add`.
+    pub path: String,
+    pub about: Option<String>,
+    /// Left out of `--help` and the man pages.
+    pub hidden: bool,
+    /// Does nothing by itself; one of its subcommands must be given.
+    pub requires_subcommand: bool,
+    /// Arguments declared on this command. `global` ones are also accepted after any of its
+    /// subcommands.
+    pub args: Vec<ArgEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArgEntry {
+    pub id: String,
+    pub long: Option<String>,
+    pub short: Option<char>,
+    pub positional: bool,
+    /// Placeholder shown in help, for arguments that take a value.
+    pub value_name: Option<String>,
+    pub value_type: ValueType,
+    /// May be given more than once, or takes several values.
+    pub repeated: bool,
+    pub required: bool,
+    pub default: Option<String>,
+    /// Environment variable read when the argument is not given.
+    pub env: Option<String>,
+    pub possible_values: Vec<PossibleValueEntry>,
+    pub global: bool,
+    pub hidden: bool,
+    pub help: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PossibleValueEntry {
+    pub name: String,
+    pub hidden: bool,
+    /// Only accepted with NBLM_PROFILE_EXPERIMENT=1.
+    pub experimental: bool,
+}
+
+/// What an argument's value is parsed as. `bool` and `count` arguments are flags that take no
+/// value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueType {
+    Bool,
+    Count,
+    String,
+    Enum,
+    Integer,
+    Number,
+    /// A byte count or a suffixed size such as `500KB`.
+    Size,
+    /// Such as `30s` or `1h30m`.
+    Duration,
+    /// An RFC 3339 timestamp or a duration before now.
+    Time,
+    Path,
+    Url,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn sorted_orders_nested_keys_but_envelopes_keep_field_order() {
+        let payload = Deleted {
+            status: "deleted",
+            count: 1,
+            response: Sorted(json!({"zeta": 1, "alpha": {"b": [{"y": 1, "x": 2}], "a": 0}})),
+            failed: None,
+            skipped: None,
+            in_flight: None,
+        };
+        assert_eq!(
+            serde_json::to_string(&payload).unwrap(),
+            r#"{"count":1,"response":{"alpha":{"a":0,"b":[{"x":2,"y":1}]},"zeta":1},"status":"deleted"}"#
+        );
+    }
+
+    #[test]
+    fn retried_sources_list_their_outcome_among_the_other_keys() {
+        let retried = [
+            RetriedSource {
+                index: 0,
+                source: "https://example.com".to_string(),
+                outcome: RetryOutcome::Added {
+                    name: "sources/s1".to_string(),
+                },
+            },
+            RetriedSource {
+                index: 2,
+                source: "notes".to_string(),
+                outcome: RetryOutcome::Failed { api_status: None },
+            },
+        ];
+        assert_eq!(
+            serde_json::to_string(&retried).unwrap(),
+            r#"[{"index":0,"name":"sources/s1","source":"https://example.com","status":"added"},{"api_status":null,"index":2,"source":"notes","status":"failed"}]"#
+        );
+    }
+}
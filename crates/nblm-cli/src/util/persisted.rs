@@ -0,0 +1,404 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Key holding the schema version in every persisted file.
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+/// How to move persisted files somewhere usable, for errors reading or creating them.
+fn dir_hint() -> String {
+    format!("set {} to a writable directory", nblm_core::CONFIG_DIR_ENV)
+}
+
+/// Key that held the version in files written before [`SCHEMA_VERSION_KEY`] existed.
+const LEGACY_VERSION_KEY: &str = "version";
+
+/// Data the CLI keeps in a JSON file between runs.
+///
+/// The file is a JSON object: the fields of `Self` plus [`SCHEMA_VERSION_KEY`].
+pub trait Persisted: Serialize + DeserializeOwned + Default {
+    /// What the file holds, for messages (e.g. `queue`).
+    const KIND: &'static str;
+    /// Version written to new files. Files with a newer version are refused.
+    const SCHEMA_VERSION: u32;
+
+    /// Turn the fields of a version `from` file into those of version `from + 1`. Called once
+    /// per version step until the file is current.
+    fn migrate(from: u32, fields: Map<String, Value>) -> Result<Map<String, Value>> {
+        let _ = fields;
+        bail!("no migration from {} format version {from}", Self::KIND)
+    }
+}
+
+/// A [`Persisted`] value stored at `path`.
+///
+/// Saving writes a uniquely named temporary file next to `path`, renames it into place and syncs
+/// the directory, so a crash leaves either the old file or the new one and concurrent saves never
+/// mix their contents. Loading migrates older versions, refuses newer ones, and
+/// moves a file that cannot be read aside to `<path>.corrupt` and starts over from
+/// `T::default()` with a warning.
+pub struct PersistedFile<T> {
+    path: PathBuf,
+    _data: PhantomData<fn() -> T>,
+}
+
+impl<T: Persisted> PersistedFile<T> {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            _data: PhantomData,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Read the file, or `T::default()` when there is none. Warnings go to stderr.
+    pub fn load(&self) -> Result<T> {
+        self.load_with(|warning| eprintln!("warning: {warning}"))
+    }
+
+    /// [`PersistedFile::load`], passing warnings to `warn`.
+    pub fn load_with(&self, mut warn: impl FnMut(&str)) -> Result<T> {
+        let raw = match fs::read(&self.path) {
+            Ok(raw) => raw,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(T::default()),
+            Err(err) => {
+                return Err(err).with_context(|| {
+                    format!("failed to read {}; {}", self.path.display(), dir_hint())
+                })
+            }
+        };
+        match self.decode(&raw)? {
+            Ok(data) => Ok(data),
+            Err(problem) => {
+                let corrupt = self.quarantine()?;
+                warn(&format!(
+                    "{} {problem}; moved it to {} and started a new {}",
+                    self.path.display(),
+                    corrupt.display(),
+                    T::KIND
+                ));
+                Ok(T::default())
+            }
+        }
+    }
+
+    /// Write `data` with the current schema version, creating the directory if needed.
+    pub fn save(&self, data: &T) -> Result<()> {
+        let dir = self.path.parent().unwrap_or(Path::new("."));
+        fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create {}; {}", dir.display(), dir_hint()))?;
+
+        let Value::Object(fields) = serde_json::to_value(data)? else {
+            bail!("{} data must serialize to a JSON object", T::KIND);
+        };
+        let mut object = Map::new();
+        object.insert(SCHEMA_VERSION_KEY.to_string(), T::SCHEMA_VERSION.into());
+        object.extend(fields);
+        let body = serde_json::to_vec_pretty(&object)?;
+
+        // A temp file of its own per save, so concurrent saves never write into the same file.
+        let mut file = tempfile::Builder::new()
+            .prefix(&self.sibling_name("tmp."))
+            .tempfile_in(dir)
+            .with_context(|| {
+                format!(
+                    "failed to create a temporary file in {}; {}",
+                    dir.display(),
+                    dir_hint()
+                )
+            })?;
+        file.write_all(&body)
+            .and_then(|()| file.as_file().sync_all())
+            .with_context(|| format!("failed to write {}", file.path().display()))?;
+        file.persist(&self.path)
+            .with_context(|| format!("failed to write {}", self.path.display()))?;
+        sync_dir(dir).with_context(|| format!("failed to sync {}", dir.display()))?;
+        Ok(())
+    }
+
+    /// The file's contents at the current version. The outer error is for files that must not
+    /// be touched (a newer version, a failed migration); the inner one describes a file that
+    /// cannot be read at all.
+    fn decode(&self, raw: &[u8]) -> Result<std::result::Result<T, String>> {
+        let mut fields = match serde_json::from_slice(raw) {
+            Ok(Value::Object(fields)) => fields,
+            Ok(_) => return Ok(Err("is not a JSON object".to_string())),
+            Err(err) => return Ok(Err(format!("is not valid JSON ({err})"))),
+        };
+        let Some(mut version) = take_version(&mut fields) else {
+            return Ok(Err(format!("has no {SCHEMA_VERSION_KEY}")));
+        };
+        if version > T::SCHEMA_VERSION {
+            bail!(
+                "unsupported {} format version {version} in {} (this nblm supports version {})",
+                T::KIND,
+                self.path.display(),
+                T::SCHEMA_VERSION
+            );
+        }
+        while version < T::SCHEMA_VERSION {
+            fields = T::migrate(version, fields)
+                .with_context(|| format!("failed to upgrade {}", self.path.display()))?;
+            version += 1;
+        }
+        Ok(serde_json::from_value(Value::Object(fields))
+            .map_err(|err| format!("could not be parsed ({err})")))
+    }
+
+    /// Rename the file to `<path>.corrupt`, replacing an earlier one.
+    fn quarantine(&self) -> Result<PathBuf> {
+        let corrupt = self.sibling("corrupt");
+        fs::rename(&self.path, &corrupt).with_context(|| {
+            format!(
+                "failed to move unreadable {} aside to {}",
+                self.path.display(),
+                corrupt.display()
+            )
+        })?;
+        Ok(corrupt)
+    }
+
+    /// `<file name>.<suffix>`, e.g. the prefix of temp files next to the file.
+    fn sibling_name(&self, suffix: &str) -> std::ffi::OsString {
+        let mut name = self.path.file_name().unwrap_or_default().to_owned();
+        name.push(".");
+        name.push(suffix);
+        name
+    }
+
+    fn sibling(&self, extension: &str) -> PathBuf {
+        self.path.with_file_name(self.sibling_name(extension))
+    }
+}
+
+/// Flush `dir`'s entries, so a rename into it survives a crash.
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> std::io::Result<()> {
+    File::open(dir)?.sync_all()
+}
+
+/// Directories cannot be opened as files on Windows, where the rename is already durable.
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+/// The version a stored object declares, from [`SCHEMA_VERSION_KEY`] or the legacy `version`
+/// key, with the key removed. Version 0 counts as none.
+fn take_version(fields: &mut Map<String, Value>) -> Option<u32> {
+    let value = fields
+        .remove(SCHEMA_VERSION_KEY)
+        .or_else(|| fields.remove(LEGACY_VERSION_KEY))?;
+    value
+        .as_u64()
+        .and_then(|version| u32::try_from(version).ok())
+        .filter(|version| *version > 0)
+}
+
+/// The version declared by a stored object, and the key it was found under, without changing it.
+pub fn stored_version(value: &Value) -> Option<(&'static str, u64)> {
+    [SCHEMA_VERSION_KEY, LEGACY_VERSION_KEY]
+        .into_iter()
+        .find_map(|key| Some((key, value.get(key)?.as_u64()?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    /// Version 1 stored `names` as one comma-separated string; version 2 stores a list.
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Bookmarks {
+        names: Vec<String>,
+    }
+
+    impl Persisted for Bookmarks {
+        const KIND: &'static str = "bookmark list";
+        const SCHEMA_VERSION: u32 = 2;
+
+        fn migrate(from: u32, mut fields: Map<String, Value>) -> Result<Map<String, Value>> {
+            match from {
+                1 => {
+                    let names = fields
+                        .remove("names")
+                        .and_then(|names| names.as_str().map(str::to_string))
+                        .unwrap_or_default();
+                    let names: Vec<Value> = names
+                        .split(',')
+                        .filter(|name| !name.is_empty())
+                        .map(|name| Value::String(name.to_string()))
+                        .collect();
+                    fields.insert("names".to_string(), Value::Array(names));
+                    Ok(fields)
+                }
+                _ => bail!("unknown version {from}"),
+            }
+        }
+    }
+
+    fn bookmarks(names: &[&str]) -> Bookmarks {
+        Bookmarks {
+            names: names.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn a_missing_file_is_the_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = PersistedFile::<Bookmarks>::new(dir.path().join("bookmarks.json"));
+        assert_eq!(file.load().unwrap(), Bookmarks::default());
+    }
+
+    /// File names in `dir`, sorted.
+    fn entries(dir: impl AsRef<Path>) -> Vec<String> {
+        let mut names: Vec<_> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn save_embeds_the_schema_version_and_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = PersistedFile::<Bookmarks>::new(dir.path().join("nested/bookmarks.json"));
+        file.save(&bookmarks(&["a", "b"])).unwrap();
+
+        let raw = fs::read_to_string(file.path()).unwrap();
+        let value: Value = serde_json::from_str(&raw).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({ "schema_version": 2, "names": ["a", "b"] })
+        );
+        assert!(
+            raw.trim_start().starts_with("{\n  \"schema_version\": 2"),
+            "{raw}"
+        );
+        assert_eq!(file.load().unwrap(), bookmarks(&["a", "b"]));
+        assert_eq!(entries(dir.path().join("nested")), ["bookmarks.json"]);
+    }
+
+    #[test]
+    fn a_torn_temp_file_leaves_the_saved_data_intact() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = PersistedFile::<Bookmarks>::new(dir.path().join("bookmarks.json"));
+        file.save(&bookmarks(&["kept"])).unwrap();
+
+        // A crash part-way through the next save leaves garbage in its temp file only.
+        let torn = dir.path().join("bookmarks.json.tmp.crashed");
+        fs::write(&torn, b"{\"schema_version\": 2, \"names\": [\"ha").unwrap();
+        assert_eq!(file.load().unwrap(), bookmarks(&["kept"]));
+
+        file.save(&bookmarks(&["kept", "new"])).unwrap();
+        assert_eq!(file.load().unwrap(), bookmarks(&["kept", "new"]));
+        assert_eq!(
+            entries(dir.path()),
+            ["bookmarks.json", "bookmarks.json.tmp.crashed"]
+        );
+    }
+
+    #[test]
+    fn concurrent_saves_each_publish_a_whole_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bookmarks.json");
+        let lists: Vec<Vec<String>> = (0..8)
+            .map(|n| (0..200).map(|i| format!("writer-{n}-{i}")).collect())
+            .collect();
+
+        std::thread::scope(|scope| {
+            for names in &lists {
+                let file = PersistedFile::<Bookmarks>::new(&path);
+                scope.spawn(move || {
+                    for _ in 0..10 {
+                        file.save(&Bookmarks {
+                            names: names.clone(),
+                        })
+                        .unwrap();
+                    }
+                });
+            }
+        });
+
+        let saved = PersistedFile::<Bookmarks>::new(&path).load().unwrap();
+        assert!(lists.contains(&saved.names));
+        assert_eq!(entries(dir.path()), ["bookmarks.json"]);
+    }
+
+    #[test]
+    fn an_older_version_is_migrated() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = PersistedFile::<Bookmarks>::new(dir.path().join("bookmarks.json"));
+        fs::write(file.path(), r#"{"schema_version": 1, "names": "a,b,c"}"#).unwrap();
+
+        assert_eq!(file.load().unwrap(), bookmarks(&["a", "b", "c"]));
+        // Loading does not rewrite the file; the next save does.
+        file.save(&file.load().unwrap()).unwrap();
+        let value: Value = serde_json::from_slice(&fs::read(file.path()).unwrap()).unwrap();
+        assert_eq!(value["schema_version"], 2);
+    }
+
+    #[test]
+    fn the_legacy_version_key_is_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = PersistedFile::<Bookmarks>::new(dir.path().join("bookmarks.json"));
+        fs::write(file.path(), r#"{"version": 1, "names": "a"}"#).unwrap();
+        assert_eq!(file.load().unwrap(), bookmarks(&["a"]));
+    }
+
+    #[test]
+    fn a_newer_version_is_refused_and_left_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = PersistedFile::<Bookmarks>::new(dir.path().join("bookmarks.json"));
+        fs::write(file.path(), r#"{"schema_version": 9, "names": []}"#).unwrap();
+
+        let err = file.load().unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("unsupported bookmark list format version 9 in"),
+            "{err}"
+        );
+        assert!(file.path().exists());
+        assert!(!file.sibling("corrupt").exists());
+    }
+
+    #[test]
+    fn an_unreadable_file_is_quarantined() {
+        for body in [
+            "{not json",
+            "[1, 2]",
+            r#"{"names": []}"#,
+            r#"{"schema_version": 2, "names": "not a list"}"#,
+        ] {
+            let dir = tempfile::tempdir().unwrap();
+            let file = PersistedFile::<Bookmarks>::new(dir.path().join("bookmarks.json"));
+            fs::write(file.path(), body).unwrap();
+
+            let mut warnings = Vec::new();
+            let loaded = file
+                .load_with(|warning| warnings.push(warning.to_string()))
+                .unwrap();
+            assert_eq!(loaded, Bookmarks::default(), "{body}");
+            assert!(!file.path().exists(), "{body}");
+            assert_eq!(fs::read_to_string(file.sibling("corrupt")).unwrap(), body);
+            assert_eq!(warnings.len(), 1, "{body}");
+            assert!(
+                warnings[0].contains("bookmarks.json.corrupt")
+                    && warnings[0].ends_with("and started a new bookmark list"),
+                "{}",
+                warnings[0]
+            );
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//! Line prompts on stderr that Ctrl-C can cancel.
+
+use std::future::Future;
+use std::io::{self, IsTerminal, Write};
+
+use anyhow::Result;
+use nblm_core::Error as CoreError;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader};
+
+use crate::util::interrupt::{Interrupt, Interrupted};
+
+/// Hidden test hook: prompt even though stdin is not a terminal.
+const TEST_ASSUME_TTY_ENV: &str = "NBLM_TEST_ASSUME_TTY";
+
+/// Exit code when a non-interactive run stopped at something that needed someone at the
+/// terminal ([`CoreError::InteractionRequired`]).
+pub const INTERACTION_REQUIRED_EXIT_CODE: i32 = 4;
+
+/// What a prompt read.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Reply {
+    /// A line, without its line ending.
+    Line(String),
+    /// Input ended before anything was typed.
+    Closed,
+    /// Ctrl-C arrived before the line was finished.
+    Cancelled,
+}
+
+/// Whether there is someone to ask: stdin is a terminal and is not carrying other input.
+pub fn can_prompt(stdin_in_use: bool) -> bool {
+    !stdin_in_use && (io::stdin().is_terminal() || std::env::var_os(TEST_ASSUME_TTY_ENV).is_some())
+}
+
+/// The error for a non-interactive run that would have to `interaction` (e.g. "ask to confirm
+/// ..."); `remedy` says how to go without it.
+pub fn interaction_required(
+    interaction: impl Into<String>,
+    remedy: impl Into<String>,
+) -> anyhow::Error {
+    CoreError::InteractionRequired {
+        interaction: interaction.into(),
+        remedy: remedy.into(),
+    }
+    .into()
+}
+
+/// [`INTERACTION_REQUIRED_EXIT_CODE`] for an error caused by a refused interaction, or `None`.
+pub fn exit_code(err: &anyhow::Error) -> Option<i32> {
+    err.chain()
+        .filter_map(|cause| cause.downcast_ref::<CoreError>())
+        .any(|err| matches!(err, CoreError::InteractionRequired { .. }))
+        .then_some(INTERACTION_REQUIRED_EXIT_CODE)
+}
+
+/// Read one line from `input`, or stop with [`Reply::Cancelled`] as soon as `cancel` resolves.
+pub async fn read_reply<R>(input: &mut R, cancel: impl Future<Output = ()>) -> io::Result<Reply>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut line = String::new();
+    tokio::select! {
+        biased;
+        () = cancel => Ok(Reply::Cancelled),
+        read = input.read_line(&mut line) => Ok(match read? {
+            0 => Reply::Closed,
+            _ => Reply::Line(line.trim_end_matches(['\r', '\n']).to_string()),
+        }),
+    }
+}
+
+/// The answer to a `[y/N]` question: yes only for `y` or `yes` in any case. Closed input is a
+/// no; a cancelled prompt is an [`Interrupted`] error, so the command exits with 130 without
+/// doing what it asked about.
+pub fn decide(reply: Reply) -> Result<bool> {
+    match reply {
+        Reply::Line(line) => Ok(matches!(
+            line.trim().to_ascii_lowercase().as_str(),
+            "y" | "yes"
+        )),
+        Reply::Closed => Ok(false),
+        Reply::Cancelled => Err(cancelled()),
+    }
+}
+
+/// The error for a prompt abandoned with Ctrl-C.
+pub fn cancelled() -> anyhow::Error {
+    Interrupted {
+        summary: "aborted at the prompt; nothing was changed".to_string(),
+    }
+    .into()
+}
+
+/// Print `prompt` on stderr and read a line from stdin, giving up on Ctrl-C.
+///
+/// While it waits, `interrupt` (the command's signal listener, if it has one) only records a
+/// signal instead of exiting mid-line. The terminal stays in line mode throughout, so the only
+/// thing to restore after Ctrl-C is the cursor: a newline is printed so later output starts on a
+/// fresh line (likewise after Ctrl-D).
+///
+/// Blocks the calling worker thread, so it needs the multi-threaded runtime `main` runs on.
+pub fn ask(prompt: &str, interrupt: Option<&Interrupt>) -> Result<Reply> {
+    eprint!("{prompt}");
+    io::stderr().flush()?;
+    let _prompting = interrupt.map(Interrupt::prompting);
+    let reply = tokio::task::block_in_place(|| {
+        tokio::runtime::Handle::current().block_on(async {
+            let ctrl_c = async {
+                if tokio::signal::ctrl_c().await.is_err() {
+                    std::future::pending::<()>().await;
+                }
+            };
+            read_reply(&mut BufReader::new(tokio::io::stdin()), ctrl_c).await
+        })
+    })?;
+    if !matches!(reply, Reply::Line(_)) {
+        eprintln!();
+    }
+    Ok(reply)
+}
+
+/// Ask `question` with a `[y/N]` hint; see [`ask`] and [`decide`].
+pub fn confirm(question: &str, interrupt: Option<&Interrupt>) -> Result<bool> {
+    decide(ask(&format!("{question} [y/N]: "), interrupt)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn reply_to(input: &str) -> Reply {
+        read_reply(&mut input.as_bytes(), std::future::pending())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn lines_are_read_without_their_ending() {
+        assert_eq!(reply_to("y\n").await, Reply::Line("y".into()));
+        assert_eq!(reply_to("Yes\r\n").await, Reply::Line("Yes".into()));
+        assert_eq!(reply_to("no").await, Reply::Line("no".into()));
+        assert_eq!(reply_to("").await, Reply::Closed);
+    }
+
+    #[tokio::test]
+    async fn cancel_wins_over_input_that_never_arrives() {
+        let (mut reader, _writer) = tokio::io::duplex(64);
+        let mut reader = BufReader::new(&mut reader);
+        let reply = read_reply(&mut reader, async {}).await.unwrap();
+        assert_eq!(reply, Reply::Cancelled);
+    }
+
+    #[test]
+    fn only_yes_confirms() {
+        for yes in ["y", "Y", "yes", " YES "] {
+            assert!(decide(Reply::Line(yes.into())).unwrap(), "{yes}");
+        }
+        for no in ["", "n", "no", "yep", "sure"] {
+            assert!(!decide(Reply::Line(no.into())).unwrap(), "{no}");
+        }
+        assert!(!decide(Reply::Closed).unwrap());
+    }
+
+    #[test]
+    fn refused_interactions_have_their_own_exit_code() {
+        let err = interaction_required("ask to confirm", "pass --yes");
+        assert_eq!(
+            err.to_string(),
+            "cannot ask to confirm when running non-interactively; pass --yes"
+        );
+        assert_eq!(exit_code(&err), Some(INTERACTION_REQUIRED_EXIT_CODE));
+        let err = err.context("deleting notebooks");
+        assert_eq!(exit_code(&err), Some(INTERACTION_REQUIRED_EXIT_CODE));
+        assert_eq!(exit_code(&anyhow::anyhow!("other")), None);
+        assert_eq!(exit_code(&CoreError::validation("bad").into()), None);
+    }
+
+    #[test]
+    fn cancelled_prompt_is_an_interruption() {
+        let err = decide(Reply::Cancelled).unwrap_err();
+        assert!(err.is::<Interrupted>());
+        assert_eq!(
+            err.to_string(),
+            "interrupted: aborted at the prompt; nothing was changed"
+        );
+    }
+}
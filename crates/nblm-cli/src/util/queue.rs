@@ -0,0 +1,284 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use nblm_core::models::enterprise::source::UserContent;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+use crate::util::payload::sort_keys;
+use crate::util::persisted::{Persisted, PersistedFile};
+
+/// Version written to new queue files. Files with a newer version are refused.
+///
+/// Version 2 stores it as `schema_version` (version 1 used `version`); the entries are
+/// unchanged.
+pub const QUEUE_FORMAT_VERSION: u32 = 2;
+
+const QUEUE_FILE: &str = "queue.json";
+
+/// A deferred API call, stored with everything needed to replay it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum QueuedOperation {
+    AddSources {
+        contents: Vec<UserContent>,
+    },
+    /// The file itself is not copied; `sha256` detects changes made before the flush.
+    UploadFile {
+        path: PathBuf,
+        file_name: String,
+        content_type: String,
+        sha256: String,
+        size: u64,
+    },
+}
+
+impl QueuedOperation {
+    pub fn describe(&self) -> String {
+        match self {
+            QueuedOperation::AddSources { contents } => {
+                format!("add {} source(s)", contents.len())
+            }
+            QueuedOperation::UploadFile { path, .. } => format!("upload {}", path.display()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueEntry {
+    pub id: String,
+    pub queued_at: String,
+    pub notebook_id: String,
+    pub operation: QueuedOperation,
+    /// SHA-256 of the notebook ID and operation, checked before the entry is replayed.
+    pub checksum: String,
+    #[serde(default)]
+    pub attempts: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+impl QueueEntry {
+    pub fn new(notebook_id: impl Into<String>, operation: QueuedOperation) -> Result<Self> {
+        let notebook_id = notebook_id.into();
+        let now = OffsetDateTime::now_utc();
+        let checksum = entry_checksum(&notebook_id, &operation)?;
+        let id = sha256_hex(format!("{}:{checksum}", now.unix_timestamp_nanos()).as_bytes())[..12]
+            .to_string();
+        Ok(Self {
+            id,
+            queued_at: now.format(&Rfc3339)?,
+            notebook_id,
+            operation,
+            checksum,
+            attempts: 0,
+            last_error: None,
+        })
+    }
+
+    /// Fail if the entry was edited after it was queued.
+    pub fn verify(&self) -> Result<()> {
+        if entry_checksum(&self.notebook_id, &self.operation)? != self.checksum {
+            bail!(
+                "queue entry {} failed its integrity check; remove it with `nblm queue clear --id {}`",
+                self.id,
+                self.id
+            );
+        }
+        Ok(())
+    }
+
+    /// Replace the operation (e.g. with only the still-failing part) and re-sign the entry.
+    pub fn set_operation(&mut self, operation: QueuedOperation) -> Result<()> {
+        self.checksum = entry_checksum(&self.notebook_id, &operation)?;
+        self.operation = operation;
+        Ok(())
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+pub struct QueueFile {
+    pub entries: Vec<QueueEntry>,
+}
+
+impl Persisted for QueueFile {
+    const KIND: &'static str = "queue";
+    const SCHEMA_VERSION: u32 = QUEUE_FORMAT_VERSION;
+
+    fn migrate(from: u32, fields: Map<String, Value>) -> Result<Map<String, Value>> {
+        match from {
+            // Only the name of the version key changed.
+            1 => Ok(fields),
+            _ => bail!("no migration from queue format version {from}"),
+        }
+    }
+}
+
+/// Queued operations in the order they were added, backed by `queue.json` in a directory.
+pub struct Queue {
+    file: PersistedFile<QueueFile>,
+    pub entries: Vec<QueueEntry>,
+}
+
+impl Queue {
+    /// `queue/` under the nblm config directory (`NBLM_CONFIG_DIR` overrides it).
+    pub fn default_dir() -> Result<PathBuf> {
+        Ok(nblm_core::config_dir()?.join("queue"))
+    }
+
+    /// Load the queue in `dir`. An unreadable `queue.json` is moved aside to
+    /// `queue.json.corrupt` with a warning and the queue starts empty.
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let file = PersistedFile::<QueueFile>::new(dir.into().join(QUEUE_FILE));
+        let entries = file.load()?.entries;
+        Ok(Self { file, entries })
+    }
+
+    /// Write the queue atomically (temp file + rename), creating the directory if needed.
+    pub fn save(&self) -> Result<()> {
+        // `QueueFile` owns its entries; clone rather than move them out of `self`.
+        self.file.save(&QueueFile {
+            entries: self.entries.clone(),
+        })
+    }
+
+    #[cfg(test)]
+    pub fn path(&self) -> PathBuf {
+        self.file.path().to_path_buf()
+    }
+}
+
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+fn entry_checksum(notebook_id: &str, operation: &QueuedOperation) -> Result<String> {
+    let mut signed = json!({
+        "notebook_id": notebook_id,
+        "operation": operation,
+    });
+    // Sign with sorted keys so checksums do not depend on field or map iteration order.
+    sort_keys(&mut signed);
+    Ok(sha256_hex(&serde_json::to_vec(&signed)?))
+}
+
+/// Files referenced by an upload entry must still exist with the same content.
+pub fn verify_file(path: &Path, expected_sha256: &str) -> Result<Vec<u8>> {
+    let data = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    if sha256_hex(&data) != expected_sha256 {
+        bail!(
+            "{} was modified after it was queued; re-queue it to upload the new content",
+            path.display()
+        );
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn web_entry() -> QueueEntry {
+        QueueEntry::new(
+            "nb1",
+            QueuedOperation::AddSources {
+                contents: vec![UserContent::web("https://example.com".to_string(), None)],
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn queue_round_trips_through_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut queue = Queue::open(dir.path()).unwrap();
+        assert!(queue.entries.is_empty());
+
+        queue.entries.push(web_entry());
+        queue.save().unwrap();
+
+        let reopened = Queue::open(dir.path()).unwrap();
+        assert_eq!(reopened.entries.len(), 1);
+        assert_eq!(reopened.entries[0].id, queue.entries[0].id);
+        reopened.entries[0].verify().unwrap();
+
+        let raw: serde_json::Value =
+            serde_json::from_slice(&fs::read(queue.path()).unwrap()).unwrap();
+        assert_eq!(raw["schema_version"], QUEUE_FORMAT_VERSION);
+        assert_eq!(raw["entries"][0]["operation"]["kind"], "add_sources");
+    }
+
+    #[test]
+    fn verify_detects_edited_entry() {
+        let mut entry = web_entry();
+        entry.notebook_id = "other".to_string();
+        let err = entry.verify().unwrap_err();
+        assert!(err.to_string().contains("failed its integrity check"));
+    }
+
+    #[test]
+    fn set_operation_re_signs_entry() {
+        let mut entry = web_entry();
+        entry
+            .set_operation(QueuedOperation::AddSources {
+                contents: Vec::new(),
+            })
+            .unwrap();
+        entry.verify().unwrap();
+    }
+
+    #[test]
+    fn open_rejects_newer_format_version() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join(QUEUE_FILE),
+            r#"{"version": 99, "entries": []}"#,
+        )
+        .unwrap();
+        let err = Queue::open(dir.path()).err().unwrap();
+        assert!(err
+            .to_string()
+            .contains("unsupported queue format version 99"));
+    }
+
+    #[test]
+    fn open_reads_a_version_1_queue() {
+        let dir = tempfile::tempdir().unwrap();
+        let entry = web_entry();
+        fs::write(
+            dir.path().join(QUEUE_FILE),
+            json!({ "version": 1, "entries": [&entry] }).to_string(),
+        )
+        .unwrap();
+        let queue = Queue::open(dir.path()).unwrap();
+        assert_eq!(queue.entries.len(), 1);
+        assert_eq!(queue.entries[0].id, entry.id);
+        queue.entries[0].verify().unwrap();
+    }
+
+    #[test]
+    fn open_quarantines_an_unreadable_queue() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(QUEUE_FILE), "{\"version\": 1, \"entr").unwrap();
+        let queue = Queue::open(dir.path()).unwrap();
+        assert!(queue.entries.is_empty());
+        assert!(!queue.path().exists());
+        assert!(dir.path().join("queue.json.corrupt").exists());
+    }
+
+    #[test]
+    fn verify_file_detects_modification() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        fs::write(&path, "original").unwrap();
+        let hash = sha256_hex(b"original");
+        assert_eq!(verify_file(&path, &hash).unwrap(), b"original");
+
+        fs::write(&path, "changed").unwrap();
+        let err = verify_file(&path, &hash).unwrap_err();
+        assert!(err.to_string().contains("was modified after it was queued"));
+    }
+}
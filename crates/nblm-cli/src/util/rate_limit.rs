@@ -0,0 +1,269 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use nblm_core::RetryEvent;
+use reqwest::StatusCode;
+
+use crate::util::output::OutputSink;
+use crate::util::payload::{RateLimitReport, RateLimitSummary};
+use crate::util::status::format_delay;
+
+/// Wait suggested in the rerun hint when the API did not send `Retry-After`.
+const DEFAULT_SUGGESTED_WAIT: Duration = Duration::from_secs(30);
+
+/// Flags whose values are replaced by `"$NBLM_ACCESS_TOKEN"` in the rerun hint.
+const TOKEN_FLAGS: &[&str] = &["--token", "--env-token"];
+
+/// 429 retries seen while a command ran, fed by [`nblm_core::RetryConfig::with_on_retry`].
+#[derive(Debug, Default)]
+pub struct RateLimits {
+    tally: Mutex<Tally>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Tally {
+    /// Retries made because of a 429.
+    pub retries: usize,
+    /// Time spent waiting before those retries.
+    pub waited: Duration,
+    /// Largest `Retry-After` the API sent, before it was capped at the maximum retry delay.
+    pub max_retry_after: Option<Duration>,
+}
+
+impl RateLimits {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Callback for [`nblm_core::RetryConfig::with_on_retry`] that records into `self`.
+    pub fn hook(self: &Arc<Self>) -> impl Fn(&RetryEvent) + Send + Sync + 'static {
+        let limits = Arc::clone(self);
+        move |event| limits.record(event)
+    }
+
+    pub fn record(&self, event: &RetryEvent) {
+        if event.status != Some(StatusCode::TOO_MANY_REQUESTS) {
+            return;
+        }
+        let mut tally = self.tally.lock().unwrap();
+        tally.retries += 1;
+        tally.waited += event.delay;
+        tally.max_retry_after = tally.max_retry_after.max(event.retry_after);
+    }
+
+    pub fn tally(&self) -> Tally {
+        *self.tally.lock().unwrap()
+    }
+
+    /// Print a summary on stderr if any request was rate limited: a line of text, or a
+    /// `rate_limit` JSON object with `--json` so stdout still holds only the command's document.
+    pub fn report(&self, out: &dyn OutputSink, json_mode: bool) {
+        let tally = self.tally();
+        if tally.retries == 0 {
+            return;
+        }
+        if json_mode {
+            let report = RateLimitReport {
+                rate_limit: RateLimitSummary {
+                    rate_limited: true,
+                    retries: tally.retries,
+                    waited_ms: tally.waited.as_millis() as u64,
+                    max_retry_after_ms: tally.max_retry_after.map(|d| d.as_millis() as u64),
+                },
+            };
+            out.err(&serde_json::to_string_pretty(&report).unwrap());
+        } else {
+            out.err(&tally.describe());
+        }
+    }
+}
+
+impl Tally {
+    pub fn describe(&self) -> String {
+        let mut line = format!(
+            "Rate limited: waited {} over {} retr{}",
+            format_delay(self.waited),
+            self.retries,
+            if self.retries == 1 { "y" } else { "ies" }
+        );
+        if let Some(retry_after) = self.max_retry_after {
+            line.push_str(&format!(
+                "; the API asked to wait up to {}",
+                format_delay(retry_after)
+            ));
+        }
+        line
+    }
+}
+
+/// Whether `err` came from a request the API still rejected with 429 after all retries.
+pub fn is_rate_limited(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<nblm_core::Error>(),
+            Some(nblm_core::Error::Http { status, .. }) if *status == StatusCode::TOO_MANY_REQUESTS
+        )
+    })
+}
+
+/// Advice appended to a `sources add` that failed with 429: how long the API asked to wait and
+/// the same command with a maximum retry delay long enough to honour that.
+///
+/// `args` is the command line as run; the program name is replaced by `nblm` and access tokens
+/// given as flags are not echoed.
+pub fn rerun_hint(tally: &Tally, args: impl IntoIterator<Item = String>) -> String {
+    let (wait, advised) = match tally.max_retry_after {
+        Some(retry_after) => (
+            retry_after.max(Duration::from_secs(1)),
+            format!("The API asked to wait {}", format_delay(retry_after)),
+        ),
+        None => (
+            DEFAULT_SUGGESTED_WAIT,
+            "The API did not say how long to wait".to_string(),
+        ),
+    };
+    let command = rerun_command(args);
+    format!(
+        "{advised} (Retry-After) before adding more sources to this notebook. \
+         To rerun with retries that wait that long:\n  \
+         NBLM_RETRY_MAX_DELAY={} {command}",
+        format_delay(wait)
+    )
+}
+
+fn rerun_command(args: impl IntoIterator<Item = String>) -> String {
+    const TOKEN: &str = "\"$NBLM_ACCESS_TOKEN\"";
+    let mut words = vec!["nblm".to_string()];
+    let mut hide_next = false;
+    for arg in args.into_iter().skip(1) {
+        if std::mem::take(&mut hide_next) {
+            words.push(TOKEN.to_string());
+        } else if TOKEN_FLAGS.contains(&arg.as_str()) {
+            words.push(arg);
+            hide_next = true;
+        } else if let Some((flag, _)) = arg
+            .split_once('=')
+            .filter(|(flag, _)| TOKEN_FLAGS.contains(flag))
+        {
+            words.push(format!("{flag}={TOKEN}"));
+        } else {
+            words.push(shell_quote(&arg));
+        }
+    }
+    words.join(" ")
+}
+
+/// `word` as one shell word: unchanged when it is plain, else single-quoted.
+pub fn shell_quote(word: &str) -> String {
+    let plain = !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:=@,+%".contains(c));
+    if plain {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(status: u16, delay_ms: u64, retry_after_secs: Option<u64>) -> RetryEvent {
+        RetryEvent {
+            attempt: 1,
+            max_retries: 3,
+            delay: Duration::from_millis(delay_ms),
+            status: StatusCode::from_u16(status).ok(),
+            retry_after: retry_after_secs.map(Duration::from_secs),
+        }
+    }
+
+    fn args(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn only_429_retries_are_counted() {
+        let limits = RateLimits::new();
+        limits.record(&event(429, 5_000, Some(30)));
+        limits.record(&event(503, 1_000, None));
+        limits.record(&event(429, 2_000, Some(2)));
+
+        assert_eq!(
+            limits.tally(),
+            Tally {
+                retries: 2,
+                waited: Duration::from_secs(7),
+                max_retry_after: Some(Duration::from_secs(30)),
+            }
+        );
+        assert_eq!(
+            limits.tally().describe(),
+            "Rate limited: waited 7s over 2 retries; the API asked to wait up to 30s"
+        );
+    }
+
+    #[test]
+    fn rerun_hint_uses_the_advised_wait() {
+        let tally = Tally {
+            retries: 3,
+            waited: Duration::from_secs(15),
+            max_retry_after: Some(Duration::from_secs(30)),
+        };
+
+        let hint = rerun_hint(
+            &tally,
+            args(&[
+                "/usr/bin/nblm",
+                "sources",
+                "add",
+                "--notebook-id",
+                "nb1",
+                "--text",
+                "it's",
+            ]),
+        );
+
+        assert!(
+            hint.starts_with("The API asked to wait 30s (Retry-After)"),
+            "{hint}"
+        );
+        assert!(
+            hint.ends_with(
+                r"NBLM_RETRY_MAX_DELAY=30s nblm sources add --notebook-id nb1 --text 'it'\''s'"
+            ),
+            "{hint}"
+        );
+    }
+
+    #[test]
+    fn rerun_hint_without_retry_after_falls_back_and_hides_tokens() {
+        let hint = rerun_hint(
+            &Tally::default(),
+            args(&[
+                "nblm",
+                "--auth",
+                "env",
+                "--token",
+                "ya29.secret",
+                "--env-token=ya29.other",
+                "sources",
+                "add",
+            ]),
+        );
+
+        assert!(
+            hint.starts_with("The API did not say how long to wait"),
+            "{hint}"
+        );
+        assert!(!hint.contains("ya29."), "{hint}");
+        assert!(
+            hint.ends_with(
+                r#"NBLM_RETRY_MAX_DELAY=30s nblm --auth env --token "$NBLM_ACCESS_TOKEN" --env-token="$NBLM_ACCESS_TOKEN" sources add"#
+            ),
+            "{hint}"
+        );
+    }
+}
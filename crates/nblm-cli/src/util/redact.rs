@@ -0,0 +1,288 @@
+use std::io;
+use std::sync::{Arc, LazyLock, Mutex};
+
+use clap::ValueEnum;
+use regex::{Captures, Regex};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::util::output::OutputSink;
+
+/// Replaces the project number.
+pub const PROJECT_NUMBER_PLACEHOLDER: &str = "PROJECT_NUMBER";
+
+/// JSON keys whose string values are titles under `--redact=aggressive`.
+const TITLE_KEYS: &[&str] = &["title", "displayName", "sourceName"];
+
+static EMAIL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}").unwrap()
+});
+
+/// `projects/<number>` in resource names and URLs, whatever the configured project.
+static PROJECT_PATH: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"projects/\d+").unwrap());
+
+/// `--redact` levels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum RedactMode {
+    /// Project numbers and email addresses
+    #[default]
+    Standard,
+    /// Also notebook and source titles in JSON output
+    Aggressive,
+}
+
+/// Replace `project_number` wherever it stands alone (not inside a longer run of digits), and the
+/// number in every `projects/<number>`.
+pub fn redact_project_number(text: &str, project_number: Option<&str>) -> String {
+    let mut text = PROJECT_PATH
+        .replace_all(text, format!("projects/{PROJECT_NUMBER_PLACEHOLDER}"))
+        .into_owned();
+    if let Some(number) = project_number.filter(|number| !number.is_empty()) {
+        let standalone = Regex::new(&format!(r"(^|\D){}(\D|$)", regex::escape(number))).unwrap();
+        // Adjacent matches share a separator, so repeat until nothing is left.
+        while standalone.is_match(&text) {
+            text = standalone
+                .replace_all(&text, format!("${{1}}{PROJECT_NUMBER_PLACEHOLDER}${{2}}"))
+                .into_owned();
+        }
+    }
+    text
+}
+
+/// Replace every email address with `user-<hash>@redacted.invalid`, where the hash covers the
+/// address and `salt`. The same address and salt always give the same placeholder.
+pub fn redact_emails(text: &str, salt: &[u8]) -> String {
+    EMAIL
+        .replace_all(text, |captures: &Captures| {
+            let mut hasher = Sha256::new();
+            hasher.update(salt);
+            hasher.update(captures[0].to_ascii_lowercase().as_bytes());
+            let digest = hasher.finalize();
+            let hash: String = digest[..4].iter().map(|b| format!("{b:02x}")).collect();
+            format!("user-{hash}@redacted.invalid")
+        })
+        .into_owned()
+}
+
+/// What `--redact` replaces, with placeholders that stay the same for the whole invocation.
+pub struct Redactor {
+    mode: RedactMode,
+    project_number: Option<String>,
+    /// Random per invocation, so placeholders correlate within one run but not across runs.
+    salt: [u8; 16],
+    /// Titles seen so far; title `n` becomes `REDACTED_TITLE_<n+1>`.
+    titles: Mutex<Vec<String>>,
+}
+
+impl Redactor {
+    pub fn new(mode: RedactMode, project_number: Option<String>) -> Self {
+        Self {
+            mode,
+            project_number,
+            salt: rand::random(),
+            titles: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn text(&self, text: &str) -> String {
+        let text = redact_project_number(text, self.project_number.as_deref());
+        let mut text = redact_emails(&text, &self.salt);
+        if self.mode == RedactMode::Aggressive {
+            let titles = self.titles.lock().unwrap();
+            // Longest first, so a title that contains another is replaced whole.
+            let mut known: Vec<_> = titles.iter().enumerate().collect();
+            known.sort_by_key(|(_, title)| std::cmp::Reverse(title.len()));
+            for (index, title) in known {
+                text = text.replace(title.as_str(), &title_placeholder(index));
+            }
+        }
+        text
+    }
+
+    pub fn json(&self, value: &Value) -> Value {
+        match value {
+            Value::String(text) => Value::String(self.text(text)),
+            Value::Array(items) => Value::Array(items.iter().map(|item| self.json(item)).collect()),
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(key, field)| {
+                        let field = match field {
+                            Value::String(title)
+                                if self.mode == RedactMode::Aggressive
+                                    && TITLE_KEYS.contains(&key.as_str())
+                                    && !title.is_empty() =>
+                            {
+                                Value::String(self.title(title))
+                            }
+                            _ => self.json(field),
+                        };
+                        (key.clone(), field)
+                    })
+                    .collect(),
+            ),
+            _ => value.clone(),
+        }
+    }
+
+    fn title(&self, title: &str) -> String {
+        let mut titles = self.titles.lock().unwrap();
+        let index = match titles.iter().position(|seen| seen == title) {
+            Some(index) => index,
+            None => {
+                titles.push(title.to_string());
+                titles.len() - 1
+            }
+        };
+        title_placeholder(index)
+    }
+}
+
+fn title_placeholder(index: usize) -> String {
+    format!("REDACTED_TITLE_{}", index + 1)
+}
+
+/// Output filter for `--redact`: everything written through it is redacted first.
+pub struct RedactingSink {
+    inner: Box<dyn OutputSink>,
+    redactor: Arc<Redactor>,
+}
+
+impl RedactingSink {
+    pub fn new(inner: Box<dyn OutputSink>, redactor: Arc<Redactor>) -> Self {
+        Self { inner, redactor }
+    }
+}
+
+impl OutputSink for RedactingSink {
+    fn out(&self, line: &str) {
+        self.inner.out(&self.redactor.text(line));
+    }
+
+    fn err(&self, line: &str) {
+        self.inner.err(&self.redactor.text(line));
+    }
+
+    /// A JSON document (such as an API response passed through as-is) is redacted like
+    /// [`OutputSink::json`] and written compactly; other text line by line; binary data passes
+    /// through unchanged.
+    fn out_raw(&self, bytes: &[u8]) -> io::Result<()> {
+        let Ok(text) = std::str::from_utf8(bytes) else {
+            return self.inner.out_raw(bytes);
+        };
+        let body = text.trim_end();
+        match serde_json::from_str::<Value>(body) {
+            Ok(value) => {
+                let mut redacted = self.redactor.json(&value).to_string();
+                redacted.push_str(&text[body.len()..]);
+                self.inner.out_raw(redacted.as_bytes())
+            }
+            Err(_) => self.inner.out_raw(self.redactor.text(text).as_bytes()),
+        }
+    }
+
+    fn json(&self, value: &Value) {
+        self.inner.json(&self.redactor.json(value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::output::CapturedOutput;
+    use serde_json::json;
+
+    #[test]
+    fn project_number_is_replaced_only_where_it_stands_alone() {
+        let text = "projects/123456/locations/global 123456 1234567 project 123456,123456";
+        assert_eq!(
+            redact_project_number(text, Some("123456")),
+            "projects/PROJECT_NUMBER/locations/global PROJECT_NUMBER 1234567 project PROJECT_NUMBER,PROJECT_NUMBER"
+        );
+        assert_eq!(
+            redact_project_number("projects/42/notebooks/abc", None),
+            "projects/PROJECT_NUMBER/notebooks/abc"
+        );
+    }
+
+    #[test]
+    fn emails_get_stable_salted_placeholders() {
+        let text =
+            "owner: Ada.L@example.com, viewer: bob@corp.example.co.uk, again: ada.l@example.com";
+        let redacted = redact_emails(text, b"salt");
+        assert!(!redacted.contains("example"), "{redacted}");
+
+        let placeholders: Vec<&str> = EMAIL.find_iter(&redacted).map(|m| m.as_str()).collect();
+        assert_eq!(placeholders.len(), 3);
+        assert_eq!(placeholders[0], placeholders[2]);
+        assert_ne!(placeholders[0], placeholders[1]);
+        assert!(placeholders[0].starts_with("user-"));
+
+        assert_eq!(redact_emails(text, b"salt"), redacted);
+        assert_ne!(redact_emails(text, b"other"), redacted);
+    }
+
+    #[test]
+    fn aggressive_mode_numbers_titles_in_order_of_appearance() {
+        let redactor = Redactor::new(RedactMode::Aggressive, Some("123456".to_string()));
+        let redacted = redactor.json(&json!({
+            "notebooks": [
+                { "title": "Q3 plans", "name": "projects/123456/locations/global/notebooks/a" },
+                { "title": "Hiring", "sources": [{ "title": "Q3 plans" }] },
+            ]
+        }));
+        assert_eq!(
+            redacted,
+            json!({
+                "notebooks": [
+                    { "title": "REDACTED_TITLE_1", "name": "projects/PROJECT_NUMBER/locations/global/notebooks/a" },
+                    { "title": "REDACTED_TITLE_2", "sources": [{ "title": "REDACTED_TITLE_1" }] },
+                ]
+            })
+        );
+        assert_eq!(redactor.text("Deleted Hiring"), "Deleted REDACTED_TITLE_2");
+
+        let standard = Redactor::new(RedactMode::Standard, None);
+        assert_eq!(
+            standard.json(&json!({ "title": "Hiring" }))["title"],
+            "Hiring"
+        );
+    }
+
+    #[test]
+    fn redacting_sink_filters_every_stream() {
+        struct Shared(Arc<CapturedOutput>);
+        impl OutputSink for Shared {
+            fn out(&self, line: &str) {
+                self.0.out(line)
+            }
+            fn err(&self, line: &str) {
+                self.0.err(line)
+            }
+            fn out_raw(&self, bytes: &[u8]) -> io::Result<()> {
+                self.0.out_raw(bytes)
+            }
+        }
+
+        let captured = Arc::new(CapturedOutput::default());
+        let sink = RedactingSink::new(
+            Box::new(Shared(Arc::clone(&captured))),
+            Arc::new(Redactor::new(RedactMode::Standard, Some("987".to_string()))),
+        );
+        sink.out("project 987");
+        sink.json(&json!({ "parent": "projects/987" }));
+        sink.out_raw(b"{\"title\": \"projects/987\"}\n").unwrap();
+        sink.out_raw(b"987 ok\n").unwrap();
+        sink.err("token for a@b.io");
+
+        assert_eq!(
+            captured.stdout(),
+            concat!(
+                "project PROJECT_NUMBER\n",
+                "{\n  \"parent\": \"projects/PROJECT_NUMBER\"\n}\n",
+                "{\"title\":\"projects/PROJECT_NUMBER\"}\n",
+                "PROJECT_NUMBER ok\n",
+            )
+        );
+        assert!(captured.stderr().starts_with("token for user-"));
+    }
+}
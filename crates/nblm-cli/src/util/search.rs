@@ -0,0 +1,159 @@
+use std::fmt;
+use std::ops::Range;
+
+use anyhow::{Context, Result};
+use regex::{Regex, RegexBuilder};
+
+/// Case-insensitive matcher for a plain-text or regular-expression query.
+#[derive(Debug, Clone)]
+pub struct Matcher {
+    regex: Regex,
+}
+
+impl Matcher {
+    pub fn new(query: &str, is_regex: bool) -> Result<Self> {
+        let pattern = if is_regex {
+            query.to_string()
+        } else {
+            regex::escape(query)
+        };
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(true)
+            .build()
+            .with_context(|| format!("invalid --regex pattern '{query}'"))?;
+        Ok(Self { regex })
+    }
+
+    /// Byte range of the first non-empty match in `text`.
+    pub fn find(&self, text: &str) -> Option<Range<usize>> {
+        self.regex
+            .find_iter(text)
+            .find(|found| !found.is_empty())
+            .map(|found| found.range())
+    }
+}
+
+/// A match with up to a fixed number of characters of context on either side.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    pub before: String,
+    pub matched: String,
+    pub after: String,
+}
+
+impl fmt::Display for Snippet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}{}", self.before, self.matched, self.after)
+    }
+}
+
+/// Extract `context` characters around `range` (a byte range on char boundaries) of `text`.
+///
+/// Context is counted in characters, not bytes, so multi-byte text is never split. Newlines
+/// are flattened to spaces and truncated ends are marked with an ellipsis.
+pub fn snippet(text: &str, range: Range<usize>, context: usize) -> Snippet {
+    let head = &text[..range.start];
+    let tail = &text[range.end..];
+
+    let before_start = match context {
+        0 => head.len(),
+        n => head
+            .char_indices()
+            .rev()
+            .nth(n - 1)
+            .map(|(index, _)| index)
+            .unwrap_or(0),
+    };
+    let after_end = tail
+        .char_indices()
+        .nth(context)
+        .map(|(index, _)| index)
+        .unwrap_or(tail.len());
+
+    let mut before = flatten(&head[before_start..]);
+    if before_start > 0 {
+        before.insert(0, '…');
+    }
+    let mut after = flatten(&tail[..after_end]);
+    if after_end < tail.len() {
+        after.push('…');
+    }
+
+    Snippet {
+        before,
+        matched: flatten(&text[range]),
+        after,
+    }
+}
+
+fn flatten(text: &str) -> String {
+    text.chars()
+        .map(|c| if c == '\n' || c == '\r' { ' ' } else { c })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matcher_is_case_insensitive_and_escapes_plain_queries() {
+        let matcher = Matcher::new("rust (book)", false).unwrap();
+        assert_eq!(matcher.find("The RUST (Book) guide"), Some(4..15));
+        assert_eq!(matcher.find("rust book"), None);
+    }
+
+    #[test]
+    fn matcher_supports_regex() {
+        let matcher = Matcher::new(r"v\d+\.\d+", true).unwrap();
+        assert_eq!(matcher.find("release V1.42 notes"), Some(8..13));
+        assert!(Matcher::new("(unclosed", true).is_err());
+    }
+
+    #[test]
+    fn matcher_skips_empty_matches() {
+        let matcher = Matcher::new("x*", true).unwrap();
+        assert_eq!(matcher.find("abxxc"), Some(2..4));
+        assert_eq!(matcher.find("abc"), None);
+    }
+
+    #[test]
+    fn snippet_adds_context_and_ellipses() {
+        let text = "the quick brown fox jumps over the lazy dog";
+        let range = Matcher::new("fox", false).unwrap().find(text).unwrap();
+        let snip = snippet(text, range, 6);
+        assert_eq!(snip.before, "…brown ");
+        assert_eq!(snip.matched, "fox");
+        assert_eq!(snip.after, " jumps…");
+    }
+
+    #[test]
+    fn snippet_without_truncation() {
+        let snip = snippet("a fox b", 2..5, 10);
+        assert_eq!(snip.to_string(), "a fox b");
+    }
+
+    #[test]
+    fn snippet_counts_characters_not_bytes() {
+        let text = "日本語のテキストで検索する例です";
+        let range = Matcher::new("検索", false).unwrap().find(text).unwrap();
+        let snip = snippet(text, range, 2);
+        assert_eq!(snip.before, "…トで");
+        assert_eq!(snip.matched, "検索");
+        assert_eq!(snip.after, "する…");
+    }
+
+    #[test]
+    fn snippet_with_zero_context() {
+        let snip = snippet("one two three", 4..7, 0);
+        assert_eq!(snip.before, "…");
+        assert_eq!(snip.matched, "two");
+        assert_eq!(snip.after, "…");
+    }
+
+    #[test]
+    fn snippet_flattens_newlines() {
+        let snip = snippet("line one\nmatch\nline three", 9..14, 4);
+        assert_eq!(snip.to_string(), "…one match lin…");
+    }
+}
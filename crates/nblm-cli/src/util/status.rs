@@ -0,0 +1,106 @@
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use nblm_core::RetryEvent;
+use reqwest::StatusCode;
+
+/// Erase the current terminal line and return the cursor to column 0.
+const CLEAR_LINE: &str = "\r\x1b[2K";
+
+/// Whether an in-place status line is currently drawn on stderr.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Show `message` on stderr.
+///
+/// With `inline`, the message replaces the previous status line instead of starting a new one;
+/// anything written through [`StdStreams`](super::output::StdStreams) clears it first.
+pub fn show(message: &str, inline: bool) {
+    let _streams = super::output::lock_streams();
+    let mut stderr = io::stderr().lock();
+    if inline {
+        let _ = write!(stderr, "{CLEAR_LINE}{message}");
+        let _ = stderr.flush();
+        ACTIVE.store(true, Ordering::SeqCst);
+    } else {
+        clear_locked(&mut stderr);
+        let _ = writeln!(stderr, "{message}");
+    }
+}
+
+/// Remove the in-place status line, if one is drawn.
+pub fn clear() {
+    clear_locked(&mut io::stderr().lock());
+}
+
+fn clear_locked(stderr: &mut impl Write) {
+    if ACTIVE.swap(false, Ordering::SeqCst) {
+        let _ = write!(stderr, "{CLEAR_LINE}");
+        let _ = stderr.flush();
+    }
+}
+
+/// Callback for [`nblm_core::RetryConfig::with_on_retry`] that reports each retry.
+pub fn retry_reporter(inline: bool) -> impl Fn(&RetryEvent) + Send + Sync + 'static {
+    move |event| show(&describe_retry(event), inline)
+}
+
+pub fn describe_retry(event: &RetryEvent) -> String {
+    let reason = match event.status {
+        Some(StatusCode::TOO_MANY_REQUESTS) => "rate limited".to_string(),
+        Some(status) => format!("server returned {}", status.as_u16()),
+        None => "request failed".to_string(),
+    };
+    format!(
+        "{reason}, retrying in {} (attempt {}/{})...",
+        format_delay(event.delay),
+        event.attempt,
+        event.max_retries
+    )
+}
+
+/// Whole seconds (rounded up) from one second on, milliseconds below that.
+pub fn format_delay(delay: Duration) -> String {
+    if delay >= Duration::from_secs(1) {
+        format!("{}s", delay.as_millis().div_ceil(1000))
+    } else {
+        format!("{}ms", delay.as_millis())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(status: Option<StatusCode>, delay: Duration) -> RetryEvent {
+        RetryEvent {
+            attempt: 3,
+            max_retries: 6,
+            delay,
+            status,
+            retry_after: None,
+        }
+    }
+
+    #[test]
+    fn describe_retry_names_the_reason() {
+        assert_eq!(
+            describe_retry(&event(
+                Some(StatusCode::TOO_MANY_REQUESTS),
+                Duration::from_secs(8)
+            )),
+            "rate limited, retrying in 8s (attempt 3/6)..."
+        );
+        assert_eq!(
+            describe_retry(&event(
+                Some(StatusCode::SERVICE_UNAVAILABLE),
+                Duration::from_millis(1500)
+            )),
+            "server returned 503, retrying in 2s (attempt 3/6)..."
+        );
+        assert_eq!(
+            describe_retry(&event(None, Duration::from_millis(250))),
+            "request failed, retrying in 250ms (attempt 3/6)..."
+        );
+    }
+}
@@ -0,0 +1,278 @@
+//! Sources piped into `sources add --stdin`.
+
+use std::fmt;
+use std::io::{BufRead, Read};
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::ValueEnum;
+use nblm_core::models::enterprise::source::UserContent;
+use serde::de::{self, DeserializeSeed, SeqAccess, Visitor};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::util::validate::{source_name_arg, validate_url};
+
+/// How `sources add --stdin` reads its input.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum StdinFormat {
+    /// One web URL per line
+    #[default]
+    Lines,
+    /// A JSON array of URL strings or of source objects
+    Json,
+}
+
+impl StdinFormat {
+    pub fn read(self, reader: impl BufRead) -> Result<Vec<UserContent>> {
+        match self {
+            StdinFormat::Lines => read_lines(reader),
+            StdinFormat::Json => read_json(reader),
+        }
+    }
+}
+
+/// One web source per non-empty trimmed line.
+pub fn read_lines(reader: impl BufRead) -> Result<Vec<UserContent>> {
+    let mut contents = Vec::new();
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line.context("failed to read URLs from stdin")?;
+        let url = line.trim();
+        if url.is_empty() {
+            continue;
+        }
+        validate_url(url).map_err(|err| anyhow!("stdin line {}: {err:#}", idx + 1))?;
+        contents.push(UserContent::web(url.to_string(), None));
+    }
+    Ok(contents)
+}
+
+/// A JSON array whose items are either URL strings (web sources) or [`StdinSource`] objects.
+///
+/// The array is read item by item, so only the converted sources are held in memory, not the
+/// document. The first invalid item stops the read; the error names its index.
+pub fn read_json(reader: impl Read) -> Result<Vec<UserContent>> {
+    let mut invalid = None;
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    let parsed = SourcesSeed {
+        invalid: &mut invalid,
+    }
+    .deserialize(&mut deserializer)
+    .and_then(|contents| deserializer.end().map(|()| contents));
+    match (parsed, invalid) {
+        (Ok(contents), _) => Ok(contents),
+        (Err(_), Some(err)) => Err(err),
+        (Err(err), None) => Err(anyhow!("stdin is not a JSON array of sources: {err}")),
+    }
+}
+
+/// An object item of `--stdin-format json`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum StdinSource {
+    Web {
+        url: String,
+        #[serde(default, alias = "title")]
+        name: Option<String>,
+    },
+    Text {
+        text: String,
+        #[serde(default, alias = "title")]
+        name: Option<String>,
+    },
+    Video {
+        url: String,
+    },
+    Drive {
+        document_id: String,
+        mime_type: String,
+        #[serde(default, alias = "title")]
+        name: Option<String>,
+    },
+}
+
+/// Check one array item and build its source the way the matching flags would.
+fn convert(item: Value) -> Result<UserContent> {
+    let source = match item {
+        Value::String(url) => StdinSource::Web { url, name: None },
+        Value::Object(_) => serde_json::from_value(item)?,
+        other => bail!("expected a URL string or an object, got {other}"),
+    };
+    Ok(match source {
+        StdinSource::Web { url, name } => {
+            validate_url(&url)?;
+            UserContent::web(url, source_name_arg(name.as_deref(), "name")?)
+        }
+        StdinSource::Text { text, name } => {
+            if text.trim().is_empty() {
+                bail!("text cannot be empty");
+            }
+            UserContent::text(text, source_name_arg(name.as_deref(), "name")?)
+        }
+        StdinSource::Video { url } => {
+            validate_url(&url)?;
+            UserContent::video(url)
+        }
+        StdinSource::Drive {
+            document_id,
+            mime_type,
+            name,
+        } => {
+            if document_id.trim().is_empty() {
+                bail!("document_id cannot be empty");
+            }
+            UserContent::google_drive(
+                document_id,
+                mime_type,
+                source_name_arg(name.as_deref(), "name")?,
+            )
+        }
+    })
+}
+
+/// Converts array items as serde_json reads them. A conversion error is stored in `invalid` and
+/// ends the parse, so it reaches the user without serde_json's position suffix.
+struct SourcesSeed<'a> {
+    invalid: &'a mut Option<anyhow::Error>,
+}
+
+impl<'de> DeserializeSeed<'de> for SourcesSeed<'_> {
+    type Value = Vec<UserContent>;
+
+    fn deserialize<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for SourcesSeed<'_> {
+    type Value = Vec<UserContent>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an array of URL strings or source objects")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut contents = Vec::new();
+        while let Some(item) = seq.next_element::<Value>()? {
+            match convert(item) {
+                Ok(content) => contents.push(content),
+                Err(err) => {
+                    let index = contents.len();
+                    *self.invalid = Some(anyhow!("stdin entry {index}: {err:#}"));
+                    return Err(de::Error::custom("invalid entry"));
+                }
+            }
+        }
+        Ok(contents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn json(input: &str) -> Result<Vec<UserContent>> {
+        read_json(input.as_bytes())
+    }
+
+    /// `UserContent` has no `PartialEq`; compare the request bodies instead.
+    fn assert_contents(actual: &[UserContent], expected: &[UserContent]) {
+        assert_eq!(
+            serde_json::to_value(actual).unwrap(),
+            serde_json::to_value(expected).unwrap()
+        );
+    }
+
+    #[test]
+    fn lines_are_web_urls() {
+        let contents =
+            read_lines("https://a.example\n\n  https://b.example  \n".as_bytes()).unwrap();
+        assert_contents(
+            &contents,
+            &[
+                UserContent::web("https://a.example".to_string(), None),
+                UserContent::web("https://b.example".to_string(), None),
+            ],
+        );
+        let err = read_lines("https://a.example\nftp://b.example\n".as_bytes()).unwrap_err();
+        assert_eq!(
+            format!("{err:#}"),
+            "stdin line 2: unsupported URL scheme: ftp"
+        );
+    }
+
+    #[test]
+    fn a_bare_array_of_strings_is_web_sources() {
+        let contents = json(r#"["https://a.example", "https://b.example"]"#).unwrap();
+        assert_contents(
+            &contents,
+            &[
+                UserContent::web("https://a.example".to_string(), None),
+                UserContent::web("https://b.example".to_string(), None),
+            ],
+        );
+        assert!(json("[]").unwrap().is_empty());
+    }
+
+    #[test]
+    fn objects_carry_their_type_and_fields() {
+        let contents = json(
+            r#"[
+                {"type": "web", "url": "https://a.example", "title": " A "},
+                {"type": "text", "text": "Meeting notes", "name": "Notes"},
+                {"type": "video", "url": "https://www.youtube.com/watch?v=abc"},
+                {"type": "drive", "document_id": "doc1", "mime_type": "application/pdf"},
+                "https://b.example"
+            ]"#,
+        )
+        .unwrap();
+        assert_contents(
+            &contents,
+            &[
+                UserContent::web("https://a.example".to_string(), Some("A".to_string())),
+                UserContent::text("Meeting notes".to_string(), Some("Notes".to_string())),
+                UserContent::video("https://www.youtube.com/watch?v=abc".to_string()),
+                UserContent::google_drive("doc1".to_string(), "application/pdf".to_string(), None),
+                UserContent::web("https://b.example".to_string(), None),
+            ],
+        );
+    }
+
+    #[test]
+    fn invalid_entries_are_named_by_index() {
+        let message = |input: &str| format!("{:#}", json(input).unwrap_err());
+        assert_eq!(
+            message(r#"["https://a.example", "not a url"]"#),
+            "stdin entry 1: invalid URL not a url: relative URL without a base"
+        );
+        assert_eq!(
+            message(r#"["https://a.example", {"type": "pdf", "url": "https://b.example"}]"#),
+            "stdin entry 1: unknown variant `pdf`, expected one of `web`, `text`, `video`, `drive`"
+        );
+        assert_eq!(
+            message(r#"[{"type": "text", "text": "  "}]"#),
+            "stdin entry 0: text cannot be empty"
+        );
+        assert_eq!(
+            message(r#"[{"type": "web", "link": "https://a.example"}]"#),
+            "stdin entry 0: unknown field `link`, expected one of `url`, `name`, `title`"
+        );
+        assert_eq!(
+            message("[42]"),
+            "stdin entry 0: expected a URL string or an object, got 42"
+        );
+    }
+
+    #[test]
+    fn documents_that_are_not_arrays_are_refused() {
+        let err = json(r#"{"url": "https://a.example"}"#).unwrap_err();
+        assert!(format!("{err:#}")
+            .starts_with("stdin is not a JSON array of sources: invalid type: map"));
+        let err = json(r#"["https://a.example""#).unwrap_err();
+        assert!(format!("{err:#}").contains("EOF while parsing"));
+        let err = json(r#"[] []"#).unwrap_err();
+        assert!(format!("{err:#}").contains("trailing characters"));
+    }
+}
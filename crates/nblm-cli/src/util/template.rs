@@ -0,0 +1,403 @@
+//! `{placeholder}` templates expanded client-side, such as `notebooks create --expand-title`.
+//!
+//! Placeholders:
+//!
+//! - `{date}` is the local date as `%Y-%m-%d`; `{date:FORMAT}` formats the local time with a
+//!   strftime-style `FORMAT` (see [`STRFTIME_SPECIFIERS`]).
+//! - `{time}` is the local time as `%H:%M`; `{time:FORMAT}` is the same as `{date:FORMAT}`.
+//! - `{user}` is the active gcloud account, or `USER` when there is none.
+//! - `{uuid}` is a random UUID (version 4).
+//!
+//! `{{` and `}}` stand for literal braces.
+
+use std::fmt::{self, Write as _};
+use std::ops::Range;
+
+use anyhow::{anyhow, Result};
+use rand::RngCore;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use crate::util::parse;
+
+/// Hidden test hook: the current time, as an RFC 3339 timestamp with the offset to use.
+const FAKE_NOW_ENV: &str = "NBLM_FAKE_NOW";
+
+/// strftime conversions understood in `{date:FORMAT}` and `{time:FORMAT}`.
+pub const STRFTIME_SPECIFIERS: &str =
+    "%Y %y %m %d %e %H %I %M %S %p %j %a %A %b %B %u %F %T %R %z %%";
+
+/// A parsed template; parsing checks every placeholder and format string.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    /// A strftime format string, already checked.
+    Time(String),
+    User,
+    Uuid,
+}
+
+/// Values substituted when rendering a [`Template`].
+pub struct Values {
+    pub now: OffsetDateTime,
+    /// Only needed when the template has `{user}`.
+    pub user: Option<String>,
+}
+
+impl Values {
+    /// The current local time (or `NBLM_FAKE_NOW`), without a user.
+    pub fn now() -> Result<Self> {
+        let now = match std::env::var(FAKE_NOW_ENV) {
+            Ok(value) => OffsetDateTime::parse(&value, &Rfc3339)
+                .map_err(|err| anyhow!("invalid {FAKE_NOW_ENV} '{value}': {err}"))?,
+            Err(_) => OffsetDateTime::now_utc().to_offset(parse::local_offset()),
+        };
+        Ok(Self { now, user: None })
+    }
+}
+
+/// A template that does not parse, with the part of it that is wrong.
+#[derive(Debug)]
+pub struct TemplateError {
+    template: String,
+    span: Range<usize>,
+    message: String,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let column = self.template[..self.span.start].chars().count();
+        let width = self.template[self.span.clone()].chars().count().max(1);
+        writeln!(f, "invalid template: {}", self.message)?;
+        writeln!(f, "  {}", self.template)?;
+        write!(f, "  {}{}", " ".repeat(column), "^".repeat(width))
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+impl Template {
+    pub fn parse(template: &str) -> std::result::Result<Self, TemplateError> {
+        let error = |span: Range<usize>, message: String| TemplateError {
+            template: template.to_string(),
+            span,
+            message,
+        };
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut rest = template.char_indices().peekable();
+        while let Some((start, c)) = rest.next() {
+            match c {
+                '{' if rest.peek().is_some_and(|&(_, next)| next == '{') => {
+                    rest.next();
+                    literal.push('{');
+                }
+                '}' if rest.peek().is_some_and(|&(_, next)| next == '}') => {
+                    rest.next();
+                    literal.push('}');
+                }
+                '}' => {
+                    return Err(error(
+                        start..start + 1,
+                        "unmatched '}' (write '}}' for a literal brace)".to_string(),
+                    ))
+                }
+                '{' => {
+                    let Some(end) = template[start..].find('}').map(|end| start + end) else {
+                        return Err(error(
+                            start..template.len(),
+                            "unclosed '{' (write '{{' for a literal brace)".to_string(),
+                        ));
+                    };
+                    while rest.peek().is_some_and(|&(index, _)| index <= end) {
+                        rest.next();
+                    }
+                    let inner = start + 1;
+                    let part = placeholder(&template[inner..end]).map_err(|(span, message)| {
+                        let span = span
+                            .map_or(start..end + 1, |span| inner + span.start..inner + span.end);
+                        error(span, message)
+                    })?;
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(part);
+                }
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+        Ok(Self { parts })
+    }
+
+    /// Whether rendering needs [`Values::user`].
+    pub fn needs_user(&self) -> bool {
+        self.parts.contains(&Part::User)
+    }
+
+    pub fn render(&self, values: &Values) -> Result<String> {
+        let mut out = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => out.push_str(text),
+                Part::Time(format) => strftime(&mut out, format, values.now),
+                Part::User => out.push_str(
+                    values
+                        .user
+                        .as_deref()
+                        .ok_or_else(|| anyhow!("{{user}} needs a gcloud account or USER"))?,
+                ),
+                Part::Uuid => out.push_str(&uuid_v4()),
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// The part for the placeholder `inner` (the text between the braces). Errors carry the span
+/// in `inner` of the problem, or `None` for the whole placeholder.
+fn placeholder(inner: &str) -> std::result::Result<Part, (Option<Range<usize>>, String)> {
+    let (name, format) = match inner.split_once(':') {
+        Some((name, format)) => (name, Some(format)),
+        None => (inner, None),
+    };
+    match (name, format) {
+        ("date", None) => Ok(Part::Time("%Y-%m-%d".to_string())),
+        ("time", None) => Ok(Part::Time("%H:%M".to_string())),
+        ("date" | "time", Some("")) => Err((None, "empty format string".to_string())),
+        ("date" | "time", Some(format)) => match check_strftime(format) {
+            Ok(()) => Ok(Part::Time(format.to_string())),
+            Err((span, message)) => {
+                let offset = name.len() + 1;
+                Err((Some(offset + span.start..offset + span.end), message))
+            }
+        },
+        ("user" | "uuid", Some(_)) => Err((None, format!("{{{name}}} takes no format"))),
+        ("user", None) => Ok(Part::User),
+        ("uuid", None) => Ok(Part::Uuid),
+        _ => Err((
+            None,
+            format!(
+                "unknown placeholder {{{inner}}}; expected {{date}}, {{date:FORMAT}}, {{time}}, {{user}} or {{uuid}}"
+            ),
+        )),
+    }
+}
+
+/// Check that every `%` in `format` starts a supported conversion; errors carry the span of
+/// the bad conversion.
+fn check_strftime(format: &str) -> std::result::Result<(), (Range<usize>, String)> {
+    let mut chars = format.char_indices();
+    while let Some((index, c)) = chars.next() {
+        if c != '%' {
+            continue;
+        }
+        match chars.next() {
+            Some((_, spec)) if is_supported(spec) => {}
+            Some((_, spec)) => {
+                return Err((
+                    index..index + 1 + spec.len_utf8(),
+                    format!("unsupported format '%{spec}'; use one of {STRFTIME_SPECIFIERS}"),
+                ))
+            }
+            None => return Err((index..index + 1, "format ends with a lone '%'".to_string())),
+        }
+    }
+    Ok(())
+}
+
+fn is_supported(spec: char) -> bool {
+    STRFTIME_SPECIFIERS
+        .split(' ')
+        .any(|known| known.ends_with(spec))
+}
+
+/// Append `now` formatted with a `format` that passed [`check_strftime`].
+fn strftime(out: &mut String, format: &str, now: OffsetDateTime) {
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        let hour12 = match now.hour() % 12 {
+            0 => 12,
+            hour => hour,
+        };
+        // Writing to a String cannot fail.
+        let _ = match chars.next() {
+            Some('Y') => write!(out, "{}", now.year()),
+            Some('y') => write!(out, "{:02}", now.year().rem_euclid(100)),
+            Some('m') => write!(out, "{:02}", u8::from(now.month())),
+            Some('d') => write!(out, "{:02}", now.day()),
+            Some('e') => write!(out, "{:>2}", now.day()),
+            Some('H') => write!(out, "{:02}", now.hour()),
+            Some('I') => write!(out, "{hour12:02}"),
+            Some('M') => write!(out, "{:02}", now.minute()),
+            Some('S') => write!(out, "{:02}", now.second()),
+            Some('p') => write!(out, "{}", if now.hour() < 12 { "AM" } else { "PM" }),
+            Some('j') => write!(out, "{:03}", now.ordinal()),
+            Some('a') => write!(out, "{}", &now.weekday().to_string()[..3]),
+            Some('A') => write!(out, "{}", now.weekday()),
+            Some('b') => write!(out, "{}", &now.month().to_string()[..3]),
+            Some('B') => write!(out, "{}", now.month()),
+            Some('u') => write!(out, "{}", now.weekday().number_from_monday()),
+            Some('F') => write!(
+                out,
+                "{}-{:02}-{:02}",
+                now.year(),
+                u8::from(now.month()),
+                now.day()
+            ),
+            Some('T') => write!(
+                out,
+                "{:02}:{:02}:{:02}",
+                now.hour(),
+                now.minute(),
+                now.second()
+            ),
+            Some('R') => write!(out, "{:02}:{:02}", now.hour(), now.minute()),
+            Some('z') => {
+                let (hours, minutes, _) = now.offset().as_hms();
+                let sign = if now.offset().is_negative() { '-' } else { '+' };
+                write!(out, "{sign}{:02}{:02}", hours.abs(), minutes.abs())
+            }
+            Some(other) => write!(out, "{other}"),
+            None => Ok(()),
+        };
+    }
+}
+
+fn uuid_v4() -> String {
+    let mut bytes = [0u8; 16];
+    rand::rng().fill_bytes(&mut bytes);
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::datetime;
+
+    fn values() -> Values {
+        Values {
+            now: datetime!(2025-03-04 09:05:07 +01:00),
+            user: Some("ada@example.com".to_string()),
+        }
+    }
+
+    fn expand(template: &str) -> String {
+        Template::parse(template)
+            .unwrap()
+            .render(&values())
+            .unwrap()
+    }
+
+    fn parse_error(template: &str) -> String {
+        Template::parse(template).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn placeholders_expand_with_their_default_formats() {
+        assert_eq!(expand("Standup {date}"), "Standup 2025-03-04");
+        assert_eq!(expand("{time} sync"), "09:05 sync");
+        assert_eq!(expand("Notes by {user}"), "Notes by ada@example.com");
+        assert_eq!(expand("No placeholders"), "No placeholders");
+    }
+
+    #[test]
+    fn format_strings_use_strftime_conversions() {
+        let cases = [
+            ("{date:%Y-%m-%d}", "2025-03-04"),
+            ("{date:%a %d %b %y}", "Tue 04 Mar 25"),
+            ("{date:%A, %B %e}", "Tuesday, March  4"),
+            ("{date:week day %u, day %j}", "week day 2, day 063"),
+            ("{time:%H:%M:%S}", "09:05:07"),
+            ("{time:%I%p}", "09AM"),
+            ("{date:%F %T %z}", "2025-03-04 09:05:07 +0100"),
+            ("{date:%R}", "09:05"),
+            ("{date:100%%}", "100%"),
+        ];
+        for (template, expected) in cases {
+            assert_eq!(expand(template), expected, "{template}");
+        }
+    }
+
+    #[test]
+    fn doubled_braces_are_literal() {
+        assert_eq!(expand("{{date}} is {date}"), "{date} is 2025-03-04");
+        assert_eq!(expand("set {{a, b}}"), "set {a, b}");
+    }
+
+    #[test]
+    fn uuids_are_random_version_4() {
+        let first = expand("{uuid}");
+        let second = expand("{uuid}");
+        assert_ne!(first, second);
+        assert_eq!(first.len(), 36);
+        assert_eq!(&first[14..15], "4");
+        assert!("89ab".contains(&first[19..20]), "{first}");
+    }
+
+    #[test]
+    fn unknown_placeholders_are_pointed_at() {
+        assert_eq!(
+            parse_error("Standup {dat}"),
+            "invalid template: unknown placeholder {dat}; expected {date}, {date:FORMAT}, {time}, {user} or {uuid}\n  Standup {dat}\n          ^^^^^"
+        );
+        assert!(parse_error("{user:upper}").contains("{user} takes no format"));
+    }
+
+    #[test]
+    fn invalid_formats_are_pointed_at() {
+        assert_eq!(
+            parse_error("Daily {date:%Y-%Q}"),
+            format!("invalid template: unsupported format '%Q'; use one of {STRFTIME_SPECIFIERS}\n  Daily {{date:%Y-%Q}}\n                 ^^")
+        );
+        assert!(parse_error("{date:%}").contains("format ends with a lone '%'"));
+        assert!(parse_error("{date:}").contains("empty format string"));
+    }
+
+    #[test]
+    fn stray_braces_are_rejected() {
+        assert_eq!(
+            parse_error("Notes {date"),
+            "invalid template: unclosed '{' (write '{{' for a literal brace)\n  Notes {date\n        ^^^^^"
+        );
+        assert_eq!(
+            parse_error("Notes }"),
+            "invalid template: unmatched '}' (write '}}' for a literal brace)\n  Notes }\n        ^"
+        );
+    }
+
+    #[test]
+    fn user_is_required_only_when_used() {
+        let template = Template::parse("{user}").unwrap();
+        assert!(template.needs_user());
+        let err = template
+            .render(&Values {
+                user: None,
+                ..values()
+            })
+            .unwrap_err();
+        assert_eq!(err.to_string(), "{user} needs a gcloud account or USER");
+        assert!(!Template::parse("{date}").unwrap().needs_user());
+    }
+}
@@ -0,0 +1,344 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use nblm_core::{Phase, PhaseEvent};
+
+use crate::util::output::OutputSink;
+use crate::util::payload::{CallTiming, PhaseTiming, TimingsReport, TimingsSummary};
+
+/// Phase durations collected for `--timings`, fed by [`nblm_core::NblmClient::with_observer`].
+#[derive(Debug)]
+pub struct Timings {
+    started: Instant,
+    events: Mutex<Vec<PhaseEvent>>,
+}
+
+impl Timings {
+    pub fn start() -> Arc<Self> {
+        Arc::new(Self {
+            started: Instant::now(),
+            events: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Observer for [`nblm_core::NblmClient::with_observer`] that records into `self`.
+    pub fn observer(self: &Arc<Self>) -> impl Fn(&PhaseEvent) + Send + Sync + 'static {
+        let timings = Arc::clone(self);
+        move |event| timings.record(event.clone())
+    }
+
+    pub fn record(&self, event: PhaseEvent) {
+        self.events.lock().unwrap().push(event);
+    }
+
+    /// Print the summary: a table on stderr, or a `timings` JSON object on stderr with `--json`
+    /// so stdout still holds only the command's own document.
+    pub fn report(&self, out: &dyn OutputSink, json_mode: bool) {
+        let summary = summarize(&self.events.lock().unwrap(), self.started.elapsed());
+        if json_mode {
+            let report = TimingsReport {
+                timings: summary.to_payload(),
+            };
+            out.err(&serde_json::to_string_pretty(&report).unwrap());
+        } else {
+            for line in summary.table() {
+                out.err(&line);
+            }
+        }
+    }
+}
+
+/// Total time and number of occurrences of one kind of phase.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Total {
+    pub count: usize,
+    pub elapsed: Duration,
+}
+
+impl Total {
+    fn add(&mut self, elapsed: Duration) {
+        self.count += 1;
+        self.elapsed += elapsed;
+    }
+}
+
+/// Requests to one method and path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Call {
+    pub method: String,
+    pub path: String,
+    pub total: Total,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Summary {
+    pub wall: Duration,
+    pub token_fetch: Total,
+    pub api: Total,
+    pub retry_wait: Total,
+    pub file_io: Total,
+    /// Wall time not covered by any phase: argument handling, output, JSON decoding.
+    pub other: Duration,
+    /// HTTP attempts grouped by method and path, in order of first use.
+    pub calls: Vec<Call>,
+}
+
+pub fn summarize(events: &[PhaseEvent], wall: Duration) -> Summary {
+    let mut token_fetch = Total::default();
+    let mut api = Total::default();
+    let mut retry_wait = Total::default();
+    let mut file_io = Total::default();
+    let mut calls: Vec<Call> = Vec::new();
+
+    for event in events {
+        match &event.phase {
+            Phase::TokenFetch => token_fetch.add(event.elapsed),
+            Phase::RetryWait => retry_wait.add(event.elapsed),
+            Phase::FileRead { .. } => file_io.add(event.elapsed),
+            Phase::Http { method, path, .. } => {
+                api.add(event.elapsed);
+                let method = method.as_str();
+                match calls
+                    .iter_mut()
+                    .find(|call| call.method == method && call.path == *path)
+                {
+                    Some(call) => call.total.add(event.elapsed),
+                    None => {
+                        let mut total = Total::default();
+                        total.add(event.elapsed);
+                        calls.push(Call {
+                            method: method.to_string(),
+                            path: path.clone(),
+                            total,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    let measured = token_fetch.elapsed + api.elapsed + retry_wait.elapsed + file_io.elapsed;
+    Summary {
+        wall,
+        token_fetch,
+        api,
+        retry_wait,
+        file_io,
+        other: wall.saturating_sub(measured),
+        calls,
+    }
+}
+
+impl Summary {
+    fn phases(&self) -> [(&'static str, Option<usize>, Duration); 5] {
+        [
+            (
+                "token fetch",
+                Some(self.token_fetch.count),
+                self.token_fetch.elapsed,
+            ),
+            ("api", Some(self.api.count), self.api.elapsed),
+            (
+                "retry wait",
+                Some(self.retry_wait.count),
+                self.retry_wait.elapsed,
+            ),
+            ("file io", Some(self.file_io.count), self.file_io.elapsed),
+            ("other", None, self.other),
+        ]
+    }
+
+    /// Share of the wall time, in whole percent.
+    pub fn percent(&self, elapsed: Duration) -> u128 {
+        if self.wall.is_zero() {
+            return 0;
+        }
+        (elapsed.as_micros() * 100 + self.wall.as_micros() / 2) / self.wall.as_micros()
+    }
+
+    fn table(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("Timings (total {}):", format_ms(self.wall)),
+            format!(
+                "  {:<12} {:>5} {:>10} {:>5}",
+                "phase", "count", "time", "share"
+            ),
+        ];
+        for (name, count, elapsed) in self.phases() {
+            let count = count.map(|n| n.to_string()).unwrap_or_default();
+            lines.push(format!(
+                "  {name:<12} {count:>5} {:>10} {:>4}%",
+                format_ms(elapsed),
+                self.percent(elapsed)
+            ));
+        }
+        if !self.calls.is_empty() {
+            lines.push("  requests:".to_string());
+            for call in &self.calls {
+                lines.push(format!(
+                    "    {:>5} {:>10}  {} {}",
+                    call.total.count,
+                    format_ms(call.total.elapsed),
+                    call.method,
+                    call.path
+                ));
+            }
+        }
+        lines
+    }
+
+    fn to_payload(&self) -> TimingsSummary<'_> {
+        let phase = |total: Total| PhaseTiming {
+            count: Some(total.count),
+            ms: millis(total.elapsed),
+        };
+        TimingsSummary {
+            total_ms: millis(self.wall),
+            token_fetch: phase(self.token_fetch),
+            api: phase(self.api),
+            retry_wait: phase(self.retry_wait),
+            file_io: phase(self.file_io),
+            other: PhaseTiming {
+                count: None,
+                ms: millis(self.other),
+            },
+            calls: self
+                .calls
+                .iter()
+                .map(|call| CallTiming {
+                    method: &call.method,
+                    path: &call.path,
+                    count: call.total.count,
+                    ms: millis(call.total.elapsed),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Milliseconds with microsecond precision.
+fn millis(duration: Duration) -> f64 {
+    duration.as_micros() as f64 / 1000.0
+}
+
+fn format_ms(duration: Duration) -> String {
+    format!("{:.1}ms", millis(duration))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::Method;
+
+    fn event(phase: Phase, ms: u64) -> PhaseEvent {
+        PhaseEvent {
+            phase,
+            elapsed: Duration::from_millis(ms),
+        }
+    }
+
+    fn http(method: Method, path: &str) -> Phase {
+        Phase::Http {
+            method,
+            path: path.to_string(),
+            status: Some(200),
+        }
+    }
+
+    #[test]
+    fn summarize_totals_each_phase_and_the_remainder() {
+        let events = [
+            event(Phase::TokenFetch, 20),
+            event(
+                Phase::FileRead {
+                    path: "a.pdf".into(),
+                },
+                5,
+            ),
+            event(http(Method::POST, "/upload"), 300),
+            event(Phase::RetryWait, 100),
+            event(Phase::TokenFetch, 10),
+            event(http(Method::POST, "/upload"), 200),
+            event(http(Method::GET, "/notebooks/nb1"), 50),
+        ];
+
+        let summary = summarize(&events, Duration::from_millis(1000));
+
+        assert_eq!(summary.token_fetch.count, 2);
+        assert_eq!(summary.token_fetch.elapsed, Duration::from_millis(30));
+        assert_eq!(summary.api.count, 3);
+        assert_eq!(summary.api.elapsed, Duration::from_millis(550));
+        assert_eq!(summary.retry_wait.count, 1);
+        assert_eq!(summary.retry_wait.elapsed, Duration::from_millis(100));
+        assert_eq!(summary.file_io.count, 1);
+        assert_eq!(summary.file_io.elapsed, Duration::from_millis(5));
+        assert_eq!(summary.other, Duration::from_millis(315));
+        assert_eq!(summary.percent(summary.api.elapsed), 55);
+    }
+
+    #[test]
+    fn summarize_groups_calls_by_method_and_path_in_first_use_order() {
+        let events = [
+            event(http(Method::GET, "/b"), 10),
+            event(http(Method::POST, "/a"), 20),
+            event(http(Method::GET, "/b"), 30),
+            event(http(Method::POST, "/b"), 40),
+        ];
+
+        let summary = summarize(&events, Duration::from_millis(100));
+
+        let calls: Vec<_> = summary
+            .calls
+            .iter()
+            .map(|call| (call.method.as_str(), call.path.as_str(), call.total.count))
+            .collect();
+        assert_eq!(
+            calls,
+            [("GET", "/b", 2), ("POST", "/a", 1), ("POST", "/b", 1)]
+        );
+        assert_eq!(summary.calls[0].total.elapsed, Duration::from_millis(40));
+    }
+
+    #[test]
+    fn other_never_goes_negative() {
+        // Requests sent concurrently add up to more than the wall time.
+        let summary = summarize(
+            &[event(http(Method::GET, "/a"), 120)],
+            Duration::from_millis(100),
+        );
+        assert_eq!(summary.other, Duration::ZERO);
+        assert_eq!(summary.percent(summary.api.elapsed), 120);
+    }
+
+    #[test]
+    fn percent_of_zero_wall_time_is_zero() {
+        let summary = summarize(&[], Duration::ZERO);
+        assert_eq!(summary.percent(Duration::from_millis(5)), 0);
+        assert!(summary.calls.is_empty());
+    }
+
+    #[test]
+    fn table_lists_phases_then_requests() {
+        let summary = summarize(
+            &[
+                event(Phase::TokenFetch, 2),
+                event(http(Method::GET, "/v1alpha/x"), 90),
+            ],
+            Duration::from_millis(100),
+        );
+        assert_eq!(
+            summary.table(),
+            [
+                "Timings (total 100.0ms):",
+                "  phase        count       time share",
+                "  token fetch      1      2.0ms    2%",
+                "  api              1     90.0ms   90%",
+                "  retry wait       0      0.0ms    0%",
+                "  file io          0      0.0ms    0%",
+                "  other                   8.0ms    8%",
+                "  requests:",
+                "        1     90.0ms  GET /v1alpha/x",
+            ]
+        );
+    }
+}
@@ -1,4 +1,6 @@
 use anyhow::{anyhow, bail, Result};
+use nblm_core::models::enterprise::name::NotebookName;
+use nblm_core::models::enterprise::source::normalize_source_name;
 
 pub fn validate_url(url: &str) -> Result<()> {
     let parsed = url::Url::parse(url).map_err(|err| anyhow!("invalid URL {url}: {err}"))?;
@@ -8,6 +10,8 @@ pub fn validate_url(url: &str) -> Result<()> {
     }
 }
 
+/// Pair each value with the name given at the same position, if any, normalized with
+/// [`source_name_arg`].
 pub fn pair_with_names(
     values: &[String],
     names: &[String],
@@ -16,21 +20,39 @@ pub fn pair_with_names(
     if names.len() > values.len() {
         bail!("{field} count exceeds number of values");
     }
-    Ok(values
+    values
         .iter()
         .enumerate()
         .map(|(idx, value)| {
-            let name = names.get(idx).and_then(|s| {
-                let trimmed = s.trim();
-                if trimmed.is_empty() {
-                    None
-                } else {
-                    Some(trimmed.to_string())
-                }
-            });
-            (value.clone(), name)
+            let name = source_name_arg(names.get(idx).map(String::as_str), field)?;
+            Ok((value.clone(), name))
         })
-        .collect())
+        .collect()
+}
+
+/// A source name flag, trimmed; blank means none. Over-long names are refused naming `field`.
+pub fn source_name_arg(name: Option<&str>, field: &str) -> Result<Option<String>> {
+    normalize_source_name(name).map_err(|err| match err {
+        nblm_core::Error::Validation(message) => anyhow!("{field}: {message}"),
+        other => other.into(),
+    })
+}
+
+/// Refuse notebook names that point outside the configured `projects/.../locations/...` parent
+/// unless the caller explicitly opted in. The override only relaxes that comparison: every name
+/// must still be a full `projects/.../notebooks/...` resource name.
+pub fn ensure_same_parent(names: &[String], parent: &str, allow_cross_project: bool) -> Result<()> {
+    for raw in names {
+        let name = NotebookName::parse(raw)?;
+        let name_parent = name.parent();
+        if !allow_cross_project && name_parent != parent {
+            bail!(
+                "notebook {raw} belongs to {name_parent}, but the configured parent is {parent}. \
+                 Pass --allow-cross-project to delete it anyway."
+            );
+        }
+    }
+    Ok(())
 }
 
 #[cfg(test)]
@@ -54,6 +76,19 @@ mod tests {
         assert!(pairs[0].1.is_none());
     }
 
+    #[test]
+    fn over_long_name_errors_with_its_flag() {
+        let values = vec!["https://example.com".to_string()];
+        let names = vec!["x".repeat(501)];
+        let err = pair_with_names(&values, &names, "--web-name").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "--web-name: source name is 501 characters long (maximum 500)"
+        );
+        let names = vec!["x".repeat(500)];
+        assert!(pair_with_names(&values, &names, "--web-name").is_ok());
+    }
+
     #[test]
     fn len_mismatch_errors() {
         let values = vec!["https://example.com".to_string()];
@@ -61,6 +96,61 @@ mod tests {
         assert!(pair_with_names(&values, &names, "--name").is_err());
     }
 
+    const PARENT: &str = "projects/123/locations/global";
+
+    #[test]
+    fn ensure_same_parent_accepts_matching_names() {
+        let names = vec![
+            "projects/123/locations/global/notebooks/a".to_string(),
+            "projects/123/locations/global/notebooks/b".to_string(),
+        ];
+        assert!(ensure_same_parent(&names, PARENT, false).is_ok());
+    }
+
+    #[test]
+    fn ensure_same_parent_rejects_other_project() {
+        let names = vec!["projects/999/locations/global/notebooks/a".to_string()];
+        let err = ensure_same_parent(&names, PARENT, false)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("projects/999/locations/global"));
+        assert!(err.contains(PARENT));
+        assert!(err.contains("--allow-cross-project"));
+    }
+
+    #[test]
+    fn ensure_same_parent_rejects_other_location() {
+        let names = vec!["projects/123/locations/us/notebooks/a".to_string()];
+        assert!(ensure_same_parent(&names, PARENT, false).is_err());
+    }
+
+    #[test]
+    fn ensure_same_parent_allows_override() {
+        let names = vec!["projects/999/locations/us/notebooks/a".to_string()];
+        assert!(ensure_same_parent(&names, PARENT, true).is_ok());
+    }
+
+    #[test]
+    fn ensure_same_parent_rejects_bare_ids() {
+        let names = vec!["nb1".to_string()];
+        let err = ensure_same_parent(&names, PARENT, false)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("invalid notebook name"));
+    }
+
+    #[test]
+    fn ensure_same_parent_override_still_rejects_bare_ids() {
+        let names = vec![
+            "projects/999/locations/global/notebooks/a".to_string(),
+            "nb1".to_string(),
+        ];
+        let err = ensure_same_parent(&names, PARENT, true)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("invalid notebook name"));
+    }
+
     #[test]
     fn validate_url_accepts_http() {
         assert!(validate_url("http://example.com").is_ok());
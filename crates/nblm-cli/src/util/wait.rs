@@ -0,0 +1,24 @@
+use std::time::Duration;
+
+use nblm_core::Error;
+
+/// Exit code when `--wait` gave up after `--wait-timeout` with the operation still running
+/// (as `timeout(1)` reports it).
+pub const WAIT_TIMED_OUT_EXIT_CODE: i32 = 124;
+
+/// Exit code when a `--wait` ended because the operation reached a failed state.
+pub const WAIT_FAILED_EXIT_CODE: i32 = 3;
+
+/// A `--wait` duration in whole seconds for summaries, e.g. `4m 12s`.
+pub fn format_elapsed(elapsed: Duration) -> String {
+    humantime::format_duration(Duration::from_secs(elapsed.as_secs())).to_string()
+}
+
+/// The exit code for an error from a `--wait`, or `None` for any other error.
+pub fn exit_code(err: &anyhow::Error) -> Option<i32> {
+    match err.downcast_ref::<Error>()? {
+        Error::WaitTimedOut { .. } => Some(WAIT_TIMED_OUT_EXIT_CODE),
+        Error::OperationFailed { .. } => Some(WAIT_FAILED_EXIT_CODE),
+        _ => None,
+    }
+}
@@ -0,0 +1,94 @@
+use std::io::IsTerminal;
+
+/// Width used when stdout is not a terminal or `COLUMNS` is unset.
+pub const DEFAULT_WIDTH: usize = 80;
+
+/// Columns to wrap text output at: `COLUMNS` when stdout is a terminal, else
+/// [`DEFAULT_WIDTH`].
+pub fn terminal_width() -> usize {
+    if !std::io::stdout().is_terminal() {
+        return DEFAULT_WIDTH;
+    }
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .filter(|width| *width > 0)
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+/// Break `text` into lines of at most `width` characters, each starting with `indent`.
+///
+/// Lines break at spaces; a word longer than the line is left whole. Newlines in `text` are kept
+/// as paragraph breaks, and blank lines between paragraphs stay blank.
+pub fn wrap(text: &str, width: usize, indent: &str) -> Vec<String> {
+    let available = width.saturating_sub(indent.chars().count()).max(1);
+    let mut lines = Vec::new();
+    for paragraph in text.trim_end().lines() {
+        let mut line = String::new();
+        let mut length = 0;
+        for word in paragraph.split_whitespace() {
+            let word_length = word.chars().count();
+            if length > 0 && length + 1 + word_length > available {
+                lines.push(format!("{indent}{line}"));
+                line.clear();
+                length = 0;
+            }
+            if length > 0 {
+                line.push(' ');
+                length += 1;
+            }
+            line.push_str(word);
+            length += word_length;
+        }
+        if line.is_empty() {
+            lines.push(String::new());
+        } else {
+            lines.push(format!("{indent}{line}"));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_paragraph_wraps_at_word_boundaries() {
+        let text = "This notebook collects quarterly reports and analyst notes about revenue growth in the European market.";
+        let lines = wrap(text, 30, "  ");
+        assert_eq!(
+            lines,
+            [
+                "  This notebook collects",
+                "  quarterly reports and",
+                "  analyst notes about revenue",
+                "  growth in the European",
+                "  market.",
+            ]
+        );
+        assert!(lines.iter().all(|line| line.chars().count() <= 30));
+    }
+
+    #[test]
+    fn embedded_newlines_start_new_paragraphs() {
+        let text = "First point.\n\nSecond point is a little longer.\n";
+        assert_eq!(
+            wrap(text, 20, ""),
+            ["First point.", "", "Second point is a", "little longer."]
+        );
+    }
+
+    #[test]
+    fn words_longer_than_the_line_are_kept_whole() {
+        assert_eq!(
+            wrap("see https://example.com/a/very/long/path now", 12, ""),
+            ["see", "https://example.com/a/very/long/path", "now"]
+        );
+    }
+
+    #[test]
+    fn width_counts_characters_not_bytes() {
+        assert_eq!(wrap("日本語 の 要約", 7, ""), ["日本語 の", "要約"]);
+    }
+}
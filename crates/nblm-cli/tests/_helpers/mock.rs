@@ -251,6 +251,52 @@ impl MockApi {
             .await;
     }
 
+    /// Stub for the 403 `SERVICE_DISABLED` a project without the Discovery Engine API gets,
+    /// using the payload vendored in nblm-core.
+    pub async fn stub_notebooks_recent_service_disabled(&self, project: &str, location: &str) {
+        let path_str = format!(
+            "/v1alpha/projects/{}/locations/{}/notebooks:listRecentlyViewed",
+            project, location
+        );
+
+        Mock::given(method("GET"))
+            .and(path(path_str))
+            .respond_with(ResponseTemplate::new(403).set_body_raw(
+                include_str!("../../../nblm-core/tests/fixtures/errors/service_disabled.json"),
+                "application/json",
+            ))
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Stub for sources:batchCreate that always answers 429 with `Retry-After: 30`
+    pub async fn stub_sources_batch_create_persistent_429(
+        &self,
+        project: &str,
+        location: &str,
+        notebook_id: &str,
+    ) {
+        let path_str = format!(
+            "/v1alpha/projects/{}/locations/{}/notebooks/{}/sources:batchCreate",
+            project, location, notebook_id
+        );
+
+        Mock::given(method("POST"))
+            .and(path(path_str))
+            .and(header("authorization", "Bearer DUMMY_TOKEN"))
+            .respond_with(
+                ResponseTemplate::new(429)
+                    .insert_header("Retry-After", "30")
+                    .set_body_json(json!({
+                        "error": {
+                            "message": "Too Many Requests"
+                        }
+                    })),
+            )
+            .mount(&self.server)
+            .await;
+    }
+
     /// Stub for POST /v1alpha/projects/{project}/locations/{location}/notebooks:batchDelete
     pub async fn stub_notebooks_batch_delete(&self, project: &str, location: &str) {
         let path_str = format!(
@@ -266,6 +312,37 @@ impl MockApi {
             .await;
     }
 
+    /// Stub for GET /v1alpha/projects/{project}/locations/{location}/notebooks/{notebook_id}
+    /// listing sources with the given IDs
+    pub async fn stub_notebook_get(
+        &self,
+        project: &str,
+        location: &str,
+        notebook_id: &str,
+        source_ids: &[&str],
+    ) {
+        let name = format!(
+            "projects/{}/locations/{}/notebooks/{}",
+            project, location, notebook_id
+        );
+        let sources: Vec<_> = source_ids
+            .iter()
+            .map(|id| json!({ "name": format!("{name}/sources/{id}"), "title": format!("Source {id}") }))
+            .collect();
+
+        Mock::given(method("GET"))
+            .and(path(format!("/v1alpha/{name}")))
+            .and(header("authorization", "Bearer DUMMY_TOKEN"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "name": name,
+                "notebookId": notebook_id,
+                "title": "Notebook",
+                "sources": sources,
+            })))
+            .mount(&self.server)
+            .await;
+    }
+
     /// Stub for POST /v1alpha/projects/{project}/locations/{location}/notebooks/{notebook_id}/sources:batchDelete
     pub async fn stub_sources_batch_delete(
         &self,
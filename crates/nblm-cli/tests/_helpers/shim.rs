@@ -0,0 +1,89 @@
+//! One in-memory API server shared by every test in a binary.
+//!
+//! Tests get a [`Project`] of their own, so they can run in parallel against the same server
+//! without `#[serial]` and without seeing each other's notebooks. Use `MockApi` instead when a
+//! test needs exact request matching, custom headers or failure sequences.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+use assert_cmd::Command;
+use nblm_core::models::enterprise::source::{NotebookSource, UserContent};
+use nblm_core::test_support::{InMemoryApi, InMemoryBackend};
+use nblm_core::NblmClient;
+use wiremock::MockServer;
+
+use super::cmd::CommonArgs;
+
+struct Shim {
+    api: InMemoryApi,
+    server: MockServer,
+}
+
+fn shim() -> &'static Shim {
+    static SHIM: OnceLock<Shim> = OnceLock::new();
+    SHIM.get_or_init(|| {
+        // The server runs on its own thread; this runtime only sets it up, so it can be
+        // created from a test's runtime or none.
+        std::thread::spawn(|| {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap();
+            let api = InMemoryApi::new();
+            let server = runtime.block_on(api.serve());
+            Shim { api, server }
+        })
+        .join()
+        .unwrap()
+    })
+}
+
+/// A project of the shared in-memory API that no other test uses.
+#[allow(dead_code)]
+pub struct Project {
+    pub args: CommonArgs,
+    /// The project's notebooks, to seed before running the CLI or inspect afterwards.
+    pub backend: InMemoryBackend,
+}
+
+#[allow(dead_code)]
+impl Project {
+    pub fn new() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(900_000);
+        let args = CommonArgs {
+            project_number: NEXT.fetch_add(1, Ordering::Relaxed).to_string(),
+            ..CommonArgs::default()
+        };
+        let backend = shim().api.backend(&args.project_number, &args.location);
+        Self { args, backend }
+    }
+
+    /// The `nblm` binary pointed at this project on the shared server.
+    pub fn nblm(&self) -> Command {
+        let mut cmd = super::cmd::nblm();
+        let base_url = format!("{}/v1alpha", shim().server.uri());
+        self.args.with_base_url(&mut cmd, &base_url);
+        cmd
+    }
+
+    /// `projects/{number}/locations/{location}/notebooks/{notebook_id}`.
+    pub fn notebook_name(&self, notebook_id: &str) -> String {
+        format!(
+            "projects/{}/locations/{}/notebooks/{notebook_id}",
+            self.args.project_number, self.args.location
+        )
+    }
+
+    /// Add a text source per title to a stored notebook, returning the new sources.
+    pub fn add_sources(&self, notebook_id: &str, titles: &[&str]) -> Vec<NotebookSource> {
+        let client = NblmClient::with_backends_for_testing(self.backend.clone());
+        let contents = titles
+            .iter()
+            .map(|title| UserContent::text("Notes".to_string(), Some(title.to_string())))
+            .collect();
+        futures::executor::block_on(client.add_sources(notebook_id, contents))
+            .unwrap()
+            .sources
+    }
+}
@@ -0,0 +1,104 @@
+mod _helpers;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use predicates::prelude::*;
+use serial_test::serial;
+use tempfile::NamedTempFile;
+use wiremock::matchers::{body_json, method, path, query_param};
+use wiremock::{Mock, ResponseTemplate};
+
+fn parent(args: &CommonArgs) -> String {
+    format!(
+        "/v1alpha/projects/{}/locations/{}",
+        args.project_number, args.location
+    )
+}
+
+#[tokio::test]
+#[serial]
+async fn api_get_sends_query_and_prints_raw_response() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    Mock::given(method("GET"))
+        .and(path(format!(
+            "{}/notebooks:listRecentlyViewed",
+            parent(&args)
+        )))
+        .and(query_param("pageSize", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "notebooks": [{"title": "Unmodelled", "brandNewField": true}]
+        })))
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "api",
+        "get",
+        "notebooks:listRecentlyViewed",
+        "--query",
+        "pageSize=2",
+    ]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"brandNewField\": true"));
+}
+
+#[tokio::test]
+#[serial]
+async fn api_post_reads_body_from_file() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    Mock::given(method("POST"))
+        .and(path(format!("{}/notebooks/nb1:newVerb", parent(&args))))
+        .and(body_json(serde_json::json!({"mode": "fast"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+
+    let body = NamedTempFile::new().unwrap();
+    std::fs::write(body.path(), r#"{"mode": "fast"}"#).unwrap();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["api", "POST", "notebooks/nb1:newVerb", "--body"])
+        .arg(format!("@{}", body.path().display()));
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("{}"));
+}
+
+#[tokio::test]
+#[serial]
+async fn api_refuses_paths_outside_the_base_url() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["api", "GET", "/../../admin"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("outside the base URL"));
+
+    let requests = mock.server.received_requests().await.unwrap_or_default();
+    assert!(requests.is_empty(), "no HTTP request should be sent");
+}
+
+#[test]
+fn api_is_hidden_from_help() {
+    let mut cmd = _helpers::cmd::nblm();
+    cmd.arg("--help");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("notebooks"))
+        .stdout(predicate::str::contains("Call an API endpoint").not());
+}
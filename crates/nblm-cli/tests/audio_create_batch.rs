@@ -0,0 +1,142 @@
+mod _helpers;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use serde_json::{json, Value};
+use serial_test::serial;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+const PARENT: &str = "/v1alpha/projects/123456/locations/global/notebooks";
+
+fn overview(status: &str) -> Value {
+    json!({ "audioOverview": { "audioOverviewId": "default", "status": status } })
+}
+
+async fn stub_create(mock: &MockApi, notebook_id: &str, response: ResponseTemplate) {
+    Mock::given(method("POST"))
+        .and(path(format!("{PARENT}/{notebook_id}/audioOverviews")))
+        .respond_with(response)
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+}
+
+async fn stub_status(mock: &MockApi, notebook_id: &str, status: &str, times: u64, priority: u8) {
+    Mock::given(method("GET"))
+        .and(path(format!(
+            "{PARENT}/{notebook_id}/audioOverviews/default"
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(overview(status)))
+        .up_to_n_times(times)
+        .with_priority(priority)
+        .mount(&mock.server)
+        .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn create_batch_waits_for_each_notebook_and_reports_failures() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    // nb-ready is ACTIVE on the first poll; nb-slow needs two; nb-broken cannot be created.
+    for notebook_id in ["nb-ready", "nb-slow"] {
+        stub_create(
+            &mock,
+            notebook_id,
+            ResponseTemplate::new(200).set_body_json(overview("AUDIO_OVERVIEW_STATUS_IN_PROGRESS")),
+        )
+        .await;
+    }
+    stub_create(
+        &mock,
+        "nb-broken",
+        ResponseTemplate::new(400).set_body_json(json!({
+            "error": { "code": 400, "message": "Notebook has no sources", "status": "FAILED_PRECONDITION" }
+        })),
+    )
+    .await;
+    stub_status(&mock, "nb-ready", "AUDIO_OVERVIEW_STATUS_ACTIVE", 1, 1).await;
+    stub_status(&mock, "nb-slow", "AUDIO_OVERVIEW_STATUS_IN_PROGRESS", 1, 1).await;
+    stub_status(&mock, "nb-slow", "AUDIO_OVERVIEW_STATUS_ACTIVE", 1, 2).await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "audio",
+        "create-batch",
+        "--notebook-id",
+        "nb-ready",
+        "--notebook-id",
+        "nb-broken",
+        "--notebook-id",
+        "nb-slow",
+        "--wait",
+        "--poll-interval",
+        "10ms",
+    ]);
+    let output = cmd.assert().failure().get_output().clone();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    let rows: Vec<Vec<&str>> = stdout
+        .lines()
+        .map(|line| line.split_whitespace().collect())
+        .collect();
+    assert_eq!(rows.len(), 4, "{stdout}");
+    assert_eq!(rows[0], ["NOTEBOOK", "STATE", "DURATION"]);
+    assert_eq!(rows[1][..2], ["nb-ready", "AUDIO_OVERVIEW_STATUS_ACTIVE"]);
+    assert_eq!(rows[2], ["nb-broken", "FAILED", "-"]);
+    assert_eq!(rows[3][..2], ["nb-slow", "AUDIO_OVERVIEW_STATUS_ACTIVE"]);
+    assert!(stderr.contains("Failed for notebook nb-broken"), "{stderr}");
+    assert!(stderr.contains("1 of 3 notebook(s) failed"), "{stderr}");
+
+    let polls = |notebook_id: &str| {
+        let status_path = format!("{PARENT}/{notebook_id}/audioOverviews/default");
+        move |request: &&wiremock::Request| {
+            request.method.as_str() == "GET" && request.url.path() == status_path
+        }
+    };
+    let requests = mock.server.received_requests().await.unwrap();
+    assert_eq!(requests.iter().filter(polls("nb-ready")).count(), 1);
+    assert_eq!(requests.iter().filter(polls("nb-slow")).count(), 2);
+    assert_eq!(requests.iter().filter(polls("nb-broken")).count(), 0);
+}
+
+#[tokio::test]
+#[serial]
+async fn create_batch_without_wait_reports_the_initial_state_as_json() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    for notebook_id in ["nb1", "nb2"] {
+        stub_create(
+            &mock,
+            notebook_id,
+            ResponseTemplate::new(200).set_body_json(overview("AUDIO_OVERVIEW_STATUS_IN_PROGRESS")),
+        )
+        .await;
+    }
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "--json",
+        "audio",
+        "create-batch",
+        "--notebook-id",
+        "nb1",
+        "--notebook-id",
+        "nb2",
+    ]);
+    let output = cmd.assert().success().get_output().clone();
+    let payload: Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    let notebooks = payload["notebooks"].as_array().unwrap();
+    assert_eq!(notebooks.len(), 2);
+    for (entry, notebook_id) in notebooks.iter().zip(["nb1", "nb2"]) {
+        assert_eq!(entry["notebook_id"], notebook_id);
+        assert_eq!(entry["state"], "AUDIO_OVERVIEW_STATUS_IN_PROGRESS");
+        assert!(entry["duration_secs"].is_number());
+        assert!(entry.get("error").is_none());
+    }
+}
@@ -0,0 +1,255 @@
+mod _helpers;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use serde_json::{json, Value};
+use serial_test::serial;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+const OVERVIEW: &str =
+    "/v1alpha/projects/123456/locations/global/notebooks/nb1/audioOverviews/default";
+const AUDIO: &[u8] = b"ID3\x04\x00fake mp3 frames";
+
+async fn stub_overview(mock: &MockApi, overview: Value, times: u64, priority: u8) {
+    Mock::given(method("GET"))
+        .and(path(OVERVIEW))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(json!({ "audioOverview": overview })),
+        )
+        .up_to_n_times(times)
+        .with_priority(priority)
+        .mount(&mock.server)
+        .await;
+}
+
+fn ready(mock: &MockApi) -> Value {
+    json!({
+        "status": "AUDIO_OVERVIEW_STATUS_COMPLETE",
+        "audio": { "audioUri": format!("{}/media/nb1.mp3", mock.server.uri()) },
+    })
+}
+
+#[tokio::test]
+#[serial]
+async fn download_writes_the_audio_file() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_overview(&mock, ready(&mock), 1, 1).await;
+    Mock::given(method("GET"))
+        .and(path("/media/nb1.mp3"))
+        .and(header("authorization", "Bearer DUMMY_TOKEN"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(AUDIO.to_vec(), "audio/mpeg"))
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("overview.mp3");
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["audio", "download", "--notebook-id", "nb1", "--out"])
+        .arg(&out);
+
+    let output = cmd.assert().success().get_output().clone();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("Downloaded audio overview to") && stdout.contains("(20 B, audio/mpeg)"),
+        "{stdout}"
+    );
+    assert_eq!(std::fs::read(&out).unwrap(), AUDIO);
+    assert!(!dir.path().join("overview.mp3.part").exists());
+}
+
+#[tokio::test]
+#[serial]
+async fn download_waits_for_generation_with_wait() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_overview(
+        &mock,
+        json!({ "status": "AUDIO_OVERVIEW_STATUS_IN_PROGRESS" }),
+        1,
+        1,
+    )
+    .await;
+    stub_overview(&mock, ready(&mock), 1, 2).await;
+    Mock::given(method("GET"))
+        .and(path("/media/nb1.mp3"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(AUDIO.to_vec(), "audio/mpeg"))
+        .mount(&mock.server)
+        .await;
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("overview.mp3");
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "--json",
+        "audio",
+        "download",
+        "--notebook-id",
+        "nb1",
+        "--out",
+    ])
+    .arg(&out)
+    .args(["--wait", "--poll-interval", "10ms"]);
+
+    let output = cmd.assert().success().get_output().clone();
+    let payload: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(payload["bytes"], AUDIO.len());
+    assert_eq!(payload["content_type"], "audio/mpeg");
+    assert_eq!(std::fs::read(&out).unwrap(), AUDIO);
+}
+
+#[tokio::test]
+#[serial]
+async fn download_of_an_unfinished_overview_suggests_wait() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_overview(
+        &mock,
+        json!({ "status": "AUDIO_OVERVIEW_STATUS_IN_PROGRESS" }),
+        1,
+        1,
+    )
+    .await;
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("overview.mp3");
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["audio", "download", "--notebook-id", "nb1", "--out"])
+        .arg(&out);
+
+    let output = cmd.assert().failure().get_output().clone();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("not ready yet (AUDIO_OVERVIEW_STATUS_IN_PROGRESS)")
+            && stderr.contains("re-run with --wait"),
+        "{stderr}"
+    );
+    assert!(!out.exists());
+}
+
+#[tokio::test]
+#[serial]
+async fn download_rejects_non_audio_responses_and_keeps_no_partial_file() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_overview(&mock, ready(&mock), 1, 1).await;
+    Mock::given(method("GET"))
+        .and(path("/media/nb1.mp3"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw("<html>sign in</html>", "text/html"))
+        .mount(&mock.server)
+        .await;
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("overview.mp3");
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["audio", "download", "--notebook-id", "nb1", "--out"])
+        .arg(&out);
+
+    let output = cmd.assert().failure().get_output().clone();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("returned text/html instead of audio"),
+        "{stderr}"
+    );
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+}
+
+#[tokio::test]
+#[serial]
+async fn download_keeps_an_existing_file_without_force() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("overview.mp3");
+    std::fs::write(&out, b"keep me").unwrap();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["audio", "download", "--notebook-id", "nb1", "--out"])
+        .arg(&out);
+
+    let output = cmd.assert().failure().get_output().clone();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("re-run with --force"), "{stderr}");
+    assert_eq!(std::fs::read(&out).unwrap(), b"keep me");
+    assert!(mock.server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn wait_timeout_exits_with_the_timeout_code_while_still_generating() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_overview(
+        &mock,
+        json!({ "status": "AUDIO_OVERVIEW_STATUS_IN_PROGRESS" }),
+        u64::MAX,
+        1,
+    )
+    .await;
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("overview.mp3");
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["audio", "download", "--notebook-id", "nb1", "--out"])
+        .arg(&out)
+        .args([
+            "--wait",
+            "--poll-interval",
+            "10ms",
+            "--wait-timeout",
+            "100ms",
+        ]);
+
+    let output = cmd.assert().code(124).get_output().clone();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("audio generation still AUDIO_OVERVIEW_STATUS_IN_PROGRESS after 100ms"),
+        "{stderr}"
+    );
+    assert!(!out.exists());
+    // The first status read plus at least two polls before giving up.
+    assert!(mock.server.received_requests().await.unwrap().len() >= 3);
+}
+
+#[tokio::test]
+#[serial]
+async fn wait_on_a_failed_generation_exits_with_the_failure_code() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_overview(
+        &mock,
+        json!({ "status": "AUDIO_OVERVIEW_STATUS_IN_PROGRESS" }),
+        1,
+        1,
+    )
+    .await;
+    stub_overview(
+        &mock,
+        json!({ "status": "AUDIO_OVERVIEW_STATUS_FAILED" }),
+        1,
+        2,
+    )
+    .await;
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("overview.mp3");
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["audio", "download", "--notebook-id", "nb1", "--out"])
+        .arg(&out)
+        .args(["--wait", "--poll-interval", "10ms"]);
+
+    let output = cmd.assert().code(3).get_output().clone();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("audio generation ended with AUDIO_OVERVIEW_STATUS_FAILED"),
+        "{stderr}"
+    );
+}
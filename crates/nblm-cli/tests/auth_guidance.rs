@@ -0,0 +1,99 @@
+mod _helpers;
+
+use _helpers::mock::MockApi;
+use predicates::prelude::*;
+use serial_test::serial;
+use tempfile::TempDir;
+
+#[tokio::test]
+#[serial]
+async fn missing_credentials_list_auth_methods_and_next_steps() {
+    let mock = MockApi::start().await;
+    let bin = TempDir::new().unwrap();
+    let config = TempDir::new().unwrap();
+
+    let mut cmd = _helpers::cmd::nblm();
+    cmd.env_clear()
+        .env("PATH", bin.path())
+        .env("HOME", config.path())
+        .env("NBLM_CONFIG_DIR", config.path())
+        .env("NBLM_RETRY_FAST", "1")
+        .args([
+            "--auth",
+            "gcloud",
+            "--project-number",
+            "123456",
+            "--base-url",
+            &mock.base_url(),
+            "notebooks",
+            "recent",
+        ]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Failed to execute gcloud command"))
+        .stderr(predicate::str::contains("Auth methods (selected: gcloud):"))
+        .stderr(predicate::str::contains(
+            "  gcloud: binary 'gcloud' not found",
+        ))
+        .stderr(predicate::str::contains("  env: NBLM_ACCESS_TOKEN unset"))
+        .stderr(predicate::str::contains(
+            "  user-oauth: experimental, no stored tokens",
+        ))
+        .stderr(predicate::str::contains(
+            "Run `nblm doctor` to check your setup, or `nblm auth login` to sign in with gcloud.",
+        ));
+
+    let requests = mock.server.received_requests().await.unwrap_or_default();
+    assert!(requests.is_empty(), "no API call without a token");
+}
+
+#[test]
+fn help_needs_no_credentials_or_configuration() {
+    let config = TempDir::new().unwrap();
+
+    let mut cmd = _helpers::cmd::nblm();
+    cmd.env_clear()
+        .env("HOME", config.path())
+        .env("NBLM_CONFIG_DIR", config.path())
+        .args(["notebooks", "--help"]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Usage: nblm notebooks"));
+}
+
+#[tokio::test]
+#[serial]
+async fn user_oauth_without_stored_tokens_fails_fast_when_not_interactive() {
+    let mock = MockApi::start().await;
+    let config = TempDir::new().unwrap();
+
+    let mut cmd = _helpers::cmd::nblm();
+    cmd.env_clear()
+        .env("HOME", config.path())
+        .env("NBLM_CONFIG_DIR", config.path())
+        .env("NBLM_PROFILE_EXPERIMENT", "1")
+        .env("NBLM_OAUTH_CLIENT_ID", "test-client-id")
+        .args([
+            "--auth",
+            "user-oauth",
+            "--project-number",
+            "123456",
+            "--base-url",
+            &mock.base_url(),
+            "notebooks",
+            "recent",
+        ]);
+
+    // stdin is not a terminal here, so the run is non-interactive without the flag.
+    cmd.assert()
+        .code(4)
+        .stderr(predicate::str::contains(
+            "Error: cannot open a browser to sign in with user-oauth (no tokens are stored for project 123456) when running non-interactively; run the command once in a terminal to sign in and store a refresh token, or use --auth env with NBLM_ACCESS_TOKEN",
+        ))
+        .stderr(predicate::str::contains("Opening browser").not());
+
+    let requests = mock.server.received_requests().await.unwrap_or_default();
+    assert!(requests.is_empty(), "no API call without a token");
+}
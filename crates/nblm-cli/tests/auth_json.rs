@@ -0,0 +1,164 @@
+#![cfg(unix)]
+
+mod _helpers;
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+use predicates::prelude::*;
+use serial_test::serial;
+use tempfile::TempDir;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Install a fake `gcloud` that is logged in as user@example.com.
+fn install_fake_gcloud(dir: &Path) {
+    let script = dir.join("gcloud");
+    std::fs::write(
+        &script,
+        r#"#!/bin/sh
+case "$1 $2" in
+  "auth print-access-token") echo "gcloud-token" ;;
+  "config get-value") echo "user@example.com" ;;
+  "auth login") exit 0 ;;
+  *) exit 1 ;;
+esac
+"#,
+    )
+    .expect("write fake gcloud");
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755))
+        .expect("make fake gcloud executable");
+}
+
+async fn tokeninfo_with_scope(scope: &str) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/tokeninfo"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "scope": scope
+        })))
+        .mount(&server)
+        .await;
+    server
+}
+
+/// `nblm auth ...` with PATH restricted to `bin` and an empty config directory.
+fn auth_cmd(bin: &Path, config: &Path, tokeninfo: &MockServer) -> assert_cmd::Command {
+    let mut cmd = _helpers::cmd::nblm();
+    cmd.env("PATH", bin)
+        .env("NBLM_CONFIG_DIR", config)
+        .env(
+            "NBLM_TOKENINFO_ENDPOINT",
+            format!("{}/tokeninfo", tokeninfo.uri()),
+        )
+        .env_remove("NBLM_ACCESS_TOKEN");
+    cmd
+}
+
+fn parse_stdout(output: &std::process::Output) -> serde_json::Value {
+    serde_json::from_slice(&output.stdout).expect("stdout should be JSON")
+}
+
+#[tokio::test]
+#[serial]
+async fn auth_status_json_with_env_token() {
+    let bin = TempDir::new().unwrap();
+    let config = TempDir::new().unwrap();
+    let tokeninfo = tokeninfo_with_scope("https://www.googleapis.com/auth/drive.file").await;
+
+    let mut cmd = auth_cmd(bin.path(), config.path(), &tokeninfo);
+    cmd.env("NBLM_ACCESS_TOKEN", "env-token")
+        .args(["--json", "auth", "status"]);
+    let output = cmd.assert().success().get_output().clone();
+
+    let json = parse_stdout(&output);
+    assert_eq!(json["auth_method"], "env");
+    assert_eq!(json["has_env_token"], true);
+    assert_eq!(json["gcloud_available"], false);
+    assert_eq!(json["gcloud_account"], serde_json::Value::Null);
+    assert_eq!(json["oauth_tokens_stored"], false);
+    assert_eq!(json["drive_scope"], true);
+}
+
+#[tokio::test]
+#[serial]
+async fn auth_status_json_without_credentials() {
+    let bin = TempDir::new().unwrap();
+    let config = TempDir::new().unwrap();
+    let tokeninfo = tokeninfo_with_scope("").await;
+
+    let mut cmd = auth_cmd(bin.path(), config.path(), &tokeninfo);
+    cmd.args(["auth", "status", "--json"]);
+    let output = cmd
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Not authenticated"))
+        .get_output()
+        .clone();
+
+    let json = parse_stdout(&output);
+    assert_eq!(json["auth_method"], serde_json::Value::Null);
+    assert_eq!(json["has_env_token"], false);
+    assert_eq!(json["drive_scope"], serde_json::Value::Null);
+}
+
+#[tokio::test]
+#[serial]
+async fn auth_status_json_reports_gcloud_account() {
+    let bin = TempDir::new().unwrap();
+    let config = TempDir::new().unwrap();
+    install_fake_gcloud(bin.path());
+    let tokeninfo = tokeninfo_with_scope("https://www.googleapis.com/auth/cloud-platform").await;
+
+    let mut cmd = auth_cmd(bin.path(), config.path(), &tokeninfo);
+    cmd.env("NBLM_ACCESS_TOKEN", "env-token")
+        .args(["--json", "auth", "status"]);
+    let output = cmd.assert().success().get_output().clone();
+
+    let json = parse_stdout(&output);
+    assert_eq!(json["auth_method"], "gcloud");
+    assert_eq!(json["gcloud_available"], true);
+    assert_eq!(json["gcloud_account"], "user@example.com");
+    assert_eq!(json["has_env_token"], true);
+    assert_eq!(json["drive_scope"], false);
+}
+
+#[tokio::test]
+#[serial]
+async fn auth_status_text_output_is_unchanged() {
+    let bin = TempDir::new().unwrap();
+    let config = TempDir::new().unwrap();
+    install_fake_gcloud(bin.path());
+    let tokeninfo = tokeninfo_with_scope("").await;
+
+    let mut cmd = auth_cmd(bin.path(), config.path(), &tokeninfo);
+    cmd.args(["auth", "status"]);
+    let output = cmd
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Authenticated"))
+        .stdout(predicate::str::contains("Account: "))
+        .stdout(predicate::str::contains("user@example.com"))
+        .stdout(predicate::str::contains("Backend: gcloud"))
+        .get_output()
+        .clone();
+    assert!(serde_json::from_slice::<serde_json::Value>(&output.stdout).is_err());
+}
+
+#[tokio::test]
+#[serial]
+async fn auth_login_json_reports_result() {
+    let bin = TempDir::new().unwrap();
+    let config = TempDir::new().unwrap();
+    install_fake_gcloud(bin.path());
+    let tokeninfo = tokeninfo_with_scope("").await;
+
+    let mut cmd = auth_cmd(bin.path(), config.path(), &tokeninfo);
+    cmd.args(["--json", "auth", "login", "--drive-access"]);
+    let output = cmd.assert().success().get_output().clone();
+
+    let json = parse_stdout(&output);
+    assert_eq!(json["method"], "gcloud");
+    assert_eq!(json["drive_access"], true);
+    assert_eq!(json["success"], true);
+}
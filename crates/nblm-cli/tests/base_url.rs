@@ -0,0 +1,108 @@
+mod _helpers;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use predicates::prelude::*;
+use serial_test::serial;
+
+#[test]
+#[serial]
+fn malformed_env_base_url_names_the_variable() {
+    let mut cmd = _helpers::cmd::nblm();
+    CommonArgs::default().apply(&mut cmd);
+    cmd.env("NBLM_BASE_URL", "localhost:8080/v1alpha")
+        .args(["notebooks", "recent"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "NBLM_BASE_URL is not a valid base URL",
+        ))
+        .stderr(predicate::str::contains("'localhost:8080/v1alpha'"))
+        .stderr(predicate::str::contains("http://localhost:8080/v1alpha"));
+}
+
+#[test]
+#[serial]
+fn malformed_flag_base_url_names_the_flag() {
+    let mut cmd = _helpers::cmd::nblm();
+    CommonArgs::default().with_base_url(&mut cmd, "example.com/v1alpha");
+    cmd.env_remove("NBLM_BASE_URL")
+        .args(["notebooks", "recent"]);
+
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "--base-url is not a valid base URL",
+    ));
+}
+
+#[tokio::test]
+#[serial]
+async fn base_url_without_version_warns() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    mock.stub_notebooks_recent(&args.project_number, &args.location)
+        .await;
+
+    // The mock serves /v1alpha/..., so this request misses, but the warning comes first.
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.server.uri());
+    cmd.args(["notebooks", "recent"]);
+
+    cmd.assert().stderr(predicate::str::contains(
+        "does not end with an API version segment",
+    ));
+}
+
+#[tokio::test]
+#[serial]
+async fn api_version_env_sets_the_upload_prefix() {
+    use std::io::Write;
+
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    Mock::given(method("POST"))
+        .and(path(format!(
+            "/upload/v1beta/projects/{}/locations/{}/notebooks/nb1/sources:uploadFile",
+            args.project_number, args.location
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "sourceId": {"id": "source-beta"},
+        })))
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    writeln!(file, "hello").unwrap();
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &format!("{}/v1beta", mock.server.uri()));
+    cmd.env("NBLM_API_VERSION", "v1beta").args([
+        "sources",
+        "upload",
+        "--notebook-id",
+        "nb1",
+        "--content-type",
+        "text/plain",
+        "--file",
+    ]);
+    cmd.arg(file.path());
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Created source: source-beta"));
+}
+
+#[test]
+fn unknown_api_version_is_rejected() {
+    let mut cmd = _helpers::cmd::nblm();
+    CommonArgs::default().apply(&mut cmd);
+    cmd.args(["--api-version", "v2", "notebooks", "recent"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("unsupported API version: v2"))
+        .stderr(predicate::str::contains("v1alpha, v1beta"));
+}
@@ -0,0 +1,416 @@
+mod _helpers;
+
+use std::io::Write;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use predicates::prelude::*;
+use serial_test::serial;
+use tempfile::TempDir;
+use wiremock::matchers::{body_partial_json, header, header_regex, method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+fn notebook_names(args: &CommonArgs, ids: &[&str]) -> Vec<String> {
+    ids.iter()
+        .map(|id| {
+            format!(
+                "projects/{}/locations/{}/notebooks/{}",
+                args.project_number, args.location, id
+            )
+        })
+        .collect()
+}
+
+/// Fail the batchDelete call for `failing_name` with 404 and accept everything else.
+async fn stub_delete_with_failure(mock: &MockApi, args: &CommonArgs, failing_name: &str) {
+    let path_str = format!(
+        "/v1alpha/projects/{}/locations/{}/notebooks:batchDelete",
+        args.project_number, args.location
+    );
+    Mock::given(method("POST"))
+        .and(path(path_str.clone()))
+        .and(body_partial_json(
+            serde_json::json!({ "names": [failing_name] }),
+        ))
+        .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+            "error": {"code": 404, "message": "Notebook not found"}
+        })))
+        .with_priority(1)
+        .mount(&mock.server)
+        .await;
+    mock.stub_notebooks_batch_delete(&args.project_number, &args.location)
+        .await;
+}
+
+fn delete_cmd(args: &CommonArgs, mock: &MockApi, names: &[String]) -> assert_cmd::Command {
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["notebooks", "delete"]);
+    for name in names {
+        cmd.args(["--notebook-name", name]);
+    }
+    cmd
+}
+
+async fn delete_request_count(mock: &MockApi) -> usize {
+    mock.server
+        .received_requests()
+        .await
+        .unwrap_or_default()
+        .iter()
+        .filter(|req| req.url.path().ends_with(":batchDelete"))
+        .count()
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_delete_fails_fast_by_default() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let names = notebook_names(&args, &["a", "b", "c"]);
+    stub_delete_with_failure(&mock, &args, &names[1]).await;
+
+    delete_cmd(&args, &mock, &names)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "Deleted 1 notebook(s) successfully",
+        ))
+        .stderr(predicate::str::contains(format!(
+            "Failed to delete {}",
+            names[1]
+        )))
+        .stderr(predicate::str::contains(format!("Skipped {}", names[2])))
+        .stderr(predicate::str::contains("1 of 3 notebook(s) failed"));
+
+    assert_eq!(delete_request_count(&mock).await, 2);
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_delete_keep_going_processes_all() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let names = notebook_names(&args, &["a", "b", "c"]);
+    stub_delete_with_failure(&mock, &args, &names[1]).await;
+
+    let mut cmd = delete_cmd(&args, &mock, &names);
+    cmd.arg("--keep-going");
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "Deleted 2 notebook(s) successfully",
+        ))
+        .stderr(predicate::str::contains("1 of 3 notebook(s) failed"));
+
+    assert_eq!(delete_request_count(&mock).await, 3);
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_delete_keep_going_allow_partial_succeeds() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let names = notebook_names(&args, &["a", "b", "c"]);
+    stub_delete_with_failure(&mock, &args, &names[1]).await;
+
+    let mut cmd = delete_cmd(&args, &mock, &names);
+    cmd.args(["--json", "--keep-going", "--allow-partial"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let json: serde_json::Value = serde_json::from_slice(&output).expect("valid JSON");
+    assert_eq!(json["status"], "partial");
+    assert_eq!(json["count"], 2);
+    assert_eq!(json["failed"][0]["name"], names[1].as_str());
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_delete_fail_fast_ignores_allow_partial() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let names = notebook_names(&args, &["a", "b", "c"]);
+    stub_delete_with_failure(&mock, &args, &names[1]).await;
+
+    let mut cmd = delete_cmd(&args, &mock, &names);
+    cmd.arg("--allow-partial");
+    cmd.assert().failure();
+}
+
+/// Interrupt the command once this many items have completed (hidden test hook).
+const INTERRUPT_AFTER_ENV: &str = "NBLM_TEST_INTERRUPT_AFTER";
+
+#[tokio::test]
+#[serial]
+async fn notebooks_delete_interrupted_reports_deleted_subset() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let names = notebook_names(&args, &["a", "b", "c", "d"]);
+    mock.stub_notebooks_batch_delete(&args.project_number, &args.location)
+        .await;
+
+    let mut cmd = delete_cmd(&args, &mock, &names);
+    cmd.env(INTERRUPT_AFTER_ENV, "2");
+    cmd.assert()
+        .code(130)
+        .stderr(predicate::str::contains(
+            "Interrupted after 2 of 4 item(s); deleted:",
+        ))
+        .stderr(predicate::str::contains(format!("  {}", names[0])))
+        .stderr(predicate::str::contains(format!("  {}", names[1])))
+        .stderr(predicate::str::contains(format!("Skipped {}", names[2])))
+        .stderr(predicate::str::contains(format!("Skipped {}", names[3])))
+        .stderr(predicate::str::contains(
+            "interrupted: 2 of 4 notebook(s) done, 2 not started",
+        ));
+
+    assert_eq!(delete_request_count(&mock).await, 2);
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_delete_interrupted_json_status() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let names = notebook_names(&args, &["a", "b", "c"]);
+    mock.stub_notebooks_batch_delete(&args.project_number, &args.location)
+        .await;
+
+    let mut cmd = delete_cmd(&args, &mock, &names);
+    cmd.env(INTERRUPT_AFTER_ENV, "1")
+        .args(["--json", "--keep-going"]);
+    let output = cmd.assert().code(130).get_output().stdout.clone();
+    let json: serde_json::Value = serde_json::from_slice(&output).expect("valid JSON");
+    assert_eq!(json["status"], "interrupted");
+    assert_eq!(json["count"], 1);
+    assert_eq!(
+        json["skipped"],
+        serde_json::json!([names[1].as_str(), names[2].as_str()])
+    );
+    assert_eq!(json["in_flight"], serde_json::json!([]));
+}
+
+fn write_files(dir: &TempDir, names: &[&str]) -> Vec<String> {
+    names
+        .iter()
+        .map(|name| {
+            let path = dir.path().join(name);
+            let mut file = std::fs::File::create(&path).expect("create file");
+            writeln!(file, "content of {name}").expect("write file");
+            path.to_str().expect("utf-8 path").to_string()
+        })
+        .collect()
+}
+
+/// Fail uploads of `bad.txt` with 400 and accept everything else.
+async fn stub_upload_with_failure(mock: &MockApi, args: &CommonArgs, notebook_id: &str) {
+    let path_str = format!(
+        "/upload/v1alpha/projects/{}/locations/{}/notebooks/{}/sources:uploadFile",
+        args.project_number, args.location, notebook_id
+    );
+    Mock::given(method("POST"))
+        .and(path(path_str))
+        .and(header("x-goog-upload-file-name", "bad.txt"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "error": {"code": 400, "message": "Unsupported file"}
+        })))
+        .with_priority(1)
+        .mount(&mock.server)
+        .await;
+    mock.stub_sources_upload_file(
+        &args.project_number,
+        &args.location,
+        notebook_id,
+        "uploaded-source",
+    )
+    .await;
+}
+
+fn upload_cmd(
+    args: &CommonArgs,
+    mock: &MockApi,
+    notebook_id: &str,
+    files: &[String],
+) -> assert_cmd::Command {
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["sources", "upload", "--notebook-id", notebook_id]);
+    for file in files {
+        cmd.args(["--file", file]);
+    }
+    cmd
+}
+
+async fn upload_request_count(mock: &MockApi) -> usize {
+    mock.server
+        .received_requests()
+        .await
+        .unwrap_or_default()
+        .iter()
+        .filter(|req| req.url.path().ends_with(":uploadFile"))
+        .count()
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_upload_keeps_going_by_default() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "nb-upload";
+    stub_upload_with_failure(&mock, &args, notebook_id).await;
+
+    let dir = TempDir::new().expect("temp dir");
+    let files = write_files(&dir, &["a.txt", "bad.txt", "c.txt"]);
+
+    upload_cmd(&args, &mock, notebook_id, &files)
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Uploaded 2 of 3 file(s)"))
+        .stderr(predicate::str::contains("Failed to upload"))
+        .stderr(predicate::str::contains("1 of 3 file(s) failed"));
+
+    assert_eq!(upload_request_count(&mock).await, 3);
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_upload_allow_partial_exits_zero() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "nb-upload";
+    stub_upload_with_failure(&mock, &args, notebook_id).await;
+
+    let dir = TempDir::new().expect("temp dir");
+    let files = write_files(&dir, &["a.txt", "bad.txt", "c.txt"]);
+
+    let mut cmd = upload_cmd(&args, &mock, notebook_id, &files);
+    cmd.args(["--json", "--allow-partial"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let json: serde_json::Value = serde_json::from_slice(&output).expect("valid JSON");
+    assert_eq!(json["uploads"].as_array().map(Vec::len), Some(2));
+    assert_eq!(json["failed"].as_array().map(Vec::len), Some(1));
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_upload_fail_fast_stops_after_failure() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "nb-upload";
+    stub_upload_with_failure(&mock, &args, notebook_id).await;
+
+    let dir = TempDir::new().expect("temp dir");
+    let files = write_files(&dir, &["a.txt", "bad.txt", "c.txt"]);
+
+    let mut cmd = upload_cmd(&args, &mock, notebook_id, &files);
+    cmd.arg("--fail-fast");
+    cmd.assert()
+        .failure()
+        .stdout(predicate::str::contains("Uploaded 1 of 3 file(s)"))
+        .stderr(predicate::str::contains("Skipped"))
+        .stderr(predicate::str::contains(
+            "1 skipped after the first failure",
+        ));
+
+    assert_eq!(upload_request_count(&mock).await, 2);
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_upload_interrupted_lists_uploaded_files() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "nb-upload";
+    stub_upload_with_failure(&mock, &args, notebook_id).await;
+
+    let dir = TempDir::new().expect("temp dir");
+    let files = write_files(&dir, &["a.txt", "b.txt", "c.txt"]);
+
+    let mut cmd = upload_cmd(&args, &mock, notebook_id, &files);
+    cmd.env(INTERRUPT_AFTER_ENV, "1");
+    cmd.assert()
+        .code(130)
+        .stdout(predicate::str::contains("Uploaded 1 of 3 file(s)"))
+        .stderr(predicate::str::contains(format!(
+            "Interrupted after 1 of 3 item(s); uploaded:\n  {}",
+            files[0]
+        )))
+        .stderr(predicate::str::contains(format!("Skipped {}", files[2])));
+
+    assert_eq!(upload_request_count(&mock).await, 1);
+}
+
+#[test]
+fn fail_fast_conflicts_with_keep_going() {
+    let args = CommonArgs::default();
+    let mut cmd = _helpers::cmd::nblm();
+    args.apply(&mut cmd);
+    cmd.args([
+        "notebooks",
+        "delete",
+        "--notebook-name",
+        "projects/123456/locations/global/notebooks/a",
+        "--fail-fast",
+        "--keep-going",
+    ]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_upload_collapses_repeated_failures_in_large_batches() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "nb-upload";
+    Mock::given(method("POST"))
+        .and(path(format!(
+            "/upload/v1alpha/projects/{}/locations/{}/notebooks/{notebook_id}/sources:uploadFile",
+            args.project_number, args.location
+        )))
+        .and(header_regex("x-goog-upload-file-name", "^bad-"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "error": {"code": 400, "message": "Unsupported file"}
+        })))
+        .with_priority(1)
+        .mount(&mock.server)
+        .await;
+    mock.stub_sources_upload_file(
+        &args.project_number,
+        &args.location,
+        notebook_id,
+        "uploaded-source",
+    )
+    .await;
+
+    // Every third file succeeds: 40 uploads, 26 failing the same way.
+    let names: Vec<String> = (0..40)
+        .map(|i| match i % 3 {
+            0 => format!("good-{i}.txt"),
+            _ => format!("bad-{i}.txt"),
+        })
+        .collect();
+    let dir = TempDir::new().expect("temp dir");
+    let files = write_files(&dir, &names.iter().map(String::as_str).collect::<Vec<_>>());
+
+    let output = upload_cmd(&args, &mock, notebook_id, &files)
+        .assert()
+        .failure()
+        .get_output()
+        .clone();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert_eq!(stdout.matches("Created source:").count(), 14, "{stdout}");
+    assert!(stdout.contains("Uploaded 14 of 40 file(s)"), "{stdout}");
+    assert_eq!(stderr.matches("Failed to upload").count(), 3, "{stderr}");
+    assert!(
+        stderr.contains("…and 23 more like this: http error 400 Bad Request: Unsupported file"),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains("26 of 40 file(s) failed; most recent: "),
+        "{stderr}"
+    );
+    assert!(stderr.contains("bad-38.txt: http error 400"), "{stderr}");
+    assert_eq!(upload_request_count(&mock).await, 40);
+}
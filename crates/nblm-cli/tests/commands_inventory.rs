@@ -0,0 +1,41 @@
+//! `nblm commands --json`: the whole command tree, snapshotted so schema changes show up in review.
+
+mod _helpers;
+
+use predicates::prelude::*;
+
+fn inventory() -> serde_json::Value {
+    let output = _helpers::cmd::nblm()
+        .args(["commands", "--json"])
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    serde_json::from_slice(&output.stdout).expect("stdout is one JSON document")
+}
+
+#[test]
+fn inventory_matches_the_snapshot() {
+    let mut inventory = inventory();
+    // The crate version changes with every release; the tree does not.
+    inventory["version"] = "[version]".into();
+    insta::assert_json_snapshot!("commands_inventory", inventory);
+}
+
+#[test]
+fn inventory_runs_without_credentials_or_a_project() {
+    _helpers::cmd::nblm()
+        .env_remove("NBLM_PROJECT_NUMBER")
+        .env_remove("NBLM_ACCESS_TOKEN")
+        .args(["--json", "commands"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("{\n  \"schema_version\": 1,"));
+
+    _helpers::cmd::nblm()
+        .arg("commands")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("nblm sources add\n"))
+        .stdout(predicate::str::contains("nblm man (hidden)\n"));
+}
@@ -0,0 +1,93 @@
+//! `Deprecation` / `Sunset` / `Warning` response headers surfaced once per command.
+
+mod _helpers;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use serde_json::{json, Value};
+use serial_test::serial;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+const NOTICE: &str = "warning: the API reports POST \
+    /v1alpha/projects/{project}/locations/{location}/notebooks:batchDelete is deprecated, \
+    sunset on 2026-06-30: Use notebooks.delete";
+
+/// A mock whose batchDelete answers with the headers, expecting one call per notebook.
+async fn deprecated_batch_delete(args: &CommonArgs, calls: u64) -> MockApi {
+    let mock = MockApi::start().await;
+    Mock::given(method("POST"))
+        .and(path(format!(
+            "/v1alpha/projects/{}/locations/{}/notebooks:batchDelete",
+            args.project_number, args.location
+        )))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .insert_header("Deprecation", "true")
+                .insert_header("Sunset", "Tue, 30 Jun 2026 23:59:59 GMT")
+                .insert_header("Warning", "299 - \"Use notebooks.delete\"")
+                .set_body_json(json!({})),
+        )
+        .expect(calls)
+        .mount(&mock.server)
+        .await;
+    mock
+}
+
+fn delete_two(mock: &MockApi, args: &CommonArgs, json_mode: bool) -> std::process::Output {
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    if json_mode {
+        cmd.arg("--json");
+    }
+    cmd.args(["notebooks", "delete"]);
+    for id in ["nb-1", "nb-2"] {
+        cmd.args([
+            "--notebook-name",
+            &format!(
+                "projects/{}/locations/{}/notebooks/{id}",
+                args.project_number, args.location
+            ),
+        ]);
+    }
+    cmd.assert().success().get_output().clone()
+}
+
+#[tokio::test]
+#[serial]
+async fn one_warning_for_repeated_calls_to_a_deprecated_endpoint() {
+    let args = CommonArgs::default();
+    let mock = deprecated_batch_delete(&args, 2).await;
+
+    let output = delete_two(&mock, &args, false);
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(stderr.matches(NOTICE).count(), 1, "{stderr}");
+    assert_eq!(stderr.matches("warning:").count(), 1, "{stderr}");
+}
+
+#[tokio::test]
+#[serial]
+async fn json_mode_reports_the_headers_on_stderr() {
+    let args = CommonArgs::default();
+    let mock = deprecated_batch_delete(&args, 2).await;
+
+    let output = delete_two(&mock, &args, true);
+
+    serde_json::from_slice::<Value>(&output.stdout).expect("stdout is the command's document");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let start = stderr.find("{\n").expect("a JSON report on stderr");
+    let report: Value = serde_json::from_str(&stderr[start..]).unwrap();
+    assert_eq!(
+        report,
+        json!({
+            "api_deprecations": [{
+                "method": "POST",
+                "path": "/v1alpha/projects/{project}/locations/{location}/notebooks:batchDelete",
+                "deprecated": true,
+                "deprecated_at": null,
+                "sunset": "2026-06-30T23:59:59Z",
+                "warnings": ["Use notebooks.delete"],
+            }]
+        })
+    );
+}
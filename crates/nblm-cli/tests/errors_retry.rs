@@ -60,3 +60,246 @@ async fn retry_401_then_success() {
         .success()
         .stdout(predicate::str::contains("notebooks"));
 }
+
+#[tokio::test]
+#[serial]
+async fn retry_429_reports_each_attempt_on_stderr() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    mock.stub_notebooks_recent_429_then_success(&args.project_number, &args.location, 2)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["notebooks", "recent"]);
+
+    // stderr is a pipe here, so each retry gets its own plain line.
+    cmd.assert()
+        .success()
+        .stderr(
+            predicate::str::is_match(r"rate limited, retrying in \d+ms \(attempt 1/2\)\.\.\.\n")
+                .unwrap(),
+        )
+        .stderr(
+            predicate::str::is_match(r"rate limited, retrying in \d+ms \(attempt 2/2\)\.\.\.\n")
+                .unwrap(),
+        )
+        .stderr(predicate::str::contains("\x1b[2K").not())
+        .stderr(predicate::str::contains("retrying HTTP request").not());
+}
+
+#[tokio::test]
+#[serial]
+async fn invalid_retry_env_is_reported() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.env("NBLM_MAX_RETRIES", "lots")
+        .args(["notebooks", "recent"]);
+
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "invalid value 'lots' for NBLM_MAX_RETRIES",
+    ));
+}
+
+#[tokio::test]
+#[serial]
+async fn retry_429_then_success_reports_the_rate_limit() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    mock.stub_notebooks_recent_429_then_success(&args.project_number, &args.location, 2)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["notebooks", "recent"]);
+
+    cmd.assert().success().stderr(
+        predicate::str::is_match(
+            r"Rate limited: waited \d+ms over 2 retries; the API asked to wait up to 0ms\n",
+        )
+        .unwrap(),
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn retry_429_then_success_reports_the_rate_limit_as_json() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    mock.stub_notebooks_recent_429_then_success(&args.project_number, &args.location, 2)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["--json", "notebooks", "recent"]);
+
+    let output = cmd.assert().success().get_output().clone();
+    serde_json::from_slice::<serde_json::Value>(&output.stdout).expect("stdout is one document");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let start = stderr.find("{\n").expect("rate limit JSON on stderr");
+    let report: serde_json::Value = serde_json::from_str(&stderr[start..]).unwrap();
+    let summary = &report["rate_limit"];
+    assert_eq!(summary["rate_limited"], true);
+    assert_eq!(summary["retries"], 2);
+    // Retry-After: 0 means no wait at all, so the accounting must not make one up.
+    assert_eq!(summary["waited_ms"], 0);
+    assert_eq!(summary["max_retry_after_ms"], 0);
+}
+
+#[tokio::test]
+#[serial]
+async fn successful_commands_without_429_print_no_rate_limit_summary() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    mock.stub_notebooks_recent_401_then_success(&args.project_number, &args.location)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["notebooks", "recent"]);
+
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Rate limited").not());
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_add_still_rate_limited_suggests_a_rerun() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "busy-notebook";
+
+    mock.stub_sources_batch_create_persistent_429(
+        &args.project_number,
+        &args.location,
+        notebook_id,
+    )
+    .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "sources",
+        "add",
+        "--notebook-id",
+        notebook_id,
+        "--web-url",
+        "https://example.com",
+        "--web-name",
+        "Example page",
+    ]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Too Many Requests"))
+        .stderr(predicate::str::contains(
+            "The API asked to wait 30s (Retry-After) before adding more sources",
+        ))
+        .stderr(predicate::str::contains("DUMMY_TOKEN").not())
+        .stderr(predicate::str::contains(format!(
+            "NBLM_RETRY_MAX_DELAY=30s nblm --project-number {} --location {} --endpoint-location {} --auth env --token \"$NBLM_ACCESS_TOKEN\" --base-url {}",
+            args.project_number,
+            args.location,
+            args.endpoint_location,
+            mock.base_url()
+        )))
+        .stderr(predicate::str::contains(
+            "sources add --notebook-id busy-notebook --web-url https://example.com --web-name 'Example page'",
+        ));
+}
+
+#[tokio::test]
+#[serial]
+async fn service_disabled_prints_the_enable_command() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    mock.stub_notebooks_recent_service_disabled(&args.project_number, &args.location)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["notebooks", "recent"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Discovery Engine API is not enabled for project 123456. Enable it with:\n  \
+             gcloud services enable discoveryengine.googleapis.com --project 123456\n\
+             or in the Cloud console: https://console.developers.google.com/apis/api/discoveryengine.googleapis.com/overview?project=123456\n",
+        ))
+        .stderr(predicate::str::contains("\"@type\"").not());
+}
+
+#[tokio::test]
+#[serial]
+async fn no_retry_fails_on_the_first_429() {
+    let args = CommonArgs::default();
+
+    for via_env in [false, true] {
+        let mock = MockApi::start().await;
+        mock.stub_notebooks_recent_429_then_success(&args.project_number, &args.location, 2)
+            .await;
+
+        let mut cmd = _helpers::cmd::nblm();
+        args.with_base_url(&mut cmd, &mock.base_url());
+        if via_env {
+            cmd.env("NBLM_NO_RETRY", "1");
+        } else {
+            cmd.arg("--no-retry");
+        }
+        // Wins over the other retry settings.
+        cmd.env_remove("RUST_BACKTRACE")
+            .env_remove("RUST_LIB_BACKTRACE")
+            .env("NBLM_MAX_RETRIES", "5")
+            .args(["notebooks", "recent"]);
+
+        cmd.assert()
+            .failure()
+            .stderr(predicate::str::contains("Too Many Requests"))
+            .stderr(predicate::str::contains(
+                "Retries are disabled (--no-retry or NBLM_NO_RETRY)",
+            ))
+            .stderr(predicate::str::contains("retrying in").not());
+        assert_eq!(mock.server.received_requests().await.unwrap().len(), 1);
+    }
+
+    // The same stub succeeds once retries are allowed.
+    let mock = MockApi::start().await;
+    mock.stub_notebooks_recent_429_then_success(&args.project_number, &args.location, 2)
+        .await;
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.env("NBLM_NO_RETRY", "false")
+        .args(["notebooks", "recent"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("notebooks"));
+}
+
+#[tokio::test]
+#[serial]
+async fn no_retry_leaves_errors_that_are_never_retried_alone() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    mock.stub_notebooks_recent_service_disabled(&args.project_number, &args.location)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["--no-retry", "notebooks", "recent"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("gcloud services enable"))
+        .stderr(predicate::str::contains("Retries are disabled").not());
+}
@@ -0,0 +1,120 @@
+mod _helpers;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use serde_json::{json, Value};
+use serial_test::serial;
+use wiremock::matchers::{body_json, method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+const PARENT: &str = "projects/987654321/locations/global";
+
+async fn respond(mock: &MockApi, verb: &str, endpoint: String, body: Value) {
+    Mock::given(method(verb))
+        .and(path(format!("/v1alpha/{PARENT}/{endpoint}")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(body))
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+}
+
+fn read(dir: &std::path::Path, name: &str) -> Value {
+    let content = std::fs::read_to_string(dir.join(format!("{name}.json"))).unwrap();
+    serde_json::from_str(&content).unwrap()
+}
+
+#[tokio::test]
+#[serial]
+async fn capture_writes_redacted_fixtures_and_deletes_the_scratch_notebook() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs {
+        project_number: "987654321".to_string(),
+        ..CommonArgs::default()
+    };
+    let dir = tempfile::tempdir().unwrap();
+
+    respond(
+        &mock,
+        "GET",
+        "notebooks:listRecentlyViewed".to_string(),
+        json!({ "notebooks": [{
+            "notebookId": "a1b2c3",
+            "title": "Kept as is",
+            "name": format!("{PARENT}/notebooks/a1b2c3"),
+        }] }),
+    )
+    .await;
+    respond(
+        &mock,
+        "GET",
+        "notebooks/a1b2c3".to_string(),
+        json!({
+            "notebookId": "a1b2c3",
+            "sources": [{ "sourceId": { "id": "zz9" }, "name": format!("{PARENT}/notebooks/a1b2c3/sources/zz9") }],
+        }),
+    )
+    .await;
+    respond(
+        &mock,
+        "POST",
+        "notebooks".to_string(),
+        json!({ "notebookId": "scratch77", "name": format!("{PARENT}/notebooks/scratch77") }),
+    )
+    .await;
+    respond(
+        &mock,
+        "POST",
+        "notebooks/scratch77/sources:batchCreate".to_string(),
+        json!({ "sources": [{ "sourceId": { "id": "yy8" } }], "errorCount": 1 }),
+    )
+    .await;
+    respond(
+        &mock,
+        "POST",
+        "notebooks/scratch77/audioOverviews".to_string(),
+        json!({ "audioOverview": { "audioOverviewId": "c825b865" } }),
+    )
+    .await;
+    Mock::given(method("POST"))
+        .and(path(format!("/v1alpha/{PARENT}/notebooks:batchDelete")))
+        .and(body_json(
+            json!({ "names": [format!("{PARENT}/notebooks/scratch77")] }),
+        ))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["fixtures", "capture", "--dir"])
+        .arg(dir.path())
+        .args(["--notebook-id", "a1b2c3", "--scratch"]);
+    let output = cmd.assert().success().get_output().clone();
+    assert_eq!(String::from_utf8_lossy(&output.stdout).lines().count(), 5);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("review titles"));
+
+    assert_eq!(
+        read(dir.path(), "list_recently_viewed"),
+        json!({ "notebooks": [{
+            "notebookId": "notebook-1",
+            "title": "Kept as is",
+            "name": "projects/123456/locations/global/notebooks/notebook-1",
+        }] })
+    );
+    assert_eq!(
+        read(dir.path(), "get_notebook")["sources"][0]["name"],
+        "projects/123456/locations/global/notebooks/notebook-1/sources/source-1"
+    );
+    assert_eq!(
+        read(dir.path(), "notebook_create")["notebookId"],
+        "notebook-2"
+    );
+    assert_eq!(
+        read(dir.path(), "batch_create_sources_with_errors")["sources"][0]["sourceId"]["id"],
+        "source-2"
+    );
+    assert_eq!(
+        read(dir.path(), "audio_overview_create")["audioOverview"]["audioOverviewId"],
+        "audio-overview-1"
+    );
+}
@@ -0,0 +1,111 @@
+mod _helpers;
+
+use std::fs;
+
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+const PAGES: &[&str] = &[
+    "nblm",
+    "nblm-notebooks",
+    "nblm-notebooks-create",
+    "nblm-notebooks-update",
+    "nblm-notebooks-recent",
+    "nblm-notebooks-delete",
+    "nblm-notebooks-budget",
+    "nblm-notebooks-sources-count",
+    "nblm-notebooks-activity",
+    "nblm-notebooks-query",
+    "nblm-sources",
+    "nblm-sources-add",
+    "nblm-sources-list",
+    "nblm-sources-delete",
+    "nblm-sources-upload",
+    "nblm-sources-get",
+    "nblm-sources-search",
+    "nblm-sources-reingest",
+    "nblm-audio",
+    "nblm-audio-create",
+    "nblm-audio-create-batch",
+    "nblm-audio-download",
+    "nblm-audio-delete",
+    "nblm-queue",
+    "nblm-queue-add",
+    "nblm-queue-flush",
+    "nblm-queue-list",
+    "nblm-queue-clear",
+    "nblm-auth",
+    "nblm-auth-login",
+    "nblm-auth-status",
+    "nblm-doctor",
+    "nblm-init",
+    "nblm-validate",
+];
+
+#[test]
+fn sources_add_help_explains_drive_access_with_examples() {
+    let mut cmd = _helpers::cmd::nblm();
+    cmd.args(["sources", "add", "--help"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("nblm auth login --drive-access"))
+        .stdout(predicate::str::contains(
+            "nblm sources add --notebook-id abc123 --web-url https://example.com",
+        ))
+        .stdout(predicate::str::contains("NBLM_PROJECT_NUMBER"));
+}
+
+#[test]
+fn help_subcommand_shows_the_extended_help() {
+    let mut cmd = _helpers::cmd::nblm();
+    cmd.args(["help", "sources", "upload"]);
+    cmd.assert().success().stdout(predicate::str::contains(
+        "--display-name is rejected by the API",
+    ));
+}
+
+#[test]
+fn short_help_leaves_out_the_extended_text() {
+    let mut cmd = _helpers::cmd::nblm();
+    cmd.args(["sources", "add", "-h"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Examples:").not());
+}
+
+#[test]
+fn man_writes_a_page_per_visible_command() {
+    let config = TempDir::new().unwrap();
+    let out = TempDir::new().unwrap();
+    let dir = out.path().join("man1");
+
+    let mut cmd = _helpers::cmd::nblm();
+    cmd.env_clear()
+        .env("HOME", config.path())
+        .env("NBLM_CONFIG_DIR", config.path())
+        .args(["man", "--dir"])
+        .arg(&dir);
+    cmd.assert().success();
+
+    let mut written: Vec<String> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    written.sort();
+    let mut expected: Vec<String> = PAGES.iter().map(|page| format!("{page}.1")).collect();
+    expected.sort();
+    assert_eq!(written, expected);
+
+    for page in PAGES {
+        let content = fs::read_to_string(dir.join(format!("{page}.1"))).unwrap();
+        assert!(
+            content.contains(&format!("\n.TH {page} 1 ")),
+            "{page}: {content}"
+        );
+        assert!(content.contains(".SH SYNOPSIS"), "{page}");
+    }
+    let add = fs::read_to_string(dir.join("nblm-sources-add.1")).unwrap();
+    assert!(add.contains(".SH EXTRA"), "{add}");
+    assert!(add.contains("\nExamples:\n"), "{add}");
+    assert!(add.contains("\nEnvironment:\n"), "{add}");
+}
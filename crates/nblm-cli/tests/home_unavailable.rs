@@ -0,0 +1,92 @@
+//! Minimal containers: HOME read-only, unset, or pointing somewhere no directory can be created.
+
+mod _helpers;
+
+use std::path::Path;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use assert_cmd::Command;
+use predicates::prelude::*;
+use serial_test::serial;
+use tempfile::{NamedTempFile, TempDir};
+
+/// The command with every config directory source cleared except `home` and `config_home`
+/// (`None` leaves the variable unset).
+fn nblm_with_home(home: Option<&Path>, config_home: Option<&Path>) -> Command {
+    let mut cmd = _helpers::cmd::nblm();
+    cmd.env_remove("NBLM_CONFIG_DIR")
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("RUST_BACKTRACE")
+        .env_remove("RUST_LIB_BACKTRACE");
+    match home {
+        Some(home) => cmd.env("HOME", home),
+        None => cmd.env_remove("HOME"),
+    };
+    if let Some(config_home) = config_home {
+        cmd.env("XDG_CONFIG_HOME", config_home);
+    }
+    cmd
+}
+
+fn read_only_dir() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(dir.path(), std::fs::Permissions::from_mode(0o555)).unwrap();
+    }
+    dir
+}
+
+#[tokio::test]
+#[serial]
+async fn env_auth_commands_run_without_a_usable_home() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    mock.stub_notebooks_recent(&args.project_number, &args.location)
+        .await;
+    let read_only = read_only_dir();
+    // A path below a regular file cannot be created, even by root.
+    let file = NamedTempFile::new().unwrap();
+    let uncreatable = file.path().join("config");
+
+    for (home, config_home) in [
+        (Some(read_only.path()), None),
+        (None, Some(uncreatable.as_path())),
+    ] {
+        let mut cmd = nblm_with_home(home, config_home);
+        args.with_base_url(&mut cmd, &mock.base_url());
+        cmd.args(["notebooks", "recent"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("panicked").not());
+    }
+}
+
+#[test]
+#[serial]
+fn queue_add_names_the_path_and_the_override() {
+    let file = NamedTempFile::new().unwrap();
+    let home = file.path().join("home");
+    let config_home = file.path().join("config");
+
+    for (home, config_home) in [
+        (Some(home.as_path()), None),
+        (None, Some(config_home.as_path())),
+    ] {
+        let mut cmd = nblm_with_home(home, config_home);
+        CommonArgs::default().apply(&mut cmd);
+        cmd.args(["queue", "add", "--notebook-id", "nb1"])
+            .args(["--web-url", "https://example.com"])
+            .assert()
+            .failure()
+            .stdout("")
+            .stderr(predicate::str::contains(
+                file.path().to_string_lossy().into_owned(),
+            ))
+            .stderr(predicate::str::contains(
+                "set NBLM_CONFIG_DIR to a writable directory",
+            ))
+            .stderr(predicate::str::contains("panicked").not());
+    }
+}
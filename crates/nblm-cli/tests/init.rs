@@ -0,0 +1,148 @@
+#![cfg(unix)]
+
+mod _helpers;
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+use predicates::prelude::*;
+use serial_test::serial;
+use tempfile::TempDir;
+
+/// Install a fake `gcloud` whose active project `my-project` has number 987654.
+fn install_fake_gcloud(dir: &Path) {
+    let script = dir.join("gcloud");
+    std::fs::write(
+        &script,
+        r#"#!/bin/sh
+case "$1 $2 $3" in
+  "--version  ") echo "Google Cloud SDK 500.0.0" ;;
+  "config get-value account") echo "user@example.com" ;;
+  "config get-value project") echo "my-project" ;;
+  "projects describe my-project") echo "987654" ;;
+  *) exit 1 ;;
+esac
+"#,
+    )
+    .expect("write fake gcloud");
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755))
+        .expect("make fake gcloud executable");
+}
+
+/// `nblm init` with PATH restricted to `bin` and no NBLM_* settings inherited.
+fn init_cmd(bin: &Path, config: &Path) -> assert_cmd::Command {
+    let mut cmd = _helpers::cmd::nblm();
+    cmd.env("PATH", bin)
+        .env("NBLM_CONFIG_DIR", config)
+        .env_remove("NBLM_PROJECT_NUMBER")
+        .env_remove("NBLM_LOCATION")
+        .env_remove("NBLM_ENDPOINT_LOCATION")
+        .env_remove("NBLM_ACCESS_TOKEN")
+        .arg("init")
+        .arg("--skip-api-check");
+    cmd
+}
+
+#[test]
+#[serial]
+fn init_yes_writes_config() {
+    let bin = TempDir::new().unwrap();
+    let config = TempDir::new().unwrap();
+
+    init_cmd(bin.path(), config.path())
+        .args(["--yes", "--project-number", "123456", "--location", "eu"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("gcloud command not found"))
+        .stdout(predicate::str::contains("nblm notebooks recent"));
+
+    let written = std::fs::read_to_string(config.path().join("config.toml")).unwrap();
+    assert!(written.contains("project_number = \"123456\"\n"));
+    assert!(written.contains("location = \"eu\"\n"));
+    assert!(written.contains("endpoint_location = \"eu\"\n"));
+}
+
+#[test]
+#[serial]
+fn init_infers_project_number_from_gcloud() {
+    let bin = TempDir::new().unwrap();
+    let config = TempDir::new().unwrap();
+    install_fake_gcloud(bin.path());
+
+    init_cmd(bin.path(), config.path())
+        .arg("--yes")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("user@example.com"))
+        .stdout(predicate::str::contains("Using project number 987654"));
+
+    let written = std::fs::read_to_string(config.path().join("config.toml")).unwrap();
+    assert!(written.contains("project_number = \"987654\"\n"));
+    assert!(written.contains("endpoint_location = \"global\"\n"));
+}
+
+#[test]
+#[serial]
+fn init_without_project_number_fails_when_it_cannot_be_inferred() {
+    let bin = TempDir::new().unwrap();
+    let config = TempDir::new().unwrap();
+
+    init_cmd(bin.path(), config.path())
+        .arg("--yes")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("pass --project-number"));
+    assert!(!config.path().join("config.toml").exists());
+}
+
+#[test]
+#[serial]
+fn init_refuses_to_overwrite_without_force() {
+    let bin = TempDir::new().unwrap();
+    let config = TempDir::new().unwrap();
+    let path = config.path().join("config.toml");
+    std::fs::write(&path, "project_number = \"111\"\n").unwrap();
+
+    init_cmd(bin.path(), config.path())
+        .args(["--yes", "--project-number", "222"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already exists"))
+        .stderr(predicate::str::contains("--force"));
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "project_number = \"111\"\n"
+    );
+
+    init_cmd(bin.path(), config.path())
+        .args(["--yes", "--project-number", "222", "--force"])
+        .assert()
+        .success();
+    assert!(std::fs::read_to_string(&path)
+        .unwrap()
+        .contains("project_number = \"222\"\n"));
+}
+
+#[test]
+#[serial]
+fn config_file_supplies_default_project_number() {
+    let bin = TempDir::new().unwrap();
+    let config = TempDir::new().unwrap();
+    std::fs::write(
+        config.path().join("config.toml"),
+        "project_number = \"424242\"\nlocation = \"us\"\n",
+    )
+    .unwrap();
+
+    // `doctor` reads the same NBLM_* variables the global flags do.
+    let mut cmd = _helpers::cmd::nblm();
+    cmd.env("PATH", bin.path())
+        .env("NBLM_CONFIG_DIR", config.path())
+        .env_remove("NBLM_PROJECT_NUMBER")
+        .env_remove("NBLM_LOCATION")
+        .env_remove("NBLM_ACCESS_TOKEN")
+        .args(["doctor", "--skip-api-check"])
+        .assert()
+        .stdout(predicate::str::contains("NBLM_PROJECT_NUMBER=424242"))
+        .stdout(predicate::str::contains("NBLM_LOCATION=us"));
+}
@@ -0,0 +1,59 @@
+mod _helpers;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use predicates::prelude::*;
+use serial_test::serial;
+
+#[tokio::test]
+#[serial]
+async fn insecure_flag_prints_warning() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    mock.stub_notebooks_recent(&args.project_number, &args.location)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["--insecure-skip-tls-verify", "notebooks", "recent"]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("nb1"))
+        .stderr(predicate::str::contains(
+            "WARNING: TLS certificate verification is disabled",
+        ));
+}
+
+#[test]
+fn insecure_flag_refuses_googleapis_base_url() {
+    let args = CommonArgs::default();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(
+        &mut cmd,
+        "https://us-discoveryengine.googleapis.com/v1alpha",
+    );
+    cmd.args(["--insecure-skip-tls-verify", "notebooks", "recent"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("WARNING"))
+        .stderr(predicate::str::contains(
+            "cannot be used with production host 'us-discoveryengine.googleapis.com'",
+        ));
+}
+
+#[test]
+fn insecure_flag_refuses_default_endpoint() {
+    let args = CommonArgs::default();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.apply(&mut cmd);
+    cmd.env_remove("NBLM_BASE_URL");
+    cmd.args(["--insecure-skip-tls-verify", "notebooks", "recent"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("googleapis.com"));
+}
@@ -1,7 +1,10 @@
 mod _helpers;
 
 use _helpers::{cmd::CommonArgs, mock::MockApi};
+use predicates::prelude::*;
 use serial_test::serial;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, ResponseTemplate};
 
 #[tokio::test]
 #[serial]
@@ -51,6 +54,7 @@ async fn notebooks_recent_json_output() {
 
     insta::assert_json_snapshot!(json_output, @r###"
     {
+      "nextPageToken": null,
       "notebooks": [
         {
           "name": "projects/123456/locations/global/notebooks/nb1",
@@ -96,10 +100,916 @@ async fn sources_add_json_output() {
       "notebook_id": "test-notebook",
       "sources": [
         {
-          "displayName": "Test Source",
-          "name": "projects/123456/locations/global/notebooks/test-notebook/sources/src1"
+          "name": "projects/123456/locations/global/notebooks/test-notebook/sources/src1",
+          "displayName": "Test Source"
         }
       ]
     }
     "#);
 }
+
+#[tokio::test]
+#[serial]
+async fn notebooks_recent_json_preserves_unknown_fields() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    Mock::given(method("GET"))
+        .and(path(format!(
+            "/v1alpha/projects/{}/locations/{}/notebooks:listRecentlyViewed",
+            args.project_number, args.location
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "notebooks": [{"title": "Raw", "notebookId": "nb-raw"}],
+            "futureTopLevelField": {"enabled": true}
+        })))
+        .mount(&mock.server)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["--json", "notebooks", "recent"]);
+
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let json_output: serde_json::Value =
+        serde_json::from_slice(&output).expect("valid JSON output");
+    assert_eq!(json_output["futureTopLevelField"]["enabled"], true);
+    assert_eq!(json_output["notebooks"][0]["notebookId"], "nb-raw");
+
+    // Text mode keeps rendering the typed notebooks only.
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["notebooks", "recent"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(r#""notebookId": "nb-raw""#))
+        .stdout(predicate::str::contains("futureTopLevelField").not());
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_get_json_preserves_unknown_fields() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    Mock::given(method("GET"))
+        .and(path(format!(
+            "/v1alpha/projects/{}/locations/{}/notebooks/nb1/sources/src1",
+            args.project_number, args.location
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "name": "projects/123456/locations/global/notebooks/nb1/sources/src1",
+            "metadata": {"wordCount": 10, "futureMetadata": "kept"}
+        })))
+        .mount(&mock.server)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "--json",
+        "sources",
+        "get",
+        "--notebook-id",
+        "nb1",
+        "--source-id",
+        "src1",
+    ]);
+
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let json_output: serde_json::Value =
+        serde_json::from_slice(&output).expect("valid JSON output");
+    assert_eq!(json_output["metadata"]["futureMetadata"], "kept");
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_add_json_preserves_unknown_fields() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    Mock::given(method("POST"))
+        .and(path(format!(
+            "/v1alpha/projects/{}/locations/{}/notebooks/nb1/sources:batchCreate",
+            args.project_number, args.location
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "sources": [{
+                "name": "projects/123456/locations/global/notebooks/nb1/sources/src1",
+                "settings": {"status": "SOURCE_STATUS_PENDING", "futureSetting": 1}
+            }],
+            "errorCount": 0
+        })))
+        .mount(&mock.server)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "--json",
+        "sources",
+        "add",
+        "--notebook-id",
+        "nb1",
+        "--text",
+        "hello",
+    ]);
+
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let json_output: serde_json::Value =
+        serde_json::from_slice(&output).expect("valid JSON output");
+    assert_eq!(json_output["notebook_id"], "nb1");
+    assert_eq!(json_output["error_count"], 0);
+    assert_eq!(json_output["sources"][0]["settings"]["futureSetting"], 1);
+}
+
+// The snapshots below pin the key order of every `--json` payload the CLI builds itself (see
+// `src/util/payload.rs`). Update them only for intentional schema changes.
+
+fn json_stdout(cmd: &mut assert_cmd::Command) -> serde_json::Value {
+    let output = cmd.assert().success().get_output().stdout.clone();
+    serde_json::from_slice(&output).expect("valid JSON output")
+}
+
+fn notebook_name(args: &CommonArgs, id: &str) -> String {
+    format!(
+        "projects/{}/locations/{}/notebooks/{}",
+        args.project_number, args.location, id
+    )
+}
+
+/// GET notebook `nb1` with two sources, the second without a word count.
+async fn stub_notebook_with_sources(mock: &MockApi, args: &CommonArgs) {
+    Mock::given(method("GET"))
+        .and(path(format!("/v1alpha/{}", notebook_name(args, "nb1"))))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "name": notebook_name(args, "nb1"),
+            "notebookId": "nb1",
+            "title": "Notebook",
+            "sources": [
+                {
+                    "name": format!("{}/sources/s1", notebook_name(args, "nb1")),
+                    "title": "Rust notes",
+                    "metadata": { "wordCount": 1200 },
+                    "settings": { "status": "SOURCE_STATUS_COMPLETE" }
+                },
+                {
+                    "name": format!("{}/sources/s2", notebook_name(args, "nb1")),
+                    "title": "Pending"
+                }
+            ]
+        })))
+        .mount(&mock.server)
+        .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_delete_json_output() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    mock.stub_notebooks_batch_delete(&args.project_number, &args.location)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "--json",
+        "notebooks",
+        "delete",
+        "--notebook-name",
+        &notebook_name(&args, "nb1"),
+    ]);
+
+    insta::assert_json_snapshot!(json_stdout(&mut cmd), @r#"
+    {
+      "count": 1,
+      "response": {},
+      "status": "deleted"
+    }
+    "#);
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_budget_json_output() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_notebook_with_sources(&mock, &args).await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "--json",
+        "notebooks",
+        "budget",
+        "--notebook-id",
+        "nb1",
+        "--budget",
+        "1000",
+    ]);
+
+    insta::assert_json_snapshot!(json_stdout(&mut cmd), @r#"
+    {
+      "budget": 1000,
+      "missing_word_counts": 1,
+      "notebook_id": "nb1",
+      "over_by": 200,
+      "sources": [
+        {
+          "name": "projects/123456/locations/global/notebooks/nb1/sources/s1",
+          "title": "Rust notes",
+          "word_count": 1200
+        },
+        {
+          "name": "projects/123456/locations/global/notebooks/nb1/sources/s2",
+          "title": "Pending"
+        }
+      ],
+      "total_words": 1200,
+      "within_budget": false
+    }
+    "#);
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_sources_count_json_output() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_notebook_with_sources(&mock, &args).await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "--json",
+        "notebooks",
+        "sources-count",
+        "--notebook-id",
+        "nb1",
+    ]);
+
+    insta::assert_json_snapshot!(json_stdout(&mut cmd), @r#"
+    {
+      "total_notebooks": 1,
+      "total_sources": 2,
+      "notebooks": [
+        {
+          "notebook_id": "nb1",
+          "title": "Notebook",
+          "sources": 2
+        }
+      ]
+    }
+    "#);
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_list_json_output() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_notebook_with_sources(&mock, &args).await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["--json", "sources", "list", "--notebook-id", "nb1"]);
+
+    insta::assert_json_snapshot!(json_stdout(&mut cmd), @r#"
+    {
+      "notebook_id": "nb1",
+      "sources": [
+        {
+          "metadata": {
+            "wordCount": 1200
+          },
+          "name": "projects/123456/locations/global/notebooks/nb1/sources/s1",
+          "settings": {
+            "status": "SOURCE_STATUS_COMPLETE"
+          },
+          "title": "Rust notes"
+        },
+        {
+          "name": "projects/123456/locations/global/notebooks/nb1/sources/s2",
+          "title": "Pending"
+        }
+      ]
+    }
+    "#);
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_search_json_output() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_notebook_with_sources(&mock, &args).await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "--json",
+        "sources",
+        "search",
+        "--notebook-id",
+        "nb1",
+        "--query",
+        "rust",
+    ]);
+
+    insta::assert_json_snapshot!(json_stdout(&mut cmd), @r#"
+    {
+      "matches": [
+        {
+          "field": "title",
+          "match": "Rust",
+          "notebook_id": "nb1",
+          "snippet": "Rust notes",
+          "source_id": "s1",
+          "source_name": "projects/123456/locations/global/notebooks/nb1/sources/s1",
+          "title": "Rust notes"
+        }
+      ],
+      "notes": [
+        "source content is not available from the API; searched titles and URLs only"
+      ],
+      "query": "rust"
+    }
+    "#);
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_delete_json_output() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    mock.stub_sources_batch_delete(&args.project_number, &args.location, "nb1")
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "--json",
+        "sources",
+        "delete",
+        "--notebook-id",
+        "nb1",
+        "--source-name",
+        &format!("{}/sources/s1", notebook_name(&args, "nb1")),
+    ]);
+
+    insta::assert_json_snapshot!(json_stdout(&mut cmd), @r#"
+    {
+      "count": 1,
+      "response": {},
+      "status": "deleted"
+    }
+    "#);
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_reingest_json_output() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    Mock::given(method("GET"))
+        .and(path(format!("/v1alpha/{}", notebook_name(&args, "nb1"))))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "name": notebook_name(&args, "nb1"),
+            "title": "Notebook",
+            "sources": [
+                {
+                    "name": format!("{}/sources/s1", notebook_name(&args, "nb1")),
+                    "title": "Blog post",
+                    "metadata": { "webUrl": "https://example.com/post" }
+                },
+                {
+                    "name": format!("{}/sources/s2", notebook_name(&args, "nb1")),
+                    "title": "Notes"
+                }
+            ]
+        })))
+        .mount(&mock.server)
+        .await;
+    mock.stub_sources_batch_delete(&args.project_number, &args.location, "nb1")
+        .await;
+    mock.stub_sources_batch_create(&args.project_number, &args.location, "nb1")
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "--json",
+        "sources",
+        "reingest",
+        "--notebook-id",
+        "nb1",
+        "--all-web",
+    ]);
+
+    insta::assert_json_snapshot!(json_stdout(&mut cmd), @r#"
+    {
+      "notebook_id": "nb1",
+      "reingested": [
+        {
+          "old_source_id": "s1",
+          "new_source_id": "src1",
+          "url": "https://example.com/post",
+          "title": "Blog post"
+        }
+      ],
+      "skipped": [
+        {
+          "source_id": "s2",
+          "title": "Notes",
+          "reason": "no web or YouTube URL in the source's metadata"
+        }
+      ],
+      "failed": []
+    }
+    "#);
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_add_retry_failed_json_output() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let batch_create = format!(
+        "/v1alpha/{}/sources:batchCreate",
+        notebook_name(&args, "nb1")
+    );
+    let source = |id: &str, status: &str| {
+        serde_json::json!({
+            "name": format!("{}/sources/{id}", notebook_name(&args, "nb1")),
+            "settings": { "status": status }
+        })
+    };
+    Mock::given(method("POST"))
+        .and(path(batch_create.clone()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "sources": [
+                source("a", "SOURCE_STATUS_COMPLETE"),
+                source("b", "SOURCE_STATUS_ERROR")
+            ],
+            "errorCount": 1
+        })))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&mock.server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(batch_create))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "sources": [source("b2", "SOURCE_STATUS_COMPLETE")]
+        })))
+        .with_priority(2)
+        .mount(&mock.server)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "--json",
+        "sources",
+        "add",
+        "--notebook-id",
+        "nb1",
+        "--web-url",
+        "https://a.example.com",
+        "--web-url",
+        "https://b.example.com",
+        "--retry-failed",
+    ]);
+
+    insta::assert_json_snapshot!(json_stdout(&mut cmd), @r#"
+    {
+      "error_count": 0,
+      "notebook_id": "nb1",
+      "retried": [
+        {
+          "index": 1,
+          "name": "projects/123456/locations/global/notebooks/nb1/sources/b2",
+          "source": "https://b.example.com",
+          "status": "added"
+        }
+      ],
+      "sources": [
+        {
+          "name": "projects/123456/locations/global/notebooks/nb1/sources/a",
+          "settings": {
+            "status": "SOURCE_STATUS_COMPLETE"
+          }
+        },
+        {
+          "name": "projects/123456/locations/global/notebooks/nb1/sources/b2",
+          "settings": {
+            "status": "SOURCE_STATUS_COMPLETE"
+          }
+        }
+      ]
+    }
+    "#);
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_upload_json_output() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    mock.stub_sources_upload_file(&args.project_number, &args.location, "nb1", "s1")
+        .await;
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("notes.txt");
+    std::fs::write(&file, "hello").unwrap();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "--json",
+        "sources",
+        "upload",
+        "--notebook-id",
+        "nb1",
+        "--file",
+    ])
+    .arg(&file);
+
+    insta::assert_json_snapshot!(json_stdout(&mut cmd), @r#"
+    {
+      "content_type": "text/plain",
+      "extra": {},
+      "file_name": "notes.txt",
+      "name": null,
+      "notebook_id": "nb1",
+      "source_id": {
+        "id": "projects/123456/locations/global/notebooks/nb1/sources/s1"
+      },
+      "state": null
+    }
+    "#);
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_upload_multiple_json_output() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    mock.stub_sources_upload_file(&args.project_number, &args.location, "nb1", "s1")
+        .await;
+    let dir = tempfile::tempdir().unwrap();
+    let first = dir.path().join("a.txt");
+    let second = dir.path().join("b.md");
+    std::fs::write(&first, "hello").unwrap();
+    std::fs::write(&second, "# hello").unwrap();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "--json",
+        "sources",
+        "upload",
+        "--notebook-id",
+        "nb1",
+        "--file",
+    ])
+    .arg(&first)
+    .arg("--file")
+    .arg(&second);
+
+    insta::assert_json_snapshot!(json_stdout(&mut cmd), @r#"
+    {
+      "failed": [],
+      "notebook_id": "nb1",
+      "skipped": [],
+      "uploads": [
+        {
+          "content_type": "text/plain",
+          "extra": {},
+          "file_name": "a.txt",
+          "source_id": {
+            "id": "projects/123456/locations/global/notebooks/nb1/sources/s1"
+          }
+        },
+        {
+          "content_type": "text/markdown",
+          "extra": {},
+          "file_name": "b.md",
+          "source_id": {
+            "id": "projects/123456/locations/global/notebooks/nb1/sources/s1"
+          }
+        }
+      ]
+    }
+    "#);
+}
+
+#[tokio::test]
+#[serial]
+async fn audio_create_json_output() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    Mock::given(method("POST"))
+        .and(path(format!(
+            "/v1alpha/{}/audioOverviews",
+            notebook_name(&args, "nb1")
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "audioOverview": {
+                "name": format!("{}/audioOverviews/default", notebook_name(&args, "nb1")),
+                "audioOverviewId": "default",
+                "status": "AUDIO_OVERVIEW_STATUS_IN_PROGRESS"
+            }
+        })))
+        .mount(&mock.server)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["--json", "audio", "create", "--notebook-id", "nb1"]);
+
+    insta::assert_json_snapshot!(json_stdout(&mut cmd), @r#"
+    {
+      "audioOverview": {
+        "audioOverviewId": "default",
+        "name": "projects/123456/locations/global/notebooks/nb1/audioOverviews/default",
+        "status": "AUDIO_OVERVIEW_STATUS_IN_PROGRESS"
+      }
+    }
+    "#);
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_query_json_output() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    Mock::given(method("POST"))
+        .and(path(format!(
+            "/v1alpha/{}:query",
+            notebook_name(&args, "nb1")
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "answer": { "answerText": "Yes [1].", "state": "SUCCEEDED" },
+            "citations": [{ "sourceId": { "id": "s1" }, "title": "Doc", "text": "Yes." }]
+        })))
+        .mount(&mock.server)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "--json",
+        "notebooks",
+        "query",
+        "--notebook-id",
+        "nb1",
+        "--question",
+        "Does it?",
+    ]);
+
+    insta::assert_json_snapshot!(json_stdout(&mut cmd), @r#"
+    {
+      "notebook_id": "nb1",
+      "query": "Does it?",
+      "response": {
+        "answer": "Yes [1].",
+        "citations": [
+          {
+            "sourceId": {
+              "id": "s1"
+            },
+            "text": "Yes.",
+            "title": "Doc"
+          }
+        ],
+        "state": "SUCCEEDED"
+      }
+    }
+    "#);
+}
+
+#[tokio::test]
+#[serial]
+async fn audio_create_batch_json_output() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    Mock::given(method("POST"))
+        .and(path(format!(
+            "/v1alpha/{}/audioOverviews",
+            notebook_name(&args, "nb1")
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "audioOverview": { "audioOverviewId": "default", "status": "AUDIO_OVERVIEW_STATUS_IN_PROGRESS" }
+        })))
+        .mount(&mock.server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(format!(
+            "/v1alpha/{}/audioOverviews",
+            notebook_name(&args, "nb2")
+        )))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "error": { "code": 400, "message": "Notebook has no sources" }
+        })))
+        .mount(&mock.server)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "--json",
+        "audio",
+        "create-batch",
+        "--notebook-id",
+        "nb1",
+        "--notebook-id",
+        "nb2",
+        "--allow-partial",
+    ]);
+
+    let mut json_output = json_stdout(&mut cmd);
+    let duration = &mut json_output["notebooks"][0]["duration_secs"];
+    assert!(duration.is_number());
+    *duration = serde_json::json!("[duration]");
+    insta::assert_json_snapshot!(json_output, @r#"
+    {
+      "notebooks": [
+        {
+          "notebook_id": "nb1",
+          "state": "AUDIO_OVERVIEW_STATUS_IN_PROGRESS",
+          "duration_secs": "[duration]"
+        },
+        {
+          "notebook_id": "nb2",
+          "state": "FAILED",
+          "duration_secs": null,
+          "error": "http error 400 Bad Request: Notebook has no sources"
+        }
+      ]
+    }
+    "#);
+}
+
+#[tokio::test]
+#[serial]
+async fn audio_download_json_output() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    Mock::given(method("GET"))
+        .and(path(format!(
+            "/v1alpha/{}/audioOverviews/default",
+            notebook_name(&args, "nb1")
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "audioOverview": {
+                "status": "AUDIO_OVERVIEW_STATUS_COMPLETE",
+                "audioUri": format!("{}/media/nb1.mp3", mock.server.uri()),
+            }
+        })))
+        .mount(&mock.server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/media/nb1.mp3"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(b"ID3 audio".to_vec(), "audio/mpeg"))
+        .mount(&mock.server)
+        .await;
+    let dir = tempfile::tempdir().unwrap();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.current_dir(dir.path()).args([
+        "--json",
+        "audio",
+        "download",
+        "--notebook-id",
+        "nb1",
+        "--out",
+        "overview.mp3",
+    ]);
+
+    insta::assert_json_snapshot!(json_stdout(&mut cmd), @r#"
+    {
+      "notebook_id": "nb1",
+      "path": "overview.mp3",
+      "bytes": 9,
+      "content_type": "audio/mpeg"
+    }
+    "#);
+}
+
+#[tokio::test]
+#[serial]
+async fn audio_delete_json_output() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    mock.stub_audio_delete(&args.project_number, &args.location, "nb1")
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["--json", "audio", "delete", "--notebook-id", "nb1"]);
+
+    insta::assert_json_snapshot!(json_stdout(&mut cmd), @r#"
+    {
+      "status": "deleted"
+    }
+    "#);
+}
+
+#[tokio::test]
+#[serial]
+async fn queue_json_output() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    mock.stub_sources_batch_create(&args.project_number, &args.location, "nb1")
+        .await;
+    let config_dir = tempfile::tempdir().unwrap();
+    let queue_cmd = |extra: &[&str]| {
+        let mut cmd = _helpers::cmd::nblm();
+        args.with_base_url(&mut cmd, &mock.base_url());
+        cmd.env("NBLM_CONFIG_DIR", config_dir.path());
+        cmd.arg("--json").arg("queue").args(extra);
+        cmd
+    };
+
+    let mut added = json_stdout(&mut queue_cmd(&[
+        "add",
+        "--notebook-id",
+        "nb1",
+        "--web-url",
+        "https://example.com",
+    ]));
+    // IDs, timestamps and checksums differ on every run.
+    for key in ["id", "queued_at", "checksum"] {
+        added["queued"][0][key] = serde_json::json!(format!("[{key}]"));
+    }
+    insta::assert_json_snapshot!(added, @r#"
+    {
+      "queued": [
+        {
+          "attempts": 0,
+          "checksum": "[checksum]",
+          "id": "[id]",
+          "notebook_id": "nb1",
+          "operation": {
+            "contents": [
+              {
+                "webContent": {
+                  "url": "https://example.com"
+                }
+              }
+            ],
+            "kind": "add_sources"
+          },
+          "queued_at": "[queued_at]"
+        }
+      ]
+    }
+    "#);
+
+    let mut flushed = json_stdout(&mut queue_cmd(&["flush"]));
+    flushed["flushed"][0]["id"] = serde_json::json!("[id]");
+    insta::assert_json_snapshot!(flushed, @r#"
+    {
+      "failed": [],
+      "flushed": [
+        {
+          "id": "[id]",
+          "result": "added 1 source(s)"
+        }
+      ],
+      "remaining": 0
+    }
+    "#);
+
+    insta::assert_json_snapshot!(json_stdout(&mut queue_cmd(&["list"])), @r#"
+    {
+      "entries": []
+    }
+    "#);
+    insta::assert_json_snapshot!(json_stdout(&mut queue_cmd(&["clear"])), @r#"
+    {
+      "remaining": 0,
+      "removed": 0
+    }
+    "#);
+}
+
+#[test]
+fn validate_json_output() {
+    let dir = tempfile::TempDir::new().unwrap();
+    let file = dir.path().join("config.toml");
+    std::fs::write(&file, "project_number = \"123456\"\n").unwrap();
+    let mut cmd = _helpers::cmd::nblm();
+    cmd.current_dir(dir.path())
+        .args(["--json", "validate", "--file", "config.toml"]);
+    insta::assert_json_snapshot!(json_stdout(&mut cmd), @r#"
+    {
+      "file": "config.toml",
+      "kind": "config",
+      "valid": true,
+      "errors": []
+    }
+    "#);
+}
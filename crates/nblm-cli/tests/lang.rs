@@ -0,0 +1,76 @@
+mod _helpers;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use predicates::prelude::*;
+use serde_json::json;
+use serial_test::serial;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+const RECENT: &str = "/v1alpha/projects/123456/locations/global/notebooks:listRecentlyViewed";
+
+async fn expect_language(mock: &MockApi, language: &str) {
+    Mock::given(method("GET"))
+        .and(path(RECENT))
+        .and(header("accept-language", language))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "notebooks": [] })))
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+}
+
+fn recent(mock: &MockApi) -> assert_cmd::Command {
+    let mut cmd = _helpers::cmd::nblm();
+    CommonArgs::default().with_base_url(&mut cmd, &mock.base_url());
+    cmd.env_remove("LC_ALL").env_remove("LANG");
+    cmd.args(["notebooks", "recent"]);
+    cmd
+}
+
+#[tokio::test]
+#[serial]
+async fn lang_flag_sets_accept_language() {
+    let mock = MockApi::start().await;
+    expect_language(&mock, "fr-CA").await;
+
+    recent(&mock)
+        .env("LANG", "de_DE.UTF-8")
+        .args(["--lang", "fr-CA"])
+        .assert()
+        .success();
+}
+
+#[tokio::test]
+#[serial]
+async fn lang_defaults_to_the_locale() {
+    let mock = MockApi::start().await;
+    expect_language(&mock, "pt-BR").await;
+
+    recent(&mock).env("LANG", "pt_BR.UTF-8").assert().success();
+}
+
+#[tokio::test]
+#[serial]
+async fn no_accept_language_for_the_c_locale() {
+    let mock = MockApi::start().await;
+    Mock::given(method("GET"))
+        .and(path(RECENT))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "notebooks": [] })))
+        .mount(&mock.server)
+        .await;
+
+    recent(&mock).env("LANG", "C.UTF-8").assert().success();
+
+    let requests = mock.server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1);
+    assert!(!requests[0].headers.contains_key("accept-language"));
+}
+
+#[test]
+fn invalid_lang_is_rejected() {
+    _helpers::cmd::nblm()
+        .args(["--lang", "english", "notebooks", "recent"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid language tag 'english'"));
+}
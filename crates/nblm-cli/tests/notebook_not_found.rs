@@ -0,0 +1,174 @@
+//! 403s and 404s on a notebook told apart: a missing notebook (which the API may mask as a
+//! 403) versus one that exists but refuses the operation.
+
+mod _helpers;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use assert_cmd::Command;
+use predicates::prelude::*;
+use serde_json::json;
+use serial_test::serial;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, ResponseTemplate};
+
+const NOTEBOOKS: &str = "/v1alpha/projects/123456/locations/global/notebooks";
+
+fn nblm(mock: &MockApi) -> Command {
+    let mut cmd = _helpers::cmd::nblm();
+    CommonArgs::default().with_base_url(&mut cmd, &mock.base_url());
+    cmd.env_remove("RUST_BACKTRACE")
+        .env_remove("RUST_LIB_BACKTRACE");
+    cmd
+}
+
+fn permission_denied() -> ResponseTemplate {
+    ResponseTemplate::new(403).set_body_json(json!({
+        "error": {
+            "code": 403,
+            "message": "Permission denied on resource (or it may not exist).",
+            "status": "PERMISSION_DENIED"
+        }
+    }))
+}
+
+/// `audio create` on `notebook_id` answered with a 403.
+async fn forbid_audio_create(mock: &MockApi, notebook_id: &str) {
+    Mock::given(method("POST"))
+        .and(path(format!("{NOTEBOOKS}/{notebook_id}/audioOverviews")))
+        .respond_with(permission_denied())
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+}
+
+async fn stub_recent(mock: &MockApi, ids: &[&str]) {
+    let notebooks: Vec<_> = ids
+        .iter()
+        .map(|id| json!({"name": format!("projects/123456/locations/global/notebooks/{id}"), "notebookId": id}))
+        .collect();
+    Mock::given(method("GET"))
+        .and(path(format!("{NOTEBOOKS}:listRecentlyViewed")))
+        .and(query_param("fields", "notebooks(name,notebookId)"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"notebooks": notebooks})))
+        .mount(&mock.server)
+        .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn a_masked_403_on_a_missing_notebook_suggests_the_closest_id() {
+    let mock = MockApi::start().await;
+    forbid_audio_create(&mock, "nb-12x").await;
+    Mock::given(method("GET"))
+        .and(path(format!("{NOTEBOOKS}/nb-12x")))
+        .and(query_param("fields", "notebookId"))
+        .respond_with(permission_denied())
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+    stub_recent(&mock, &["research", "nb-123"]).await;
+
+    let output = nblm(&mock)
+        .args(["audio", "create", "--notebook-id", "nb-12x"])
+        .assert()
+        .failure()
+        .get_output()
+        .clone();
+
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "Error: notebook nb-12x not found in project 123456 (did you mean nb-123?); \
+         the API answered 403 Forbidden: Permission denied on resource (or it may not exist).\n"
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn a_403_on_a_visible_notebook_is_a_permission_error() {
+    let mock = MockApi::start().await;
+    forbid_audio_create(&mock, "nb-123").await;
+    Mock::given(method("GET"))
+        .and(path(format!("{NOTEBOOKS}/nb-123")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"notebookId": "nb-123"})))
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+
+    nblm(&mock)
+        .args(["audio", "create", "--notebook-id", "nb-123"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "notebook nb-123 exists, but the API refused this operation on it (403 Forbidden): \
+             Permission denied on resource (or it may not exist).",
+        ))
+        .stderr(predicate::str::contains("not found").not());
+}
+
+#[tokio::test]
+#[serial]
+async fn a_404_inside_a_visible_notebook_is_left_alone() {
+    let mock = MockApi::start().await;
+    Mock::given(method("DELETE"))
+        .and(path(format!("{NOTEBOOKS}/nb-123/audioOverviews/default")))
+        .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+            "error": {"code": 404, "message": "Audio overview not found"}
+        })))
+        .mount(&mock.server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("{NOTEBOOKS}/nb-123")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"notebookId": "nb-123"})))
+        .mount(&mock.server)
+        .await;
+
+    nblm(&mock)
+        .args(["audio", "delete", "--notebook-id", "nb-123"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Audio overview not found"))
+        .stderr(predicate::str::contains("notebook nb-123").not());
+}
+
+#[tokio::test]
+#[serial]
+async fn a_missing_notebook_without_a_close_match_gets_no_suggestion() {
+    let mock = MockApi::start().await;
+    forbid_audio_create(&mock, "typo").await;
+    Mock::given(method("GET"))
+        .and(path(format!("{NOTEBOOKS}/typo")))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&mock.server)
+        .await;
+    stub_recent(&mock, &["nb-123"]).await;
+
+    nblm(&mock)
+        .args(["audio", "create", "--notebook-id", "typo"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "notebook typo not found in project 123456; the API answered 403 Forbidden:",
+        ))
+        .stderr(predicate::str::contains("did you mean").not());
+}
+
+#[tokio::test]
+#[serial]
+async fn other_errors_are_not_probed() {
+    let mock = MockApi::start().await;
+    Mock::given(method("POST"))
+        .and(path(format!("{NOTEBOOKS}/nb-123/audioOverviews")))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+            "error": {"code": 400, "message": "Invalid request.", "status": "INVALID_ARGUMENT"}
+        })))
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+
+    nblm(&mock)
+        .args(["audio", "create", "--notebook-id", "nb-123"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid request."));
+    assert_eq!(mock.server.received_requests().await.unwrap().len(), 1);
+}
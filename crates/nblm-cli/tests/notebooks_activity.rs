@@ -0,0 +1,124 @@
+mod _helpers;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use serial_test::serial;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+/// A notebook with two sources added out of order, one source without a timestamp, and no
+/// audio overview.
+async fn stub_notebook(mock: &MockApi, args: &CommonArgs) {
+    let parent = format!(
+        "projects/{}/locations/{}/notebooks/nb1",
+        args.project_number, args.location
+    );
+    let notebook = serde_json::json!({
+        "name": parent,
+        "notebookId": "nb1",
+        "title": "Activity",
+        "metadata": {
+            "createTime": "2025-01-01T09:00:00Z",
+            "lastViewed": "2025-01-05T12:00:00Z",
+        },
+        "sources": [
+            {
+                "name": format!("{parent}/sources/s2"),
+                "title": "Second",
+                "metadata": {"sourceAddedTimestamp": "2025-01-03T08:00:00Z"},
+            },
+            {
+                "name": format!("{parent}/sources/s1"),
+                "title": "First",
+                "metadata": {"sourceAddedTimestamp": "2025-01-02T08:00:00Z"},
+            },
+            {"name": format!("{parent}/sources/s3"), "title": "Undated"},
+        ],
+    });
+    Mock::given(method("GET"))
+        .and(path(format!("/v1alpha/{parent}")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(notebook))
+        .mount(&mock.server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("/v1alpha/{parent}/audioOverviews/default")))
+        .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+            "error": {"code": 404, "message": "Audio overview not found", "status": "NOT_FOUND"}
+        })))
+        .mount(&mock.server)
+        .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn activity_lists_events_oldest_first() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_notebook(&mock, &args).await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["notebooks", "activity", "--notebook-id", "nb1"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let stdout = String::from_utf8(output).unwrap();
+
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines[0], "Notebook nb1: 4 event(s)");
+    let events: Vec<(&str, &str)> = lines[1..5]
+        .iter()
+        .map(|line| {
+            let line = line.trim_start();
+            let (at, rest) = line.split_once("  ").unwrap();
+            let (_, what) = rest.trim_start().split_once("ago  ").unwrap();
+            (at, what.trim())
+        })
+        .collect();
+    assert_eq!(
+        events,
+        [
+            ("2025-01-01T09:00:00Z", "Notebook created"),
+            ("2025-01-02T08:00:00Z", "Source added: First"),
+            ("2025-01-03T08:00:00Z", "Source added: Second"),
+            ("2025-01-05T12:00:00Z", "Last viewed"),
+        ]
+    );
+    assert_eq!(
+        lines[5],
+        "Note: 1 event(s) lacked timestamps and are not shown."
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn activity_json_lists_typed_events() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_notebook(&mock, &args).await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["--json", "notebooks", "activity", "--notebook-id", "nb1"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+    let kinds: Vec<&str> = json["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|event| event["kind"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            "notebook_created",
+            "source_added",
+            "source_added",
+            "last_viewed"
+        ]
+    );
+    assert_eq!(json["events"][1]["title"], "First");
+    assert_eq!(
+        json["events"][1]["source"],
+        "projects/123456/locations/global/notebooks/nb1/sources/s1"
+    );
+    assert_eq!(json["missing_timestamps"], 1);
+}
@@ -0,0 +1,94 @@
+mod _helpers;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use predicates::prelude::*;
+use serial_test::serial;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+async fn stub_notebook_with_sources(mock: &MockApi, args: &CommonArgs, notebook_id: &str) {
+    let source = |id: &str, title: &str, words: u64| {
+        serde_json::json!({
+            "name": format!(
+                "projects/{}/locations/{}/notebooks/{}/sources/{}",
+                args.project_number, args.location, notebook_id, id
+            ),
+            "title": title,
+            "metadata": { "wordCount": words }
+        })
+    };
+    let response = serde_json::json!({
+        "name": format!(
+            "projects/{}/locations/{}/notebooks/{}",
+            args.project_number, args.location, notebook_id
+        ),
+        "notebookId": notebook_id,
+        "title": "Budget Notebook",
+        "sources": [
+            source("s1", "Small", 1200),
+            source("s2", "Large", 9000),
+            source("s3", "Medium", 4800),
+        ]
+    });
+
+    Mock::given(method("GET"))
+        .and(path(format!(
+            "/v1alpha/projects/{}/locations/{}/notebooks/{}",
+            args.project_number, args.location, notebook_id
+        )))
+        .and(header("authorization", format!("Bearer {}", args.token)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(response))
+        .mount(&mock.server)
+        .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_budget_reports_sorted_totals() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_notebook_with_sources(&mock, &args, "nb-budget").await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "--json",
+        "notebooks",
+        "budget",
+        "--notebook-id",
+        "nb-budget",
+        "--budget",
+        "10000",
+    ]);
+
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let json: serde_json::Value = serde_json::from_slice(&output).expect("valid JSON");
+    assert_eq!(json["total_words"], 15000);
+    assert_eq!(json["budget"], 10000);
+    assert_eq!(json["within_budget"], false);
+    assert_eq!(json["over_by"], 5000);
+    let titles: Vec<&str> = json["sources"]
+        .as_array()
+        .expect("sources array")
+        .iter()
+        .map(|s| s["title"].as_str().unwrap())
+        .collect();
+    assert_eq!(titles, vec!["Large", "Medium", "Small"]);
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_budget_text_verdict_within_budget() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_notebook_with_sources(&mock, &args, "nb-budget").await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["notebooks", "budget", "--notebook-id", "nb-budget"]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("15000 words across 3 source(s)"))
+        .stdout(predicate::str::contains("Within budget"));
+}
@@ -41,3 +41,117 @@ async fn notebooks_create_with_env_base_url() {
         .success()
         .stdout(predicate::str::contains("test-notebook-id"));
 }
+
+#[tokio::test]
+#[serial]
+async fn notebooks_create_turns_an_emoji_shortcode_into_the_emoji() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    mock.stub_notebooks_create(&args.project_number, &args.location, "Reading")
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "notebooks",
+        "create",
+        "--title",
+        "Reading",
+        "--emoji",
+        ":books:",
+    ]);
+    cmd.assert().success();
+
+    let requests = mock.server.received_requests().await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(body["emoji"], "📚");
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_create_rejects_more_than_one_emoji_before_sending() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "notebooks",
+        "create",
+        "--title",
+        "Reading",
+        "--emoji",
+        "📚📘",
+    ]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "invalid emoji '📚📘': only a single emoji is allowed",
+        ))
+        .stderr(predicate::str::contains(":rocket:"));
+    assert!(mock.server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_create_expands_title_placeholders() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    mock.stub_notebooks_create(&args.project_number, &args.location, "Standup")
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.env("NBLM_FAKE_NOW", "2025-03-04T09:05:00+09:00").args([
+        "notebooks",
+        "create",
+        "--expand-title",
+        "--title",
+        "Standup {date} ({date:%a %d %b}, {time}) {{team}}",
+    ]);
+    cmd.assert().success();
+
+    let requests = mock.server.received_requests().await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(
+        body["title"],
+        "Standup 2025-03-04 (Tue 04 Mar, 09:05) {team}"
+    );
+
+    // Without --expand-title the braces are sent as written.
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["notebooks", "create", "--title", "Standup {date}"]);
+    cmd.assert().success();
+    let requests = mock.server.received_requests().await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&requests[1].body).unwrap();
+    assert_eq!(body["title"], "Standup {date}");
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_create_rejects_unknown_title_placeholders_before_sending() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "notebooks",
+        "create",
+        "--expand-title",
+        "--title",
+        "Standup {today}",
+    ]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "invalid template: unknown placeholder {today}",
+        ))
+        .stderr(predicate::str::contains("          ^^^^^^^"));
+    assert!(mock.server.received_requests().await.unwrap().is_empty());
+}
@@ -61,3 +61,303 @@ async fn notebooks_delete_multiple() {
         "Deleted 2 notebook(s) successfully",
     ));
 }
+
+#[tokio::test]
+#[serial]
+async fn notebooks_delete_rejects_cross_project_name() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    let foreign_name = format!(
+        "projects/999999/locations/{}/notebooks/prod-nb",
+        args.location
+    );
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["notebooks", "delete", "--notebook-name", &foreign_name]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("projects/999999/locations/global"))
+        .stderr(predicate::str::contains(format!(
+            "projects/{}/locations/{}",
+            args.project_number, args.location
+        )))
+        .stderr(predicate::str::contains("--allow-cross-project"));
+
+    let requests = mock.server.received_requests().await.unwrap_or_default();
+    assert!(requests.is_empty(), "no HTTP request should be sent");
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_delete_allows_cross_project_with_override() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    mock.stub_notebooks_batch_delete(&args.project_number, &args.location)
+        .await;
+
+    let foreign_name = format!(
+        "projects/999999/locations/{}/notebooks/prod-nb",
+        args.location
+    );
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "notebooks",
+        "delete",
+        "--notebook-name",
+        &foreign_name,
+        "--allow-cross-project",
+    ]);
+
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Deleted 1 notebook(s) successfully",
+    ));
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_delete_multiple_reports_parent() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    mock.stub_notebooks_batch_delete(&args.project_number, &args.location)
+        .await;
+
+    let names: Vec<String> = ["a", "b"]
+        .iter()
+        .map(|id| {
+            format!(
+                "projects/{}/locations/{}/notebooks/{}",
+                args.project_number, args.location, id
+            )
+        })
+        .collect();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "notebooks",
+        "delete",
+        "--notebook-name",
+        &names[0],
+        "--notebook-name",
+        &names[1],
+    ]);
+
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains(format!(
+            "Deleting 2 notebook(s) from projects/{}/locations/{}",
+            args.project_number, args.location
+        )));
+}
+
+fn notebook_names(args: &CommonArgs, count: usize) -> Vec<String> {
+    (1..=count)
+        .map(|i| {
+            format!(
+                "projects/{}/locations/{}/notebooks/nb-{i}",
+                args.project_number, args.location
+            )
+        })
+        .collect()
+}
+
+fn delete_args(names: &[String]) -> Vec<String> {
+    let mut cmd_args = vec!["notebooks".to_string(), "delete".to_string()];
+    for name in names {
+        cmd_args.push("--notebook-name".to_string());
+        cmd_args.push(name.clone());
+    }
+    cmd_args
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_delete_json_merges_the_api_responses() {
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let names: Vec<String> = ["nb-1", "nb-2"]
+        .iter()
+        .map(|id| {
+            format!(
+                "projects/{}/locations/{}/notebooks/{id}",
+                args.project_number, args.location
+            )
+        })
+        .collect();
+    for (name, body) in names.iter().zip([
+        serde_json::json!({"deleteTime": "2025-01-01T00:00:00Z"}),
+        serde_json::json!({"purgeTime": "2025-01-31T00:00:00Z"}),
+    ]) {
+        Mock::given(method("POST"))
+            .and(path(format!(
+                "/v1alpha/projects/{}/locations/{}/notebooks:batchDelete",
+                args.project_number, args.location
+            )))
+            .and(body_partial_json(serde_json::json!({"names": [name]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(&mock.server)
+            .await;
+    }
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.arg("--json").args(delete_args(&names));
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let payload: serde_json::Value = serde_json::from_slice(&output).unwrap();
+
+    assert_eq!(payload["count"], 2);
+    assert_eq!(
+        payload["response"],
+        serde_json::json!({
+            "deleteTime": "2025-01-01T00:00:00Z",
+            "purgeTime": "2025-01-31T00:00:00Z"
+        })
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_delete_over_max_delete_fails_fast_without_terminal() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(delete_args(&notebook_names(&args, 26)));
+    cmd.assert().code(4).stderr(predicate::str::contains(
+        "cannot ask to confirm deleting 26 notebook(s) (more than --max-delete 25) when running non-interactively; pass --yes",
+    ));
+
+    // --non-interactive wins over a terminal.
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.env("NBLM_TEST_ASSUME_TTY", "1")
+        .arg("--non-interactive")
+        .args(delete_args(&notebook_names(&args, 26)))
+        .write_stdin("yes\n");
+    cmd.assert()
+        .code(4)
+        .stderr(predicate::str::contains("About to delete").not());
+
+    let requests = mock.server.received_requests().await.unwrap_or_default();
+    assert!(requests.is_empty(), "no HTTP request should be sent");
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_delete_over_max_delete_with_yes_or_interactive() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    mock.stub_notebooks_batch_delete(&args.project_number, &args.location)
+        .await;
+    let names = notebook_names(&args, 26);
+
+    // --interactive asks even though stdin is not a terminal.
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.arg("--interactive")
+        .args(delete_args(&names[..13]))
+        .args(["--max-delete", "10"])
+        .write_stdin("y\n");
+    cmd.assert().success().stderr(predicate::str::contains(
+        "About to delete 13 notebook(s), more than --max-delete 10.",
+    ));
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(delete_args(&names[13..]))
+        .args(["--max-delete", "10", "--yes"]);
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Deleted 13 notebook(s) successfully",
+    ));
+
+    let requests = mock.server.received_requests().await.unwrap_or_default();
+    assert_eq!(requests.len(), 26);
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_delete_prompt_closed_by_eof_deletes_nothing() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.env("NBLM_TEST_ASSUME_TTY", "1")
+        .args(delete_args(&notebook_names(&args, 3)))
+        .args(["--max-delete", "2"])
+        .write_stdin("");
+
+    cmd.assert()
+        .code(1)
+        .stderr(predicate::str::contains(
+            "About to delete 3 notebook(s), more than --max-delete 2.",
+        ))
+        .stderr(predicate::str::contains("aborted; nothing was deleted"));
+
+    let requests = mock.server.received_requests().await.unwrap_or_default();
+    assert!(requests.is_empty(), "no HTTP request should be sent");
+
+    mock.stub_notebooks_batch_delete(&args.project_number, &args.location)
+        .await;
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.env("NBLM_TEST_ASSUME_TTY", "1")
+        .args(delete_args(&notebook_names(&args, 3)))
+        .args(["--max-delete", "2"])
+        .write_stdin("yes\n");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Deleted 3 notebook(s) successfully",
+    ));
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_delete_raised_max_delete_allows_large_batch() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    mock.stub_notebooks_batch_delete(&args.project_number, &args.location)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(delete_args(&notebook_names(&args, 26)))
+        .args(["--max-delete", "30"]);
+
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Deleted 26 notebook(s) successfully",
+    ));
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_delete_dry_run_lists_names_without_deleting() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let names = notebook_names(&args, 30);
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(delete_args(&names)).arg("--dry-run");
+
+    let mut assert = cmd.assert().success().stderr(predicate::str::contains(
+        "Dry run: would delete 30 notebook(s):",
+    ));
+    for name in &names {
+        assert = assert.stdout(predicate::str::contains(name.as_str()));
+    }
+
+    let requests = mock.server.received_requests().await.unwrap_or_default();
+    assert!(requests.is_empty(), "no HTTP request should be sent");
+}
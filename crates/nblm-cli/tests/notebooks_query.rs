@@ -0,0 +1,115 @@
+mod _helpers;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use predicates::prelude::*;
+use serde_json::{json, Value};
+use serial_test::serial;
+use wiremock::matchers::{body_json, method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+async fn stub_query(mock: &MockApi, args: &CommonArgs, request: Value, response: ResponseTemplate) {
+    Mock::given(method("POST"))
+        .and(path(format!(
+            "/v1alpha/projects/{}/locations/{}/notebooks/nb1:query",
+            args.project_number, args.location
+        )))
+        .and(body_json(request))
+        .respond_with(response)
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+}
+
+fn query_cmd(mock: &MockApi, args: &CommonArgs, extra: &[&str]) -> assert_cmd::Command {
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "notebooks",
+        "query",
+        "--notebook-id",
+        "nb1",
+        "--question",
+        "What changed?",
+    ])
+    .args(extra);
+    cmd
+}
+
+#[tokio::test]
+#[serial]
+async fn query_prints_the_answer_and_numbered_citations() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_query(
+        &mock,
+        &args,
+        json!({ "query": "What changed?", "sourceIds": [{ "id": "s1" }, { "id": "s2" }] }),
+        ResponseTemplate::new(200).set_body_json(json!({
+            "answer": "Revenue grew [1] while costs fell [2].",
+            "citations": [
+                { "sourceId": { "id": "s1" }, "title": "Q3 report", "text": "Revenue grew 12%." },
+                { "sourceId": { "id": "s2" }, "snippet": "Costs fell 3%." },
+            ],
+        })),
+    )
+    .await;
+
+    let output = query_cmd(&mock, &args, &["--source-id", "s1", "--source-id", "s2"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Revenue grew [1] while costs fell [2].\n\
+         \n\
+         Citations:\n  \
+           [1] Q3 report (s1)\n      \
+               Revenue grew 12%.\n  \
+           [2] s2\n      \
+               Costs fell 3%.\n"
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn query_reports_an_empty_answer() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_query(
+        &mock,
+        &args,
+        json!({ "query": "What changed?" }),
+        ResponseTemplate::new(200).set_body_json(json!({ "answer": "" })),
+    )
+    .await;
+
+    query_cmd(&mock, &args, &[])
+        .assert()
+        .success()
+        .stdout("No answer found in the notebook's sources.\n");
+}
+
+#[tokio::test]
+#[serial]
+async fn query_explains_when_the_endpoint_is_unavailable() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_query(
+        &mock,
+        &args,
+        json!({ "query": "What changed?" }),
+        ResponseTemplate::new(404).set_body_json(json!({
+            "error": { "code": 404, "message": "Method not found.", "status": "NOT_FOUND" }
+        })),
+    )
+    .await;
+
+    query_cmd(&mock, &args, &[])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "notebook query is not available for this project or API profile",
+        ));
+}
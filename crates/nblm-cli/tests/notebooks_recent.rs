@@ -38,3 +38,346 @@ async fn notebooks_recent_with_page_size() {
 
     cmd.assert().success();
 }
+
+#[tokio::test]
+#[serial]
+async fn notebooks_recent_pages_with_page_token() {
+    use wiremock::matchers::{method, path, query_param, query_param_is_missing};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let list_path = format!(
+        "/v1alpha/projects/{}/locations/{}/notebooks:listRecentlyViewed",
+        args.project_number, args.location
+    );
+    Mock::given(method("GET"))
+        .and(path(&list_path))
+        .and(query_param_is_missing("pageToken"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "notebooks": [{ "title": "First", "notebookId": "nb1" }],
+            "nextPageToken": "page-2"
+        })))
+        .mount(&mock.server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(&list_path))
+        .and(query_param("pageToken", "page-2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "notebooks": [{ "title": "Second", "notebookId": "nb2" }]
+        })))
+        .mount(&mock.server)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["--json", "notebooks", "recent"]);
+    let output = cmd.assert().success().get_output().clone();
+    let body: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(body["nextPageToken"], "page-2");
+    assert!(output.stderr.is_empty());
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["--json", "notebooks", "recent", "--page-token", "page-2"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let body: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(body["notebooks"][0]["notebookId"], "nb2");
+    assert_eq!(body["nextPageToken"], serde_json::Value::Null);
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["notebooks", "recent"]);
+    cmd.assert().success().stderr(predicate::str::contains(
+        "more results available; use --page-token page-2 for the next page",
+    ));
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["notebooks", "recent", "--page-token", "page-2"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("nb2"))
+        .stderr(predicate::str::contains("more results").not());
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_recent_field_mask_follows_output_mode() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    mock.stub_notebooks_recent(&args.project_number, &args.location)
+        .await;
+
+    let runs: [&[&str]; 4] = [
+        &["notebooks", "recent"],
+        &["--json", "notebooks", "recent"],
+        &["--json", "notebooks", "recent", "--fields", "minimal"],
+        &["notebooks", "recent", "--fields", "notebooks(title)"],
+    ];
+    for run in runs {
+        let mut cmd = _helpers::cmd::nblm();
+        args.with_base_url(&mut cmd, &mock.base_url());
+        cmd.args(run);
+        cmd.assert().success();
+    }
+
+    let minimal = "notebooks(name,notebookId,title,emoji),nextPageToken";
+    let fields: Vec<Option<String>> = mock
+        .server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .map(|request| {
+            request
+                .url
+                .query_pairs()
+                .find(|(key, _)| key == "fields")
+                .map(|(_, value)| value.into_owned())
+        })
+        .collect();
+    assert_eq!(
+        fields,
+        [
+            Some(minimal.to_string()),
+            None,
+            Some(minimal.to_string()),
+            Some("notebooks(title)".to_string()),
+        ]
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_recent_created_after_filters_by_creation_time() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    Mock::given(method("GET"))
+        .and(path(format!(
+            "/v1alpha/projects/{}/locations/{}/notebooks:listRecentlyViewed",
+            args.project_number, args.location
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "notebooks": [
+                { "title": "New", "notebookId": "new", "metadata": { "createTime": "2025-02-01T00:00:00Z" } },
+                { "title": "Old", "notebookId": "old", "metadata": { "createTime": "2024-12-31T23:59:59Z" } },
+                { "title": "Unknown", "notebookId": "unknown" }
+            ]
+        })))
+        .mount(&mock.server)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "--json",
+        "notebooks",
+        "recent",
+        "--created-after",
+        "2025-01-01T00:00:00Z",
+    ]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let body: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let ids: Vec<&str> = body["notebooks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|notebook| notebook["notebookId"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, ["new"]);
+
+    // Text output asks for the full notebooks, since the minimal mask has no creation time.
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["notebooks", "recent", "--created-after", "30d"]);
+    cmd.assert().success();
+    let requests = mock.server.received_requests().await.unwrap();
+    assert!(requests[1]
+        .url
+        .query_pairs()
+        .all(|(key, _)| key != "fields"));
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["notebooks", "recent", "--created-after", "last week"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid time 'last week'"));
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_recent_debug_http_logs_the_response() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    mock.stub_notebooks_recent(&args.project_number, &args.location)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.env_remove("NBLM_DEBUG_HTTP")
+        .args(["--debug-http", "notebooks", "recent"]);
+
+    cmd.assert().success().stderr(predicate::str::contains(
+        "[nblm::http] method=GET status=200",
+    ));
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_recent_since_last_run_lists_only_new_notebooks() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let config_dir = tempfile::tempdir().unwrap();
+    let listing = format!(
+        "/v1alpha/projects/{}/locations/{}/notebooks:listRecentlyViewed",
+        args.project_number, args.location
+    );
+    let first = serde_json::json!({
+        "notebooks": [
+            { "title": "Second", "notebookId": "nb2", "metadata": { "createTime": "2025-02-01T00:00:00Z" } },
+            { "title": "First", "notebookId": "nb1", "metadata": { "createTime": "2025-01-01T00:00:00Z" } }
+        ]
+    });
+    let mut second = first.clone();
+    second["notebooks"].as_array_mut().unwrap().insert(
+        0,
+        serde_json::json!({ "title": "Third", "notebookId": "nb3", "metadata": { "createTime": "2025-03-01T00:00:00Z" } }),
+    );
+
+    let run = |extra: &[&str]| {
+        let mut cmd = _helpers::cmd::nblm();
+        args.with_base_url(&mut cmd, &mock.base_url());
+        cmd.env("NBLM_CONFIG_DIR", config_dir.path())
+            .args(["--json", "notebooks", "recent", "--since-last-run", "slack"])
+            .args(extra);
+        let output = cmd.assert().success().get_output().stdout.clone();
+        let body: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        body["notebooks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|notebook| notebook["notebookId"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    Mock::given(method("GET"))
+        .and(path(listing.as_str()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&first))
+        .mount(&mock.server)
+        .await;
+    assert_eq!(run(&[]), ["nb2", "nb1"]);
+
+    mock.server.reset().await;
+    Mock::given(method("GET"))
+        .and(path(listing.as_str()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(&second))
+        .mount(&mock.server)
+        .await;
+    // --dry-run-mark shows the new notebook without moving the mark.
+    assert_eq!(run(&["--dry-run-mark"]), ["nb3"]);
+    assert_eq!(run(&[]), ["nb3"]);
+    assert!(run(&[]).is_empty());
+
+    let marks: serde_json::Value = serde_json::from_slice(
+        &std::fs::read(config_dir.path().join("recent-marks.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        marks,
+        serde_json::json!({ "schema_version": 1, "marks": { "slack": "2025-03-01T00:00:00Z" } })
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_recent_since_last_run_keeps_the_mark_when_listing_fails() {
+    use wiremock::matchers::method;
+    use wiremock::{Mock, ResponseTemplate};
+
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let config_dir = tempfile::tempdir().unwrap();
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(403).set_body_json(serde_json::json!({
+            "error": { "code": 403, "message": "denied", "status": "PERMISSION_DENIED" }
+        })))
+        .mount(&mock.server)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.env("NBLM_CONFIG_DIR", config_dir.path()).args([
+        "notebooks",
+        "recent",
+        "--since-last-run",
+        "slack",
+    ]);
+    cmd.assert().failure();
+    assert!(!config_dir.path().join("recent-marks.json").exists());
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["notebooks", "recent", "--dry-run-mark"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--since-last-run"));
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["notebooks", "recent", "--since-last-run", "a/b"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid mark name 'a/b'"));
+}
+
+#[tokio::test]
+#[serial]
+async fn notebooks_recent_handles_a_body_without_notebooks() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    Mock::given(method("GET"))
+        .and(path(format!(
+            "/v1alpha/projects/{}/locations/{}/notebooks:listRecentlyViewed",
+            args.project_number, args.location
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+        .mount(&mock.server)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["--json", "notebooks", "recent"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let body: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({"notebooks": [], "nextPageToken": null})
+    );
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["notebooks", "recent", "--created-after", "30d"]);
+    cmd.assert()
+        .success()
+        .stdout("No recently viewed notebooks.\n");
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["notebooks", "sources-count", "--all"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("0 notebook(s), 0 source(s)"));
+}
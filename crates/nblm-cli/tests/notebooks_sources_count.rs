@@ -0,0 +1,150 @@
+mod _helpers;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use serde_json::{json, Value};
+use serial_test::serial;
+use wiremock::matchers::{method, path, query_param, query_param_is_missing};
+use wiremock::{Mock, ResponseTemplate};
+
+const RECENT: &str = "/v1alpha/projects/123456/locations/global/notebooks:listRecentlyViewed";
+const FIELDS: &str = "notebooks(name,notebookId,title,sources(name)),nextPageToken";
+
+/// A notebook as the source-count field mask returns it: identity, title and source names.
+fn skinny(id: &str, title: &str, sources: usize) -> Value {
+    let name = format!("projects/123456/locations/global/notebooks/{id}");
+    json!({
+        "name": name,
+        "notebookId": id,
+        "title": title,
+        "sources": (0..sources)
+            .map(|index| json!({ "name": format!("{name}/sources/s{index}") }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+async fn stub_two_pages(mock: &MockApi) {
+    Mock::given(method("GET"))
+        .and(path(RECENT))
+        .and(query_param_is_missing("pageToken"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "notebooks": [skinny("nb-a", "Alpha", 1), skinny("nb-b", "Beta", 3)],
+            "nextPageToken": "page-2",
+        })))
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+    // nb-b shows up again because it was viewed while the pages were read.
+    Mock::given(method("GET"))
+        .and(path(RECENT))
+        .and(query_param("pageToken", "page-2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "notebooks": [skinny("nb-c", "Gamma", 5), skinny("nb-b", "Beta", 3), skinny("nb-d", "Delta", 0)],
+        })))
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_count_all_pages_sorted_by_count_with_a_field_mask() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_two_pages(&mock).await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["notebooks", "sources-count", "--all"]);
+
+    let output = cmd.assert().success().get_output().clone();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let rows: Vec<Vec<&str>> = stdout
+        .lines()
+        .map(|line| line.split_whitespace().collect())
+        .collect();
+    assert_eq!(
+        rows,
+        [
+            vec!["SOURCES", "NOTEBOOK", "TITLE"],
+            vec!["5", "nb-c", "Gamma"],
+            vec!["3", "nb-b", "Beta"],
+            vec!["1", "nb-a", "Alpha"],
+            vec!["0", "nb-d", "Delta"],
+            vec!["4", "notebook(s),", "9", "source(s)"],
+        ],
+        "{stdout}"
+    );
+
+    let requests = mock.server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 2);
+    for request in &requests {
+        let fields = request
+            .url
+            .query_pairs()
+            .find(|(key, _)| key == "fields")
+            .map(|(_, value)| value.into_owned());
+        assert_eq!(fields.as_deref(), Some(FIELDS), "{}", request.url);
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_count_writes_csv_and_json() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_two_pages(&mock).await;
+    let dir = tempfile::tempdir().unwrap();
+    let csv = dir.path().join("counts.csv");
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["--json", "notebooks", "sources-count", "--all", "--out"])
+        .arg(&csv);
+
+    let output = cmd.assert().success().get_output().clone();
+    let payload: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(payload["total_notebooks"], 4);
+    assert_eq!(payload["total_sources"], 9);
+    assert_eq!(payload["notebooks"][0]["notebook_id"], "nb-c");
+    assert_eq!(
+        std::fs::read_to_string(&csv).unwrap(),
+        "notebook_id,title,sources\nnb-c,Gamma,5\nnb-b,Beta,3\nnb-a,Alpha,1\nnb-d,Delta,0\n"
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_count_needs_all_or_notebook_ids() {
+    let mut cmd = _helpers::cmd::nblm();
+    CommonArgs::default().apply(&mut cmd);
+    cmd.args(["notebooks", "sources-count"]);
+    let output = cmd.assert().failure().get_output().clone();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("--all"), "{stderr}");
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_count_fetches_a_repeated_notebook_once() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    for (id, sources) in [("nb-a", 1), ("nb-b", 3)] {
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/v1alpha/projects/123456/locations/global/notebooks/{id}"
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_json(skinny(id, id, sources)))
+            .expect(1)
+            .mount(&mock.server)
+            .await;
+    }
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["notebooks", "sources-count"]);
+    for id in ["nb-a", "nb-b", "nb-a", "nb-b", "nb-a"] {
+        cmd.args(["--notebook-id", id]);
+    }
+    cmd.assert().success();
+    // Mock expectations verify each notebook was requested once.
+}
@@ -0,0 +1,79 @@
+mod _helpers;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use serde_json::json;
+use serial_test::serial;
+use wiremock::matchers::{body_json, method, path, query_param};
+use wiremock::{Mock, ResponseTemplate};
+
+const NOTEBOOK: &str = "/v1alpha/projects/123456/locations/global/notebooks/nb1";
+
+async fn stub_get(mock: &MockApi) {
+    Mock::given(method("GET"))
+        .and(path(NOTEBOOK))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "name": "projects/123456/locations/global/notebooks/nb1",
+            "notebookId": "nb1",
+            "title": "Research",
+            "emoji": "📘",
+        })))
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+}
+
+fn update(mock: &MockApi, extra: &[&str]) -> assert_cmd::Command {
+    let mut cmd = _helpers::cmd::nblm();
+    CommonArgs::default().with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["notebooks", "update", "--notebook-id", "nb1", "--show-diff"])
+        .args(extra);
+    cmd
+}
+
+#[tokio::test]
+#[serial]
+async fn show_diff_prints_the_changes_then_updates() {
+    let mock = MockApi::start().await;
+    stub_get(&mock).await;
+    Mock::given(method("PATCH"))
+        .and(path(NOTEBOOK))
+        .and(query_param("updateMask", "title"))
+        .and(body_json(json!({ "title": "Research 2025" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "notebookId": "nb1",
+            "title": "Research 2025",
+            "emoji": "📘",
+        })))
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+
+    let output = update(&mock, &["--title", "Research 2025"])
+        .env("NO_COLOR", "1")
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.starts_with("- title: Research\n+ title: Research 2025\n"),
+        "{stdout}"
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn show_diff_without_changes_sends_no_update() {
+    let mock = MockApi::start().await;
+    stub_get(&mock).await;
+
+    update(&mock, &["--title", "Research", "--emoji", "📘"])
+        .assert()
+        .success()
+        .stdout("no changes\n");
+
+    let requests = mock.server.received_requests().await.unwrap();
+    assert!(requests
+        .iter()
+        .all(|request| request.method.as_str() == "GET"));
+}
@@ -0,0 +1,126 @@
+//! Byte-exact endings of stdout and stderr, as a consumer reading the pipes sees them.
+
+mod _helpers;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use assert_cmd::Command;
+use serial_test::serial;
+
+/// The command with the mock's base URL and no backtraces, so stderr is just the error.
+fn nblm(mock: &MockApi) -> Command {
+    let mut cmd = _helpers::cmd::nblm();
+    CommonArgs::default().with_base_url(&mut cmd, &mock.base_url());
+    cmd.env_remove("RUST_BACKTRACE")
+        .env_remove("RUST_LIB_BACKTRACE");
+    cmd
+}
+
+fn ends_with_one_newline(bytes: &[u8]) -> bool {
+    bytes.ends_with(b"\n") && !bytes.ends_with(b"\n\n")
+}
+
+#[tokio::test]
+#[serial]
+async fn successful_text_output_ends_with_one_newline() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    mock.stub_notebooks_recent(&args.project_number, &args.location)
+        .await;
+
+    let output = nblm(&mock)
+        .args(["notebooks", "recent"])
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+
+    assert!(ends_with_one_newline(&output.stdout), "{output:?}");
+    assert!(output.stderr.is_empty(), "{output:?}");
+}
+
+#[tokio::test]
+#[serial]
+async fn successful_json_output_is_one_document_and_one_newline() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    mock.stub_notebooks_recent_429_then_success(&args.project_number, &args.location, 2)
+        .await;
+
+    let output = nblm(&mock)
+        .args(["--json", "notebooks", "recent"])
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+
+    // The retry notes went to stderr; stdout is the payload alone.
+    assert!(!output.stderr.is_empty());
+    assert!(ends_with_one_newline(&output.stderr), "{output:?}");
+    assert!(ends_with_one_newline(&output.stdout), "{output:?}");
+    let document = &output.stdout[..output.stdout.len() - 1];
+    serde_json::from_slice::<serde_json::Value>(document).expect("stdout is one JSON document");
+}
+
+#[tokio::test]
+#[serial]
+async fn api_failures_leave_stdout_empty_and_end_stderr_with_one_newline() {
+    for json in [false, true] {
+        let mock = MockApi::start().await;
+        let args = CommonArgs::default();
+        mock.stub_notebooks_recent_persistent_429(&args.project_number, &args.location)
+            .await;
+
+        let mut cmd = nblm(&mock);
+        if json {
+            cmd.arg("--json");
+        }
+        let output = cmd
+            .args(["notebooks", "recent"])
+            .assert()
+            .failure()
+            .get_output()
+            .clone();
+
+        assert_eq!(output.stdout, b"", "json={json}");
+        assert!(ends_with_one_newline(&output.stderr), "{output:?}");
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn validation_failures_print_only_the_error() {
+    let mock = MockApi::start().await;
+
+    for mode in ["ids", "titles"] {
+        let output = nblm(&mock)
+            .args(["--json", "sources", "list", "--notebook-id", "nb1"])
+            .args(["--output", mode])
+            .assert()
+            .failure()
+            .get_output()
+            .clone();
+
+        assert_eq!(output.stdout, b"");
+        assert_eq!(
+            String::from_utf8(output.stderr).unwrap(),
+            format!("Error: --output {mode} cannot be combined with --json\n")
+        );
+    }
+
+    // Rejected by nblm-core before any request, in text and JSON mode alike.
+    for json in [false, true] {
+        let mut cmd = nblm(&mock);
+        if json {
+            cmd.arg("--json");
+        }
+        let output = cmd
+            .args(["sources", "list", "--notebook-id", "a/b"])
+            .assert()
+            .failure()
+            .get_output()
+            .clone();
+
+        assert_eq!(output.stdout, b"", "json={json}");
+        assert!(ends_with_one_newline(&output.stderr), "{output:?}");
+    }
+}
@@ -0,0 +1,252 @@
+mod _helpers;
+
+use std::path::Path;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use predicates::prelude::*;
+use serde_json::{json, Value};
+use serial_test::serial;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+fn queue_cmd(config_dir: &Path, base_url: &str, args: &[&str]) -> assert_cmd::Command {
+    let mut cmd = _helpers::cmd::nblm();
+    CommonArgs::default().with_base_url(&mut cmd, base_url);
+    cmd.env("NBLM_CONFIG_DIR", config_dir);
+    cmd.arg("queue").args(args);
+    cmd
+}
+
+fn list_entries(config_dir: &Path, base_url: &str) -> Vec<Value> {
+    let output = queue_cmd(config_dir, base_url, &["list"])
+        .arg("--json")
+        .output()
+        .expect("run queue list");
+    assert!(output.status.success());
+    let payload: Value = serde_json::from_slice(&output.stdout).expect("list JSON");
+    payload["entries"].as_array().cloned().unwrap_or_default()
+}
+
+#[tokio::test]
+#[serial]
+async fn queue_add_then_list() {
+    let mock = MockApi::start().await;
+    let config = tempfile::tempdir().unwrap();
+    let file = config.path().join("notes.txt");
+    std::fs::write(&file, "field notes").unwrap();
+
+    queue_cmd(
+        config.path(),
+        &mock.base_url(),
+        &[
+            "add",
+            "--notebook-id",
+            "nb1",
+            "--web-url",
+            "https://example.com",
+            "--file",
+            file.to_str().unwrap(),
+        ],
+    )
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("2 operation(s) in the queue"));
+
+    let entries = list_entries(config.path(), &mock.base_url());
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["notebook_id"], "nb1");
+    assert_eq!(entries[0]["operation"]["kind"], "add_sources");
+    assert_eq!(
+        entries[0]["operation"]["contents"][0]["webContent"]["url"],
+        "https://example.com"
+    );
+    assert_eq!(entries[1]["operation"]["kind"], "upload_file");
+    assert_eq!(entries[1]["operation"]["file_name"], "notes.txt");
+    assert_eq!(entries[1]["operation"]["size"], 11);
+
+    queue_cmd(config.path(), &mock.base_url(), &["list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("add 1 source(s)"))
+        .stdout(predicate::str::contains("upload "));
+}
+
+#[tokio::test]
+#[serial]
+async fn queue_flush_keeps_only_the_failed_entry() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let config = tempfile::tempdir().unwrap();
+    let file = config.path().join("notes.txt");
+    std::fs::write(&file, "field notes").unwrap();
+
+    mock.stub_sources_batch_create(&args.project_number, &args.location, "nb-ok")
+        .await;
+    Mock::given(method("POST"))
+        .and(path(format!(
+            "/upload/v1alpha/projects/{}/locations/{}/notebooks/nb-missing/sources:uploadFile",
+            args.project_number, args.location
+        )))
+        .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+            "error": {"code": 404, "message": "notebook not found", "status": "NOT_FOUND"}
+        })))
+        .mount(&mock.server)
+        .await;
+
+    queue_cmd(
+        config.path(),
+        &mock.base_url(),
+        &[
+            "add",
+            "--notebook-id",
+            "nb-ok",
+            "--web-url",
+            "https://example.com",
+        ],
+    )
+    .assert()
+    .success();
+    queue_cmd(
+        config.path(),
+        &mock.base_url(),
+        &[
+            "add",
+            "--notebook-id",
+            "nb-missing",
+            "--file",
+            file.to_str().unwrap(),
+        ],
+    )
+    .assert()
+    .success();
+
+    queue_cmd(config.path(), &mock.base_url(), &["flush"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "Flushed 1 of 2 queued operation(s)",
+        ))
+        .stderr(predicate::str::contains(
+            "1 of 2 queued operation(s) failed",
+        ));
+
+    let entries = list_entries(config.path(), &mock.base_url());
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["notebook_id"], "nb-missing");
+    assert_eq!(entries[0]["attempts"], 1);
+    assert!(entries[0]["last_error"]
+        .as_str()
+        .unwrap()
+        .contains("notebook not found"));
+}
+
+#[tokio::test]
+#[serial]
+async fn queue_flush_rejects_modified_file() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let config = tempfile::tempdir().unwrap();
+    let file = config.path().join("notes.txt");
+    std::fs::write(&file, "original").unwrap();
+
+    Mock::given(method("POST"))
+        .and(path(format!(
+            "/upload/v1alpha/projects/{}/locations/{}/notebooks/nb1/sources:uploadFile",
+            args.project_number, args.location
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+        .expect(0)
+        .mount(&mock.server)
+        .await;
+
+    queue_cmd(
+        config.path(),
+        &mock.base_url(),
+        &[
+            "add",
+            "--notebook-id",
+            "nb1",
+            "--file",
+            file.to_str().unwrap(),
+        ],
+    )
+    .assert()
+    .success();
+    std::fs::write(&file, "edited after queueing").unwrap();
+
+    queue_cmd(config.path(), &mock.base_url(), &["flush", "--json"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("was modified after it was queued"));
+
+    assert_eq!(list_entries(config.path(), &mock.base_url()).len(), 1);
+}
+
+#[tokio::test]
+#[serial]
+async fn queue_clear_removes_entries() {
+    let mock = MockApi::start().await;
+    let config = tempfile::tempdir().unwrap();
+
+    queue_cmd(
+        config.path(),
+        &mock.base_url(),
+        &["add", "--notebook-id", "nb1", "--text", "hello"],
+    )
+    .assert()
+    .success();
+
+    queue_cmd(config.path(), &mock.base_url(), &["clear", "--id", "nope"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "no queued operation with ID 'nope'",
+        ));
+
+    queue_cmd(config.path(), &mock.base_url(), &["clear"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed 1 queued operation(s)"));
+    assert!(list_entries(config.path(), &mock.base_url()).is_empty());
+}
+
+#[test]
+#[serial]
+fn queue_add_list_and_clear_need_no_credentials_or_network() {
+    let bin = tempfile::tempdir().unwrap();
+    let config = tempfile::tempdir().unwrap();
+
+    for auth in ["gcloud", "env", "user-oauth"] {
+        // No gcloud on PATH, no NBLM_ACCESS_TOKEN, no stored OAuth tokens, and an API endpoint
+        // nothing listens on.
+        let queue = |args: &[&str]| {
+            let mut cmd = _helpers::cmd::nblm();
+            cmd.env_clear()
+                .env("PATH", bin.path())
+                .env("HOME", config.path())
+                .env("NBLM_CONFIG_DIR", config.path())
+                .env("NBLM_PROFILE_EXPERIMENT", "1")
+                .env("NBLM_OAUTH_CLIENT_ID", "test-client-id")
+                .args(["--auth", auth, "--project-number", "123456"])
+                .args(["--base-url", "http://127.0.0.1:9/v1alpha"])
+                .arg("queue")
+                .args(args);
+            cmd
+        };
+
+        queue(&["add", "--notebook-id", "nb1", "--text", "hello"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("1 operation(s) in the queue"));
+        queue(&["list"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("add 1 source(s)"));
+        // Only flush needs them.
+        queue(&["flush"]).assert().failure();
+        queue(&["clear"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Removed 1 queued operation(s)"));
+    }
+}
@@ -0,0 +1,91 @@
+mod _helpers;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use serde_json::{json, Value};
+use serial_test::serial;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+const PROJECT: &str = "987654321";
+
+async fn stub_recent(mock: &MockApi) {
+    let parent = format!("projects/{PROJECT}/locations/global");
+    Mock::given(method("GET"))
+        .and(path(format!(
+            "/v1alpha/{parent}/notebooks:listRecentlyViewed"
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "notebooks": [
+                {
+                    "name": format!("{parent}/notebooks/nb1"),
+                    "notebookId": "nb1",
+                    "title": "Acquisition plans",
+                    "metadata": { "owner": "ada@example.com", "note": format!("billed to {PROJECT}") },
+                },
+                {
+                    "name": format!("{parent}/notebooks/nb2"),
+                    "notebookId": "nb2",
+                    "title": "Acquisition plans",
+                    "metadata": { "owner": "ADA@example.com" },
+                },
+            ]
+        })))
+        .mount(&mock.server)
+        .await;
+}
+
+fn recent(mock: &MockApi, redact: &str) -> (String, String) {
+    let args = CommonArgs {
+        project_number: PROJECT.to_string(),
+        ..CommonArgs::default()
+    };
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["notebooks", "recent", "--json", redact]);
+    let output = cmd.assert().success().get_output().clone();
+    (
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+#[tokio::test]
+#[serial]
+async fn redact_removes_the_project_number_and_emails_from_json_output() {
+    let mock = MockApi::start().await;
+    stub_recent(&mock).await;
+
+    let (stdout, stderr) = recent(&mock, "--redact");
+    for stream in [&stdout, &stderr] {
+        assert!(!stream.contains(PROJECT), "{stream}");
+        assert!(!stream.contains("example.com"), "{stream}");
+    }
+
+    let payload: Value = serde_json::from_str(&stdout).unwrap();
+    let notebooks = payload["notebooks"].as_array().unwrap();
+    assert_eq!(
+        notebooks[0]["name"],
+        "projects/PROJECT_NUMBER/locations/global/notebooks/nb1"
+    );
+    assert_eq!(notebooks[0]["metadata"]["note"], "billed to PROJECT_NUMBER");
+    assert_eq!(notebooks[0]["title"], "Acquisition plans");
+    // Same address, same placeholder.
+    assert_eq!(
+        notebooks[0]["metadata"]["owner"],
+        notebooks[1]["metadata"]["owner"]
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn aggressive_redaction_also_replaces_titles() {
+    let mock = MockApi::start().await;
+    stub_recent(&mock).await;
+
+    let (stdout, _) = recent(&mock, "--redact=aggressive");
+    assert!(!stdout.contains("Acquisition"), "{stdout}");
+
+    let payload: Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(payload["notebooks"][0]["title"], "REDACTED_TITLE_1");
+    assert_eq!(payload["notebooks"][1]["title"], "REDACTED_TITLE_1");
+}
@@ -1,8 +1,10 @@
 mod _helpers;
 
-use _helpers::{cmd::CommonArgs, mock::MockApi};
+use _helpers::{cmd::CommonArgs, mock::MockApi, shim::Project};
 use predicates::prelude::*;
 use serial_test::serial;
+use wiremock::matchers::{body_json, method, path};
+use wiremock::{Mock, ResponseTemplate};
 
 #[tokio::test]
 #[serial]
@@ -31,3 +33,603 @@ async fn sources_add_web_url() {
         .success()
         .stdout(predicate::str::contains("sources"));
 }
+
+fn batch_create_path(args: &CommonArgs, notebook_id: &str) -> String {
+    format!(
+        "/v1alpha/projects/{}/locations/{}/notebooks/{}/sources:batchCreate",
+        args.project_number, args.location, notebook_id
+    )
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_add_sends_web_fetch_options() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "test-notebook";
+
+    Mock::given(method("POST"))
+        .and(path(batch_create_path(&args, notebook_id)))
+        .and(body_json(serde_json::json!({
+            "userContents": [
+                {
+                    "webContent": {
+                        "url": "https://a.example.com",
+                        "fetchOptions": { "userAgent": "Mozilla/5.0", "waitForRender": true, "futureOption": 2 }
+                    }
+                },
+                {
+                    "webContent": {
+                        "url": "https://b.example.com",
+                        "fetchOptions": { "userAgent": "Mozilla/5.0", "waitForRender": true, "futureOption": 2 }
+                    }
+                }
+            ]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({"sources": []})))
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "sources",
+        "add",
+        "--notebook-id",
+        notebook_id,
+        "--web-url",
+        "https://a.example.com",
+        "--web-url",
+        "https://b.example.com",
+        "--web-fetch-option",
+        "userAgent=Mozilla/5.0",
+        "--web-fetch-option",
+        "waitForRender=true",
+        "--web-fetch-option",
+        "futureOption=2",
+    ]);
+
+    cmd.assert().success();
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_add_omits_fetch_options_when_none_given() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "test-notebook";
+
+    mock.stub_sources_batch_create(&args.project_number, &args.location, notebook_id)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "sources",
+        "add",
+        "--notebook-id",
+        notebook_id,
+        "--web-url",
+        "https://example.com",
+    ]);
+    cmd.assert().success();
+
+    let requests = mock.server.received_requests().await.unwrap_or_default();
+    let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({ "userContents": [{ "webContent": { "url": "https://example.com" } }] })
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_add_rejects_fetch_options_without_web_urls() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "sources",
+        "add",
+        "--notebook-id",
+        "test-notebook",
+        "--text",
+        "hello",
+        "--web-fetch-option",
+        "waitForRender=true",
+    ]);
+
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "--web-fetch-option requires at least one --web-url",
+    ));
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_add_trims_names_and_omits_empty_ones() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "test-notebook";
+
+    mock.stub_sources_batch_create(&args.project_number, &args.location, notebook_id)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "sources",
+        "add",
+        "--notebook-id",
+        notebook_id,
+        "--web-url",
+        "https://example.com",
+        "--web-name",
+        "  Example  ",
+        "--text",
+        "Some notes",
+        "--text-name",
+        "",
+    ]);
+    cmd.assert().success();
+
+    let requests = mock.server.received_requests().await.unwrap_or_default();
+    let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({ "userContents": [
+            { "webContent": { "url": "https://example.com", "sourceName": "Example" } },
+            { "textContent": { "content": "Some notes" } }
+        ] })
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_add_rejects_over_long_names_before_sending() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "sources",
+        "add",
+        "--notebook-id",
+        "test-notebook",
+        "--text",
+        "Some notes",
+        "--text-name",
+        &"n".repeat(501),
+    ]);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "--text-name: source name is 501 characters long (maximum 500)",
+    ));
+
+    let requests = mock.server.received_requests().await.unwrap_or_default();
+    assert!(requests.is_empty());
+}
+
+fn source(args: &CommonArgs, notebook_id: &str, id: &str, status: &str) -> serde_json::Value {
+    serde_json::json!({
+        "name": format!(
+            "projects/{}/locations/{}/notebooks/{notebook_id}/sources/{id}",
+            args.project_number, args.location
+        ),
+        "settings": { "status": status },
+    })
+}
+
+/// Two sources submitted pending: `s1` is complete at the first check, `s2` at the second.
+async fn mock_two_source_ingestion(mock: &MockApi, args: &CommonArgs, notebook_id: &str) {
+    let pending = |id| source(args, notebook_id, id, "SOURCE_STATUS_PENDING");
+    let complete = |id| source(args, notebook_id, id, "SOURCE_STATUS_COMPLETE");
+    Mock::given(method("POST"))
+        .and(path(batch_create_path(args, notebook_id)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "sources": [pending("s1"), pending("s2")]
+        })))
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+    let source_path = |id: &str| {
+        format!(
+            "/v1alpha/projects/{}/locations/{}/notebooks/{notebook_id}/sources/{id}",
+            args.project_number, args.location
+        )
+    };
+    Mock::given(method("GET"))
+        .and(path(source_path("s1")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(complete("s1")))
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(source_path("s2")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(pending("s2")))
+        .up_to_n_times(1)
+        .mount(&mock.server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(source_path("s2")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(complete("s2")))
+        .mount(&mock.server)
+        .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_add_wait_reports_the_time_each_source_took() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "test-notebook";
+    mock_two_source_ingestion(&mock, &args, notebook_id).await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "--json",
+        "sources",
+        "add",
+        "--notebook-id",
+        notebook_id,
+        "--web-url",
+        "https://example.com",
+        "--text",
+        "Some notes",
+        "--wait",
+        "--poll-interval",
+        "10ms",
+    ]);
+    let output = cmd.assert().success().get_output().clone();
+    let summary: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    let sources = summary["sources"].as_array().unwrap();
+    let column = |name: &str| -> Vec<serde_json::Value> {
+        sources.iter().map(|source| source[name].clone()).collect()
+    };
+    assert_eq!(column("source_id"), ["s1", "s2"]);
+    assert_eq!(column("kind"), ["web", "text"]);
+    assert_eq!(
+        column("state"),
+        ["SOURCE_STATUS_COMPLETE", "SOURCE_STATUS_COMPLETE"]
+    );
+    assert_eq!(column("polls"), [1, 2]);
+    let durations: Vec<f64> = column("duration_secs")
+        .iter()
+        .map(|secs| secs.as_f64().unwrap())
+        .collect();
+    // At least the poll interval (then 1.5 times it for the second check), and well under
+    // the timeout.
+    assert!(
+        durations[0] >= 0.01 && durations[1] >= 0.025,
+        "{durations:?}"
+    );
+    assert!(durations.iter().all(|secs| *secs < 30.0), "{durations:?}");
+
+    let by_kind = summary["by_kind"].as_array().unwrap();
+    let kinds: Vec<_> = by_kind.iter().map(|kind| kind["kind"].clone()).collect();
+    assert_eq!(kinds, ["text", "web"]);
+    for (kind, secs) in by_kind.iter().zip([durations[1], durations[0]]) {
+        assert_eq!(kind["count"], 1);
+        assert_eq!(kind["median_secs"].as_f64(), Some(secs));
+        assert_eq!(kind["max_secs"].as_f64(), Some(secs));
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_add_wait_prints_a_timing_table() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "test-notebook";
+    mock_two_source_ingestion(&mock, &args, notebook_id).await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "sources",
+        "add",
+        "--notebook-id",
+        notebook_id,
+        "--web-url",
+        "https://example.com",
+        "--text",
+        "Some notes",
+        "--wait",
+        "--poll-interval",
+        "10ms",
+    ]);
+    let output = cmd.assert().success().get_output().clone();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<Vec<&str>> = stdout
+        .lines()
+        .map(|line| line.split_whitespace().collect())
+        .collect();
+
+    assert_eq!(lines[0], ["SOURCE", "KIND", "STATE", "DURATION", "POLLS"]);
+    assert_eq!(lines[1], ["s1", "web", "SOURCE_STATUS_COMPLETE", "0s", "1"]);
+    assert_eq!(
+        lines[2],
+        ["s2", "text", "SOURCE_STATUS_COMPLETE", "0s", "2"]
+    );
+    assert_eq!(lines[4], ["KIND", "COUNT", "MEDIAN", "MAX"]);
+    assert_eq!(lines[5], ["text", "1", "0s", "0s"]);
+    assert_eq!(lines[6], ["web", "1", "0s", "0s"]);
+}
+
+#[test]
+fn sources_add_wait_cannot_be_combined_with_retry_failed() {
+    let project = Project::new();
+
+    project
+        .nblm()
+        .args([
+            "sources",
+            "add",
+            "--notebook-id",
+            "test-notebook",
+            "--text",
+            "hello",
+            "--wait",
+            "--retry-failed",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+/// Split a command line into words the way `sh` would for quotes and plain words.
+fn shell_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' => words.extend(word.take()),
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                word.extend(chars.by_ref().take_while(|&c| c != '\''));
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                word.extend(chars.by_ref().take_while(|&c| c != '"'));
+            }
+            '\\' => word.get_or_insert_with(String::new).extend(chars.next()),
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    words
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_add_show_curl_prints_a_runnable_command_without_the_token() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "test-notebook";
+
+    mock.stub_sources_batch_create(&args.project_number, &args.location, notebook_id)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "--show-curl",
+        "sources",
+        "add",
+        "--notebook-id",
+        notebook_id,
+        "--text",
+        "It's a note",
+    ]);
+    let output = cmd.assert().success().get_output().clone();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(!stderr.contains(&args.token), "{stderr}");
+    let line = stderr
+        .lines()
+        .find(|line| line.starts_with("curl "))
+        .unwrap_or_else(|| panic!("no curl line in {stderr}"));
+    let words = shell_words(line);
+    let expected_url = format!(
+        "{}{}",
+        mock.base_url().trim_end_matches("/v1alpha"),
+        batch_create_path(&args, notebook_id)
+    );
+    assert_eq!(words[..4], ["curl", "-X", "POST", expected_url.as_str()]);
+    assert!(words
+        .windows(2)
+        .any(|pair| pair == ["-H", "Authorization: Bearer $TOKEN"]));
+    let body = words
+        .windows(2)
+        .find(|pair| pair[0] == "--data-raw")
+        .map(|pair| pair[1].as_str())
+        .unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(body).unwrap(),
+        serde_json::json!({ "userContents": [{ "textContent": { "content": "It's a note" } }] })
+    );
+}
+
+#[test]
+fn sources_add_reads_urls_from_stdin_one_per_line() {
+    let project = Project::new();
+    project.backend.insert_notebook("test-notebook", "Research");
+
+    project
+        .nblm()
+        .args([
+            "sources",
+            "add",
+            "--notebook-id",
+            "test-notebook",
+            "--text",
+            "Some notes",
+            "--stdin",
+        ])
+        .write_stdin("https://a.example.com\n\n  https://b.example.com\n")
+        .assert()
+        .success();
+
+    let notebook = project.backend.notebook("test-notebook").unwrap();
+    assert_eq!(notebook.sources.len(), 3);
+}
+
+#[test]
+fn sources_add_reads_a_mixed_json_array_from_stdin() {
+    let project = Project::new();
+    project.backend.insert_notebook("test-notebook", "Research");
+    let input = serde_json::json!([
+        "https://a.example.com",
+        { "type": "web", "url": "https://b.example.com", "title": "B" },
+        { "type": "text", "text": "Meeting notes", "name": "Notes" },
+    ]);
+
+    project
+        .nblm()
+        .args([
+            "sources",
+            "add",
+            "--notebook-id",
+            "test-notebook",
+            "--stdin",
+            "--stdin-format",
+            "json",
+        ])
+        .write_stdin(input.to_string())
+        .assert()
+        .success();
+
+    let titles: Vec<_> = project
+        .backend
+        .notebook("test-notebook")
+        .unwrap()
+        .sources
+        .into_iter()
+        .filter_map(|source| source.title)
+        .collect();
+    assert_eq!(titles[1..], ["B", "Notes"]);
+}
+
+#[test]
+fn sources_add_names_the_invalid_stdin_entry_and_sends_nothing() {
+    let project = Project::new();
+    project.backend.insert_notebook("test-notebook", "Research");
+
+    project
+        .nblm()
+        .args([
+            "sources",
+            "add",
+            "--notebook-id",
+            "test-notebook",
+            "--stdin",
+            "--stdin-format",
+            "json",
+        ])
+        .write_stdin(r#"["https://a.example.com", {"type": "video"}]"#)
+        .env_remove("RUST_BACKTRACE")
+        .env_remove("RUST_LIB_BACKTRACE")
+        .assert()
+        .failure()
+        .stderr("Error: stdin entry 1: missing field `url`\n");
+    assert!(project
+        .backend
+        .notebook("test-notebook")
+        .unwrap()
+        .sources
+        .is_empty());
+}
+
+#[test]
+fn sources_add_stdin_format_needs_stdin() {
+    Project::new()
+        .nblm()
+        .args([
+            "sources",
+            "add",
+            "--notebook-id",
+            "test-notebook",
+            "--stdin-format",
+            "json",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--stdin"));
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_add_splits_large_stdin_input_into_several_requests() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "test-notebook";
+    let prefix = format!(
+        "projects/{}/locations/{}/notebooks/{notebook_id}/sources",
+        args.project_number, args.location
+    );
+    // Answer each request with one source per submitted URL, named after its position.
+    Mock::given(method("POST"))
+        .and(path(batch_create_path(&args, notebook_id)))
+        .respond_with(move |request: &wiremock::Request| {
+            let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            let sources: Vec<_> = body["userContents"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|content| {
+                    let url = content["webContent"]["url"].as_str().unwrap();
+                    let id = url.rsplit('/').next().unwrap();
+                    serde_json::json!({ "name": format!("{prefix}/{id}") })
+                })
+                .collect();
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({ "sources": sources }))
+        })
+        .expect(3)
+        .mount(&mock.server)
+        .await;
+    let urls: Vec<String> = (0..300)
+        .map(|n| format!("https://example.com/s{n}"))
+        .collect();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "--json",
+        "sources",
+        "add",
+        "--notebook-id",
+        notebook_id,
+        "--stdin",
+        "--stdin-format",
+        "json",
+    ])
+    .write_stdin(serde_json::to_string(&urls).unwrap());
+    let output = cmd.assert().success().get_output().clone();
+
+    let requests = mock.server.received_requests().await.unwrap_or_default();
+    let batches: Vec<Vec<String>> = requests
+        .iter()
+        .map(|request| {
+            let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            body["userContents"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|content| content["webContent"]["url"].as_str().unwrap().to_string())
+                .collect()
+        })
+        .collect();
+    assert_eq!(
+        batches.iter().map(Vec::len).collect::<Vec<_>>(),
+        [100, 100, 100]
+    );
+    assert_eq!(batches.concat(), urls);
+
+    let summary: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(summary["sources"].as_array().unwrap().len(), 300);
+}
@@ -44,6 +44,13 @@ async fn sources_delete_multiple() {
 
     mock.stub_sources_batch_delete(&args.project_number, &args.location, notebook_id)
         .await;
+    mock.stub_notebook_get(
+        &args.project_number,
+        &args.location,
+        notebook_id,
+        &["src-1", "src-2", "src-3"],
+    )
+    .await;
 
     let source_name1 = format!(
         "projects/{}/locations/{}/notebooks/{}/sources/src-1",
@@ -71,3 +78,241 @@ async fn sources_delete_multiple() {
         .success()
         .stdout(predicate::str::contains("Deleted 2 source(s) successfully"));
 }
+
+#[tokio::test]
+#[serial]
+async fn sources_delete_every_source_requires_allow_match_all() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "test-notebook-id";
+
+    mock.stub_notebook_get(
+        &args.project_number,
+        &args.location,
+        notebook_id,
+        &["src-1", "src-2"],
+    )
+    .await;
+
+    let names: Vec<String> = ["src-1", "src-2"]
+        .iter()
+        .map(|id| {
+            format!(
+                "projects/{}/locations/{}/notebooks/{}/sources/{}",
+                args.project_number, args.location, notebook_id, id
+            )
+        })
+        .collect();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "sources",
+        "delete",
+        "--notebook-id",
+        notebook_id,
+        "--source-name",
+        &names[0],
+        "--source-name",
+        &names[1],
+    ]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "refusing to delete all 2 source(s) in scope",
+        ))
+        .stderr(predicate::str::contains("--allow-match-all"));
+
+    let requests = mock.server.received_requests().await.unwrap_or_default();
+    assert!(
+        requests
+            .iter()
+            .all(|request| request.method.as_str() == "GET"),
+        "nothing should be deleted"
+    );
+}
+
+fn source_name(args: &CommonArgs, notebook_id: &str, source_id: &str) -> String {
+    format!(
+        "projects/{}/locations/{}/notebooks/{}/sources/{}",
+        args.project_number, args.location, notebook_id, source_id
+    )
+}
+
+/// Names sent in the one batchDelete request the mock received.
+async fn deleted_names(mock: &MockApi) -> Vec<String> {
+    let requests = mock.server.received_requests().await.unwrap();
+    let deletes: Vec<_> = requests
+        .iter()
+        .filter(|request| request.url.path().ends_with("sources:batchDelete"))
+        .collect();
+    assert_eq!(deletes.len(), 1, "expected one batchDelete request");
+    let body: serde_json::Value = serde_json::from_slice(&deletes[0].body).unwrap();
+    serde_json::from_value(body["names"].clone()).unwrap()
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_delete_by_source_id() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "test-notebook-id";
+
+    mock.stub_sources_batch_delete(&args.project_number, &args.location, notebook_id)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "sources",
+        "delete",
+        "--notebook-id",
+        notebook_id,
+        "--source-id",
+        "src-123",
+    ]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Deleted 1 source(s) successfully"));
+    assert_eq!(
+        deleted_names(&mock).await,
+        vec![source_name(&args, notebook_id, "src-123")]
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_delete_by_source_title() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "test-notebook-id";
+
+    mock.stub_sources_batch_delete(&args.project_number, &args.location, notebook_id)
+        .await;
+    mock.stub_notebook_get(
+        &args.project_number,
+        &args.location,
+        notebook_id,
+        &["alpha", "beta"],
+    )
+    .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "sources",
+        "delete",
+        "--notebook-id",
+        notebook_id,
+        "--source-title",
+        "BETA",
+    ]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Deleted 1 source(s) successfully"));
+    assert_eq!(
+        deleted_names(&mock).await,
+        vec![source_name(&args, notebook_id, "beta")]
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_delete_mixed_inputs_are_deduplicated() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "test-notebook-id";
+
+    mock.stub_sources_batch_delete(&args.project_number, &args.location, notebook_id)
+        .await;
+    mock.stub_notebook_get(
+        &args.project_number,
+        &args.location,
+        notebook_id,
+        &["alpha", "beta", "gamma", "delta"],
+    )
+    .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "sources",
+        "delete",
+        "--notebook-id",
+        notebook_id,
+        "--source-name",
+        &source_name(&args, notebook_id, "alpha"),
+        "--source-id",
+        "beta",
+        "--source-id",
+        "alpha",
+        "--source-title",
+        "gamma",
+        "--source-title",
+        "Source beta",
+    ]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Deleted 3 source(s) successfully"));
+    assert_eq!(
+        deleted_names(&mock).await,
+        vec![
+            source_name(&args, notebook_id, "alpha"),
+            source_name(&args, notebook_id, "beta"),
+            source_name(&args, notebook_id, "gamma"),
+        ]
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_delete_aborts_when_a_title_is_ambiguous() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "test-notebook-id";
+
+    mock.stub_sources_batch_delete(&args.project_number, &args.location, notebook_id)
+        .await;
+    mock.stub_notebook_get(
+        &args.project_number,
+        &args.location,
+        notebook_id,
+        &["alpha", "beta"],
+    )
+    .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "sources",
+        "delete",
+        "--notebook-id",
+        notebook_id,
+        "--source-id",
+        "alpha",
+        "--source-title",
+        "Source",
+        "--source-title",
+        "missing",
+    ]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "could not resolve every source to delete; nothing was deleted",
+        ))
+        .stderr(predicate::str::contains(
+            "--source-title 'Source': matches 2 sources: Source alpha (alpha), Source beta (beta)",
+        ))
+        .stderr(predicate::str::contains(
+            "--source-title 'missing': no source title contains it",
+        ));
+    let requests = mock.server.received_requests().await.unwrap();
+    assert!(requests
+        .iter()
+        .all(|request| !request.url.path().ends_with("sources:batchDelete")));
+}
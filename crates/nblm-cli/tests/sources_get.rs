@@ -116,10 +116,12 @@ async fn sources_get_json_output() {
         source_id,
     ]);
 
-    cmd.assert()
-        .success()
-        .stdout(predicate::str::contains(r#""title": "Test Source""#))
-        .stdout(predicate::str::contains(format!(r#""id": "{}"#, source_id)));
+    // JSON mode forwards the server payload as-is.
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let json_output: serde_json::Value =
+        serde_json::from_slice(&output).expect("valid JSON output");
+    assert_eq!(json_output["title"], "Test Source");
+    assert_eq!(json_output["sourceId"]["id"], source_id);
 }
 
 #[tokio::test]
@@ -0,0 +1,198 @@
+mod _helpers;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use predicates::prelude::*;
+use serial_test::serial;
+use wiremock::matchers::{body_json, method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+const NOTEBOOK_ID: &str = "nb-1";
+
+fn notebook_name(args: &CommonArgs) -> String {
+    format!(
+        "projects/{}/locations/{}/notebooks/{}",
+        args.project_number, args.location, NOTEBOOK_ID
+    )
+}
+
+fn source_name(args: &CommonArgs, id: &str) -> String {
+    format!("{}/sources/{id}", notebook_name(args))
+}
+
+async fn stub_notebook(mock: &MockApi, args: &CommonArgs) {
+    Mock::given(method("GET"))
+        .and(path(format!("/v1alpha/{}", notebook_name(args))))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "name": notebook_name(args),
+            "notebookId": NOTEBOOK_ID,
+            "title": "Notebook",
+            "sources": [
+                { "name": source_name(args, "s1"), "title": "First" },
+                { "name": source_name(args, "s2") }
+            ]
+        })))
+        .mount(&mock.server)
+        .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_list_ids_pipes_into_delete_stdin() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_notebook(&mock, &args).await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "sources",
+        "list",
+        "--notebook-id",
+        NOTEBOOK_ID,
+        "--output",
+        "ids",
+    ]);
+    let ids = cmd.assert().success().get_output().stdout.clone();
+    assert_eq!(
+        String::from_utf8_lossy(&ids),
+        format!(
+            "{}\n{}\n",
+            source_name(&args, "s1"),
+            source_name(&args, "s2")
+        )
+    );
+
+    Mock::given(method("POST"))
+        .and(path(format!(
+            "/v1alpha/{}/sources:batchDelete",
+            notebook_name(&args)
+        )))
+        .and(body_json(serde_json::json!({
+            "names": [source_name(&args, "s1"), source_name(&args, "s2")]
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "sources",
+        "delete",
+        "--notebook-id",
+        NOTEBOOK_ID,
+        "--stdin",
+        "--allow-match-all",
+    ])
+    .write_stdin(ids);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Deleted 2 source(s) successfully"));
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_list_titles_marks_untitled_sources() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_notebook(&mock, &args).await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "sources",
+        "list",
+        "--notebook-id",
+        NOTEBOOK_ID,
+        "--output",
+        "titles",
+    ]);
+    cmd.assert().success().stdout("First\n(untitled)\n");
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_list_details_json() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_notebook(&mock, &args).await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["--json", "sources", "list", "--notebook-id", NOTEBOOK_ID]);
+
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let json: serde_json::Value = serde_json::from_slice(&output).expect("valid JSON");
+    assert_eq!(json["notebook_id"], NOTEBOOK_ID);
+    assert_eq!(json["sources"].as_array().map(Vec::len), Some(2));
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_list_rejects_line_output_with_json() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "--json",
+        "sources",
+        "list",
+        "--notebook-id",
+        NOTEBOOK_ID,
+        "--output",
+        "ids",
+    ]);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "--output ids cannot be combined with --json",
+    ));
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_delete_stdin_rejects_empty_input() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["sources", "delete", "--notebook-id", NOTEBOOK_ID, "--stdin"])
+        .write_stdin("\n  \n");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("no source names given"));
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_list_asks_for_minimal_fields_unless_json() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_notebook(&mock, &args).await;
+
+    for json in [false, true] {
+        let mut cmd = _helpers::cmd::nblm();
+        args.with_base_url(&mut cmd, &mock.base_url());
+        if json {
+            cmd.arg("--json");
+        }
+        cmd.args(["sources", "list", "--notebook-id", NOTEBOOK_ID]);
+        cmd.assert().success();
+    }
+
+    let requests = mock.server.received_requests().await.unwrap();
+    let fields = |index: usize| {
+        requests[index]
+            .url
+            .query_pairs()
+            .find(|(key, _)| key == "fields")
+            .map(|(_, value)| value.into_owned())
+    };
+    assert_eq!(
+        fields(0).as_deref(),
+        Some("name,notebookId,title,emoji,sources(name,sourceId,title,settings(status))")
+    );
+    assert_eq!(fields(1), None);
+}
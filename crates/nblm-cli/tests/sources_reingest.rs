@@ -0,0 +1,184 @@
+mod _helpers;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use predicates::prelude::*;
+use serde_json::json;
+use serial_test::serial;
+use wiremock::matchers::{body_json, method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+const NOTEBOOK_ID: &str = "nb-1";
+
+fn notebook_name(args: &CommonArgs) -> String {
+    format!(
+        "projects/{}/locations/{}/notebooks/{NOTEBOOK_ID}",
+        args.project_number, args.location
+    )
+}
+
+fn source_name(args: &CommonArgs, id: &str) -> String {
+    format!("{}/sources/{id}", notebook_name(args))
+}
+
+/// Expect `old` to be deleted and `content` to be added in its place as source `new`.
+async fn expect_replacement(
+    mock: &MockApi,
+    args: &CommonArgs,
+    old: &str,
+    content: serde_json::Value,
+    new: &str,
+) {
+    Mock::given(method("POST"))
+        .and(path(format!(
+            "/v1alpha/{}/sources:batchDelete",
+            notebook_name(args)
+        )))
+        .and(body_json(json!({ "names": [source_name(args, old)] })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(format!(
+            "/v1alpha/{}/sources:batchCreate",
+            notebook_name(args)
+        )))
+        .and(body_json(json!({ "userContents": [content] })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "sources": [{ "name": source_name(args, new), "title": "Refreshed" }]
+        })))
+        .expect(1)
+        .mount(&mock.server)
+        .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn reingest_replaces_a_web_source_with_the_same_url_and_name() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    Mock::given(method("GET"))
+        .and(path(format!("/v1alpha/{}", source_name(&args, "s1"))))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "name": source_name(&args, "s1"),
+            "title": "Release notes",
+            "metadata": { "webUrl": "https://example.com/releases" }
+        })))
+        .mount(&mock.server)
+        .await;
+    expect_replacement(
+        &mock,
+        &args,
+        "s1",
+        json!({ "webContent": { "url": "https://example.com/releases", "sourceName": "Release notes" } }),
+        "s9",
+    )
+    .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "sources",
+        "reingest",
+        "--notebook-id",
+        NOTEBOOK_ID,
+        "--source-id",
+        "s1",
+    ]);
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Reingested s1 -> s9 (https://example.com/releases)",
+    ));
+}
+
+#[tokio::test]
+#[serial]
+async fn reingest_refuses_a_source_without_a_url() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    Mock::given(method("GET"))
+        .and(path(format!("/v1alpha/{}", source_name(&args, "s2"))))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "name": source_name(&args, "s2"),
+            "title": "Pasted notes",
+            "metadata": { "wordCount": 12 }
+        })))
+        .mount(&mock.server)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+        .expect(0)
+        .mount(&mock.server)
+        .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "sources",
+        "reingest",
+        "--notebook-id",
+        NOTEBOOK_ID,
+        "--source-id",
+        "s2",
+    ]);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "source s2 cannot be reingested: no web or YouTube URL in the source's metadata",
+    ));
+}
+
+#[tokio::test]
+#[serial]
+async fn reingest_all_web_skips_sources_it_cannot_refresh() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    Mock::given(method("GET"))
+        .and(path(format!("/v1alpha/{}", notebook_name(&args))))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "name": notebook_name(&args),
+            "title": "Research",
+            "sources": [
+                {
+                    "name": source_name(&args, "web"),
+                    "title": "Blog post",
+                    "metadata": { "webUrl": "https://example.com/post" }
+                },
+                { "name": source_name(&args, "text"), "title": "Notes" },
+                {
+                    "name": source_name(&args, "video"),
+                    "title": "Talk",
+                    "metadata": { "youtubeMetadata": { "videoId": "abc" } }
+                }
+            ]
+        })))
+        .mount(&mock.server)
+        .await;
+    expect_replacement(
+        &mock,
+        &args,
+        "web",
+        json!({ "webContent": { "url": "https://example.com/post", "sourceName": "Blog post" } }),
+        "web2",
+    )
+    .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "sources",
+        "reingest",
+        "--notebook-id",
+        NOTEBOOK_ID,
+        "--all-web",
+    ]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Reingested web -> web2 (https://example.com/post)",
+        ))
+        .stdout(predicate::str::contains("Reingested 1 of 1 web source(s)"))
+        .stderr(predicate::str::contains(
+            "Skipped text (Notes): no web or YouTube URL in the source's metadata",
+        ))
+        .stderr(predicate::str::contains(
+            "Skipped video (Talk): YouTube video; reingest it with --source-id",
+        ));
+}
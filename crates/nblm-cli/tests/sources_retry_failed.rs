@@ -0,0 +1,239 @@
+mod _helpers;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use predicates::prelude::*;
+use serial_test::serial;
+use wiremock::matchers::{body_json, method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+fn batch_create_path(args: &CommonArgs, notebook_id: &str) -> String {
+    format!(
+        "/v1alpha/projects/{}/locations/{}/notebooks/{}/sources:batchCreate",
+        args.project_number, args.location, notebook_id
+    )
+}
+
+fn source(args: &CommonArgs, notebook_id: &str, id: &str, status: &str) -> serde_json::Value {
+    serde_json::json!({
+        "name": format!(
+            "projects/{}/locations/{}/notebooks/{}/sources/{}",
+            args.project_number, args.location, notebook_id, id
+        ),
+        "settings": { "status": status }
+    })
+}
+
+fn web_contents(urls: &[&str]) -> serde_json::Value {
+    serde_json::json!({
+        "userContents": urls
+            .iter()
+            .map(|url| serde_json::json!({ "webContent": { "url": url } }))
+            .collect::<Vec<_>>()
+    })
+}
+
+fn add_cmd(
+    args: &CommonArgs,
+    mock: &MockApi,
+    notebook_id: &str,
+    urls: &[&str],
+) -> assert_cmd::Command {
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["sources", "add", "--notebook-id", notebook_id]);
+    for url in urls {
+        cmd.args(["--web-url", url]);
+    }
+    cmd
+}
+
+async fn batch_create_bodies(mock: &MockApi) -> Vec<serde_json::Value> {
+    mock.server
+        .received_requests()
+        .await
+        .unwrap_or_default()
+        .iter()
+        .filter(|req| req.url.path().ends_with(":batchCreate"))
+        .map(|req| serde_json::from_slice(&req.body).expect("JSON body"))
+        .collect()
+}
+
+const URLS: [&str; 3] = [
+    "https://a.example.com",
+    "https://b.example.com",
+    "https://c.example.com",
+];
+
+#[tokio::test]
+#[serial]
+async fn retry_failed_resubmits_only_failed_sources() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "nb-retry";
+
+    Mock::given(method("POST"))
+        .and(path(batch_create_path(&args, notebook_id)))
+        .and(body_json(web_contents(&URLS)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "sources": [
+                source(&args, notebook_id, "a", "SOURCE_STATUS_COMPLETE"),
+                source(&args, notebook_id, "b", "SOURCE_STATUS_ERROR"),
+                source(&args, notebook_id, "c", "SOURCE_STATUS_ERROR"),
+            ],
+            "errorCount": 2
+        })))
+        .mount(&mock.server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(batch_create_path(&args, notebook_id)))
+        .and(body_json(web_contents(&URLS[1..])))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "sources": [
+                source(&args, notebook_id, "b2", "SOURCE_STATUS_COMPLETE"),
+                source(&args, notebook_id, "c2", "SOURCE_STATUS_PENDING"),
+            ],
+            "errorCount": 0
+        })))
+        .mount(&mock.server)
+        .await;
+
+    let mut cmd = add_cmd(&args, &mock, notebook_id, &URLS);
+    cmd.arg("--retry-failed");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Retrying 2 failed source(s)"))
+        .stderr(predicate::str::contains("https://b.example.com: added"))
+        .stderr(predicate::str::contains("https://c.example.com: added"));
+
+    let bodies = batch_create_bodies(&mock).await;
+    assert_eq!(bodies.len(), 2);
+    assert_eq!(bodies[1], web_contents(&URLS[1..]));
+}
+
+#[tokio::test]
+#[serial]
+async fn retry_failed_reports_sources_that_fail_again() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "nb-retry";
+
+    Mock::given(method("POST"))
+        .and(path(batch_create_path(&args, notebook_id)))
+        .and(body_json(web_contents(&URLS)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "sources": [
+                source(&args, notebook_id, "a", "SOURCE_STATUS_COMPLETE"),
+                source(&args, notebook_id, "b", "SOURCE_STATUS_ERROR"),
+                source(&args, notebook_id, "c", "SOURCE_STATUS_ERROR"),
+            ],
+            "errorCount": 2
+        })))
+        .mount(&mock.server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(batch_create_path(&args, notebook_id)))
+        .and(body_json(web_contents(&URLS[1..])))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "sources": [
+                source(&args, notebook_id, "b2", "SOURCE_STATUS_COMPLETE"),
+                source(&args, notebook_id, "c2", "SOURCE_STATUS_ERROR"),
+            ],
+            "errorCount": 1
+        })))
+        .mount(&mock.server)
+        .await;
+
+    let mut cmd = add_cmd(&args, &mock, notebook_id, &URLS);
+    cmd.args(["--json", "--retry-failed"]);
+    let assert = cmd
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("1 of 3 source(s) still failed"));
+    let json: serde_json::Value =
+        serde_json::from_slice(&assert.get_output().stdout).expect("valid JSON");
+    assert_eq!(json["sources"].as_array().map(Vec::len), Some(2));
+    assert_eq!(json["retried"][0]["status"], "added");
+    assert_eq!(json["retried"][1]["status"], "failed");
+    assert_eq!(json["retried"][1]["api_status"], "SOURCE_STATUS_ERROR");
+    assert_eq!(json["error_count"], 1);
+}
+
+#[tokio::test]
+#[serial]
+async fn without_retry_failed_sources_are_submitted_once() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "nb-retry";
+
+    Mock::given(method("POST"))
+        .and(path(batch_create_path(&args, notebook_id)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "sources": [
+                source(&args, notebook_id, "a", "SOURCE_STATUS_COMPLETE"),
+                source(&args, notebook_id, "b", "SOURCE_STATUS_ERROR"),
+                source(&args, notebook_id, "c", "SOURCE_STATUS_ERROR"),
+            ],
+            "errorCount": 2
+        })))
+        .mount(&mock.server)
+        .await;
+
+    add_cmd(&args, &mock, notebook_id, &URLS).assert().success();
+    assert_eq!(batch_create_bodies(&mock).await.len(), 1);
+}
+
+#[tokio::test]
+#[serial]
+async fn whole_batch_429_is_retried_after_retry_after() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "nb-retry";
+
+    Mock::given(method("POST"))
+        .and(path(batch_create_path(&args, notebook_id)))
+        .respond_with(
+            ResponseTemplate::new(429)
+                .insert_header("Retry-After", "0")
+                .set_body_json(serde_json::json!({
+                    "error": {"code": 429, "message": "Too Many Requests"}
+                })),
+        )
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&mock.server)
+        .await;
+    mock.stub_sources_batch_create(&args.project_number, &args.location, notebook_id)
+        .await;
+
+    let mut cmd = add_cmd(&args, &mock, notebook_id, &URLS[..1]);
+    cmd.arg("--retry-failed");
+    cmd.assert().success();
+
+    let bodies = batch_create_bodies(&mock).await;
+    assert_eq!(bodies.len(), 2);
+    assert_eq!(bodies[0], bodies[1]);
+}
+
+#[tokio::test]
+#[serial]
+async fn whole_batch_409_is_not_retried() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "nb-retry";
+
+    Mock::given(method("POST"))
+        .and(path(batch_create_path(&args, notebook_id)))
+        .respond_with(ResponseTemplate::new(409).set_body_json(serde_json::json!({
+            "error": {"code": 409, "message": "Source already exists"}
+        })))
+        .mount(&mock.server)
+        .await;
+
+    let mut cmd = add_cmd(&args, &mock, notebook_id, &URLS[..1]);
+    cmd.arg("--retry-failed");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Source already exists"));
+
+    assert_eq!(batch_create_bodies(&mock).await.len(), 1);
+}
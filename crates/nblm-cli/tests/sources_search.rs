@@ -0,0 +1,166 @@
+mod _helpers;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use predicates::prelude::*;
+use serial_test::serial;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+fn notebook_name(args: &CommonArgs, id: &str) -> String {
+    format!(
+        "projects/{}/locations/{}/notebooks/{}",
+        args.project_number, args.location, id
+    )
+}
+
+async fn stub_notebook(mock: &MockApi, args: &CommonArgs, id: &str, sources: serde_json::Value) {
+    Mock::given(method("GET"))
+        .and(path(format!("/v1alpha/{}", notebook_name(args, id))))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "name": notebook_name(args, id),
+            "notebookId": id,
+            "title": format!("Notebook {id}"),
+            "sources": sources,
+        })))
+        .mount(&mock.server)
+        .await;
+}
+
+/// Two notebooks with three sources; "rust" matches one title and one URL.
+async fn stub_two_notebooks(mock: &MockApi, args: &CommonArgs) {
+    Mock::given(method("GET"))
+        .and(path(format!(
+            "/v1alpha/projects/{}/locations/{}/notebooks:listRecentlyViewed",
+            args.project_number, args.location
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "notebooks": [
+                { "name": notebook_name(args, "nb-1"), "title": "One" },
+                { "name": notebook_name(args, "nb-2"), "title": "Two" }
+            ]
+        })))
+        .mount(&mock.server)
+        .await;
+
+    stub_notebook(
+        mock,
+        args,
+        "nb-1",
+        serde_json::json!([
+            {
+                "name": format!("{}/sources/s1", notebook_name(args, "nb-1")),
+                "title": "The Rust Programming Language"
+            },
+            {
+                "name": format!("{}/sources/s2", notebook_name(args, "nb-1")),
+                "title": "Cooking notes"
+            }
+        ]),
+    )
+    .await;
+    stub_notebook(
+        mock,
+        args,
+        "nb-2",
+        serde_json::json!([
+            {
+                "name": format!("{}/sources/s3", notebook_name(args, "nb-2")),
+                "title": "Language reference",
+                "metadata": { "webUrl": "https://doc.rust-lang.org/reference/" }
+            }
+        ]),
+    )
+    .await;
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_search_all_notebooks_json() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_two_notebooks(&mock, &args).await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "--json",
+        "sources",
+        "search",
+        "--query",
+        "RUST",
+        "--all-notebooks",
+    ]);
+
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let json: serde_json::Value = serde_json::from_slice(&output).expect("valid JSON");
+    let matches = json["matches"].as_array().expect("matches array");
+    assert_eq!(matches.len(), 2);
+    assert_eq!(matches[0]["notebook_id"], "nb-1");
+    assert_eq!(matches[0]["source_id"], "s1");
+    assert_eq!(matches[0]["field"], "title");
+    assert_eq!(matches[0]["match"], "Rust");
+    assert_eq!(matches[1]["notebook_id"], "nb-2");
+    assert_eq!(matches[1]["source_id"], "s3");
+    assert_eq!(matches[1]["field"], "url");
+    assert_eq!(matches[1]["match"], "rust");
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_search_single_notebook_text() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_two_notebooks(&mock, &args).await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "sources",
+        "search",
+        "--query",
+        r"cook\w+",
+        "--regex",
+        "--notebook-id",
+        "nb-1",
+    ]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("nb-1/s2  Cooking notes"))
+        .stdout(predicate::str::contains("title: "))
+        .stdout(predicate::str::contains("Rust").not())
+        .stderr(predicate::str::contains("searched titles and URLs only"));
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_search_fails_when_no_notebook_is_reachable() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "sources",
+        "search",
+        "--query",
+        "anything",
+        "--notebook-id",
+        "missing",
+    ]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("could not fetch notebook missing"));
+}
+
+#[test]
+fn sources_search_requires_a_scope() {
+    let args = CommonArgs::default();
+    let mut cmd = _helpers::cmd::nblm();
+    args.apply(&mut cmd);
+    cmd.args(["sources", "search", "--query", "x"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--notebook-id"));
+}
@@ -46,3 +46,270 @@ async fn sources_upload_file() {
         .success()
         .stdout(predicate::str::contains("Created source:"));
 }
+
+#[tokio::test]
+#[serial]
+async fn sources_upload_file_prints_resource_name_and_state() {
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "notebook-upload";
+    let resource_name = format!(
+        "projects/{}/locations/{}/notebooks/{}/sources/source-upload",
+        args.project_number, args.location, notebook_id
+    );
+
+    Mock::given(method("POST"))
+        .and(path(format!(
+            "/upload/v1alpha/projects/{}/locations/{}/notebooks/{}/sources:uploadFile",
+            args.project_number, args.location, notebook_id
+        )))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "sourceId": {"id": "source-upload"},
+            "name": resource_name,
+            "state": "SOURCE_STATE_PENDING",
+        })))
+        .mount(&mock.server)
+        .await;
+
+    let mut temp_file = NamedTempFile::new().expect("temp file");
+    writeln!(temp_file, "hello world").expect("write temp file");
+    let file_path = temp_file.into_temp_path();
+    let file_str = file_path.to_str().expect("path to str").to_string();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "sources",
+        "upload",
+        "--notebook-id",
+        notebook_id,
+        "--file",
+        &file_str,
+        "--content-type",
+        "text/plain",
+    ]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Created source: source-upload"))
+        .stdout(predicate::str::contains(format!(
+            "Resource name: {resource_name}"
+        )))
+        .stdout(predicate::str::contains("State: SOURCE_STATE_PENDING"));
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_upload_url_downloads_then_uploads_the_file() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "notebook-upload";
+    let pdf = b"%PDF-1.4\n%fake pdf for tests\n".to_vec();
+
+    Mock::given(method("GET"))
+        .and(path("/papers/latest"))
+        .respond_with(
+            ResponseTemplate::new(302).insert_header("location", "/papers/Attention%20Study.pdf"),
+        )
+        .mount(&mock.server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/papers/Attention%20Study.pdf"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(pdf.clone(), "application/pdf"))
+        .mount(&mock.server)
+        .await;
+    mock.stub_sources_upload_file(
+        &args.project_number,
+        &args.location,
+        notebook_id,
+        "source-upload",
+    )
+    .await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["sources", "upload", "--notebook-id", notebook_id, "--url"])
+        .arg(format!("{}/papers/latest", mock.server.uri()));
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Created source:"));
+
+    let requests = mock.server.received_requests().await.unwrap();
+    let upload = requests
+        .iter()
+        .find(|request| request.url.path().ends_with(":uploadFile"))
+        .expect("uploadFile request");
+    assert_eq!(upload.body.len(), pdf.len());
+    assert_eq!(upload.body, pdf);
+    assert_eq!(upload.headers["content-type"], "application/pdf");
+    assert_eq!(
+        upload.headers["x-goog-upload-file-name"],
+        "Attention Study.pdf"
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_upload_url_refuses_web_pages() {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    Mock::given(method("GET"))
+        .and(path("/article"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_raw("<html><body>hi</body></html>", "text/html; charset=utf-8"),
+        )
+        .mount(&mock.server)
+        .await;
+
+    let url = format!("{}/article", mock.server.uri());
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["sources", "upload", "--notebook-id", "nb1", "--url", &url]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("returned a web page (text/html)"))
+        .stderr(predicate::str::contains(format!(
+            "nblm sources add --web-url {url}"
+        )));
+
+    let requests = mock.server.received_requests().await.unwrap();
+    assert!(requests
+        .iter()
+        .all(|request| !request.url.path().ends_with(":uploadFile")));
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_upload_accepts_a_full_notebook_name_for_the_configured_project() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "notebook-upload";
+    let notebook_name = format!(
+        "projects/{}/locations/{}/notebooks/{notebook_id}",
+        args.project_number, args.location
+    );
+
+    // Only matches /upload/v1alpha/projects/.../notebooks/notebook-upload/sources:uploadFile,
+    // so the name must not be repeated in the URL.
+    mock.stub_sources_upload_file(
+        &args.project_number,
+        &args.location,
+        notebook_id,
+        "source-upload",
+    )
+    .await;
+
+    let mut temp_file = NamedTempFile::new().expect("temp file");
+    writeln!(temp_file, "hello world").expect("write temp file");
+    let file_path = temp_file.into_temp_path();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "sources",
+        "upload",
+        "--notebook-id",
+        &notebook_name,
+        "--file",
+    ])
+    .arg(&file_path)
+    .args(["--content-type", "text/plain"]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Created source:"));
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_upload_rejects_a_notebook_name_from_another_project() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    let mut temp_file = NamedTempFile::new().expect("temp file");
+    writeln!(temp_file, "hello world").expect("write temp file");
+    let file_path = temp_file.into_temp_path();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "sources",
+        "upload",
+        "--notebook-id",
+        "projects/999/locations/global/notebooks/nb1",
+        "--file",
+    ])
+    .arg(&file_path);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "notebook 'projects/999/locations/global/notebooks/nb1' is a resource name in projects/999/locations/global",
+        ))
+        .stderr(predicate::str::contains("pass the bare ID 'nb1'"));
+    assert!(mock.server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn sources_upload_markdown_uses_the_content_type_override() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let notebook_id = "notebook-upload";
+    mock.stub_sources_upload_file(
+        &args.project_number,
+        &args.location,
+        notebook_id,
+        "source-upload",
+    )
+    .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let notes = dir.path().join("notes.md");
+    std::fs::write(&notes, "# Notes\n").unwrap();
+    let transcript = dir.path().join("talk.srt");
+    std::fs::write(&transcript, "1\n00:00:00,000 --> 00:00:01,000\nHi\n").unwrap();
+    // The config file's [mime_overrides] wins over the built-in table.
+    let config_dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        config_dir.path().join("config.toml"),
+        "[mime_overrides]\nsrt = \"text/x-subtitles\"\n",
+    )
+    .unwrap();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.env("NBLM_CONFIG_DIR", config_dir.path())
+        .args(["sources", "upload", "--notebook-id", notebook_id, "--file"])
+        .arg(&notes)
+        .arg("--file")
+        .arg(&transcript);
+    cmd.assert().success();
+
+    let requests = mock.server.received_requests().await.unwrap();
+    let content_types: Vec<_> = requests
+        .iter()
+        .filter(|request| request.url.path().ends_with(":uploadFile"))
+        .map(|request| {
+            request.headers["content-type"]
+                .to_str()
+                .unwrap()
+                .to_string()
+        })
+        .collect();
+    assert_eq!(content_types, ["text/markdown", "text/x-subtitles"]);
+}
@@ -0,0 +1,97 @@
+mod _helpers;
+
+use std::time::Duration;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use predicates::prelude::*;
+use serial_test::serial;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+async fn stub_slow_recent(mock: &MockApi, args: &CommonArgs, delay: Duration) -> String {
+    let path_str = format!(
+        "/v1alpha/projects/{}/locations/{}/notebooks:listRecentlyViewed",
+        args.project_number, args.location
+    );
+    Mock::given(method("GET"))
+        .and(path(&path_str))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({"notebooks": []}))
+                .set_delay(delay),
+        )
+        .mount(&mock.server)
+        .await;
+    path_str
+}
+
+#[tokio::test]
+#[serial]
+async fn json_timings_show_the_api_phase_dominating_a_slow_response() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    let path_str = stub_slow_recent(&mock, &args, Duration::from_millis(400)).await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["--json", "--timings", "notebooks", "recent"]);
+
+    let output = cmd.assert().success().get_output().clone();
+    // stdout still holds only the command's document.
+    let stdout: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(stdout["notebooks"], serde_json::json!([]));
+
+    let report: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    let timings = &report["timings"];
+    let total = timings["total_ms"].as_f64().unwrap();
+    let api = timings["api"]["ms"].as_f64().unwrap();
+    assert_eq!(timings["api"]["count"], 1);
+    assert!(api >= 400.0, "{timings}");
+    for phase in ["token_fetch", "retry_wait", "file_io", "other"] {
+        let ms = timings[phase]["ms"].as_f64().unwrap();
+        assert!(
+            ms < api,
+            "{phase} ({ms}ms) should be below api ({api}ms): {timings}"
+        );
+    }
+    assert!(api / total > 0.5, "{timings}");
+    assert_eq!(timings["calls"][0]["method"], "GET");
+    assert_eq!(timings["calls"][0]["path"], path_str.as_str());
+}
+
+#[tokio::test]
+#[serial]
+async fn text_timings_print_a_table_on_stderr() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_slow_recent(&mock, &args, Duration::from_millis(50)).await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["--timings", "notebooks", "recent"]);
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Timings").not())
+        .stderr(predicate::str::contains("Timings (total "))
+        .stderr(predicate::str::is_match(r"(?m)^  api +1 +\d+\.\dms").unwrap())
+        .stderr(predicate::str::contains(
+            "GET /v1alpha/projects/123456/locations/global/notebooks:listRecentlyViewed",
+        ));
+}
+
+#[tokio::test]
+#[serial]
+async fn no_timings_without_the_flag() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_slow_recent(&mock, &args, Duration::ZERO).await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args(["notebooks", "recent"]);
+
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Timings").not());
+}
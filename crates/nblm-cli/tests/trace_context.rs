@@ -0,0 +1,129 @@
+mod _helpers;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use predicates::prelude::*;
+use serial_test::serial;
+use wiremock::matchers::method;
+use wiremock::{Mock, ResponseTemplate};
+
+const ENV_TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+const FLAG_TRACEPARENT: &str = "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-00";
+
+async fn stub_any_get(mock: &MockApi) {
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+        .mount(&mock.server)
+        .await;
+}
+
+async fn sent_header(mock: &MockApi, name: &str) -> Option<String> {
+    let requests = mock.server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1);
+    requests[0]
+        .headers
+        .get(name)
+        .map(|value| value.to_str().unwrap().to_string())
+}
+
+#[tokio::test]
+#[serial]
+async fn traceparent_env_is_propagated_to_api_requests() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_any_get(&mock).await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.env("TRACEPARENT", ENV_TRACEPARENT)
+        .env("TRACESTATE", "vendor=abc")
+        .args(["notebooks", "recent"]);
+
+    cmd.assert().success();
+    assert_eq!(
+        sent_header(&mock, "traceparent").await.as_deref(),
+        Some(ENV_TRACEPARENT)
+    );
+    assert_eq!(
+        sent_header(&mock, "tracestate").await.as_deref(),
+        Some("vendor=abc")
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn no_trace_headers_without_traceparent() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_any_get(&mock).await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.env_remove("TRACEPARENT")
+        .env("TRACESTATE", "vendor=abc")
+        .args(["notebooks", "recent"]);
+
+    cmd.assert().success();
+    assert_eq!(sent_header(&mock, "traceparent").await, None);
+    assert_eq!(sent_header(&mock, "tracestate").await, None);
+}
+
+#[tokio::test]
+#[serial]
+async fn malformed_traceparent_env_is_ignored_with_a_warning() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_any_get(&mock).await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.env("TRACEPARENT", "not-a-trace")
+        .args(["notebooks", "recent"]);
+
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::starts_with(
+            "warning: ignoring TRACEPARENT/TRACESTATE",
+        ))
+        .stderr(predicate::str::contains(
+            "invalid traceparent 'not-a-trace'",
+        ));
+    assert_eq!(sent_header(&mock, "traceparent").await, None);
+}
+
+#[tokio::test]
+#[serial]
+async fn api_traceparent_flag_overrides_the_env() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+    stub_any_get(&mock).await;
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.env("TRACEPARENT", ENV_TRACEPARENT)
+        .env("TRACESTATE", "vendor=abc")
+        .args([
+            "api",
+            "get",
+            "notebooks/nb1",
+            "--traceparent",
+            FLAG_TRACEPARENT,
+        ]);
+
+    cmd.assert().success();
+    assert_eq!(
+        sent_header(&mock, "traceparent").await.as_deref(),
+        Some(FLAG_TRACEPARENT)
+    );
+    // tracestate belongs to the env trace, so it is not carried over.
+    assert_eq!(sent_header(&mock, "tracestate").await, None);
+}
+
+#[test]
+fn api_rejects_a_malformed_traceparent_flag() {
+    let mut cmd = _helpers::cmd::nblm();
+    cmd.args(["api", "get", "notebooks/nb1", "--traceparent", "00-xyz"]);
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid traceparent '00-xyz'"));
+}
@@ -0,0 +1,79 @@
+mod _helpers;
+
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+/// `nblm validate` with no environment at all: no credentials, config dir or NBLM_* variables.
+fn validate(dir: &TempDir, args: &[&str]) -> assert_cmd::Command {
+    let mut cmd = _helpers::cmd::nblm();
+    cmd.env_clear()
+        .env("HOME", dir.path())
+        .env("NBLM_CONFIG_DIR", dir.path().join("config"))
+        .current_dir(dir.path())
+        .arg("validate")
+        .args(args);
+    cmd
+}
+
+#[test]
+fn valid_config_passes_without_any_environment() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("config.toml"),
+        "project_number = \"123456\"\nlocation = \"global\"\n",
+    )
+    .unwrap();
+
+    validate(&dir, &["--file", "config.toml"])
+        .assert()
+        .success()
+        .stdout("config.toml: valid\n");
+}
+
+#[test]
+fn invalid_config_lists_problems_and_exits_2() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("settings"),
+        "project_number = \"123456\"\nendpoint_location = \"asia\"\n",
+    )
+    .unwrap();
+
+    validate(&dir, &["--file", "settings"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "pass --kind config or --kind queue",
+        ));
+
+    validate(&dir, &["--file", "settings", "--kind", "config"])
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains(
+            "settings:2:21: endpoint_location: invalid endpoint configuration: unsupported endpoint location: asia",
+        ))
+        .stdout(predicate::str::contains("settings: 1 problem(s)"));
+}
+
+#[test]
+fn invalid_queue_reports_json_with_field_paths() {
+    let dir = TempDir::new().unwrap();
+    std::fs::write(
+        dir.path().join("queue.json"),
+        r#"{"version": 1, "entries": [{"id": "a", "queued_at": "now", "notebook_id": "nb1", "checksum": "x", "operation": {"kind": "add_sources", "contents": [{"webContent": {"url": 7}}]}}]}"#,
+    )
+    .unwrap();
+
+    let output = validate(&dir, &["--json", "--file", "queue.json"])
+        .assert()
+        .code(2)
+        .get_output()
+        .stdout
+        .clone();
+    let report: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(report["file"], "queue.json");
+    assert_eq!(report["kind"], "queue");
+    assert_eq!(report["valid"], false);
+    assert_eq!(report["errors"].as_array().unwrap().len(), 1);
+    assert_eq!(report["errors"][0]["line"], 1);
+}
@@ -0,0 +1,159 @@
+mod _helpers;
+
+use std::io::Write;
+
+use _helpers::{cmd::CommonArgs, mock::MockApi};
+use predicates::prelude::*;
+use serial_test::serial;
+use tempfile::NamedTempFile;
+
+#[test]
+fn invalid_max_file_size_reports_expected_forms() {
+    let args = CommonArgs::default();
+    let mut cmd = _helpers::cmd::nblm();
+    args.apply(&mut cmd);
+    cmd.args([
+        "sources",
+        "upload",
+        "--notebook-id",
+        "nb",
+        "--file",
+        "missing.txt",
+        "--max-file-size",
+        "10 megs",
+    ]);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "invalid size '10 megs': expected forms like 500KB, 10MiB",
+    ));
+}
+
+#[test]
+fn zero_max_file_size_is_refused() {
+    let args = CommonArgs::default();
+    let mut cmd = _helpers::cmd::nblm();
+    args.apply(&mut cmd);
+    cmd.args([
+        "sources",
+        "upload",
+        "--notebook-id",
+        "nb",
+        "--file",
+        "missing.txt",
+        "--max-file-size",
+        "0MB",
+    ]);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "invalid size '0MB': must be greater than zero",
+    ));
+}
+
+#[test]
+fn resource_name_from_another_project_as_notebook_id_is_refused() {
+    let args = CommonArgs::default();
+    let mut cmd = _helpers::cmd::nblm();
+    args.apply(&mut cmd);
+    cmd.args([
+        "sources",
+        "get",
+        "--notebook-id",
+        "projects/123/locations/global/notebooks/nb",
+        "--source-id",
+        "src",
+    ]);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "notebook 'projects/123/locations/global/notebooks/nb' is a resource name in projects/123/locations/global, \
+         but the client is configured for projects/123456/locations/global; pass the bare ID 'nb'",
+    ));
+}
+
+#[test]
+fn path_as_notebook_id_is_refused() {
+    let args = CommonArgs::default();
+    let mut cmd = _helpers::cmd::nblm();
+    args.apply(&mut cmd);
+    cmd.args([
+        "sources",
+        "get",
+        "--notebook-id",
+        "nb/sources/src",
+        "--source-id",
+        "src",
+    ]);
+    cmd.assert().code(2).stderr(predicate::str::contains(
+        "invalid notebook ID 'nb/sources/src'",
+    ));
+}
+
+#[test]
+fn empty_source_id_is_refused() {
+    let args = CommonArgs::default();
+    let mut cmd = _helpers::cmd::nblm();
+    args.apply(&mut cmd);
+    cmd.args(["sources", "get", "--notebook-id", "nb", "--source-id", ""]);
+    cmd.assert()
+        .code(2)
+        .stderr(predicate::str::contains("source ID must not be empty"));
+}
+
+#[test]
+fn invalid_timeout_reports_expected_forms() {
+    let args = CommonArgs::default();
+    let mut cmd = _helpers::cmd::nblm();
+    args.apply(&mut cmd);
+    cmd.args(["--timeout", "5 parsecs", "notebooks", "recent"]);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "invalid duration '5 parsecs': expected forms like 30s, 2m, 1h30m",
+    ));
+}
+
+#[test]
+fn zero_timeout_is_refused() {
+    let args = CommonArgs::default();
+    let mut cmd = _helpers::cmd::nblm();
+    args.apply(&mut cmd);
+    cmd.args(["--timeout", "0s", "notebooks", "recent"]);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "invalid duration '0s': must be greater than zero",
+    ));
+}
+
+#[test]
+fn invalid_retry_min_delay_reports_expected_forms() {
+    let args = CommonArgs::default();
+    let mut cmd = _helpers::cmd::nblm();
+    args.apply(&mut cmd);
+    cmd.args(["--retry-min-delay", "soon", "notebooks", "recent"]);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "invalid duration 'soon': expected forms like 30s, 2m, 1h30m",
+    ));
+}
+
+#[tokio::test]
+#[serial]
+async fn upload_refuses_files_over_max_file_size() {
+    let mock = MockApi::start().await;
+    let args = CommonArgs::default();
+
+    let mut file = NamedTempFile::new().expect("temp file");
+    file.write_all(&[b'x'; 2048]).expect("write file");
+    let path = file.path().to_str().expect("utf-8 path").to_string();
+
+    let mut cmd = _helpers::cmd::nblm();
+    args.with_base_url(&mut cmd, &mock.base_url());
+    cmd.args([
+        "sources",
+        "upload",
+        "--notebook-id",
+        "nb",
+        "--file",
+        &path,
+        "--max-file-size",
+        "1KiB",
+    ]);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "is 2048 bytes, larger than --max-file-size (1024 bytes)",
+    ));
+
+    let requests = mock.server.received_requests().await.unwrap_or_default();
+    assert!(requests.is_empty());
+}
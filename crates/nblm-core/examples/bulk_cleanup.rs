@@ -0,0 +1,114 @@
+//! Delete every recently viewed notebook whose title starts with a prefix, after confirming on
+//! stdin.
+//!
+//! ```sh
+//! export NBLM_PROJECT_NUMBER=123456789012
+//! export NBLM_ACCESS_TOKEN="$(gcloud auth print-access-token)"
+//! cargo run -p nblm-core --example bulk_cleanup -- "scratch-"
+//! ```
+//!
+//! `NBLM_LOCATION` and `NBLM_ENDPOINT_LOCATION` default to `global`. `NBLM_BASE_URL` points the
+//! client at another server, such as a local mock.
+
+use std::env;
+use std::error::Error;
+use std::io::{self, BufRead, Write};
+use std::sync::Arc;
+
+use futures::TryStreamExt;
+use nblm_core::models::enterprise::notebook::Notebook;
+use nblm_core::{client_options_from_env, EnvTokenProvider, EnvironmentConfig, NblmClient};
+
+/// Settings read from the environment.
+pub struct Config {
+    pub project_number: String,
+    pub location: String,
+    pub endpoint_location: String,
+    pub base_url: Option<String>,
+}
+
+impl Config {
+    pub fn from_env() -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            project_number: env::var("NBLM_PROJECT_NUMBER")
+                .map_err(|_| "set NBLM_PROJECT_NUMBER to your Google Cloud project number")?,
+            location: env::var("NBLM_LOCATION").unwrap_or_else(|_| "global".to_string()),
+            endpoint_location: env::var("NBLM_ENDPOINT_LOCATION")
+                .unwrap_or_else(|_| "global".to_string()),
+            base_url: env::var("NBLM_BASE_URL").ok(),
+        })
+    }
+
+    /// A client authenticated with the token in `NBLM_ACCESS_TOKEN`.
+    pub fn client(&self) -> nblm_core::Result<NblmClient> {
+        let environment = EnvironmentConfig::enterprise(
+            &self.project_number,
+            &self.location,
+            &self.endpoint_location,
+        )?;
+        let client = NblmClient::new(
+            Arc::new(EnvTokenProvider::new("NBLM_ACCESS_TOKEN")),
+            environment,
+        )?
+        .with_options(client_options_from_env()?);
+        match &self.base_url {
+            Some(base_url) => client.with_base_url(base_url),
+            None => Ok(client),
+        }
+    }
+}
+
+/// Notebooks on every page of the recently viewed list whose title starts with `prefix`.
+pub async fn matching(client: &NblmClient, prefix: &str) -> nblm_core::Result<Vec<Notebook>> {
+    client
+        .stream_recently_viewed(Some(50))
+        .try_filter(|notebook| futures::future::ready(notebook.title.starts_with(prefix)))
+        .try_collect()
+        .await
+}
+
+/// List the notebooks matching `prefix`, ask on `answers` whether to delete them and do so on
+/// `y` or `yes`. Returns how many were deleted.
+pub async fn run(
+    client: &NblmClient,
+    prefix: &str,
+    mut answers: impl BufRead,
+) -> Result<usize, Box<dyn Error>> {
+    let notebooks = matching(client, prefix).await?;
+    if notebooks.is_empty() {
+        println!("no notebooks titled {prefix:?}...");
+        return Ok(0);
+    }
+    for notebook in &notebooks {
+        println!(
+            "{}  {}",
+            notebook.notebook_id().unwrap_or("?"),
+            notebook.title
+        );
+    }
+
+    print!("Delete these {} notebook(s)? [y/N]: ", notebooks.len());
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    answers.read_line(&mut answer)?;
+    if !matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes") {
+        println!("nothing deleted");
+        return Ok(0);
+    }
+
+    let names: Vec<String> = notebooks.into_iter().filter_map(|nb| nb.name).collect();
+    let deleted = names.len();
+    client.delete_notebooks(names).await?;
+    println!("deleted {deleted} notebook(s)");
+    Ok(deleted)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let prefix = env::args()
+        .nth(1)
+        .ok_or("usage: bulk_cleanup <title prefix>")?;
+    let client = Config::from_env()?.client()?;
+    run(&client, &prefix, io::stdin().lock()).await?;
+    Ok(())
+}
@@ -0,0 +1,112 @@
+//! Create a notebook, add a web page and a text note to it, and print the new IDs.
+//!
+//! ```sh
+//! export NBLM_PROJECT_NUMBER=123456789012
+//! export NBLM_ACCESS_TOKEN="$(gcloud auth print-access-token)"
+//! cargo run -p nblm-core --example create_and_ingest -- "Reading list"
+//! ```
+//!
+//! `NBLM_LOCATION` and `NBLM_ENDPOINT_LOCATION` default to `global`. `NBLM_BASE_URL` points the
+//! client at another server, such as a local mock. The `NBLM_TIMEOUT` and `NBLM_*_RETRIES`
+//! settings apply as they do in the CLI.
+
+use std::env;
+use std::error::Error;
+use std::sync::Arc;
+
+use nblm_core::models::enterprise::source::UserContent;
+use nblm_core::{client_options_from_env, EnvTokenProvider, EnvironmentConfig, NblmClient};
+
+/// Settings read from the environment.
+pub struct Config {
+    pub project_number: String,
+    pub location: String,
+    pub endpoint_location: String,
+    pub base_url: Option<String>,
+}
+
+impl Config {
+    pub fn from_env() -> Result<Self, Box<dyn Error>> {
+        Ok(Self {
+            project_number: env::var("NBLM_PROJECT_NUMBER")
+                .map_err(|_| "set NBLM_PROJECT_NUMBER to your Google Cloud project number")?,
+            location: env::var("NBLM_LOCATION").unwrap_or_else(|_| "global".to_string()),
+            endpoint_location: env::var("NBLM_ENDPOINT_LOCATION")
+                .unwrap_or_else(|_| "global".to_string()),
+            base_url: env::var("NBLM_BASE_URL").ok(),
+        })
+    }
+
+    /// A client authenticated with the token in `NBLM_ACCESS_TOKEN`.
+    pub fn client(&self) -> nblm_core::Result<NblmClient> {
+        let environment = EnvironmentConfig::enterprise(
+            &self.project_number,
+            &self.location,
+            &self.endpoint_location,
+        )?;
+        let client = NblmClient::new(
+            Arc::new(EnvTokenProvider::new("NBLM_ACCESS_TOKEN")),
+            environment,
+        )?
+        .with_options(client_options_from_env()?);
+        match &self.base_url {
+            Some(base_url) => client.with_base_url(base_url),
+            None => Ok(client),
+        }
+    }
+}
+
+/// IDs of what [`run`] created.
+#[derive(Debug)]
+pub struct Created {
+    pub notebook_id: String,
+    pub source_ids: Vec<String>,
+}
+
+pub async fn run(client: &NblmClient, title: &str) -> nblm_core::Result<Created> {
+    let notebook = client.create_notebook(title).await?;
+    let notebook_id = notebook
+        .notebook_id()
+        .ok_or_else(|| nblm_core::Error::Validation("created notebook has no ID".to_string()))?
+        .to_string();
+
+    let response = client
+        .add_sources(
+            notebook_id.as_str(),
+            vec![
+                UserContent::web("https://www.rust-lang.org/".to_string(), None),
+                UserContent::text(
+                    "Notes to read alongside the Rust homepage.".to_string(),
+                    Some("Notes".to_string()),
+                ),
+            ],
+        )
+        .await?;
+    if let Some(count) = response.error_count.filter(|count| *count > 0) {
+        eprintln!("{count} source(s) could not be added");
+    }
+
+    Ok(Created {
+        notebook_id,
+        source_ids: response
+            .created_ids()
+            .into_iter()
+            .map(str::to_string)
+            .collect(),
+    })
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let title = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "nblm example".to_string());
+    let client = Config::from_env()?.client()?;
+
+    let created = run(&client, &title).await?;
+    println!("notebook: {}", created.notebook_id);
+    for source_id in &created.source_ids {
+        println!("source:   {source_id}");
+    }
+    Ok(())
+}
@@ -0,0 +1,69 @@
+//! Authenticate with a stored OAuth refresh token, as saved by the CLI's `--auth user-oauth`, and
+//! make one API call.
+//!
+//! ```sh
+//! export NBLM_PROJECT_NUMBER=123456789012
+//! export NBLM_OAUTH_CLIENT_ID=...apps.googleusercontent.com
+//! export NBLM_OAUTH_CLIENT_SECRET=...   # or NBLM_OAUTH_CLIENT_SECRET_FILE=/path/to/secret
+//! cargo run -p nblm-core --example oauth_refresh
+//! ```
+//!
+//! Tokens are read from the CLI's credentials file unless `NBLM_OAUTH_CREDENTIALS` names another
+//! one. `NBLM_OAUTH_TOKEN_ENDPOINT` and `NBLM_BASE_URL` point the token refresh and the API call
+//! at other servers, such as local mocks. `NBLM_LOCATION` and `NBLM_ENDPOINT_LOCATION` default to
+//! `global`.
+
+use std::env;
+use std::error::Error;
+use std::sync::Arc;
+
+use nblm_core::auth::oauth::OAuthClientConfig;
+use nblm_core::{
+    client_options_from_env, ApiProfile, EnvironmentConfig, FileRefreshTokenStore, NblmClient,
+    OAuthFlow, RefreshTokenProvider, TokenStoreKey,
+};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let project_number = env::var("NBLM_PROJECT_NUMBER")
+        .map_err(|_| "set NBLM_PROJECT_NUMBER to your Google Cloud project number")?;
+    let location = env::var("NBLM_LOCATION").unwrap_or_else(|_| "global".to_string());
+    let endpoint_location =
+        env::var("NBLM_ENDPOINT_LOCATION").unwrap_or_else(|_| "global".to_string());
+
+    let mut oauth = OAuthClientConfig::from_env()?.into_oauth_config();
+    if let Ok(endpoint) = env::var("NBLM_OAUTH_TOKEN_ENDPOINT") {
+        oauth.token_endpoint = endpoint;
+    }
+    let flow = OAuthFlow::new(oauth, Arc::new(reqwest::Client::new()))?;
+
+    let store = match env::var("NBLM_OAUTH_CREDENTIALS") {
+        Ok(path) => FileRefreshTokenStore::from_path(path)?,
+        Err(_) => FileRefreshTokenStore::new()?,
+    };
+    // The key the CLI stores tokens under, so its saved login is picked up.
+    let key = TokenStoreKey {
+        profile: ApiProfile::Enterprise,
+        project_number: Some(project_number.clone()),
+        endpoint_location: Some(endpoint_location.clone()),
+        user_hint: None,
+    };
+    let provider = RefreshTokenProvider::new(flow, Arc::new(store), key);
+
+    let environment = EnvironmentConfig::enterprise(project_number, location, endpoint_location)?;
+    let mut client =
+        NblmClient::new(Arc::new(provider), environment)?.with_options(client_options_from_env()?);
+    if let Ok(base_url) = env::var("NBLM_BASE_URL") {
+        client = client.with_base_url(base_url)?;
+    }
+
+    let recent = client.list_recently_viewed(Some(5)).await?;
+    for notebook in &recent.notebooks {
+        println!(
+            "{}  {}",
+            notebook.notebook_id().unwrap_or("?"),
+            notebook.title
+        );
+    }
+    Ok(())
+}
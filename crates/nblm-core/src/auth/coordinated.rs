@@ -0,0 +1,152 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+
+use super::{ProviderKind, TokenProvider};
+use crate::error::Result;
+
+/// How long a refreshed token is handed to later `refresh_token` callers instead of refreshing
+/// again.
+pub const DEFAULT_REFRESH_REUSE_WINDOW: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+struct RefreshedToken {
+    access_token: String,
+    at: Instant,
+}
+
+/// Wraps a provider so concurrent `refresh_token` calls share a single refresh.
+///
+/// When many requests get a 401 for the same expired token, the first caller refreshes while
+/// the rest wait for it and reuse its token. A refresh that finished within the reuse window
+/// (five seconds by default) answers further calls without refreshing again. A failed refresh
+/// is not reused: the next waiter tries again. `access_token` is passed through unchanged.
+pub struct CoordinatedProvider {
+    inner: Arc<dyn TokenProvider>,
+    window: Duration,
+    last_refresh: Mutex<Option<RefreshedToken>>,
+}
+
+impl CoordinatedProvider {
+    pub fn new(inner: Arc<dyn TokenProvider>) -> Self {
+        Self {
+            inner,
+            window: DEFAULT_REFRESH_REUSE_WINDOW,
+            last_refresh: Mutex::new(None),
+        }
+    }
+
+    /// Reuse a refreshed token for `window` instead of the default five seconds.
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+}
+
+impl std::fmt::Debug for CoordinatedProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CoordinatedProvider")
+            .field("kind", &self.inner.kind())
+            .field("window", &self.window)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl TokenProvider for CoordinatedProvider {
+    async fn access_token(&self) -> Result<String> {
+        self.inner.access_token().await
+    }
+
+    async fn refresh_token(&self) -> Result<String> {
+        let mut last = self.last_refresh.lock().await;
+        if let Some(refreshed) = last.as_ref() {
+            if refreshed.at.elapsed() < self.window {
+                return Ok(refreshed.access_token.clone());
+            }
+        }
+
+        let access_token = self.inner.refresh_token().await?;
+        *last = Some(RefreshedToken {
+            access_token: access_token.clone(),
+            at: Instant::now(),
+        });
+        Ok(access_token)
+    }
+
+    fn kind(&self) -> ProviderKind {
+        self.inner.kind()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::error::Error;
+
+    /// Always hands out `old`; the Nth refresh returns `new-N` after a short delay.
+    #[derive(Default)]
+    struct CountingProvider {
+        refreshes: AtomicUsize,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl TokenProvider for CountingProvider {
+        async fn access_token(&self) -> Result<String> {
+            Ok("old".to_string())
+        }
+
+        async fn refresh_token(&self) -> Result<String> {
+            let n = self.refreshes.fetch_add(1, Ordering::SeqCst) + 1;
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            if self.fail {
+                return Err(Error::TokenProvider("refresh failed".to_string()));
+            }
+            Ok(format!("new-{n}"))
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_refreshes_share_one_call() {
+        let inner = Arc::new(CountingProvider::default());
+        let provider = Arc::new(CoordinatedProvider::new(inner.clone()));
+
+        let tokens = futures::future::join_all((0..10).map(|_| {
+            let provider = Arc::clone(&provider);
+            async move { provider.refresh_token().await.unwrap() }
+        }))
+        .await;
+
+        assert_eq!(inner.refreshes.load(Ordering::SeqCst), 1);
+        assert!(tokens.iter().all(|token| token == "new-1"), "{tokens:?}");
+        assert_eq!(provider.access_token().await.unwrap(), "old");
+    }
+
+    #[tokio::test]
+    async fn refreshes_again_once_the_window_has_passed() {
+        let inner = Arc::new(CountingProvider::default());
+        let provider = CoordinatedProvider::new(inner.clone()).with_window(Duration::ZERO);
+
+        assert_eq!(provider.refresh_token().await.unwrap(), "new-1");
+        assert_eq!(provider.refresh_token().await.unwrap(), "new-2");
+        assert_eq!(inner.refreshes.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn failed_refreshes_are_not_reused() {
+        let inner = Arc::new(CountingProvider {
+            fail: true,
+            ..Default::default()
+        });
+        let provider = CoordinatedProvider::new(inner.clone());
+
+        assert!(provider.refresh_token().await.is_err());
+        assert!(provider.refresh_token().await.is_err());
+        assert_eq!(inner.refreshes.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,277 @@
+use std::env;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use time::OffsetDateTime;
+use tokio::sync::Mutex;
+
+use super::{ProviderKind, TokenProvider};
+use crate::error::{Error, Result};
+
+/// Environment variable used by Google client libraries to override the metadata host.
+pub const METADATA_HOST_ENV: &str = "GCE_METADATA_HOST";
+pub const DEFAULT_METADATA_HOST: &str = "metadata.google.internal";
+
+const TOKEN_PATH: &str = "/computeMetadata/v1/instance/service-accounts/default/token";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Deserialize)]
+struct MetadataTokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+#[derive(Debug, Clone)]
+struct CachedToken {
+    access_token: String,
+    expires_at: OffsetDateTime,
+}
+
+impl CachedToken {
+    fn is_fresh(&self, now: OffsetDateTime) -> bool {
+        now < self.expires_at - REFRESH_MARGIN
+    }
+}
+
+/// Token provider backed by the GCE/Cloud Run metadata server (Application Default Credentials).
+///
+/// Tokens are cached until shortly before expiry. The metadata host defaults to
+/// `metadata.google.internal` and can be overridden via `GCE_METADATA_HOST`.
+#[derive(Debug)]
+pub struct MetadataTokenProvider {
+    client: Client,
+    host: String,
+    cache: Mutex<Option<CachedToken>>,
+}
+
+impl MetadataTokenProvider {
+    pub fn new() -> Self {
+        let host = env::var(METADATA_HOST_ENV)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .unwrap_or_else(|| DEFAULT_METADATA_HOST.to_string());
+        Self::with_host(host)
+    }
+
+    /// Use an explicit metadata host (`host` or `host:port`), ignoring the environment.
+    pub fn with_host(host: impl Into<String>) -> Self {
+        Self {
+            client: Client::new(),
+            host: host.into(),
+            cache: Mutex::new(None),
+        }
+    }
+
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    fn token_url(&self) -> String {
+        format!("http://{}{}", self.host, TOKEN_PATH)
+    }
+
+    async fn fetch_token(&self) -> Result<CachedToken> {
+        let response = self
+            .client
+            .get(self.token_url())
+            .header("Metadata-Flavor", "Google")
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await
+            .map_err(|err| {
+                Error::TokenProvider(format!(
+                    "metadata server at {} is unreachable. `--auth metadata` only works on GCE, Cloud Run, or GKE (set {} to override the host).\nError: {}",
+                    self.host, METADATA_HOST_ENV, err
+                ))
+            })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response
+                .text()
+                .await
+                .unwrap_or_else(|_| String::from("<failed to read body>"));
+            return Err(Error::TokenProvider(format!(
+                "metadata server token request failed (status {}): {}",
+                status.as_u16(),
+                body.trim()
+            )));
+        }
+
+        let token: MetadataTokenResponse = response.json().await.map_err(|err| {
+            Error::TokenProvider(format!("invalid metadata server token response: {err}"))
+        })?;
+
+        Ok(CachedToken {
+            access_token: token.access_token,
+            expires_at: OffsetDateTime::now_utc() + Duration::from_secs(token.expires_in),
+        })
+    }
+
+    async fn ensure_token(&self, force_refresh: bool) -> Result<String> {
+        let mut cache = self.cache.lock().await;
+        if !force_refresh {
+            if let Some(entry) = cache.as_ref() {
+                if entry.is_fresh(OffsetDateTime::now_utc()) {
+                    return Ok(entry.access_token.clone());
+                }
+            }
+        }
+
+        let token = self.fetch_token().await?;
+        let access_token = token.access_token.clone();
+        *cache = Some(token);
+        Ok(access_token)
+    }
+}
+
+impl Default for MetadataTokenProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl TokenProvider for MetadataTokenProvider {
+    async fn access_token(&self) -> Result<String> {
+        self.ensure_token(false).await
+    }
+
+    async fn refresh_token(&self) -> Result<String> {
+        self.ensure_token(true).await
+    }
+
+    fn kind(&self) -> ProviderKind {
+        ProviderKind::Metadata
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn token_body(token: &str, expires_in: u64) -> serde_json::Value {
+        serde_json::json!({
+            "access_token": token,
+            "expires_in": expires_in,
+            "token_type": "Bearer"
+        })
+    }
+
+    fn provider_for(server: &MockServer) -> MetadataTokenProvider {
+        MetadataTokenProvider::with_host(server.address().to_string())
+    }
+
+    #[tokio::test]
+    async fn fetches_token_with_metadata_flavor_header() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(TOKEN_PATH))
+            .and(header("Metadata-Flavor", "Google"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(token_body("meta-token", 3600)))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = provider_for(&server);
+        assert_eq!(provider.access_token().await.unwrap(), "meta-token");
+        assert_eq!(provider.kind(), ProviderKind::Metadata);
+    }
+
+    #[tokio::test]
+    async fn caches_token_until_expiry() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(TOKEN_PATH))
+            .respond_with(ResponseTemplate::new(200).set_body_json(token_body("cached", 3600)))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = provider_for(&server);
+        assert_eq!(provider.access_token().await.unwrap(), "cached");
+        assert_eq!(provider.access_token().await.unwrap(), "cached");
+    }
+
+    #[tokio::test]
+    async fn refetches_token_when_near_expiry() {
+        let server = MockServer::start().await;
+        // expires_in below the refresh margin is treated as already stale.
+        Mock::given(method("GET"))
+            .and(path(TOKEN_PATH))
+            .respond_with(ResponseTemplate::new(200).set_body_json(token_body("short", 30)))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let provider = provider_for(&server);
+        provider.access_token().await.unwrap();
+        provider.access_token().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn refresh_token_bypasses_cache() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(TOKEN_PATH))
+            .respond_with(ResponseTemplate::new(200).set_body_json(token_body("fresh", 3600)))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let provider = provider_for(&server);
+        provider.access_token().await.unwrap();
+        provider.refresh_token().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn unreachable_host_reports_metadata_hint() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let provider = MetadataTokenProvider::with_host(addr.to_string());
+        let err = provider.access_token().await.unwrap_err();
+        match err {
+            Error::TokenProvider(message) => {
+                assert!(message.contains("is unreachable"));
+                assert!(message.contains(METADATA_HOST_ENV));
+            }
+            other => panic!("expected TokenProvider error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn non_success_status_is_reported() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(TOKEN_PATH))
+            .respond_with(ResponseTemplate::new(404).set_body_string("not found"))
+            .mount(&server)
+            .await;
+
+        let provider = provider_for(&server);
+        let err = provider.access_token().await.unwrap_err();
+        assert!(err.to_string().contains("status 404"));
+    }
+
+    #[test]
+    #[serial]
+    fn new_reads_host_from_env() {
+        let original = env::var(METADATA_HOST_ENV).ok();
+        env::set_var(METADATA_HOST_ENV, "127.0.0.1:8080");
+        assert_eq!(MetadataTokenProvider::new().host(), "127.0.0.1:8080");
+        env::remove_var(METADATA_HOST_ENV);
+        assert_eq!(MetadataTokenProvider::new().host(), DEFAULT_METADATA_HOST);
+        if let Some(value) = original {
+            env::set_var(METADATA_HOST_ENV, value);
+        }
+    }
+}
@@ -1,13 +1,23 @@
 use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
 
 use async_trait::async_trait;
-use reqwest::Client;
-use serde::Deserialize;
 use tokio::process::Command;
 
 use crate::error::{Error, Result};
 
+mod coordinated;
+mod metadata;
 pub mod oauth;
+mod scope;
+
+pub use coordinated::{CoordinatedProvider, DEFAULT_REFRESH_REUSE_WINDOW};
+pub use metadata::{MetadataTokenProvider, DEFAULT_METADATA_HOST, METADATA_HOST_ENV};
+pub use scope::{
+    ensure_drive_scope, ScopeChecker, TokenProject, DEFAULT_TOKENINFO_ENDPOINT,
+    TOKENINFO_ENDPOINT_ENV,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ProviderKind {
@@ -15,6 +25,7 @@ pub enum ProviderKind {
     EnvAccessToken,
     StaticToken,
     UserOauth,
+    Metadata,
 }
 
 impl ProviderKind {
@@ -24,6 +35,7 @@ impl ProviderKind {
             ProviderKind::EnvAccessToken => "env-access-token",
             ProviderKind::StaticToken => "static-token",
             ProviderKind::UserOauth => "user-oauth",
+            ProviderKind::Metadata => "metadata-server",
         }
     }
 
@@ -32,6 +44,28 @@ impl ProviderKind {
     }
 }
 
+/// Result of a provider's cheap readiness check, made without fetching a token.
+///
+/// `Ready` only means the prerequisite is in place: a gcloud binary that exists may still have no
+/// logged-in account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProbeStatus {
+    Ready(String),
+    Missing(String),
+}
+
+impl ProbeStatus {
+    pub fn is_ready(&self) -> bool {
+        matches!(self, ProbeStatus::Ready(_))
+    }
+
+    pub fn detail(&self) -> &str {
+        match self {
+            ProbeStatus::Ready(detail) | ProbeStatus::Missing(detail) => detail,
+        }
+    }
+}
+
 #[async_trait]
 pub trait TokenProvider: Send + Sync {
     async fn access_token(&self) -> Result<String>;
@@ -44,100 +78,126 @@ pub trait TokenProvider: Send + Sync {
     }
 }
 
-const TOKENINFO_ENDPOINT: &str = "https://www.googleapis.com/oauth2/v3/tokeninfo";
-const DRIVE_SCOPE: &str = "https://www.googleapis.com/auth/drive";
-const DRIVE_FILE_SCOPE: &str = "https://www.googleapis.com/auth/drive.file";
-
-#[derive(Debug, Deserialize)]
-struct TokenInfoResponse {
-    scope: Option<String>,
+#[derive(Debug, Default, Clone)]
+pub struct GcloudTokenProvider {
+    binary: String,
+    non_interactive: bool,
 }
 
-pub async fn ensure_drive_scope(provider: &dyn TokenProvider) -> Result<()> {
-    let client = Client::new();
-    let endpoint =
-        std::env::var("NBLM_TOKENINFO_ENDPOINT").unwrap_or_else(|_| TOKENINFO_ENDPOINT.to_string());
-    ensure_drive_scope_internal(provider, &client, &endpoint).await
-}
+impl GcloudTokenProvider {
+    pub fn new(binary: impl Into<String>) -> Self {
+        Self {
+            binary: binary.into(),
+            non_interactive: false,
+        }
+    }
 
-async fn ensure_drive_scope_internal(
-    provider: &dyn TokenProvider,
-    client: &Client,
-    endpoint: &str,
-) -> Result<()> {
-    let access_token = provider.access_token().await?;
-
-    let response = client
-        .get(endpoint)
-        .query(&[("access_token", access_token.as_str())])
-        .send()
-        .await
-        .map_err(|err| {
-            Error::TokenProvider(format!("failed to validate Google Drive token: {err}"))
-        })?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response
-            .text()
-            .await
-            .unwrap_or_else(|_| String::from("<failed to read body>"));
-        return Err(Error::TokenProvider(format!(
-            "failed to validate Google Drive token (status {}): {}",
-            status.as_u16(),
-            body.trim()
-        )));
-    }
-
-    let info: TokenInfoResponse = response
-        .json()
-        .await
-        .map_err(|err| Error::TokenProvider(format!("invalid tokeninfo response: {err}")))?;
-
-    let scopes = info.scope.unwrap_or_default();
-    if scope_grants_drive_access(&scopes) {
-        Ok(())
-    } else {
-        Err(Error::TokenProvider(
-            "Google Drive access token is missing the required drive.file scope. Run `gcloud auth login --enable-gdrive-access` and retry.".to_string(),
-        ))
+    /// Never let gcloud wait for input: it runs with prompts disabled and without stdin, and a
+    /// reauthentication it would have asked for fails with [`Error::InteractionRequired`].
+    pub fn with_non_interactive(mut self, non_interactive: bool) -> Self {
+        self.non_interactive = non_interactive;
+        self
+    }
+
+    /// Whether the gcloud binary can be found, without running it.
+    pub fn probe(&self) -> ProbeStatus {
+        match find_binary(&self.binary) {
+            Some(path) => ProbeStatus::Ready(format!("binary found at {}", path.display())),
+            None => ProbeStatus::Missing(format!("binary '{}' not found", self.binary)),
+        }
     }
 }
 
-fn scope_grants_drive_access(scopes: &str) -> bool {
-    scopes
-        .split_whitespace()
-        .any(|scope| scope == DRIVE_FILE_SCOPE || scope == DRIVE_SCOPE)
+/// Resolve `binary` the way a subprocess spawn would: as a path if it has a directory part,
+/// otherwise by searching `PATH`.
+fn find_binary(binary: &str) -> Option<PathBuf> {
+    let path = Path::new(binary);
+    if path.components().count() > 1 {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+    let suffixes: &[&str] = if cfg!(windows) {
+        &["", ".exe", ".cmd", ".bat"]
+    } else {
+        &[""]
+    };
+    env::split_paths(&env::var_os("PATH")?).find_map(|dir| {
+        suffixes
+            .iter()
+            .map(|suffix| dir.join(format!("{binary}{suffix}")))
+            .find(|candidate| candidate.is_file())
+    })
 }
 
-#[cfg(test)]
-pub(crate) async fn ensure_drive_scope_with_endpoint(
-    provider: &dyn TokenProvider,
-    client: &Client,
-    endpoint: &str,
-) -> Result<()> {
-    ensure_drive_scope_internal(provider, client, endpoint).await
+/// A `gcloud auth print-access-token` failure recognized by a phrase of its stderr.
+struct GcloudFailure {
+    /// Matched case-insensitively; any one of them identifies the failure.
+    phrases: &'static [&'static str],
+    /// What went wrong and the command that fixes it.
+    guidance: &'static str,
+    /// Whether gcloud asks the user to fix it when it can (from a terminal).
+    prompts: bool,
 }
 
-#[derive(Debug, Default, Clone)]
-pub struct GcloudTokenProvider {
-    binary: String,
+/// Known failures, checked in order. A policy reauthentication is reported by gcloud as a failed
+/// refresh, so it comes before the expired credentials.
+const GCLOUD_FAILURES: &[GcloudFailure] = &[
+    GcloudFailure {
+        phrases: &["reauthentication required", "reauthentication failed"],
+        guidance: "gcloud needs you to reauthenticate, as your organization's session policy \
+                   requires, and cannot ask for it when nblm runs it. Run 'gcloud auth login' in \
+                   a terminal, then retry.",
+        prompts: true,
+    },
+    GcloudFailure {
+        phrases: &["there was a problem refreshing your current auth tokens"],
+        guidance: "gcloud's stored credentials have expired or been revoked. Run 'gcloud auth \
+                   login' to obtain new ones, and 'gcloud auth application-default login' too if \
+                   you also use application default credentials.",
+        prompts: false,
+    },
+    GcloudFailure {
+        phrases: &[
+            "do not have any active credentials",
+            "do not currently have an active account selected",
+        ],
+        guidance: "gcloud has no logged-in account. Run 'gcloud auth login' to sign in, or \
+                   'gcloud config set account ACCOUNT' to select an account you logged in with \
+                   before.",
+        prompts: false,
+    },
+];
+
+/// The known failure `stderr` reports, if any.
+fn gcloud_failure(stderr: &str) -> Option<&'static GcloudFailure> {
+    let lowered = stderr.to_lowercase();
+    GCLOUD_FAILURES.iter().find(|failure| {
+        failure
+            .phrases
+            .iter()
+            .any(|phrase| lowered.contains(phrase))
+    })
 }
 
-impl GcloudTokenProvider {
-    pub fn new(binary: impl Into<String>) -> Self {
-        Self {
-            binary: binary.into(),
-        }
-    }
+/// Guidance for a failed `gcloud auth print-access-token`, followed by its stderr.
+fn gcloud_failure_message(stderr: &str) -> String {
+    let guidance = gcloud_failure(stderr).map_or(
+        "Failed to get access token from gcloud. Please run 'gcloud auth login' to authenticate.",
+        |failure| failure.guidance,
+    );
+    format!("{guidance}\nError: {stderr}")
 }
 
 #[async_trait]
 impl TokenProvider for GcloudTokenProvider {
     async fn access_token(&self) -> Result<String> {
-        let output = Command::new(&self.binary)
-            .arg("auth")
-            .arg("print-access-token")
+        let mut command = Command::new(&self.binary);
+        command.arg("auth").arg("print-access-token");
+        if self.non_interactive {
+            command
+                .env("CLOUDSDK_CORE_DISABLE_PROMPTS", "1")
+                .stdin(Stdio::null());
+        }
+        let output = command
             .output()
             .await
             .map_err(|err| {
@@ -149,10 +209,18 @@ impl TokenProvider for GcloudTokenProvider {
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(Error::TokenProvider(format!(
-                "Failed to get access token from gcloud. Please run 'gcloud auth login' to authenticate.\nError: {}",
-                stderr.trim()
-            )));
+            let stderr = stderr.trim();
+            if self.non_interactive && gcloud_failure(stderr).is_some_and(|failure| failure.prompts)
+            {
+                return Err(Error::InteractionRequired {
+                    interaction: "let gcloud ask you to reauthenticate".to_string(),
+                    remedy: format!(
+                        "run 'gcloud auth login' in a terminal first, or pass a token with \
+                         --auth env and NBLM_ACCESS_TOKEN.\nError: {stderr}"
+                    ),
+                });
+            }
+            return Err(Error::TokenProvider(gcloud_failure_message(stderr)));
         }
 
         let token = String::from_utf8(output.stdout)
@@ -175,6 +243,16 @@ impl EnvTokenProvider {
     pub fn new(key: impl Into<String>) -> Self {
         Self { key: key.into() }
     }
+
+    /// Whether the variable is set to a non-blank value.
+    pub fn probe(&self) -> ProbeStatus {
+        match env::var(&self.key) {
+            Ok(value) if !value.trim().is_empty() => {
+                ProbeStatus::Ready(format!("{} set", self.key))
+            }
+            _ => ProbeStatus::Missing(format!("{} unset", self.key)),
+        }
+    }
 }
 
 #[async_trait]
@@ -216,8 +294,6 @@ impl TokenProvider for StaticTokenProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use wiremock::matchers::{method, path, query_param};
-    use wiremock::{Mock, MockServer, ResponseTemplate};
 
     #[tokio::test]
     async fn static_token_provider_returns_token() {
@@ -247,12 +323,195 @@ mod tests {
             .contains("environment variable NONEXISTENT_TOKEN missing"));
     }
 
+    #[test]
+    fn env_token_provider_probe_ignores_blank_values() {
+        let provider = EnvTokenProvider::new("TEST_NBLM_PROBE_TOKEN");
+        std::env::set_var("TEST_NBLM_PROBE_TOKEN", "  ");
+        assert_eq!(
+            provider.probe(),
+            ProbeStatus::Missing("TEST_NBLM_PROBE_TOKEN unset".to_string())
+        );
+        std::env::set_var("TEST_NBLM_PROBE_TOKEN", "token");
+        assert_eq!(
+            provider.probe(),
+            ProbeStatus::Ready("TEST_NBLM_PROBE_TOKEN set".to_string())
+        );
+        std::env::remove_var("TEST_NBLM_PROBE_TOKEN");
+    }
+
+    #[test]
+    fn gcloud_token_provider_probe_checks_explicit_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join("gcloud");
+        let provider = GcloudTokenProvider::new(binary.to_string_lossy());
+        assert!(!provider.probe().is_ready());
+        assert!(provider.probe().detail().ends_with("gcloud' not found"));
+
+        std::fs::write(&binary, "").unwrap();
+        assert!(provider.probe().is_ready());
+        assert!(provider.probe().detail().starts_with("binary found at "));
+    }
+
+    #[test]
+    fn gcloud_token_provider_probe_reports_unknown_commands() {
+        let provider = GcloudTokenProvider::new("nblm-test-no-such-gcloud");
+        assert_eq!(
+            provider.probe(),
+            ProbeStatus::Missing("binary 'nblm-test-no-such-gcloud' not found".to_string())
+        );
+    }
+
+    /// stderr of `gcloud auth print-access-token` as gcloud prints it in each case.
+    const REAUTH_STDERR: &str = "ERROR: (gcloud.auth.print-access-token) There was a problem \
+        refreshing your current auth tokens: Reauthentication failed. cannot prompt during \
+        non-interactive execution.\nPlease run:\n\n  $ gcloud auth login\n\nto obtain new \
+        credentials.";
+    const EXPIRED_STDERR: &str = "ERROR: (gcloud.auth.print-access-token) There was a problem \
+        refreshing your current auth tokens: ('invalid_grant: Token has been expired or \
+        revoked.', {'error': 'invalid_grant', 'error_description': 'Token has been expired or \
+        revoked.'})\nPlease run:\n\n  $ gcloud auth login\n\nto obtain new credentials.";
+    const NOT_LOGGED_IN_STDERR: &str = "ERROR: (gcloud.auth.print-access-token) You do not \
+        currently have an active account selected.\nPlease run:\n\n  $ gcloud auth login\n\nto \
+        obtain new credentials.";
+    const NO_CREDENTIALS_STDERR: &str =
+        "ERROR: (gcloud.auth.print-access-token) You do not have any active credentials.";
+
+    #[test]
+    fn gcloud_failures_get_their_own_guidance() {
+        let cases = [
+            (REAUTH_STDERR, "gcloud needs you to reauthenticate"),
+            (
+                "ERROR: Reauthentication required.",
+                "gcloud needs you to reauthenticate",
+            ),
+            (EXPIRED_STDERR, "gcloud's stored credentials have expired"),
+            (NOT_LOGGED_IN_STDERR, "gcloud has no logged-in account"),
+            (NO_CREDENTIALS_STDERR, "gcloud has no logged-in account"),
+            (
+                "ERROR: (gcloud) something else broke",
+                "Failed to get access token from gcloud. Please run 'gcloud auth login'",
+            ),
+        ];
+        for (stderr, guidance) in cases {
+            let message = gcloud_failure_message(stderr);
+            assert!(message.starts_with(guidance), "{stderr} -> {message}");
+            assert!(
+                message.ends_with(&format!("\nError: {stderr}")),
+                "{message}"
+            );
+        }
+    }
+
+    #[test]
+    fn gcloud_guidance_names_the_matching_command() {
+        assert!(gcloud_failure_message(EXPIRED_STDERR)
+            .contains("'gcloud auth application-default login'"));
+        assert!(!gcloud_failure_message(REAUTH_STDERR).contains("application-default"));
+        assert!(gcloud_failure_message(NOT_LOGGED_IN_STDERR)
+            .contains("'gcloud config set account ACCOUNT'"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn gcloud_token_provider_reports_what_gcloud_printed() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join("gcloud");
+        std::fs::write(dir.path().join("stderr.txt"), EXPIRED_STDERR).unwrap();
+        std::fs::write(
+            &binary,
+            "#!/bin/sh\ncat \"$(dirname \"$0\")/stderr.txt\" >&2\nexit 1\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let err = GcloudTokenProvider::new(binary.to_string_lossy())
+            .access_token()
+            .await
+            .unwrap_err();
+        let Error::TokenProvider(message) = err else {
+            panic!("unexpected error: {err:?}");
+        };
+        assert_eq!(message, gcloud_failure_message(EXPIRED_STDERR));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn non_interactive_gcloud_reports_the_reauthentication_it_would_ask_for() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join("gcloud");
+        std::fs::write(dir.path().join("stderr.txt"), REAUTH_STDERR).unwrap();
+        // Fails like a reauthentication would, and records how it was run. Stdin is only read
+        // with prompts disabled: otherwise it is the test runner's, which may never close.
+        std::fs::write(
+            &binary,
+            "#!/bin/sh\nd=\"$(dirname \"$0\")\"\necho \"$CLOUDSDK_CORE_DISABLE_PROMPTS\" > \"$d/prompts\"\n\
+             [ -n \"$CLOUDSDK_CORE_DISABLE_PROMPTS\" ] && cat > \"$d/stdin\"\n\
+             cat \"$d/stderr.txt\" >&2\nexit 1\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let provider = GcloudTokenProvider::new(binary.to_string_lossy());
+        let err = provider.access_token().await.unwrap_err();
+        assert!(matches!(err, Error::TokenProvider(_)), "{err:?}");
+
+        let err = provider
+            .with_non_interactive(true)
+            .access_token()
+            .await
+            .unwrap_err();
+        let Error::InteractionRequired {
+            interaction,
+            remedy,
+        } = &err
+        else {
+            panic!("unexpected error: {err:?}");
+        };
+        assert_eq!(interaction, "let gcloud ask you to reauthenticate");
+        assert!(remedy.starts_with("run 'gcloud auth login' in a terminal first"));
+        assert!(remedy.ends_with(&format!("\nError: {REAUTH_STDERR}")));
+        let prompts = std::fs::read_to_string(dir.path().join("prompts")).unwrap();
+        assert_eq!(prompts.trim(), "1");
+        assert_eq!(std::fs::read(dir.path().join("stdin")).unwrap(), b"");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn non_interactive_gcloud_keeps_other_failures() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join("gcloud");
+        std::fs::write(dir.path().join("stderr.txt"), NOT_LOGGED_IN_STDERR).unwrap();
+        std::fs::write(
+            &binary,
+            "#!/bin/sh\ncat \"$(dirname \"$0\")/stderr.txt\" >&2\nexit 1\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let err = GcloudTokenProvider::new(binary.to_string_lossy())
+            .with_non_interactive(true)
+            .access_token()
+            .await
+            .unwrap_err();
+        let Error::TokenProvider(message) = err else {
+            panic!("unexpected error: {err:?}");
+        };
+        assert_eq!(message, gcloud_failure_message(NOT_LOGGED_IN_STDERR));
+    }
+
     #[test]
     fn provider_kind_as_str_returns_correct_labels() {
         assert_eq!(ProviderKind::GcloudOauth.as_str(), "gcloud-oauth");
         assert_eq!(ProviderKind::EnvAccessToken.as_str(), "env-access-token");
         assert_eq!(ProviderKind::StaticToken.as_str(), "static-token");
         assert_eq!(ProviderKind::UserOauth.as_str(), "user-oauth");
+        assert_eq!(ProviderKind::Metadata.as_str(), "metadata-server");
     }
 
     #[test]
@@ -261,6 +520,7 @@ mod tests {
         assert!(!ProviderKind::EnvAccessToken.is_experimental());
         assert!(!ProviderKind::StaticToken.is_experimental());
         assert!(ProviderKind::UserOauth.is_experimental());
+        assert!(!ProviderKind::Metadata.is_experimental());
     }
 
     #[test]
@@ -280,93 +540,4 @@ mod tests {
         let provider = StaticTokenProvider::new("token");
         assert_eq!(provider.kind(), ProviderKind::StaticToken);
     }
-
-    fn expect_scope_result(scopes: &str, expected: bool) {
-        assert_eq!(scope_grants_drive_access(scopes), expected);
-    }
-
-    #[test]
-    fn scope_grants_drive_access_detects_required_scopes() {
-        expect_scope_result(DRIVE_FILE_SCOPE, true);
-        expect_scope_result(DRIVE_SCOPE, true);
-        expect_scope_result(
-            "https://www.googleapis.com/auth/spreadsheets.readonly",
-            false,
-        );
-        expect_scope_result(
-            &format!("{DRIVE_FILE_SCOPE} https://www.googleapis.com/auth/calendar"),
-            true,
-        );
-    }
-
-    #[tokio::test]
-    async fn ensure_drive_scope_accepts_valid_scope() {
-        let server = MockServer::start().await;
-        Mock::given(method("GET"))
-            .and(path("/oauth2/v3/tokeninfo"))
-            .and(query_param("access_token", "valid-token"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-                "scope": DRIVE_FILE_SCOPE
-            })))
-            .mount(&server)
-            .await;
-
-        let provider = StaticTokenProvider::new("valid-token");
-        let client = reqwest::Client::new();
-        let endpoint = format!("{}/oauth2/v3/tokeninfo", server.uri());
-        let result = ensure_drive_scope_with_endpoint(&provider, &client, &endpoint).await;
-        assert!(result.is_ok());
-    }
-
-    #[tokio::test]
-    async fn ensure_drive_scope_rejects_missing_scope() {
-        let server = MockServer::start().await;
-        Mock::given(method("GET"))
-            .and(path("/oauth2/v3/tokeninfo"))
-            .and(query_param("access_token", "no-scope"))
-            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
-                "scope": "https://www.googleapis.com/auth/spreadsheets.readonly"
-            })))
-            .mount(&server)
-            .await;
-
-        let provider = StaticTokenProvider::new("no-scope");
-        let client = reqwest::Client::new();
-        let endpoint = format!("{}/oauth2/v3/tokeninfo", server.uri());
-        let err = ensure_drive_scope_with_endpoint(&provider, &client, &endpoint)
-            .await
-            .unwrap_err();
-
-        match err {
-            Error::TokenProvider(message) => {
-                assert!(message.contains("drive.file scope"));
-            }
-            _ => panic!("expected TokenProvider error"),
-        }
-    }
-
-    #[tokio::test]
-    async fn ensure_drive_scope_converts_http_failures() {
-        let server = MockServer::start().await;
-        Mock::given(method("GET"))
-            .and(path("/oauth2/v3/tokeninfo"))
-            .and(query_param("access_token", "bad-token"))
-            .respond_with(ResponseTemplate::new(400).set_body_string("invalid_token"))
-            .mount(&server)
-            .await;
-
-        let provider = StaticTokenProvider::new("bad-token");
-        let client = reqwest::Client::new();
-        let endpoint = format!("{}/oauth2/v3/tokeninfo", server.uri());
-        let err = ensure_drive_scope_with_endpoint(&provider, &client, &endpoint)
-            .await
-            .unwrap_err();
-
-        match err {
-            Error::TokenProvider(message) => {
-                assert!(message.contains("status 400"));
-            }
-            _ => panic!("expected TokenProvider error"),
-        }
-    }
 }
@@ -0,0 +1,424 @@
+//! Cross-process lock around the browser bootstrap, so concurrent commands with no stored tokens
+//! run one browser flow between them instead of one each.
+
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+use super::{
+    FileRefreshTokenStore, OAuthError, RefreshTokenStore, Result, SerializedTokens, TokenStoreKey,
+};
+
+/// A lock older than this is considered abandoned. A little longer than the browser flow's own
+/// ten-minute callback timeout, so a live flow is never taken over.
+pub const BOOTSTRAP_LOCK_STALE_AFTER: Duration = Duration::from_secs(11 * 60);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Contents of the lock file: who holds it and since when.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockOwner {
+    pub pid: u32,
+    #[serde(with = "time::serde::rfc3339")]
+    pub acquired_at: OffsetDateTime,
+}
+
+impl LockOwner {
+    fn current() -> Self {
+        Self {
+            pid: std::process::id(),
+            acquired_at: OffsetDateTime::now_utc(),
+        }
+    }
+
+    /// Abandoned: too old, or held by a process that no longer exists (where that can be told).
+    fn is_stale(&self, now: OffsetDateTime) -> bool {
+        let age = now - self.acquired_at;
+        age > BOOTSTRAP_LOCK_STALE_AFTER || process_exists(self.pid) == Some(false)
+    }
+}
+
+/// Held bootstrap lock; the lock file is removed on drop.
+#[derive(Debug)]
+pub struct BootstrapLock {
+    path: PathBuf,
+    contents: String,
+}
+
+impl Drop for BootstrapLock {
+    fn drop(&mut self) {
+        // Only remove the file if it is still ours, not a successor's after a stale takeover.
+        if std::fs::read_to_string(&self.path).is_ok_and(|current| current == self.contents) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Outcome of [`FileRefreshTokenStore::try_lock_bootstrap`].
+#[derive(Debug)]
+pub enum LockAttempt {
+    Acquired(BootstrapLock),
+    HeldBy(LockOwner),
+}
+
+/// Outcome of [`FileRefreshTokenStore::take_bootstrap_turn`].
+#[derive(Debug)]
+pub enum BootstrapTurn {
+    /// This process holds the lock and should run the browser flow, then drop the lock.
+    Lead(BootstrapLock),
+    /// Usable tokens were stored, by another process or before the lock was taken.
+    Stored(SerializedTokens),
+}
+
+impl FileRefreshTokenStore {
+    /// Lock file next to the credentials file.
+    pub fn bootstrap_lock_path(&self) -> PathBuf {
+        let name = self
+            .file_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("credentials.json");
+        self.file_path
+            .with_file_name(format!("{name}.bootstrap.lock"))
+    }
+
+    /// Take the bootstrap lock unless a live process holds it. A stale lock is taken over.
+    pub async fn try_lock_bootstrap(&self) -> Result<LockAttempt> {
+        self.ensure_config_dir().await?;
+        let path = self.bootstrap_lock_path();
+        // One takeover attempt: if a stale lock is replaced by somebody else first, theirs wins.
+        for _ in 0..2 {
+            let owner = LockOwner::current();
+            let contents = serde_json::to_string(&owner)?;
+            match create_new(&path, &contents) {
+                Ok(()) => return Ok(LockAttempt::Acquired(BootstrapLock { path, contents })),
+                Err(err) if err.kind() == ErrorKind::AlreadyExists => {}
+                Err(err) => return Err(OAuthError::Storage(err)),
+            }
+
+            let existing = match std::fs::read_to_string(&path) {
+                Ok(existing) => existing,
+                // Released between our create and read; try again.
+                Err(err) if err.kind() == ErrorKind::NotFound => continue,
+                Err(err) => return Err(OAuthError::Storage(err)),
+            };
+            match serde_json::from_str::<LockOwner>(&existing) {
+                Ok(holder) if !holder.is_stale(OffsetDateTime::now_utc()) => {
+                    return Ok(LockAttempt::HeldBy(holder))
+                }
+                // Stale, or not a lock we understand: remove it if unchanged.
+                _ => remove_if_unchanged(&path, &existing)?,
+            }
+        }
+        match std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|existing| serde_json::from_str(&existing).ok())
+        {
+            Some(holder) => Ok(LockAttempt::HeldBy(holder)),
+            None => Err(OAuthError::Config(format!(
+                "could not take the bootstrap lock {}",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Wait until either this process may run the browser flow for `key`, or another process has
+    /// stored tokens for it that satisfy `usable`.
+    ///
+    /// `on_wait` is called once, when another live process is found holding the lock. Fails with
+    /// [`OAuthError::BootstrapWaitTimeout`] after `timeout` without either.
+    pub async fn take_bootstrap_turn(
+        &self,
+        key: &TokenStoreKey,
+        usable: impl Fn(&SerializedTokens) -> bool,
+        timeout: Duration,
+        on_wait: impl FnOnce(&LockOwner),
+    ) -> Result<BootstrapTurn> {
+        let started = Instant::now();
+        let mut on_wait = Some(on_wait);
+        loop {
+            let holder = match self.try_lock_bootstrap().await? {
+                LockAttempt::Acquired(lock) => {
+                    // The previous holder may have finished between the caller's check and now.
+                    return Ok(match self.load(key).await?.filter(&usable) {
+                        Some(tokens) => BootstrapTurn::Stored(tokens),
+                        None => BootstrapTurn::Lead(lock),
+                    });
+                }
+                LockAttempt::HeldBy(holder) => holder,
+            };
+            if let Some(on_wait) = on_wait.take() {
+                on_wait(&holder);
+            }
+            if let Some(tokens) = self.load(key).await?.filter(&usable) {
+                return Ok(BootstrapTurn::Stored(tokens));
+            }
+            if started.elapsed() >= timeout {
+                return Err(OAuthError::BootstrapWaitTimeout {
+                    pid: holder.pid,
+                    lock_path: self.bootstrap_lock_path(),
+                });
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// Create `path` holding `contents`, failing with `AlreadyExists` if it is there. The contents
+/// are written to a temporary file first and hard-linked into place, so the lock never exists
+/// half-written.
+fn create_new(path: &Path, contents: &str) -> std::io::Result<()> {
+    let temp = path.with_extension(format!("{}.tmp", rand::random::<u64>()));
+    let mut file = std::fs::File::create(&temp)?;
+    let linked = file
+        .write_all(contents.as_bytes())
+        .and_then(|()| std::fs::hard_link(&temp, path));
+    let _ = std::fs::remove_file(&temp);
+    linked
+}
+
+fn remove_if_unchanged(path: &Path, seen: &str) -> Result<()> {
+    match std::fs::read_to_string(path) {
+        Ok(current) if current == seen => match std::fs::remove_file(path) {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(OAuthError::Storage(err)),
+        },
+        Ok(_) => Ok(()),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(OAuthError::Storage(err)),
+    }
+}
+
+/// Whether `pid` is a running process, where the platform lets us tell cheaply (`/proc`).
+fn process_exists(pid: u32) -> Option<bool> {
+    let proc = Path::new("/proc");
+    if !proc.join("self").exists() {
+        return None;
+    }
+    Some(proc.join(pid.to_string()).exists())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::ApiProfile;
+    use std::sync::Arc;
+
+    fn key() -> TokenStoreKey {
+        TokenStoreKey {
+            profile: ApiProfile::Enterprise,
+            project_number: Some("123".to_string()),
+            endpoint_location: Some("global".to_string()),
+            user_hint: None,
+        }
+    }
+
+    fn tokens(refresh_token: &str) -> SerializedTokens {
+        SerializedTokens {
+            refresh_token: refresh_token.to_string(),
+            scopes: vec![],
+            expires_at: None,
+            token_type: "Bearer".to_string(),
+            updated_at: OffsetDateTime::now_utc(),
+            client_fingerprint: None,
+        }
+    }
+
+    fn store(dir: &Path) -> FileRefreshTokenStore {
+        FileRefreshTokenStore::from_path(dir.join("credentials.json")).unwrap()
+    }
+
+    fn write_lock(store: &FileRefreshTokenStore, owner: &LockOwner) {
+        std::fs::write(
+            store.bootstrap_lock_path(),
+            serde_json::to_string(owner).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn acquire_blocks_others_until_released() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path());
+
+        let lock = match store.try_lock_bootstrap().await.unwrap() {
+            LockAttempt::Acquired(lock) => lock,
+            other => panic!("expected to acquire, got {other:?}"),
+        };
+        match store.try_lock_bootstrap().await.unwrap() {
+            LockAttempt::HeldBy(owner) => assert_eq!(owner.pid, std::process::id()),
+            other => panic!("expected the lock to be held, got {other:?}"),
+        }
+
+        drop(lock);
+        assert!(!store.bootstrap_lock_path().exists());
+        assert!(matches!(
+            store.try_lock_bootstrap().await.unwrap(),
+            LockAttempt::Acquired(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn old_lock_is_taken_over() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path());
+        write_lock(
+            &store,
+            &LockOwner {
+                pid: std::process::id(),
+                acquired_at: OffsetDateTime::now_utc() - BOOTSTRAP_LOCK_STALE_AFTER * 2,
+            },
+        );
+
+        let lock = match store.try_lock_bootstrap().await.unwrap() {
+            LockAttempt::Acquired(lock) => lock,
+            other => panic!("expected a stale takeover, got {other:?}"),
+        };
+        let owner: LockOwner =
+            serde_json::from_str(&std::fs::read_to_string(store.bootstrap_lock_path()).unwrap())
+                .unwrap();
+        assert!(!owner.is_stale(OffsetDateTime::now_utc()));
+        drop(lock);
+    }
+
+    #[tokio::test]
+    async fn lock_of_a_dead_process_is_taken_over() {
+        if process_exists(std::process::id()).is_none() {
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path());
+        write_lock(
+            &store,
+            &LockOwner {
+                pid: u32::MAX - 1,
+                acquired_at: OffsetDateTime::now_utc(),
+            },
+        );
+
+        assert!(matches!(
+            store.try_lock_bootstrap().await.unwrap(),
+            LockAttempt::Acquired(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn unreadable_lock_is_taken_over() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path());
+        std::fs::write(store.bootstrap_lock_path(), "{\"pid\":").unwrap();
+
+        assert!(matches!(
+            store.try_lock_bootstrap().await.unwrap(),
+            LockAttempt::Acquired(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn released_lock_of_a_successor_is_left_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path());
+        let LockAttempt::Acquired(lock) = store.try_lock_bootstrap().await.unwrap() else {
+            panic!("expected to acquire");
+        };
+        // Somebody took the lock over in the meantime.
+        let successor = LockOwner {
+            pid: 1,
+            acquired_at: OffsetDateTime::now_utc(),
+        };
+        write_lock(&store, &successor);
+
+        drop(lock);
+        assert!(store.bootstrap_lock_path().exists());
+    }
+
+    #[tokio::test]
+    async fn waiter_picks_up_the_tokens_stored_by_the_lead() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(store(dir.path()));
+
+        let lead = match store
+            .take_bootstrap_turn(&key(), |_| true, Duration::from_secs(5), |_| {})
+            .await
+            .unwrap()
+        {
+            BootstrapTurn::Lead(lock) => lock,
+            other => panic!("expected to lead, got {other:?}"),
+        };
+
+        let waiter = {
+            let store = Arc::clone(&store);
+            tokio::spawn(async move {
+                let mut waited_on = None;
+                let turn = store
+                    .take_bootstrap_turn(
+                        &key(),
+                        |tokens| tokens.refresh_token == "from-lead",
+                        Duration::from_secs(5),
+                        |owner| waited_on = Some(owner.pid),
+                    )
+                    .await
+                    .unwrap();
+                (turn, waited_on)
+            })
+        };
+
+        tokio::time::sleep(POLL_INTERVAL).await;
+        store.save(&key(), &tokens("from-lead")).await.unwrap();
+        drop(lead);
+
+        let (turn, waited_on) = waiter.await.unwrap();
+        match turn {
+            BootstrapTurn::Stored(tokens) => assert_eq!(tokens.refresh_token, "from-lead"),
+            other => panic!("expected the lead's tokens, got {other:?}"),
+        }
+        assert_eq!(waited_on, Some(std::process::id()));
+    }
+
+    #[tokio::test]
+    async fn waiter_leads_when_the_lead_gives_up_without_tokens() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(store(dir.path()));
+        let LockAttempt::Acquired(lead) = store.try_lock_bootstrap().await.unwrap() else {
+            panic!("expected to acquire");
+        };
+
+        let waiter = {
+            let store = Arc::clone(&store);
+            tokio::spawn(async move {
+                store
+                    .take_bootstrap_turn(&key(), |_| true, Duration::from_secs(5), |_| {})
+                    .await
+                    .unwrap()
+            })
+        };
+        tokio::time::sleep(POLL_INTERVAL).await;
+        drop(lead);
+
+        assert!(matches!(waiter.await.unwrap(), BootstrapTurn::Lead(_)));
+    }
+
+    #[tokio::test]
+    async fn waiting_times_out_while_the_lock_is_held() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = store(dir.path());
+        let LockAttempt::Acquired(_lead) = store.try_lock_bootstrap().await.unwrap() else {
+            panic!("expected to acquire");
+        };
+
+        let err = store
+            .take_bootstrap_turn(&key(), |_| true, Duration::ZERO, |_| {})
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(err, OAuthError::BootstrapWaitTimeout { pid, .. } if pid == std::process::id()),
+            "{err}"
+        );
+        assert!(err.to_string().contains("bootstrap.lock"), "{err}");
+    }
+}
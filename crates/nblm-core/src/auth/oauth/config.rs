@@ -1,38 +1,66 @@
+use std::fmt;
+use std::path::Path;
+
 use super::{OAuthConfig, OAuthError, Result};
 
+const CLIENT_SECRET_ENV: &str = "NBLM_OAUTH_CLIENT_SECRET";
+const CLIENT_SECRET_FILE_ENV: &str = "NBLM_OAUTH_CLIENT_SECRET_FILE";
+/// Overrides [`OAuthConfig::AUTH_ENDPOINT`].
+pub const AUTH_ENDPOINT_ENV: &str = "NBLM_OAUTH_AUTH_ENDPOINT";
+/// Overrides [`OAuthConfig::TOKEN_ENDPOINT`].
+pub const TOKEN_ENDPOINT_ENV: &str = "NBLM_OAUTH_TOKEN_ENDPOINT";
+
 /// OAuth client configuration loaded from the environment.
-#[derive(Debug, Clone)]
+///
+/// The `Debug` output never includes the client secret.
+#[derive(Clone)]
 pub struct OAuthClientConfig {
     pub client_id: String,
     pub client_secret: Option<String>,
     pub redirect_uri: String,
     pub audience: Option<String>,
+    /// Where the browser is sent to sign in; Google's unless `NBLM_OAUTH_AUTH_ENDPOINT` is set.
+    pub auth_endpoint: String,
+    /// Where codes and refresh tokens are exchanged; Google's unless `NBLM_OAUTH_TOKEN_ENDPOINT`
+    /// is set.
+    pub token_endpoint: String,
 }
 
 impl OAuthClientConfig {
     /// Load client configuration from environment variables.
+    ///
+    /// The client secret is read from the file named by `NBLM_OAUTH_CLIENT_SECRET_FILE` when
+    /// that is set, and from `NBLM_OAUTH_CLIENT_SECRET` otherwise. Endpoint overrides must pass
+    /// [`validate_endpoint`].
     pub fn from_env() -> Result<Self> {
         let client_id = std::env::var("NBLM_OAUTH_CLIENT_ID")
             .map_err(|_| OAuthError::MissingEnvVar("NBLM_OAUTH_CLIENT_ID"))?;
 
-        let client_secret = std::env::var("NBLM_OAUTH_CLIENT_SECRET").ok();
+        let client_secret = match std::env::var_os(CLIENT_SECRET_FILE_ENV) {
+            Some(path) if !path.is_empty() => Some(read_client_secret_file(Path::new(&path))?),
+            _ => std::env::var(CLIENT_SECRET_ENV).ok(),
+        };
         let redirect_uri = std::env::var("NBLM_OAUTH_REDIRECT_URI")
             .unwrap_or_else(|_| OAuthConfig::DEFAULT_REDIRECT_URI.to_string());
         let audience = std::env::var("NBLM_OAUTH_AUDIENCE").ok();
+        let auth_endpoint = endpoint_from_env(AUTH_ENDPOINT_ENV, OAuthConfig::AUTH_ENDPOINT)?;
+        let token_endpoint = endpoint_from_env(TOKEN_ENDPOINT_ENV, OAuthConfig::TOKEN_ENDPOINT)?;
 
         Ok(Self {
             client_id,
             client_secret,
             redirect_uri,
             audience,
+            auth_endpoint,
+            token_endpoint,
         })
     }
 
     /// Convert this configuration into a complete `OAuthConfig` value.
     pub fn into_oauth_config(self) -> OAuthConfig {
         OAuthConfig {
-            auth_endpoint: OAuthConfig::AUTH_ENDPOINT.to_string(),
-            token_endpoint: OAuthConfig::TOKEN_ENDPOINT.to_string(),
+            auth_endpoint: self.auth_endpoint,
+            token_endpoint: self.token_endpoint,
             client_id: self.client_id,
             client_secret: self.client_secret,
             redirect_uri: self.redirect_uri,
@@ -46,6 +74,114 @@ impl OAuthClientConfig {
     }
 }
 
+impl fmt::Debug for OAuthClientConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OAuthClientConfig")
+            .field("client_id", &self.client_id)
+            .field("client_secret", &redacted(&self.client_secret))
+            .field("redirect_uri", &self.redirect_uri)
+            .field("audience", &self.audience)
+            .field("auth_endpoint", &self.auth_endpoint)
+            .field("token_endpoint", &self.token_endpoint)
+            .finish()
+    }
+}
+
+/// The endpoint override in `var`, or `default` when it is unset or empty.
+fn endpoint_from_env(var: &str, default: &str) -> Result<String> {
+    match std::env::var(var) {
+        Ok(value) if !value.trim().is_empty() => {
+            let value = value.trim();
+            validate_endpoint(var, value)?;
+            Ok(value.to_string())
+        }
+        _ => Ok(default.to_string()),
+    }
+}
+
+/// Check that an OAuth endpoint is an absolute `https` URL. Plain `http` is accepted only for
+/// loopback hosts (`localhost`, `127.0.0.0/8`, `::1`), so a test server can stand in for Google
+/// without tokens ever crossing the network unencrypted. `name` is the setting the value came
+/// from, for the error message.
+pub fn validate_endpoint(name: &str, value: &str) -> Result<()> {
+    let url = url::Url::parse(value).map_err(|err| {
+        OAuthError::Config(format!("{name} must be an absolute URL ({value}): {err}"))
+    })?;
+    match url.scheme() {
+        "https" => {}
+        "http" if is_loopback(&url) => {}
+        "http" => {
+            return Err(OAuthError::Config(format!(
+                "{name} must use https ({value}); http is only allowed for localhost"
+            )))
+        }
+        scheme => {
+            return Err(OAuthError::Config(format!(
+                "{name} must be an https URL ({value}), not {scheme}"
+            )))
+        }
+    }
+    if url.host().is_none() {
+        return Err(OAuthError::Config(format!("{name} has no host ({value})")));
+    }
+    Ok(())
+}
+
+fn is_loopback(url: &url::Url) -> bool {
+    match url.host() {
+        Some(url::Host::Domain(domain)) => domain.eq_ignore_ascii_case("localhost"),
+        Some(url::Host::Ipv4(ip)) => ip.is_loopback(),
+        Some(url::Host::Ipv6(ip)) => ip.is_loopback(),
+        None => false,
+    }
+}
+
+/// Stand-in for an optional secret in `Debug` output.
+pub(super) fn redacted(secret: &Option<String>) -> Option<&'static str> {
+    secret.as_ref().map(|_| "<redacted>")
+}
+
+/// Read the client secret from `path`, trimmed of surrounding whitespace. Errors name the
+/// file but never its contents.
+fn read_client_secret_file(path: &Path) -> Result<String> {
+    let contents = std::fs::read_to_string(path).map_err(|err| {
+        OAuthError::Config(format!(
+            "cannot read {CLIENT_SECRET_FILE_ENV} ({}): {err}",
+            path.display()
+        ))
+    })?;
+    let secret = contents.trim();
+    if secret.is_empty() {
+        return Err(OAuthError::Config(format!(
+            "{CLIENT_SECRET_FILE_ENV} ({}) is empty",
+            path.display()
+        )));
+    }
+    if let Some(warning) = permissions_warning(path) {
+        tracing::warn!("{warning}");
+    }
+    Ok(secret.to_string())
+}
+
+/// A warning when the secret file can be read by its group or by other users.
+#[cfg(unix)]
+fn permissions_warning(path: &Path) -> Option<String> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = std::fs::metadata(path).ok()?.permissions().mode();
+    (mode & 0o077 != 0).then(|| {
+        format!(
+            "{CLIENT_SECRET_FILE_ENV} ({}) is readable by other users (mode {:o}); restrict it with `chmod 600`",
+            path.display(),
+            mode & 0o777
+        )
+    })
+}
+
+#[cfg(not(unix))]
+fn permissions_warning(_path: &Path) -> Option<String> {
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -106,4 +242,160 @@ mod tests {
         assert!(config.client_secret.is_none());
         assert!(config.audience.is_none());
     }
+
+    #[test]
+    fn endpoints_must_be_https_or_loopback_http() {
+        for ok in [
+            "https://oauth2.googleapis.com/token",
+            "https://oauth.example.gov:8443/o/token",
+            "http://127.0.0.1:8080/token",
+            "http://127.1.2.3/token",
+            "http://localhost/token",
+            "http://[::1]:9000/token",
+        ] {
+            assert!(
+                validate_endpoint("NBLM_OAUTH_TOKEN_ENDPOINT", ok).is_ok(),
+                "{ok}"
+            );
+        }
+
+        for (bad, expected) in [
+            ("/token", "must be an absolute URL"),
+            ("oauth.example.gov/token", "must be an absolute URL"),
+            ("http://oauth.example.gov/token", "must use https"),
+            ("http://10.0.0.1/token", "must use https"),
+            ("ftp://oauth.example.gov/token", "not ftp"),
+            ("file:///etc/token", "not file"),
+        ] {
+            let err = validate_endpoint("NBLM_OAUTH_TOKEN_ENDPOINT", bad)
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("NBLM_OAUTH_TOKEN_ENDPOINT"), "{err}");
+            assert!(err.contains(expected), "{bad}: {err}");
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn from_env_reads_endpoint_overrides() {
+        let _guards = clean_env();
+        let config = OAuthClientConfig::from_env().unwrap();
+        assert_eq!(config.auth_endpoint, OAuthConfig::AUTH_ENDPOINT);
+        assert_eq!(config.token_endpoint, OAuthConfig::TOKEN_ENDPOINT);
+
+        std::env::set_var(AUTH_ENDPOINT_ENV, "https://oauth.example.gov/auth");
+        std::env::set_var(TOKEN_ENDPOINT_ENV, " http://127.0.0.1:9000/token ");
+        let config = OAuthClientConfig::from_env().unwrap().into_oauth_config();
+        assert_eq!(config.auth_endpoint, "https://oauth.example.gov/auth");
+        assert_eq!(config.token_endpoint, "http://127.0.0.1:9000/token");
+
+        std::env::set_var(TOKEN_ENDPOINT_ENV, "http://oauth.example.gov/token");
+        let err = OAuthClientConfig::from_env().unwrap_err().to_string();
+        assert!(
+            err.contains("NBLM_OAUTH_TOKEN_ENDPOINT must use https"),
+            "{err}"
+        );
+    }
+
+    /// Guards for every variable `from_env` reads, with only the client ID set.
+    fn clean_env() -> Vec<EnvGuard> {
+        let guards = [
+            "NBLM_OAUTH_CLIENT_ID",
+            CLIENT_SECRET_ENV,
+            CLIENT_SECRET_FILE_ENV,
+            "NBLM_OAUTH_REDIRECT_URI",
+            "NBLM_OAUTH_AUDIENCE",
+            AUTH_ENDPOINT_ENV,
+            TOKEN_ENDPOINT_ENV,
+        ]
+        .map(EnvGuard::new);
+        for guard in &guards {
+            std::env::remove_var(guard.key);
+        }
+        std::env::set_var("NBLM_OAUTH_CLIENT_ID", "client-id");
+        guards.into()
+    }
+
+    fn secret_file(contents: &str) -> tempfile::NamedTempFile {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), contents).unwrap();
+        file
+    }
+
+    #[test]
+    #[serial]
+    fn from_env_reads_the_secret_file_over_the_variable() {
+        let _guards = clean_env();
+        let file = secret_file("  file-secret\n");
+        std::env::set_var(CLIENT_SECRET_FILE_ENV, file.path());
+        std::env::set_var(CLIENT_SECRET_ENV, "env-secret");
+
+        let config = OAuthClientConfig::from_env().unwrap();
+        assert_eq!(config.client_secret.as_deref(), Some("file-secret"));
+    }
+
+    #[test]
+    #[serial]
+    fn from_env_rejects_an_empty_secret_file() {
+        let _guards = clean_env();
+        let file = secret_file(" \n");
+        std::env::set_var(CLIENT_SECRET_FILE_ENV, file.path());
+
+        let err = OAuthClientConfig::from_env().unwrap_err().to_string();
+        assert!(err.contains(&file.path().display().to_string()), "{err}");
+        assert!(err.contains("is empty"), "{err}");
+    }
+
+    #[test]
+    #[serial]
+    fn from_env_fails_on_a_missing_secret_file_even_with_the_variable_set() {
+        let _guards = clean_env();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("missing-secret");
+        std::env::set_var(CLIENT_SECRET_FILE_ENV, &path);
+        std::env::set_var(CLIENT_SECRET_ENV, "env-secret");
+
+        let err = OAuthClientConfig::from_env().unwrap_err().to_string();
+        assert!(
+            err.contains("cannot read NBLM_OAUTH_CLIENT_SECRET_FILE"),
+            "{err}"
+        );
+        assert!(err.contains(&path.display().to_string()), "{err}");
+        assert!(!err.contains("env-secret"), "{err}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn permissions_warning_flags_group_or_world_readable_files() {
+        use std::os::unix::fs::PermissionsExt;
+        let file = secret_file("file-secret");
+        let set_mode = |mode| {
+            std::fs::set_permissions(file.path(), std::fs::Permissions::from_mode(mode)).unwrap()
+        };
+
+        set_mode(0o600);
+        assert_eq!(permissions_warning(file.path()), None);
+        set_mode(0o644);
+        let warning = permissions_warning(file.path()).unwrap();
+        assert!(warning.contains("mode 644"), "{warning}");
+        assert!(warning.contains("chmod 600"), "{warning}");
+        set_mode(0o640);
+        assert!(permissions_warning(file.path()).is_some());
+    }
+
+    #[test]
+    #[serial]
+    fn the_secret_never_appears_in_debug_output() {
+        let _guards = clean_env();
+        let file = secret_file("file-secret");
+        std::env::set_var(CLIENT_SECRET_FILE_ENV, file.path());
+
+        let config = OAuthClientConfig::from_env().unwrap();
+        let debug = format!("{config:?}");
+        assert!(debug.contains("<redacted>"), "{debug}");
+        let oauth_debug = format!("{:?}", config.into_oauth_config());
+        for output in [debug, oauth_debug] {
+            assert!(!output.contains("file-secret"), "{output}");
+        }
+    }
 }
@@ -14,12 +14,46 @@ pub enum OAuthError {
     #[error("Token refresh failed: {0}")]
     Refresh(String),
 
+    /// The token endpoint refused the refresh token (`invalid_grant`, `invalid_client` or
+    /// `unauthorized_client`). Retrying cannot help; the user has to sign in again.
+    #[error(
+        "the OAuth server rejected the stored refresh token ({error}{}); it was revoked, has expired or belongs to another client. Delete credentials.json from the nblm config directory and sign in again with `nblm --auth user-oauth` from a terminal",
+        description.as_deref().map(|d| format!(": {d}")).unwrap_or_default()
+    )]
+    RefreshRejected {
+        error: String,
+        description: Option<String>,
+    },
+
+    /// The token endpoint could not be reached or answered with a retryable status (5xx, 429,
+    /// 408). `status` is `None` for network failures.
+    #[error("OAuth token endpoint unavailable: {message}")]
+    RefreshUnavailable {
+        status: Option<u16>,
+        retry_after: Option<std::time::Duration>,
+        message: String,
+    },
+
     #[error("Token revocation failed: {0}")]
     Revocation(String),
 
     #[error("Token storage error: {0}")]
     Storage(#[from] std::io::Error),
 
+    #[error(
+        "stored refresh token was issued to a different OAuth client (client fingerprint {stored}, NBLM_OAUTH_CLIENT_ID has {active}); sign in again with `nblm --auth user-oauth` from a terminal with NBLM_OAUTH_DISABLE_BOOTSTRAP unset, or restore the previous NBLM_OAUTH_CLIENT_ID"
+    )]
+    ClientMismatch { stored: String, active: String },
+
+    #[error(
+        "timed out waiting for another nblm process (pid {pid}) to finish signing in; if no other nblm process is running, delete {} and try again",
+        lock_path.display()
+    )]
+    BootstrapWaitTimeout {
+        pid: u32,
+        lock_path: std::path::PathBuf,
+    },
+
     #[error("CSRF state mismatch: expected {expected}, got {actual}")]
     StateMismatch { expected: String, actual: String },
 
@@ -36,4 +70,11 @@ pub enum OAuthError {
     Json(#[from] SerdeJsonError),
 }
 
+impl OAuthError {
+    /// Whether a token refresh that failed with this error may succeed when retried.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::RefreshUnavailable { .. })
+    }
+}
+
 pub type Result<T> = std::result::Result<T, OAuthError>;
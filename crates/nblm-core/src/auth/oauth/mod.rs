@@ -2,30 +2,38 @@ use std::collections::HashMap;
 use std::fmt;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 
 use async_trait::async_trait;
 use oauth2::{
-    basic::BasicClient, AuthUrl, AuthorizationCode, ClientId, ClientSecret, CsrfToken, EndpointSet,
-    PkceCodeChallenge, PkceCodeVerifier, RedirectUrl, RefreshToken, Scope, StandardTokenResponse,
-    TokenResponse as OAuth2TokenResponse, TokenUrl,
+    basic::BasicClient, AsyncHttpClient, AuthUrl, AuthorizationCode, ClientId, ClientSecret,
+    CsrfToken, EndpointSet, PkceCodeChallenge, PkceCodeVerifier, RedirectUrl, RefreshToken, Scope,
+    StandardTokenResponse, TokenResponse as OAuth2TokenResponse, TokenUrl,
 };
-use parking_lot::RwLock;
-use reqwest::Client;
+use parking_lot::{Mutex, RwLock};
+use reqwest::{header::RETRY_AFTER, Client, StatusCode};
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
+use tracing::warn;
 
-use crate::auth::{ProviderKind, TokenProvider};
+use crate::auth::{ProbeStatus, ProviderKind, TokenProvider};
+use crate::client::retry::{parse_retry_after, should_retry_status};
+use crate::client::RetryConfig;
+use crate::debug_http::log_token_response;
 use crate::env::ApiProfile;
 use crate::error::{Error as CoreError, Result as CoreResult};
 
+mod bootstrap_lock;
 mod config;
 mod error;
 pub mod loopback;
 #[cfg(test)]
 pub mod testing;
 
-pub use config::OAuthClientConfig;
+pub use bootstrap_lock::{
+    BootstrapLock, BootstrapTurn, LockAttempt, LockOwner, BOOTSTRAP_LOCK_STALE_AFTER,
+};
+pub use config::{validate_endpoint, OAuthClientConfig, AUTH_ENDPOINT_ENV, TOKEN_ENDPOINT_ENV};
 pub use error::{OAuthError, Result};
 
 impl From<OAuthError> for CoreError {
@@ -39,7 +47,9 @@ impl From<OAuthError> for CoreError {
 // ============================================================================
 
 /// OAuth2 configuration for Authorization Code Flow with PKCE
-#[derive(Debug, Clone)]
+///
+/// The `Debug` output never includes the client secret.
+#[derive(Clone)]
 pub struct OAuthConfig {
     pub auth_endpoint: String,
     pub token_endpoint: String,
@@ -51,13 +61,30 @@ pub struct OAuthConfig {
     pub additional_params: HashMap<String, String>,
 }
 
+impl std::fmt::Debug for OAuthConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OAuthConfig")
+            .field("auth_endpoint", &self.auth_endpoint)
+            .field("token_endpoint", &self.token_endpoint)
+            .field("client_id", &self.client_id)
+            .field("client_secret", &config::redacted(&self.client_secret))
+            .field("redirect_uri", &self.redirect_uri)
+            .field("scopes", &self.scopes)
+            .field("audience", &self.audience)
+            .field("additional_params", &self.additional_params)
+            .finish()
+    }
+}
+
 const GOOGLE_REVOKE_ENDPOINT: &str = "https://oauth2.googleapis.com/revoke";
 const REVOKE_ENDPOINT_ENV: &str = "NBLM_OAUTH_REVOKE_ENDPOINT";
 
 impl OAuthConfig {
     pub const DEFAULT_REDIRECT_URI: &str = "http://127.0.0.1:4317";
-    pub(crate) const AUTH_ENDPOINT: &str = "https://accounts.google.com/o/oauth2/v2/auth";
-    pub(crate) const TOKEN_ENDPOINT: &str = "https://oauth2.googleapis.com/token";
+    /// Google's authorization endpoint, used unless `NBLM_OAUTH_AUTH_ENDPOINT` is set.
+    pub const AUTH_ENDPOINT: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+    /// Google's token endpoint, used unless `NBLM_OAUTH_TOKEN_ENDPOINT` is set.
+    pub const TOKEN_ENDPOINT: &str = "https://oauth2.googleapis.com/token";
     pub(crate) const SCOPE_CLOUD_PLATFORM: &str = "https://www.googleapis.com/auth/cloud-platform";
     pub(crate) const SCOPE_DRIVE_FILE: &str = "https://www.googleapis.com/auth/drive.file";
 
@@ -189,6 +216,35 @@ pub struct SerializedTokens {
     pub token_type: String,
     #[serde(with = "time::serde::rfc3339")]
     pub updated_at: OffsetDateTime,
+    /// [`client_fingerprint`] of the OAuth client the refresh token was issued to. Missing in
+    /// entries written before it was recorded; those are accepted and upgraded on the next save.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_fingerprint: Option<String>,
+}
+
+impl SerializedTokens {
+    /// Fail with [`OAuthError::ClientMismatch`] when the token was issued to a client other than
+    /// `client_id`. Refreshing it would only fail with `invalid_grant`.
+    pub fn ensure_client(&self, client_id: &str) -> Result<()> {
+        let active = client_fingerprint(client_id);
+        match &self.client_fingerprint {
+            Some(stored) if *stored != active => Err(OAuthError::ClientMismatch {
+                stored: stored.clone(),
+                active,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Short, non-reversible identifier for an OAuth client ID: the first 12 hex digits of its
+/// SHA-256, so credentials files do not spell out the client ID.
+pub fn client_fingerprint(client_id: &str) -> String {
+    use sha2::{Digest, Sha256};
+    Sha256::digest(client_id.as_bytes())[..6]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
 }
 
 /// Credentials file format
@@ -228,28 +284,33 @@ pub trait RefreshTokenStore: Send + Sync {
 // FileRefreshTokenStore
 // ============================================================================
 
-const CONFIG_DIR_ENV: &str = "NBLM_CONFIG_DIR";
+/// Variable naming the directory [`config_dir`] returns instead of the platform default.
+pub const CONFIG_DIR_ENV: &str = "NBLM_CONFIG_DIR";
 
 /// File-based implementation of RefreshTokenStore
 pub struct FileRefreshTokenStore {
     file_path: std::path::PathBuf,
 }
 
+/// Directory holding nblm's local state (credentials, queue, config). `NBLM_CONFIG_DIR` overrides the
+/// platform default.
+pub fn config_dir() -> Result<PathBuf> {
+    if let Ok(custom_dir) = std::env::var(CONFIG_DIR_ENV) {
+        return Ok(PathBuf::from(custom_dir));
+    }
+
+    let dirs = directories::ProjectDirs::from("com", "nblm", "nblm-rs").ok_or_else(|| {
+        OAuthError::Config(format!(
+            "failed to find config directory (no home directory); set {CONFIG_DIR_ENV} to a writable directory"
+        ))
+    })?;
+    Ok(dirs.config_dir().to_path_buf())
+}
+
 impl FileRefreshTokenStore {
     /// Create a new FileRefreshTokenStore
     pub fn new() -> Result<Self> {
-        if let Ok(custom_dir) = std::env::var(CONFIG_DIR_ENV) {
-            let file_path = PathBuf::from(custom_dir).join("credentials.json");
-            return Self::from_path(file_path);
-        }
-
-        let dirs = directories::ProjectDirs::from("com", "nblm", "nblm-rs")
-            .ok_or_else(|| OAuthError::Config("failed to find config directory".to_string()))?;
-
-        let config_dir = dirs.config_dir();
-        let file_path = config_dir.join("credentials.json");
-
-        Self::from_path(file_path)
+        Self::from_path(config_dir()?.join("credentials.json"))
     }
 
     /// Create a store backed by an explicit credentials file path.
@@ -259,11 +320,48 @@ impl FileRefreshTokenStore {
         })
     }
 
+    /// Whether any refresh tokens are stored. Unlike `load`, this never creates the config
+    /// directory.
+    pub async fn has_entries(&self) -> Result<bool> {
+        if !self.file_path.exists() {
+            return Ok(false);
+        }
+        let content = tokio::fs::read_to_string(&self.file_path)
+            .await
+            .map_err(|e| OAuthError::Config(format!("failed to read credentials file: {}", e)))?;
+        let file: CredentialsFile = serde_json::from_str(&content)
+            .map_err(|e| OAuthError::Config(format!("failed to parse credentials file: {}", e)))?;
+        Ok(!file.entries.is_empty())
+    }
+
+    /// Synchronous readiness check for error reporting; never creates the config directory.
+    pub fn probe(&self) -> ProbeStatus {
+        let content = match std::fs::read_to_string(&self.file_path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                return ProbeStatus::Missing("no stored tokens".to_string())
+            }
+            Err(err) => return ProbeStatus::Missing(format!("credentials file unreadable: {err}")),
+        };
+        match serde_json::from_str::<CredentialsFile>(&content) {
+            Ok(file) if !file.entries.is_empty() => {
+                ProbeStatus::Ready(format!("{} stored token(s)", file.entries.len()))
+            }
+            Ok(_) => ProbeStatus::Missing("no stored tokens".to_string()),
+            Err(err) => ProbeStatus::Missing(format!("credentials file unreadable: {err}")),
+        }
+    }
+
     /// Ensure config directory exists with proper permissions (async)
     async fn ensure_config_dir(&self) -> Result<()> {
         if let Some(config_dir) = self.file_path.parent() {
             tokio::fs::create_dir_all(config_dir).await.map_err(|e| {
-                OAuthError::Config(format!("failed to create config directory: {}", e))
+                OAuthError::Config(format!(
+                    "failed to create config directory {}: {}; set {} to a writable directory",
+                    config_dir.display(),
+                    e,
+                    CONFIG_DIR_ENV
+                ))
             })?;
 
             #[cfg(unix)]
@@ -286,10 +384,9 @@ impl FileRefreshTokenStore {
         Ok(())
     }
 
-    /// Load credentials file
+    /// Load credentials file. Reading never creates the config directory, so stored tokens stay
+    /// usable on a read-only filesystem.
     async fn load_file(&self) -> Result<CredentialsFile> {
-        self.ensure_config_dir().await?;
-
         if !self.file_path.exists() {
             return Ok(CredentialsFile::new());
         }
@@ -417,7 +514,11 @@ pub struct OAuthFlow {
 
 impl OAuthFlow {
     /// Create a new OAuthFlow
+    ///
+    /// Both endpoints must pass [`validate_endpoint`]: `https`, or `http` on a loopback host.
     pub fn new(config: OAuthConfig, http: Arc<Client>) -> Result<Self> {
+        validate_endpoint("auth_endpoint", &config.auth_endpoint)?;
+        validate_endpoint("token_endpoint", &config.token_endpoint)?;
         let client_id = ClientId::new(config.client_id.clone());
         let auth_url = AuthUrl::new(config.auth_endpoint.clone())
             .map_err(|e| OAuthError::Config(format!("invalid auth_url: {}", e)))?;
@@ -508,8 +609,23 @@ impl OAuthFlow {
             .exchange_code(code)
             .set_pkce_verifier(pkce_verifier);
 
+        let http = |request: oauth2::HttpRequest| {
+            let client = Arc::clone(&self.http);
+            async move {
+                let (method, url) = (request.method().clone(), request.uri().to_string());
+                let response = client.as_ref().call(request).await?;
+                log_token_response(
+                    method.as_str(),
+                    &url,
+                    response.status().as_u16(),
+                    response.body(),
+                );
+                Ok::<_, oauth2::HttpClientError<reqwest::Error>>(response)
+            }
+        };
+
         let token_response = token_request
-            .request_async(self.http.as_ref())
+            .request_async(&http)
             .await
             .map_err(|e| OAuthError::Config(format!("oauth token exchange failed: {}", e)))?;
 
@@ -520,15 +636,43 @@ impl OAuthFlow {
     }
 
     /// Refresh access token using refresh token
+    ///
+    /// Fails with [`OAuthError::RefreshRejected`] when the token endpoint refuses the refresh
+    /// token and [`OAuthError::RefreshUnavailable`] when a retry may succeed. Makes a single
+    /// request; [`RefreshTokenProvider`] adds the retries.
     pub async fn refresh(&self, refresh_token: &str) -> Result<OAuthTokens> {
         let refresh_token = RefreshToken::new(refresh_token.to_string());
 
         let token_request = self.client.exchange_refresh_token(&refresh_token);
 
+        // oauth2 only reports the error body, so keep the status and Retry-After here.
+        let last_response = Arc::new(Mutex::new(None));
+        let http = |request: oauth2::HttpRequest| {
+            let client = Arc::clone(&self.http);
+            let last_response = Arc::clone(&last_response);
+            async move {
+                let (method, url) = (request.method().clone(), request.uri().to_string());
+                let response = client.as_ref().call(request).await?;
+                log_token_response(
+                    method.as_str(),
+                    &url,
+                    response.status().as_u16(),
+                    response.body(),
+                );
+                let retry_after = response
+                    .headers()
+                    .get(RETRY_AFTER)
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| parse_retry_after(value, SystemTime::now()));
+                *last_response.lock() = Some((response.status(), retry_after));
+                Ok::<_, oauth2::HttpClientError<reqwest::Error>>(response)
+            }
+        };
+
         let token_response = token_request
-            .request_async(self.http.as_ref())
+            .request_async(&http)
             .await
-            .map_err(|e| OAuthError::Config(format!("oauth token refresh failed: {}", e)))?;
+            .map_err(|e| refresh_error(e, last_response.lock().take()))?;
 
         Ok(OAuthTokens::from_oauth2_response(
             token_response,
@@ -555,11 +699,12 @@ impl OAuthFlow {
             .await
             .map_err(|e| OAuthError::Revocation(format!("revocation request failed: {}", e)))?;
 
-        if response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        log_token_response("POST", &endpoint, status.as_u16(), body.as_bytes());
+        if status.is_success() {
             Ok(())
         } else {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
             Err(OAuthError::Revocation(format!(
                 "revocation failed (status {}): {}",
                 status, body
@@ -568,6 +713,51 @@ impl OAuthFlow {
     }
 }
 
+type RefreshRequestError = oauth2::RequestTokenError<
+    oauth2::HttpClientError<reqwest::Error>,
+    oauth2::basic::BasicErrorResponse,
+>;
+
+/// Sort a failed refresh into rejected (sign in again), transient (retry) or other.
+fn refresh_error(
+    err: RefreshRequestError,
+    response: Option<(StatusCode, Option<Duration>)>,
+) -> OAuthError {
+    use oauth2::basic::BasicErrorResponseType;
+    use oauth2::RequestTokenError;
+
+    if let RequestTokenError::ServerResponse(body) = &err {
+        if matches!(
+            body.error(),
+            BasicErrorResponseType::InvalidGrant
+                | BasicErrorResponseType::InvalidClient
+                | BasicErrorResponseType::UnauthorizedClient
+        ) {
+            return OAuthError::RefreshRejected {
+                error: body.error().as_ref().to_string(),
+                description: body.error_description().cloned(),
+            };
+        }
+    }
+    let message = format!("oauth token refresh failed: {err}");
+    match (&err, response) {
+        // The request never got a response: connection refused, reset or timed out.
+        (RequestTokenError::Request(_), _) => OAuthError::RefreshUnavailable {
+            status: None,
+            retry_after: None,
+            message,
+        },
+        (_, Some((status, retry_after))) if should_retry_status(status) => {
+            OAuthError::RefreshUnavailable {
+                status: Some(status.as_u16()),
+                retry_after,
+                message,
+            }
+        }
+        _ => OAuthError::Refresh(message),
+    }
+}
+
 // ============================================================================
 // RefreshTokenProvider
 // ============================================================================
@@ -578,8 +768,12 @@ pub struct RefreshTokenProvider<S: RefreshTokenStore> {
     store: Arc<S>,
     cache: RwLock<Option<TokenCacheEntry>>,
     store_key: TokenStoreKey,
+    retry: RetryConfig,
 }
 
+/// Retries after a transient refresh failure by default, for three attempts in total.
+const DEFAULT_REFRESH_MAX_RETRIES: usize = 2;
+
 impl<S: RefreshTokenStore> RefreshTokenProvider<S> {
     /// Create a new RefreshTokenProvider
     pub fn new(flow: OAuthFlow, store: Arc<S>, store_key: TokenStoreKey) -> Self {
@@ -588,6 +782,55 @@ impl<S: RefreshTokenStore> RefreshTokenProvider<S> {
             store,
             cache: RwLock::new(None),
             store_key,
+            retry: RetryConfig::default().with_max_retries(DEFAULT_REFRESH_MAX_RETRIES),
+        }
+    }
+
+    /// Retry policy for token refreshes that fail with a 5xx, 429 or 408 status or a network
+    /// error. Rejected refresh tokens (`invalid_grant`, `invalid_client`) are never retried.
+    pub fn with_retry_config(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// [`OAuthFlow::refresh`] with retries for transient failures, honoring Retry-After (capped
+    /// at the configured maximum delay).
+    async fn refresh_with_retry(&self, refresh_token: &str) -> Result<OAuthTokens> {
+        let mut backoff = self.retry.backoff();
+        let mut attempts = 0usize;
+        loop {
+            let err = match self.flow.refresh(refresh_token).await {
+                Ok(tokens) => return Ok(tokens),
+                Err(err) if err.is_transient() && attempts < self.retry.max_retries => err,
+                Err(err) => return Err(err),
+            };
+            attempts += 1;
+            let max_delay = self.retry.max_delay;
+            let backoff_delay = backoff.next().map(|d| d.min(max_delay));
+            let (status, retry_after) = match &err {
+                OAuthError::RefreshUnavailable {
+                    status,
+                    retry_after,
+                    ..
+                } => (
+                    status.and_then(|s| StatusCode::from_u16(s).ok()),
+                    *retry_after,
+                ),
+                _ => (None, None),
+            };
+            let delay = retry_after
+                .map(|d| d.min(max_delay))
+                .or(backoff_delay)
+                .unwrap_or_default();
+            self.retry.notify(attempts, delay, status, retry_after);
+            warn!(
+                %err,
+                attempt = attempts,
+                max_retries = self.retry.max_retries,
+                retry_after = ?delay,
+                "retrying OAuth token refresh"
+            );
+            tokio::time::sleep(delay).await;
         }
     }
 
@@ -610,9 +853,10 @@ impl<S: RefreshTokenStore> RefreshTokenProvider<S> {
             .load(&self.store_key)
             .await?
             .ok_or_else(|| OAuthError::Config("refresh token unavailable".to_string()))?;
+        stored.ensure_client(&self.flow.config.client_id)?;
 
         // Refresh access token
-        let tokens = self.flow.refresh(&stored.refresh_token).await?;
+        let tokens = self.refresh_with_retry(&stored.refresh_token).await?;
         let refresh_token = tokens
             .refresh_token
             .clone()
@@ -643,8 +887,13 @@ impl<S: RefreshTokenStore> RefreshTokenProvider<S> {
             expires_at: Some(tokens.expires_at),
             token_type,
             updated_at: now,
+            client_fingerprint: Some(client_fingerprint(&self.flow.config.client_id)),
         };
-        self.store.save(&self.store_key, &serialized).await?;
+        // A store that cannot be written (a read-only home, for example) must not fail a call that
+        // already has its token; the next run refreshes again from the stored refresh token.
+        if let Err(err) = self.store.save(&self.store_key, &serialized).await {
+            tracing::warn!("could not store the refreshed OAuth tokens: {err}");
+        }
 
         Ok(tokens)
     }
@@ -671,6 +920,7 @@ impl<S: RefreshTokenStore> TokenProvider for RefreshTokenProvider<S> {
 mod tests {
     use super::*;
     use crate::auth::oauth::testing::fake::FakeOAuthServer;
+    use crate::client::RetryEvent;
     use tempfile::tempdir;
     use wiremock::matchers::{method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
@@ -771,6 +1021,45 @@ mod tests {
         assert_eq!(tokens.access_token, "new-access-token");
     }
 
+    #[tokio::test]
+    #[serial_test::serial]
+    async fn refresh_honors_debug_http_without_logging_tokens() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "new-access-token",
+                "expires_in": 3600,
+                "token_type": "Bearer"
+            })))
+            .mount(&server)
+            .await;
+        let config = OAuthConfig {
+            auth_endpoint: "https://accounts.google.com/o/oauth2/v2/auth".to_string(),
+            token_endpoint: format!("{}/token", server.uri()),
+            client_id: "test-client-id".to_string(),
+            client_secret: None,
+            redirect_uri: "http://127.0.0.1:4317".to_string(),
+            scopes: vec!["scope1".to_string()],
+            audience: None,
+            additional_params: HashMap::new(),
+        };
+        let flow = OAuthFlow::new(config, Arc::new(Client::new())).unwrap();
+
+        crate::set_debug_http(true);
+        let refreshed = flow.refresh("refresh-token-123").await;
+        crate::debug_http::reset();
+
+        assert_eq!(refreshed.unwrap().access_token, "new-access-token");
+        let logged: Vec<_> = crate::debug_http::take_logged()
+            .into_iter()
+            .filter(|line| line.contains(&server.uri()))
+            .collect();
+        assert_eq!(logged.len(), 1, "{logged:?}");
+        assert!(logged[0].contains("status=200"), "{}", logged[0]);
+        assert!(!logged[0].contains("new-access-token"), "{}", logged[0]);
+    }
+
     // Test 1: PKCE validity tests (security critical)
     #[test]
     fn test_pkce_code_verifier_and_challenge_generation() {
@@ -902,6 +1191,7 @@ mod tests {
                     expires_at: Some(OffsetDateTime::now_utc() + Duration::from_secs(3600)),
                     token_type: "Bearer".to_string(),
                     updated_at: OffsetDateTime::now_utc(),
+                    client_fingerprint: None,
                 };
                 store_clone.save(&key_clone, &tokens).await
             });
@@ -941,6 +1231,7 @@ mod tests {
             expires_at: Some(OffsetDateTime::now_utc() + Duration::from_secs(3600)),
             token_type: "Bearer".to_string(),
             updated_at: OffsetDateTime::now_utc(),
+            client_fingerprint: None,
         };
 
         store.save(&key, &tokens).await.unwrap();
@@ -957,6 +1248,74 @@ mod tests {
         store.delete(&key).await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_file_store_reads_without_creating_and_names_the_override_on_save() {
+        let blocker = tempfile::NamedTempFile::new().unwrap();
+        // Nothing can be created below a regular file, even by root.
+        let store_path = blocker.path().join("nblm").join("credentials.json");
+        let store = FileRefreshTokenStore::from_path(&store_path).unwrap();
+        let key = TokenStoreKey {
+            profile: ApiProfile::Enterprise,
+            project_number: Some("read-only-test".to_string()),
+            endpoint_location: Some("global".to_string()),
+            user_hint: None,
+        };
+
+        assert!(store.load(&key).await.unwrap().is_none());
+
+        let tokens = SerializedTokens {
+            refresh_token: "token".to_string(),
+            scopes: vec![],
+            expires_at: None,
+            token_type: "Bearer".to_string(),
+            updated_at: OffsetDateTime::now_utc(),
+            client_fingerprint: None,
+        };
+        let message = store.save(&key, &tokens).await.unwrap_err().to_string();
+        assert!(
+            message.contains(&blocker.path().join("nblm").display().to_string()),
+            "{message}"
+        );
+        assert!(message.contains(CONFIG_DIR_ENV), "{message}");
+    }
+
+    #[tokio::test]
+    async fn test_file_store_has_entries() {
+        let temp_dir = tempdir().unwrap();
+        let store_path = temp_dir.path().join("nested").join("credentials.json");
+        let store = FileRefreshTokenStore::from_path(&store_path).unwrap();
+        assert!(!store.has_entries().await.unwrap());
+        assert_eq!(
+            store.probe(),
+            ProbeStatus::Missing("no stored tokens".to_string())
+        );
+        assert!(!store_path.parent().unwrap().exists());
+
+        let key = TokenStoreKey {
+            profile: ApiProfile::Enterprise,
+            project_number: Some("entries-test".to_string()),
+            endpoint_location: Some("global".to_string()),
+            user_hint: None,
+        };
+        let tokens = SerializedTokens {
+            refresh_token: "token".to_string(),
+            scopes: vec![],
+            expires_at: None,
+            token_type: "Bearer".to_string(),
+            updated_at: OffsetDateTime::now_utc(),
+            client_fingerprint: None,
+        };
+        store.save(&key, &tokens).await.unwrap();
+        assert!(store.has_entries().await.unwrap());
+        assert_eq!(
+            store.probe(),
+            ProbeStatus::Ready("1 stored token(s)".to_string())
+        );
+
+        store.delete(&key).await.unwrap();
+        assert!(!store.has_entries().await.unwrap());
+    }
+
     #[test]
     #[serial_test::serial]
     fn test_file_store_respects_custom_config_dir() {
@@ -1024,6 +1383,7 @@ mod tests {
             expires_at: Some(OffsetDateTime::now_utc() - Duration::from_secs(3600)), // Expired
             token_type: "Bearer".to_string(),
             updated_at: OffsetDateTime::now_utc() - Duration::from_secs(3600),
+            client_fingerprint: None,
         };
         store.save(&key, &initial_tokens).await.unwrap();
 
@@ -1088,6 +1448,7 @@ mod tests {
             expires_at: Some(OffsetDateTime::now_utc() - Duration::from_secs(3600)), // Expired
             token_type: "Bearer".to_string(),
             updated_at: OffsetDateTime::now_utc() - Duration::from_secs(3600),
+            client_fingerprint: None,
         };
         store.save(&key, &initial_tokens).await.unwrap();
 
@@ -1272,10 +1633,268 @@ mod tests {
             expires_at: None,
             token_type: "Bearer".into(),
             updated_at: OffsetDateTime::now_utc(),
+            client_fingerprint: None,
         };
         store.save(&key, &serialized).await.unwrap();
         assert!(path.exists());
         store.delete_file().await.unwrap();
         assert!(!path.exists());
     }
+
+    /// Store holding one expired entry issued to `fingerprint`, plus a provider for `client_id`
+    /// whose token endpoint is `server`.
+    async fn provider_with_stored_client(
+        server: &MockServer,
+        client_id: &str,
+        fingerprint: Option<String>,
+    ) -> (
+        RefreshTokenProvider<FileRefreshTokenStore>,
+        Arc<FileRefreshTokenStore>,
+        TokenStoreKey,
+        tempfile::TempDir,
+    ) {
+        let config = OAuthConfig {
+            auth_endpoint: "https://accounts.google.com/o/oauth2/v2/auth".to_string(),
+            token_endpoint: format!("{}/token", server.uri()),
+            client_id: client_id.to_string(),
+            client_secret: None,
+            redirect_uri: "http://127.0.0.1:4317".to_string(),
+            scopes: vec!["scope1".to_string()],
+            audience: None,
+            additional_params: HashMap::new(),
+        };
+        let flow = OAuthFlow::new(config, Arc::new(Client::new())).unwrap();
+        let temp_dir = tempdir().unwrap();
+        let store = Arc::new(
+            FileRefreshTokenStore::from_path(temp_dir.path().join("credentials.json")).unwrap(),
+        );
+        let key = TokenStoreKey {
+            profile: ApiProfile::Enterprise,
+            project_number: Some("client-check".to_string()),
+            endpoint_location: Some("global".to_string()),
+            user_hint: None,
+        };
+        let stored = SerializedTokens {
+            refresh_token: "stored-refresh-token".to_string(),
+            scopes: vec!["scope1".to_string()],
+            expires_at: None,
+            token_type: "Bearer".to_string(),
+            updated_at: OffsetDateTime::now_utc(),
+            client_fingerprint: fingerprint,
+        };
+        store.save(&key, &stored).await.unwrap();
+        let provider = RefreshTokenProvider::new(flow, Arc::clone(&store), key.clone());
+        (provider, store, key, temp_dir)
+    }
+
+    async fn mount_token_endpoint(server: &MockServer) {
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "new-access-token",
+                "expires_in": 3600,
+                "token_type": "Bearer"
+            })))
+            .mount(server)
+            .await;
+    }
+
+    #[test]
+    fn client_fingerprint_is_short_stable_and_hides_the_id() {
+        let fingerprint = client_fingerprint("1234-abc.apps.googleusercontent.com");
+        assert_eq!(fingerprint.len(), 12);
+        assert!(fingerprint.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(
+            fingerprint,
+            client_fingerprint("1234-abc.apps.googleusercontent.com")
+        );
+        assert_ne!(
+            fingerprint,
+            client_fingerprint("5678-def.apps.googleusercontent.com")
+        );
+        assert!(!fingerprint.contains("1234"));
+    }
+
+    #[tokio::test]
+    async fn refresh_proceeds_when_stored_client_matches() {
+        let server = MockServer::start().await;
+        mount_token_endpoint(&server).await;
+        let (provider, store, key, _dir) = provider_with_stored_client(
+            &server,
+            "org-client",
+            Some(client_fingerprint("org-client")),
+        )
+        .await;
+
+        assert_eq!(provider.access_token().await.unwrap(), "new-access-token");
+        let stored = store.load(&key).await.unwrap().unwrap();
+        assert_eq!(
+            stored.client_fingerprint,
+            Some(client_fingerprint("org-client"))
+        );
+    }
+
+    #[tokio::test]
+    async fn refresh_is_refused_when_stored_client_differs() {
+        let server = MockServer::start().await;
+        mount_token_endpoint(&server).await;
+        let (provider, store, key, _dir) = provider_with_stored_client(
+            &server,
+            "org-client",
+            Some(client_fingerprint("personal-test-client")),
+        )
+        .await;
+
+        let err = provider.ensure_tokens(false).await.unwrap_err();
+        match &err {
+            OAuthError::ClientMismatch { stored, active } => {
+                assert_eq!(*stored, client_fingerprint("personal-test-client"));
+                assert_eq!(*active, client_fingerprint("org-client"));
+            }
+            other => panic!("expected ClientMismatch, got {other:?}"),
+        }
+        assert!(err.to_string().contains("sign in again"), "{err}");
+        // The token endpoint was never asked, and the entry is left for the original client.
+        assert!(server.received_requests().await.unwrap().is_empty());
+        let stored = store.load(&key).await.unwrap().unwrap();
+        assert_eq!(stored.refresh_token, "stored-refresh-token");
+    }
+
+    #[tokio::test]
+    async fn legacy_entry_without_client_is_accepted_and_upgraded() {
+        let server = MockServer::start().await;
+        mount_token_endpoint(&server).await;
+        let (provider, store, key, _dir) =
+            provider_with_stored_client(&server, "org-client", None).await;
+
+        assert_eq!(provider.access_token().await.unwrap(), "new-access-token");
+        let upgraded = store.load(&key).await.unwrap().unwrap();
+        assert_eq!(
+            upgraded.client_fingerprint,
+            Some(client_fingerprint("org-client"))
+        );
+        assert_eq!(upgraded.refresh_token, "stored-refresh-token");
+    }
+
+    #[test]
+    fn legacy_entries_deserialize_without_client_fingerprint() {
+        let tokens: SerializedTokens = serde_json::from_value(serde_json::json!({
+            "refresh_token": "rt",
+            "scopes": [],
+            "expires_at": null,
+            "token_type": "Bearer",
+            "updated_at": "2025-01-01T00:00:00Z"
+        }))
+        .unwrap();
+        assert_eq!(tokens.client_fingerprint, None);
+        tokens.ensure_client("any-client").unwrap();
+        let json = serde_json::to_value(&tokens).unwrap();
+        assert!(json.get("client_fingerprint").is_none());
+    }
+
+    fn fast_refresh_retry(events: Arc<Mutex<Vec<RetryEvent>>>) -> RetryConfig {
+        RetryConfig::default()
+            .with_min_delay(Duration::from_millis(50))
+            .with_max_delay(Duration::from_millis(100))
+            .with_max_retries(2)
+            .with_jitter(false)
+            .with_on_retry(move |event| events.lock().push(event.clone()))
+    }
+
+    #[tokio::test]
+    async fn transient_refresh_failure_is_retried_honoring_retry_after() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .respond_with(
+                ResponseTemplate::new(500)
+                    .insert_header("retry-after", "0")
+                    .set_body_string("backend error"),
+            )
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        mount_token_endpoint(&server).await;
+        let (provider, _store, _key, _dir) =
+            provider_with_stored_client(&server, "org-client", None).await;
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let provider = provider.with_retry_config(fast_refresh_retry(Arc::clone(&events)));
+
+        assert_eq!(provider.access_token().await.unwrap(), "new-access-token");
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+        let events = events.lock();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].status, Some(StatusCode::INTERNAL_SERVER_ERROR));
+        // Retry-After: 0 wins over the 50ms backoff.
+        assert_eq!(events[0].delay, Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn rejected_refresh_token_fails_without_retrying() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "error": "invalid_grant",
+                "error_description": "Token has been expired or revoked."
+            })))
+            .mount(&server)
+            .await;
+        let (provider, _store, _key, _dir) =
+            provider_with_stored_client(&server, "org-client", None).await;
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let provider = provider.with_retry_config(fast_refresh_retry(Arc::clone(&events)));
+
+        let err = provider.ensure_tokens(false).await.unwrap_err();
+        match &err {
+            OAuthError::RefreshRejected { error, description } => {
+                assert_eq!(error, "invalid_grant");
+                assert_eq!(
+                    description.as_deref(),
+                    Some("Token has been expired or revoked.")
+                );
+            }
+            other => panic!("expected RefreshRejected, got {other:?}"),
+        }
+        assert!(err.to_string().contains("sign in again"), "{err}");
+        assert!(!err.is_transient());
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+        assert!(events.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn persistent_refresh_failure_stops_after_configured_attempts() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/token"))
+            .respond_with(ResponseTemplate::new(503).set_body_json(serde_json::json!({
+                "error": "temporarily_unavailable"
+            })))
+            .mount(&server)
+            .await;
+        let (provider, _store, _key, _dir) =
+            provider_with_stored_client(&server, "org-client", None).await;
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let provider = provider.with_retry_config(fast_refresh_retry(Arc::clone(&events)));
+
+        let err = provider.ensure_tokens(false).await.unwrap_err();
+        assert!(
+            matches!(
+                err,
+                OAuthError::RefreshUnavailable {
+                    status: Some(503),
+                    ..
+                }
+            ),
+            "{err:?}"
+        );
+        // One request plus max_retries (2) retries, backing off exponentially.
+        assert_eq!(server.received_requests().await.unwrap().len(), 3);
+        let events = events.lock();
+        let attempts: Vec<_> = events.iter().map(|event| event.attempt).collect();
+        assert_eq!(attempts, [1, 2]);
+        assert_eq!(events[0].delay, Duration::from_millis(50));
+        assert_eq!(events[1].delay, Duration::from_millis(100));
+    }
 }
@@ -0,0 +1,500 @@
+use std::env;
+
+use reqwest::Client;
+use serde::Deserialize;
+
+use super::TokenProvider;
+use crate::error::{Error, Result};
+
+/// Environment variable that overrides the tokeninfo endpoint used by [`ScopeChecker::from_env`].
+pub const TOKENINFO_ENDPOINT_ENV: &str = "NBLM_TOKENINFO_ENDPOINT";
+pub const DEFAULT_TOKENINFO_ENDPOINT: &str = "https://www.googleapis.com/oauth2/v3/tokeninfo";
+
+const DRIVE_SCOPE: &str = "https://www.googleapis.com/auth/drive";
+const DRIVE_FILE_SCOPE: &str = "https://www.googleapis.com/auth/drive.file";
+
+/// Projects of the OAuth clients gcloud mints user tokens with (`gcloud auth login` and
+/// `gcloud auth application-default login`). Their tokens say nothing about the user's project.
+const GCLOUD_CLIENT_PROJECTS: &[&str] = &["32555940559", "764086051850"];
+const OAUTH_CLIENT_SUFFIX: &str = ".apps.googleusercontent.com";
+const SERVICE_ACCOUNT_SUFFIX: &str = ".iam.gserviceaccount.com";
+
+#[derive(Debug, Default, Deserialize)]
+struct TokenInfoResponse {
+    scope: Option<String>,
+    azp: Option<String>,
+    aud: Option<String>,
+    email: Option<String>,
+}
+
+/// How the project (or account domain) an access token was issued for compares with the one it
+/// is used with; see [`ScopeChecker::check_token_project`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenProject {
+    /// The token was issued for the expected project or domain, described here.
+    Matches(String),
+    /// The token was issued for `found` rather than `expected` (e.g. `project 123`).
+    Mismatch { found: String, expected: String },
+    /// The token does not reveal a project; the reason says why.
+    Unknown(String),
+}
+
+/// Checks the scopes granted to an access token via Google's tokeninfo endpoint.
+///
+/// `new()` targets Google's endpoint; use [`ScopeChecker::with_endpoint`] to point a single
+/// checker at a mock without touching the environment.
+#[derive(Debug, Clone)]
+pub struct ScopeChecker {
+    client: Client,
+    endpoint: String,
+}
+
+impl ScopeChecker {
+    pub fn new() -> Self {
+        Self {
+            client: Client::new(),
+            endpoint: DEFAULT_TOKENINFO_ENDPOINT.to_string(),
+        }
+    }
+
+    /// Like [`ScopeChecker::new`], but honours `NBLM_TOKENINFO_ENDPOINT` when it is set.
+    pub fn from_env() -> Self {
+        match env::var(TOKENINFO_ENDPOINT_ENV)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+        {
+            Some(endpoint) => Self::new().with_endpoint(endpoint),
+            None => Self::new(),
+        }
+    }
+
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    pub fn with_http_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Fail with [`Error::TokenProvider`] unless the provider's token grants Drive access.
+    pub async fn ensure_drive_scope(&self, provider: &dyn TokenProvider) -> Result<()> {
+        let info = self.token_info(provider, "Google Drive token").await?;
+        let scopes = info.scope.unwrap_or_default();
+        if scope_grants_drive_access(&scopes) {
+            Ok(())
+        } else {
+            Err(Error::TokenProvider(
+                "Google Drive access token is missing the required drive.file scope. Run `gcloud auth login --enable-gdrive-access` and retry.".to_string(),
+            ))
+        }
+    }
+
+    /// Compare where the provider's token was issued with `project_number` and, when given, the
+    /// email domain of the account it should belong to.
+    ///
+    /// OAuth client IDs carry the number of the project that owns the client, so tokens from a
+    /// project's own client can be checked. Tokens minted through gcloud's shared clients, and
+    /// service account tokens (whose email gives the project ID, not its number), come back as
+    /// [`TokenProject::Unknown`] unless the domain settles it.
+    pub async fn check_token_project(
+        &self,
+        provider: &dyn TokenProvider,
+        project_number: &str,
+        expected_domain: Option<&str>,
+    ) -> Result<TokenProject> {
+        let info = self.token_info(provider, "token project").await?;
+        Ok(compare_token_project(
+            &info,
+            project_number,
+            expected_domain,
+        ))
+    }
+
+    /// Ask the tokeninfo endpoint about the provider's token. `what` names the check in errors.
+    async fn token_info(
+        &self,
+        provider: &dyn TokenProvider,
+        what: &str,
+    ) -> Result<TokenInfoResponse> {
+        let access_token = provider.access_token().await?;
+
+        let response = self
+            .client
+            .get(&self.endpoint)
+            .query(&[("access_token", access_token.as_str())])
+            .send()
+            .await
+            .map_err(|err| Error::TokenProvider(format!("failed to validate {what}: {err}")))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response
+                .text()
+                .await
+                .unwrap_or_else(|_| String::from("<failed to read body>"));
+            return Err(Error::TokenProvider(format!(
+                "failed to validate {what} (status {}): {}",
+                status.as_u16(),
+                body.trim()
+            )));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|err| Error::TokenProvider(format!("invalid tokeninfo response: {err}")))
+    }
+}
+
+impl Default for ScopeChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Check Drive access with [`ScopeChecker::from_env`].
+pub async fn ensure_drive_scope(provider: &dyn TokenProvider) -> Result<()> {
+    ScopeChecker::from_env().ensure_drive_scope(provider).await
+}
+
+fn compare_token_project(
+    info: &TokenInfoResponse,
+    project_number: &str,
+    expected_domain: Option<&str>,
+) -> TokenProject {
+    let email = info.email.as_deref().filter(|email| !email.is_empty());
+    let mut domain_matched = None;
+    if let (Some(expected), Some(email)) = (expected_domain, email) {
+        let domain = email.rsplit_once('@').map_or("", |(_, domain)| domain);
+        if !domain.eq_ignore_ascii_case(expected) {
+            return TokenProject::Mismatch {
+                found: format!("account {email}"),
+                expected: format!("an account in {expected}"),
+            };
+        }
+        domain_matched = Some(format!("account {email}"));
+    }
+
+    let client_project = [info.azp.as_deref(), info.aud.as_deref()]
+        .into_iter()
+        .flatten()
+        .find_map(oauth_client_project);
+    match client_project {
+        Some(found) if found == project_number => {
+            return TokenProject::Matches(format!("project {found}"));
+        }
+        Some(found) => {
+            return TokenProject::Mismatch {
+                found: format!("project {found}"),
+                expected: format!("project {project_number}"),
+            };
+        }
+        None => {}
+    }
+    if let Some(matched) = domain_matched {
+        return TokenProject::Matches(matched);
+    }
+    match email.and_then(|email| {
+        email
+            .strip_suffix(SERVICE_ACCOUNT_SUFFIX)
+            .and_then(|rest| rest.rsplit_once('@'))
+    }) {
+        Some((_, project_id)) => TokenProject::Unknown(format!(
+            "the token belongs to a service account of project ID `{project_id}`; tokeninfo does not give its project number"
+        )),
+        None => TokenProject::Unknown(
+            "the token was issued through gcloud's OAuth client, which does not name a project"
+                .to_string(),
+        ),
+    }
+}
+
+/// The project number in an OAuth client ID (`NUMBER-xyz.apps.googleusercontent.com`), unless it
+/// is one of gcloud's shared clients.
+fn oauth_client_project(client_id: &str) -> Option<&str> {
+    let (number, _) = client_id
+        .strip_suffix(OAUTH_CLIENT_SUFFIX)?
+        .split_once('-')?;
+    (!number.is_empty()
+        && number.chars().all(|c| c.is_ascii_digit())
+        && !GCLOUD_CLIENT_PROJECTS.contains(&number))
+    .then_some(number)
+}
+
+fn scope_grants_drive_access(scopes: &str) -> bool {
+    scopes
+        .split_whitespace()
+        .any(|scope| scope == DRIVE_FILE_SCOPE || scope == DRIVE_SCOPE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::StaticTokenProvider;
+    use serial_test::serial;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn expect_scope_result(scopes: &str, expected: bool) {
+        assert_eq!(scope_grants_drive_access(scopes), expected);
+    }
+
+    #[test]
+    fn scope_grants_drive_access_detects_required_scopes() {
+        expect_scope_result(DRIVE_FILE_SCOPE, true);
+        expect_scope_result(DRIVE_SCOPE, true);
+        expect_scope_result(
+            "https://www.googleapis.com/auth/spreadsheets.readonly",
+            false,
+        );
+        expect_scope_result(
+            &format!("{DRIVE_FILE_SCOPE} https://www.googleapis.com/auth/calendar"),
+            true,
+        );
+    }
+
+    fn info(azp: Option<&str>, email: Option<&str>) -> TokenInfoResponse {
+        TokenInfoResponse {
+            azp: azp.map(str::to_string),
+            aud: azp.map(str::to_string),
+            email: email.map(str::to_string),
+            ..TokenInfoResponse::default()
+        }
+    }
+
+    #[test]
+    fn token_project_matches_the_oauth_client_project() {
+        let token = info(Some("123456-abc.apps.googleusercontent.com"), None);
+        assert_eq!(
+            compare_token_project(&token, "123456", None),
+            TokenProject::Matches("project 123456".to_string())
+        );
+    }
+
+    #[test]
+    fn token_project_mismatch_names_both_projects() {
+        let token = info(
+            Some("987654-abc.apps.googleusercontent.com"),
+            Some("ada@example.com"),
+        );
+        assert_eq!(
+            compare_token_project(&token, "123456", None),
+            TokenProject::Mismatch {
+                found: "project 987654".to_string(),
+                expected: "project 123456".to_string(),
+            }
+        );
+        assert_eq!(
+            compare_token_project(&token, "987654", Some("corp.example")),
+            TokenProject::Mismatch {
+                found: "account ada@example.com".to_string(),
+                expected: "an account in corp.example".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn tokens_that_do_not_name_a_project_are_unknown() {
+        let gcloud = info(
+            Some("32555940559.apps.googleusercontent.com"),
+            Some("ada@example.com"),
+        );
+        assert!(matches!(
+            compare_token_project(&gcloud, "123456", None),
+            TokenProject::Unknown(reason) if reason.contains("gcloud's OAuth client")
+        ));
+        let adc = info(
+            Some("764086051850-6qr4p6gpi6hn506pt8ejuq83di341hur.apps.googleusercontent.com"),
+            None,
+        );
+        assert!(matches!(
+            compare_token_project(&adc, "123456", None),
+            TokenProject::Unknown(_)
+        ));
+        let service_account = info(
+            Some("104886243957452123456"),
+            Some("runner@my-project.iam.gserviceaccount.com"),
+        );
+        assert!(matches!(
+            compare_token_project(&service_account, "123456", None),
+            TokenProject::Unknown(reason) if reason.contains("project ID `my-project`")
+        ));
+        // The domain still settles it when the project cannot be read.
+        assert_eq!(
+            compare_token_project(&gcloud, "123456", Some("EXAMPLE.com")),
+            TokenProject::Matches("account ada@example.com".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn check_token_project_reads_tokeninfo() {
+        let server = tokeninfo_server(
+            "token",
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "azp": "987654-abc.apps.googleusercontent.com",
+                "aud": "987654-abc.apps.googleusercontent.com",
+                "scope": DRIVE_SCOPE
+            })),
+        )
+        .await;
+
+        let provider = StaticTokenProvider::new("token");
+        let result = checker_for(&server)
+            .check_token_project(&provider, "123456", None)
+            .await
+            .unwrap();
+        assert!(
+            matches!(result, TokenProject::Mismatch { .. }),
+            "{result:?}"
+        );
+    }
+
+    #[test]
+    fn new_targets_google_tokeninfo() {
+        assert_eq!(ScopeChecker::new().endpoint(), DEFAULT_TOKENINFO_ENDPOINT);
+        assert_eq!(
+            ScopeChecker::default().endpoint(),
+            DEFAULT_TOKENINFO_ENDPOINT
+        );
+        assert_eq!(
+            ScopeChecker::new()
+                .with_endpoint("http://127.0.0.1:9/tokeninfo")
+                .endpoint(),
+            "http://127.0.0.1:9/tokeninfo"
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn from_env_prefers_the_environment_override() {
+        let original = env::var(TOKENINFO_ENDPOINT_ENV).ok();
+
+        env::set_var(TOKENINFO_ENDPOINT_ENV, "http://127.0.0.1:9/tokeninfo");
+        assert_eq!(
+            ScopeChecker::from_env().endpoint(),
+            "http://127.0.0.1:9/tokeninfo"
+        );
+        env::set_var(TOKENINFO_ENDPOINT_ENV, "  ");
+        assert_eq!(
+            ScopeChecker::from_env().endpoint(),
+            DEFAULT_TOKENINFO_ENDPOINT
+        );
+
+        match original {
+            Some(value) => env::set_var(TOKENINFO_ENDPOINT_ENV, value),
+            None => env::remove_var(TOKENINFO_ENDPOINT_ENV),
+        }
+    }
+
+    async fn tokeninfo_server(token: &str, response: ResponseTemplate) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/oauth2/v3/tokeninfo"))
+            .and(query_param("access_token", token))
+            .respond_with(response)
+            .expect(1)
+            .mount(&server)
+            .await;
+        server
+    }
+
+    fn checker_for(server: &MockServer) -> ScopeChecker {
+        ScopeChecker::new().with_endpoint(format!("{}/oauth2/v3/tokeninfo", server.uri()))
+    }
+
+    #[tokio::test]
+    async fn ensure_drive_scope_accepts_valid_scope() {
+        let server = tokeninfo_server(
+            "valid-token",
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "scope": DRIVE_FILE_SCOPE
+            })),
+        )
+        .await;
+
+        let provider = StaticTokenProvider::new("valid-token");
+        let result = checker_for(&server).ensure_drive_scope(&provider).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn ensure_drive_scope_rejects_missing_scope() {
+        let server = tokeninfo_server(
+            "no-scope",
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "scope": "https://www.googleapis.com/auth/spreadsheets.readonly"
+            })),
+        )
+        .await;
+
+        let provider = StaticTokenProvider::new("no-scope");
+        let err = checker_for(&server)
+            .ensure_drive_scope(&provider)
+            .await
+            .unwrap_err();
+
+        match err {
+            Error::TokenProvider(message) => {
+                assert!(message.contains("drive.file scope"));
+            }
+            _ => panic!("expected TokenProvider error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn ensure_drive_scope_converts_http_failures() {
+        let server = tokeninfo_server(
+            "bad-token",
+            ResponseTemplate::new(400).set_body_string("invalid_token"),
+        )
+        .await;
+
+        let provider = StaticTokenProvider::new("bad-token");
+        let err = checker_for(&server)
+            .ensure_drive_scope(&provider)
+            .await
+            .unwrap_err();
+
+        match err {
+            Error::TokenProvider(message) => {
+                assert!(message.contains("status 400"));
+            }
+            _ => panic!("expected TokenProvider error"),
+        }
+    }
+
+    #[tokio::test]
+    async fn checkers_with_different_endpoints_are_independent() {
+        let granted = tokeninfo_server(
+            "token",
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "scope": DRIVE_SCOPE
+            })),
+        )
+        .await;
+        let denied = tokeninfo_server(
+            "token",
+            ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "scope": "https://www.googleapis.com/auth/cloud-platform"
+            })),
+        )
+        .await;
+
+        let provider = StaticTokenProvider::new("token");
+        assert!(checker_for(&granted)
+            .ensure_drive_scope(&provider)
+            .await
+            .is_ok());
+        assert!(checker_for(&denied)
+            .ensure_drive_scope(&provider)
+            .await
+            .is_err());
+    }
+}
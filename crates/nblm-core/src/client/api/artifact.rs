@@ -0,0 +1,203 @@
+use bytes::Bytes;
+use reqwest::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use reqwest::Url;
+
+use crate::client::NblmClient;
+use crate::error::{Error, Result};
+
+/// Query parameters that make a URL carry its own access grant.
+const SIGNATURE_PARAMS: &[&str] = &["x-goog-signature", "x-amz-signature", "signature"];
+
+/// A generated file, such as an audio overview, whose body is read chunk by chunk.
+pub struct ArtifactDownload {
+    response: reqwest::Response,
+}
+
+impl ArtifactDownload {
+    /// URL after redirects.
+    pub fn url(&self) -> &Url {
+        self.response.url()
+    }
+
+    /// Media type from the response, without parameters (`audio/mpeg; x=1` -> `audio/mpeg`).
+    pub fn content_type(&self) -> Option<String> {
+        let header = self.response.headers().get(CONTENT_TYPE)?.to_str().ok()?;
+        let essence = header.split(';').next()?.trim().to_ascii_lowercase();
+        (!essence.is_empty()).then_some(essence)
+    }
+
+    /// Body size announced by the server, if any.
+    pub fn content_length(&self) -> Option<u64> {
+        self.response
+            .headers()
+            .get(CONTENT_LENGTH)?
+            .to_str()
+            .ok()?
+            .parse()
+            .ok()
+    }
+
+    /// Next piece of the body, or `None` once it has been read in full.
+    pub async fn chunk(&mut self) -> Result<Option<Bytes>> {
+        self.response.chunk().await.map_err(Error::Request)
+    }
+}
+
+impl NblmClient {
+    /// Start downloading a file the API points to, such as
+    /// [`AudioOverviewResponse::audio_uri`](crate::models::enterprise::audio::AudioOverviewResponse::audio_uri).
+    ///
+    /// The request goes out with the client's credentials only when `uri` is on the API host or
+    /// another `*.googleapis.com` host and is not signed. Signed URLs and other hosts (such as
+    /// `*.googleusercontent.com`) grant access by themselves, and must not receive the token.
+    pub async fn download_artifact(&self, uri: &str) -> Result<ArtifactDownload> {
+        let url = Url::parse(uri)?;
+        if !matches!(url.scheme(), "https" | "http") {
+            return Err(Error::validation(format!(
+                "cannot download {uri}: only http(s) URLs are supported"
+            )));
+        }
+        let base_url = Url::parse(self.environment.base_url())?;
+        let authenticated = sends_credentials(&url, &base_url);
+        let response = self.http.request_stream(url, authenticated).await?;
+        Ok(ArtifactDownload { response })
+    }
+}
+
+/// Whether a download from `url` should carry the client's token (see
+/// [`NblmClient::download_artifact`]).
+fn sends_credentials(url: &Url, base_url: &Url) -> bool {
+    let signed = url
+        .query_pairs()
+        .any(|(key, _)| SIGNATURE_PARAMS.contains(&key.to_ascii_lowercase().as_str()));
+    if signed {
+        return false;
+    }
+    let same_origin = url.scheme() == base_url.scheme()
+        && url.host_str() == base_url.host_str()
+        && url.port_or_known_default() == base_url.port_or_known_default();
+    let google_api = url.scheme() == "https"
+        && url
+            .host_str()
+            .is_some_and(|host| host.to_ascii_lowercase().ends_with(".googleapis.com"));
+    same_origin || google_api
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::StaticTokenProvider;
+    use crate::env::EnvironmentConfig;
+    use std::sync::Arc;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn client(server: &MockServer) -> NblmClient {
+        let provider = Arc::new(StaticTokenProvider::new("test-token"));
+        let env = EnvironmentConfig::enterprise("123", "global", "global").unwrap();
+        NblmClient::new(provider, env)
+            .unwrap()
+            .with_base_url(format!("{}/v1alpha", server.uri()))
+            .unwrap()
+    }
+
+    #[rstest::rstest]
+    #[case::api_host("https://us-discoveryengine.googleapis.com/v1alpha/media/a", true)]
+    #[case::other_google_api("https://storage.googleapis.com/bucket/a.mp3", true)]
+    #[case::signed_v4(
+        "https://storage.googleapis.com/bucket/a.mp3?X-Goog-Algorithm=x&X-Goog-Signature=abc",
+        false
+    )]
+    #[case::signed_v2(
+        "https://storage.googleapis.com/bucket/a.mp3?Expires=1&Signature=abc",
+        false
+    )]
+    #[case::user_content("https://lh3.googleusercontent.com/notebooklm/a", false)]
+    #[case::lookalike("https://googleapis.com.example.net/a", false)]
+    #[case::plain_http_google("http://storage.googleapis.com/bucket/a.mp3", false)]
+    #[test]
+    fn credentials_go_only_to_google_api_hosts(#[case] url: &str, #[case] expected: bool) {
+        let base = Url::parse("https://us-discoveryengine.googleapis.com/v1alpha").unwrap();
+        assert_eq!(
+            sends_credentials(&Url::parse(url).unwrap(), &base),
+            expected,
+            "{url}"
+        );
+    }
+
+    #[tokio::test]
+    async fn downloads_from_the_api_host_with_the_token() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/media/audio.mp3"))
+            .and(header("authorization", "Bearer test-token"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw(b"ID3 audio".to_vec(), "Audio/MPEG"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut download = client(&server)
+            .download_artifact(&format!("{}/media/audio.mp3", server.uri()))
+            .await
+            .unwrap();
+
+        assert_eq!(download.content_type().as_deref(), Some("audio/mpeg"));
+        assert_eq!(download.content_length(), Some(9));
+        let mut body = Vec::new();
+        while let Some(chunk) = download.chunk().await.unwrap() {
+            body.extend_from_slice(&chunk);
+        }
+        assert_eq!(body, b"ID3 audio");
+    }
+
+    #[tokio::test]
+    async fn signed_urls_are_fetched_without_the_token() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/bucket/audio.mp3"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"audio".to_vec()))
+            .mount(&server)
+            .await;
+
+        client(&server)
+            .download_artifact(&format!(
+                "{}/bucket/audio.mp3?X-Goog-Signature=abc",
+                server.uri()
+            ))
+            .await
+            .unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(!requests[0].headers.contains_key("authorization"));
+        assert!(!requests[0].headers.contains_key("x-goog-user-project"));
+    }
+
+    #[tokio::test]
+    async fn error_statuses_and_other_schemes_fail() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+                "error": {"code": 404, "message": "Audio not found"}
+            })))
+            .mount(&server)
+            .await;
+        let client = client(&server);
+
+        let err = client
+            .download_artifact(&format!("{}/media/gone.mp3", server.uri()))
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("Audio not found"), "{err}");
+
+        let err = client
+            .download_artifact("file:///etc/passwd")
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::Validation(_)), "{err}");
+    }
+}
@@ -6,7 +6,8 @@ use crate::error::Result;
 use crate::models::enterprise::audio::{AudioOverviewRequest, AudioOverviewResponse};
 
 use super::models::{
-    requests::audio as wire_audio_req, responses::audio::AudioOverviewApiResponse,
+    requests::audio as wire_audio_req,
+    responses::audio::{AudioOverviewApiResponse, AudioOverviewGetResponse},
 };
 
 pub(crate) struct EnterpriseAudioBackend {
@@ -42,6 +43,20 @@ impl AudioBackend for EnterpriseAudioBackend {
         Ok(api_response.audio_overview.into())
     }
 
+    async fn get_audio_overview(&self, notebook_id: &str) -> Result<AudioOverviewResponse> {
+        let path = format!(
+            "{}/audioOverviews/default",
+            self.ctx.url_builder.notebook_path(notebook_id)
+        );
+        let url = self.ctx.url_builder.build_url(&path)?;
+        let api_response: AudioOverviewGetResponse = self
+            .ctx
+            .http
+            .request_json(Method::GET, url, None::<&()>)
+            .await?;
+        Ok(api_response.into_inner().into())
+    }
+
     async fn delete_audio_overview(&self, notebook_id: &str) -> Result<()> {
         let path = format!(
             "{}/audioOverviews/default",
@@ -77,11 +92,7 @@ mod tests {
         let token = Arc::new(StaticTokenProvider::new("token"));
         let retryer = Retryer::new(RetryConfig::default());
         let http = Arc::new(HttpClient::new(client, token, retryer, None));
-        let url_builder = new_url_builder(
-            env.profile(),
-            env.base_url().to_string(),
-            env.parent_path().to_string(),
-        );
+        let url_builder = new_url_builder(&env);
         let ctx = BackendContext::new(http, url_builder);
         EnterpriseAudioBackend::new(ctx)
     }
@@ -1,11 +1,18 @@
 use crate::models::enterprise::{
-    audio as domain_audio, notebook as domain_notebook, source as domain_source,
+    audio as domain_audio, notebook as domain_notebook, query as domain_query,
+    source as domain_source,
 };
 
 use super::models::{
     notebook as wire_notebook,
-    requests::{audio as wire_audio_req, notebook as wire_notebook_req, source as wire_source_req},
-    responses::{audio as wire_audio_resp, list as wire_list_resp, source as wire_source_resp},
+    requests::{
+        audio as wire_audio_req, notebook as wire_notebook_req, query as wire_query_req,
+        source as wire_source_req,
+    },
+    responses::{
+        audio as wire_audio_resp, list as wire_list_resp, query as wire_query_resp,
+        source as wire_source_resp,
+    },
     source as wire_source,
 };
 
@@ -29,6 +36,56 @@ impl From<domain_audio::AudioOverviewRequest> for wire_audio_req::AudioOverviewR
     }
 }
 
+// ---------- Query ----------
+
+impl From<domain_query::QueryRequest> for wire_query_req::QueryRequest {
+    fn from(value: domain_query::QueryRequest) -> Self {
+        Self {
+            query: value.query,
+            source_ids: value.source_ids.map(|ids| {
+                ids.into_iter()
+                    .map(|id| wire_source::NotebookSourceId {
+                        id: Some(id),
+                        extra: Default::default(),
+                    })
+                    .collect()
+            }),
+        }
+    }
+}
+
+impl From<wire_query_resp::QueryResponse> for domain_query::QueryResponse {
+    fn from(value: wire_query_resp::QueryResponse) -> Self {
+        let mut extra = value.extra;
+        let mut citations = value.citations;
+        let answer = match value.answer {
+            Some(wire_query_resp::QueryAnswer::Text(text)) => Some(text),
+            Some(wire_query_resp::QueryAnswer::Structured(answer)) => {
+                citations.extend(answer.citations);
+                extra.extend(answer.extra);
+                answer.answer_text
+            }
+            None => None,
+        };
+        Self {
+            answer: answer.or(value.answer_text),
+            citations: citations.into_iter().map(Into::into).collect(),
+            extra,
+        }
+    }
+}
+
+impl From<wire_query_resp::Citation> for domain_query::Citation {
+    fn from(value: wire_query_resp::Citation) -> Self {
+        Self {
+            source_id: value.source_id.map(Into::into),
+            title: value.title,
+            text: value.text,
+            extra: value.extra,
+        }
+    }
+}
+
 // ---------- Notebook ----------
 
 impl From<wire_notebook::Notebook> for domain_notebook::Notebook {
@@ -38,6 +95,7 @@ impl From<wire_notebook::Notebook> for domain_notebook::Notebook {
             title: value.title,
             notebook_id: value.notebook_id,
             emoji: value.emoji,
+            summary: value.summary,
             sources: value.sources.into_iter().map(Into::into).collect(),
             metadata: value.metadata.map(Into::into),
             extra: value.extra,
@@ -62,6 +120,7 @@ impl From<wire_notebook::NotebookRef> for domain_notebook::NotebookRef {
         Self {
             notebook_id: value.notebook_id,
             name: value.name,
+            title: String::new(),
         }
     }
 }
@@ -76,6 +135,7 @@ impl From<wire_list_resp::ListRecentlyViewedResponse>
                 .into_iter()
                 .map(domain_notebook::Notebook::from)
                 .collect(),
+            next_page_token: value.next_page_token,
         }
     }
 }
@@ -198,6 +258,7 @@ impl From<wire_source::WebContent> for domain_source::WebContent {
         Self {
             url: value.url,
             source_name: value.source_name,
+            fetch_options: value.fetch_options,
         }
     }
 }
@@ -255,6 +316,7 @@ impl From<domain_source::WebContent> for wire_source::WebContent {
         Self {
             url: value.url,
             source_name: value.source_name,
+            fetch_options: value.fetch_options,
         }
     }
 }
@@ -329,6 +391,8 @@ impl From<wire_source_resp::UploadSourceFileResponse> for domain_source::UploadS
     fn from(value: wire_source_resp::UploadSourceFileResponse) -> Self {
         Self {
             source_id: value.source_id.map(domain_source::NotebookSourceId::from),
+            name: value.name,
+            state: value.state,
             extra: value.extra,
         }
     }
@@ -338,6 +402,8 @@ impl From<domain_source::UploadSourceFileResponse> for wire_source_resp::UploadS
     fn from(value: domain_source::UploadSourceFileResponse) -> Self {
         Self {
             source_id: value.source_id.map(wire_source::NotebookSourceId::from),
+            name: value.name,
+            state: value.state,
             extra: value.extra,
         }
     }
@@ -401,6 +467,7 @@ mod tests {
             web_content: domain_source::WebContent {
                 url: "https://example.com".to_string(),
                 source_name: Some("Example".to_string()),
+                fetch_options: Some(serde_json::json!({"waitForRender": true})),
             },
         };
 
@@ -414,6 +481,7 @@ mod tests {
             ) => {
                 assert_eq!(d.url, b.url);
                 assert_eq!(d.source_name, b.source_name);
+                assert_eq!(d.fetch_options, b.fetch_options);
             }
             _ => panic!("UserContent variant mismatch"),
         }
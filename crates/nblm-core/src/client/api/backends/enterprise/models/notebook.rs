@@ -8,11 +8,24 @@ use super::source::NotebookSource;
 #[serde(rename_all = "camelCase")]
 pub struct Notebook {
     pub name: Option<String>,
+    #[serde(default)]
     pub title: String,
-    #[serde(rename = "notebookId", skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename = "notebookId",
+        alias = "notebook_id",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub notebook_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub emoji: Option<String>,
+    /// Generated summary of the notebook's sources, when the API includes one.
+    #[serde(
+        alias = "description",
+        alias = "notebook_summary",
+        alias = "notebookSummary",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub summary: Option<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub sources: Vec<NotebookSource>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -31,13 +44,13 @@ pub struct NotebookRef {
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct NotebookMetadata {
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "create_time", skip_serializing_if = "Option::is_none")]
     pub create_time: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "is_shareable", skip_serializing_if = "Option::is_none")]
     pub is_shareable: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "is_shared", skip_serializing_if = "Option::is_none")]
     pub is_shared: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "last_viewed", skip_serializing_if = "Option::is_none")]
     pub last_viewed: Option<String>,
     #[serde(flatten)]
     pub extra: HashMap<String, serde_json::Value>,
@@ -54,6 +67,7 @@ mod tests {
             title: "Test Notebook".to_string(),
             notebook_id: None,
             emoji: None,
+            summary: None,
             metadata: None,
             sources: Vec::new(),
             extra: Default::default(),
@@ -69,6 +83,7 @@ mod tests {
             title: "Test Notebook".to_string(),
             notebook_id: Some("nb123".to_string()),
             emoji: None,
+            summary: None,
             metadata: None,
             sources: Vec::new(),
             extra: Default::default(),
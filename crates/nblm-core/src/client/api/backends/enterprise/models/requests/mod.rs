@@ -1,3 +1,4 @@
 pub mod audio;
 pub mod notebook;
+pub mod query;
 pub mod source;
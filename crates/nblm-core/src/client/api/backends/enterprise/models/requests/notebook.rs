@@ -6,6 +6,31 @@ use serde::{Deserialize, Serialize};
 #[serde(rename_all = "camelCase")]
 pub struct CreateNotebookRequest {
     pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<String>,
+}
+
+/// Body of `PATCH notebooks/{id}`; only the provided fields are sent and listed in the mask.
+#[derive(Debug, Clone, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateNotebookRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<String>,
+}
+
+impl UpdateNotebookRequest {
+    pub fn update_mask(&self) -> String {
+        let mut fields = Vec::new();
+        if self.title.is_some() {
+            fields.push("title");
+        }
+        if self.emoji.is_some() {
+            fields.push("emoji");
+        }
+        fields.join(",")
+    }
 }
 
 /// Batch delete notebooks request.
@@ -37,9 +62,30 @@ mod tests {
     fn create_notebook_request_serializes_correctly() {
         let request = CreateNotebookRequest {
             title: "Test Notebook".to_string(),
+            emoji: None,
         };
         let json = serde_json::to_string(&request).unwrap();
         assert!(json.contains(r#""title":"Test Notebook""#));
+        assert!(!json.contains("emoji"));
+    }
+
+    #[test]
+    fn update_notebook_request_mask_lists_provided_fields() {
+        let title_only = UpdateNotebookRequest {
+            title: Some("Renamed".to_string()),
+            emoji: None,
+        };
+        assert_eq!(title_only.update_mask(), "title");
+        assert_eq!(
+            serde_json::to_value(&title_only).unwrap(),
+            serde_json::json!({"title": "Renamed"})
+        );
+
+        let both = UpdateNotebookRequest {
+            title: Some("Renamed".to_string()),
+            emoji: Some("📘".to_string()),
+        };
+        assert_eq!(both.update_mask(), "title,emoji");
     }
 
     #[test]
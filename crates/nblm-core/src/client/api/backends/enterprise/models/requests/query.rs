@@ -0,0 +1,41 @@
+use serde::Serialize;
+
+use crate::client::api::backends::enterprise::models::source::NotebookSourceId;
+
+/// Body of `notebooks/{id}:query`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryRequest {
+    pub query: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_ids: Option<Vec<NotebookSourceId>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_request_sends_source_ids_as_objects() {
+        let request = QueryRequest {
+            query: "Why?".to_string(),
+            source_ids: Some(vec![NotebookSourceId {
+                id: Some("s1".to_string()),
+                extra: Default::default(),
+            }]),
+        };
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({ "query": "Why?", "sourceIds": [{ "id": "s1" }] })
+        );
+
+        let request = QueryRequest {
+            query: "Why?".to_string(),
+            source_ids: None,
+        };
+        assert_eq!(
+            serde_json::to_string(&request).unwrap(),
+            r#"{"query":"Why?"}"#
+        );
+    }
+}
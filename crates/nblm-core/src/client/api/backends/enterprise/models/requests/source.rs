@@ -36,6 +36,7 @@ mod tests {
                     web_content: WebContent {
                         url: "https://example.com".to_string(),
                         source_name: None,
+                        fetch_options: None,
                     },
                 },
                 UserContent::Text {
@@ -50,6 +51,39 @@ mod tests {
         assert!(json.contains("userContents"));
         assert!(json.contains("https://example.com"));
         assert!(json.contains("Sample text"));
+        assert!(!json.contains("fetchOptions"));
+    }
+
+    #[test]
+    fn batch_create_sources_request_sends_fetch_options_verbatim() {
+        let request = BatchCreateSourcesRequest {
+            user_contents: vec![UserContent::Web {
+                web_content: WebContent {
+                    url: "https://example.com".to_string(),
+                    source_name: None,
+                    fetch_options: Some(serde_json::json!({
+                        "userAgent": "Mozilla/5.0",
+                        "waitForRender": true,
+                        "someFutureOption": 3
+                    })),
+                },
+            }],
+        };
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "userContents": [{
+                    "webContent": {
+                        "url": "https://example.com",
+                        "fetchOptions": {
+                            "userAgent": "Mozilla/5.0",
+                            "waitForRender": true,
+                            "someFutureOption": 3
+                        }
+                    }
+                }]
+            })
+        );
     }
 
     #[test]
@@ -6,19 +6,37 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct AudioOverviewApiResponse {
+    #[serde(alias = "audio_overview")]
     pub audio_overview: AudioOverviewResponse,
 }
 
+/// Body of a GET on the audio overview: the resource itself, or wrapped like the create response.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum AudioOverviewGetResponse {
+    Wrapped(AudioOverviewApiResponse),
+    Bare(AudioOverviewResponse),
+}
+
+impl AudioOverviewGetResponse {
+    pub fn into_inner(self) -> AudioOverviewResponse {
+        match self {
+            Self::Wrapped(wrapped) => wrapped.audio_overview,
+            Self::Bare(bare) => bare,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct AudioOverviewResponse {
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "audio_overview_id", skip_serializing_if = "Option::is_none")]
     pub audio_overview_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub status: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "generation_options", skip_serializing_if = "Option::is_none")]
     pub generation_options: Option<serde_json::Value>,
     #[serde(flatten)]
     pub extra: HashMap<String, serde_json::Value>,
@@ -90,6 +108,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn get_response_accepts_bare_and_wrapped_bodies() {
+        let bare: AudioOverviewGetResponse =
+            serde_json::from_str(r#"{"audioOverviewId": "a1", "status": "S"}"#).unwrap();
+        let wrapped: AudioOverviewGetResponse =
+            serde_json::from_str(r#"{"audioOverview": {"audioOverviewId": "a1", "status": "S"}}"#)
+                .unwrap();
+        for response in [bare, wrapped] {
+            let response = response.into_inner();
+            assert_eq!(response.audio_overview_id.as_deref(), Some("a1"));
+            assert_eq!(response.status.as_deref(), Some("S"));
+            assert!(response.extra.is_empty());
+        }
+    }
+
     #[test]
     fn audio_overview_api_response_deserializes_correctly() {
         let json = r#"{
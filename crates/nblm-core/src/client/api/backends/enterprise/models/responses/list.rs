@@ -8,6 +8,8 @@ use super::super::notebook::Notebook;
 pub struct ListRecentlyViewedResponse {
     #[serde(default)]
     pub notebooks: Vec<Notebook>,
+    #[serde(alias = "next_page_token", skip_serializing_if = "Option::is_none")]
+    pub next_page_token: Option<String>,
 }
 
 #[cfg(test)]
@@ -43,9 +45,18 @@ mod tests {
                 title: "Test Notebook".to_string(),
                 ..Default::default()
             }],
+            next_page_token: None,
         };
         let json = serde_json::to_string(&response).unwrap();
         assert!(json.contains("notebooks"));
         assert!(json.contains("Test Notebook"));
+        assert!(!json.contains("nextPageToken"));
+    }
+
+    #[test]
+    fn list_recently_viewed_response_reads_next_page_token() {
+        let json = r#"{"notebooks": [], "nextPageToken": "page-2"}"#;
+        let response: ListRecentlyViewedResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.next_page_token.as_deref(), Some("page-2"));
     }
 }
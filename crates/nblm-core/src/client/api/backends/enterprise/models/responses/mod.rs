@@ -1,3 +1,4 @@
 pub mod audio;
 pub mod list;
+pub mod query;
 pub mod source;
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::client::api::backends::enterprise::models::source::NotebookSourceId;
+
+/// Response of `notebooks/{id}:query`.
+///
+/// `answer` is either the text itself or an object holding it next to the citations; both shapes
+/// are accepted.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryResponse {
+    #[serde(default)]
+    pub answer: Option<QueryAnswer>,
+    #[serde(default, alias = "answer_text")]
+    pub answer_text: Option<String>,
+    #[serde(default)]
+    pub citations: Vec<Citation>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum QueryAnswer {
+    Text(String),
+    Structured(StructuredAnswer),
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct StructuredAnswer {
+    #[serde(default, alias = "answer_text", alias = "text")]
+    pub answer_text: Option<String>,
+    #[serde(default)]
+    pub citations: Vec<Citation>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Citation {
+    #[serde(default, alias = "source_id")]
+    pub source_id: Option<NotebookSourceId>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default, alias = "snippet")]
+    pub text: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answer_may_be_text_or_an_object() {
+        let flat: QueryResponse = serde_json::from_str(
+            r#"{"answer": "Yes.", "citations": [{"sourceId": {"id": "s1"}, "snippet": "..."}]}"#,
+        )
+        .unwrap();
+        assert!(matches!(flat.answer, Some(QueryAnswer::Text(ref text)) if text == "Yes."));
+        assert_eq!(flat.citations[0].text.as_deref(), Some("..."));
+
+        let nested: QueryResponse = serde_json::from_str(
+            r#"{"answer": {"answerText": "Yes.", "citations": [{"title": "Doc"}], "state": "SUCCEEDED"}}"#,
+        )
+        .unwrap();
+        let Some(QueryAnswer::Structured(answer)) = nested.answer else {
+            panic!("expected a structured answer");
+        };
+        assert_eq!(answer.answer_text.as_deref(), Some("Yes."));
+        assert_eq!(answer.citations[0].title.as_deref(), Some("Doc"));
+        assert!(answer.extra.contains_key("state"));
+    }
+}
@@ -9,15 +9,21 @@ use super::super::source::{NotebookSource, NotebookSourceId};
 pub struct BatchCreateSourcesResponse {
     #[serde(default)]
     pub sources: Vec<NotebookSource>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "error_count", skip_serializing_if = "Option::is_none")]
     pub error_count: Option<i32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct UploadSourceFileResponse {
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "source_id", skip_serializing_if = "Option::is_none")]
     pub source_id: Option<NotebookSourceId>,
+    /// Full resource name of the created source.
+    #[serde(alias = "sourceName", skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Ingestion state of the created source.
+    #[serde(alias = "ingestionState", skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
     #[serde(flatten)]
     pub extra: HashMap<String, serde_json::Value>,
 }
@@ -71,4 +77,48 @@ mod tests {
             Some("abc123")
         );
     }
+
+    #[test]
+    fn upload_source_file_response_reads_name_and_state() {
+        let json = r#"{
+            "sourceId": {"id": "source-id"},
+            "name": "projects/123/locations/global/notebooks/abc/sources/source-id",
+            "state": "SOURCE_STATE_PENDING",
+            "requestId": "abc123"
+        }"#;
+        let response: UploadSourceFileResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            response.name.as_deref(),
+            Some("projects/123/locations/global/notebooks/abc/sources/source-id")
+        );
+        assert_eq!(response.state.as_deref(), Some("SOURCE_STATE_PENDING"));
+        assert!(!response.extra.contains_key("name"));
+        assert!(response.extra.contains_key("requestId"));
+    }
+
+    #[test]
+    fn upload_source_file_response_accepts_field_aliases() {
+        let json = r#"{
+            "sourceName": "projects/123/locations/global/notebooks/abc/sources/x",
+            "ingestionState": "SOURCE_STATE_COMPLETE"
+        }"#;
+        let response: UploadSourceFileResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            response.name.as_deref(),
+            Some("projects/123/locations/global/notebooks/abc/sources/x")
+        );
+        assert_eq!(response.state.as_deref(), Some("SOURCE_STATE_COMPLETE"));
+        assert!(response.extra.is_empty());
+    }
+
+    #[test]
+    fn upload_source_file_response_without_new_fields() {
+        let json = r#"{"sourceId": {"id": "source-id"}}"#;
+        let response: UploadSourceFileResponse = serde_json::from_str(json).unwrap();
+        assert!(response.name.is_none());
+        assert!(response.state.is_none());
+
+        let value = serde_json::to_value(&response).unwrap();
+        assert_eq!(value, serde_json::json!({"sourceId": {"id": "source-id"}}));
+    }
 }
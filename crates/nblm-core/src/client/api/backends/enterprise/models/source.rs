@@ -10,7 +10,7 @@ pub struct NotebookSource {
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub settings: Option<NotebookSourceSettings>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "source_id", skip_serializing_if = "Option::is_none")]
     pub source_id: Option<NotebookSourceId>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
@@ -21,11 +21,14 @@ pub struct NotebookSource {
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct NotebookSourceMetadata {
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        alias = "source_added_timestamp",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub source_added_timestamp: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "word_count", skip_serializing_if = "Option::is_none")]
     pub word_count: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "youtube_metadata", skip_serializing_if = "Option::is_none")]
     pub youtube_metadata: Option<NotebookSourceYoutubeMetadata>,
     #[serde(flatten)]
     pub extra: HashMap<String, serde_json::Value>,
@@ -34,9 +37,9 @@ pub struct NotebookSourceMetadata {
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct NotebookSourceYoutubeMetadata {
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "channel_name", skip_serializing_if = "Option::is_none")]
     pub channel_name: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "video_id", skip_serializing_if = "Option::is_none")]
     pub video_id: Option<String>,
     #[serde(flatten)]
     pub extra: HashMap<String, serde_json::Value>,
@@ -87,6 +90,8 @@ pub struct WebContent {
     pub url: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub source_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fetch_options: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
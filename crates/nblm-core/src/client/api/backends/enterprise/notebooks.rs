@@ -1,15 +1,19 @@
 use async_trait::async_trait;
-use reqwest::Method;
+use reqwest::{Method, StatusCode};
 
 use crate::client::api::backends::{BackendContext, NotebooksBackend};
-use crate::error::Result;
+use crate::client::stream::JsonArrayStream;
+use crate::client::ResponseEnvelope;
+use crate::error::{Error, Result};
 use crate::models::enterprise::notebook::{
     BatchDeleteNotebooksRequest, BatchDeleteNotebooksResponse, ListRecentlyViewedResponse, Notebook,
 };
+use crate::models::enterprise::query::{QueryRequest, QueryResponse};
 
 use super::models::{
-    notebook as wire_notebook, requests::notebook as wire_notebook_req,
-    responses::list as wire_list_resp,
+    notebook as wire_notebook,
+    requests::{notebook as wire_notebook_req, query as wire_query_req},
+    responses::{list as wire_list_resp, query as wire_query_resp},
 };
 
 pub(crate) struct EnterpriseNotebooksBackend {
@@ -35,6 +39,36 @@ impl EnterpriseNotebooksBackend {
             .request_json(Method::POST, url, Some(&request))
             .await
     }
+
+    fn list_recently_viewed_url(
+        &self,
+        page_size: Option<u32>,
+        page_token: Option<&str>,
+        field_mask: Option<&str>,
+    ) -> Result<url::Url> {
+        let path = format!(
+            "{}:listRecentlyViewed",
+            self.ctx.url_builder.notebooks_collection()
+        );
+        let mut url = self.ctx.url_builder.build_url(&path)?;
+        if let Some(size) = page_size {
+            let clamped = size.clamp(PAGE_SIZE_MIN, PAGE_SIZE_MAX);
+            url.query_pairs_mut()
+                .append_pair("pageSize", &clamped.to_string());
+        }
+        if let Some(token) = page_token {
+            url.query_pairs_mut().append_pair("pageToken", token);
+        }
+        append_field_mask(&mut url, field_mask);
+        Ok(url)
+    }
+}
+
+/// Ask for a partial response with the standard `fields` system parameter.
+fn append_field_mask(url: &mut url::Url, field_mask: Option<&str>) {
+    if let Some(mask) = field_mask {
+        url.query_pairs_mut().append_pair("fields", mask);
+    }
 }
 
 const PAGE_SIZE_MIN: u32 = 1;
@@ -42,12 +76,12 @@ const PAGE_SIZE_MAX: u32 = 500;
 
 #[async_trait]
 impl NotebooksBackend for EnterpriseNotebooksBackend {
-    async fn create_notebook(&self, title: String) -> Result<Notebook> {
+    async fn create_notebook(&self, title: String, emoji: Option<String>) -> Result<Notebook> {
         let url = self
             .ctx
             .url_builder
             .build_url(&self.ctx.url_builder.notebooks_collection())?;
-        let request = wire_notebook_req::CreateNotebookRequest { title };
+        let request = wire_notebook_req::CreateNotebookRequest { title, emoji };
         let notebook: wire_notebook::Notebook = self
             .ctx
             .http
@@ -56,6 +90,52 @@ impl NotebooksBackend for EnterpriseNotebooksBackend {
         Ok(notebook.into())
     }
 
+    async fn get_notebook(&self, notebook_id: &str, field_mask: Option<&str>) -> Result<Notebook> {
+        if notebook_id.trim().is_empty() {
+            return Err(Error::validation("notebook_id cannot be empty"));
+        }
+        let mut url = self
+            .ctx
+            .url_builder
+            .build_url(&self.ctx.url_builder.notebook_path(notebook_id))?;
+        append_field_mask(&mut url, field_mask);
+        let notebook: wire_notebook::Notebook = self
+            .ctx
+            .http
+            .request_json::<(), _>(Method::GET, url, None::<&()>)
+            .await?;
+        Ok(notebook.into())
+    }
+
+    async fn update_notebook(
+        &self,
+        notebook_id: &str,
+        title: Option<String>,
+        emoji: Option<String>,
+    ) -> Result<Notebook> {
+        if notebook_id.trim().is_empty() {
+            return Err(Error::validation("notebook_id cannot be empty"));
+        }
+        if title.is_none() && emoji.is_none() {
+            return Err(Error::validation(
+                "at least one of title or emoji must be provided",
+            ));
+        }
+        let request = wire_notebook_req::UpdateNotebookRequest { title, emoji };
+        let mut url = self
+            .ctx
+            .url_builder
+            .build_url(&self.ctx.url_builder.notebook_path(notebook_id))?;
+        url.query_pairs_mut()
+            .append_pair("updateMask", &request.update_mask());
+        let notebook: wire_notebook::Notebook = self
+            .ctx
+            .http
+            .request_json(Method::PATCH, url, Some(&request))
+            .await?;
+        Ok(notebook.into())
+    }
+
     async fn batch_delete_notebooks(
         &self,
         request: BatchDeleteNotebooksRequest,
@@ -81,23 +161,57 @@ impl NotebooksBackend for EnterpriseNotebooksBackend {
     async fn list_recently_viewed(
         &self,
         page_size: Option<u32>,
-    ) -> Result<ListRecentlyViewedResponse> {
-        let path = format!(
-            "{}:listRecentlyViewed",
-            self.ctx.url_builder.notebooks_collection()
-        );
-        let mut url = self.ctx.url_builder.build_url(&path)?;
-        if let Some(size) = page_size {
-            let clamped = size.clamp(PAGE_SIZE_MIN, PAGE_SIZE_MAX);
-            url.query_pairs_mut()
-                .append_pair("pageSize", &clamped.to_string());
-        }
-        let response: wire_list_resp::ListRecentlyViewedResponse = self
+        page_token: Option<&str>,
+        field_mask: Option<&str>,
+    ) -> Result<ResponseEnvelope<ListRecentlyViewedResponse>> {
+        let url = self.list_recently_viewed_url(page_size, page_token, field_mask)?;
+        let response: ResponseEnvelope<wire_list_resp::ListRecentlyViewedResponse> = self
             .ctx
             .http
-            .request_json::<(), _>(Method::GET, url, None::<&()>)
+            .request_json_raw::<(), _>(Method::GET, url, None::<&()>)
             .await?;
-        Ok(response.into())
+        Ok(response.map(Into::into))
+    }
+
+    async fn stream_recently_viewed_page(
+        &self,
+        page_size: Option<u32>,
+        page_token: Option<&str>,
+    ) -> Result<JsonArrayStream<Notebook>> {
+        let url = self.list_recently_viewed_url(page_size, page_token, None)?;
+        self.ctx
+            .http
+            .request_json_stream::<wire_notebook::Notebook, _>(Method::GET, url, "notebooks")
+            .await
+    }
+
+    async fn query_notebook(
+        &self,
+        notebook_id: &str,
+        request: QueryRequest,
+    ) -> Result<QueryResponse> {
+        if request.query.trim().is_empty() {
+            return Err(Error::validation("query cannot be empty"));
+        }
+        let path = format!("{}:query", self.ctx.url_builder.notebook_path(notebook_id));
+        let url = self.ctx.url_builder.build_url(&path)?;
+        let request = wire_query_req::QueryRequest::from(request);
+        let response: Result<wire_query_resp::QueryResponse> = self
+            .ctx
+            .http
+            .request_json(Method::POST, url, Some(&request))
+            .await;
+        match response {
+            Ok(response) => Ok(response.into()),
+            // Not every project or profile exposes the endpoint yet.
+            Err(Error::Http {
+                status, message, ..
+            }) if status == StatusCode::NOT_FOUND => Err(Error::Unavailable {
+                feature: "notebook query",
+                detail: format!("HTTP 404: {message}"),
+            }),
+            Err(err) => Err(err),
+        }
     }
 }
 
@@ -121,11 +235,7 @@ mod tests {
         let token = Arc::new(StaticTokenProvider::new("token"));
         let retryer = Retryer::new(RetryConfig::default());
         let http = Arc::new(HttpClient::new(client, token, retryer, None));
-        let url_builder = new_url_builder(
-            env.profile(),
-            env.base_url().to_string(),
-            env.parent_path().to_string(),
-        );
+        let url_builder = new_url_builder(&env);
         let ctx = BackendContext::new(http, url_builder);
         EnterpriseNotebooksBackend::new(ctx)
     }
@@ -137,6 +247,20 @@ mod tests {
         assert_eq!(collection, "projects/123/locations/global/notebooks");
     }
 
+    #[tokio::test]
+    async fn update_notebook_requires_a_field() {
+        let backend = create_test_backend();
+        let err = backend.update_notebook("nb", None, None).await.unwrap_err();
+        assert!(matches!(err, Error::Validation(_)));
+    }
+
+    #[tokio::test]
+    async fn get_notebook_validates_empty_notebook_id() {
+        let backend = create_test_backend();
+        let err = backend.get_notebook("  ", None).await.unwrap_err();
+        assert!(matches!(err, Error::Validation(_)));
+    }
+
     #[test]
     fn batch_delete_url_construction() {
         let backend = create_test_backend();
@@ -200,4 +324,18 @@ mod tests {
             .append_pair("pageSize", &clamped.to_string());
         assert!(url.as_str().contains("pageSize=50"));
     }
+
+    #[test]
+    fn list_recently_viewed_url_appends_field_mask() {
+        let backend = create_test_backend();
+        let url = backend
+            .list_recently_viewed_url(Some(10), Some("tok"), Some("notebooks(title)"))
+            .unwrap();
+        assert_eq!(
+            url.query(),
+            Some("pageSize=10&pageToken=tok&fields=notebooks%28title%29")
+        );
+        let url = backend.list_recently_viewed_url(None, None, None).unwrap();
+        assert_eq!(url.query(), None);
+    }
 }
@@ -6,6 +6,7 @@ use reqwest::{
 };
 
 use crate::client::api::backends::{BackendContext, SourcesBackend};
+use crate::client::ResponseEnvelope;
 use crate::error::{Error, Result};
 use crate::models::enterprise::source::{
     BatchCreateSourcesRequest, BatchCreateSourcesResponse, BatchDeleteSourcesRequest,
@@ -30,7 +31,7 @@ impl EnterpriseSourcesBackend {
         &self,
         notebook_id: &str,
         request: wire_source_req::BatchCreateSourcesRequest,
-    ) -> Result<wire_source_resp::BatchCreateSourcesResponse> {
+    ) -> Result<ResponseEnvelope<wire_source_resp::BatchCreateSourcesResponse>> {
         let path = format!(
             "{}/sources:batchCreate",
             self.ctx.url_builder.notebook_path(notebook_id)
@@ -38,7 +39,7 @@ impl EnterpriseSourcesBackend {
         let url = self.ctx.url_builder.build_url(&path)?;
         self.ctx
             .http
-            .request_json(Method::POST, url, Some(&request))
+            .request_json_raw(Method::POST, url, Some(&request))
             .await
     }
 
@@ -70,14 +71,14 @@ impl SourcesBackend for EnterpriseSourcesBackend {
         let response = self
             .batch_create_internal(notebook_id, wire_request)
             .await?;
-        Ok(response.into())
+        Ok(response.into_inner().into())
     }
 
     async fn add_sources(
         &self,
         notebook_id: &str,
         contents: Vec<UserContent>,
-    ) -> Result<BatchCreateSourcesResponse> {
+    ) -> Result<ResponseEnvelope<BatchCreateSourcesResponse>> {
         let wire_request = wire_source_req::BatchCreateSourcesRequest {
             user_contents: contents
                 .into_iter()
@@ -87,7 +88,7 @@ impl SourcesBackend for EnterpriseSourcesBackend {
         let response = self
             .batch_create_internal(notebook_id, wire_request)
             .await?;
-        Ok(response.into())
+        Ok(response.map(Into::into))
     }
 
     async fn batch_delete_sources(
@@ -161,7 +162,11 @@ impl SourcesBackend for EnterpriseSourcesBackend {
         Ok(response.into())
     }
 
-    async fn get_source(&self, notebook_id: &str, source_id: &str) -> Result<NotebookSource> {
+    async fn get_source(
+        &self,
+        notebook_id: &str,
+        source_id: &str,
+    ) -> Result<ResponseEnvelope<NotebookSource>> {
         if notebook_id.trim().is_empty() {
             return Err(Error::validation("notebook_id cannot be empty"));
         }
@@ -175,12 +180,12 @@ impl SourcesBackend for EnterpriseSourcesBackend {
             source_id
         );
         let url = self.ctx.url_builder.build_url(&path)?;
-        let source: wire_source::NotebookSource = self
+        let source: ResponseEnvelope<wire_source::NotebookSource> = self
             .ctx
             .http
-            .request_json::<(), _>(Method::GET, url, None::<&()>)
+            .request_json_raw::<(), _>(Method::GET, url, None::<&()>)
             .await?;
-        Ok(source.into())
+        Ok(source.map(Into::into))
     }
 }
 
@@ -205,11 +210,7 @@ mod tests {
         let token = Arc::new(StaticTokenProvider::new("token"));
         let retryer = Retryer::new(RetryConfig::default());
         let http = Arc::new(HttpClient::new(client, token, retryer, None));
-        let url_builder = new_url_builder(
-            env.profile(),
-            env.base_url().to_string(),
-            env.parent_path().to_string(),
-        );
+        let url_builder = new_url_builder(&env);
         let ctx = BackendContext::new(http, url_builder);
         EnterpriseSourcesBackend::new(ctx)
     }
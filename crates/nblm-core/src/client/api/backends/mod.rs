@@ -4,7 +4,7 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 
-use crate::client::{http::HttpClient, url::UrlBuilder};
+use crate::client::{http::HttpClient, stream::JsonArrayStream, url::UrlBuilder, ResponseEnvelope};
 use crate::env::ApiProfile;
 use crate::error::Result;
 use crate::models::enterprise::{
@@ -13,6 +13,7 @@ use crate::models::enterprise::{
         BatchDeleteNotebooksRequest, BatchDeleteNotebooksResponse, ListRecentlyViewedResponse,
         Notebook,
     },
+    query::{QueryRequest, QueryResponse},
     source::{
         BatchCreateSourcesRequest, BatchCreateSourcesResponse, BatchDeleteSourcesRequest,
         BatchDeleteSourcesResponse, NotebookSource, UploadSourceFileResponse, UserContent,
@@ -41,7 +42,14 @@ impl BackendContext {
 
 #[async_trait]
 pub(crate) trait NotebooksBackend: Send + Sync + 'static {
-    async fn create_notebook(&self, title: String) -> Result<Notebook>;
+    async fn create_notebook(&self, title: String, emoji: Option<String>) -> Result<Notebook>;
+    async fn update_notebook(
+        &self,
+        notebook_id: &str,
+        title: Option<String>,
+        emoji: Option<String>,
+    ) -> Result<Notebook>;
+    async fn get_notebook(&self, notebook_id: &str, field_mask: Option<&str>) -> Result<Notebook>;
     async fn batch_delete_notebooks(
         &self,
         request: BatchDeleteNotebooksRequest,
@@ -53,7 +61,20 @@ pub(crate) trait NotebooksBackend: Send + Sync + 'static {
     async fn list_recently_viewed(
         &self,
         page_size: Option<u32>,
-    ) -> Result<ListRecentlyViewedResponse>;
+        page_token: Option<&str>,
+        field_mask: Option<&str>,
+    ) -> Result<ResponseEnvelope<ListRecentlyViewedResponse>>;
+    /// One page of recently viewed notebooks, decoded as the body arrives.
+    async fn stream_recently_viewed_page(
+        &self,
+        page_size: Option<u32>,
+        page_token: Option<&str>,
+    ) -> Result<JsonArrayStream<Notebook>>;
+    async fn query_notebook(
+        &self,
+        notebook_id: &str,
+        request: QueryRequest,
+    ) -> Result<QueryResponse>;
 }
 
 #[async_trait]
@@ -67,7 +88,7 @@ pub(crate) trait SourcesBackend: Send + Sync + 'static {
         &self,
         notebook_id: &str,
         contents: Vec<UserContent>,
-    ) -> Result<BatchCreateSourcesResponse>;
+    ) -> Result<ResponseEnvelope<BatchCreateSourcesResponse>>;
     async fn batch_delete_sources(
         &self,
         notebook_id: &str,
@@ -85,7 +106,11 @@ pub(crate) trait SourcesBackend: Send + Sync + 'static {
         content_type: &str,
         data: Vec<u8>,
     ) -> Result<UploadSourceFileResponse>;
-    async fn get_source(&self, notebook_id: &str, source_id: &str) -> Result<NotebookSource>;
+    async fn get_source(
+        &self,
+        notebook_id: &str,
+        source_id: &str,
+    ) -> Result<ResponseEnvelope<NotebookSource>>;
 }
 
 #[async_trait]
@@ -95,6 +120,7 @@ pub(crate) trait AudioBackend: Send + Sync + 'static {
         notebook_id: &str,
         request: AudioOverviewRequest,
     ) -> Result<AudioOverviewResponse>;
+    async fn get_audio_overview(&self, notebook_id: &str) -> Result<AudioOverviewResponse>;
     async fn delete_audio_overview(&self, notebook_id: &str) -> Result<()>;
 }
 
@@ -102,6 +128,9 @@ pub(crate) struct ClientBackends {
     notebooks: Arc<dyn NotebooksBackend>,
     sources: Arc<dyn SourcesBackend>,
     audio: Arc<dyn AudioBackend>,
+    /// Set for backends that do not talk to the API; the client keeps them when its HTTP
+    /// settings change.
+    fixed: bool,
 }
 
 impl ClientBackends {
@@ -118,6 +147,7 @@ impl ClientBackends {
                     notebooks,
                     sources,
                     audio,
+                    fixed: false,
                 }
             }
             ApiProfile::Personal | ApiProfile::Workspace => {
@@ -129,6 +159,26 @@ impl ClientBackends {
         }
     }
 
+    /// Backends that serve requests without HTTP, such as
+    /// [`InMemoryBackend`](crate::test_support::InMemoryBackend).
+    #[cfg(feature = "test-util")]
+    pub fn fixed(
+        notebooks: Arc<dyn NotebooksBackend>,
+        sources: Arc<dyn SourcesBackend>,
+        audio: Arc<dyn AudioBackend>,
+    ) -> Self {
+        Self {
+            notebooks,
+            sources,
+            audio,
+            fixed: true,
+        }
+    }
+
+    pub fn is_fixed(&self) -> bool {
+        self.fixed
+    }
+
     pub fn notebooks(&self) -> &Arc<dyn NotebooksBackend> {
         &self.notebooks
     }
@@ -160,11 +210,7 @@ mod tests {
         let token = Arc::new(StaticTokenProvider::new("token"));
         let retryer = Retryer::new(RetryConfig::default());
         let http = Arc::new(HttpClient::new(client, token, retryer, None));
-        let url_builder = crate::client::url::new_url_builder(
-            env.profile(),
-            env.base_url().to_string(),
-            env.parent_path().to_string(),
-        );
+        let url_builder = crate::client::url::new_url_builder(&env);
         BackendContext::new(http, url_builder)
     }
 
@@ -1,27 +1,160 @@
+mod artifact;
 pub(crate) mod backends;
+mod notebook_access;
+mod raw;
 
-use crate::client::NblmClient;
-use crate::error::Result;
+pub use self::artifact::ArtifactDownload;
+
+use std::collections::HashSet;
+
+use futures::stream::{self, Stream};
+use serde_json::Value;
+
+use crate::client::emoji::normalize_emoji;
+use crate::client::poll::{poll_until, poll_until_timed, PollConfig, PollState, Polled};
+use crate::client::stream::JsonArrayStream;
+use crate::client::{NblmClient, ResponseEnvelope};
+use crate::error::{Error, Result};
 use crate::models::enterprise::{
+    activity::NotebookActivity,
     audio::{AudioOverviewRequest, AudioOverviewResponse},
+    budget::NotebookWordBudget,
+    id::{NotebookId, SourceId},
     notebook::{
         BatchDeleteNotebooksRequest, BatchDeleteNotebooksResponse, ListRecentlyViewedResponse,
-        Notebook,
+        Notebook, SOURCE_COUNT_FIELDS,
     },
+    query::{QueryRequest, QueryResponse},
     source::{
         BatchCreateSourcesRequest, BatchCreateSourcesResponse, BatchDeleteSourcesRequest,
-        BatchDeleteSourcesResponse, NotebookSource, UploadSourceFileResponse, UserContent,
+        BatchDeleteSourcesResponse, NotebookSource, PartialIngestResult, UploadSourceFileResponse,
+        UserContent,
     },
+    stats::SourceCounts,
 };
 
 impl NblmClient {
+    /// Create an empty notebook titled `title`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> nblm_core::Result<()> {
+    /// # let (_api, client) = nblm_core::test_support::doctest::client().await?;
+    /// let notebook = client.create_notebook("Research").await?;
+    /// assert_eq!(notebook.title, "Research");
+    /// // Follow-up calls take the notebook ID.
+    /// let notebook_id = notebook.notebook_id().expect("created notebooks have an ID");
+    /// assert_eq!(notebook_id, "nb-1");
+    /// # Ok(())
+    /// # }
+    /// ```
     pub async fn create_notebook(&self, title: impl Into<String>) -> Result<Notebook> {
+        self.create_notebook_with_emoji(title, None).await
+    }
+
+    /// Create a notebook shown with `emoji`, which goes through [`normalize_emoji`] first.
+    pub async fn create_notebook_with_emoji(
+        &self,
+        title: impl Into<String>,
+        emoji: Option<String>,
+    ) -> Result<Notebook> {
+        let emoji = emoji.as_deref().map(normalize_emoji).transpose()?;
+        self.backends
+            .notebooks()
+            .create_notebook(title.into(), emoji)
+            .await
+    }
+
+    /// Update a notebook's title and/or emoji. Only the provided fields are sent; `emoji` goes
+    /// through [`normalize_emoji`] first.
+    pub async fn update_notebook(
+        &self,
+        notebook_id: impl Into<NotebookId>,
+        title: Option<String>,
+        emoji: Option<String>,
+    ) -> Result<Notebook> {
+        let notebook_id = NotebookId::checked(notebook_id, self.environment.parent_path())?;
+        let emoji = emoji.as_deref().map(normalize_emoji).transpose()?;
+        self.backends
+            .notebooks()
+            .update_notebook(notebook_id.as_str(), title, emoji)
+            .await
+    }
+
+    pub async fn get_notebook(&self, notebook_id: impl Into<NotebookId>) -> Result<Notebook> {
+        self.get_notebook_with_fields(notebook_id, None).await
+    }
+
+    /// Like [`NblmClient::get_notebook`], but asks for a partial response limited to
+    /// `field_mask` (the `fields` query parameter, e.g. [`NOTEBOOK_FIELDS_MINIMAL`]). Fields
+    /// left out of the mask come back as their defaults.
+    ///
+    /// [`NOTEBOOK_FIELDS_MINIMAL`]: crate::models::enterprise::notebook::NOTEBOOK_FIELDS_MINIMAL
+    pub async fn get_notebook_with_fields(
+        &self,
+        notebook_id: impl Into<NotebookId>,
+        field_mask: Option<&str>,
+    ) -> Result<Notebook> {
+        let notebook_id = NotebookId::checked(notebook_id, self.environment.parent_path())?;
+        let field_mask = checked_field_mask(field_mask)?;
+        self.backends
+            .notebooks()
+            .get_notebook(notebook_id.as_str(), field_mask)
+            .await
+    }
+
+    /// Ask a question of the notebook's sources (`notebooks/{id}:query`).
+    ///
+    /// Returns [`Error::Unavailable`] when the API answers 404, which is how projects and
+    /// profiles without the endpoint respond.
+    pub async fn query_notebook(
+        &self,
+        notebook_id: impl Into<NotebookId>,
+        request: QueryRequest,
+    ) -> Result<QueryResponse> {
+        let notebook_id = NotebookId::checked(notebook_id, self.environment.parent_path())?;
         self.backends
             .notebooks()
-            .create_notebook(title.into())
+            .query_notebook(notebook_id.as_str(), request)
             .await
     }
 
+    /// Sum the word counts of every source in a notebook, largest sources first.
+    pub async fn notebook_word_budget(
+        &self,
+        notebook_id: impl Into<NotebookId>,
+    ) -> Result<NotebookWordBudget> {
+        let notebook_id = NotebookId::checked(notebook_id, self.environment.parent_path())?;
+        let notebook = self.get_notebook(&notebook_id).await?;
+        Ok(NotebookWordBudget::from_sources(
+            notebook_id.into_string(),
+            &notebook.sources,
+        ))
+    }
+
+    /// A best-effort timeline of the notebook from the timestamps on the notebook, its sources
+    /// and its audio overview; see [`NotebookActivity`]. A notebook without an audio overview
+    /// (a 404 for it) simply has no audio event.
+    pub async fn notebook_activity(
+        &self,
+        notebook_id: impl Into<NotebookId>,
+    ) -> Result<NotebookActivity> {
+        let notebook_id = NotebookId::checked(notebook_id, self.environment.parent_path())?;
+        let notebook = self.get_notebook(&notebook_id).await?;
+        let audio = match self.get_audio_overview(&notebook_id).await {
+            Ok(audio) => Some(audio),
+            Err(Error::Http { status, .. }) if status == reqwest::StatusCode::NOT_FOUND => None,
+            Err(err) => return Err(err),
+        };
+        Ok(NotebookActivity::from_parts(
+            notebook_id.into_string(),
+            &notebook,
+            audio.as_ref(),
+        ))
+    }
+
     pub async fn batch_delete_notebooks(
         &self,
         request: BatchDeleteNotebooksRequest,
@@ -32,6 +165,21 @@ impl NblmClient {
             .await
     }
 
+    /// Delete notebooks by full resource name (`projects/.../notebooks/{id}`), one request
+    /// each.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> nblm_core::Result<()> {
+    /// # let (_api, client) = nblm_core::test_support::doctest::client().await?;
+    /// let notebook = client.create_notebook("Research").await?;
+    /// let name = notebook.name.expect("created notebooks have a resource name");
+    /// client.delete_notebooks(vec![name]).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
     pub async fn delete_notebooks(
         &self,
         notebook_names: Vec<String>,
@@ -42,101 +190,465 @@ impl NblmClient {
             .await
     }
 
+    /// The first page of recently viewed notebooks, up to `page_size` of them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> nblm_core::Result<()> {
+    /// # let (_api, client) = nblm_core::test_support::doctest::client().await?;
+    /// let page = client.list_recently_viewed(Some(2)).await?;
+    /// let titles: Vec<_> = page.notebooks.iter().map(|notebook| notebook.title.as_str()).collect();
+    /// assert_eq!(titles, ["Research", "Reading list"]);
+    /// // More notebooks are available; `stream_recently_viewed` fetches every page.
+    /// assert!(page.next_page_token.is_some());
+    /// # Ok(())
+    /// # }
+    /// ```
     pub async fn list_recently_viewed(
         &self,
         page_size: Option<u32>,
     ) -> Result<ListRecentlyViewedResponse> {
+        self.list_recently_viewed_raw(page_size)
+            .await
+            .map(ResponseEnvelope::into_inner)
+    }
+
+    /// Like [`NblmClient::list_recently_viewed`], but also returns the raw response body.
+    pub async fn list_recently_viewed_raw(
+        &self,
+        page_size: Option<u32>,
+    ) -> Result<ResponseEnvelope<ListRecentlyViewedResponse>> {
+        self.list_recently_viewed_raw_with_fields(page_size, None)
+            .await
+    }
+
+    /// Like [`NblmClient::list_recently_viewed`], but asks for a partial response limited to
+    /// `field_mask` (e.g. [`RECENT_NOTEBOOKS_FIELDS_MINIMAL`]). Keep `nextPageToken` in a custom
+    /// mask if more pages are needed.
+    ///
+    /// [`RECENT_NOTEBOOKS_FIELDS_MINIMAL`]: crate::models::enterprise::notebook::RECENT_NOTEBOOKS_FIELDS_MINIMAL
+    pub async fn list_recently_viewed_with_fields(
+        &self,
+        page_size: Option<u32>,
+        field_mask: Option<&str>,
+    ) -> Result<ListRecentlyViewedResponse> {
+        self.list_recently_viewed_raw_with_fields(page_size, field_mask)
+            .await
+            .map(ResponseEnvelope::into_inner)
+    }
+
+    /// Like [`NblmClient::list_recently_viewed_with_fields`], but also returns the raw response
+    /// body.
+    pub async fn list_recently_viewed_raw_with_fields(
+        &self,
+        page_size: Option<u32>,
+        field_mask: Option<&str>,
+    ) -> Result<ResponseEnvelope<ListRecentlyViewedResponse>> {
+        self.list_recently_viewed_page_raw(page_size, None, field_mask)
+            .await
+    }
+
+    /// The page of recently viewed notebooks starting at `page_token` (the first page when
+    /// `None`), with the raw response body. Pass the `next_page_token` of one page to get the
+    /// next; it is `None` on the last page.
+    pub async fn list_recently_viewed_page_raw(
+        &self,
+        page_size: Option<u32>,
+        page_token: Option<&str>,
+        field_mask: Option<&str>,
+    ) -> Result<ResponseEnvelope<ListRecentlyViewedResponse>> {
+        let field_mask = checked_field_mask(field_mask)?;
         self.backends
             .notebooks()
-            .list_recently_viewed(page_size)
+            .list_recently_viewed(page_size, page_token, field_mask)
             .await
     }
 
+    /// Recently viewed notebooks across all pages, fetched lazily.
+    ///
+    /// A page is requested only once the consumer has taken every notebook from the previous
+    /// one, so dropping the stream stops further requests. Each page is decoded as its body
+    /// arrives, so memory does not grow with the page size. The stream ends after the first
+    /// error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> nblm_core::Result<()> {
+    /// # let (_api, client) = nblm_core::test_support::doctest::client().await?;
+    /// use futures::TryStreamExt;
+    ///
+    /// let notebooks: Vec<_> = client.stream_recently_viewed(Some(2)).try_collect().await?;
+    /// let ids: Vec<_> = notebooks.iter().filter_map(|notebook| notebook.notebook_id()).collect();
+    /// assert_eq!(ids, ["nb-1", "nb-2", "nb-3"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stream_recently_viewed(
+        &self,
+        page_size: Option<u32>,
+    ) -> impl Stream<Item = Result<Notebook>> + Send + '_ {
+        enum Page {
+            /// Next page to request: `None` for the first page.
+            Fetch(Option<String>),
+            /// Items of the current page, and how many have been taken.
+            Reading(JsonArrayStream<Notebook>, usize),
+            Done,
+        }
+
+        let start = (Page::Fetch(None), HashSet::new());
+        stream::unfold(start, move |(mut page, mut tokens)| async move {
+            loop {
+                page = match page {
+                    Page::Done => return None,
+                    Page::Fetch(token) => match self
+                        .backends
+                        .notebooks()
+                        .stream_recently_viewed_page(page_size, token.as_deref())
+                        .await
+                    {
+                        Ok(items) => Page::Reading(items, 0),
+                        Err(err) => return Some((Err(err), (Page::Done, tokens))),
+                    },
+                    Page::Reading(mut items, taken) => match items.next_item().await {
+                        Some(Ok(notebook)) => {
+                            return Some((Ok(notebook), (Page::Reading(items, taken + 1), tokens)))
+                        }
+                        Some(Err(err)) => return Some((Err(err), (Page::Done, tokens))),
+                        None => match items.rest().await {
+                            Ok(rest) => {
+                                let next = rest.get("nextPageToken").and_then(Value::as_str);
+                                match next_page(next, taken, &mut tokens) {
+                                    Some(next) => Page::Fetch(Some(next)),
+                                    None => Page::Done,
+                                }
+                            }
+                            Err(err) => return Some((Err(err), (Page::Done, tokens))),
+                        },
+                    },
+                };
+            }
+        })
+    }
+
+    /// Source counts for every recently viewed notebook, across all pages.
+    ///
+    /// Each page asks only for [`SOURCE_COUNT_FIELDS`], so the responses carry notebook IDs,
+    /// titles and source names and nothing else. A notebook seen on more than one page is
+    /// counted once; see [`SourceCounts::from_notebooks`]. Each page is reduced to notebook
+    /// references and counts before the next is fetched.
+    pub async fn notebook_source_counts(&self, page_size: Option<u32>) -> Result<SourceCounts> {
+        let mut entries = Vec::new();
+        let mut tokens = HashSet::new();
+        let mut token: Option<String> = None;
+        loop {
+            let page = self
+                .backends
+                .notebooks()
+                .list_recently_viewed(page_size, token.as_deref(), Some(SOURCE_COUNT_FIELDS))
+                .await?
+                .into_inner();
+            let count = page.notebooks.len();
+            entries.extend(page.notebooks.iter().filter_map(SourceCounts::entry));
+            token = next_page(page.next_page_token.as_deref(), count, &mut tokens);
+            if token.is_none() {
+                break;
+            }
+        }
+        Ok(SourceCounts::from_refs(entries))
+    }
+
     pub async fn batch_create_sources(
         &self,
-        notebook_id: &str,
+        notebook_id: impl Into<NotebookId>,
         request: BatchCreateSourcesRequest,
     ) -> Result<BatchCreateSourcesResponse> {
+        let notebook_id = NotebookId::checked(notebook_id, self.environment.parent_path())?;
         let includes_drive = has_drive_content(request.user_contents.iter());
         self.ensure_drive_scope_if_needed(includes_drive).await?;
         self.backends
             .sources()
-            .batch_create_sources(notebook_id, request)
+            .batch_create_sources(notebook_id.as_str(), request)
             .await
     }
 
+    /// Add web, text, video or Google Drive sources to a notebook in one request.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> nblm_core::Result<()> {
+    /// # let (_api, client) = nblm_core::test_support::doctest::client().await?;
+    /// use nblm_core::models::enterprise::source::UserContent;
+    ///
+    /// let response = client
+    ///     .add_sources(
+    ///         "nb-1",
+    ///         vec![
+    ///             UserContent::web("https://example.com".to_string(), None),
+    ///             UserContent::text("Meeting notes".to_string(), Some("Notes".to_string())),
+    ///         ],
+    ///     )
+    ///     .await?;
+    /// assert_eq!(response.sources[0].title.as_deref(), Some("Example Domain"));
+    /// # Ok(())
+    /// # }
+    /// ```
     pub async fn add_sources(
         &self,
-        notebook_id: &str,
+        notebook_id: impl Into<NotebookId>,
         contents: Vec<UserContent>,
     ) -> Result<BatchCreateSourcesResponse> {
+        self.add_sources_raw(notebook_id, contents)
+            .await
+            .map(ResponseEnvelope::into_inner)
+    }
+
+    /// Like [`NblmClient::add_sources`], but also returns the raw response body.
+    pub async fn add_sources_raw(
+        &self,
+        notebook_id: impl Into<NotebookId>,
+        contents: Vec<UserContent>,
+    ) -> Result<ResponseEnvelope<BatchCreateSourcesResponse>> {
+        let notebook_id = NotebookId::checked(notebook_id, self.environment.parent_path())?;
         let includes_drive = has_drive_content(contents.iter());
         self.ensure_drive_scope_if_needed(includes_drive).await?;
         self.backends
             .sources()
-            .add_sources(notebook_id, contents)
+            .add_sources(notebook_id.as_str(), contents)
             .await
     }
 
+    /// Like [`NblmClient::add_sources`], but splits the response into created and failed sources
+    /// so callers can re-submit only the failures.
+    pub async fn add_sources_partial(
+        &self,
+        notebook_id: impl Into<NotebookId>,
+        contents: Vec<UserContent>,
+    ) -> Result<PartialIngestResult> {
+        let response = self.add_sources(notebook_id, contents.clone()).await?;
+        Ok(PartialIngestResult::from_response(contents, response))
+    }
+
     pub async fn batch_delete_sources(
         &self,
-        notebook_id: &str,
+        notebook_id: impl Into<NotebookId>,
         request: BatchDeleteSourcesRequest,
     ) -> Result<BatchDeleteSourcesResponse> {
         self.backends
             .sources()
-            .batch_delete_sources(notebook_id, request)
+            .batch_delete_sources(
+                NotebookId::checked(notebook_id, self.environment.parent_path())?.as_str(),
+                request,
+            )
             .await
     }
 
     pub async fn delete_sources(
         &self,
-        notebook_id: &str,
+        notebook_id: impl Into<NotebookId>,
         source_names: Vec<String>,
     ) -> Result<BatchDeleteSourcesResponse> {
         self.backends
             .sources()
-            .delete_sources(notebook_id, source_names)
+            .delete_sources(
+                NotebookId::checked(notebook_id, self.environment.parent_path())?.as_str(),
+                source_names,
+            )
             .await
     }
 
+    /// Upload `data` as a file source named `file_name`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> nblm_core::Result<()> {
+    /// # let (_api, client) = nblm_core::test_support::doctest::client().await?;
+    /// let response = client
+    ///     .upload_source_file("nb-1", "notes.md", "text/markdown", b"# Notes".to_vec())
+    ///     .await?;
+    /// let source_id = response.source_id.and_then(|id| id.id);
+    /// assert_eq!(source_id.as_deref(), Some("src-2"));
+    /// # Ok(())
+    /// # }
+    /// ```
     pub async fn upload_source_file(
         &self,
-        notebook_id: &str,
+        notebook_id: impl Into<NotebookId>,
         file_name: &str,
         content_type: &str,
         data: Vec<u8>,
     ) -> Result<UploadSourceFileResponse> {
+        let notebook_id = NotebookId::checked(notebook_id, self.environment.parent_path())?;
         self.backends
             .sources()
-            .upload_source_file(notebook_id, file_name, content_type, data)
+            .upload_source_file(notebook_id.as_str(), file_name, content_type, data)
+            .await
+    }
+
+    pub async fn get_source(
+        &self,
+        notebook_id: impl Into<NotebookId>,
+        source_id: impl Into<SourceId>,
+    ) -> Result<NotebookSource> {
+        self.get_source_raw(notebook_id, source_id)
             .await
+            .map(ResponseEnvelope::into_inner)
+    }
+
+    /// Poll each of `sources` (as returned when they were added) until it is ingested.
+    ///
+    /// Sources are polled at the same time, each on its own [`PollConfig`] schedule, and the
+    /// results come back in the order given. Each [`Polled`] holds the source's last state,
+    /// or [`Error::OperationFailed`] when ingestion failed and [`Error::WaitTimedOut`] when it
+    /// was still running after `config.timeout`, together with the time spent waiting and the
+    /// number of status checks. A source without an ID cannot be polled and fails at once.
+    pub async fn wait_for_sources(
+        &self,
+        notebook_id: impl Into<NotebookId>,
+        sources: &[NotebookSource],
+        config: PollConfig,
+    ) -> Vec<Polled<NotebookSource>> {
+        let notebook_id = notebook_id.into();
+        let waits = sources.iter().map(|source| {
+            let notebook_id = notebook_id.clone();
+            async move {
+                let Some(source_id) = source.source_id().map(SourceId::from) else {
+                    return Polled {
+                        result: Err(Error::validation(format!(
+                            "source {:?} has no ID to poll",
+                            source.name
+                        ))),
+                        elapsed: Default::default(),
+                        polls: 0,
+                    };
+                };
+                poll_until_timed(
+                    config,
+                    "source ingestion",
+                    source.clone(),
+                    |source: &NotebookSource| {
+                        let status = source.status().unwrap_or("-").to_string();
+                        if source.is_failed() {
+                            PollState::Failed(status)
+                        } else if source.is_ingested() {
+                            PollState::Done
+                        } else {
+                            PollState::Pending(status)
+                        }
+                    },
+                    || self.get_source(notebook_id.clone(), source_id.clone()),
+                )
+                .await
+            }
+        });
+        futures::future::join_all(waits).await
     }
 
-    pub async fn get_source(&self, notebook_id: &str, source_id: &str) -> Result<NotebookSource> {
+    /// Like [`NblmClient::get_source`], but also returns the raw response body.
+    pub async fn get_source_raw(
+        &self,
+        notebook_id: impl Into<NotebookId>,
+        source_id: impl Into<SourceId>,
+    ) -> Result<ResponseEnvelope<NotebookSource>> {
+        let notebook_id = NotebookId::checked(notebook_id, self.environment.parent_path())?;
+        let source_id = SourceId::checked(source_id)?;
         self.backends
             .sources()
-            .get_source(notebook_id, source_id)
+            .get_source(notebook_id.as_str(), source_id.as_str())
             .await
     }
 
     pub async fn create_audio_overview(
         &self,
-        notebook_id: &str,
+        notebook_id: impl Into<NotebookId>,
         request: AudioOverviewRequest,
     ) -> Result<AudioOverviewResponse> {
+        let notebook_id = NotebookId::checked(notebook_id, self.environment.parent_path())?;
         self.backends
             .audio()
-            .create_audio_overview(notebook_id, request)
+            .create_audio_overview(notebook_id.as_str(), request)
             .await
     }
 
-    pub async fn delete_audio_overview(&self, notebook_id: &str) -> Result<()> {
+    /// Current state of the notebook's audio overview, e.g. to poll a generation started with
+    /// [`NblmClient::create_audio_overview`].
+    pub async fn get_audio_overview(
+        &self,
+        notebook_id: impl Into<NotebookId>,
+    ) -> Result<AudioOverviewResponse> {
+        let notebook_id = NotebookId::checked(notebook_id, self.environment.parent_path())?;
         self.backends
             .audio()
-            .delete_audio_overview(notebook_id)
+            .get_audio_overview(notebook_id.as_str())
             .await
     }
+
+    /// Poll the notebook's audio overview from `current` on until it is ready.
+    ///
+    /// A failed generation ends with [`Error::OperationFailed`] and one still running after
+    /// `config.timeout` with [`Error::WaitTimedOut`]; see [`PollConfig`] for the schedule.
+    pub async fn wait_for_audio_overview(
+        &self,
+        notebook_id: impl Into<NotebookId>,
+        current: AudioOverviewResponse,
+        config: PollConfig,
+    ) -> Result<AudioOverviewResponse> {
+        let notebook_id = NotebookId::checked(notebook_id, self.environment.parent_path())?;
+        poll_until(
+            config,
+            "audio generation",
+            current,
+            |overview: &AudioOverviewResponse| {
+                let status = overview.status.clone().unwrap_or_else(|| "-".to_string());
+                if overview.is_ready() {
+                    PollState::Done
+                } else if overview.is_failed() {
+                    PollState::Failed(status)
+                } else {
+                    PollState::Pending(status)
+                }
+            },
+            || self.get_audio_overview(notebook_id.clone()),
+        )
+        .await
+    }
+
+    pub async fn delete_audio_overview(&self, notebook_id: impl Into<NotebookId>) -> Result<()> {
+        let notebook_id = NotebookId::checked(notebook_id, self.environment.parent_path())?;
+        self.backends
+            .audio()
+            .delete_audio_overview(notebook_id.as_str())
+            .await
+    }
+}
+
+/// Token for the page after one that held `count` notebooks, or `None` when the listing is over.
+///
+/// Besides a missing or empty token, an empty page ends it (the API has been seen to return
+/// `{}`, or no notebooks with a token, for projects without any), and so does a token handed
+/// out before, which would page forever.
+fn next_page(token: Option<&str>, count: usize, seen: &mut HashSet<String>) -> Option<String> {
+    let token = token.filter(|token| !token.is_empty())?;
+    if count == 0 {
+        tracing::debug!("empty page with a nextPageToken; ending the listing");
+        return None;
+    }
+    seen.insert(token.to_string()).then(|| token.to_string())
+}
+
+/// A blank mask would send `fields=`, which the API treats as a request for nothing.
+fn checked_field_mask(field_mask: Option<&str>) -> Result<Option<&str>> {
+    match field_mask.map(str::trim) {
+        Some("") => Err(Error::validation("field mask must not be empty")),
+        mask => Ok(mask),
+    }
 }
 
 fn has_drive_content<'a, I>(contents: I) -> bool
@@ -151,35 +663,16 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::auth::StaticTokenProvider;
+    use crate::auth::{ScopeChecker, StaticTokenProvider};
     use crate::env::EnvironmentConfig;
     use crate::error::Error;
     use serde_json::json;
-    use serial_test::serial;
     use std::sync::Arc;
     use wiremock::matchers::{method, path, query_param};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
-    struct EnvGuard {
-        key: &'static str,
-        original: Option<String>,
-    }
-
-    impl EnvGuard {
-        fn new(key: &'static str) -> Self {
-            let original = std::env::var(key).ok();
-            Self { key, original }
-        }
-    }
-
-    impl Drop for EnvGuard {
-        fn drop(&mut self) {
-            if let Some(value) = &self.original {
-                std::env::set_var(self.key, value);
-            } else {
-                std::env::remove_var(self.key);
-            }
-        }
+    fn tokeninfo_checker(server: &MockServer) -> ScopeChecker {
+        ScopeChecker::new().with_endpoint(format!("{}/tokeninfo", server.uri()))
     }
 
     async fn build_client(base_url: &str) -> NblmClient {
@@ -195,16 +688,12 @@ mod tests {
     #[case::with_drive_scope("https://www.googleapis.com/auth/drive.file", true, 1)]
     #[case::without_drive_scope("https://www.googleapis.com/auth/cloud-platform", false, 0)]
     #[tokio::test]
-    #[serial]
     async fn add_sources_validates_drive_scope(
         #[case] scope: &str,
         #[case] should_succeed: bool,
         #[case] api_call_count: u64,
     ) {
         let server = MockServer::start().await;
-        let tokeninfo_url = format!("{}/tokeninfo", server.uri());
-        let _guard = EnvGuard::new("NBLM_TOKENINFO_ENDPOINT");
-        std::env::set_var("NBLM_TOKENINFO_ENDPOINT", &tokeninfo_url);
 
         Mock::given(method("GET"))
             .and(path("/tokeninfo"))
@@ -228,7 +717,9 @@ mod tests {
             .mount(&server)
             .await;
 
-        let client = build_client(&format!("{}/v1alpha", server.uri())).await;
+        let client = build_client(&format!("{}/v1alpha", server.uri()))
+            .await
+            .with_scope_checker(tokeninfo_checker(&server));
 
         let result = client
             .add_sources(
@@ -262,12 +753,8 @@ mod tests {
     }
 
     #[tokio::test]
-    #[serial]
     async fn batch_create_sources_validates_drive_scope() {
         let server = MockServer::start().await;
-        let tokeninfo_url = format!("{}/tokeninfo", server.uri());
-        let _guard = EnvGuard::new("NBLM_TOKENINFO_ENDPOINT");
-        std::env::set_var("NBLM_TOKENINFO_ENDPOINT", &tokeninfo_url);
 
         Mock::given(method("GET"))
             .and(path("/tokeninfo"))
@@ -291,7 +778,9 @@ mod tests {
             .mount(&server)
             .await;
 
-        let client = build_client(&format!("{}/v1alpha", server.uri())).await;
+        let client = build_client(&format!("{}/v1alpha", server.uri()))
+            .await
+            .with_scope_checker(tokeninfo_checker(&server));
 
         let request = BatchCreateSourcesRequest {
             user_contents: vec![UserContent::google_drive(
@@ -308,4 +797,346 @@ mod tests {
 
         assert!(result.sources.is_empty());
     }
+
+    #[tokio::test]
+    async fn update_notebook_patches_only_provided_fields() {
+        let server = MockServer::start().await;
+        Mock::given(method("PATCH"))
+            .and(path("/v1alpha/projects/123/locations/global/notebooks/nb1"))
+            .and(query_param("updateMask", "emoji"))
+            .and(wiremock::matchers::body_json(json!({"emoji": "📘"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "name": "projects/123/locations/global/notebooks/nb1",
+                "title": "Existing",
+                "emoji": "📘"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = build_client(&format!("{}/v1alpha", server.uri())).await;
+        let notebook = client
+            .update_notebook("nb1", None, Some("📘".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(notebook.emoji.as_deref(), Some("📘"));
+        assert_eq!(notebook.title, "Existing");
+    }
+
+    #[tokio::test]
+    async fn notebook_emoji_is_normalized_before_sending() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1alpha/projects/123/locations/global/notebooks"))
+            .and(wiremock::matchers::body_json(
+                json!({"title": "Launch", "emoji": "🚀"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "name": "projects/123/locations/global/notebooks/nb1",
+                "title": "Launch",
+                "emoji": "🚀"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = build_client(&format!("{}/v1alpha", server.uri())).await;
+        client
+            .create_notebook_with_emoji("Launch", Some(":rocket:".to_string()))
+            .await
+            .unwrap();
+        let err = client
+            .update_notebook("nb1", None, Some("rocket".to_string()))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Validation(_)), "{err:?}");
+    }
+
+    async fn mock_recent_pages(
+        server: &MockServer,
+        page_two: ResponseTemplate,
+        page_two_calls: u64,
+    ) {
+        use wiremock::matchers::query_param_is_missing;
+
+        let list_path = "/v1alpha/projects/123/locations/global/notebooks:listRecentlyViewed";
+        Mock::given(method("GET"))
+            .and(path(list_path))
+            .and(query_param_is_missing("pageToken"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "notebooks": [
+                    {"title": "One", "notebookId": "nb1"},
+                    {"title": "Two", "notebookId": "nb2"}
+                ],
+                "nextPageToken": "page-2"
+            })))
+            .expect(1)
+            .mount(server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(list_path))
+            .and(query_param("pageToken", "page-2"))
+            .respond_with(page_two)
+            .expect(page_two_calls)
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn stream_recently_viewed_yields_all_pages_in_order() {
+        use futures::TryStreamExt;
+
+        let server = MockServer::start().await;
+        let page_two = ResponseTemplate::new(200).set_body_json(json!({
+            "notebooks": [{"title": "Three", "notebookId": "nb3"}]
+        }));
+        mock_recent_pages(&server, page_two, 1).await;
+        let client = build_client(&format!("{}/v1alpha", server.uri())).await;
+
+        let notebooks: Vec<Notebook> = client
+            .stream_recently_viewed(Some(2))
+            .try_collect()
+            .await
+            .unwrap();
+        let ids: Vec<_> = notebooks
+            .iter()
+            .map(|nb| nb.notebook_id.as_deref().unwrap())
+            .collect();
+        assert_eq!(ids, ["nb1", "nb2", "nb3"]);
+    }
+
+    #[tokio::test]
+    async fn stream_recently_viewed_fetches_pages_lazily() {
+        use futures::StreamExt;
+
+        let server = MockServer::start().await;
+        mock_recent_pages(&server, ResponseTemplate::new(200), 0).await;
+        let client = build_client(&format!("{}/v1alpha", server.uri())).await;
+
+        let first: Vec<_> = client
+            .stream_recently_viewed(Some(2))
+            .take(2)
+            .collect()
+            .await;
+        assert_eq!(first.len(), 2);
+        assert!(first.iter().all(Result::is_ok));
+        // Mock expectations verify page two was never requested.
+    }
+
+    #[tokio::test]
+    async fn stream_recently_viewed_surfaces_page_error_after_earlier_items() {
+        use futures::StreamExt;
+
+        let server = MockServer::start().await;
+        let page_two = ResponseTemplate::new(400).set_body_json(json!({
+            "error": {"code": 400, "message": "bad page token"}
+        }));
+        mock_recent_pages(&server, page_two, 1).await;
+        let client = build_client(&format!("{}/v1alpha", server.uri())).await;
+
+        let items: Vec<_> = client.stream_recently_viewed(None).collect().await;
+        assert_eq!(items.len(), 3);
+        assert_eq!(
+            items[0].as_ref().unwrap().notebook_id.as_deref(),
+            Some("nb1")
+        );
+        assert_eq!(
+            items[1].as_ref().unwrap().notebook_id.as_deref(),
+            Some("nb2")
+        );
+        match &items[2] {
+            Err(Error::Http { status, .. }) => assert_eq!(*status, 400),
+            other => panic!("expected HTTP 400 error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn stream_recently_viewed_matches_the_buffered_parse_of_a_large_page() {
+        use futures::TryStreamExt;
+
+        let notebooks: Vec<_> = (0..10_000)
+            .map(|i| {
+                json!({
+                    "title": format!("Notebook {i} \u{1F4D8} \"quoted\""),
+                    "notebookId": format!("nb{i}"),
+                    "emoji": "📘",
+                    "metadata": {"isShared": i % 2 == 0, "sourceCount": i % 50},
+                    "sources": [{"name": format!("sources/s{i}"), "title": "A source"}],
+                    "futureField": {"nested": [i, null]}
+                })
+            })
+            .collect();
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(
+                "/v1alpha/projects/123/locations/global/notebooks:listRecentlyViewed",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "notebooks": notebooks,
+                "nextPageToken": ""
+            })))
+            .mount(&server)
+            .await;
+        let client = build_client(&format!("{}/v1alpha", server.uri())).await;
+
+        let buffered = client.list_recently_viewed(None).await.unwrap().notebooks;
+        let streamed: Vec<Notebook> = client
+            .stream_recently_viewed(None)
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(streamed.len(), 10_000);
+        assert_eq!(
+            serde_json::to_value(&streamed).unwrap(),
+            serde_json::to_value(&buffered).unwrap()
+        );
+    }
+
+    async fn mock_single_page(server: &MockServer, body: Value) {
+        Mock::given(method("GET"))
+            .and(path(
+                "/v1alpha/projects/123/locations/global/notebooks:listRecentlyViewed",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .expect(1)
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn listing_without_a_notebooks_key_is_empty() {
+        use futures::TryStreamExt;
+
+        let server = MockServer::start().await;
+        mock_single_page(&server, json!({})).await;
+        let client = build_client(&format!("{}/v1alpha", server.uri())).await;
+
+        let streamed: Vec<Notebook> = client
+            .stream_recently_viewed(None)
+            .try_collect()
+            .await
+            .unwrap();
+        assert!(streamed.is_empty());
+    }
+
+    #[tokio::test]
+    async fn source_counts_without_a_notebooks_key_are_zero() {
+        let server = MockServer::start().await;
+        mock_single_page(&server, json!({})).await;
+        let client = build_client(&format!("{}/v1alpha", server.uri())).await;
+
+        let counts = client.notebook_source_counts(None).await.unwrap();
+        assert!(counts.notebooks.is_empty());
+        assert_eq!(counts.total_sources, 0);
+    }
+
+    #[tokio::test]
+    async fn empty_page_with_a_token_ends_the_listing() {
+        use futures::TryStreamExt;
+
+        let server = MockServer::start().await;
+        mock_single_page(&server, json!({"notebooks": [], "nextPageToken": "page-2"})).await;
+        let client = build_client(&format!("{}/v1alpha", server.uri())).await;
+
+        let streamed: Vec<Notebook> = client
+            .stream_recently_viewed(None)
+            .try_collect()
+            .await
+            .unwrap();
+        assert!(streamed.is_empty());
+        // Mock expectations verify the token was not followed.
+    }
+
+    #[tokio::test]
+    async fn final_empty_page_keeps_the_earlier_notebooks() {
+        use futures::TryStreamExt;
+
+        let server = MockServer::start().await;
+        let page_two = ResponseTemplate::new(200).set_body_json(json!({
+            "notebooks": [],
+            "nextPageToken": "page-3"
+        }));
+        mock_recent_pages(&server, page_two, 1).await;
+        let client = build_client(&format!("{}/v1alpha", server.uri())).await;
+
+        let streamed: Vec<Notebook> = client
+            .stream_recently_viewed(None)
+            .try_collect()
+            .await
+            .unwrap();
+        let ids: Vec<_> = streamed
+            .iter()
+            .map(|nb| nb.notebook_id.as_deref().unwrap())
+            .collect();
+        assert_eq!(ids, ["nb1", "nb2"]);
+        // Mock expectations verify "page-3" was not requested.
+    }
+
+    fn source_in(status: &str, id: &str) -> serde_json::Value {
+        json!({
+            "name": format!("projects/123/locations/global/notebooks/nb/sources/{id}"),
+            "settings": {"status": status},
+        })
+    }
+
+    #[tokio::test]
+    async fn wait_for_sources_times_each_source_until_it_settles() {
+        let server = MockServer::start().await;
+        let source_path =
+            |id: &str| format!("/v1alpha/projects/123/locations/global/notebooks/nb/sources/{id}");
+        Mock::given(method("GET"))
+            .and(path(source_path("s1")))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(source_in("SOURCE_STATUS_PENDING", "s1")),
+            )
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(source_path("s1")))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(source_in("SOURCE_STATUS_COMPLETE", "s1")),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(source_path("s2")))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(source_in("SOURCE_STATUS_ERROR", "s2")),
+            )
+            .mount(&server)
+            .await;
+        let client = build_client(&format!("{}/v1alpha", server.uri())).await;
+        let sources: Vec<NotebookSource> = [
+            source_in("SOURCE_STATUS_PENDING", "s1"),
+            source_in("SOURCE_STATUS_PENDING", "s2"),
+            source_in("SOURCE_STATUS_COMPLETE", "s3"),
+            json!({"name": ""}),
+        ]
+        .into_iter()
+        .map(|source| serde_json::from_value(source).unwrap())
+        .collect();
+        let interval = std::time::Duration::from_millis(10);
+        let config = PollConfig::new(interval, std::time::Duration::from_secs(5));
+
+        let waits = client.wait_for_sources("nb", &sources, config).await;
+
+        let [s1, s2, s3, unnamed] = &waits[..] else {
+            panic!("expected four results, got {waits:?}");
+        };
+        let done = s1.result.as_ref().unwrap();
+        assert_eq!(done.status(), Some("SOURCE_STATUS_COMPLETE"));
+        assert_eq!(s1.polls, 2);
+        // 10ms, then 15ms while the status stayed PENDING.
+        assert!(s1.elapsed >= interval * 5 / 2, "{:?}", s1.elapsed);
+        assert!(
+            matches!(&s2.result, Err(Error::OperationFailed { state, .. }) if state == "SOURCE_STATUS_ERROR")
+        );
+        assert_eq!(s2.polls, 1);
+        assert!(s3.result.is_ok());
+        assert_eq!((s3.polls, s3.elapsed), (0, std::time::Duration::ZERO));
+        assert!(matches!(unnamed.result, Err(Error::Validation(_))));
+        assert_eq!(unnamed.polls, 0);
+    }
 }
@@ -0,0 +1,147 @@
+use reqwest::StatusCode;
+
+use crate::client::NblmClient;
+use crate::error::Error;
+use crate::models::enterprise::id::NotebookId;
+
+/// Partial response mask for the existence probe: nothing but the ID.
+const PROBE_NOTEBOOK_FIELDS: &str = "notebookId";
+/// Partial response mask for the listing the suggestion is picked from.
+const PROBE_RECENT_FIELDS: &str = "notebooks(name,notebookId)";
+/// Recently viewed notebooks compared with a missing ID; one page, the API's largest.
+const PROBE_PAGE_SIZE: u32 = 500;
+
+impl NblmClient {
+    /// Work out what a 403 or 404 from a call on `notebook_id` meant. The API answers 403 for
+    /// notebooks that do not exist, so a typo in an ID reads like a permission problem.
+    ///
+    /// Fetches the notebook's ID and, when that fails as well, one page of recently viewed
+    /// notebooks. Returns [`Error::NotebookNotFound`] when neither finds the notebook, suggesting
+    /// the closest listed ID, and [`Error::NotebookForbidden`] for a 403 on a notebook that is
+    /// visible. Returns `None`, meaning `err` stands as is, for any other error, for a 404 on a
+    /// visible notebook (something inside it is missing) and when a probe fails in another way.
+    pub async fn explain_notebook_error(
+        &self,
+        notebook_id: impl Into<NotebookId>,
+        err: &Error,
+    ) -> Option<Error> {
+        let Error::Http {
+            status,
+            message,
+            body,
+        } = err
+        else {
+            return None;
+        };
+        if !is_masked(*status) {
+            return None;
+        }
+        let notebook_id = NotebookId::checked(notebook_id, self.environment.parent_path()).ok()?;
+
+        let fetched = match self
+            .get_notebook_with_fields(&notebook_id, Some(PROBE_NOTEBOOK_FIELDS))
+            .await
+        {
+            Ok(_) => true,
+            Err(Error::Http { status, .. }) if is_masked(status) => false,
+            Err(_) => return None,
+        };
+        let listed: Vec<String> = if fetched {
+            Vec::new()
+        } else {
+            self.list_recently_viewed_with_fields(Some(PROBE_PAGE_SIZE), Some(PROBE_RECENT_FIELDS))
+                .await
+                .ok()?
+                .notebooks
+                .iter()
+                .filter_map(|notebook| notebook.notebook_id().map(str::to_string))
+                .collect()
+        };
+
+        if fetched || listed.iter().any(|id| id == notebook_id.as_str()) {
+            return (*status == StatusCode::FORBIDDEN).then(|| Error::NotebookForbidden {
+                notebook_id: notebook_id.into_string(),
+                status: *status,
+                message: message.clone(),
+                body: body.clone(),
+            });
+        }
+        Some(Error::NotebookNotFound {
+            suggestion: closest(notebook_id.as_str(), &listed).map(str::to_string),
+            notebook_id: notebook_id.into_string(),
+            project: self.project_number().to_string(),
+            status: *status,
+            message: message.clone(),
+        })
+    }
+}
+
+fn is_masked(status: StatusCode) -> bool {
+    matches!(status, StatusCode::FORBIDDEN | StatusCode::NOT_FOUND)
+}
+
+/// The candidate closest to `id`, if it is within a third of `id`'s length in edits (at least
+/// one). Ties go to the earlier candidate, which for the recently viewed list is the more recent.
+fn closest<'a>(id: &str, candidates: &'a [String]) -> Option<&'a str> {
+    let limit = (id.chars().count() / 3).max(1);
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(id, candidate), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate.as_str())
+}
+
+/// Levenshtein distance: single-character insertions, deletions and substitutions.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn edit_distance_counts_single_character_edits() {
+        assert_eq!(edit_distance("nb-123", "nb-123"), 0);
+        assert_eq!(edit_distance("nb-12x", "nb-123"), 1);
+        assert_eq!(edit_distance("nb-13", "nb-123"), 1);
+        assert_eq!(edit_distance("nb-1234", "nb-123"), 1);
+        assert_eq!(edit_distance("nb-213", "nb-123"), 2);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("naïve", "naive"), 1);
+    }
+
+    #[test]
+    fn closest_prefers_the_smallest_distance_then_the_first_listed() {
+        let listed = ids(&["research", "nb-124", "nb-123"]);
+        assert_eq!(closest("nb-12x", &listed), Some("nb-124"));
+        assert_eq!(closest("nb-1230", &listed), Some("nb-123"));
+        assert_eq!(closest("reserch", &listed), Some("research"));
+    }
+
+    #[test]
+    fn closest_ignores_unrelated_ids() {
+        let listed = ids(&["a1b2c3", "zzzzzz"]);
+        assert_eq!(closest("nb-123", &listed), None);
+        assert_eq!(closest("nb-123", &[]), None);
+        // Short IDs still allow one edit.
+        assert_eq!(closest("ab", &ids(&["ac"])), Some("ac"));
+    }
+}
@@ -0,0 +1,272 @@
+use reqwest::header::HeaderMap;
+use reqwest::{Method, Url};
+use serde_json::Value;
+
+use crate::client::{NblmClient, TraceContext};
+use crate::error::{Error, Result};
+
+impl NblmClient {
+    /// Call an endpoint the typed API does not cover yet, reusing auth, retries, the user
+    /// project header and the base URL.
+    ///
+    /// `path` is resolved against the parent path (`projects/.../locations/...`) unless it starts
+    /// with `/`, in which case it is taken relative to the base URL. The resolved URL must stay
+    /// under the base URL. An empty response body is returned as `Value::Null`.
+    pub async fn raw_request(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Value>,
+        query: &[(&str, &str)],
+    ) -> Result<Value> {
+        self.send_raw(method, path, body, query, HeaderMap::new())
+            .await
+    }
+
+    /// [`NblmClient::raw_request`] sent as part of `trace` instead of the client's
+    /// [`NblmClient::with_trace_context`] context.
+    pub async fn raw_request_with_trace(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Value>,
+        query: &[(&str, &str)],
+        trace: &TraceContext,
+    ) -> Result<Value> {
+        self.send_raw(method, path, body, query, trace.headers())
+            .await
+    }
+
+    async fn send_raw(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Value>,
+        query: &[(&str, &str)],
+        headers: HeaderMap,
+    ) -> Result<Value> {
+        let mut url = resolve_raw_url(
+            self.environment.base_url(),
+            self.environment.parent_path(),
+            path,
+        )?;
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+
+        let bytes = self
+            .http
+            .request_bytes(method, url, body.as_ref(), headers)
+            .await?;
+        if bytes.iter().all(u8::is_ascii_whitespace) {
+            return Ok(Value::Null);
+        }
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+}
+
+/// URL for a [`NblmClient::raw_request`] path, refusing anything that leaves `base`.
+fn resolve_raw_url(base: &str, parent: &str, path: &str) -> Result<Url> {
+    let base = base.trim_end_matches('/');
+    let url = match path.strip_prefix('/') {
+        Some(absolute) => Url::parse(&format!("{base}/{absolute}"))?,
+        None => Url::parse(&format!("{base}/{parent}/{path}"))?,
+    };
+
+    let base_url = Url::parse(base)?;
+    let base_path = base_url.path().trim_end_matches('/');
+    let same_origin = url.scheme() == base_url.scheme()
+        && url.host_str() == base_url.host_str()
+        && url.port_or_known_default() == base_url.port_or_known_default();
+    let under_base = url
+        .path()
+        .strip_prefix(base_path)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'));
+    if !same_origin || !under_base {
+        return Err(Error::Validation(format!(
+            "path '{path}' resolves to {url}, outside the base URL {base}"
+        )));
+    }
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::StaticTokenProvider;
+    use crate::env::EnvironmentConfig;
+    use serde_json::json;
+    use std::sync::Arc;
+    use wiremock::matchers::{body_json, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const BASE: &str = "http://localhost:8080/v1alpha";
+    const PARENT: &str = "projects/123/locations/global";
+
+    fn client(server: &MockServer) -> NblmClient {
+        let provider = Arc::new(StaticTokenProvider::new("test-token"));
+        let env = EnvironmentConfig::enterprise("123", "global", "global").unwrap();
+        NblmClient::new(provider, env)
+            .unwrap()
+            .with_base_url(format!("{}/v1alpha", server.uri()))
+            .unwrap()
+    }
+
+    #[test]
+    fn relative_paths_resolve_under_the_parent() {
+        let url = resolve_raw_url(BASE, PARENT, "notebooks/nb1:share").unwrap();
+        assert_eq!(
+            url.as_str(),
+            "http://localhost:8080/v1alpha/projects/123/locations/global/notebooks/nb1:share"
+        );
+    }
+
+    #[test]
+    fn leading_slash_resolves_under_the_base_url() {
+        let url = resolve_raw_url(BASE, PARENT, "/projects/999/locations/us/notebooks").unwrap();
+        assert_eq!(
+            url.as_str(),
+            "http://localhost:8080/v1alpha/projects/999/locations/us/notebooks"
+        );
+    }
+
+    #[rstest::rstest]
+    #[case::dot_dot("/../admin")]
+    #[case::nested_dot_dot("notebooks/../../../../../../etc")]
+    #[case::encoded_dot_dot("/%2e%2e/admin")]
+    #[case::sibling_prefix("/../v1alphaX/notebooks")]
+    #[case::backslash("/..\\..\\admin")]
+    #[test]
+    fn paths_leaving_the_base_url_are_rejected(#[case] raw_path: &str) {
+        let err = resolve_raw_url(BASE, PARENT, raw_path).unwrap_err();
+        assert!(matches!(err, Error::Validation(_)), "{err}");
+        assert!(err.to_string().contains("outside the base URL"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn get_round_trip_with_query_params() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(
+                "/v1alpha/projects/123/locations/global/notebooks:listRecentlyViewed",
+            ))
+            .and(query_param("pageSize", "5"))
+            .and(query_param("filter", "title=a b"))
+            .and(header("authorization", "Bearer test-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "notebooks": [{"title": "A", "futureField": 1}]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let value = client(&server)
+            .raw_request(
+                Method::GET,
+                "notebooks:listRecentlyViewed",
+                None,
+                &[("pageSize", "5"), ("filter", "title=a b")],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(value["notebooks"][0]["futureField"], 1);
+    }
+
+    #[tokio::test]
+    async fn post_round_trip_sends_body_and_handles_empty_response() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path(
+                "/v1alpha/projects/123/locations/global/notebooks/nb1:share",
+            ))
+            .and(body_json(json!({"accountAndRoles": []})))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let value = client(&server)
+            .raw_request(
+                Method::POST,
+                "notebooks/nb1:share",
+                Some(json!({"accountAndRoles": []})),
+                &[],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(value, Value::Null);
+    }
+
+    #[tokio::test]
+    async fn traversal_is_rejected_before_any_request() {
+        let server = MockServer::start().await;
+
+        let err = client(&server)
+            .raw_request(Method::GET, "/../../secrets", None, &[])
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, Error::Validation(_)), "{err}");
+        let requests = server.received_requests().await.unwrap_or_default();
+        assert!(requests.is_empty());
+    }
+
+    #[tokio::test]
+    async fn http_errors_keep_the_api_message() {
+        let server = MockServer::start().await;
+        Mock::given(method("DELETE"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+                "error": {"code": 404, "message": "Notebook not found"}
+            })))
+            .mount(&server)
+            .await;
+
+        let err = client(&server)
+            .raw_request(Method::DELETE, "notebooks/missing", None, &[])
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("Notebook not found"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn raw_request_with_trace_overrides_the_client_context() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+            .mount(&server)
+            .await;
+        let client_wide =
+            TraceContext::parse("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+                .unwrap()
+                .with_tracestate("vendor=client")
+                .unwrap();
+        let per_request =
+            TraceContext::parse("00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-00").unwrap();
+        let client = client(&server).with_trace_context(client_wide.clone());
+
+        client
+            .raw_request_with_trace(Method::GET, "notebooks/nb1", None, &[], &per_request)
+            .await
+            .unwrap();
+        client
+            .raw_request(Method::GET, "notebooks/nb2", None, &[])
+            .await
+            .unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        let traceparents: Vec<_> = requests
+            .iter()
+            .map(|request| request.headers["traceparent"].to_str().unwrap())
+            .collect();
+        assert_eq!(
+            traceparents,
+            [per_request.traceparent(), client_wide.traceparent()]
+        );
+        assert!(!requests[0].headers.contains_key("tracestate"));
+        assert_eq!(requests[1].headers["tracestate"], "vendor=client");
+        assert_eq!(requests[0].headers.get_all("traceparent").iter().count(), 1);
+    }
+}
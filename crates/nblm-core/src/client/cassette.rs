@@ -0,0 +1,394 @@
+//! Record-and-replay HTTP transport for tests (`cassette` feature).
+//!
+//! A cassette is a JSON file of request/response pairs. In record mode requests go to the real
+//! API and each exchange is appended to the cassette; in replay mode no request leaves the process
+//! and responses come from the cassette instead. Requests are matched on method, path (with
+//! query) and a SHA-256 digest of the body, each recorded interaction is used at most once, and
+//! the scheme and host are ignored so a cassette replays against any base URL.
+//!
+//! Request headers are never written, so bearer tokens do not reach the file. Other secrets, such
+//! as the project number in every path, are replaced by [`CassetteTransport::redact`] before the
+//! interaction is stored. Replay applies the same replacements to the outgoing request before
+//! matching.
+
+use std::path::{Path, PathBuf};
+
+use parking_lot::Mutex;
+use reqwest::{Client, Request, Response};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::error::{Error, Result};
+
+/// Set to `1` to make [`CassetteTransport::from_env`] record instead of replay.
+pub const CASSETTE_RECORD_ENV: &str = "NBLM_CASSETTE_RECORD";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    Record,
+    Replay,
+}
+
+pub struct CassetteTransport {
+    path: PathBuf,
+    mode: CassetteMode,
+    redactions: Vec<(String, String)>,
+    state: Mutex<CassetteState>,
+}
+
+#[derive(Default)]
+struct CassetteState {
+    interactions: Vec<Interaction>,
+    used: Vec<bool>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedRequest {
+    method: String,
+    path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body_sha256: Option<String>,
+    /// JSON request bodies, kept for reading the cassette; matching only uses the digest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    body: RecordedBody,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RecordedBody {
+    Json(Value),
+    Text(String),
+}
+
+impl CassetteTransport {
+    /// Replay the interactions stored at `path`.
+    pub fn replay(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let content = std::fs::read_to_string(&path)
+            .map_err(|err| cassette_error(&path, format!("failed to read: {err}")))?;
+        let file: CassetteFile = serde_json::from_str(&content)
+            .map_err(|err| cassette_error(&path, format!("failed to parse: {err}")))?;
+        let used = vec![false; file.interactions.len()];
+        Ok(Self::with_state(
+            path,
+            CassetteMode::Replay,
+            CassetteState {
+                interactions: file.interactions,
+                used,
+            },
+        ))
+    }
+
+    /// Send requests to the real API and collect them for [`CassetteTransport::save`].
+    pub fn record(path: impl Into<PathBuf>) -> Self {
+        Self::with_state(path.into(), CassetteMode::Record, CassetteState::default())
+    }
+
+    /// [`CassetteTransport::record`] when `NBLM_CASSETTE_RECORD=1`, otherwise
+    /// [`CassetteTransport::replay`].
+    pub fn from_env(path: impl Into<PathBuf>) -> Result<Self> {
+        match std::env::var(CASSETTE_RECORD_ENV).as_deref() {
+            Ok("1") => Ok(Self::record(path)),
+            _ => Self::replay(path),
+        }
+    }
+
+    fn with_state(path: PathBuf, mode: CassetteMode, state: CassetteState) -> Self {
+        Self {
+            path,
+            mode,
+            redactions: Vec::new(),
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Replace `secret` with `placeholder` in paths and bodies before they are stored or matched.
+    /// Empty secrets are ignored.
+    pub fn redact(mut self, secret: impl Into<String>, placeholder: impl Into<String>) -> Self {
+        let secret = secret.into();
+        if !secret.is_empty() {
+            self.redactions.push((secret, placeholder.into()));
+        }
+        self
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write the recorded interactions to the cassette file. Does nothing in replay mode.
+    pub fn save(&self) -> Result<()> {
+        if self.mode == CassetteMode::Replay {
+            return Ok(());
+        }
+        let file = CassetteFile {
+            interactions: self.state.lock().interactions.clone(),
+        };
+        let mut content = serde_json::to_string_pretty(&file)?;
+        content.push('\n');
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|err| cassette_error(&self.path, format!("failed to create: {err}")))?;
+        }
+        std::fs::write(&self.path, content)
+            .map_err(|err| cassette_error(&self.path, format!("failed to write: {err}")))
+    }
+
+    pub(crate) async fn execute(&self, client: &Client, request: Request) -> Result<Response> {
+        let recorded = self.describe(&request);
+        match self.mode {
+            CassetteMode::Replay => self.replay_response(&recorded),
+            CassetteMode::Record => {
+                let response = client.execute(request).await.map_err(Error::Request)?;
+                let status = response.status();
+                let body = response.bytes().await.map_err(Error::Request)?;
+                let text = self.apply_redactions(&String::from_utf8_lossy(&body));
+                let body_value = match serde_json::from_str(&text) {
+                    Ok(value) => RecordedBody::Json(value),
+                    Err(_) => RecordedBody::Text(text),
+                };
+                let mut state = self.state.lock();
+                state.interactions.push(Interaction {
+                    request: recorded,
+                    response: RecordedResponse {
+                        status: status.as_u16(),
+                        body: body_value,
+                    },
+                });
+                state.used.push(true);
+                drop(state);
+                build_response(status.as_u16(), body.to_vec())
+            }
+        }
+    }
+
+    fn describe(&self, request: &Request) -> RecordedRequest {
+        let url = request.url();
+        let path = match url.query() {
+            Some(query) => format!("{}?{query}", url.path()),
+            None => url.path().to_string(),
+        };
+        let body = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .filter(|bytes| !bytes.is_empty());
+        let (body_sha256, body) = match body {
+            Some(bytes) => {
+                let text = std::str::from_utf8(bytes)
+                    .ok()
+                    .map(|text| self.apply_redactions(text));
+                let digest = match &text {
+                    Some(text) => Sha256::digest(text.as_bytes()),
+                    None => Sha256::digest(bytes),
+                };
+                let value = text.and_then(|text| serde_json::from_str(&text).ok());
+                (Some(hex(&digest)), value)
+            }
+            None => (None, None),
+        };
+        RecordedRequest {
+            method: request.method().to_string(),
+            path: self.apply_redactions(&path),
+            body_sha256,
+            body,
+        }
+    }
+
+    fn replay_response(&self, request: &RecordedRequest) -> Result<Response> {
+        let mut state = self.state.lock();
+        let CassetteState { interactions, used } = &mut *state;
+        let index = interactions
+            .iter()
+            .zip(used.iter())
+            .position(|(interaction, used)| {
+                !used
+                    && interaction.request.method == request.method
+                    && interaction.request.path == request.path
+                    && interaction.request.body_sha256 == request.body_sha256
+            })
+            .ok_or_else(|| {
+                cassette_error(
+                    &self.path,
+                    format!(
+                        "no unused interaction for {} {} (body sha256 {})",
+                        request.method,
+                        request.path,
+                        request.body_sha256.as_deref().unwrap_or("none")
+                    ),
+                )
+            })?;
+        used[index] = true;
+        let response = &interactions[index].response;
+        let body = match &response.body {
+            RecordedBody::Json(value) => serde_json::to_vec(value)?,
+            RecordedBody::Text(text) => text.clone().into_bytes(),
+        };
+        build_response(response.status, body)
+    }
+
+    fn apply_redactions(&self, text: &str) -> String {
+        self.redactions
+            .iter()
+            .fold(text.to_string(), |text, (secret, placeholder)| {
+                text.replace(secret.as_str(), placeholder)
+            })
+    }
+}
+
+fn build_response(status: u16, body: Vec<u8>) -> Result<Response> {
+    let response = http::Response::builder()
+        .status(status)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body)
+        .map_err(|err| Error::Cassette(format!("invalid recorded response: {err}")))?;
+    Ok(Response::from(response))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn cassette_error(path: &Path, message: String) -> Error {
+    Error::Cassette(format!("{}: {message}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::tempdir;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn post(url: &str, body: &Value) -> Request {
+        Client::new()
+            .post(url)
+            .bearer_auth("secret-token")
+            .body(serde_json::to_vec(body).unwrap())
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn records_redacted_interactions_and_replays_them() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1alpha/projects/987654/items"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "name": "projects/987654/items/1"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempdir().unwrap();
+        let cassette_path = dir.path().join("nested").join("items.json");
+        let url = format!("{}/v1alpha/projects/987654/items", server.uri());
+        let body = json!({"parent": "projects/987654"});
+
+        let recorder = CassetteTransport::record(&cassette_path).redact("987654", "123456");
+        let response = recorder
+            .execute(&Client::new(), post(&url, &body))
+            .await
+            .unwrap();
+        // The caller still sees the real response.
+        let live: Value = response.json().await.unwrap();
+        assert_eq!(live["name"], "projects/987654/items/1");
+        recorder.save().unwrap();
+
+        let content = std::fs::read_to_string(&cassette_path).unwrap();
+        assert!(!content.contains("987654"), "{content}");
+        assert!(!content.contains("secret-token"), "{content}");
+
+        let player = CassetteTransport::replay(&cassette_path)
+            .unwrap()
+            .redact("987654", "123456");
+        let replayed = player
+            .execute(
+                &Client::new(),
+                post(
+                    "https://example.invalid/v1alpha/projects/987654/items",
+                    &body,
+                ),
+            )
+            .await
+            .unwrap();
+        assert_eq!(replayed.status(), 200);
+        let replayed: Value = replayed.json().await.unwrap();
+        assert_eq!(replayed["name"], "projects/123456/items/1");
+    }
+
+    #[tokio::test]
+    async fn replay_rejects_unmatched_and_reused_requests() {
+        let dir = tempdir().unwrap();
+        let cassette_path = dir.path().join("get.json");
+        std::fs::write(
+            &cassette_path,
+            serde_json::to_string(&json!({
+                "interactions": [{
+                    "request": {"method": "GET", "path": "/v1alpha/items"},
+                    "response": {"status": 404, "body": {"text": "not found"}}
+                }]
+            }))
+            .unwrap(),
+        )
+        .unwrap();
+        let player = CassetteTransport::replay(&cassette_path).unwrap();
+        let client = Client::new();
+        let get = || {
+            client
+                .get("http://localhost/v1alpha/items")
+                .build()
+                .unwrap()
+        };
+
+        let response = player.execute(&client, get()).await.unwrap();
+        assert_eq!(response.status(), 404);
+        assert_eq!(response.text().await.unwrap(), "not found");
+
+        let err = player.execute(&client, get()).await.unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("no unused interaction for GET /v1alpha/items"),
+            "{err}"
+        );
+
+        let err = player
+            .execute(&client, post("http://localhost/v1alpha/items", &json!({})))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("POST /v1alpha/items"), "{err}");
+    }
+
+    #[test]
+    fn replay_reports_missing_cassettes() {
+        let err = CassetteTransport::replay("/nonexistent/cassette.json")
+            .err()
+            .unwrap();
+        assert!(err
+            .to_string()
+            .contains("/nonexistent/cassette.json: failed to read"));
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Content type sent when nothing is known about a file.
+pub const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+
+/// Extensions whose usual MIME type NotebookLM does not parse well, and the type to upload them
+/// with instead. Keys are lowercase and without the dot.
+///
+/// - Markdown is sent as `text/markdown` whichever extension it has (`.md` otherwise has no
+///   known type at all), so headings and lists are kept.
+/// - JSON Lines and subtitle files (`.srt`, `.vtt`) have no type NotebookLM reads, so they go as
+///   plain text.
+/// - `.m4a` is guessed as the non-standard `audio/m4a`; the registered type is `audio/mp4`.
+pub const BUILTIN_CONTENT_TYPE_OVERRIDES: &[(&str, &str)] = &[
+    ("jsonl", "text/plain"),
+    ("m4a", "audio/mp4"),
+    ("markdown", "text/markdown"),
+    ("md", "text/markdown"),
+    ("srt", "text/plain"),
+    ("vtt", "text/plain"),
+];
+
+/// The content type to upload `path` with, from its extension.
+///
+/// `overrides` maps extensions (any case, with or without the leading dot) to content types and
+/// wins over [`BUILTIN_CONTENT_TYPE_OVERRIDES`], which wins over the usual MIME type for the
+/// extension. Files with no known type get [`DEFAULT_CONTENT_TYPE`]. When an override replaces
+/// the usual type, the change is logged at debug level.
+pub fn infer_content_type(path: &Path, overrides: &HashMap<String, String>) -> String {
+    let guessed = mime_guess::from_path(path)
+        .first()
+        .map(|mime| mime.essence_str().to_string());
+    let Some(extension) = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase)
+    else {
+        return guessed.unwrap_or_else(|| DEFAULT_CONTENT_TYPE.to_string());
+    };
+    let overridden = overrides
+        .iter()
+        .find(|(key, _)| key.trim_start_matches('.').eq_ignore_ascii_case(&extension))
+        .map(|(_, content_type)| content_type.as_str())
+        .or_else(|| {
+            BUILTIN_CONTENT_TYPE_OVERRIDES
+                .iter()
+                .find(|(key, _)| *key == extension)
+                .map(|(_, content_type)| *content_type)
+        });
+    match overridden {
+        Some(content_type) => {
+            if guessed.as_deref() != Some(content_type) {
+                tracing::debug!(
+                    "content type of {} overridden: {} -> {content_type}",
+                    path.display(),
+                    guessed.as_deref().unwrap_or(DEFAULT_CONTENT_TYPE)
+                );
+            }
+            content_type.to_string()
+        }
+        None => guessed.unwrap_or_else(|| DEFAULT_CONTENT_TYPE.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn infer(path: &str, overrides: &[(&str, &str)]) -> String {
+        let overrides = overrides
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        infer_content_type(Path::new(path), &overrides)
+    }
+
+    #[test]
+    fn builtin_overrides_apply_to_any_case() {
+        let cases = [
+            ("notes.md", "text/markdown"),
+            ("README.MD", "text/markdown"),
+            ("notes.markdown", "text/markdown"),
+            ("events.jsonl", "text/plain"),
+            ("talk.srt", "text/plain"),
+            ("talk.vtt", "text/plain"),
+            ("interview.m4a", "audio/mp4"),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(infer(path, &[]), expected, "{path}");
+        }
+    }
+
+    #[test]
+    fn other_extensions_fall_through_to_the_usual_type() {
+        let cases = [
+            ("paper.pdf", "application/pdf"),
+            ("notes.txt", "text/plain"),
+            ("episode.mp3", "audio/mpeg"),
+            ("archive.unknownext", DEFAULT_CONTENT_TYPE),
+            ("Makefile", DEFAULT_CONTENT_TYPE),
+            ("dir.d/no_extension", DEFAULT_CONTENT_TYPE),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(infer(path, &[]), expected, "{path}");
+        }
+    }
+
+    #[test]
+    fn given_overrides_win_over_builtin_ones() {
+        let overrides = [(".MD", "text/plain"), ("log", "text/plain")];
+        assert_eq!(infer("notes.md", &overrides), "text/plain");
+        assert_eq!(infer("server.log", &overrides), "text/plain");
+        assert_eq!(infer("events.jsonl", &overrides), "text/plain");
+        assert_eq!(infer("paper.pdf", &overrides), "application/pdf");
+    }
+
+    #[test]
+    fn builtin_table_is_sorted_and_lowercase() {
+        assert!(BUILTIN_CONTENT_TYPE_OVERRIDES
+            .windows(2)
+            .all(|pair| pair[0].0 < pair[1].0));
+        assert!(BUILTIN_CONTENT_TYPE_OVERRIDES
+            .iter()
+            .all(|(key, _)| *key == key.to_ascii_lowercase() && !key.starts_with('.')));
+    }
+}
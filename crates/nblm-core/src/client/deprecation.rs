@@ -0,0 +1,235 @@
+use std::fmt;
+use std::sync::Arc;
+
+use reqwest::header::{HeaderMap, HeaderName, WARNING};
+use reqwest::Method;
+use time::OffsetDateTime;
+use url::Url;
+
+use super::http::path_template;
+
+const DEPRECATION: HeaderName = HeaderName::from_static("deprecation");
+const SUNSET: HeaderName = HeaderName::from_static("sunset");
+
+/// What a response said about its endpoint going away, from the `Deprecation` (RFC 9745),
+/// `Sunset` (RFC 8594) and `Warning` headers. Reported to the
+/// [`NblmClient::with_deprecation_observer`] callback.
+///
+/// [`NblmClient::with_deprecation_observer`]: super::NblmClient::with_deprecation_observer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeprecationNotice {
+    pub method: Method,
+    /// URL path with resource IDs replaced by placeholders, so calls to the same endpoint for
+    /// different notebooks compare equal.
+    pub path: String,
+    /// `Deprecation` was sent.
+    pub deprecated: bool,
+    /// When the endpoint was or will be deprecated, if `Deprecation` carried a date.
+    pub deprecated_at: Option<OffsetDateTime>,
+    /// When the endpoint stops working, from `Sunset`.
+    pub sunset: Option<OffsetDateTime>,
+    /// Text of each `Warning` header.
+    pub warnings: Vec<String>,
+}
+
+impl DeprecationNotice {
+    /// The notice for a response to `method url` with `headers`, or `None` when it carries none of
+    /// the headers.
+    pub fn from_headers(method: &Method, url: &Url, headers: &HeaderMap) -> Option<Self> {
+        let text = |name: &HeaderName| {
+            headers
+                .get_all(name)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .collect::<Vec<_>>()
+        };
+        let deprecation = text(&DEPRECATION);
+        let sunset = text(&SUNSET);
+        let warnings: Vec<String> = text(&WARNING).into_iter().map(warning_text).collect();
+        if deprecation.is_empty() && sunset.is_empty() && warnings.is_empty() {
+            return None;
+        }
+        Some(Self {
+            method: method.clone(),
+            path: path_template(url.path()),
+            deprecated: !deprecation.is_empty(),
+            deprecated_at: deprecation.into_iter().find_map(parse_deprecation),
+            sunset: sunset.into_iter().find_map(parse_sunset),
+            warnings,
+        })
+    }
+
+    /// One line for users, e.g. `the API reports GET /v1alpha/.../notebooks/{notebook} is
+    /// deprecated, sunset on 2026-06-30`.
+    pub fn describe(&self) -> String {
+        let mut line = format!("the API reports {} {}", self.method, self.path);
+        match (self.deprecated, self.sunset) {
+            (true, Some(sunset)) => {
+                line.push_str(&format!(" is deprecated, sunset on {}", date(sunset)))
+            }
+            (true, None) => line.push_str(" is deprecated"),
+            (false, Some(sunset)) => line.push_str(&format!(" will be sunset on {}", date(sunset))),
+            (false, None) => line.push_str(" returned a warning"),
+        }
+        if let Some(deprecated_at) = self.deprecated_at {
+            line.push_str(&format!(" (deprecated since {})", date(deprecated_at)));
+        }
+        for warning in &self.warnings {
+            line.push_str(&format!(": {warning}"));
+        }
+        line
+    }
+}
+
+/// Callback invoked for each response that carries a [`DeprecationNotice`].
+#[derive(Clone)]
+pub struct DeprecationObserver(Arc<dyn Fn(&DeprecationNotice) + Send + Sync>);
+
+impl fmt::Debug for DeprecationObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DeprecationObserver(..)")
+    }
+}
+
+impl DeprecationObserver {
+    pub fn new(observer: impl Fn(&DeprecationNotice) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(observer))
+    }
+
+    pub(crate) fn notify(&self, method: &Method, url: &Url, headers: &HeaderMap) {
+        if let Some(notice) = DeprecationNotice::from_headers(method, url, headers) {
+            (self.0)(&notice);
+        }
+    }
+}
+
+/// `Sunset` is an HTTP-date.
+pub(crate) fn parse_sunset(value: &str) -> Option<OffsetDateTime> {
+    httpdate::parse_http_date(value.trim())
+        .ok()
+        .map(OffsetDateTime::from)
+}
+
+/// `Deprecation` is `@<unix seconds>` (RFC 9745); drafts of it used an HTTP-date or `true`, which
+/// carries no date.
+pub(crate) fn parse_deprecation(value: &str) -> Option<OffsetDateTime> {
+    let value = value.trim();
+    match value.strip_prefix('@') {
+        Some(seconds) => OffsetDateTime::from_unix_timestamp(seconds.parse().ok()?).ok(),
+        None => parse_sunset(value),
+    }
+}
+
+/// The quoted text of a `Warning` value such as `299 - "Deprecated API"`, or the whole value
+/// when it has no quoted part.
+fn warning_text(value: &str) -> String {
+    let quoted = value
+        .split_once('"')
+        .and_then(|(_, rest)| rest.split_once('"'))
+        .map(|(text, _)| text);
+    quoted.unwrap_or(value).to_string()
+}
+
+/// `YYYY-MM-DD`, in UTC.
+fn date(at: OffsetDateTime) -> String {
+    at.to_offset(time::UtcOffset::UTC).date().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+    use time::format_description::well_known::Rfc3339;
+
+    fn utc(rfc3339: &str) -> OffsetDateTime {
+        OffsetDateTime::parse(rfc3339, &Rfc3339).unwrap()
+    }
+
+    fn headers(pairs: &[(&str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(
+                HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                HeaderValue::from_str(value).unwrap(),
+            );
+        }
+        headers
+    }
+
+    fn notice(pairs: &[(&str, &str)]) -> Option<DeprecationNotice> {
+        let url = Url::parse(
+            "https://example.com/v1alpha/projects/1/locations/global/notebooks/nb1/sources/s1",
+        )
+        .unwrap();
+        DeprecationNotice::from_headers(&Method::GET, &url, &headers(pairs))
+    }
+
+    #[test]
+    fn sunset_is_an_http_date() {
+        assert_eq!(
+            parse_sunset("Tue, 30 Jun 2026 23:59:59 GMT"),
+            Some(utc("2026-06-30T23:59:59Z"))
+        );
+        assert_eq!(parse_sunset("2026-06-30"), None);
+        assert_eq!(parse_sunset(""), None);
+    }
+
+    #[test]
+    fn deprecation_accepts_unix_seconds_and_http_dates() {
+        assert_eq!(
+            parse_deprecation("@1688169599"),
+            Some(utc("2023-06-30T23:59:59Z"))
+        );
+        assert_eq!(
+            parse_deprecation("Sun, 11 Nov 2018 23:59:59 GMT"),
+            Some(utc("2018-11-11T23:59:59Z"))
+        );
+        assert_eq!(parse_deprecation("true"), None);
+        assert_eq!(parse_deprecation("@soon"), None);
+    }
+
+    #[test]
+    fn responses_without_the_headers_have_no_notice() {
+        assert_eq!(notice(&[("content-type", "application/json")]), None);
+    }
+
+    #[test]
+    fn notices_describe_the_endpoint_and_dates() {
+        let notice = notice(&[
+            ("Deprecation", "@1688169599"),
+            ("Sunset", "Tue, 30 Jun 2026 23:59:59 GMT"),
+            ("Warning", "299 - \"Use v1beta instead\""),
+        ])
+        .unwrap();
+        assert!(notice.deprecated);
+        assert_eq!(
+            notice.path,
+            "/v1alpha/projects/{project}/locations/{location}/notebooks/{notebook}/sources/{source}"
+        );
+        assert_eq!(notice.warnings, ["Use v1beta instead"]);
+        assert_eq!(
+            notice.describe(),
+            "the API reports GET /v1alpha/projects/{project}/locations/{location}/notebooks/{notebook}/sources/{source} \
+             is deprecated, sunset on 2026-06-30 (deprecated since 2023-06-30): Use v1beta instead"
+        );
+    }
+
+    #[test]
+    fn a_dateless_deprecation_or_a_lone_warning_still_counts() {
+        let deprecated = notice(&[("Deprecation", "true")]).unwrap();
+        assert!(deprecated.deprecated);
+        assert_eq!(deprecated.deprecated_at, None);
+        assert!(deprecated.describe().ends_with("is deprecated"));
+
+        let sunset = notice(&[("Sunset", "Tue, 30 Jun 2026 23:59:59 GMT")]).unwrap();
+        assert!(sunset.describe().ends_with("will be sunset on 2026-06-30"));
+
+        let warning = notice(&[("Warning", "299 endpoint changing")]).unwrap();
+        assert!(!warning.deprecated);
+        assert!(warning
+            .describe()
+            .ends_with("returned a warning: 299 endpoint changing"));
+    }
+}
@@ -0,0 +1,229 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::error::{Error, Result};
+
+/// Shortcodes accepted by [`normalize_emoji`], sorted by name.
+const SHORTCODES: &[(&str, &str)] = &[
+    ("art", "🎨"),
+    ("bar_chart", "📊"),
+    ("blue_book", "📘"),
+    ("book", "📖"),
+    ("books", "📚"),
+    ("brain", "🧠"),
+    ("briefcase", "💼"),
+    ("bulb", "💡"),
+    ("calendar", "📅"),
+    ("chart_with_upwards_trend", "📈"),
+    ("computer", "💻"),
+    ("dna", "🧬"),
+    ("earth_africa", "🌍"),
+    ("earth_americas", "🌎"),
+    ("earth_asia", "🌏"),
+    ("fire", "🔥"),
+    ("gear", "⚙️"),
+    ("globe_with_meridians", "🌐"),
+    ("headphones", "🎧"),
+    ("heart", "❤️"),
+    ("link", "🔗"),
+    ("mag", "🔍"),
+    ("memo", "📝"),
+    ("microphone", "🎤"),
+    ("microscope", "🔬"),
+    ("mortar_board", "🎓"),
+    ("newspaper", "📰"),
+    ("notebook", "📓"),
+    ("pushpin", "📌"),
+    ("robot", "🤖"),
+    ("rocket", "🚀"),
+    ("scales", "⚖️"),
+    ("seedling", "🌱"),
+    ("sparkles", "✨"),
+    ("star", "⭐"),
+    ("telescope", "🔭"),
+    ("test_tube", "🧪"),
+];
+
+const KEYCAP: char = '\u{20E3}';
+
+/// Check a notebook emoji and return the form to send to the API.
+///
+/// Accepts a single emoji, including one made of several code points that displays as one
+/// (a flag, a keycap, a skin tone or a ZWJ sequence such as 👩‍🔬), or a `:shortcode:` from a
+/// short list (`:rocket:`, `:books:`, ...), which is replaced by its emoji. Anything else, such
+/// as two emoji or a plain word, is an [`Error::Validation`] that lists the accepted forms.
+pub fn normalize_emoji(input: &str) -> Result<String> {
+    let emoji = input.trim();
+    if let Some(code) = emoji
+        .strip_prefix(':')
+        .and_then(|rest| rest.strip_suffix(':'))
+        .filter(|code| !code.is_empty())
+    {
+        return SHORTCODES
+            .iter()
+            .find(|(name, _)| *name == code)
+            .map(|(_, emoji)| emoji.to_string())
+            .ok_or_else(|| invalid(input, "unknown shortcode"));
+    }
+    // What displays as one emoji is one extended grapheme cluster.
+    let mut graphemes = emoji.graphemes(true);
+    let Some(first) = graphemes.next() else {
+        return Err(invalid(input, "it is empty"));
+    };
+    if !is_emoji(first) {
+        return Err(invalid(input, "it is not an emoji"));
+    }
+    if graphemes.next().is_some() {
+        return Err(invalid(input, "only a single emoji is allowed"));
+    }
+    Ok(emoji.to_string())
+}
+
+fn invalid(input: &str, reason: &str) -> Error {
+    let shortcodes: Vec<String> = SHORTCODES
+        .iter()
+        .map(|(name, _)| format!(":{name}:"))
+        .collect();
+    Error::Validation(format!(
+        "invalid emoji '{input}': {reason}; expected a single emoji such as 📘 or 👩‍🔬, or one of the shortcodes {}",
+        shortcodes.join(", ")
+    ))
+}
+
+/// Whether a grapheme cluster is an emoji: a flag (two regional indicators), a keycap, or one
+/// that starts with a pictograph. Segmentation already keeps modifiers, variation selectors,
+/// tags and ZWJ joins in the cluster, so only its first code point needs checking.
+fn is_emoji(grapheme: &str) -> bool {
+    let mut chars = grapheme.chars();
+    match chars.next() {
+        Some(first) if is_regional_indicator(first) => {
+            chars.next().is_some_and(is_regional_indicator)
+        }
+        Some(first) if first.is_ascii_digit() || first == '#' || first == '*' => {
+            grapheme.ends_with(KEYCAP)
+        }
+        Some(first) => is_pictograph(first),
+        None => false,
+    }
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&c)
+}
+
+fn is_skin_tone(c: char) -> bool {
+    ('\u{1F3FB}'..='\u{1F3FF}').contains(&c)
+}
+
+/// Code points that can start an emoji: the pictograph blocks plus the older symbols that
+/// have an emoji presentation. Regional indicators and skin tones only count in sequences.
+fn is_pictograph(c: char) -> bool {
+    if is_regional_indicator(c) || is_skin_tone(c) {
+        return false;
+    }
+    matches!(
+        c,
+        '\u{1F000}'..='\u{1FAFF}'
+            | '\u{2600}'..='\u{27BF}'
+            | '\u{2300}'..='\u{23FF}'
+            | '\u{2B05}'..='\u{2B55}'
+            | '\u{2194}'..='\u{21AA}'
+            | '\u{25AA}'..='\u{25FE}'
+            | '\u{2934}'..='\u{2935}'
+            | '\u{00A9}'
+            | '\u{00AE}'
+            | '\u{203C}'
+            | '\u{2049}'
+            | '\u{2122}'
+            | '\u{2139}'
+            | '\u{24C2}'
+            | '\u{3030}'
+            | '\u{303D}'
+            | '\u{3297}'
+            | '\u{3299}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_single_emoji() {
+        let cases = [
+            ("📘", "📘"),
+            (" 🚀 ", "🚀"),
+            ("⭐", "⭐"),
+            ("❤️", "❤️"),
+            ("👍🏽", "👍🏽"),
+            ("🇯🇵", "🇯🇵"),
+            ("#️⃣", "#️⃣"),
+            (
+                "🏴\u{E0067}\u{E0062}\u{E0073}\u{E0063}\u{E0074}\u{E007F}",
+                "🏴\u{E0067}\u{E0062}\u{E0073}\u{E0063}\u{E0074}\u{E007F}",
+            ),
+            // ZWJ sequences display as one emoji.
+            ("👩‍🔬", "👩‍🔬"),
+            ("👨‍👩‍👧‍👦", "👨‍👩‍👧‍👦"),
+            ("🏳️‍🌈", "🏳️‍🌈"),
+            ("🧑🏽‍💻", "🧑🏽‍💻"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(normalize_emoji(input).unwrap(), expected, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn translates_shortcodes() {
+        let cases = [
+            (":rocket:", "🚀"),
+            (":books:", "📚"),
+            (":brain:", "🧠"),
+            (":microscope:", "🔬"),
+            (" :gear: ", "⚙️"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(normalize_emoji(input).unwrap(), expected, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn every_shortcode_is_a_valid_emoji_and_the_list_is_sorted() {
+        for (name, emoji) in SHORTCODES {
+            assert_eq!(normalize_emoji(emoji).unwrap(), *emoji, ":{name}:");
+        }
+        assert!(SHORTCODES.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn rejects_everything_else() {
+        let cases = [
+            ("🚀🚀", "only a single emoji is allowed"),
+            ("📚 📘", "only a single emoji is allowed"),
+            ("🚀x", "only a single emoji is allowed"),
+            ("rocket", "it is not an emoji"),
+            ("a", "it is not an emoji"),
+            ("1", "it is not an emoji"),
+            ("🇯", "it is not an emoji"),
+            ("🏽", "it is not an emoji"),
+            ("", "it is empty"),
+            (":unicorn_face:", "unknown shortcode"),
+        ];
+        for (input, reason) in cases {
+            let err = normalize_emoji(input).unwrap_err();
+            assert!(matches!(err, Error::Validation(_)), "{input:?}");
+            assert!(err.to_string().contains(reason), "{input:?}: {err}");
+        }
+    }
+
+    #[test]
+    fn the_error_lists_the_accepted_forms() {
+        let message = normalize_emoji("rocket").unwrap_err().to_string();
+        assert!(
+            message.starts_with(
+                "validation error: invalid emoji 'rocket': it is not an emoji; expected a single emoji such as 📘 or 👩‍🔬, or one of the shortcodes :art:, :bar_chart:,"
+            ),
+            "{message}"
+        );
+        assert!(message.ends_with(":test_tube:"), "{message}");
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use reqwest::Url;
+use tokio::time::Instant;
+
+use crate::error::{Error, Result};
+
+/// When to stop sending requests to a host that keeps refusing connections.
+///
+/// After `threshold` connect-level failures in a row, each within `window` of the previous one,
+/// requests to that host fail with [`Error::HostUnavailable`] without being sent until `window`
+/// has passed. The next request is then sent again; any response from the host clears its
+/// record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HostFailureConfig {
+    pub threshold: u32,
+    pub window: Duration,
+}
+
+impl Default for HostFailureConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 3,
+            window: Duration::from_secs(30),
+        }
+    }
+}
+
+impl HostFailureConfig {
+    pub fn with_threshold(mut self, threshold: u32) -> Self {
+        self.threshold = threshold.max(1);
+        self
+    }
+
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct HostState {
+    failures: u32,
+    last_failure: Instant,
+}
+
+/// Consecutive connect failures per `host:port`, shared by every clone of a client.
+#[derive(Debug, Default)]
+pub(crate) struct HostFailureCache {
+    config: HostFailureConfig,
+    hosts: Mutex<HashMap<String, HostState>>,
+}
+
+impl HostFailureCache {
+    pub(crate) fn new(config: HostFailureConfig) -> Self {
+        Self {
+            config,
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Fail with [`Error::HostUnavailable`] when `url`'s host reached the threshold less than a
+    /// window ago.
+    pub(crate) fn check(&self, url: &Url) -> Result<()> {
+        let host = host_key(url);
+        let Some(state) = self.hosts.lock().get(&host).copied() else {
+            return Ok(());
+        };
+        if state.failures < self.config.threshold {
+            return Ok(());
+        }
+        let retry_in = self
+            .config
+            .window
+            .saturating_sub(state.last_failure.elapsed());
+        if retry_in.is_zero() {
+            return Ok(());
+        }
+        Err(Error::HostUnavailable {
+            host,
+            failures: state.failures,
+            retry_in: Duration::from_secs(retry_in.as_secs_f64().ceil() as u64),
+        })
+    }
+
+    /// Count a failed connection to `url`'s host. A failure more than a window after the
+    /// previous one starts a new run.
+    pub(crate) fn record_failure(&self, url: &Url) {
+        let now = Instant::now();
+        let mut hosts = self.hosts.lock();
+        let state = hosts.entry(host_key(url)).or_insert(HostState {
+            failures: 0,
+            last_failure: now,
+        });
+        if now.duration_since(state.last_failure) > self.config.window {
+            state.failures = 0;
+        }
+        state.failures += 1;
+        state.last_failure = now;
+    }
+
+    /// Forget `url`'s host after it answered.
+    pub(crate) fn record_success(&self, url: &Url) {
+        self.hosts.lock().remove(&host_key(url));
+    }
+}
+
+fn host_key(url: &Url) -> String {
+    let host = url.host_str().unwrap_or_default();
+    match url.port_or_known_default() {
+        Some(port) => format!("{host}:{port}"),
+        None => host.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url() -> Url {
+        Url::parse("https://us-discoveryengine.googleapis.com/v1alpha/projects/1").unwrap()
+    }
+
+    fn cache(threshold: u32) -> HostFailureCache {
+        HostFailureCache::new(
+            HostFailureConfig::default()
+                .with_threshold(threshold)
+                .with_window(Duration::from_secs(10)),
+        )
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn fails_fast_after_the_threshold_until_the_window_passes() {
+        let cache = cache(2);
+        cache.record_failure(&url());
+        assert!(cache.check(&url()).is_ok());
+        cache.record_failure(&url());
+
+        tokio::time::advance(Duration::from_secs(4)).await;
+        match cache.check(&url()) {
+            Err(Error::HostUnavailable {
+                host,
+                failures,
+                retry_in,
+            }) => {
+                assert_eq!(host, "us-discoveryengine.googleapis.com:443");
+                assert_eq!(failures, 2);
+                assert_eq!(retry_in, Duration::from_secs(6));
+            }
+            other => panic!("expected HostUnavailable, got {other:?}"),
+        }
+        // Other hosts are unaffected.
+        assert!(cache
+            .check(&Url::parse("http://localhost:8080/v1alpha").unwrap())
+            .is_ok());
+
+        tokio::time::advance(Duration::from_secs(6)).await;
+        assert!(cache.check(&url()).is_ok());
+        // A further failure on the probe request blocks the host again right away.
+        cache.record_failure(&url());
+        assert!(cache.check(&url()).is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn success_resets_the_count() {
+        let cache = cache(2);
+        cache.record_failure(&url());
+        cache.record_success(&url());
+        cache.record_failure(&url());
+        assert!(cache.check(&url()).is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn failures_further_apart_than_the_window_are_not_consecutive() {
+        let cache = cache(2);
+        cache.record_failure(&url());
+        tokio::time::advance(Duration::from_secs(11)).await;
+        cache.record_failure(&url());
+        assert!(cache.check(&url()).is_ok());
+    }
+
+    #[test]
+    fn error_message_says_when_the_host_is_retried() {
+        let err = Error::HostUnavailable {
+            host: "example.com:443".to_string(),
+            failures: 3,
+            retry_in: Duration::from_secs(25),
+        };
+        assert_eq!(
+            err.to_string(),
+            "example.com:443 refused 3 connections in a row; not retrying it for another 25s"
+        );
+    }
+}
@@ -1,15 +1,30 @@
-use std::borrow::Cow;
-use std::sync::{Arc, OnceLock};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use reqwest::{header::HeaderMap, Client, Method, RequestBuilder, StatusCode, Url};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use crate::auth::TokenProvider;
+use crate::auth::{CoordinatedProvider, TokenProvider};
+use crate::debug_http;
 use crate::error::{Error, Result};
 
+use super::deprecation::DeprecationObserver;
+use super::host_failures::HostFailureCache;
+use super::observer::{self, Phase, PhaseObserver, RequestObserver};
+use super::request_cache::RequestCache;
+use super::response::ResponseEnvelope;
 use super::retry::Retryer;
+use super::stream::JsonArrayStream;
+use super::trace::TraceContext;
+
+/// Largest JSON request body sent by default: the API rejects request payloads over 10 MiB.
+pub const DEFAULT_MAX_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+/// Largest response body read by default. API responses are far smaller; anything bigger is a
+/// misdirected base URL or a proxy error page.
+pub const DEFAULT_MAX_RESPONSE_SIZE: usize = 64 * 1024 * 1024;
 
 /// HTTP layer implementation for NBLM API requests
 #[derive(Clone)]
@@ -18,6 +33,23 @@ pub(crate) struct HttpClient {
     pub(super) token_provider: Arc<dyn TokenProvider>,
     pub(super) retryer: Retryer,
     pub(super) user_project: Option<String>,
+    /// `Accept-Language` sent with every request.
+    pub(super) accept_language: Option<String>,
+    pub(super) observer: Option<PhaseObserver>,
+    pub(super) request_observer: Option<RequestObserver>,
+    pub(super) deprecation_observer: Option<DeprecationObserver>,
+    pub(super) trace_context: Option<TraceContext>,
+    pub(super) host_failures: Option<Arc<HostFailureCache>>,
+    /// Successful GET bodies by URL, set by [`super::NblmClient::with_request_cache`].
+    pub(super) request_cache: Option<Arc<RequestCache>>,
+    /// Largest serialized JSON body sent; larger ones fail before any network I/O.
+    pub(super) max_body_size: usize,
+    /// Largest response body read; reading stops with [`Error::ResponseTooLarge`] past it.
+    pub(super) max_response_size: usize,
+    /// Timeout configured on `client`, reported in [`Error::Timeout`].
+    pub(super) timeout: Duration,
+    #[cfg(feature = "cassette")]
+    pub(super) cassette: Option<Arc<super::cassette::CassetteTransport>>,
 }
 
 impl HttpClient {
@@ -29,10 +61,73 @@ impl HttpClient {
     ) -> Self {
         Self {
             client,
-            token_provider,
+            // Shared by every clone, so concurrent 401s trigger one refresh between them.
+            token_provider: Arc::new(CoordinatedProvider::new(token_provider)),
             retryer,
             user_project,
+            accept_language: None,
+            observer: None,
+            request_observer: None,
+            deprecation_observer: None,
+            trace_context: None,
+            host_failures: None,
+            request_cache: None,
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            timeout: super::DEFAULT_TIMEOUT,
+            #[cfg(feature = "cassette")]
+            cassette: None,
+        }
+    }
+
+    async fn send(&self, request: reqwest::Request) -> Result<reqwest::Response> {
+        #[cfg(feature = "cassette")]
+        if let Some(cassette) = &self.cassette {
+            return cassette.execute(&self.client, request).await;
+        }
+        let method = request.method().clone();
+        let url = request.url().clone();
+        if let Some(host_failures) = &self.host_failures {
+            host_failures.check(&url)?;
+        }
+        let started = Instant::now();
+        let result = self.client.execute(request).await;
+        if let Some(host_failures) = &self.host_failures {
+            match &result {
+                Ok(_) => host_failures.record_success(&url),
+                Err(err) if err.is_connect() => host_failures.record_failure(&url),
+                Err(_) => {}
+            }
+        }
+        result.map_err(|err| self.classify(err, &method, &url, started.elapsed()))
+    }
+
+    /// Turn a transport error into [`Error::Timeout`], [`Error::Connect`] or, for anything
+    /// else, [`Error::Request`].
+    fn classify(
+        &self,
+        err: reqwest::Error,
+        method: &Method,
+        url: &Url,
+        elapsed: Duration,
+    ) -> Error {
+        if err.is_timeout() {
+            return Error::Timeout {
+                method: method.to_string(),
+                path: path_template(url.path()),
+                timeout: self.timeout,
+                elapsed: Duration::from_millis(elapsed.as_millis() as u64),
+            };
         }
+        if err.is_connect() {
+            let host = match (url.host_str(), url.port()) {
+                (Some(host), Some(port)) => format!("{host}:{port}"),
+                (host, None) => host.unwrap_or_default().to_string(),
+                (None, Some(_)) => String::new(),
+            };
+            return Error::Connect { host, source: err };
+        }
+        Error::Request(err)
     }
 
     pub async fn request_json<B, R>(&self, method: Method, url: Url, body: Option<&B>) -> Result<R>
@@ -40,28 +135,140 @@ impl HttpClient {
         B: Serialize + ?Sized,
         R: DeserializeOwned,
     {
-        let body_bytes = match body {
-            Some(value) => Some(Bytes::from(serde_json::to_vec(value).map_err(Error::Json)?)),
-            None => None,
-        };
-        let body_bytes = Arc::new(body_bytes);
-        let builder_fn = {
-            let body_bytes = Arc::clone(&body_bytes);
-            move |mut builder: RequestBuilder| -> Result<RequestBuilder> {
-                if let Some(bytes) = &*body_bytes {
-                    builder = builder
-                        .header(reqwest::header::CONTENT_TYPE, "application/json")
-                        .body(bytes.clone());
-                }
-                Ok(builder)
-            }
-        };
+        self.request_json_raw(method, url, body)
+            .await
+            .map(ResponseEnvelope::into_inner)
+    }
 
+    /// Same as [`HttpClient::request_json`], but keeps the raw response body alongside the
+    /// decoded value.
+    pub async fn request_json_raw<B, R>(
+        &self,
+        method: Method,
+        url: Url,
+        body: Option<&B>,
+    ) -> Result<ResponseEnvelope<R>>
+    where
+        B: Serialize + ?Sized,
+        R: DeserializeOwned,
+    {
+        let cache = self
+            .request_cache
+            .as_ref()
+            .filter(|_| method == Method::GET);
+        if let Some(body) = cache.and_then(|cache| cache.get(&url)) {
+            let parsed = serde_json::from_slice::<R>(&body)?;
+            return Ok(ResponseEnvelope::new(parsed, body));
+        }
         let method_for_parse = method.clone();
         let url_for_parse = url.clone();
-        let response = self.execute_with_builder(method, url, builder_fn).await?;
+        let with_body = self.json_body(&method, &url, body)?;
+        let response = self.execute_with_builder(method, url, with_body).await?;
+
+        let envelope = self
+            .parse_json_response(&method_for_parse, &url_for_parse, response)
+            .await?;
+        if let Some(cache) = cache {
+            cache.insert(&url_for_parse, Bytes::copy_from_slice(envelope.raw()));
+        }
+        Ok(envelope)
+    }
+
+    /// Send an optional JSON body plus `headers` and return the successful response body
+    /// undecoded.
+    pub async fn request_bytes<B>(
+        &self,
+        method: Method,
+        url: Url,
+        body: Option<&B>,
+        headers: HeaderMap,
+    ) -> Result<Bytes>
+    where
+        B: Serialize + ?Sized,
+    {
+        let method_for_log = method.clone();
+        let url_for_log = url.clone();
+        let with_body = self.json_body(&method, &url, body)?;
+        let response = self
+            .execute_with_builder(method, url, move |builder: RequestBuilder| {
+                with_body(builder.headers(headers.clone()))
+            })
+            .await?;
+
+        let status = response.status();
+        let body = self.read_body(&method_for_log, response).await?;
+        log_http_response(&method_for_log, &url_for_log, status, &body);
+        if !status.is_success() {
+            return Err(Error::http(
+                status,
+                String::from_utf8_lossy(&body).into_owned(),
+            ));
+        }
+        Ok(body)
+    }
 
-        parse_json_response(&method_for_parse, &url_for_parse, response).await
+    /// Send a request and decode the `field` array of the JSON object response item by item,
+    /// without buffering the body. Error responses are read in full as usual.
+    pub async fn request_json_stream<W, T>(
+        &self,
+        method: Method,
+        url: Url,
+        field: &'static str,
+    ) -> Result<JsonArrayStream<T>>
+    where
+        W: DeserializeOwned + Into<T>,
+        T: Send + 'static,
+    {
+        let method_for_log = method.clone();
+        let url_for_log = url.clone();
+        let with_body = self.json_body(&method, &url, None::<&()>)?;
+        let response = self.execute_with_builder(method, url, with_body).await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = self.read_body(&method_for_log, response).await?;
+            log_http_response(&method_for_log, &url_for_log, status, &body);
+            return Err(Error::http(
+                status,
+                String::from_utf8_lossy(&body).into_owned(),
+            ));
+        }
+        Ok(JsonArrayStream::spawn::<W>(response, field))
+    }
+
+    /// GET `url` and return the successful response with its body unread, for the caller to
+    /// stream. Without `authenticated` the request carries none of the client's credentials or
+    /// headers, for URLs that grant access by themselves. Error responses are read in full.
+    pub async fn request_stream(&self, url: Url, authenticated: bool) -> Result<reqwest::Response> {
+        let url_for_log = url.clone();
+        let response = if authenticated {
+            self.execute_with_builder(Method::GET, url, Ok).await?
+        } else {
+            let run = || {
+                let url = url.clone();
+                async move {
+                    let request = self.client.get(url).build().map_err(Error::Request)?;
+                    if let Some(request_observer) = &self.request_observer {
+                        request_observer.notify(&request);
+                    }
+                    self.send(request).await
+                }
+            };
+            self.retryer
+                .run_observed(run, self.observer.as_ref(), self.max_response_size)
+                .await?
+        };
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = self.read_body(&Method::GET, response).await?;
+            log_http_response(&Method::GET, &url_for_log, status, &body);
+            return Err(Error::http(
+                status,
+                String::from_utf8_lossy(&body).into_owned(),
+            ));
+        }
+        Ok(response)
     }
 
     pub async fn request_binary<R>(
@@ -97,70 +304,141 @@ impl HttpClient {
         let url_for_parse = url.clone();
         let response = self.execute_with_builder(method, url, builder_fn).await?;
 
-        parse_json_response(&method_for_parse, &url_for_parse, response).await
+        self.parse_json_response(&method_for_parse, &url_for_parse, response)
+            .await
+            .map(ResponseEnvelope::into_inner)
     }
 }
 
-const MAX_BODY_PREVIEW: usize = 2048;
-
-fn debug_http_enabled() -> bool {
-    static FLAG: OnceLock<bool> = OnceLock::new();
-    *FLAG.get_or_init(|| match std::env::var("NBLM_DEBUG_HTTP") {
-        Ok(value) => matches!(value.to_ascii_lowercase().as_str(), "1" | "true" | "yes"),
-        Err(_) => false,
-    })
+/// Request builder step that attaches `body` serialized as JSON, when there is one.
+impl HttpClient {
+    /// Serialize `body` once and return a closure that attaches it to each attempt. Bodies over
+    /// `max_body_size` fail here with [`Error::Validation`], before anything is sent.
+    fn json_body<B>(
+        &self,
+        method: &Method,
+        url: &Url,
+        body: Option<&B>,
+    ) -> Result<impl Fn(RequestBuilder) -> Result<RequestBuilder> + Send + Sync + 'static>
+    where
+        B: Serialize + ?Sized,
+    {
+        let body_bytes = match body {
+            Some(value) => Some(Bytes::from(serde_json::to_vec(value).map_err(Error::Json)?)),
+            None => None,
+        };
+        if let Some(bytes) = body_bytes
+            .as_ref()
+            .filter(|bytes| bytes.len() > self.max_body_size)
+        {
+            return Err(Error::Validation(format!(
+                "request body for {method} {} is {} bytes, larger than the {}-byte limit; split it into smaller requests",
+                url.path(),
+                bytes.len(),
+                self.max_body_size
+            )));
+        }
+        Ok(
+            move |mut builder: RequestBuilder| -> Result<RequestBuilder> {
+                if let Some(bytes) = &body_bytes {
+                    builder = builder
+                        .header(reqwest::header::CONTENT_TYPE, "application/json")
+                        .body(bytes.clone());
+                }
+                Ok(builder)
+            },
+        )
+    }
 }
 
-fn build_body_preview(body: &[u8]) -> Cow<'_, str> {
-    match std::str::from_utf8(body) {
-        Ok(text) => {
-            if text.len() > MAX_BODY_PREVIEW {
-                let mut preview = text[..MAX_BODY_PREVIEW].to_string();
-                preview.push('…');
-                Cow::Owned(preview)
-            } else {
-                Cow::Borrowed(text)
+/// `path` with the ID after each known collection replaced by a placeholder, e.g.
+/// `/v1alpha/projects/{project}/locations/{location}/notebooks/{notebook}/sources:batchCreate`.
+/// Custom method suffixes such as `:batchCreate` are kept.
+pub(crate) fn path_template(path: &str) -> String {
+    const COLLECTIONS: [(&str, &str); 4] = [
+        ("projects", "{project}"),
+        ("locations", "{location}"),
+        ("notebooks", "{notebook}"),
+        ("sources", "{source}"),
+    ];
+    let mut previous = "";
+    path.split('/')
+        .map(|segment| {
+            let placeholder = COLLECTIONS
+                .iter()
+                .find(|(collection, _)| *collection == previous)
+                .map(|(_, placeholder)| *placeholder);
+            previous = segment;
+            match placeholder {
+                Some(placeholder) if !segment.is_empty() => match segment.split_once(':') {
+                    Some((_, custom_method)) => format!("{placeholder}:{custom_method}"),
+                    None => placeholder.to_string(),
+                },
+                _ => segment.to_string(),
             }
-        }
-        Err(_) => Cow::Owned(format!("<non-utf8 body: {} bytes>", body.len())),
-    }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
 }
 
 fn log_http_response(method: &Method, url: &Url, status: StatusCode, body: &[u8]) {
-    if !debug_http_enabled() {
-        return;
+    debug_http::log_http_response(method.as_str(), url.as_str(), status.as_u16(), body);
+}
+
+impl HttpClient {
+    async fn parse_json_response<R>(
+        &self,
+        method: &Method,
+        url: &Url,
+        response: reqwest::Response,
+    ) -> Result<ResponseEnvelope<R>>
+    where
+        R: DeserializeOwned,
+    {
+        let status = response.status();
+        let body = self.read_body(method, response).await?;
+        log_http_response(method, url, status, &body);
+
+        if !status.is_success() {
+            let text = String::from_utf8_lossy(&body).into_owned();
+            return Err(Error::http(status, text));
+        }
+
+        let parsed = serde_json::from_slice::<R>(&body)?;
+        Ok(ResponseEnvelope::new(parsed, body))
     }
 
-    let preview = build_body_preview(body);
-    eprintln!(
-        "[nblm::http] method={} status={} url={} body_len={} body={}",
-        method,
-        status.as_u16(),
-        url,
-        body.len(),
-        preview
-    );
+    async fn read_body(&self, method: &Method, response: reqwest::Response) -> Result<Bytes> {
+        let url = response.url().clone();
+        match read_body(response, self.max_response_size).await {
+            // The timeout covers the whole exchange, so by now all of it has been used.
+            Err(Error::Request(err)) => Err(self.classify(err, method, &url, self.timeout)),
+            result => result,
+        }
+    }
 }
 
-async fn parse_json_response<R>(
-    method: &Method,
-    url: &Url,
-    response: reqwest::Response,
-) -> Result<R>
-where
-    R: DeserializeOwned,
-{
-    let status = response.status();
-    let body = response.bytes().await.map_err(Error::Request)?;
-    log_http_response(method, url, status, &body);
-
-    if !status.is_success() {
-        let text = String::from_utf8_lossy(&body).into_owned();
-        return Err(Error::http(status, text));
+/// Read the whole body, giving up with [`Error::ResponseTooLarge`] as soon as it is known to
+/// exceed `limit` bytes. The client's timeout covers the body as well as the headers.
+pub(super) async fn read_body(mut response: reqwest::Response, limit: usize) -> Result<Bytes> {
+    let too_large = |response: &reqwest::Response| Error::ResponseTooLarge {
+        url: response.url().to_string(),
+        limit,
+    };
+    if response
+        .content_length()
+        .is_some_and(|length| length > limit as u64)
+    {
+        return Err(too_large(&response));
     }
-
-    let parsed = serde_json::from_slice::<R>(&body)?;
-    Ok(parsed)
+    let mut body = BytesMut::new();
+    while let Some(chunk) = response.chunk().await.map_err(Error::Request)? {
+        if body.len() + chunk.len() > limit {
+            return Err(too_large(&response));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body.freeze())
 }
 
 impl HttpClient {
@@ -173,111 +451,181 @@ impl HttpClient {
     where
         F: Fn(RequestBuilder) -> Result<RequestBuilder> + Send + Sync + 'static,
     {
-        let client = self.client.clone();
-        let method_clone = method.clone();
-        let url_clone = url.clone();
-        let provider = Arc::clone(&self.token_provider);
-        let user_project = self.user_project.clone();
         let builder_fn = Arc::new(builder_fn);
+        if let Some(cache) = self
+            .request_cache
+            .as_ref()
+            .filter(|_| method != Method::GET)
+        {
+            cache.invalidate(&url);
+        }
 
         let run = {
-            let client = client.clone();
-            let method = method_clone.clone();
-            let url = url_clone.clone();
-            let provider = Arc::clone(&provider);
-            let user_project = user_project.clone();
+            let http = self.clone();
+            let method = method.clone();
+            let url = url.clone();
             let builder_fn = Arc::clone(&builder_fn);
             move || {
-                let client = client.clone();
+                let http = http.clone();
                 let method = method.clone();
                 let url = url.clone();
-                let provider = Arc::clone(&provider);
-                let user_project = user_project.clone();
                 let builder_fn = Arc::clone(&builder_fn);
                 async move {
-                    let token = provider.access_token().await?;
-                    let mut builder = client.request(method, url).bearer_auth(token);
-                    if let Some(project) = &user_project {
-                        builder = builder.header("x-goog-user-project", project);
-                    }
-                    builder = builder_fn(builder)?;
-                    let request = builder.build().map_err(Error::Request)?;
-                    let response = client.execute(request).await.map_err(Error::Request)?;
-                    Ok(response)
+                    let token = http.timed_token(false).await?;
+                    http.send_with_token(method, url, token, builder_fn.as_ref())
+                        .await
                 }
             }
         };
 
-        let mut response = self.retryer.run_with_retry(run).await?;
+        let mut response = self
+            .retryer
+            .run_observed(run, self.observer.as_ref(), self.max_response_size)
+            .await?;
 
         if response.status() == StatusCode::UNAUTHORIZED {
             let status = response.status();
-            let body = response.bytes().await.map_err(Error::Request)?;
+            let body = self.read_body(&method, response).await?;
             log_http_response(&method, &url, status, &body);
             let run_refresh = {
-                let client = client.clone();
-                let method = method_clone.clone();
-                let url = url_clone.clone();
-                let provider = Arc::clone(&provider);
-                let user_project = user_project.clone();
+                let http = self.clone();
+                let method = method.clone();
+                let url = url.clone();
                 let builder_fn = Arc::clone(&builder_fn);
                 move || {
-                    let client = client.clone();
+                    let http = http.clone();
                     let method = method.clone();
                     let url = url.clone();
-                    let provider = Arc::clone(&provider);
-                    let user_project = user_project.clone();
                     let builder_fn = Arc::clone(&builder_fn);
                     async move {
-                        let token = provider.refresh_token().await?;
-                        let mut builder = client.request(method, url).bearer_auth(token);
-                        if let Some(project) = &user_project {
-                            builder = builder.header("x-goog-user-project", project);
-                        }
-                        builder = builder_fn(builder)?;
-                        let request = builder.build().map_err(Error::Request)?;
-                        let response = client.execute(request).await.map_err(Error::Request)?;
-                        Ok(response)
+                        let token = http.timed_token(true).await?;
+                        http.send_with_token(method, url, token, builder_fn.as_ref())
+                            .await
                     }
                 }
             };
-            response = self.retryer.run_with_retry(run_refresh).await?;
+            response = self
+                .retryer
+                .run_observed(run_refresh, self.observer.as_ref(), self.max_response_size)
+                .await?;
         }
 
         Ok(response)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn build_body_preview_returns_borrowed_for_short_utf8() {
-        let input = b"short body";
-        let preview = build_body_preview(input);
-        assert!(matches!(preview, Cow::Borrowed("short body")));
+    async fn send_with_token<F>(
+        &self,
+        method: Method,
+        url: Url,
+        token: String,
+        builder_fn: &F,
+    ) -> Result<reqwest::Response>
+    where
+        F: Fn(RequestBuilder) -> Result<RequestBuilder>,
+    {
+        // Captured up front because the request takes ownership of the method and URL.
+        let timed = self.observer.as_ref().map(|observer| {
+            (
+                observer,
+                method.clone(),
+                url.path().to_string(),
+                Instant::now(),
+            )
+        });
+        let mut builder = self.client.request(method, url).bearer_auth(token);
+        if let Some(project) = &self.user_project {
+            builder = builder.header("x-goog-user-project", project);
+        }
+        if let Some(language) = &self.accept_language {
+            builder = builder.header(reqwest::header::ACCEPT_LANGUAGE, language);
+        }
+        builder = builder_fn(builder)?;
+        let mut request = builder.build().map_err(Error::Request)?;
+        if let Some(trace) = &self.trace_context {
+            trace.apply_default(request.headers_mut());
+        }
+        // Explicit contexts win; otherwise the request span becomes the server span's parent.
+        #[cfg(feature = "otel")]
+        let span = {
+            let span = tracing::info_span!(
+                "nblm.request",
+                http.request.method = %request.method(),
+                url.template = %path_template(request.url().path()),
+            );
+            if let Some(trace) = TraceContext::from_span(&span) {
+                trace.apply_default(request.headers_mut());
+            }
+            span
+        };
+        if let Some(request_observer) = &self.request_observer {
+            request_observer.notify(&request);
+        }
+        let sent = self
+            .deprecation_observer
+            .as_ref()
+            .map(|observer| (observer, request.method().clone(), request.url().clone()));
+        #[cfg(feature = "otel")]
+        let response = tracing::Instrument::instrument(self.send(request), span).await;
+        #[cfg(not(feature = "otel"))]
+        let response = self.send(request).await;
+        if let (Some((observer, method, url)), Ok(response)) = (sent, &response) {
+            observer.notify(&method, &url, response.headers());
+        }
+        if let Some((observer, method, path, started)) = timed {
+            let status = response
+                .as_ref()
+                .ok()
+                .map(|response| response.status().as_u16());
+            observer.notify(
+                Phase::Http {
+                    method,
+                    path,
+                    status,
+                },
+                started.elapsed(),
+            );
+        }
+        response
     }
 
-    #[test]
-    fn build_body_preview_truncates_long_utf8() {
-        let long_text = "x".repeat(MAX_BODY_PREVIEW + 10);
-        let preview = build_body_preview(long_text.as_bytes());
-        let expected = format!("{}…", "x".repeat(MAX_BODY_PREVIEW));
-        match preview {
-            Cow::Owned(truncated) => assert_eq!(truncated, expected),
-            _ => panic!("expected owned truncated preview"),
-        }
+    async fn timed_token(&self, refresh: bool) -> Result<String> {
+        let started = observer::start(self.observer.as_ref());
+        let token = if refresh {
+            self.token_provider.refresh_token().await
+        } else {
+            self.token_provider.access_token().await
+        };
+        observer::finish(self.observer.as_ref(), started, Phase::TokenFetch);
+        token
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::path_template;
 
     #[test]
-    fn build_body_preview_handles_non_utf8() {
-        let binary = [0xffu8, 0x00, 0xfe];
-        let preview = build_body_preview(&binary);
-        let expected = format!("<non-utf8 body: {} bytes>", binary.len());
-        match preview {
-            Cow::Owned(msg) => assert_eq!(msg, expected),
-            _ => panic!("expected owned message for non-utf8 body"),
+    fn path_template_hides_resource_ids() {
+        let cases = [
+            (
+                "/v1alpha/projects/123/locations/global/notebooks/nb-1/sources:batchCreate",
+                "/v1alpha/projects/{project}/locations/{location}/notebooks/{notebook}/sources:batchCreate",
+            ),
+            (
+                "/v1alpha/projects/123/locations/us/notebooks:listRecentlyViewed",
+                "/v1alpha/projects/{project}/locations/{location}/notebooks:listRecentlyViewed",
+            ),
+            (
+                "/v1alpha/projects/123/locations/us/notebooks/nb-1/sources/src-2",
+                "/v1alpha/projects/{project}/locations/{location}/notebooks/{notebook}/sources/{source}",
+            ),
+            (
+                "/upload/v1alpha/projects/123/locations/us/notebooks/nb-1/sources:uploadFile",
+                "/upload/v1alpha/projects/{project}/locations/{location}/notebooks/{notebook}/sources:uploadFile",
+            ),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(path_template(path), expected);
         }
     }
 }
@@ -0,0 +1,84 @@
+use crate::error::{Error, Result};
+
+/// Check that `tag` looks like a BCP 47 language tag (`fr`, `en-US`, `zh-Hant-TW`) and return it
+/// trimmed.
+///
+/// The check is deliberately loose: a 2-3 letter language followed by any number of 1-8
+/// character alphanumeric subtags. It exists to catch values such as `english` or `en_US.UTF-8`,
+/// not to validate against the registry; the API ignores tags it does not know.
+pub fn parse_language_tag(tag: &str) -> Result<String> {
+    let tag = tag.trim();
+    let mut subtags = tag.split('-');
+    let language = subtags.next().unwrap_or_default();
+    let valid = (2..=3).contains(&language.len())
+        && language.bytes().all(|byte| byte.is_ascii_alphabetic())
+        && subtags.all(|subtag| {
+            (1..=8).contains(&subtag.len())
+                && subtag.bytes().all(|byte| byte.is_ascii_alphanumeric())
+        });
+    if !valid {
+        return Err(Error::Validation(format!(
+            "invalid language tag '{tag}': expected a BCP 47 tag such as 'en', 'fr-CA' or 'zh-Hant-TW'"
+        )));
+    }
+    Ok(tag.to_string())
+}
+
+/// The language tag for a POSIX locale such as `LANG=pt_BR.UTF-8` (`pt-BR`), or `None` for `C`,
+/// `POSIX` and anything else that does not convert to a valid tag.
+pub fn language_tag_from_locale(locale: &str) -> Option<String> {
+    let name = locale.split(['.', '@']).next().unwrap_or_default().trim();
+    if name.eq_ignore_ascii_case("C") || name.eq_ignore_ascii_case("POSIX") {
+        return None;
+    }
+    parse_language_tag(&name.replace('_', "-")).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_common_tags() {
+        for tag in ["en", "fr-CA", "zh-Hant-TW", "es-419", "ja", " de-DE "] {
+            assert_eq!(parse_language_tag(tag).unwrap(), tag.trim(), "{tag}");
+        }
+    }
+
+    #[test]
+    fn rejects_clearly_invalid_tags() {
+        for tag in [
+            "english",
+            "",
+            "e",
+            "en_US",
+            "en-",
+            "en-US.UTF-8",
+            "fr-toolongsubtag",
+            "1a",
+        ] {
+            let err = parse_language_tag(tag).unwrap_err();
+            assert!(matches!(err, Error::Validation(_)), "{tag}");
+        }
+        assert_eq!(
+            parse_language_tag("english").unwrap_err().to_string(),
+            "validation error: invalid language tag 'english': expected a BCP 47 tag such as 'en', 'fr-CA' or 'zh-Hant-TW'"
+        );
+    }
+
+    #[test]
+    fn locales_convert_to_tags() {
+        assert_eq!(
+            language_tag_from_locale("pt_BR.UTF-8").as_deref(),
+            Some("pt-BR")
+        );
+        assert_eq!(
+            language_tag_from_locale("de_DE@euro").as_deref(),
+            Some("de-DE")
+        );
+        assert_eq!(language_tag_from_locale("fr").as_deref(), Some("fr"));
+        assert_eq!(language_tag_from_locale("C.UTF-8"), None);
+        assert_eq!(language_tag_from_locale("POSIX"), None);
+        assert_eq!(language_tag_from_locale(""), None);
+    }
+}
@@ -0,0 +1,164 @@
+//! Prometheus-style metrics for client calls, recorded through the [`metrics`](::metrics) facade.
+//!
+//! Install a recorder (for example `metrics-exporter-prometheus`) and call
+//! [`NblmClient::with_metrics`](super::NblmClient::with_metrics):
+//!
+//! | Metric | Kind | Labels |
+//! |--------|------|--------|
+//! | `nblm_requests_total` | counter | `method`, `path`, `status` |
+//! | `nblm_request_duration_seconds` | histogram | `method`, `path` |
+//! | `nblm_retries_total` | counter | none |
+//!
+//! `path` is a template such as `/notebooks/{id}/sources:batchCreate`, so notebook and source IDs
+//! do not create a series each. `status` is the HTTP status code, or `error` when no response
+//! arrived.
+
+use super::observer::{Phase, PhaseEvent};
+
+pub const REQUESTS_TOTAL: &str = "nblm_requests_total";
+pub const REQUEST_DURATION_SECONDS: &str = "nblm_request_duration_seconds";
+pub const RETRIES_TOTAL: &str = "nblm_retries_total";
+
+/// Records each [`PhaseEvent`] as metrics. Token fetches and file reads are not recorded.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MetricsHooks;
+
+impl MetricsHooks {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn record(&self, event: &PhaseEvent) {
+        match &event.phase {
+            Phase::Http {
+                method,
+                path,
+                status,
+            } => {
+                let method = method.to_string();
+                let path = template_path(path);
+                let status = status.map_or_else(|| "error".to_string(), |code| code.to_string());
+                ::metrics::counter!(
+                    REQUESTS_TOTAL,
+                    "method" => method.clone(),
+                    "path" => path.clone(),
+                    "status" => status,
+                )
+                .increment(1);
+                ::metrics::histogram!(
+                    REQUEST_DURATION_SECONDS,
+                    "method" => method,
+                    "path" => path,
+                )
+                .record(event.elapsed.as_secs_f64());
+            }
+            Phase::RetryWait => ::metrics::counter!(RETRIES_TOTAL).increment(1),
+            Phase::TokenFetch | Phase::FileRead { .. } => {}
+        }
+    }
+}
+
+/// Reduce a request path to a low-cardinality template: the API version and
+/// `projects/*/locations/*` parent are dropped, and every resource ID becomes `{id}`.
+///
+/// `/v1alpha/projects/123/locations/global/notebooks/abc/sources:batchCreate` becomes
+/// `/notebooks/{id}/sources:batchCreate`. Any prefix before the version (such as `/upload`) is
+/// kept.
+pub fn template_path(path: &str) -> String {
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let mut prefix = Vec::new();
+    let mut rest = segments.as_slice();
+    while let Some((first, tail)) = rest.split_first() {
+        if is_version(first) {
+            rest = tail;
+            break;
+        }
+        if *first == "projects" {
+            break;
+        }
+        prefix.push(*first);
+        rest = tail;
+    }
+    if let ["projects", _, "locations", _, tail @ ..] = rest {
+        rest = tail;
+    } else if let ["projects", _, tail @ ..] = rest {
+        rest = tail;
+    }
+
+    let mut out = String::new();
+    for segment in &prefix {
+        out.push('/');
+        out.push_str(segment);
+    }
+    // Resource paths alternate collection and ID; a `:verb` suffix is kept on either.
+    for (index, segment) in rest.iter().enumerate() {
+        out.push('/');
+        if index % 2 == 1 {
+            out.push_str("{id}");
+            if let Some((_, verb)) = segment.split_once(':') {
+                out.push(':');
+                out.push_str(verb);
+            }
+        } else {
+            out.push_str(segment);
+        }
+    }
+    if out.is_empty() {
+        out.push('/');
+    }
+    out
+}
+
+fn is_version(segment: &str) -> bool {
+    segment
+        .strip_prefix('v')
+        .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template_path_replaces_ids_and_drops_the_parent() {
+        let cases = [
+            (
+                "/v1alpha/projects/123/locations/global/notebooks/abc-1/sources:batchCreate",
+                "/notebooks/{id}/sources:batchCreate",
+            ),
+            (
+                "/v1alpha/projects/123/locations/global/notebooks:listRecentlyViewed",
+                "/notebooks:listRecentlyViewed",
+            ),
+            (
+                "/v1alpha/projects/123/locations/us/notebooks/nb1:query",
+                "/notebooks/{id}:query",
+            ),
+            (
+                "/v1alpha/projects/123/locations/global/notebooks/nb1/sources/src-9",
+                "/notebooks/{id}/sources/{id}",
+            ),
+            (
+                "/v1alpha/projects/123/locations/global/notebooks/nb1/audioOverviews/default",
+                "/notebooks/{id}/audioOverviews/{id}",
+            ),
+            (
+                "/upload/v1alpha/projects/123/locations/global/notebooks/nb1/sources:uploadFile",
+                "/upload/notebooks/{id}/sources:uploadFile",
+            ),
+            ("/v1/projects/123/notebooks/nb1", "/notebooks/{id}"),
+            ("/", "/"),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(template_path(path), expected, "{path}");
+        }
+    }
+
+    #[test]
+    fn version_segments_need_a_digit() {
+        assert!(is_version("v1alpha"));
+        assert!(is_version("v1"));
+        assert!(!is_version("views"));
+        assert!(!is_version("v"));
+    }
+}
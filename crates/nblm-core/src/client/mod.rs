@@ -1,50 +1,106 @@
 use std::{sync::Arc, time::Duration};
 
-use reqwest::{Client, Url};
+use reqwest::Client;
 
-use crate::auth::{ensure_drive_scope, TokenProvider};
-use crate::env::EnvironmentConfig;
+use crate::auth::{ScopeChecker, TokenProject, TokenProvider};
+use crate::env::{base_url_has_version, validate_base_url, EnvironmentConfig};
 use crate::error::Result;
 
-mod api;
+pub(crate) mod api;
+#[cfg(feature = "cassette")]
+pub mod cassette;
+mod content_type;
+mod deprecation;
+mod emoji;
+mod host_failures;
 mod http;
-mod retry;
+mod language;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+mod observer;
+mod options;
+mod poll;
+mod request_cache;
+mod response;
+pub(crate) mod retry;
+pub(crate) mod stream;
+mod trace;
+mod upload_file;
 mod url;
 
-pub use self::retry::{RetryConfig, Retryer};
+pub use self::api::ArtifactDownload;
+pub use self::content_type::{
+    infer_content_type, BUILTIN_CONTENT_TYPE_OVERRIDES, DEFAULT_CONTENT_TYPE,
+};
+pub use self::deprecation::{DeprecationNotice, DeprecationObserver};
+pub use self::emoji::normalize_emoji;
+pub use self::host_failures::HostFailureConfig;
+pub use self::http::{DEFAULT_MAX_BODY_SIZE, DEFAULT_MAX_RESPONSE_SIZE};
+pub use self::language::{language_tag_from_locale, parse_language_tag};
+pub use self::observer::{Phase, PhaseEvent, PhaseObserver, RequestEvent, RequestObserver};
+pub use self::options::{
+    client_options_from_env, ClientOptions, MAX_RETRIES_ENV, RETRY_MAX_DELAY_ENV,
+    RETRY_MIN_DELAY_ENV, TIMEOUT_ENV,
+};
+pub use self::poll::{
+    poll_timed_with_sleeper, poll_until, poll_until_timed, poll_with_sleeper, DurationStats,
+    PollBackoff, PollConfig, PollState, Polled, DEFAULT_MAX_POLL_INTERVAL,
+};
+pub use self::response::ResponseEnvelope;
+pub use self::retry::{RetryConfig, RetryEvent, Retryer};
+pub use self::trace::TraceContext;
+pub use self::upload_file::{read_upload_file, UploadFileError};
 
 use self::api::backends::{BackendContext, ClientBackends};
 use self::http::HttpClient;
 use self::url::{new_url_builder, UrlBuilder};
 
-const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+pub(crate) const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
 
 pub struct NblmClient {
     pub(self) http: Arc<HttpClient>,
     pub(self) url_builder: Arc<dyn UrlBuilder>,
-    backends: ClientBackends,
+    pub(crate) backends: ClientBackends,
     environment: EnvironmentConfig,
+    scope_checker: ScopeChecker,
     timeout: Duration,
+    danger_accept_invalid_certs: bool,
 }
 
 impl NblmClient {
+    /// A client for `environment` that authenticates every request with `token_provider`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> nblm_core::Result<()> {
+    /// # let api = nblm_core::test_support::doctest::mock_api().await;
+    /// # let base_url = api.base_url();
+    /// use std::sync::Arc;
+    ///
+    /// use nblm_core::{EnvironmentConfig, NblmClient, StaticTokenProvider};
+    ///
+    /// let environment = EnvironmentConfig::enterprise("123456", "global", "us")?;
+    /// let token = Arc::new(StaticTokenProvider::new("ya29.example-token"));
+    /// // `with_base_url` points the client at another server, such as a local mock.
+    /// let client = NblmClient::new(token, environment)?.with_base_url(base_url)?;
+    ///
+    /// let notebook = client.create_notebook("Research").await?;
+    /// assert_eq!(notebook.notebook_id(), Some("nb-1"));
+    /// # Ok(())
+    /// # }
+    /// ```
     pub fn new(
         token_provider: Arc<dyn TokenProvider>,
         environment: EnvironmentConfig,
     ) -> Result<Self> {
-        let client = Client::builder()
-            .user_agent(concat!("nblm-cli/", env!("CARGO_PKG_VERSION")))
-            .timeout(DEFAULT_TIMEOUT)
-            .build()
-            .map_err(crate::error::Error::from)?;
+        let client =
+            build_http_client(DEFAULT_TIMEOUT, false).map_err(crate::error::Error::from)?;
 
         let retryer = Retryer::new(RetryConfig::default());
         let http = Arc::new(HttpClient::new(client, token_provider, retryer, None));
-        let url_builder = new_url_builder(
-            environment.profile(),
-            environment.base_url().to_string(),
-            environment.parent_path().to_string(),
-        );
+        let url_builder = new_url_builder(&environment);
         let ctx = BackendContext::new(Arc::clone(&http), Arc::clone(&url_builder));
         let backends = ClientBackends::new(environment.profile(), ctx);
 
@@ -53,7 +109,9 @@ impl NblmClient {
             url_builder,
             backends,
             environment,
+            scope_checker: ScopeChecker::from_env(),
             timeout: DEFAULT_TIMEOUT,
+            danger_accept_invalid_certs: false,
         })
     }
 
@@ -68,90 +126,277 @@ impl NblmClient {
         Self::new(token_provider, env)
     }
 
+    /// API environment (profile, base URL and parent path) this client targets.
+    pub fn environment(&self) -> &EnvironmentConfig {
+        &self.environment
+    }
+
+    /// Checker used to confirm Drive access before adding Google Drive sources.
+    pub fn scope_checker(&self) -> &ScopeChecker {
+        &self.scope_checker
+    }
+
+    /// Replace the Drive scope checker. Defaults to [`ScopeChecker::from_env`].
+    pub fn with_scope_checker(mut self, checker: ScopeChecker) -> Self {
+        self.scope_checker = checker;
+        self
+    }
+
+    /// HTTP request timeout currently in effect.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Retry settings currently in effect.
+    pub fn retry_config(&self) -> &RetryConfig {
+        self.http.retryer.config()
+    }
+
+    /// Apply retry settings and, when set, the timeout and response size limit from `options`.
+    pub fn with_options(mut self, options: ClientOptions) -> Self {
+        if let Some(timeout) = options.timeout {
+            self = self.with_timeout(timeout);
+        }
+        if let Some(bytes) = options.max_response_size {
+            self = self.with_max_response_size(bytes);
+        }
+        self.with_retry_config(options.retry)
+    }
+
     pub fn with_timeout(mut self, timeout: Duration) -> Self {
         self.timeout = timeout;
         // Update the underlying HTTP client's timeout
-        let client = Client::builder()
-            .user_agent(concat!("nblm-cli/", env!("CARGO_PKG_VERSION")))
-            .timeout(timeout)
-            .build()
+        let client = build_http_client(timeout, self.danger_accept_invalid_certs)
             .expect("Failed to rebuild client with new timeout");
-
-        let token_provider = Arc::clone(&self.http.token_provider);
-        let retryer = self.http.retryer.clone();
-        let user_project = self.http.user_project.clone();
-        self.http = Arc::new(HttpClient::new(
-            client,
-            token_provider,
-            retryer,
-            user_project,
-        ));
-        self.rebuild_backends();
+        self.rebuild_http(|http| {
+            http.client = client;
+            http.timeout = timeout;
+        });
         self
     }
 
     pub fn with_retry_config(mut self, config: RetryConfig) -> Self {
-        let client = Client::builder()
-            .user_agent(concat!("nblm-cli/", env!("CARGO_PKG_VERSION")))
-            .timeout(self.timeout)
-            .build()
-            .expect("Failed to rebuild client");
-
-        let token_provider = Arc::clone(&self.http.token_provider);
-        let retryer = Retryer::new(config);
-        let user_project = self.http.user_project.clone();
-        self.http = Arc::new(HttpClient::new(
-            client,
-            token_provider,
-            retryer,
-            user_project,
-        ));
-        self.rebuild_backends();
+        self.rebuild_http(|http| http.retryer = Retryer::new(config));
+        self
+    }
+
+    /// Fail fast on hosts that keep refusing connections instead of waiting out the connect
+    /// timeout for each request (see [`HostFailureConfig`]). Off by default; the record is
+    /// shared by concurrent requests on this client.
+    pub fn with_host_failure_cache(mut self, config: HostFailureConfig) -> Self {
+        let cache = Arc::new(host_failures::HostFailureCache::new(config));
+        self.rebuild_http(|http| http.host_failures = Some(cache));
+        self
+    }
+
+    /// Answer repeated GETs for the same URL from memory instead of sending them again, for
+    /// commands that read the same notebooks or sources from several places. Entries never
+    /// expire and at most 256 are kept; any other request drops the entries it could have
+    /// changed (the notebook it names and every listing, or everything when it names no
+    /// notebook). The cache is shared by clones of this client, so use it on a short-lived
+    /// client and not one that polls for status changes.
+    pub fn with_request_cache(mut self) -> Self {
+        let cache = Arc::new(request_cache::RequestCache::default());
+        self.rebuild_http(|http| http.request_cache = Some(cache));
+        self
+    }
+
+    /// Refuse JSON request bodies larger than `bytes` with [`Error::Validation`](crate::Error::Validation)
+    /// instead of sending them. Defaults to [`DEFAULT_MAX_BODY_SIZE`]; file uploads are not
+    /// affected.
+    pub fn with_max_body_size(mut self, bytes: usize) -> Self {
+        self.rebuild_http(|http| http.max_body_size = bytes);
+        self
+    }
+
+    /// Request body limit set by [`NblmClient::with_max_body_size`].
+    pub fn max_body_size(&self) -> usize {
+        self.http.max_body_size
+    }
+
+    /// Stop reading a response body past `bytes` and fail with
+    /// [`Error::ResponseTooLarge`](crate::Error::ResponseTooLarge). Defaults to
+    /// [`DEFAULT_MAX_RESPONSE_SIZE`]; artifact downloads are not affected.
+    pub fn with_max_response_size(mut self, bytes: usize) -> Self {
+        self.rebuild_http(|http| http.max_response_size = bytes);
         self
     }
 
+    /// Response body limit set by [`NblmClient::with_max_response_size`].
+    pub fn max_response_size(&self) -> usize {
+        self.http.max_response_size
+    }
+
+    /// Call `observer` after each token fetch, HTTP attempt and retry wait with how long it
+    /// took. Without an observer no timings are taken.
+    pub fn with_observer(mut self, observer: impl Fn(&PhaseEvent) + Send + Sync + 'static) -> Self {
+        let observer = PhaseObserver::new(observer);
+        self.rebuild_http(|http| http.observer = Some(observer));
+        self
+    }
+
+    /// Call `observer` with each request just before it is sent, once per attempt.
+    pub fn with_request_observer(
+        mut self,
+        observer: impl Fn(&RequestEvent) + Send + Sync + 'static,
+    ) -> Self {
+        let observer = RequestObserver::new(observer);
+        self.rebuild_http(|http| http.request_observer = Some(observer));
+        self
+    }
+
+    /// Call `observer` for each response carrying `Deprecation`, `Sunset` or `Warning` headers,
+    /// retries included.
+    pub fn with_deprecation_observer(
+        mut self,
+        observer: impl Fn(&DeprecationNotice) + Send + Sync + 'static,
+    ) -> Self {
+        let observer = DeprecationObserver::new(observer);
+        self.rebuild_http(|http| http.deprecation_observer = Some(observer));
+        self
+    }
+
+    /// Record request counts, durations and retries as metrics (see [`metrics::MetricsHooks`]).
+    /// Uses the observer slot, so it replaces any [`NblmClient::with_observer`] callback.
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(self, hooks: metrics::MetricsHooks) -> Self {
+        self.with_observer(move |event| hooks.record(event))
+    }
+
+    /// Report a phase the caller timed itself, such as reading a file before
+    /// [`NblmClient::upload_source_file`], to the observer set by [`NblmClient::with_observer`].
+    pub fn report_phase(&self, phase: Phase, elapsed: Duration) {
+        if let Some(observer) = &self.http.observer {
+            observer.notify(phase, elapsed);
+        }
+    }
+
+    /// Whether an observer is set, so callers can skip timing work nobody will see.
+    pub fn is_observed(&self) -> bool {
+        self.http.observer.is_some()
+    }
+
+    /// Send `context` as the `traceparent`/`tracestate` headers on every request.
+    /// [`NblmClient::raw_request_with_trace`] can override it for a single call.
+    pub fn with_trace_context(mut self, context: TraceContext) -> Self {
+        self.rebuild_http(|http| http.trace_context = Some(context));
+        self
+    }
+
+    /// Trace context set by [`NblmClient::with_trace_context`].
+    pub fn trace_context(&self) -> Option<&TraceContext> {
+        self.http.trace_context.as_ref()
+    }
+
+    /// Send `tag` (a BCP 47 language tag such as `fr-CA`) as `Accept-Language` on every
+    /// request, so error messages and generated text come back in that language where the API
+    /// supports it. Rejects values that are not language tags (see [`parse_language_tag`]).
+    pub fn with_accept_language(mut self, tag: &str) -> Result<Self> {
+        let tag = parse_language_tag(tag)?;
+        self.rebuild_http(|http| http.accept_language = Some(tag));
+        Ok(self)
+    }
+
+    /// Language set by [`NblmClient::with_accept_language`].
+    pub fn accept_language(&self) -> Option<&str> {
+        self.http.accept_language.as_deref()
+    }
+
     pub fn with_user_project(mut self, project: impl Into<String>) -> Self {
-        let client = Client::builder()
-            .user_agent(concat!("nblm-cli/", env!("CARGO_PKG_VERSION")))
-            .timeout(self.timeout)
-            .build()
-            .expect("Failed to rebuild client");
-
-        let token_provider = Arc::clone(&self.http.token_provider);
-        let retryer = self.http.retryer.clone();
-        let user_project = Some(project.into());
-        self.http = Arc::new(HttpClient::new(
-            client,
-            token_provider,
-            retryer,
-            user_project,
-        ));
-        self.rebuild_backends();
+        let project = project.into();
+        self.rebuild_http(|http| http.user_project = Some(project));
+        self
+    }
+
+    /// Accept any TLS certificate, including self-signed and expired ones.
+    ///
+    /// Only meant for talking to local HTTPS mock servers; it removes all protection against
+    /// man-in-the-middle attacks.
+    pub fn with_danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.danger_accept_invalid_certs = accept;
+        let client = build_http_client(self.timeout, accept).expect("Failed to rebuild client");
+        self.rebuild_http(|http| http.client = client);
         self
     }
 
-    /// Override API base URL (for tests). Accepts absolute URL. Trims trailing slash.
+    /// Send every request through `cassette`, recording or replaying it (see
+    /// [`cassette::CassetteTransport`]). Survives later builder calls.
+    #[cfg(feature = "cassette")]
+    pub fn with_cassette(mut self, cassette: Arc<cassette::CassetteTransport>) -> Self {
+        self.rebuild_http(|http| http.cassette = Some(cassette));
+        self
+    }
+
+    /// Override API base URL (for tests). Requires an absolute http(s) URL; trims trailing
+    /// slashes and logs a warning when the path has no version segment such as `/v1alpha`.
     pub fn with_base_url(mut self, base: impl Into<String>) -> Result<Self> {
-        let base = base.into().trim().trim_end_matches('/').to_string();
-        // Basic sanity check: absolute URL
-        let _ = Url::parse(&base).map_err(crate::error::Error::from)?;
-        self.environment = self.environment.clone().with_base_url(base.clone());
-        let parent = self.environment.parent_path().to_string();
-        self.url_builder = new_url_builder(self.environment.profile(), base, parent);
+        let base = validate_base_url(&base.into())?;
+        if !base_url_has_version(&base) {
+            tracing::warn!(
+                "base URL {base} does not end with an API version segment such as /v1alpha; request paths are appended to it as-is"
+            );
+        }
+        self.environment = self.environment.clone().with_base_url(base);
+        self.url_builder = new_url_builder(&self.environment);
         self.rebuild_backends();
         Ok(self)
     }
 }
 
+fn build_http_client(
+    timeout: Duration,
+    danger_accept_invalid_certs: bool,
+) -> reqwest::Result<Client> {
+    Client::builder()
+        .user_agent(concat!("nblm-cli/", env!("CARGO_PKG_VERSION")))
+        .timeout(timeout)
+        .danger_accept_invalid_certs(danger_accept_invalid_certs)
+        .build()
+}
+
 impl NblmClient {
+    /// Replace the HTTP layer with a modified copy, keeping every setting `update` leaves alone.
+    fn rebuild_http(&mut self, update: impl FnOnce(&mut HttpClient)) {
+        let mut http = HttpClient::clone(&self.http);
+        update(&mut http);
+        self.http = Arc::new(http);
+        self.rebuild_backends();
+    }
+
     fn rebuild_backends(&mut self) {
+        if self.backends.is_fixed() {
+            return;
+        }
         let ctx = BackendContext::new(Arc::clone(&self.http), Arc::clone(&self.url_builder));
         self.backends = ClientBackends::new(self.environment.profile(), ctx);
     }
 
+    /// Compare the project the client's access token was issued for with the project in the
+    /// parent path; see [`ScopeChecker::check_token_project`].
+    pub async fn check_token_project(&self, expected_domain: Option<&str>) -> Result<TokenProject> {
+        self.scope_checker
+            .check_token_project(
+                self.http.token_provider.as_ref(),
+                self.project_number(),
+                expected_domain,
+            )
+            .await
+    }
+
+    /// The project number in the parent path.
+    pub(crate) fn project_number(&self) -> &str {
+        self.environment
+            .parent_path()
+            .strip_prefix("projects/")
+            .and_then(|rest| rest.split('/').next())
+            .unwrap_or_default()
+    }
+
     pub(crate) async fn ensure_drive_scope_if_needed(&self, includes_drive: bool) -> Result<()> {
-        if includes_drive {
-            ensure_drive_scope(self.http.token_provider.as_ref()).await?;
+        if includes_drive && !self.backends.is_fixed() {
+            self.scope_checker
+                .ensure_drive_scope(self.http.token_provider.as_ref())
+                .await?;
         }
         Ok(())
     }
@@ -193,6 +438,18 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn with_base_url_rejects_url_without_scheme() {
+        let provider = Arc::new(crate::auth::StaticTokenProvider::new("test"));
+        let env = EnvironmentConfig::enterprise("123", "global", "us").unwrap();
+        let client = NblmClient::new(provider, env).unwrap();
+        let err = client
+            .with_base_url("localhost:8080/v1alpha")
+            .err()
+            .unwrap();
+        assert!(matches!(err, crate::error::Error::InvalidBaseUrl { .. }));
+    }
+
     #[test]
     #[allow(deprecated)]
     fn new_enterprise_constructs_client_correctly() {
@@ -217,4 +474,153 @@ mod tests {
         let result = NblmClient::new_enterprise(provider, "123", "global", "invalid");
         assert!(result.is_err());
     }
+
+    /// The crate root exports this `NblmClient` and the backend-delegating API lives on it.
+    #[test]
+    fn crate_root_exports_backend_client() {
+        fn same_type(client: crate::NblmClient) -> NblmClient {
+            client
+        }
+        let provider = Arc::new(crate::auth::StaticTokenProvider::new("test"));
+        let env = EnvironmentConfig::enterprise("123", "global", "us").unwrap();
+        let client = same_type(crate::NblmClient::new(provider, env).unwrap());
+        assert_eq!(
+            client.environment().profile(),
+            crate::ApiProfile::Enterprise
+        );
+
+        // ID parameters are generic, so name them through calls rather than as fn items.
+        let _ = |client: &crate::NblmClient| {
+            drop(client.get_source("nb", "src"));
+            drop(client.upload_source_file("nb", "f", "text/plain", Vec::new()));
+            drop(client.create_audio_overview("nb", Default::default()));
+            drop(client.delete_audio_overview("nb"));
+            drop(client.add_sources("nb", Vec::new()));
+            drop(client.delete_sources("nb", Vec::new()));
+            drop(client.get_notebook("nb"));
+        };
+        let _ = crate::NblmClient::list_recently_viewed;
+        let _ = crate::NblmClient::delete_notebooks;
+    }
+
+    /// One-shot HTTPS server presenting the self-signed `localhost` fixture certificate.
+    async fn spawn_self_signed_server(body: &'static str) -> std::net::SocketAddr {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio_rustls::rustls::pki_types::pem::PemObject;
+        use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+        use tokio_rustls::rustls::{crypto::ring, ServerConfig};
+
+        let cert = CertificateDer::from_pem_slice(include_bytes!(
+            "../../tests/fixtures/tls/localhost.crt"
+        ))
+        .unwrap();
+        let key =
+            PrivateKeyDer::from_pem_slice(include_bytes!("../../tests/fixtures/tls/localhost.key"))
+                .unwrap();
+        let config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_no_client_auth()
+            .with_single_cert(vec![cert], key)
+            .unwrap();
+        let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(config));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            while let Ok((tcp, _)) = listener.accept().await {
+                let Ok(mut tls) = acceptor.accept(tcp).await else {
+                    continue;
+                };
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match tls.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = tls.write_all(response.as_bytes()).await;
+                let _ = tls.shutdown().await;
+            }
+        });
+        addr
+    }
+
+    fn client_for(addr: std::net::SocketAddr) -> NblmClient {
+        let provider = Arc::new(crate::auth::StaticTokenProvider::new("test"));
+        let env = EnvironmentConfig::enterprise("123", "global", "global").unwrap();
+        NblmClient::new(provider, env)
+            .unwrap()
+            .with_retry_config(RetryConfig::disabled())
+            .with_base_url(format!("https://localhost:{}/v1alpha", addr.port()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn self_signed_certificate_is_rejected_by_default() {
+        let addr = spawn_self_signed_server(r#"{"notebooks":[]}"#).await;
+        let result = client_for(addr).list_recently_viewed(None).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn danger_accept_invalid_certs_allows_self_signed_server() {
+        let addr = spawn_self_signed_server(r#"{"notebooks":[]}"#).await;
+        let client = client_for(addr).with_danger_accept_invalid_certs(true);
+        let response = client.list_recently_viewed(None).await.unwrap();
+        assert!(response.notebooks.is_empty());
+    }
+
+    #[tokio::test]
+    async fn danger_accept_invalid_certs_survives_later_builder_calls() {
+        let addr = spawn_self_signed_server(r#"{"notebooks":[]}"#).await;
+        let client = client_for(addr)
+            .with_danger_accept_invalid_certs(true)
+            .with_timeout(Duration::from_secs(5))
+            .with_retry_config(RetryConfig::disabled());
+        assert!(client.list_recently_viewed(None).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn accept_language_is_sent_on_every_request() {
+        use wiremock::matchers::{header, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(header("accept-language", "fr-CA"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = Arc::new(crate::auth::StaticTokenProvider::new("test"));
+        let env = EnvironmentConfig::enterprise("123", "global", "global").unwrap();
+        let client = NblmClient::new(provider, env)
+            .unwrap()
+            .with_accept_language("fr-CA")
+            .unwrap()
+            .with_timeout(Duration::from_secs(5))
+            .with_base_url(format!("{}/v1alpha", server.uri()))
+            .unwrap();
+        assert_eq!(client.accept_language(), Some("fr-CA"));
+        client.list_recently_viewed(None).await.unwrap();
+    }
+
+    #[test]
+    fn with_accept_language_rejects_invalid_tags() {
+        let provider = Arc::new(crate::auth::StaticTokenProvider::new("test"));
+        let env = EnvironmentConfig::enterprise("123", "global", "us").unwrap();
+        let err = NblmClient::new(provider, env)
+            .unwrap()
+            .with_accept_language("english")
+            .err()
+            .unwrap();
+        assert!(matches!(err, crate::error::Error::Validation(_)));
+    }
 }
@@ -0,0 +1,214 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use reqwest::header::HeaderMap;
+use reqwest::Method;
+use url::Url;
+
+/// Part of a client call that took time, reported to the [`NblmClient::with_observer`] callback.
+///
+/// [`NblmClient::with_observer`]: super::NblmClient::with_observer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Phase {
+    /// Getting or refreshing an access token from the token provider.
+    TokenFetch,
+    /// One HTTP attempt, from sending the request until the response headers arrive.
+    Http {
+        method: Method,
+        /// URL path, without the query string.
+        path: String,
+        /// Response status, or `None` when no response arrived (connection error, timeout).
+        status: Option<u16>,
+    },
+    /// Waiting before a retry.
+    RetryWait,
+    /// Reading a local file before uploading it. Reported by callers through
+    /// [`NblmClient::report_phase`](super::NblmClient::report_phase).
+    FileRead { path: PathBuf },
+}
+
+/// A finished [`Phase`] and how long it took.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhaseEvent {
+    pub phase: Phase,
+    pub elapsed: Duration,
+}
+
+/// Callback invoked after each timed phase.
+#[derive(Clone)]
+pub struct PhaseObserver(Arc<dyn Fn(&PhaseEvent) + Send + Sync>);
+
+impl fmt::Debug for PhaseObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PhaseObserver(..)")
+    }
+}
+
+impl PhaseObserver {
+    pub fn new(observer: impl Fn(&PhaseEvent) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(observer))
+    }
+
+    pub fn notify(&self, phase: Phase, elapsed: Duration) {
+        (self.0)(&PhaseEvent { phase, elapsed });
+    }
+}
+
+/// A request about to go out, reported to the [`NblmClient::with_request_observer`] callback once
+/// per attempt, retries included.
+///
+/// [`NblmClient::with_request_observer`]: super::NblmClient::with_request_observer
+#[derive(Debug, Clone)]
+pub struct RequestEvent {
+    pub method: Method,
+    pub url: Url,
+    /// Headers as sent, `Authorization` included.
+    pub headers: HeaderMap,
+    /// The body, or `None` when there is none or it is streamed (file uploads).
+    pub body: Option<Bytes>,
+}
+
+/// Callback invoked before each HTTP attempt.
+#[derive(Clone)]
+pub struct RequestObserver(Arc<dyn Fn(&RequestEvent) + Send + Sync>);
+
+impl fmt::Debug for RequestObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RequestObserver(..)")
+    }
+}
+
+impl RequestObserver {
+    pub fn new(observer: impl Fn(&RequestEvent) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(observer))
+    }
+
+    pub(crate) fn notify(&self, request: &reqwest::Request) {
+        (self.0)(&RequestEvent {
+            method: request.method().clone(),
+            url: request.url().clone(),
+            headers: request.headers().clone(),
+            body: request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .map(Bytes::copy_from_slice),
+        });
+    }
+}
+
+/// Start time for a phase, taken only when somebody is observing.
+pub(crate) fn start(observer: Option<&PhaseObserver>) -> Option<Instant> {
+    observer.map(|_| Instant::now())
+}
+
+/// Report `phase` as running since `started`; a no-op without an observer.
+pub(crate) fn finish(observer: Option<&PhaseObserver>, started: Option<Instant>, phase: Phase) {
+    if let (Some(observer), Some(started)) = (observer, started) {
+        observer.notify(phase, started.elapsed());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::StaticTokenProvider;
+    use crate::client::{NblmClient, RetryConfig};
+    use crate::env::EnvironmentConfig;
+    use std::sync::Mutex;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn observer_sees_token_http_and_retry_phases_in_order() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .with_priority(2)
+            .mount(&server)
+            .await;
+
+        let phases = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&phases);
+        let provider = Arc::new(StaticTokenProvider::new("test-token"));
+        let env = EnvironmentConfig::enterprise("123", "global", "global").unwrap();
+        let client = NblmClient::new(provider, env)
+            .unwrap()
+            .with_retry_config(
+                RetryConfig::default()
+                    .with_min_delay(Duration::from_millis(5))
+                    .with_jitter(false),
+            )
+            .with_observer(move |event| recorded.lock().unwrap().push(event.phase.clone()))
+            .with_base_url(format!("{}/v1alpha", server.uri()))
+            .unwrap();
+
+        client.list_recently_viewed(None).await.unwrap();
+
+        let http = |status| Phase::Http {
+            method: Method::GET,
+            path: "/v1alpha/projects/123/locations/global/notebooks:listRecentlyViewed".to_string(),
+            status: Some(status),
+        };
+        assert_eq!(
+            *phases.lock().unwrap(),
+            vec![
+                Phase::TokenFetch,
+                http(503),
+                Phase::RetryWait,
+                Phase::TokenFetch,
+                http(200),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn request_observer_sees_headers_and_body_before_sending() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+            .mount(&server)
+            .await;
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&seen);
+        let provider = Arc::new(StaticTokenProvider::new("test-token"));
+        let env = EnvironmentConfig::enterprise("123", "global", "global").unwrap();
+        let client = NblmClient::new(provider, env)
+            .unwrap()
+            .with_request_observer(move |event| recorded.lock().unwrap().push(event.clone()))
+            .with_base_url(format!("{}/v1alpha", server.uri()))
+            .unwrap();
+
+        client.create_notebook("Research").await.unwrap();
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 1);
+        assert_eq!(seen[0].method, Method::POST);
+        assert_eq!(
+            seen[0].url.path(),
+            "/v1alpha/projects/123/locations/global/notebooks"
+        );
+        assert_eq!(seen[0].headers["authorization"], "Bearer test-token");
+        let body: serde_json::Value =
+            serde_json::from_slice(seen[0].body.as_ref().unwrap()).unwrap();
+        assert_eq!(body["title"], "Research");
+    }
+
+    #[test]
+    fn report_phase_without_observer_is_a_no_op() {
+        let provider = Arc::new(StaticTokenProvider::new("test-token"));
+        let env = EnvironmentConfig::enterprise("123", "global", "global").unwrap();
+        let client = NblmClient::new(provider, env).unwrap();
+        assert!(!client.is_observed());
+        client.report_phase(Phase::TokenFetch, Duration::from_millis(1));
+    }
+}
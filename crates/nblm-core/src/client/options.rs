@@ -0,0 +1,180 @@
+use std::env;
+use std::time::Duration;
+
+use super::retry::RetryConfig;
+use crate::error::{Error, Result};
+
+pub const MAX_RETRIES_ENV: &str = "NBLM_MAX_RETRIES";
+pub const RETRY_MIN_DELAY_ENV: &str = "NBLM_RETRY_MIN_DELAY";
+pub const RETRY_MAX_DELAY_ENV: &str = "NBLM_RETRY_MAX_DELAY";
+pub const TIMEOUT_ENV: &str = "NBLM_TIMEOUT";
+
+/// Retry, timeout and response size settings shared by the CLI and the Python bindings.
+///
+/// Built from `NBLM_*` variables by [`client_options_from_env`]; callers apply their own flags or
+/// arguments on top, so explicit settings always win over the environment.
+#[derive(Debug, Clone, Default)]
+pub struct ClientOptions {
+    pub retry: RetryConfig,
+    /// HTTP request timeout, body reading included; `None` keeps the client default.
+    pub timeout: Option<Duration>,
+    /// Largest response body read; `None` keeps
+    /// [`DEFAULT_MAX_RESPONSE_SIZE`](super::DEFAULT_MAX_RESPONSE_SIZE).
+    pub max_response_size: Option<usize>,
+}
+
+impl RetryConfig {
+    /// Defaults overridden by `NBLM_MAX_RETRIES`, `NBLM_RETRY_MIN_DELAY` and
+    /// `NBLM_RETRY_MAX_DELAY`. Delays use forms like `500ms`, `2s` or `1m`.
+    pub fn from_env() -> Result<Self> {
+        retry_config_from(&env_lookup)
+    }
+}
+
+/// [`ClientOptions`] from the `NBLM_*` environment variables, including `NBLM_TIMEOUT`.
+pub fn client_options_from_env() -> Result<ClientOptions> {
+    client_options_from(&env_lookup)
+}
+
+fn env_lookup(name: &str) -> Option<String> {
+    env::var(name).ok()
+}
+
+fn client_options_from(lookup: &dyn Fn(&str) -> Option<String>) -> Result<ClientOptions> {
+    let timeout = match read(lookup, TIMEOUT_ENV) {
+        Some(value) => match parse_duration(TIMEOUT_ENV, &value)? {
+            Duration::ZERO => {
+                return Err(invalid(TIMEOUT_ENV, &value, "must be greater than zero"))
+            }
+            timeout => Some(timeout),
+        },
+        None => None,
+    };
+    Ok(ClientOptions {
+        retry: retry_config_from(lookup)?,
+        timeout,
+        max_response_size: None,
+    })
+}
+
+fn retry_config_from(lookup: &dyn Fn(&str) -> Option<String>) -> Result<RetryConfig> {
+    let mut config = RetryConfig::default();
+    if let Some(value) = read(lookup, MAX_RETRIES_ENV) {
+        let retries = value
+            .parse()
+            .map_err(|_| invalid(MAX_RETRIES_ENV, &value, "expected a whole number such as 3"))?;
+        config = config.with_max_retries(retries);
+    }
+    if let Some(value) = read(lookup, RETRY_MIN_DELAY_ENV) {
+        config = config.with_min_delay(parse_duration(RETRY_MIN_DELAY_ENV, &value)?);
+    }
+    if let Some(value) = read(lookup, RETRY_MAX_DELAY_ENV) {
+        config = config.with_max_delay(parse_duration(RETRY_MAX_DELAY_ENV, &value)?);
+    }
+    if config.min_delay > config.max_delay {
+        return Err(Error::Validation(format!(
+            "{RETRY_MIN_DELAY_ENV} ({}) must not exceed {RETRY_MAX_DELAY_ENV} ({})",
+            humantime::format_duration(config.min_delay),
+            humantime::format_duration(config.max_delay)
+        )));
+    }
+    Ok(config)
+}
+
+/// Unset and blank variables both mean "use the default".
+fn read(lookup: &dyn Fn(&str) -> Option<String>, name: &str) -> Option<String> {
+    lookup(name)
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn parse_duration(name: &'static str, value: &str) -> Result<Duration> {
+    humantime::parse_duration(value)
+        .map_err(|_| invalid(name, value, "expected a duration such as 500ms, 2s or 1m"))
+}
+
+fn invalid(name: &'static str, value: &str, reason: &str) -> Error {
+    Error::InvalidEnv {
+        name,
+        value: value.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(vars: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+        move |name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        }
+    }
+
+    #[test]
+    fn missing_and_blank_variables_keep_defaults() {
+        let defaults = RetryConfig::default();
+        let options = client_options_from(&lookup(&[(MAX_RETRIES_ENV, "  ")])).unwrap();
+        assert_eq!(options.retry.max_retries, defaults.max_retries);
+        assert_eq!(options.retry.min_delay, defaults.min_delay);
+        assert_eq!(options.retry.max_delay, defaults.max_delay);
+        assert_eq!(options.timeout, None);
+    }
+
+    #[test]
+    fn variables_override_defaults() {
+        let options = client_options_from(&lookup(&[
+            (MAX_RETRIES_ENV, "6"),
+            (RETRY_MIN_DELAY_ENV, "250ms"),
+            (RETRY_MAX_DELAY_ENV, "1m"),
+            (TIMEOUT_ENV, "90s"),
+        ]))
+        .unwrap();
+        assert_eq!(options.retry.max_retries, 6);
+        assert_eq!(options.retry.min_delay, Duration::from_millis(250));
+        assert_eq!(options.retry.max_delay, Duration::from_secs(60));
+        assert_eq!(options.timeout, Some(Duration::from_secs(90)));
+    }
+
+    #[test]
+    fn invalid_values_name_the_variable() {
+        let cases: [(&'static [(&'static str, &'static str)], &str); 4] = [
+            (
+                &[(MAX_RETRIES_ENV, "lots")],
+                "invalid value 'lots' for NBLM_MAX_RETRIES: expected a whole number",
+            ),
+            (
+                &[(MAX_RETRIES_ENV, "-1")],
+                "invalid value '-1' for NBLM_MAX_RETRIES",
+            ),
+            (
+                &[(RETRY_MIN_DELAY_ENV, "soon")],
+                "invalid value 'soon' for NBLM_RETRY_MIN_DELAY: expected a duration",
+            ),
+            (
+                &[(TIMEOUT_ENV, "0s")],
+                "invalid value '0s' for NBLM_TIMEOUT: must be greater than zero",
+            ),
+        ];
+        for (vars, expected) in cases {
+            let err = client_options_from(&lookup(vars)).unwrap_err();
+            assert!(matches!(err, Error::InvalidEnv { .. }), "{err:?}");
+            assert!(err.to_string().contains(expected), "{err}");
+        }
+    }
+
+    #[test]
+    fn min_delay_above_max_delay_is_rejected() {
+        let err = retry_config_from(&lookup(&[
+            (RETRY_MIN_DELAY_ENV, "10s"),
+            (RETRY_MAX_DELAY_ENV, "1s"),
+        ]))
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "validation error: NBLM_RETRY_MIN_DELAY (10s) must not exceed NBLM_RETRY_MAX_DELAY (1s)"
+        );
+    }
+}
@@ -0,0 +1,469 @@
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use crate::error::{Error, Result};
+
+/// Longest wait between two status checks unless [`PollConfig::with_max_interval`] says
+/// otherwise.
+pub const DEFAULT_MAX_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+const POLL_BACKOFF_FACTOR: f64 = 1.5;
+
+/// How often to check on a long-running operation, and for how long.
+///
+/// The first check comes `interval` after the state passed to [`poll_until`]. Each check that
+/// sees the same pending state waits 1.5 times longer than the previous one, up to
+/// `max_interval`; a change of state starts again at `interval`. Polling stops with
+/// [`Error::WaitTimedOut`] once `timeout` has passed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PollConfig {
+    pub interval: Duration,
+    pub max_interval: Duration,
+    pub timeout: Duration,
+}
+
+impl PollConfig {
+    /// Poll every `interval` at first, backing off to at most [`DEFAULT_MAX_POLL_INTERVAL`]
+    /// (or `interval`, if that is longer).
+    pub fn new(interval: Duration, timeout: Duration) -> Self {
+        Self {
+            interval,
+            max_interval: interval.max(DEFAULT_MAX_POLL_INTERVAL),
+            timeout,
+        }
+    }
+
+    pub fn with_max_interval(mut self, max_interval: Duration) -> Self {
+        self.max_interval = max_interval.max(self.interval);
+        self
+    }
+}
+
+/// Where a polled operation stands, as reported by the caller of [`poll_until`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PollState {
+    /// Still running, e.g. `AUDIO_OVERVIEW_STATUS_IN_PROGRESS`. A different value than on the
+    /// previous check resets the backoff.
+    Pending(String),
+    /// Finished successfully; polling stops and returns the value.
+    Done,
+    /// Finished with this failed state; polling stops with [`Error::OperationFailed`].
+    Failed(String),
+}
+
+/// Delays between status checks: `interval`, then 1.5 times the previous delay up to
+/// `max_interval`, until [`reset`](Self::reset).
+#[derive(Debug, Clone)]
+pub struct PollBackoff {
+    interval: Duration,
+    max_interval: Duration,
+    next: Duration,
+}
+
+impl PollBackoff {
+    pub fn new(interval: Duration, max_interval: Duration) -> Self {
+        Self {
+            interval,
+            max_interval: max_interval.max(interval),
+            next: interval,
+        }
+    }
+
+    /// The delay before the next check; each call grows the one after it.
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self.next;
+        self.next = delay.mul_f64(POLL_BACKOFF_FACTOR).min(self.max_interval);
+        delay
+    }
+
+    /// Start again at `interval`, e.g. after the operation moved to a new state.
+    pub fn reset(&mut self) {
+        self.next = self.interval;
+    }
+}
+
+/// Check on `current` with `fetch` until `state` reports it done or failed, or `config.timeout`
+/// passes.
+///
+/// `operation` names what is being waited for in the errors, e.g. `"audio generation"`: a
+/// [`PollState::Failed`] state ends with [`Error::OperationFailed`] and running out of time with
+/// [`Error::WaitTimedOut`], so callers can tell the two apart. Errors from `fetch` are returned
+/// as they are.
+pub async fn poll_until<T, S, F, Fut>(
+    config: PollConfig,
+    operation: &str,
+    current: T,
+    state: S,
+    fetch: F,
+) -> Result<T>
+where
+    S: Fn(&T) -> PollState,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    poll_with_sleeper(config, operation, current, state, fetch, tokio::time::sleep).await
+}
+
+/// [`poll_until`] with the waits between checks made by `sleep` instead of `tokio::time::sleep`.
+///
+/// Time counts against `config.timeout` as the sum of the delays passed to `sleep` plus the time
+/// spent in `fetch`, so a `sleep` that returns at once checks the whole schedule without
+/// waiting.
+pub async fn poll_with_sleeper<T, S, F, Fut, Z, ZFut>(
+    config: PollConfig,
+    operation: &str,
+    current: T,
+    state: S,
+    fetch: F,
+    sleep: Z,
+) -> Result<T>
+where
+    S: Fn(&T) -> PollState,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+    Z: FnMut(Duration) -> ZFut,
+    ZFut: Future<Output = ()>,
+{
+    poll_timed_with_sleeper(config, operation, current, state, fetch, sleep)
+        .await
+        .result
+}
+
+/// The outcome of [`poll_until_timed`], with how long it took and how many checks it made.
+#[derive(Debug)]
+pub struct Polled<T> {
+    pub result: Result<T>,
+    /// Time from the start of polling to the end, counted as in [`poll_with_sleeper`].
+    pub elapsed: Duration,
+    /// Number of calls to `fetch`.
+    pub polls: u32,
+}
+
+/// [`poll_until`], also reporting the time spent and the number of checks, whether polling
+/// ended in success, a failed state, a timeout or an error from `fetch`.
+pub async fn poll_until_timed<T, S, F, Fut>(
+    config: PollConfig,
+    operation: &str,
+    current: T,
+    state: S,
+    fetch: F,
+) -> Polled<T>
+where
+    S: Fn(&T) -> PollState,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    poll_timed_with_sleeper(config, operation, current, state, fetch, tokio::time::sleep).await
+}
+
+/// [`poll_until_timed`] with the waits between checks made by `sleep`; see
+/// [`poll_with_sleeper`].
+pub async fn poll_timed_with_sleeper<T, S, F, Fut, Z, ZFut>(
+    config: PollConfig,
+    operation: &str,
+    mut current: T,
+    state: S,
+    mut fetch: F,
+    mut sleep: Z,
+) -> Polled<T>
+where
+    S: Fn(&T) -> PollState,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+    Z: FnMut(Duration) -> ZFut,
+    ZFut: Future<Output = ()>,
+{
+    let mut backoff = PollBackoff::new(config.interval, config.max_interval);
+    let mut elapsed = Duration::ZERO;
+    let mut polls = 0;
+    let mut previous: Option<String> = None;
+    let result = loop {
+        let pending = match state(&current) {
+            PollState::Done => break Ok(current),
+            PollState::Failed(state) => {
+                break Err(Error::OperationFailed {
+                    operation: operation.to_string(),
+                    state,
+                })
+            }
+            PollState::Pending(pending) => pending,
+        };
+        if previous
+            .as_ref()
+            .is_some_and(|previous| *previous != pending)
+        {
+            backoff.reset();
+        }
+        let remaining = config.timeout.saturating_sub(elapsed);
+        if remaining.is_zero() {
+            break Err(Error::WaitTimedOut {
+                operation: operation.to_string(),
+                state: pending,
+                timeout: config.timeout,
+            });
+        }
+        // The last check lands on the deadline rather than past it.
+        let delay = backoff.next_delay().min(remaining);
+        previous = Some(pending);
+
+        sleep(delay).await;
+        elapsed += delay;
+        let started = Instant::now();
+        polls += 1;
+        let fetched = fetch().await;
+        elapsed += started.elapsed();
+        match fetched {
+            Ok(next) => current = next,
+            Err(err) => break Err(err),
+        }
+    };
+    Polled {
+        result,
+        elapsed,
+        polls,
+    }
+}
+
+/// Count, median and maximum of a set of durations, e.g. per source kind for capacity
+/// planning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DurationStats {
+    pub count: usize,
+    /// The middle duration, or the mean of the two middle ones for an even count.
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl DurationStats {
+    /// Stats over `durations`, or `None` when there are none.
+    pub fn from_durations(durations: impl IntoIterator<Item = Duration>) -> Option<Self> {
+        let mut durations: Vec<Duration> = durations.into_iter().collect();
+        durations.sort_unstable();
+        let count = durations.len();
+        let max = *durations.last()?;
+        let median = if count % 2 == 1 {
+            durations[count / 2]
+        } else {
+            (durations[count / 2 - 1] + durations[count / 2]) / 2
+        };
+        Some(Self { count, median, max })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    fn secs(secs: f64) -> Duration {
+        Duration::from_secs_f64(secs)
+    }
+
+    fn pending(state: &str) -> PollState {
+        PollState::Pending(state.to_string())
+    }
+
+    /// Poll through `states` (the first one is the starting state) and return the result
+    /// and every delay the sleeper was asked for.
+    async fn run(config: PollConfig, states: Vec<PollState>) -> (Result<PollState>, Vec<Duration>) {
+        let mut states = VecDeque::from(states);
+        let first = states.pop_front().unwrap();
+        let mut delays = Vec::new();
+        let result = poll_with_sleeper(
+            config,
+            "audio generation",
+            first,
+            PollState::clone,
+            || {
+                let next = states.pop_front().unwrap_or_else(|| pending("RUNNING"));
+                async move { Ok(next) }
+            },
+            |delay| {
+                delays.push(delay);
+                std::future::ready(())
+            },
+        )
+        .await;
+        (result, delays)
+    }
+
+    #[test]
+    fn backoff_grows_by_half_up_to_the_max_and_resets() {
+        let mut backoff = PollBackoff::new(secs(10.0), secs(30.0));
+        let delays: Vec<_> = (0..5).map(|_| backoff.next_delay()).collect();
+        assert_eq!(
+            delays,
+            [secs(10.0), secs(15.0), secs(22.5), secs(30.0), secs(30.0)]
+        );
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), secs(10.0));
+    }
+
+    #[tokio::test]
+    async fn a_state_change_starts_the_schedule_again() {
+        let config = PollConfig::new(secs(10.0), secs(600.0)).with_max_interval(secs(30.0));
+        let (result, delays) = run(
+            config,
+            vec![
+                pending("QUEUED"),
+                pending("QUEUED"),
+                pending("QUEUED"),
+                pending("IN_PROGRESS"),
+                pending("IN_PROGRESS"),
+                PollState::Done,
+            ],
+        )
+        .await;
+        assert_eq!(result.unwrap(), PollState::Done);
+        assert_eq!(
+            delays,
+            [secs(10.0), secs(15.0), secs(22.5), secs(10.0), secs(15.0)]
+        );
+    }
+
+    #[tokio::test]
+    async fn running_out_of_time_is_a_timeout_with_the_last_state() {
+        let config = PollConfig::new(secs(10.0), secs(60.0)).with_max_interval(secs(30.0));
+        let (result, delays) = run(config, vec![pending("RUNNING")]).await;
+        // 10 + 15 + 22.5 leaves 12.5s (less the time spent fetching), so the last check is
+        // made at the deadline.
+        assert_eq!(delays[..3], [secs(10.0), secs(15.0), secs(22.5)]);
+        assert_eq!(delays.len(), 4, "{delays:?}");
+        assert!(
+            delays[3] <= secs(12.5) && delays[3] > secs(12.4),
+            "{delays:?}"
+        );
+        match result.unwrap_err() {
+            Error::WaitTimedOut {
+                operation,
+                state,
+                timeout,
+            } => {
+                assert_eq!(operation, "audio generation");
+                assert_eq!(state, "RUNNING");
+                assert_eq!(timeout, secs(60.0));
+            }
+            other => panic!("expected a timeout, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn a_failed_state_is_not_a_timeout() {
+        let config = PollConfig::new(secs(10.0), secs(60.0));
+        let (result, delays) = run(
+            config,
+            vec![pending("IN_PROGRESS"), PollState::Failed("FAILED".into())],
+        )
+        .await;
+        assert_eq!(delays, [secs(10.0)]);
+        let err = result.unwrap_err();
+        assert!(
+            matches!(&err, Error::OperationFailed { state, .. } if state == "FAILED"),
+            "{err:?}"
+        );
+        assert_eq!(err.to_string(), "audio generation ended with FAILED");
+    }
+
+    /// Like [`run`], timed, with a `fetch` that fails once the scripted states run out.
+    async fn run_timed(
+        config: PollConfig,
+        states: Vec<PollState>,
+    ) -> (Polled<PollState>, Duration) {
+        let mut states = VecDeque::from(states);
+        let first = states.pop_front().unwrap();
+        let mut slept = Duration::ZERO;
+        let polled = poll_timed_with_sleeper(
+            config,
+            "source ingestion",
+            first,
+            PollState::clone,
+            || {
+                let next = states
+                    .pop_front()
+                    .ok_or_else(|| Error::validation("no more states"));
+                async move { next }
+            },
+            |delay| {
+                slept += delay;
+                std::future::ready(())
+            },
+        )
+        .await;
+        (polled, slept)
+    }
+
+    /// The recorded time is the injected sleeps plus the (near-instant) fetches.
+    fn assert_elapsed(polled: &Polled<PollState>, slept: Duration) {
+        assert!(
+            polled.elapsed >= slept && polled.elapsed - slept < secs(0.1),
+            "elapsed {:?}, slept {slept:?}",
+            polled.elapsed
+        );
+    }
+
+    #[tokio::test]
+    async fn timed_polling_records_the_elapsed_time_and_checks() {
+        let config = PollConfig::new(secs(2.0), secs(600.0));
+        let (polled, slept) = run_timed(
+            config,
+            vec![
+                pending("PENDING"),
+                pending("PENDING"),
+                pending("IN_PROGRESS"),
+                PollState::Done,
+            ],
+        )
+        .await;
+        assert_eq!(polled.result.as_ref().unwrap(), &PollState::Done);
+        assert_eq!(polled.polls, 3);
+        // 2s, then 3s while still PENDING, then 2s again after the state changed.
+        assert_eq!(slept, secs(7.0));
+        assert_elapsed(&polled, slept);
+
+        let (polled, slept) = run_timed(config, vec![PollState::Done]).await;
+        assert_eq!(polled.result.as_ref().unwrap(), &PollState::Done);
+        assert_eq!((polled.polls, slept), (0, Duration::ZERO));
+        assert_elapsed(&polled, slept);
+    }
+
+    #[tokio::test]
+    async fn timed_polling_keeps_the_timing_of_failures_timeouts_and_errors() {
+        let config = PollConfig::new(secs(2.0), secs(10.0));
+        let (polled, slept) = run_timed(
+            config,
+            vec![pending("PENDING"), PollState::Failed("ERROR".into())],
+        )
+        .await;
+        assert!(matches!(polled.result, Err(Error::OperationFailed { .. })));
+        assert_eq!((polled.polls, slept), (1, secs(2.0)));
+        assert_elapsed(&polled, slept);
+
+        let states = vec![pending("PENDING"); 10];
+        let (polled, slept) = run_timed(config, states).await;
+        assert!(matches!(polled.result, Err(Error::WaitTimedOut { .. })));
+        // 2 + 3 + 4.5 and the rest of the 10s.
+        assert_eq!(polled.polls, 4);
+        assert!(slept <= secs(10.0) && slept > secs(9.9), "{slept:?}");
+        assert_elapsed(&polled, slept);
+
+        let (polled, slept) = run_timed(config, vec![pending("PENDING")]).await;
+        assert!(matches!(polled.result, Err(Error::Validation(_))));
+        assert_eq!((polled.polls, slept), (1, secs(2.0)));
+        assert_elapsed(&polled, slept);
+    }
+
+    #[test]
+    fn duration_stats_take_the_middle_and_the_largest() {
+        assert_eq!(DurationStats::from_durations([]), None);
+        assert_eq!(
+            DurationStats::from_durations([secs(9.0), secs(1.0), secs(4.0)]),
+            Some(DurationStats {
+                count: 3,
+                median: secs(4.0),
+                max: secs(9.0),
+            })
+        );
+        let even = DurationStats::from_durations([secs(1.0), secs(8.0), secs(2.0), secs(4.0)]);
+        assert_eq!(even.unwrap().median, secs(3.0));
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::{HashMap, VecDeque};
+
+use bytes::Bytes;
+use parking_lot::Mutex;
+use reqwest::Url;
+
+/// Most responses kept; the oldest is dropped to make room for a new one.
+const MAX_ENTRIES: usize = 256;
+
+#[derive(Debug)]
+struct Entry {
+    body: Bytes,
+    /// Notebook the URL belongs to, or `None` for listings and other project-level reads.
+    notebook: Option<String>,
+}
+
+/// Successful GET response bodies by URL, shared by every clone of a client.
+///
+/// Entries never expire: the cache is meant to live for one command. Any other method drops
+/// what it could have changed before it is sent: everything read from the same notebook plus
+/// every project-level read such as `listRecentlyViewed`, or the whole cache when the URL
+/// names no notebook (creating or batch-deleting notebooks).
+#[derive(Debug, Default)]
+pub(crate) struct RequestCache {
+    inner: Mutex<Entries>,
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    entries: HashMap<String, Entry>,
+    /// Keys in insertion order, for eviction.
+    order: VecDeque<String>,
+}
+
+impl RequestCache {
+    pub(crate) fn get(&self, url: &Url) -> Option<Bytes> {
+        let inner = self.inner.lock();
+        inner
+            .entries
+            .get(url.as_str())
+            .map(|entry| entry.body.clone())
+    }
+
+    pub(crate) fn insert(&self, url: &Url, body: Bytes) {
+        let mut inner = self.inner.lock();
+        let key = url.to_string();
+        let entry = Entry {
+            body,
+            notebook: notebook_id(url),
+        };
+        if inner.entries.insert(key.clone(), entry).is_some() {
+            return;
+        }
+        inner.order.push_back(key);
+        while inner.order.len() > MAX_ENTRIES {
+            if let Some(oldest) = inner.order.pop_front() {
+                inner.entries.remove(&oldest);
+            }
+        }
+    }
+
+    /// Drop every entry a request that changes `url` could make stale.
+    pub(crate) fn invalidate(&self, url: &Url) {
+        let mut inner = self.inner.lock();
+        let Entries { entries, order } = &mut *inner;
+        match notebook_id(url) {
+            Some(changed) => entries.retain(|_, entry| {
+                entry
+                    .notebook
+                    .as_ref()
+                    .is_some_and(|notebook| *notebook != changed)
+            }),
+            None => entries.clear(),
+        }
+        order.retain(|key| entries.contains_key(key));
+    }
+}
+
+/// The ID after `notebooks/` in `url`'s path, without a custom method suffix.
+fn notebook_id(url: &Url) -> Option<String> {
+    let mut segments = url.path_segments()?;
+    segments.find(|segment| *segment == "notebooks")?;
+    let id = segments.next()?;
+    let id = id.split_once(':').map_or(id, |(id, _)| id);
+    (!id.is_empty()).then(|| id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARENT: &str = "https://example.com/v1alpha/projects/1/locations/global";
+
+    fn url(path: &str) -> Url {
+        Url::parse(&format!("{PARENT}{path}")).unwrap()
+    }
+
+    fn cached(cache: &RequestCache, paths: &[&str]) -> Vec<bool> {
+        paths
+            .iter()
+            .map(|path| cache.get(&url(path)).is_some())
+            .collect()
+    }
+
+    #[test]
+    fn notebook_ids_are_read_from_the_path() {
+        assert_eq!(
+            notebook_id(&url("/notebooks/nb1/sources/s1")).as_deref(),
+            Some("nb1")
+        );
+        assert_eq!(
+            notebook_id(&url("/notebooks/nb1/sources:batchCreate")).as_deref(),
+            Some("nb1")
+        );
+        let upload = PARENT.replace("/v1alpha/", "/upload/v1alpha/");
+        let upload = Url::parse(&format!("{upload}/notebooks/nb1/sources:uploadFile")).unwrap();
+        assert_eq!(notebook_id(&upload).as_deref(), Some("nb1"));
+        assert_eq!(notebook_id(&url("/notebooks:listRecentlyViewed")), None);
+        assert_eq!(notebook_id(&url("/notebooks")), None);
+    }
+
+    #[test]
+    fn writes_drop_the_notebook_and_project_level_reads() {
+        let cache = RequestCache::default();
+        let paths = [
+            "/notebooks:listRecentlyViewed",
+            "/notebooks/nb1",
+            "/notebooks/nb1/sources/s1",
+            "/notebooks/nb2",
+        ];
+        for path in paths {
+            cache.insert(&url(path), Bytes::from_static(b"{}"));
+        }
+        assert_eq!(cached(&cache, &paths), [true; 4]);
+
+        cache.invalidate(&url("/notebooks/nb1/sources:batchCreate"));
+        assert_eq!(cached(&cache, &paths), [false, false, false, true]);
+
+        cache.insert(&url(paths[0]), Bytes::from_static(b"{}"));
+        cache.invalidate(&url("/notebooks"));
+        assert_eq!(cached(&cache, &paths), [false; 4]);
+        assert!(cache.inner.lock().order.is_empty());
+    }
+
+    #[test]
+    fn the_oldest_entry_makes_room_for_a_new_one() {
+        let cache = RequestCache::default();
+        for n in 0..=MAX_ENTRIES {
+            cache.insert(&url(&format!("/notebooks/nb{n}")), Bytes::new());
+        }
+        assert_eq!(cache.inner.lock().entries.len(), MAX_ENTRIES);
+        assert_eq!(
+            cached(
+                &cache,
+                &["/notebooks/nb0", "/notebooks/nb1", "/notebooks/nb256"]
+            ),
+            [false, true, true]
+        );
+    }
+}
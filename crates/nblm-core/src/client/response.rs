@@ -0,0 +1,65 @@
+use bytes::Bytes;
+
+/// Typed API response paired with the raw body it was decoded from.
+///
+/// Callers that need to forward the exact server payload (for example the CLI's
+/// `--json` mode) can use [`ResponseEnvelope::raw`] instead of re-serializing the
+/// typed value, which would drop fields the models do not capture.
+#[derive(Debug, Clone)]
+pub struct ResponseEnvelope<T> {
+    value: T,
+    raw: Bytes,
+}
+
+impl<T> ResponseEnvelope<T> {
+    pub fn new(value: T, raw: Bytes) -> Self {
+        Self { value, raw }
+    }
+
+    /// Typed representation of the response.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Raw response body exactly as returned by the server.
+    pub fn raw(&self) -> &[u8] {
+        &self.raw
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    pub fn into_parts(self) -> (T, Bytes) {
+        (self.value, self.raw)
+    }
+
+    /// Convert the typed value while keeping the raw body untouched.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> ResponseEnvelope<U> {
+        ResponseEnvelope {
+            value: f(self.value),
+            raw: self.raw,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_preserves_raw_body() {
+        let envelope = ResponseEnvelope::new(1_u32, Bytes::from_static(br#"{"n":1}"#));
+        let mapped = envelope.map(|value| value.to_string());
+        assert_eq!(mapped.value(), "1");
+        assert_eq!(mapped.raw(), br#"{"n":1}"#);
+    }
+
+    #[test]
+    fn into_parts_returns_value_and_raw() {
+        let envelope = ResponseEnvelope::new("typed", Bytes::from_static(b"{}"));
+        let (value, raw) = envelope.into_parts();
+        assert_eq!(value, "typed");
+        assert_eq!(&raw[..], b"{}");
+    }
+}
@@ -1,17 +1,47 @@
+use std::fmt;
+use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
-use backon::{BackoffBuilder, ExponentialBuilder};
+use backon::{BackoffBuilder, ExponentialBackoff, ExponentialBuilder};
 use httpdate::parse_http_date;
 use reqwest::{header::RETRY_AFTER, StatusCode};
 use tokio::time::sleep;
 use tracing::warn;
 
+use super::http::{read_body, DEFAULT_MAX_RESPONSE_SIZE};
+use super::observer::{self, Phase, PhaseObserver};
 use crate::error::{Error, Result};
 
 const DEFAULT_RETRY_MIN_DELAY_MS: u64 = 500;
 const DEFAULT_RETRY_MAX_DELAY_SECS: u64 = 5;
 const DEFAULT_RETRY_MAX_RETRIES: usize = 3;
 
+/// A retry that is about to happen, passed to the [`RetryConfig::with_on_retry`] callback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryEvent {
+    /// 1-based number of the retry about to be made.
+    pub attempt: usize,
+    /// Maximum number of retries configured.
+    pub max_retries: usize,
+    /// How long the client waits before retrying.
+    pub delay: Duration,
+    /// Status of the failed response, or `None` when the request itself failed (connect/timeout).
+    pub status: Option<StatusCode>,
+    /// Wait the response asked for in `Retry-After`, before `delay` capped it at
+    /// [`RetryConfig::max_delay`].
+    pub retry_after: Option<Duration>,
+}
+
+/// Callback invoked before each retry sleep.
+#[derive(Clone)]
+pub struct RetryHook(Arc<dyn Fn(&RetryEvent) + Send + Sync>);
+
+impl fmt::Debug for RetryHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RetryHook(..)")
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RetryConfig {
     /// Minimum backoff delay between retry attempts.
@@ -21,9 +51,17 @@ pub struct RetryConfig {
     /// Maximum number of retry attempts after the initial request.
     pub max_retries: usize,
     pub jitter: bool,
+    /// Called before each retry, e.g. to show progress while rate limited.
+    pub on_retry: Option<RetryHook>,
 }
 
 impl RetryConfig {
+    /// No retries: each request is sent once and its first failure is returned as is, which
+    /// makes the failing response easy to see while debugging.
+    pub fn disabled() -> Self {
+        Self::default().with_max_retries(0)
+    }
+
     pub fn with_min_delay(mut self, delay: Duration) -> Self {
         self.min_delay = delay;
         self
@@ -43,6 +81,42 @@ impl RetryConfig {
         self.jitter = jitter;
         self
     }
+
+    pub fn with_on_retry(mut self, hook: impl Fn(&RetryEvent) + Send + Sync + 'static) -> Self {
+        self.on_retry = Some(RetryHook(Arc::new(hook)));
+        self
+    }
+
+    /// Exponential backoff between `min_delay` and `max_delay`, yielding `max_retries` delays.
+    pub(crate) fn backoff(&self) -> ExponentialBackoff {
+        let mut builder = ExponentialBuilder::default()
+            .with_min_delay(self.min_delay)
+            .with_max_delay(self.max_delay)
+            .with_max_times(self.max_retries);
+        if self.jitter {
+            builder = builder.with_jitter();
+        }
+        builder.build()
+    }
+
+    /// Report a retry about to happen to the [`RetryConfig::with_on_retry`] callback.
+    pub(crate) fn notify(
+        &self,
+        attempt: usize,
+        delay: Duration,
+        status: Option<StatusCode>,
+        retry_after: Option<Duration>,
+    ) {
+        if let Some(RetryHook(hook)) = &self.on_retry {
+            hook(&RetryEvent {
+                attempt,
+                max_retries: self.max_retries,
+                delay,
+                status,
+                retry_after,
+            });
+        }
+    }
 }
 
 impl Default for RetryConfig {
@@ -52,6 +126,7 @@ impl Default for RetryConfig {
             max_delay: Duration::from_secs(DEFAULT_RETRY_MAX_DELAY_SECS),
             max_retries: DEFAULT_RETRY_MAX_RETRIES,
             jitter: true,
+            on_retry: None,
         }
     }
 }
@@ -66,19 +141,32 @@ impl Retryer {
         Self { config }
     }
 
-    pub async fn run_with_retry<F, Fut>(&self, mut operation: F) -> Result<reqwest::Response>
+    pub fn config(&self) -> &RetryConfig {
+        &self.config
+    }
+
+    pub async fn run_with_retry<F, Fut>(&self, operation: F) -> Result<reqwest::Response>
     where
         F: FnMut() -> Fut,
         Fut: std::future::Future<Output = std::result::Result<reqwest::Response, Error>>,
     {
-        let mut builder = ExponentialBuilder::default()
-            .with_min_delay(self.config.min_delay)
-            .with_max_delay(self.config.max_delay)
-            .with_max_times(self.config.max_retries);
-        if self.config.jitter {
-            builder = builder.with_jitter();
-        }
-        let mut backoff = builder.build();
+        self.run_observed(operation, None, DEFAULT_MAX_RESPONSE_SIZE)
+            .await
+    }
+
+    /// [`Retryer::run_with_retry`], reporting each wait before a retry to `observer`. Bodies of
+    /// responses that are retried or turned into errors are read up to `body_limit` bytes.
+    pub(crate) async fn run_observed<F, Fut>(
+        &self,
+        mut operation: F,
+        observer: Option<&PhaseObserver>,
+        body_limit: usize,
+    ) -> Result<reqwest::Response>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = std::result::Result<reqwest::Response, Error>>,
+    {
+        let mut backoff = self.config.backoff();
         let mut attempts = 0usize;
 
         loop {
@@ -88,8 +176,11 @@ impl Retryer {
                         let status = response.status();
                         let retry_after = retry_after_delay(&response);
                         if attempts >= self.config.max_retries {
-                            let body = response.text().await.unwrap_or_default();
-                            return Err(Error::http(status, body));
+                            let body = read_body(response, body_limit).await?;
+                            return Err(Error::http(
+                                status,
+                                String::from_utf8_lossy(&body).into_owned(),
+                            ));
                         }
                         attempts += 1;
                         let max_delay = self.config.max_delay;
@@ -98,7 +189,8 @@ impl Retryer {
                             .map(|d| d.min(max_delay))
                             .or(backoff_delay)
                             .unwrap_or(Duration::from_millis(0));
-                        let _ = response.bytes().await;
+                        let _ = read_body(response, body_limit).await;
+                        self.notify(attempts, delay, Some(status), retry_after);
                         warn!(
                             %status,
                             attempt = attempts,
@@ -106,7 +198,7 @@ impl Retryer {
                             retry_after = ?delay,
                             "retrying HTTP request due to status"
                         );
-                        sleep(delay).await;
+                        wait(delay, observer).await;
                         continue;
                     }
                     return Ok(response);
@@ -118,6 +210,11 @@ impl Retryer {
                         }
                         attempts += 1;
                         if let Some(delay) = backoff.next().map(|d| d.min(self.config.max_delay)) {
+                            let status = match &err {
+                                Error::Http { status, .. } => Some(*status),
+                                _ => None,
+                            };
+                            self.notify(attempts, delay, status, None);
                             warn!(
                                 ?err,
                                 attempt = attempts,
@@ -125,7 +222,7 @@ impl Retryer {
                                 retry_after = ?delay,
                                 "retrying HTTP request due to error"
                             );
-                            sleep(delay).await;
+                            wait(delay, observer).await;
                             continue;
                         }
                     }
@@ -134,9 +231,25 @@ impl Retryer {
             }
         }
     }
+
+    fn notify(
+        &self,
+        attempt: usize,
+        delay: Duration,
+        status: Option<StatusCode>,
+        retry_after: Option<Duration>,
+    ) {
+        self.config.notify(attempt, delay, status, retry_after);
+    }
+}
+
+async fn wait(delay: Duration, observer: Option<&PhaseObserver>) {
+    let started = observer::start(observer);
+    sleep(delay).await;
+    observer::finish(observer, started, Phase::RetryWait);
 }
 
-fn should_retry_status(status: StatusCode) -> bool {
+pub(crate) fn should_retry_status(status: StatusCode) -> bool {
     matches!(
         status,
         StatusCode::TOO_MANY_REQUESTS | StatusCode::REQUEST_TIMEOUT
@@ -150,15 +263,16 @@ fn retry_after_delay(response: &reqwest::Response) -> Option<Duration> {
         .and_then(|value| parse_retry_after(value.to_str().ok()?, SystemTime::now()))
 }
 
-fn is_retryable_error(err: &Error) -> bool {
+pub(crate) fn is_retryable_error(err: &Error) -> bool {
     match err {
         Error::Request(req_err) => req_err.is_connect() || req_err.is_timeout(),
+        Error::Timeout { .. } | Error::Connect { .. } => true,
         Error::Http { status, .. } => should_retry_status(*status),
         _ => false,
     }
 }
 
-fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+pub(crate) fn parse_retry_after(value: &str, now: SystemTime) -> Option<Duration> {
     if let Ok(seconds) = value.parse::<u64>() {
         return Some(Duration::from_secs(seconds));
     }
@@ -210,6 +324,8 @@ mod tests {
         assert!(!should_retry_status(StatusCode::NOT_FOUND));
         assert!(!should_retry_status(StatusCode::BAD_REQUEST));
         assert!(!should_retry_status(StatusCode::UNAUTHORIZED));
+        // A conflict will not resolve itself by repeating the same request.
+        assert!(!should_retry_status(StatusCode::CONFLICT));
     }
 
     #[test]
@@ -224,6 +340,111 @@ mod tests {
         assert!(is_retryable_error(&err));
     }
 
+    #[tokio::test]
+    async fn on_retry_reports_attempt_delay_and_status() {
+        use std::sync::Mutex;
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "3"))
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .with_priority(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200))
+            .with_priority(3)
+            .mount(&server)
+            .await;
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&events);
+        let retryer = Retryer::new(
+            RetryConfig::default()
+                .with_min_delay(Duration::from_millis(10))
+                .with_max_delay(Duration::from_millis(40))
+                .with_max_retries(5)
+                .with_jitter(false)
+                .with_on_retry(move |event| recorded.lock().unwrap().push(event.clone())),
+        );
+        let client = reqwest::Client::new();
+        let response = retryer
+            .run_with_retry(|| {
+                let request = client.get(server.uri());
+                async move { request.send().await.map_err(Error::from) }
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let events = events.lock().unwrap();
+        assert_eq!(
+            *events,
+            vec![
+                // Retry-After wins over backoff but is capped at max_delay.
+                RetryEvent {
+                    attempt: 1,
+                    max_retries: 5,
+                    delay: Duration::from_millis(40),
+                    status: Some(StatusCode::TOO_MANY_REQUESTS),
+                    retry_after: Some(Duration::from_secs(3)),
+                },
+                RetryEvent {
+                    attempt: 2,
+                    max_retries: 5,
+                    delay: Duration::from_millis(20),
+                    status: Some(StatusCode::SERVICE_UNAVAILABLE),
+                    retry_after: None,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn disabled_makes_exactly_one_attempt() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let retries = Arc::new(AtomicUsize::new(0));
+        let counted = Arc::clone(&retries);
+        let retryer = Retryer::new(RetryConfig::disabled().with_on_retry(move |_| {
+            counted.fetch_add(1, Ordering::SeqCst);
+        }));
+        let attempts = AtomicUsize::new(0);
+        let client = reqwest::Client::new();
+        let err = retryer
+            .run_with_retry(|| {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                let request = client.get(server.uri());
+                async move { request.send().await.map_err(Error::from) }
+            })
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            Error::Http { status, .. } if status == StatusCode::TOO_MANY_REQUESTS
+        ));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+        assert_eq!(retries.load(Ordering::SeqCst), 0);
+    }
+
     #[test]
     fn is_retryable_error_for_non_retryable() {
         let err = Error::TokenProvider("test".to_string());
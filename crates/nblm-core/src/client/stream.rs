@@ -0,0 +1,354 @@
+//! Incremental decoding of list responses.
+//!
+//! A list page can be several megabytes. Instead of buffering the body and decoding it into a
+//! `Vec`, [`JsonArrayStream`] runs serde_json on a blocking thread over the body chunks as they
+//! arrive and hands the items of one array field over one at a time. Both hand-offs are bounded
+//! channels, so memory stays around one body chunk plus one item however large the page is.
+
+use std::fmt;
+use std::io::{self, Read};
+use std::marker::PhantomData;
+
+use bytes::{Buf, Bytes};
+use serde::de::{self, DeserializeOwned, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde_json::{Map, Value};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::error::{Error, Result};
+
+/// Body chunks read ahead of the parser.
+const CHUNK_BUFFER: usize = 4;
+
+/// Items of one array field of a JSON object response, decoded as they arrive.
+///
+/// Take the items with [`JsonArrayStream::next_item`], then the object's other fields with
+/// [`JsonArrayStream::rest`]. Dropping the stream stops reading the response.
+pub(crate) struct JsonArrayStream<T> {
+    items: mpsc::Receiver<Result<T>>,
+    rest: oneshot::Receiver<Map<String, Value>>,
+}
+
+impl<T: Send + 'static> JsonArrayStream<T> {
+    /// Decode `field` of the JSON object in `response` item by item as `W`, converted into `T`.
+    pub(crate) fn spawn<W>(response: reqwest::Response, field: &'static str) -> Self
+    where
+        W: DeserializeOwned + Into<T>,
+    {
+        let (chunk_tx, chunk_rx) = mpsc::channel(CHUNK_BUFFER);
+        tokio::spawn(pump_chunks(response, chunk_tx));
+        Self::from_chunks::<W>(chunk_rx, field)
+    }
+
+    fn from_chunks<W>(chunks: mpsc::Receiver<io::Result<Bytes>>, field: &'static str) -> Self
+    where
+        W: DeserializeOwned + Into<T>,
+    {
+        // Capacity 1: the parser stays at most one item ahead of the consumer.
+        let (item_tx, items) = mpsc::channel(1);
+        let (rest_tx, rest) = oneshot::channel();
+        tokio::task::spawn_blocking(move || {
+            let reader = ChunkReader {
+                chunks,
+                current: Bytes::new(),
+            };
+            let mut deserializer = serde_json::Deserializer::from_reader(reader);
+            let seed = ObjectSeed::<W, T> {
+                field,
+                items: &item_tx,
+                _item: PhantomData,
+            };
+            let parsed = seed
+                .deserialize(&mut deserializer)
+                .and_then(|other_fields| deserializer.end().map(|()| other_fields));
+            match parsed {
+                Ok(other_fields) => {
+                    let _ = rest_tx.send(other_fields);
+                }
+                // Nobody is listening when the consumer dropped the stream; otherwise the
+                // parse error ends the items.
+                Err(err) => {
+                    let _ = item_tx.blocking_send(Err(Error::Json(err)));
+                }
+            }
+        });
+        Self { items, rest }
+    }
+
+    /// A stream over items that are already decoded, followed by `rest`.
+    #[cfg(feature = "test-util")]
+    pub(crate) fn from_items(items: Vec<T>, rest: Map<String, Value>) -> Self {
+        let (item_tx, item_rx) = mpsc::channel(items.len().max(1));
+        for item in items {
+            item_tx
+                .try_send(Ok(item))
+                .expect("channel has room for every item");
+        }
+        let (rest_tx, rest_rx) = oneshot::channel();
+        let _ = rest_tx.send(rest);
+        Self {
+            items: item_rx,
+            rest: rest_rx,
+        }
+    }
+
+    /// The next item, or `None` once the array has ended. Stop after the first error.
+    pub(crate) async fn next_item(&mut self) -> Option<Result<T>> {
+        self.items.recv().await
+    }
+
+    /// The object's fields other than the array, once every item has been taken.
+    pub(crate) async fn rest(self) -> Result<Map<String, Value>> {
+        self.rest.await.map_err(|_| {
+            Error::Endpoint("list response ended before it was fully decoded".to_string())
+        })
+    }
+}
+
+async fn pump_chunks(mut response: reqwest::Response, chunks: mpsc::Sender<io::Result<Bytes>>) {
+    loop {
+        let next = match response.chunk().await {
+            Ok(Some(chunk)) => Ok(chunk),
+            Ok(None) => return,
+            Err(err) => Err(io::Error::other(err)),
+        };
+        let failed = next.is_err();
+        if chunks.send(next).await.is_err() || failed {
+            return;
+        }
+    }
+}
+
+/// Blocking [`Read`] over the body chunks sent by [`pump_chunks`].
+struct ChunkReader {
+    chunks: mpsc::Receiver<io::Result<Bytes>>,
+    current: Bytes,
+}
+
+impl Read for ChunkReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while !self.current.has_remaining() {
+            match self.chunks.blocking_recv() {
+                Some(chunk) => self.current = chunk?,
+                None => return Ok(0),
+            }
+        }
+        let len = buf.len().min(self.current.len());
+        self.current.copy_to_slice(&mut buf[..len]);
+        Ok(len)
+    }
+}
+
+/// Decodes the top-level object: `field` goes to [`ArraySeed`], everything else is collected.
+struct ObjectSeed<'a, W, T> {
+    field: &'static str,
+    items: &'a mpsc::Sender<Result<T>>,
+    _item: PhantomData<fn() -> W>,
+}
+
+impl<'de, W, T> DeserializeSeed<'de> for ObjectSeed<'_, W, T>
+where
+    W: DeserializeOwned + Into<T>,
+{
+    type Value = Map<String, Value>;
+
+    fn deserialize<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, W, T> Visitor<'de> for ObjectSeed<'_, W, T>
+where
+    W: DeserializeOwned + Into<T>,
+{
+    type Value = Map<String, Value>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a JSON object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(
+        self,
+        mut map: A,
+    ) -> std::result::Result<Self::Value, A::Error> {
+        let mut other_fields = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if key == self.field {
+                map.next_value_seed(ArraySeed::<W, T> {
+                    items: self.items,
+                    _item: PhantomData,
+                })?;
+            } else {
+                other_fields.insert(key, map.next_value()?);
+            }
+        }
+        Ok(other_fields)
+    }
+}
+
+/// Sends each element of an array (or nothing for `null`) to the consumer as it is decoded.
+struct ArraySeed<'a, W, T> {
+    items: &'a mpsc::Sender<Result<T>>,
+    _item: PhantomData<fn() -> W>,
+}
+
+impl<'de, W, T> DeserializeSeed<'de> for ArraySeed<'_, W, T>
+where
+    W: DeserializeOwned + Into<T>,
+{
+    type Value = ();
+
+    fn deserialize<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<(), D::Error> {
+        deserializer.deserialize_option(self)
+    }
+}
+
+impl<'de, W, T> Visitor<'de> for ArraySeed<'_, W, T>
+where
+    W: DeserializeOwned + Into<T>,
+{
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an array")
+    }
+
+    fn visit_none<E: de::Error>(self) -> std::result::Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_unit<E: de::Error>(self) -> std::result::Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_some<D: de::Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<(), A::Error> {
+        while let Some(item) = seq.next_element::<W>()? {
+            if self.items.blocking_send(Ok(item.into())).is_err() {
+                return Err(de::Error::custom("list stream dropped"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Item {
+        id: u32,
+    }
+
+    /// Stream over `body` split into `chunk_size`-byte chunks.
+    fn stream_of(body: &str, chunk_size: usize) -> JsonArrayStream<Item> {
+        let (tx, rx) = mpsc::channel(CHUNK_BUFFER);
+        let chunks: Vec<Bytes> = body
+            .as_bytes()
+            .chunks(chunk_size)
+            .map(Bytes::copy_from_slice)
+            .collect();
+        tokio::spawn(async move {
+            for chunk in chunks {
+                if tx.send(Ok(chunk)).await.is_err() {
+                    return;
+                }
+            }
+        });
+        JsonArrayStream::from_chunks::<Item>(rx, "items")
+    }
+
+    async fn collect(stream: &mut JsonArrayStream<Item>) -> Vec<Result<Item>> {
+        let mut items = Vec::new();
+        while let Some(item) = stream.next_item().await {
+            items.push(item);
+        }
+        items
+    }
+
+    #[tokio::test]
+    async fn items_and_other_fields_survive_any_chunk_boundary() {
+        let body = r#"{"before": 1, "items": [{"id": 1}, {"id": 2}, {"id": 3}], "next": "tok"}"#;
+        for chunk_size in [1, 2, 7, body.len()] {
+            let mut stream = stream_of(body, chunk_size);
+            let ids: Vec<u32> = collect(&mut stream)
+                .await
+                .into_iter()
+                .map(|item| item.unwrap().id)
+                .collect();
+            assert_eq!(ids, [1, 2, 3], "chunk size {chunk_size}");
+            let rest = stream.rest().await.unwrap();
+            assert_eq!(rest["before"], 1);
+            assert_eq!(rest["next"], "tok");
+        }
+    }
+
+    #[tokio::test]
+    async fn missing_or_null_array_yields_no_items() {
+        for body in [r#"{"next": "tok"}"#, r#"{"items": null}"#, "{}"] {
+            let mut stream = stream_of(body, 3);
+            assert!(collect(&mut stream).await.is_empty(), "{body}");
+            stream.rest().await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn malformed_body_fails_after_the_items_before_it() {
+        let mut stream = stream_of(r#"{"items": [{"id": 1}, {"id": "two"}]}"#, 4);
+        let items = collect(&mut stream).await;
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].as_ref().unwrap(), &Item { id: 1 });
+        assert!(matches!(items[1], Err(Error::Json(_))), "{:?}", items[1]);
+        assert!(stream.rest().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn truncated_body_is_an_error() {
+        let mut stream = stream_of(r#"{"items": [{"id": 1}, {"id""#, 5);
+        let items = collect(&mut stream).await;
+        assert!(items.last().unwrap().is_err());
+    }
+
+    #[tokio::test]
+    async fn dropping_the_stream_stops_the_parser() {
+        let body = format!(
+            r#"{{"items": [{}]}}"#,
+            (0..1000)
+                .map(|id| format!(r#"{{"id": {id}}}"#))
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+        let (tx, rx) = mpsc::channel(CHUNK_BUFFER);
+        let mut stream = JsonArrayStream::<Item>::from_chunks::<Item>(rx, "items");
+        let sender = tokio::spawn(async move {
+            let mut sent = 0usize;
+            for chunk in body.as_bytes().chunks(16) {
+                if tx.send(Ok(Bytes::copy_from_slice(chunk))).await.is_err() {
+                    break;
+                }
+                sent += 1;
+            }
+            sent
+        });
+
+        assert_eq!(stream.next_item().await.unwrap().unwrap().id, 0);
+        drop(stream);
+
+        // The parser gives up, so the body is not read to the end.
+        let total_chunks = (1000 * 11) / 16;
+        assert!(sender.await.unwrap() < total_chunks);
+    }
+}
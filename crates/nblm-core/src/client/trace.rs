@@ -0,0 +1,277 @@
+use reqwest::header::{HeaderMap, HeaderValue};
+
+use crate::error::{Error, Result};
+
+const TRACEPARENT: &str = "traceparent";
+const TRACESTATE: &str = "tracestate";
+
+const TRACEPARENT_FORM: &str =
+    "expected 00-<32 hex trace id>-<16 hex parent id>-<2 hex flags>, lowercase";
+
+/// W3C Trace Context sent as the `traceparent` and `tracestate` headers, so requests show up as
+/// children of the caller's span instead of as orphan spans.
+///
+/// Set it for every request with [`NblmClient::with_trace_context`], or for a single call with
+/// [`NblmClient::raw_request_with_trace`]. With the `otel` feature, a request that has neither
+/// takes its context from the current `tracing` span (see [`TraceContext::from_span`]).
+///
+/// [`NblmClient::with_trace_context`]: super::NblmClient::with_trace_context
+/// [`NblmClient::raw_request_with_trace`]: super::NblmClient::raw_request_with_trace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    traceparent: String,
+    tracestate: Option<String>,
+}
+
+impl TraceContext {
+    /// Parse a `traceparent` value such as
+    /// `00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01`.
+    ///
+    /// Versions other than `00` are accepted as long as they start with the same four fields.
+    pub fn parse(traceparent: &str) -> Result<Self> {
+        let value = traceparent.trim();
+        if !is_valid_traceparent(value) {
+            return Err(Error::Validation(format!(
+                "invalid traceparent '{value}': {TRACEPARENT_FORM}"
+            )));
+        }
+        Ok(Self {
+            traceparent: value.to_string(),
+            tracestate: None,
+        })
+    }
+
+    /// Also send `tracestate`. A blank value sends none.
+    pub fn with_tracestate(mut self, tracestate: &str) -> Result<Self> {
+        let value = tracestate.trim();
+        if value.is_empty() {
+            self.tracestate = None;
+            return Ok(self);
+        }
+        if !value.bytes().all(|byte| (b' '..=b'~').contains(&byte)) {
+            return Err(Error::Validation(format!(
+                "invalid tracestate '{value}': only printable ASCII is allowed"
+            )));
+        }
+        self.tracestate = Some(value.to_string());
+        Ok(self)
+    }
+
+    pub fn traceparent(&self) -> &str {
+        &self.traceparent
+    }
+
+    pub fn tracestate(&self) -> Option<&str> {
+        self.tracestate.as_deref()
+    }
+
+    /// The OpenTelemetry span context attached to `span` by a `tracing-opentelemetry` layer, or
+    /// `None` when no layer is installed or the span has no valid context.
+    #[cfg(feature = "otel")]
+    pub fn from_span(span: &tracing::Span) -> Option<Self> {
+        use opentelemetry::trace::TraceContextExt;
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+        let context = span.context();
+        let otel_span = context.span();
+        let span_context = otel_span.span_context();
+        if !span_context.is_valid() {
+            return None;
+        }
+        let tracestate = span_context.trace_state().header();
+        Some(Self {
+            traceparent: format!(
+                "00-{}-{}-{:02x}",
+                span_context.trace_id(),
+                span_context.span_id(),
+                span_context.trace_flags().to_u8()
+            ),
+            tracestate: (!tracestate.is_empty()).then_some(tracestate),
+        })
+    }
+
+    /// Add the headers to `headers` unless a `traceparent` is already there, so a per-request
+    /// context (with its own `tracestate`, or none) wins over the client-wide one.
+    pub(crate) fn apply_default(&self, headers: &mut HeaderMap) {
+        if !headers.contains_key(TRACEPARENT) {
+            headers.extend(self.headers());
+        }
+    }
+
+    pub(crate) fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        // Both values were checked to be printable ASCII when parsed.
+        headers.insert(
+            TRACEPARENT,
+            HeaderValue::from_str(&self.traceparent).expect("validated traceparent"),
+        );
+        if let Some(state) = &self.tracestate {
+            headers.insert(
+                TRACESTATE,
+                HeaderValue::from_str(state).expect("validated tracestate"),
+            );
+        }
+        headers
+    }
+}
+
+fn is_valid_traceparent(value: &str) -> bool {
+    let mut fields = value.split('-');
+    let (Some(version), Some(trace_id), Some(parent_id), Some(flags)) =
+        (fields.next(), fields.next(), fields.next(), fields.next())
+    else {
+        return false;
+    };
+    let extra = fields.next().is_some();
+    is_lower_hex(version, 2)
+        && version != "ff"
+        && !(version == "00" && extra)
+        && is_lower_hex(trace_id, 32)
+        && !is_all_zero(trace_id)
+        && is_lower_hex(parent_id, 16)
+        && !is_all_zero(parent_id)
+        && is_lower_hex(flags, 2)
+}
+
+fn is_lower_hex(field: &str, len: usize) -> bool {
+    field.len() == len
+        && field
+            .bytes()
+            .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+fn is_all_zero(field: &str) -> bool {
+    field.bytes().all(|b| b == b'0')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::StaticTokenProvider;
+    use crate::client::NblmClient;
+    use crate::env::EnvironmentConfig;
+    use rstest::rstest;
+    use std::sync::Arc;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const VALID: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    #[test]
+    fn parse_accepts_a_valid_traceparent() {
+        let context = TraceContext::parse(&format!(" {VALID} ")).unwrap();
+        assert_eq!(context.traceparent(), VALID);
+        assert_eq!(context.tracestate(), None);
+    }
+
+    #[test]
+    fn parse_accepts_future_versions_with_extra_fields() {
+        TraceContext::parse(
+            "cc-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-what-the-future-holds",
+        )
+        .unwrap();
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::uppercase("00-4BF92F3577B34DA6A3CE929D0E0E4736-00F067AA0BA902B7-01")]
+    #[case::short_trace_id("00-4bf92f3577b34da6-00f067aa0ba902b7-01")]
+    #[case::zero_trace_id("00-00000000000000000000000000000000-00f067aa0ba902b7-01")]
+    #[case::zero_parent_id("00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01")]
+    #[case::forbidden_version("ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")]
+    #[case::extra_field_in_v00("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-00")]
+    #[case::missing_flags("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7")]
+    fn parse_rejects_malformed_traceparents(#[case] value: &str) {
+        let err = TraceContext::parse(value).unwrap_err();
+        assert!(matches!(err, Error::Validation(_)), "{err}");
+        assert!(err.to_string().contains("invalid traceparent"), "{err}");
+    }
+
+    #[test]
+    fn tracestate_must_be_printable_ascii() {
+        let context = TraceContext::parse(VALID).unwrap();
+        let with_state = context
+            .clone()
+            .with_tracestate("congo=t61rcWkgMzE")
+            .unwrap();
+        assert_eq!(with_state.tracestate(), Some("congo=t61rcWkgMzE"));
+        assert_eq!(
+            context.clone().with_tracestate("  ").unwrap().tracestate(),
+            None
+        );
+        assert!(context.with_tracestate("a=b\nc").is_err());
+    }
+
+    #[test]
+    fn apply_default_keeps_an_existing_traceparent() {
+        let client_wide = TraceContext::parse(VALID)
+            .unwrap()
+            .with_tracestate("vendor=client")
+            .unwrap();
+        let per_request =
+            TraceContext::parse("00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-00").unwrap();
+
+        let mut headers = per_request.headers();
+        client_wide.apply_default(&mut headers);
+        assert_eq!(headers[TRACEPARENT], per_request.traceparent());
+        assert!(!headers.contains_key(TRACESTATE));
+
+        let mut headers = HeaderMap::new();
+        client_wide.apply_default(&mut headers);
+        assert_eq!(headers[TRACEPARENT], VALID);
+        assert_eq!(headers[TRACESTATE], "vendor=client");
+    }
+
+    async fn client_against(server: &MockServer) -> NblmClient {
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"title": "A"})),
+            )
+            .mount(server)
+            .await;
+        let provider = Arc::new(StaticTokenProvider::new("test-token"));
+        let env = EnvironmentConfig::enterprise("123", "global", "global").unwrap();
+        NblmClient::new(provider, env)
+            .unwrap()
+            .with_base_url(format!("{}/v1alpha", server.uri()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn client_context_is_sent_on_every_request() {
+        let server = MockServer::start().await;
+        let context = TraceContext::parse(VALID)
+            .unwrap()
+            .with_tracestate("vendor=value")
+            .unwrap();
+        let client = client_against(&server)
+            .await
+            .with_trace_context(context.clone());
+        assert_eq!(client.trace_context(), Some(&context));
+
+        client.list_recently_viewed(None).await.unwrap();
+        client.get_notebook("nb1").await.unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 2);
+        for request in requests {
+            let sent = request.headers[TRACEPARENT].to_str().unwrap();
+            assert!(is_valid_traceparent(sent), "{sent}");
+            assert_eq!(sent, VALID);
+            assert_eq!(request.headers[TRACESTATE], "vendor=value");
+        }
+    }
+
+    #[tokio::test]
+    async fn no_trace_headers_without_a_context() {
+        let server = MockServer::start().await;
+        let client = client_against(&server).await;
+
+        client.list_recently_viewed(None).await.unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(!requests[0].headers.contains_key(TRACEPARENT));
+        assert!(!requests[0].headers.contains_key(TRACESTATE));
+    }
+}
@@ -0,0 +1,171 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+/// Why a local file could not be read for [`NblmClient::upload_source_file`](super::NblmClient::upload_source_file).
+#[derive(Error, Debug)]
+pub enum UploadFileError {
+    #[error("file not found: {}", .path.display())]
+    NotFound { path: PathBuf },
+    #[error("path is not a file: {}", .path.display())]
+    NotAFile { path: PathBuf },
+    #[error("cannot upload empty files: {} is empty", .path.display())]
+    Empty { path: PathBuf },
+    #[error("{} is {size} bytes, larger than the {limit}-byte limit", .path.display())]
+    TooLarge {
+        path: PathBuf,
+        size: u64,
+        limit: u64,
+    },
+    #[error(
+        "{} changed while it was being read (expected {expected} bytes, read {read})",
+        .path.display()
+    )]
+    ChangedDuringRead {
+        path: PathBuf,
+        expected: u64,
+        read: u64,
+    },
+    #[error("failed to read {}: {source}", .path.display())]
+    Io { path: PathBuf, source: io::Error },
+}
+
+/// Read `path` for upload, checking the file that was actually opened rather than the path.
+///
+/// The file is opened once and its metadata taken from the handle, so a path swapped for a FIFO,
+/// a directory or a larger file between a check and the read cannot slip through. Non-regular
+/// files, empty files and (with `max_size`) oversized files are rejected before reading, and the
+/// read fails if the length no longer matches what the handle reported.
+pub fn read_upload_file(
+    path: &Path,
+    max_size: Option<u64>,
+) -> std::result::Result<Vec<u8>, UploadFileError> {
+    let io_error = |source: io::Error| UploadFileError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let mut file = open(path).map_err(|source| match source.kind() {
+        io::ErrorKind::NotFound => UploadFileError::NotFound {
+            path: path.to_path_buf(),
+        },
+        _ => io_error(source),
+    })?;
+    let metadata = file.metadata().map_err(io_error)?;
+    if !metadata.is_file() {
+        return Err(UploadFileError::NotAFile {
+            path: path.to_path_buf(),
+        });
+    }
+    let size = metadata.len();
+    if size == 0 {
+        return Err(UploadFileError::Empty {
+            path: path.to_path_buf(),
+        });
+    }
+    if let Some(limit) = max_size.filter(|limit| size > *limit) {
+        return Err(UploadFileError::TooLarge {
+            path: path.to_path_buf(),
+            size,
+            limit,
+        });
+    }
+
+    // One byte past the expected size is enough to tell that the file grew.
+    let mut data = Vec::with_capacity(usize::try_from(size).unwrap_or_default());
+    (&mut file)
+        .take(size.saturating_add(1))
+        .read_to_end(&mut data)
+        .map_err(io_error)?;
+    let read = data.len() as u64;
+    if read != size {
+        return Err(UploadFileError::ChangedDuringRead {
+            path: path.to_path_buf(),
+            expected: size,
+            read,
+        });
+    }
+    Ok(data)
+}
+
+/// Open without blocking: opening a FIFO for reading otherwise waits for a writer, before the
+/// handle can be checked at all.
+#[cfg(unix)]
+fn open(path: &Path) -> io::Result<File> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(path)
+}
+
+#[cfg(not(unix))]
+fn open(path: &Path) -> io::Result<File> {
+    OpenOptions::new().read(true).open(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_a_regular_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, b"hello").unwrap();
+        assert_eq!(read_upload_file(&path, Some(5)).unwrap(), b"hello");
+        assert_eq!(read_upload_file(&path, None).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn rejects_missing_empty_and_oversized_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing.txt");
+        assert!(matches!(
+            read_upload_file(&missing, None),
+            Err(UploadFileError::NotFound { .. })
+        ));
+
+        let empty = dir.path().join("empty.txt");
+        std::fs::write(&empty, b"").unwrap();
+        let err = read_upload_file(&empty, None).unwrap_err();
+        assert!(matches!(err, UploadFileError::Empty { .. }));
+        assert!(err.to_string().starts_with("cannot upload empty files"));
+
+        let large = dir.path().join("large.bin");
+        std::fs::write(&large, vec![0u8; 2048]).unwrap();
+        let err = read_upload_file(&large, Some(1024)).unwrap_err();
+        assert!(matches!(
+            err,
+            UploadFileError::TooLarge {
+                size: 2048,
+                limit: 1024,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn rejects_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            read_upload_file(dir.path(), None),
+            Err(UploadFileError::NotAFile { .. })
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_a_fifo_without_blocking() {
+        let dir = tempfile::tempdir().unwrap();
+        let fifo = dir.path().join("pipe");
+        let c_path = std::ffi::CString::new(fifo.as_os_str().as_encoded_bytes()).unwrap();
+        assert_eq!(unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) }, 0);
+
+        let err = read_upload_file(&fifo, None).unwrap_err();
+        assert!(matches!(err, UploadFileError::NotAFile { .. }));
+        assert!(err.to_string().contains("is not a file"));
+    }
+}
@@ -1,6 +1,7 @@
 use reqwest::Url;
 
 use super::UrlBuilder;
+use crate::env::ApiVersion;
 use crate::error::{Error, Result};
 
 /// Enterprise-specific URL builder.
@@ -8,11 +9,16 @@ use crate::error::{Error, Result};
 pub(crate) struct EnterpriseUrlBuilder {
     base: String,
     parent: String,
+    version: ApiVersion,
 }
 
 impl EnterpriseUrlBuilder {
-    pub fn new(base: String, parent: String) -> Self {
-        Self { base, parent }
+    pub fn new(base: String, parent: String, version: ApiVersion) -> Self {
+        Self {
+            base,
+            parent,
+            version,
+        }
     }
 }
 
@@ -33,8 +39,9 @@ impl UrlBuilder for EnterpriseUrlBuilder {
     fn build_upload_url(&self, path: &str) -> Result<Url> {
         let base = self.base.trim_end_matches('/');
         let trimmed_path = path.trim_start_matches('/');
-        let upload_base = if let Some((prefix, _)) = base.rsplit_once("/v1alpha") {
-            format!("{}/upload/v1alpha/{}", prefix, trimmed_path)
+        let version = self.version.as_str();
+        let upload_base = if let Some((prefix, _)) = base.rsplit_once(&format!("/{version}")) {
+            format!("{}/upload/{}/{}", prefix, version, trimmed_path)
         } else {
             format!("{}/upload/{}", base, trimmed_path)
         };
@@ -51,6 +58,7 @@ mod tests {
         let builder = EnterpriseUrlBuilder::new(
             "http://example.com/v1alpha".to_string(),
             "projects/123/locations/global".to_string(),
+            ApiVersion::V1Alpha,
         );
 
         // Test with leading slash
@@ -73,6 +81,7 @@ mod tests {
         let builder = EnterpriseUrlBuilder::new(
             "https://us-discoveryengine.googleapis.com/v1alpha".to_string(),
             "projects/123/locations/global".to_string(),
+            ApiVersion::V1Alpha,
         );
 
         let url = builder
@@ -83,4 +92,21 @@ mod tests {
             "https://us-discoveryengine.googleapis.com/upload/v1alpha/projects/123/notebooks/abc/sources:uploadFile"
         );
     }
+
+    #[test]
+    fn build_upload_url_uses_the_configured_version() {
+        let builder = EnterpriseUrlBuilder::new(
+            "https://us-discoveryengine.googleapis.com/v1beta".to_string(),
+            "projects/123/locations/global".to_string(),
+            ApiVersion::V1Beta,
+        );
+
+        let url = builder
+            .build_upload_url("/projects/123/notebooks/abc/sources:uploadFile")
+            .unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://us-discoveryengine.googleapis.com/upload/v1beta/projects/123/notebooks/abc/sources:uploadFile"
+        );
+    }
 }
@@ -4,7 +4,7 @@ use std::sync::Arc;
 
 use reqwest::Url;
 
-use crate::env::ApiProfile;
+use crate::env::{ApiProfile, EnvironmentConfig};
 use crate::error::Result;
 
 pub(crate) use enterprise::EnterpriseUrlBuilder;
@@ -17,14 +17,15 @@ pub(crate) trait UrlBuilder: Send + Sync {
     fn build_upload_url(&self, path: &str) -> Result<Url>;
 }
 
-pub(crate) fn new_url_builder(
-    profile: ApiProfile,
-    base: String,
-    parent: String,
-) -> Arc<dyn UrlBuilder> {
+pub(crate) fn new_url_builder(environment: &EnvironmentConfig) -> Arc<dyn UrlBuilder> {
     // TODO(profile-support): add profile-specific builders when new SKUs become available.
+    let profile = environment.profile();
     match profile {
-        ApiProfile::Enterprise => Arc::new(EnterpriseUrlBuilder::new(base, parent)),
+        ApiProfile::Enterprise => Arc::new(EnterpriseUrlBuilder::new(
+            environment.base_url().to_string(),
+            environment.parent_path().to_string(),
+            environment.api_version(),
+        )),
         ApiProfile::Personal | ApiProfile::Workspace => {
             unimplemented!(
                 "UrlBuilder for profile '{}' is not implemented",
@@ -0,0 +1,288 @@
+//! Response logging for `NBLM_DEBUG_HTTP=1` and the CLI's `--debug-http`.
+//!
+//! The switch is read from the environment on first use and can be changed at any time with
+//! [`set_debug_http`], so turning it on after some requests were already made still logs the
+//! following ones. API calls and the OAuth token endpoints share it.
+
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+pub const DEBUG_HTTP_ENV: &str = "NBLM_DEBUG_HTTP";
+
+const MAX_BODY_PREVIEW: usize = 2048;
+
+/// Token fields replaced before an OAuth response body is logged.
+const SECRET_FIELDS: &[&str] = &["access_token", "refresh_token", "id_token"];
+
+const UNSET: u8 = 0;
+const OFF: u8 = 1;
+const ON: u8 = 2;
+
+static STATE: AtomicU8 = AtomicU8::new(UNSET);
+
+/// Turn logging of HTTP response bodies to stderr on or off for the whole process, overriding
+/// `NBLM_DEBUG_HTTP`. Logged bodies can contain notebook contents.
+pub fn set_debug_http(enabled: bool) {
+    STATE.store(if enabled { ON } else { OFF }, Ordering::Relaxed);
+}
+
+pub(crate) fn debug_http_enabled() -> bool {
+    match STATE.load(Ordering::Relaxed) {
+        UNSET => {
+            let enabled = std::env::var(DEBUG_HTTP_ENV).is_ok_and(|value| {
+                matches!(value.to_ascii_lowercase().as_str(), "1" | "true" | "yes")
+            });
+            // Keep a value set by `set_debug_http` in the meantime.
+            let _ = STATE.compare_exchange(
+                UNSET,
+                if enabled { ON } else { OFF },
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            );
+            STATE.load(Ordering::Relaxed) == ON
+        }
+        state => state == ON,
+    }
+}
+
+/// Log a response body when debugging is on.
+pub(crate) fn log_http_response(method: &str, url: &str, status: u16, body: &[u8]) {
+    if !debug_http_enabled() {
+        return;
+    }
+    emit(format!(
+        "[nblm::http] method={} status={} url={} body_len={} body={}",
+        method,
+        status,
+        url,
+        body.len(),
+        build_body_preview(body)
+    ));
+}
+
+/// Like [`log_http_response`] for OAuth token endpoints, with the tokens blanked out.
+pub(crate) fn log_token_response(method: &str, url: &str, status: u16, body: &[u8]) {
+    if !debug_http_enabled() {
+        return;
+    }
+    log_http_response(method, url, status, &redact_tokens(body));
+}
+
+fn redact_tokens(body: &[u8]) -> Cow<'_, [u8]> {
+    let Ok(serde_json::Value::Object(mut fields)) = serde_json::from_slice(body) else {
+        return Cow::Borrowed(body);
+    };
+    for field in SECRET_FIELDS {
+        if let Some(value) = fields.get_mut(*field) {
+            *value = serde_json::Value::String("<redacted>".to_string());
+        }
+    }
+    Cow::Owned(serde_json::to_vec(&fields).unwrap_or_default())
+}
+
+fn build_body_preview(body: &[u8]) -> Cow<'_, str> {
+    match std::str::from_utf8(body) {
+        Ok(text) => {
+            if text.len() > MAX_BODY_PREVIEW {
+                let mut end = MAX_BODY_PREVIEW;
+                while !text.is_char_boundary(end) {
+                    end -= 1;
+                }
+                let mut preview = text[..end].to_string();
+                preview.push('…');
+                Cow::Owned(preview)
+            } else {
+                Cow::Borrowed(text)
+            }
+        }
+        Err(_) => Cow::Owned(format!("<non-utf8 body: {} bytes>", body.len())),
+    }
+}
+
+#[cfg(not(test))]
+fn emit(line: String) {
+    eprintln!("{line}");
+}
+
+#[cfg(test)]
+static LOGGED: parking_lot::Mutex<Vec<String>> = parking_lot::Mutex::new(Vec::new());
+
+#[cfg(test)]
+fn emit(line: String) {
+    LOGGED.lock().push(line);
+}
+
+/// Lines logged so far, clearing the log. Tests using this must be `#[serial]`.
+#[cfg(test)]
+pub(crate) fn take_logged() -> Vec<String> {
+    std::mem::take(&mut *LOGGED.lock())
+}
+
+/// Forget [`set_debug_http`] so the next check reads the environment again.
+#[cfg(test)]
+pub(crate) fn reset() {
+    STATE.store(UNSET, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serial_test::serial;
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::{EnvironmentConfig, NblmClient, StaticTokenProvider};
+
+    async fn server() -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"title": "Logged"})),
+            )
+            .mount(&server)
+            .await;
+        server
+    }
+
+    fn client(server: &MockServer) -> NblmClient {
+        let env = EnvironmentConfig::enterprise("123", "global", "global").unwrap();
+        NblmClient::new(Arc::new(StaticTokenProvider::new("token")), env)
+            .unwrap()
+            .with_base_url(format!("{}/v1alpha", server.uri()))
+            .unwrap()
+    }
+
+    /// Lines logged for requests to `server`; tests running alongside may log their own.
+    fn logged_for(server: &MockServer) -> Vec<String> {
+        take_logged()
+            .into_iter()
+            .filter(|line| line.contains(&server.uri()))
+            .collect()
+    }
+
+    struct EnvGuard(Option<String>);
+
+    impl EnvGuard {
+        fn set(value: Option<&str>) -> Self {
+            let previous = std::env::var(DEBUG_HTTP_ENV).ok();
+            match value {
+                Some(value) => std::env::set_var(DEBUG_HTTP_ENV, value),
+                None => std::env::remove_var(DEBUG_HTTP_ENV),
+            }
+            reset();
+            take_logged();
+            Self(previous)
+        }
+    }
+
+    impl Drop for EnvGuard {
+        fn drop(&mut self) {
+            match &self.0 {
+                Some(value) => std::env::set_var(DEBUG_HTTP_ENV, value),
+                None => std::env::remove_var(DEBUG_HTTP_ENV),
+            }
+            reset();
+        }
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn enabling_after_a_request_logs_later_requests() {
+        let _env = EnvGuard::set(None);
+        let server = server().await;
+        let client = client(&server);
+
+        client.get_notebook("nb1").await.unwrap();
+        assert!(logged_for(&server).is_empty());
+
+        set_debug_http(true);
+        client.get_notebook("nb1").await.unwrap();
+
+        let logged = logged_for(&server);
+        assert_eq!(logged.len(), 1, "{logged:?}");
+        assert!(
+            logged[0].starts_with("[nblm::http] method=GET status=200")
+                && logged[0].contains("/notebooks/nb1")
+                && logged[0].contains(r#"body={"title":"Logged"}"#),
+            "{}",
+            logged[0]
+        );
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn env_var_alone_enables_logging() {
+        let _env = EnvGuard::set(Some("1"));
+        let server = server().await;
+
+        client(&server).get_notebook("nb1").await.unwrap();
+
+        assert_eq!(logged_for(&server).len(), 1);
+    }
+
+    #[test]
+    #[serial]
+    fn set_debug_http_overrides_the_env_var() {
+        let _env = EnvGuard::set(Some("true"));
+
+        set_debug_http(false);
+
+        assert!(!debug_http_enabled());
+    }
+
+    #[test]
+    fn token_fields_are_redacted() {
+        let body = br#"{"access_token":"ya29.secret","refresh_token":"1//r","expires_in":3599}"#;
+
+        let redacted = redact_tokens(body);
+
+        let text = std::str::from_utf8(&redacted).unwrap();
+        assert!(
+            !text.contains("ya29.secret") && !text.contains("1//r"),
+            "{text}"
+        );
+        assert!(text.contains(r#""expires_in":3599"#), "{text}");
+        assert_eq!(redact_tokens(b"not json").as_ref(), b"not json");
+    }
+
+    #[test]
+    fn build_body_preview_returns_borrowed_for_short_utf8() {
+        let input = b"short body";
+        let preview = build_body_preview(input);
+        assert!(matches!(preview, Cow::Borrowed("short body")));
+    }
+
+    #[test]
+    fn build_body_preview_truncates_long_utf8() {
+        let long_text = "x".repeat(MAX_BODY_PREVIEW + 10);
+        let preview = build_body_preview(long_text.as_bytes());
+        let expected = format!("{}…", "x".repeat(MAX_BODY_PREVIEW));
+        match preview {
+            Cow::Owned(truncated) => assert_eq!(truncated, expected),
+            _ => panic!("expected owned truncated preview"),
+        }
+    }
+
+    #[test]
+    fn build_body_preview_handles_non_utf8() {
+        let binary = [0xffu8, 0x00, 0xfe];
+        let preview = build_body_preview(&binary);
+        let expected = format!("<non-utf8 body: {} bytes>", binary.len());
+        match preview {
+            Cow::Owned(msg) => assert_eq!(msg, expected),
+            _ => panic!("expected owned message for non-utf8 body"),
+        }
+    }
+
+    #[test]
+    fn long_bodies_are_cut_on_a_char_boundary() {
+        let body = "é".repeat(MAX_BODY_PREVIEW);
+
+        let preview = build_body_preview(body.as_bytes());
+
+        assert!(preview.ends_with('…'));
+        assert!(preview.len() <= MAX_BODY_PREVIEW + '…'.len_utf8());
+    }
+}
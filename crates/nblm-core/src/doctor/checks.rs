@@ -1,11 +1,15 @@
 use colored::Colorize;
+use serde::Serialize;
 use std::env;
 
-use crate::auth::{ensure_drive_scope, EnvTokenProvider};
+use crate::auth::oauth::{validate_endpoint, AUTH_ENDPOINT_ENV, TOKEN_ENDPOINT_ENV};
+use crate::auth::{EnvTokenProvider, ScopeChecker, TokenProject};
+use crate::env::{normalize_endpoint_location, suggest_endpoint_location, ENDPOINT_LOCATIONS};
 use crate::error::Error;
 
 /// Status of a diagnostic check
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum CheckStatus {
     Pass,
     Warning,
@@ -46,12 +50,15 @@ impl CheckStatus {
 }
 
 /// Result of a single diagnostic check
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct CheckResult {
     pub name: String,
     pub status: CheckStatus,
     pub message: String,
     pub suggestion: Option<String>,
+    /// What the API access check listed; `None` for every other check.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notebooks: Option<NotebookSample>,
 }
 
 impl CheckResult {
@@ -61,6 +68,7 @@ impl CheckResult {
             status,
             message: message.into(),
             suggestion: None,
+            notebooks: None,
         }
     }
 
@@ -69,6 +77,17 @@ impl CheckResult {
         self
     }
 
+    /// Leave the notebook title out of the message and [`NotebookSample::latest_title`], for
+    /// output that is shared.
+    pub fn without_titles(mut self) -> Self {
+        if let Some(sample) = &mut self.notebooks {
+            if sample.latest_title.take().is_some() {
+                self.message = sample.describe();
+            }
+        }
+        self
+    }
+
     /// Format check result for display
     pub fn format(&self) -> String {
         self.format_with_marker(self.status.as_marker())
@@ -88,6 +107,91 @@ impl CheckResult {
     }
 }
 
+/// What the API access check's listing found, so a wrong project number or location shows up as
+/// an empty parent rather than a passing check.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NotebookSample {
+    /// `projects/{number}/locations/{location}` the notebooks were listed under.
+    pub parent: String,
+    /// Page size of the listing; `listed` equal to it means there may be more.
+    pub page_size: u32,
+    /// Recently viewed notebooks returned.
+    pub listed: usize,
+    /// Whether any notebook was found under `parent`.
+    pub parent_matched: bool,
+    /// Title of the most recently viewed notebook, cut to 40 characters.
+    pub latest_title: Option<String>,
+}
+
+/// Notebooks listed by the API access check.
+const PROBE_PAGE_SIZE: u32 = 50;
+/// Partial response mask for the API access check's listing.
+const PROBE_FIELDS: &str = "notebooks(notebookId,title)";
+/// Characters of the most recent notebook's title shown by the API access check.
+const TITLE_PREVIEW_CHARS: usize = 40;
+
+impl NotebookSample {
+    fn new(parent: &str, notebooks: &[crate::models::enterprise::notebook::Notebook]) -> Self {
+        Self {
+            parent: parent.to_string(),
+            page_size: PROBE_PAGE_SIZE,
+            listed: notebooks.len(),
+            parent_matched: !notebooks.is_empty(),
+            latest_title: notebooks
+                .first()
+                .map(|notebook| preview(&notebook.title, TITLE_PREVIEW_CHARS)),
+        }
+    }
+
+    fn describe(&self) -> String {
+        if !self.parent_matched {
+            return format!(
+                "Connected to NotebookLM API, but {} has no recently viewed notebooks",
+                self.parent
+            );
+        }
+        let more = if self.listed as u64 >= u64::from(self.page_size) {
+            " or more"
+        } else {
+            ""
+        };
+        let mut message = format!(
+            "Successfully connected to NotebookLM API: {}{more} recently viewed notebook(s) in {}",
+            self.listed, self.parent
+        );
+        if let Some(title) = &self.latest_title {
+            message.push_str(&format!(", most recent \"{title}\""));
+        }
+        message
+    }
+
+    fn into_check(self) -> CheckResult {
+        let result = if self.parent_matched {
+            CheckResult::new("api_connectivity", CheckStatus::Pass, self.describe())
+        } else {
+            CheckResult::new("api_connectivity", CheckStatus::Warning, self.describe())
+                .with_suggestion(
+                    "An empty list may mean a wrong NBLM_PROJECT_NUMBER or NBLM_LOCATION; \
+                     notebooks are listed once they have been opened",
+                )
+        };
+        CheckResult {
+            notebooks: Some(self),
+            ..result
+        }
+    }
+}
+
+/// `text` cut to `max` characters, ending in `…` when it was longer.
+fn preview(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(max - 1).collect();
+    cut.push('…');
+    cut
+}
+
 /// Summary of all diagnostic checks
 #[derive(Debug)]
 pub struct DiagnosticsSummary {
@@ -156,12 +260,16 @@ impl DiagnosticsSummary {
     }
 }
 
+/// Check on a present environment variable value: `Err((problem, suggestion))` when unusable.
+pub type EnvVarValidator = fn(&str) -> std::result::Result<(), (String, String)>;
+
 /// Configuration for an environment variable check
 pub struct EnvVarCheck {
     pub name: &'static str,
     pub required: bool,
     pub suggestion: &'static str,
     pub show_value: bool,
+    pub validate: Option<EnvVarValidator>,
 }
 
 /// Static configuration table for environment variable checks
@@ -171,24 +279,28 @@ const ENV_VAR_CHECKS: &[EnvVarCheck] = &[
         required: true,
         suggestion: "export NBLM_PROJECT_NUMBER=<your-project-number>",
         show_value: true,
+        validate: None,
     },
     EnvVarCheck {
         name: "NBLM_ENDPOINT_LOCATION",
         required: false,
         suggestion: "export NBLM_ENDPOINT_LOCATION=us  # or 'eu' or 'global'",
         show_value: true,
+        validate: Some(validate_endpoint_location),
     },
     EnvVarCheck {
         name: "NBLM_LOCATION",
         required: false,
         suggestion: "export NBLM_LOCATION=global",
         show_value: true,
+        validate: None,
     },
     EnvVarCheck {
         name: "NBLM_ACCESS_TOKEN",
         required: false,
         suggestion: "export NBLM_ACCESS_TOKEN=$(gcloud auth print-access-token)",
         show_value: false,
+        validate: None,
     },
 ];
 
@@ -196,6 +308,14 @@ const ENV_VAR_CHECKS: &[EnvVarCheck] = &[
 fn check_env_var(config: &EnvVarCheck) -> CheckResult {
     match env::var(config.name) {
         Ok(value) if !value.is_empty() => {
+            if let Some(Err((message, suggestion))) = config.validate.map(|check| check(&value)) {
+                return CheckResult::new(
+                    format!("env_var_{}", config.name.to_lowercase()),
+                    CheckStatus::Error,
+                    message,
+                )
+                .with_suggestion(suggestion);
+            }
             let message = if config.show_value {
                 format!("{}={}", config.name, value)
             } else {
@@ -228,11 +348,60 @@ fn check_env_var(config: &EnvVarCheck) -> CheckResult {
     }
 }
 
+/// Reject endpoint locations the client cannot build a URL for, pointing region-like values at
+/// the matching multi-region.
+fn validate_endpoint_location(value: &str) -> std::result::Result<(), (String, String)> {
+    if normalize_endpoint_location(value.to_string()).is_ok() {
+        return Ok(());
+    }
+    let message = format!(
+        "NBLM_ENDPOINT_LOCATION={} is not a supported endpoint location (expected one of: {})",
+        value,
+        ENDPOINT_LOCATIONS.join(", ")
+    );
+    let suggestion = match suggest_endpoint_location(value) {
+        Some(suggested) => format!(
+            "export NBLM_ENDPOINT_LOCATION={suggested}  # '{value}' is a Google Cloud region; endpoint locations are multi-regions"
+        ),
+        None => "export NBLM_ENDPOINT_LOCATION=us  # or 'eu' or 'global'".to_string(),
+    };
+    Err((message, suggestion))
+}
+
 /// Run all environment variable checks
 pub fn check_environment_variables() -> Vec<CheckResult> {
     ENV_VAR_CHECKS.iter().map(check_env_var).collect()
 }
 
+/// Report the OAuth endpoint overrides that are set, so a leftover test endpoint does not go
+/// unnoticed. Nothing is reported for endpoints left at Google's defaults.
+pub fn check_oauth_endpoints() -> Vec<CheckResult> {
+    [
+        (AUTH_ENDPOINT_ENV, "authorization"),
+        (TOKEN_ENDPOINT_ENV, "token"),
+    ]
+    .into_iter()
+    .filter_map(|(var, endpoint)| {
+        let value = env::var(var)
+            .ok()
+            .filter(|value| !value.trim().is_empty())?;
+        let name = format!("env_var_{}", var.to_lowercase());
+        Some(match validate_endpoint(var, value.trim()) {
+            Ok(()) => CheckResult::new(
+                name,
+                CheckStatus::Pass,
+                format!(
+                    "{var}={} overrides the OAuth {endpoint} endpoint",
+                    value.trim()
+                ),
+            ),
+            Err(err) => CheckResult::new(name, CheckStatus::Error, err.to_string())
+                .with_suggestion(format!("unset {var} to use Google's endpoint")),
+        })
+    })
+    .collect()
+}
+
 /// Configuration for a command availability check
 pub struct CommandCheck {
     pub name: &'static str,
@@ -288,10 +457,15 @@ pub fn check_commands() -> Vec<CheckResult> {
 
 /// Validate that `NBLM_ACCESS_TOKEN`, when present, grants Google Drive access.
 pub async fn check_drive_access_token() -> Vec<CheckResult> {
+    check_drive_access_token_with(&ScopeChecker::from_env()).await
+}
+
+/// [`check_drive_access_token`] against the tokeninfo endpoint of `checker`.
+pub async fn check_drive_access_token_with(checker: &ScopeChecker) -> Vec<CheckResult> {
     match env::var("NBLM_ACCESS_TOKEN") {
         Ok(value) if !value.trim().is_empty() => {
             let provider = EnvTokenProvider::new("NBLM_ACCESS_TOKEN");
-            match ensure_drive_scope(&provider).await {
+            match checker.ensure_drive_scope(&provider).await {
                 Ok(_) => vec![CheckResult::new(
                     "drive_scope_nblm_access_token",
                     CheckStatus::Pass,
@@ -332,6 +506,57 @@ pub async fn check_drive_access_token() -> Vec<CheckResult> {
     }
 }
 
+/// Warn when the access token was issued for a different project than `NBLM_PROJECT_NUMBER`.
+pub async fn check_token_project() -> Vec<CheckResult> {
+    check_token_project_with(&ScopeChecker::from_env()).await
+}
+
+/// [`check_token_project`] against the tokeninfo endpoint of `checker`.
+pub async fn check_token_project_with(checker: &ScopeChecker) -> Vec<CheckResult> {
+    let project_number = match env::var("NBLM_PROJECT_NUMBER") {
+        Ok(val) if !val.trim().is_empty() => val.trim().to_string(),
+        _ => return Vec::new(),
+    };
+    let provider: Box<dyn crate::auth::TokenProvider> = match env::var("NBLM_ACCESS_TOKEN")
+        .ok()
+        .filter(|s| !s.is_empty())
+    {
+        Some(_) => Box::new(EnvTokenProvider::new("NBLM_ACCESS_TOKEN")),
+        None if is_gcloud_available() => Box::new(crate::auth::GcloudTokenProvider::new("gcloud")),
+        None => return Vec::new(),
+    };
+
+    let result = match checker
+        .check_token_project(provider.as_ref(), &project_number, None)
+        .await
+    {
+        Ok(TokenProject::Matches(found)) => CheckResult::new(
+            "token_project",
+            CheckStatus::Pass,
+            format!("Access token was issued for {found}"),
+        ),
+        Ok(TokenProject::Mismatch { found, expected }) => CheckResult::new(
+            "token_project",
+            CheckStatus::Warning,
+            format!("Access token was issued for {found}, but NBLM_PROJECT_NUMBER is {expected}"),
+        )
+        .with_suggestion(format!(
+            "Run `gcloud config set project {project_number}` and `gcloud auth application-default set-quota-project {project_number}`, then refresh the token"
+        )),
+        Ok(TokenProject::Unknown(reason)) => CheckResult::new(
+            "token_project",
+            CheckStatus::Pass,
+            format!("Token project not checked: {reason}"),
+        ),
+        Err(err) => CheckResult::new(
+            "token_project",
+            CheckStatus::Warning,
+            format!("Could not determine the access token's project: {err}"),
+        ),
+    };
+    vec![result]
+}
+
 /// Check NotebookLM API connectivity by calling list_recently_viewed
 pub async fn check_api_connectivity() -> Vec<CheckResult> {
     use crate::auth::GcloudTokenProvider;
@@ -394,20 +619,74 @@ pub async fn check_api_connectivity() -> Vec<CheckResult> {
         }
     };
 
-    // Try to call list_recently_viewed
-    match client.list_recently_viewed(Some(1)).await {
-        Ok(_) => vec![CheckResult::new(
-            "api_connectivity",
-            CheckStatus::Pass,
-            "Successfully connected to NotebookLM API",
-        )],
-        Err(err) => {
-            let err_string = err.to_string();
-            let (status, message, suggestion) = categorize_api_error(&err_string);
+    check_api_connectivity_with(client).await
+}
 
-            vec![CheckResult::new("api_connectivity", status, message).with_suggestion(suggestion)]
+/// [`check_api_connectivity`] with a given client: list up to 50 recently viewed notebooks,
+/// report how many there are and the latest one's title, and warn when there are none. Any
+/// `Deprecation`, `Sunset` or `Warning` headers on the response are warnings of their own.
+pub async fn check_api_connectivity_with(client: crate::client::NblmClient) -> Vec<CheckResult> {
+    use std::sync::{Arc, Mutex};
+
+    let notices = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&notices);
+    let client = client.with_deprecation_observer(move |notice| {
+        let mut seen = seen.lock().unwrap();
+        if !seen.contains(notice) {
+            seen.push(notice.clone());
         }
+    });
+
+    let listed = client
+        .list_recently_viewed_with_fields(Some(PROBE_PAGE_SIZE), Some(PROBE_FIELDS))
+        .await;
+    let mut results = match listed {
+        Ok(response) => {
+            let parent = client.environment().parent_path();
+            vec![NotebookSample::new(parent, &response.notebooks).into_check()]
+        }
+        Err(err) => match service_disabled_finding(&err) {
+            Some(finding) => vec![finding],
+            None => {
+                let err_string = err.to_string();
+                let (status, message, suggestion) = categorize_api_error(&err_string);
+                vec![CheckResult::new("api_connectivity", status, message)
+                    .with_suggestion(suggestion)]
+            }
+        },
+    };
+    results.extend(notices.lock().unwrap().iter().map(|notice| {
+        CheckResult::new("api_deprecation", CheckStatus::Warning, notice.describe())
+            .with_suggestion("Check the nblm release notes and upgrade before the sunset date")
+    }));
+    results
+}
+
+/// A finding of its own for a project without the Discovery Engine API, whose 403 would
+/// otherwise read as a missing IAM role.
+fn service_disabled_finding(err: &crate::Error) -> Option<CheckResult> {
+    let crate::Error::ServiceDisabled {
+        service_title,
+        project,
+        activation_url,
+        ..
+    } = err
+    else {
+        return None;
+    };
+    let project = project.as_deref().unwrap_or("this project");
+    let mut suggestion = format!("Run `{}`", err.enable_service_command()?);
+    if let Some(url) = activation_url {
+        suggestion.push_str(&format!(" or enable it at {url}"));
     }
+    Some(
+        CheckResult::new(
+            "api_enabled",
+            CheckStatus::Error,
+            format!("{service_title} is not enabled for project {project}"),
+        )
+        .with_suggestion(suggestion),
+    )
 }
 
 /// Check if gcloud command is available
@@ -576,6 +855,7 @@ mod tests {
             required: true,
             suggestion: "export TEST_VAR=value",
             show_value: true,
+            validate: None,
         };
         let result = check_env_var(&config);
         assert_eq!(result.status, CheckStatus::Pass);
@@ -591,6 +871,7 @@ mod tests {
             required: true,
             suggestion: "export MISSING_VAR=value",
             show_value: true,
+            validate: None,
         };
         let result = check_env_var(&config);
         assert_eq!(result.status, CheckStatus::Error);
@@ -606,6 +887,7 @@ mod tests {
             required: false,
             suggestion: "export OPTIONAL_VAR=value",
             show_value: true,
+            validate: None,
         };
         let result = check_env_var(&config);
         assert_eq!(result.status, CheckStatus::Warning);
@@ -643,13 +925,10 @@ mod tests {
     #[serial]
     async fn test_drive_access_check_passes_with_valid_scope() {
         let token_guard = EnvGuard::new("NBLM_ACCESS_TOKEN");
-        let endpoint_guard = EnvGuard::new("NBLM_TOKENINFO_ENDPOINT");
-
         env::set_var("NBLM_ACCESS_TOKEN", "test-token");
 
         let server = MockServer::start().await;
-        let tokeninfo_url = format!("{}/tokeninfo", server.uri());
-        env::set_var("NBLM_TOKENINFO_ENDPOINT", &tokeninfo_url);
+        let checker = ScopeChecker::new().with_endpoint(format!("{}/tokeninfo", server.uri()));
 
         Mock::given(method("GET"))
             .and(path("/tokeninfo"))
@@ -661,26 +940,22 @@ mod tests {
             .mount(&server)
             .await;
 
-        let results = check_drive_access_token().await;
+        let results = check_drive_access_token_with(&checker).await;
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].status, CheckStatus::Pass);
         assert!(results[0].message.contains("grants Google Drive access"));
 
         drop(token_guard);
-        drop(endpoint_guard);
     }
 
     #[tokio::test]
     #[serial]
     async fn test_drive_access_check_reports_missing_scope() {
         let token_guard = EnvGuard::new("NBLM_ACCESS_TOKEN");
-        let endpoint_guard = EnvGuard::new("NBLM_TOKENINFO_ENDPOINT");
-
         env::set_var("NBLM_ACCESS_TOKEN", "test-token");
 
         let server = MockServer::start().await;
-        let tokeninfo_url = format!("{}/tokeninfo", server.uri());
-        env::set_var("NBLM_TOKENINFO_ENDPOINT", &tokeninfo_url);
+        let checker = ScopeChecker::new().with_endpoint(format!("{}/tokeninfo", server.uri()));
 
         Mock::given(method("GET"))
             .and(path("/tokeninfo"))
@@ -692,13 +967,98 @@ mod tests {
             .mount(&server)
             .await;
 
-        let results = check_drive_access_token().await;
+        let results = check_drive_access_token_with(&checker).await;
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].status, CheckStatus::Warning);
         assert!(results[0].message.contains("lacks Google Drive scope"));
 
         drop(token_guard);
-        drop(endpoint_guard);
+    }
+
+    async fn token_project_results(tokeninfo: serde_json::Value) -> Vec<CheckResult> {
+        let token_guard = EnvGuard::new("NBLM_ACCESS_TOKEN");
+        let project_guard = EnvGuard::new("NBLM_PROJECT_NUMBER");
+        env::set_var("NBLM_ACCESS_TOKEN", "test-token");
+        env::set_var("NBLM_PROJECT_NUMBER", "123456");
+
+        let server = MockServer::start().await;
+        let checker = ScopeChecker::new().with_endpoint(format!("{}/tokeninfo", server.uri()));
+        Mock::given(method("GET"))
+            .and(path("/tokeninfo"))
+            .and(query_param("access_token", "test-token"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(tokeninfo))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let results = check_token_project_with(&checker).await;
+        drop(project_guard);
+        drop(token_guard);
+        results
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_token_project_check_warns_on_mismatch() {
+        let results = token_project_results(serde_json::json!({
+            "azp": "987654-abc.apps.googleusercontent.com"
+        }))
+        .await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].status, CheckStatus::Warning);
+        assert!(results[0].message.contains("project 987654"));
+        assert!(results[0]
+            .suggestion
+            .as_deref()
+            .unwrap()
+            .contains("gcloud config set project 123456"));
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_token_project_check_notes_unknown_project() {
+        let results = token_project_results(serde_json::json!({
+            "azp": "32555940559.apps.googleusercontent.com"
+        }))
+        .await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].status, CheckStatus::Pass);
+        assert!(results[0].message.starts_with("Token project not checked"));
+    }
+
+    #[test]
+    fn test_service_disabled_is_its_own_finding() {
+        let body = serde_json::json!({
+            "error": {
+                "code": 403,
+                "details": [{
+                    "reason": "SERVICE_DISABLED",
+                    "metadata": {
+                        "activationUrl": "https://console.example/enable",
+                        "consumer": "projects/123456",
+                        "serviceTitle": "Discovery Engine API",
+                        "service": "discoveryengine.googleapis.com",
+                    },
+                }],
+            },
+        });
+        let err = crate::Error::http(reqwest::StatusCode::FORBIDDEN, body.to_string());
+
+        let finding = service_disabled_finding(&err).unwrap();
+        assert_eq!(finding.name, "api_enabled");
+        assert_eq!(finding.status, CheckStatus::Error);
+        assert_eq!(
+            finding.message,
+            "Discovery Engine API is not enabled for project 123456"
+        );
+        assert_eq!(
+            finding.suggestion.as_deref(),
+            Some(
+                "Run `gcloud services enable discoveryengine.googleapis.com --project 123456` \
+                 or enable it at https://console.example/enable"
+            )
+        );
+        assert!(service_disabled_finding(&crate::Error::validation("other")).is_none());
     }
 
     #[test]
@@ -733,6 +1093,168 @@ mod tests {
         assert!(suggestion.contains("internet connection"));
     }
 
+    #[tokio::test]
+    async fn api_connectivity_reports_deprecation_headers_once() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(
+                "/v1alpha/projects/123/locations/global/notebooks:listRecentlyViewed",
+            ))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Deprecation", "@1688169599")
+                    .insert_header("Sunset", "Tue, 30 Jun 2026 23:59:59 GMT")
+                    .set_body_json(serde_json::json!({"notebooks": [{"title": "Research"}]})),
+            )
+            .mount(&server)
+            .await;
+
+        let results = check_api_connectivity_with(probe_client(&server)).await;
+
+        let statuses: Vec<_> = results
+            .iter()
+            .map(|result| (result.name.as_str(), result.status.clone()))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                ("api_connectivity", CheckStatus::Pass),
+                ("api_deprecation", CheckStatus::Warning),
+            ]
+        );
+        assert!(
+            results[1]
+                .message
+                .contains("notebooks:listRecentlyViewed is deprecated, sunset on 2026-06-30"),
+            "{}",
+            results[1].message
+        );
+    }
+
+    fn probe_client(server: &MockServer) -> crate::client::NblmClient {
+        crate::client::NblmClient::new(
+            std::sync::Arc::new(crate::auth::StaticTokenProvider::new("token")),
+            crate::env::EnvironmentConfig::enterprise("123", "global", "global").unwrap(),
+        )
+        .unwrap()
+        .with_base_url(format!("{}/v1alpha", server.uri()))
+        .unwrap()
+    }
+
+    async fn mock_recently_viewed(notebooks: serde_json::Value) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(
+                "/v1alpha/projects/123/locations/global/notebooks:listRecentlyViewed",
+            ))
+            .and(query_param("pageSize", "50"))
+            .and(query_param("fields", "notebooks(notebookId,title)"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "notebooks": notebooks })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[tokio::test]
+    async fn api_connectivity_reports_the_notebooks_it_listed() {
+        let server = mock_recently_viewed(serde_json::json!([
+            { "notebookId": "nb-2", "title": "Quarterly planning notes for the platform team offsite" },
+            { "notebookId": "nb-1", "title": "Research" },
+        ]))
+        .await;
+
+        let results = check_api_connectivity_with(probe_client(&server)).await;
+
+        assert_eq!(results.len(), 1);
+        let result = &results[0];
+        assert_eq!(result.status, CheckStatus::Pass);
+        assert_eq!(
+            result.message,
+            "Successfully connected to NotebookLM API: 2 recently viewed notebook(s) in \
+             projects/123/locations/global, most recent \"Quarterly planning notes for the platfo…\""
+        );
+        assert_eq!(result.suggestion, None);
+        let sample = result.notebooks.as_ref().unwrap();
+        assert_eq!(
+            serde_json::to_value(sample).unwrap(),
+            serde_json::json!({
+                "parent": "projects/123/locations/global",
+                "page_size": 50,
+                "listed": 2,
+                "parent_matched": true,
+                "latest_title": "Quarterly planning notes for the platfo…",
+            })
+        );
+        let json = serde_json::to_value(result).unwrap();
+        assert_eq!(json["name"], "api_connectivity");
+        assert_eq!(json["status"], "pass");
+        assert_eq!(json["suggestion"], serde_json::Value::Null);
+        assert_eq!(json["notebooks"]["listed"], 2);
+        assert_eq!(json["notebooks"]["parent_matched"], true);
+
+        let hidden = result.clone().without_titles();
+        assert_eq!(
+            hidden.message,
+            "Successfully connected to NotebookLM API: 2 recently viewed notebook(s) in \
+             projects/123/locations/global"
+        );
+        assert_eq!(hidden.notebooks.unwrap().latest_title, None);
+    }
+
+    #[tokio::test]
+    async fn api_connectivity_warns_when_the_parent_has_no_notebooks() {
+        let server = mock_recently_viewed(serde_json::json!([])).await;
+
+        let results = check_api_connectivity_with(probe_client(&server)).await;
+
+        assert_eq!(results.len(), 1);
+        let result = &results[0];
+        assert_eq!(result.status, CheckStatus::Warning);
+        assert_eq!(
+            result.message,
+            "Connected to NotebookLM API, but projects/123/locations/global has no recently \
+             viewed notebooks"
+        );
+        assert!(result
+            .suggestion
+            .as_deref()
+            .unwrap()
+            .contains("wrong NBLM_PROJECT_NUMBER or NBLM_LOCATION"));
+        let sample = result.notebooks.as_ref().unwrap();
+        assert_eq!(
+            serde_json::to_value(sample).unwrap(),
+            serde_json::json!({
+                "parent": "projects/123/locations/global",
+                "page_size": 50,
+                "listed": 0,
+                "parent_matched": false,
+                "latest_title": null,
+            })
+        );
+        let json = serde_json::to_value(result).unwrap();
+        assert_eq!(json["status"], "warning");
+        assert_eq!(json["notebooks"]["listed"], 0);
+        assert_eq!(json["notebooks"]["parent_matched"], false);
+    }
+
+    #[test]
+    fn a_full_page_is_reported_as_a_lower_bound() {
+        let notebooks: Vec<_> = (0..50)
+            .map(|n| crate::models::enterprise::notebook::Notebook {
+                title: format!("Notebook {n}"),
+                ..Default::default()
+            })
+            .collect();
+        let sample = NotebookSample::new("projects/123/locations/global", &notebooks);
+        assert!(sample
+            .describe()
+            .contains(": 50 or more recently viewed notebook(s) in"));
+    }
+
     #[test]
     fn test_categorize_api_error_generic() {
         let (status, message, suggestion) = categorize_api_error("Some random error");
@@ -806,6 +1328,7 @@ mod tests {
             required: true,
             suggestion: "export SECRET_VAR=value",
             show_value: false,
+            validate: None,
         };
         let result = check_env_var(&config);
         assert_eq!(result.status, CheckStatus::Pass);
@@ -814,6 +1337,52 @@ mod tests {
         env::remove_var("SECRET_VAR");
     }
 
+    fn endpoint_location_check() -> &'static EnvVarCheck {
+        ENV_VAR_CHECKS
+            .iter()
+            .find(|check| check.name == "NBLM_ENDPOINT_LOCATION")
+            .unwrap()
+    }
+
+    #[test]
+    #[serial]
+    fn test_check_endpoint_location_flags_region_with_correction() {
+        let _guard = EnvGuard::new("NBLM_ENDPOINT_LOCATION");
+        env::set_var("NBLM_ENDPOINT_LOCATION", "us-central1");
+        let result = check_env_var(endpoint_location_check());
+        assert_eq!(result.status, CheckStatus::Error);
+        assert!(
+            result
+                .message
+                .contains("us-central1 is not a supported endpoint location"),
+            "{}",
+            result.message
+        );
+        assert!(
+            result.message.contains("global, us, eu"),
+            "{}",
+            result.message
+        );
+        let suggestion = result.suggestion.unwrap();
+        assert!(
+            suggestion.starts_with("export NBLM_ENDPOINT_LOCATION=us "),
+            "{suggestion}"
+        );
+        assert!(suggestion.contains("Google Cloud region"), "{suggestion}");
+    }
+
+    #[test]
+    #[serial]
+    fn test_check_endpoint_location_accepts_supported_values() {
+        let _guard = EnvGuard::new("NBLM_ENDPOINT_LOCATION");
+        for value in ["US", "eu"] {
+            env::set_var("NBLM_ENDPOINT_LOCATION", value);
+            let result = check_env_var(endpoint_location_check());
+            assert_eq!(result.status, CheckStatus::Pass, "{value}");
+            assert_eq!(result.message, format!("NBLM_ENDPOINT_LOCATION={value}"));
+        }
+    }
+
     #[test]
     fn test_check_environment_variables_integration() {
         let results = check_environment_variables();
@@ -821,6 +1390,35 @@ mod tests {
         assert_eq!(results.len(), ENV_VAR_CHECKS.len());
     }
 
+    #[test]
+    #[serial]
+    fn oauth_endpoint_overrides_are_reported_when_set() {
+        let _auth = EnvGuard::new(AUTH_ENDPOINT_ENV);
+        let _token = EnvGuard::new(TOKEN_ENDPOINT_ENV);
+        env::remove_var(AUTH_ENDPOINT_ENV);
+        env::remove_var(TOKEN_ENDPOINT_ENV);
+        assert!(check_oauth_endpoints().is_empty());
+
+        env::set_var(TOKEN_ENDPOINT_ENV, "https://oauth.example.gov/token");
+        let results = check_oauth_endpoints();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].status, CheckStatus::Pass);
+        assert_eq!(
+            results[0].message,
+            "NBLM_OAUTH_TOKEN_ENDPOINT=https://oauth.example.gov/token overrides the OAuth token endpoint"
+        );
+
+        env::set_var(AUTH_ENDPOINT_ENV, "http://oauth.example.gov/auth");
+        let results = check_oauth_endpoints();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].status, CheckStatus::Error);
+        assert!(
+            results[0].message.contains("must use https"),
+            "{}",
+            results[0].message
+        );
+    }
+
     #[test]
     fn test_check_commands_integration() {
         let results = check_commands();
@@ -1,6 +1,8 @@
 pub mod checks;
 
 pub use checks::{
-    check_api_connectivity, check_commands, check_drive_access_token, check_environment_variables,
-    CheckResult, CheckStatus, DiagnosticsSummary,
+    check_api_connectivity, check_api_connectivity_with, check_commands, check_drive_access_token,
+    check_drive_access_token_with, check_environment_variables, check_oauth_endpoints,
+    check_token_project, check_token_project_with, CheckResult, CheckStatus, DiagnosticsSummary,
+    NotebookSample,
 };
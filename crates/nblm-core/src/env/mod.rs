@@ -1,3 +1,5 @@
+use url::Url;
+
 use crate::error::{Error, Result};
 
 const PROFILE_NAME_ENTERPRISE: &str = "enterprise";
@@ -35,6 +37,48 @@ impl ApiProfile {
     }
 }
 
+/// Discovery Engine API versions the client can address. The version is the last segment of the
+/// base URL and of the upload URL prefix.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ApiVersion {
+    #[default]
+    V1Alpha,
+    V1Beta,
+}
+
+impl ApiVersion {
+    /// Every version, in the order they are listed in errors and help output.
+    pub const ALL: &'static [ApiVersion] = &[ApiVersion::V1Alpha, ApiVersion::V1Beta];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiVersion::V1Alpha => "v1alpha",
+            ApiVersion::V1Beta => "v1beta",
+        }
+    }
+
+    pub fn parse(input: &str) -> Result<Self> {
+        let lowered = input.trim().to_ascii_lowercase();
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|version| version.as_str() == lowered)
+            .ok_or_else(|| {
+                let known: Vec<_> = Self::ALL.iter().map(ApiVersion::as_str).collect();
+                Error::Endpoint(format!(
+                    "unsupported API version: {lowered} (expected one of: {})",
+                    known.join(", ")
+                ))
+            })
+    }
+}
+
+impl std::fmt::Display for ApiVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 pub const PROFILE_EXPERIMENT_FLAG: &str = "NBLM_PROFILE_EXPERIMENT";
 
 /// Returns `true` when experimental profile support is enabled via
@@ -104,6 +148,7 @@ impl ProfileParams {
 #[derive(Debug, Clone)]
 pub struct EnvironmentConfig {
     profile: ApiProfile,
+    api_version: ApiVersion,
     base_url: String,
     parent_path: String,
 }
@@ -125,6 +170,10 @@ impl EnvironmentConfig {
         self.profile
     }
 
+    pub fn api_version(&self) -> ApiVersion {
+        self.api_version
+    }
+
     pub fn base_url(&self) -> &str {
         &self.base_url
     }
@@ -139,6 +188,17 @@ impl EnvironmentConfig {
         self
     }
 
+    /// Return a copy that addresses `version`. A base URL ending in the current version segment
+    /// has it replaced; an overridden base URL without one is kept as it is.
+    pub fn with_api_version(mut self, version: ApiVersion) -> Self {
+        let current = format!("/{}", self.api_version.as_str());
+        if let Some(prefix) = self.base_url.strip_suffix(&current) {
+            self.base_url = format!("{prefix}/{}", version.as_str());
+        }
+        self.api_version = version;
+        self
+    }
+
     pub fn from_profile(profile: ApiProfile, params: ProfileParams) -> Result<Self> {
         let params_profile = params.expected_profile();
         if profile != params_profile {
@@ -153,11 +213,16 @@ impl EnvironmentConfig {
                     endpoint_location,
                 } => {
                     let endpoint = normalize_endpoint_location(endpoint_location)?;
-                    let base_url =
-                        format!("https://{}discoveryengine.googleapis.com/v1alpha", endpoint);
+                    let api_version = ApiVersion::default();
+                    let base_url = format!(
+                        "https://{}discoveryengine.googleapis.com/{}",
+                        endpoint,
+                        api_version.as_str()
+                    );
                     let parent_path = format!("projects/{}/locations/{}", project_number, location);
                     Ok(Self {
                         profile: ApiProfile::Enterprise,
+                        api_version,
                         base_url,
                         parent_path,
                     })
@@ -169,6 +234,10 @@ impl EnvironmentConfig {
     }
 }
 
+/// Endpoint locations accepted by [`normalize_endpoint_location`], in the order they are listed
+/// in errors and help output.
+pub const ENDPOINT_LOCATIONS: &[&str] = &["global", "us", "eu"];
+
 /// Normalize endpoint location strings to the canonical discovery engine prefix.
 pub fn normalize_endpoint_location(input: String) -> Result<String> {
     let trimmed = input.trim().trim_end_matches('-').to_lowercase();
@@ -177,14 +246,73 @@ pub fn normalize_endpoint_location(input: String) -> Result<String> {
         "eu" => "eu-",
         "global" => "global-",
         other => {
-            return Err(Error::Endpoint(format!(
-                "unsupported endpoint location: {other}"
-            )))
+            let mut message = format!(
+                "unsupported endpoint location: {other} (expected one of: {})",
+                ENDPOINT_LOCATIONS.join(", ")
+            );
+            if let Some(suggested) = suggest_endpoint_location(other) {
+                message.push_str(&format!(
+                    "; '{other}' looks like a Google Cloud region, use '{suggested}' instead"
+                ));
+            }
+            return Err(Error::Endpoint(message));
         }
     };
     Ok(normalized.to_string())
 }
 
+/// The endpoint location to use instead of a region-like value such as `us-central1` or
+/// `europe-west4`.
+pub fn suggest_endpoint_location(input: &str) -> Option<&'static str> {
+    let lowered = input.trim().to_lowercase();
+    let (multi_region, rest) = lowered.split_once('-')?;
+    if rest.is_empty() {
+        return None;
+    }
+    match multi_region {
+        "us" => Some("us"),
+        "eu" | "europe" => Some("eu"),
+        _ => None,
+    }
+}
+
+/// Check an API base URL override and return it without surrounding whitespace or trailing
+/// slashes.
+pub fn validate_base_url(input: &str) -> Result<String> {
+    let base = input.trim().trim_end_matches('/');
+    let invalid = |reason: &str| Error::InvalidBaseUrl {
+        value: input.trim().to_string(),
+        reason: reason.to_string(),
+    };
+    let url = match Url::parse(base) {
+        Ok(url) => url,
+        Err(url::ParseError::RelativeUrlWithoutBase) => return Err(invalid("missing scheme")),
+        Err(err) => return Err(invalid(&err.to_string())),
+    };
+    // `localhost:8080/v1alpha` parses with `localhost` as the scheme.
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(invalid("missing http:// or https:// scheme"));
+    }
+    if url.host_str().is_none_or(str::is_empty) {
+        return Err(invalid("missing host"));
+    }
+    Ok(base.to_string())
+}
+
+/// Whether the last path segment of `base` is an API version such as `v1alpha` or `v1`.
+///
+/// Request paths are appended to the base URL as-is, so a base without one is almost always a
+/// mistake.
+pub fn base_url_has_version(base: &str) -> bool {
+    let Ok(url) = Url::parse(base.trim().trim_end_matches('/')) else {
+        return false;
+    };
+    url.path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .and_then(|segment| segment.strip_prefix('v'))
+        .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+}
+
 fn unsupported_profile_error(profile: ApiProfile) -> Error {
     Error::Endpoint(format!(
         "API profile '{}' is not available yet",
@@ -261,6 +389,79 @@ mod tests {
         assert!(format!("{err}").contains("unsupported endpoint location"));
     }
 
+    #[test]
+    fn normalize_endpoint_location_error_lists_supported_values() {
+        let message = normalize_endpoint_location("asia".into())
+            .unwrap_err()
+            .to_string();
+        assert!(
+            message.contains("asia (expected one of: global, us, eu)"),
+            "{message}"
+        );
+        assert!(!message.contains("region"), "{message}");
+    }
+
+    #[test]
+    fn normalize_endpoint_location_error_corrects_regions() {
+        let message = normalize_endpoint_location("us-central1".into())
+            .unwrap_err()
+            .to_string();
+        assert!(
+            message.contains("expected one of: global, us, eu"),
+            "{message}"
+        );
+        assert!(
+            message.contains("'us-central1' looks like a Google Cloud region, use 'us' instead"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn suggest_endpoint_location_maps_regions_to_multi_regions() {
+        assert_eq!(suggest_endpoint_location("us-central1"), Some("us"));
+        assert_eq!(suggest_endpoint_location("US-East4"), Some("us"));
+        assert_eq!(suggest_endpoint_location("europe-west4"), Some("eu"));
+        assert_eq!(suggest_endpoint_location("asia-northeast1"), None);
+        assert_eq!(suggest_endpoint_location("us"), None);
+    }
+
+    #[test]
+    fn validate_base_url_rejects_missing_scheme() {
+        for input in ["localhost:8080/v1alpha", "/v1alpha", "example.com/v1alpha"] {
+            match validate_base_url(input).unwrap_err() {
+                Error::InvalidBaseUrl { value, reason } => {
+                    assert_eq!(value, input);
+                    assert!(reason.contains("scheme"), "{input}: {reason}");
+                }
+                other => panic!("unexpected error for {input}: {other:?}"),
+            }
+        }
+        let message = validate_base_url("localhost:8080/v1alpha")
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("http://localhost:8080/v1alpha"));
+    }
+
+    #[test]
+    fn validate_base_url_trims_trailing_slash() {
+        assert_eq!(
+            validate_base_url(" http://127.0.0.1:8080/v1alpha/ ").unwrap(),
+            "http://127.0.0.1:8080/v1alpha"
+        );
+    }
+
+    #[test]
+    fn base_url_has_version_checks_last_segment() {
+        assert!(base_url_has_version("http://localhost:8080/v1alpha"));
+        assert!(base_url_has_version("http://localhost:8080/v1alpha/"));
+        assert!(base_url_has_version("https://example.com/api/v1"));
+        assert!(!base_url_has_version("http://localhost:8080"));
+        assert!(!base_url_has_version("http://localhost:8080/api"));
+        assert!(!base_url_has_version(
+            "http://localhost:8080/v1alpha/projects"
+        ));
+    }
+
     #[test]
     fn with_base_url_overrides_base_url() {
         let env = EnvironmentConfig::enterprise("123", "global", "us")
@@ -270,6 +471,35 @@ mod tests {
         assert_eq!(env.parent_path(), "projects/123/locations/global");
     }
 
+    #[test]
+    fn with_api_version_replaces_the_version_segment() {
+        let env = EnvironmentConfig::enterprise("123", "global", "us").unwrap();
+        assert_eq!(env.api_version(), ApiVersion::V1Alpha);
+
+        let beta = env.clone().with_api_version(ApiVersion::V1Beta);
+        assert_eq!(beta.api_version(), ApiVersion::V1Beta);
+        assert_eq!(
+            beta.base_url(),
+            "https://us-discoveryengine.googleapis.com/v1beta"
+        );
+        let back = beta.with_api_version(ApiVersion::V1Alpha);
+        assert_eq!(back.base_url(), env.base_url());
+
+        let custom = env
+            .with_base_url("http://localhost:8080/api")
+            .with_api_version(ApiVersion::V1Beta);
+        assert_eq!(custom.base_url(), "http://localhost:8080/api");
+    }
+
+    #[test]
+    fn api_version_parse_is_case_insensitive_and_lists_known_versions() {
+        assert_eq!(ApiVersion::parse("v1alpha").unwrap(), ApiVersion::V1Alpha);
+        assert_eq!(ApiVersion::parse(" V1Beta ").unwrap(), ApiVersion::V1Beta);
+        let message = ApiVersion::parse("v2").unwrap_err().to_string();
+        assert!(message.contains("unsupported API version: v2"), "{message}");
+        assert!(message.contains("v1alpha, v1beta"), "{message}");
+    }
+
     #[test]
     fn api_profile_parse_accepts_all_known_variants() {
         let enterprise = ApiProfile::parse("enterprise").unwrap();
@@ -9,18 +9,128 @@ pub enum Error {
     Endpoint(String),
     #[error("request error: {0}")]
     Request(#[from] reqwest::Error),
+    /// No response arrived within the client's timeout
+    /// ([`NblmClient::with_timeout`](crate::NblmClient::with_timeout)). `path` has its resource
+    /// IDs replaced by placeholders such as `{notebook}`.
+    #[error(
+        "{method} {path} timed out after {} (timeout: {})",
+        humantime::format_duration(*elapsed),
+        humantime::format_duration(*timeout)
+    )]
+    Timeout {
+        method: String,
+        path: String,
+        timeout: std::time::Duration,
+        elapsed: std::time::Duration,
+    },
+    /// The connection to `host` could not be made (refused, unreachable, DNS or TLS failure).
+    #[error("could not connect to {host}: {source}")]
+    Connect {
+        host: String,
+        source: reqwest::Error,
+    },
     #[error("http error {status}: {message}")]
     Http {
         status: StatusCode,
         message: String,
         body: String,
     },
+    /// The API is not enabled on the project the request was billed to: a 403 whose details
+    /// carry reason `SERVICE_DISABLED` or an `activationUrl`. `project` is the consumer project
+    /// number from the response, when it names one.
+    #[error("{}", service_disabled_message(.service_title, .service, .project.as_deref(), .activation_url.as_deref()))]
+    ServiceDisabled {
+        service: String,
+        service_title: String,
+        project: Option<String>,
+        activation_url: Option<String>,
+        body: String,
+    },
+    /// A call on a notebook failed with 403 or 404 and
+    /// [`NblmClient::explain_notebook_error`](crate::NblmClient::explain_notebook_error) found
+    /// no notebook with that ID. The API answers 403 for notebooks that do not exist as well as
+    /// for ones the caller may not use. `suggestion` is the closest ID among the recently viewed
+    /// notebooks, when one is close enough to be a typo.
+    #[error(
+        "notebook {notebook_id} not found in project {project}{}; the API answered {status}: {message}",
+        did_you_mean(.suggestion.as_deref())
+    )]
+    NotebookNotFound {
+        notebook_id: String,
+        project: String,
+        suggestion: Option<String>,
+        status: StatusCode,
+        message: String,
+    },
+    /// A call on a notebook failed with 403 although the notebook exists and is visible to the
+    /// caller, so the operation itself is not permitted.
+    #[error("notebook {notebook_id} exists, but the API refused this operation on it ({status}): {message}")]
+    NotebookForbidden {
+        notebook_id: String,
+        status: StatusCode,
+        message: String,
+        body: String,
+    },
     #[error("json deserialize error: {0}")]
     Json(#[from] serde_json::Error),
     #[error("url parse error: {0}")]
     Url(#[from] url::ParseError),
     #[error("validation error: {0}")]
     Validation(String),
+    #[error("{feature} is not available for this project or API profile ({detail})")]
+    Unavailable {
+        feature: &'static str,
+        detail: String,
+    },
+    #[error(
+        "invalid base URL '{value}': {reason} (expected an absolute URL such as http://localhost:8080/v1alpha)"
+    )]
+    InvalidBaseUrl { value: String, reason: String },
+    #[cfg(feature = "cassette")]
+    #[error("cassette error: {0}")]
+    Cassette(String),
+    /// Returned without sending the request while a host is marked unreachable (see
+    /// [`HostFailureConfig`](crate::client::HostFailureConfig)).
+    #[error(
+        "{host} refused {failures} connections in a row; not retrying it for another {}",
+        humantime::format_duration(*retry_in)
+    )]
+    HostUnavailable {
+        host: String,
+        failures: u32,
+        retry_in: std::time::Duration,
+    },
+    /// The response body went past the limit set by
+    /// [`NblmClient::with_max_response_size`](crate::NblmClient::with_max_response_size).
+    #[error(
+        "response from {url} is larger than the {limit}-byte limit; check the base URL, or raise the limit if the response is expected"
+    )]
+    ResponseTooLarge { url: String, limit: usize },
+    /// A [`poll_until`](crate::client::poll_until) ran out of time with the operation still in
+    /// `state`.
+    #[error(
+        "{operation} still {state} after {}",
+        humantime::format_duration(*timeout)
+    )]
+    WaitTimedOut {
+        operation: String,
+        state: String,
+        timeout: std::time::Duration,
+    },
+    /// A polled operation finished in a failed `state`.
+    #[error("{operation} ended with {state}")]
+    OperationFailed { operation: String, state: String },
+    /// Going on would need someone at the terminal (a browser sign-in, a confirmation, a gcloud
+    /// reauthentication prompt), and the caller runs non-interactively. `interaction` says what
+    /// was needed and `remedy` how to provide it ahead of time.
+    #[error("cannot {interaction} when running non-interactively; {remedy}")]
+    InteractionRequired { interaction: String, remedy: String },
+    #[error("invalid value '{value}' for {name}: {reason}")]
+    InvalidEnv {
+        name: &'static str,
+        value: String,
+        reason: String,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -28,6 +138,11 @@ pub type Result<T> = std::result::Result<T, Error>;
 impl Error {
     pub fn http(status: StatusCode, body: impl Into<String>) -> Self {
         let body = body.into();
+        if status == StatusCode::FORBIDDEN {
+            if let Some(error) = service_disabled(&body) {
+                return error;
+            }
+        }
         let message = extract_error_message(&body).unwrap_or_else(|| body.clone());
         Self::Http {
             status,
@@ -39,6 +154,98 @@ impl Error {
     pub fn validation(message: impl Into<String>) -> Self {
         Self::Validation(message.into())
     }
+
+    /// Whether the client retries this failure (429, 408, 5xx, timeouts and connection errors)
+    /// unless retries are disabled with [`RetryConfig::disabled`].
+    ///
+    /// [`RetryConfig::disabled`]: crate::RetryConfig::disabled
+    pub fn is_retryable(&self) -> bool {
+        crate::client::retry::is_retryable_error(self)
+    }
+
+    /// For [`Error::ServiceDisabled`], the `gcloud` command that enables the API, with the
+    /// project number filled in when the response named it.
+    pub fn enable_service_command(&self) -> Option<String> {
+        match self {
+            Self::ServiceDisabled {
+                service, project, ..
+            } => Some(enable_command(service, project.as_deref())),
+            _ => None,
+        }
+    }
+}
+
+/// The service every NotebookLM Enterprise request goes to.
+const DISCOVERY_ENGINE_SERVICE: &str = "discoveryengine.googleapis.com";
+
+/// [`Error::ServiceDisabled`] from a 403 body whose `google.rpc.ErrorInfo` detail has reason
+/// `SERVICE_DISABLED` or an `activationUrl`, or `None` for any other body.
+fn service_disabled(body: &str) -> Option<Error> {
+    let json: serde_json::Value = serde_json::from_str(body).ok()?;
+    let details = json.get("error")?.get("details")?.as_array()?;
+    let info = details.iter().find(|detail| {
+        detail.get("reason").and_then(|reason| reason.as_str()) == Some("SERVICE_DISABLED")
+            || detail
+                .get("metadata")
+                .and_then(|metadata| metadata.get("activationUrl"))
+                .is_some()
+    })?;
+    let metadata = |key: &str| {
+        info.get("metadata")
+            .and_then(|metadata| metadata.get(key))
+            .and_then(|value| value.as_str())
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    let service = metadata("service").unwrap_or_else(|| DISCOVERY_ENGINE_SERVICE.to_string());
+    Some(Error::ServiceDisabled {
+        service_title: metadata("serviceTitle").unwrap_or_else(|| service.clone()),
+        service,
+        project: metadata("consumer")
+            .map(|consumer| {
+                consumer
+                    .strip_prefix("projects/")
+                    .unwrap_or(&consumer)
+                    .to_string()
+            })
+            .or_else(|| metadata("containerInfo")),
+        activation_url: metadata("activationUrl"),
+        body: body.to_string(),
+    })
+}
+
+fn enable_command(service: &str, project: Option<&str>) -> String {
+    format!(
+        "gcloud services enable {service} --project {}",
+        project.unwrap_or("PROJECT_NUMBER")
+    )
+}
+
+fn service_disabled_message(
+    service_title: &str,
+    service: &str,
+    project: Option<&str>,
+    activation_url: Option<&str>,
+) -> String {
+    let on_project = match project {
+        Some(project) => format!("project {project}"),
+        None => "this project".to_string(),
+    };
+    let mut message = format!(
+        "{service_title} is not enabled for {on_project}. Enable it with:\n  {}",
+        enable_command(service, project)
+    );
+    if let Some(url) = activation_url {
+        message.push_str(&format!("\nor in the Cloud console: {url}"));
+    }
+    message.push_str("\nIf you enabled it recently, wait a few minutes and retry.");
+    message
+}
+
+fn did_you_mean(suggestion: Option<&str>) -> String {
+    suggestion
+        .map(|suggestion| format!(" (did you mean {suggestion}?)"))
+        .unwrap_or_default()
 }
 
 fn extract_error_message(body: &str) -> Option<String> {
@@ -96,4 +303,34 @@ mod tests {
             _ => panic!("expected Error::Http"),
         }
     }
+
+    #[test]
+    fn service_disabled_without_metadata_falls_back_to_discovery_engine() {
+        let body = r#"{"error":{"code":403,"details":[{"reason":"SERVICE_DISABLED"}]}}"#;
+        let err = Error::http(StatusCode::FORBIDDEN, body);
+        assert_eq!(
+            err.enable_service_command().as_deref(),
+            Some("gcloud services enable discoveryengine.googleapis.com --project PROJECT_NUMBER")
+        );
+        assert_eq!(
+            err.to_string(),
+            "discoveryengine.googleapis.com is not enabled for this project. Enable it with:\n  \
+             gcloud services enable discoveryengine.googleapis.com --project PROJECT_NUMBER\n\
+             If you enabled it recently, wait a few minutes and retry."
+        );
+    }
+
+    #[test]
+    fn other_forbidden_responses_stay_http_errors() {
+        let body = r#"{"error":{"code":403,"message":"denied","details":[{"reason":"IAM_PERMISSION_DENIED"}]}}"#;
+        let err = Error::http(StatusCode::FORBIDDEN, body);
+        assert!(matches!(err, Error::Http { .. }), "{err:?}");
+        assert_eq!(err.enable_service_command(), None);
+        // Only a 403 means the service is disabled.
+        let body = r#"{"error":{"details":[{"reason":"SERVICE_DISABLED"}]}}"#;
+        assert!(matches!(
+            Error::http(StatusCode::BAD_REQUEST, body),
+            Error::Http { .. }
+        ));
+    }
 }
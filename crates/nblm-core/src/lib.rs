@@ -1,21 +1,66 @@
+//! Client for the NotebookLM Enterprise API.
+//!
+//! Build an [`NblmClient`] from a [`TokenProvider`] and an [`EnvironmentConfig`], then call its
+//! notebook, source and audio methods. Each method's docs include a runnable example.
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//!
+//! use nblm_core::models::enterprise::source::UserContent;
+//! use nblm_core::{EnvironmentConfig, GcloudTokenProvider, NblmClient};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> nblm_core::Result<()> {
+//! let environment = EnvironmentConfig::enterprise("123456789012", "global", "us")?;
+//! let client = NblmClient::new(Arc::new(GcloudTokenProvider::new("gcloud")), environment)?;
+//!
+//! let notebook = client.create_notebook("Research").await?;
+//! let notebook_id = notebook.notebook_id().unwrap_or_default().to_string();
+//! client
+//!     .add_sources(
+//!         notebook_id,
+//!         vec![UserContent::web("https://example.com".to_string(), None)],
+//!     )
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
 pub mod auth;
 pub mod client;
+mod debug_http;
 pub mod doctor;
 pub mod env;
 mod error;
 pub mod models;
+#[cfg(feature = "test-util")]
+pub mod test_support;
 
 pub use auth::oauth::{
-    AuthorizeContext, AuthorizeParams, FileRefreshTokenStore, OAuthConfig, OAuthFlow, OAuthTokens,
-    RefreshTokenProvider, RefreshTokenStore, SerializedTokens, TokenCacheEntry, TokenStoreKey,
+    config_dir, AuthorizeContext, AuthorizeParams, FileRefreshTokenStore, OAuthConfig, OAuthFlow,
+    OAuthTokens, RefreshTokenProvider, RefreshTokenStore, SerializedTokens, TokenCacheEntry,
+    TokenStoreKey, CONFIG_DIR_ENV,
 };
 pub use auth::{
-    ensure_drive_scope, EnvTokenProvider, GcloudTokenProvider, ProviderKind, StaticTokenProvider,
-    TokenProvider,
+    ensure_drive_scope, CoordinatedProvider, EnvTokenProvider, GcloudTokenProvider,
+    MetadataTokenProvider, ProbeStatus, ProviderKind, ScopeChecker, StaticTokenProvider,
+    TokenProject, TokenProvider, DEFAULT_REFRESH_REUSE_WINDOW,
+};
+pub use client::{
+    client_options_from_env, infer_content_type, language_tag_from_locale, normalize_emoji,
+    parse_language_tag, read_upload_file, ArtifactDownload, ClientOptions, DeprecationNotice,
+    DeprecationObserver, DurationStats, HostFailureConfig, NblmClient, Phase, PhaseEvent,
+    PhaseObserver, PollBackoff, PollConfig, PollState, Polled, RequestEvent, RequestObserver,
+    ResponseEnvelope, RetryConfig, RetryEvent, Retryer, TraceContext, UploadFileError,
+    DEFAULT_MAX_BODY_SIZE, DEFAULT_MAX_RESPONSE_SIZE,
+};
+pub use debug_http::{set_debug_http, DEBUG_HTTP_ENV};
+pub use env::{
+    ApiProfile, ApiVersion, EnvironmentConfig, ProfileParams, ENDPOINT_LOCATIONS,
+    PROFILE_EXPERIMENT_FLAG,
 };
-pub use client::{NblmClient, RetryConfig, Retryer};
-pub use env::{ApiProfile, EnvironmentConfig, ProfileParams, PROFILE_EXPERIMENT_FLAG};
 pub use error::{Error, Result};
+pub use models::enterprise::id::{NotebookId, SourceId};
 
 use std::sync::Arc;
 
@@ -0,0 +1,219 @@
+use serde::{Deserialize, Serialize};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use super::audio::AudioOverviewResponse;
+use super::notebook::Notebook;
+
+/// What an [`ActivityEvent`] records.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    NotebookCreated,
+    SourceAdded,
+    AudioOverviewCreated,
+    LastViewed,
+}
+
+/// One timestamped event in a [`NotebookActivity`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ActivityEvent {
+    pub kind: ActivityKind,
+    #[serde(with = "time::serde::rfc3339")]
+    pub at: OffsetDateTime,
+    /// Resource name of the source, for [`ActivityKind::SourceAdded`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Title of the source, for [`ActivityKind::SourceAdded`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+impl ActivityEvent {
+    fn new(kind: ActivityKind, at: OffsetDateTime) -> Self {
+        Self {
+            kind,
+            at,
+            source: None,
+            title: None,
+        }
+    }
+}
+
+/// A best-effort timeline of a notebook, assembled from the timestamps the API returns: when the
+/// notebook was created, when each source was added, when the audio overview was created and
+/// when the notebook was last viewed. It is not an audit log: deletions, renames and who did
+/// what are not recorded anywhere the API exposes.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NotebookActivity {
+    pub notebook_id: String,
+    /// Oldest first.
+    pub events: Vec<ActivityEvent>,
+    /// Events left out because the API gave no timestamp, or one that is not RFC 3339.
+    pub missing_timestamps: usize,
+}
+
+impl NotebookActivity {
+    /// Assemble the timeline from a full notebook (with metadata and sources) and its audio
+    /// overview, if it has one. Events at the same instant keep the order notebook, sources,
+    /// audio overview, last viewed.
+    pub fn from_parts(
+        notebook_id: impl Into<String>,
+        notebook: &Notebook,
+        audio: Option<&AudioOverviewResponse>,
+    ) -> Self {
+        let mut events = Vec::new();
+        let mut missing_timestamps = 0;
+        let mut push = |event: Option<ActivityEvent>| match event {
+            Some(event) => events.push(event),
+            None => missing_timestamps += 1,
+        };
+
+        let metadata = notebook.metadata.as_ref();
+        push(
+            parse(metadata.and_then(|metadata| metadata.create_time.as_deref()))
+                .map(|at| ActivityEvent::new(ActivityKind::NotebookCreated, at)),
+        );
+        for source in &notebook.sources {
+            let added = source
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.source_added_timestamp.as_deref());
+            push(parse(added).map(|at| ActivityEvent {
+                source: Some(source.name.clone()),
+                title: source.title.clone(),
+                ..ActivityEvent::new(ActivityKind::SourceAdded, at)
+            }));
+        }
+        if let Some(audio) = audio {
+            let created = audio
+                .extra
+                .get("createTime")
+                .and_then(|value| value.as_str());
+            push(
+                parse(created).map(|at| ActivityEvent::new(ActivityKind::AudioOverviewCreated, at)),
+            );
+        }
+        push(
+            parse(metadata.and_then(|metadata| metadata.last_viewed.as_deref()))
+                .map(|at| ActivityEvent::new(ActivityKind::LastViewed, at)),
+        );
+
+        events.sort_by_key(|event| event.at);
+        Self {
+            notebook_id: notebook_id.into(),
+            events,
+            missing_timestamps,
+        }
+    }
+}
+
+fn parse(timestamp: Option<&str>) -> Option<OffsetDateTime> {
+    timestamp.and_then(|value| OffsetDateTime::parse(value, &Rfc3339).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notebook(value: serde_json::Value) -> Notebook {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn kinds(activity: &NotebookActivity) -> Vec<(ActivityKind, Option<&str>)> {
+        activity
+            .events
+            .iter()
+            .map(|event| (event.kind, event.title.as_deref()))
+            .collect()
+    }
+
+    #[test]
+    fn events_are_sorted_oldest_first() {
+        let notebook = notebook(serde_json::json!({
+            "title": "Research",
+            "metadata": {
+                "createTime": "2025-01-01T09:00:00Z",
+                "lastViewed": "2025-01-05T12:00:00Z",
+            },
+            "sources": [
+                {
+                    "name": "projects/1/locations/global/notebooks/nb1/sources/s2",
+                    "title": "Later",
+                    "metadata": {"sourceAddedTimestamp": "2025-01-03T08:00:00Z"},
+                },
+                {
+                    "name": "projects/1/locations/global/notebooks/nb1/sources/s1",
+                    "title": "Earlier",
+                    "metadata": {"sourceAddedTimestamp": "2025-01-02T08:00:00.123456Z"},
+                },
+            ],
+        }));
+        let audio: AudioOverviewResponse = serde_json::from_value(serde_json::json!({
+            "status": "AUDIO_OVERVIEW_STATUS_COMPLETE",
+            "createTime": "2025-01-04T10:00:00+02:00",
+        }))
+        .unwrap();
+
+        let activity = NotebookActivity::from_parts("nb1", &notebook, Some(&audio));
+
+        assert_eq!(
+            kinds(&activity),
+            [
+                (ActivityKind::NotebookCreated, None),
+                (ActivityKind::SourceAdded, Some("Earlier")),
+                (ActivityKind::SourceAdded, Some("Later")),
+                (ActivityKind::AudioOverviewCreated, None),
+                (ActivityKind::LastViewed, None),
+            ]
+        );
+        assert_eq!(activity.missing_timestamps, 0);
+        assert_eq!(
+            activity.events[1].source.as_deref(),
+            Some("projects/1/locations/global/notebooks/nb1/sources/s1")
+        );
+    }
+
+    #[test]
+    fn events_without_a_usable_timestamp_are_counted() {
+        let notebook = notebook(serde_json::json!({
+            "title": "Sparse",
+            "metadata": {"createTime": "yesterday"},
+            "sources": [
+                {"name": "s1", "title": "No metadata"},
+                {"name": "s2", "title": "Dated", "metadata": {"sourceAddedTimestamp": "2025-02-01T00:00:00Z"}},
+            ],
+        }));
+        // An overview without createTime, plus the unparsable createTime and missing lastViewed.
+        let audio = AudioOverviewResponse::default();
+
+        let activity = NotebookActivity::from_parts("nb1", &notebook, Some(&audio));
+
+        assert_eq!(
+            kinds(&activity),
+            [(ActivityKind::SourceAdded, Some("Dated"))]
+        );
+        assert_eq!(activity.missing_timestamps, 4);
+
+        let without_audio = NotebookActivity::from_parts("nb1", &notebook, None);
+        assert_eq!(without_audio.missing_timestamps, 3);
+    }
+
+    #[test]
+    fn events_serialize_with_rfc3339_times() {
+        let notebook = notebook(serde_json::json!({
+            "title": "One",
+            "metadata": {"createTime": "2025-01-01T09:00:00Z"},
+        }));
+        let activity = NotebookActivity::from_parts("nb1", &notebook, None);
+
+        assert_eq!(
+            serde_json::to_value(&activity).unwrap(),
+            serde_json::json!({
+                "notebook_id": "nb1",
+                "events": [{"kind": "notebook_created", "at": "2025-01-01T09:00:00Z"}],
+                "missing_timestamps": 1,
+            })
+        );
+    }
+}
@@ -3,6 +3,8 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use super::source::find_url;
+
 /// Domain-level request for creating an audio overview.
 ///
 /// As of today the API expects an empty object, but fields are kept optional for future use.
@@ -14,14 +16,67 @@ pub struct AudioOverviewRequest {}
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct AudioOverviewResponse {
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "audio_overview_id", skip_serializing_if = "Option::is_none")]
     pub audio_overview_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub status: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "generation_options", skip_serializing_if = "Option::is_none")]
     pub generation_options: Option<Value>,
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
 }
+
+impl AudioOverviewResponse {
+    /// Whether generation finished and the overview can be played.
+    pub fn is_ready(&self) -> bool {
+        self.status.as_deref().is_some_and(|status| {
+            status.ends_with("_COMPLETE")
+                || status.ends_with("_ACTIVE")
+                || status.ends_with("_READY")
+        })
+    }
+
+    /// Whether generation failed.
+    pub fn is_failed(&self) -> bool {
+        self.status
+            .as_deref()
+            .is_some_and(|status| status.ends_with("_FAILED") || status.ends_with("_ERROR"))
+    }
+
+    /// Where the generated audio can be downloaded, once the API reports it: the first `http(s)`
+    /// value under a URL-like key (`audioUri`, `downloadUrl`, ...), nested objects included.
+    ///
+    /// Fetch it with [`NblmClient::download_artifact`](crate::NblmClient::download_artifact).
+    pub fn audio_uri(&self) -> Option<String> {
+        find_url(&self.extra)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn audio_uri_is_read_from_extra_fields() {
+        let ready: AudioOverviewResponse = serde_json::from_value(serde_json::json!({
+            "status": "AUDIO_OVERVIEW_STATUS_COMPLETE",
+            "name": "projects/1/locations/global/notebooks/nb/audioOverviews/default",
+            "audio": {"mimeType": "audio/mpeg", "audioUri": "https://example.com/a.mp3"},
+        }))
+        .unwrap();
+        assert!(ready.is_ready());
+        assert_eq!(
+            ready.audio_uri().as_deref(),
+            Some("https://example.com/a.mp3")
+        );
+
+        let pending: AudioOverviewResponse = serde_json::from_value(serde_json::json!({
+            "status": "AUDIO_OVERVIEW_STATUS_IN_PROGRESS",
+            "name": "projects/1/locations/global/notebooks/nb/audioOverviews/default",
+        }))
+        .unwrap();
+        assert_eq!(pending.audio_uri(), None);
+    }
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+
+use super::source::NotebookSource;
+
+/// Default word budget for a notebook before audio overview quality starts to degrade.
+pub const DEFAULT_WORD_BUDGET: u64 = 500_000;
+
+/// Word count of a single source within a [`NotebookWordBudget`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SourceWordCount {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// `None` when the API did not report a word count (counted as 0 in the total).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub word_count: Option<u64>,
+}
+
+impl SourceWordCount {
+    pub fn words(&self) -> u64 {
+        self.word_count.unwrap_or(0)
+    }
+}
+
+/// Aggregated word counts for every source in a notebook.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NotebookWordBudget {
+    pub notebook_id: String,
+    pub total_words: u64,
+    /// Sources sorted by word count, largest first.
+    pub sources: Vec<SourceWordCount>,
+    /// Number of sources without a reported word count.
+    pub missing_word_counts: usize,
+}
+
+impl NotebookWordBudget {
+    pub fn from_sources(notebook_id: impl Into<String>, sources: &[NotebookSource]) -> Self {
+        let mut entries: Vec<SourceWordCount> = sources
+            .iter()
+            .map(|source| SourceWordCount {
+                name: source.name.clone(),
+                title: source.title.clone(),
+                word_count: source.metadata.as_ref().and_then(|meta| meta.word_count),
+            })
+            .collect();
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.words()));
+
+        Self {
+            notebook_id: notebook_id.into(),
+            total_words: entries.iter().map(SourceWordCount::words).sum(),
+            missing_word_counts: entries.iter().filter(|e| e.word_count.is_none()).count(),
+            sources: entries,
+        }
+    }
+
+    /// Number of words above `budget`, or `None` when the notebook fits.
+    pub fn over_budget(&self, budget: u64) -> Option<u64> {
+        self.total_words
+            .checked_sub(budget)
+            .filter(|over| *over > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::enterprise::source::NotebookSourceMetadata;
+
+    fn source(name: &str, words: Option<u64>) -> NotebookSource {
+        NotebookSource {
+            name: name.to_string(),
+            title: Some(format!("Title {name}")),
+            metadata: Some(NotebookSourceMetadata {
+                word_count: words,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sums_and_sorts_descending() {
+        let sources = vec![
+            source("a", Some(100)),
+            source("b", Some(900)),
+            source("c", Some(400)),
+        ];
+        let budget = NotebookWordBudget::from_sources("nb", &sources);
+        assert_eq!(budget.total_words, 1400);
+        let names: Vec<&str> = budget.sources.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["b", "c", "a"]);
+        assert_eq!(budget.missing_word_counts, 0);
+    }
+
+    #[test]
+    fn missing_word_counts_are_treated_as_zero() {
+        let mut no_metadata = source("d", None);
+        no_metadata.metadata = None;
+        let sources = vec![source("a", Some(50)), source("b", None), no_metadata];
+        let budget = NotebookWordBudget::from_sources("nb", &sources);
+        assert_eq!(budget.total_words, 50);
+        assert_eq!(budget.missing_word_counts, 2);
+        assert_eq!(budget.sources[0].name, "a");
+    }
+
+    #[test]
+    fn over_budget_reports_excess() {
+        let budget = NotebookWordBudget::from_sources("nb", &[source("a", Some(1200))]);
+        assert_eq!(budget.over_budget(1000), Some(200));
+        assert_eq!(budget.over_budget(1200), None);
+        assert_eq!(budget.over_budget(5000), None);
+    }
+
+    #[test]
+    fn empty_notebook_has_zero_total() {
+        let budget = NotebookWordBudget::from_sources("nb", &[]);
+        assert_eq!(budget.total_words, 0);
+        assert!(budget.sources.is_empty());
+    }
+}
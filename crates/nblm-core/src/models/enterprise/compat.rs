@@ -0,0 +1,11 @@
+//! Response shapes that differ between API versions ([`crate::ApiVersion`]).
+//!
+//! The models are written against `v1alpha`. When a `v1beta` response is known to name or nest a
+//! field differently, the difference is absorbed here rather than in a second set of models: a
+//! renamed field gets a `#[serde(alias = "...")]` on the model pointing at an entry below, and a
+//! reshaped one gets a `deserialize_with` adapter defined in this module. Each entry names the
+//! model field, the `v1alpha` shape and the `v1beta` shape, and has a fixture under
+//! `tests/fixtures/responses` read through the same typed model by `tests/api_version.rs`.
+//!
+//! No differences are recorded yet, so both versions share the models unchanged and the tests
+//! serve the `v1alpha` fixtures to a `v1beta` client.
@@ -0,0 +1,236 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+use crate::models::enterprise::name::NotebookName;
+
+/// ID of a notebook (the last segment of `projects/.../notebooks/{notebook_id}`).
+///
+/// Client methods take `impl Into<NotebookId>`, so plain strings keep working; passing a
+/// [`SourceId`] where a notebook is expected does not compile. Conversions from strings are not
+/// checked, the client validates the ID before sending a request. Use [`NotebookId::parse`] to
+/// check it up front. A full notebook resource name is accepted by the client when it names a
+/// notebook under the client's own project and location (see [`NotebookId::resolve`]).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NotebookId(String);
+
+/// ID of a source within a notebook (the last segment of `.../sources/{source_id}`).
+///
+/// See [`NotebookId`] for how conversions and validation work.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SourceId(String);
+
+impl NotebookId {
+    /// Parse an ID, rejecting empty values and values containing `/`.
+    pub fn parse(id: impl Into<String>) -> Result<Self> {
+        let id = Self(id.into());
+        id.validate()?;
+        Ok(id)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        validate_id("notebook", &self.0)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+
+    /// The bare ID to use under `parent` (`projects/{project}/locations/{location}`).
+    ///
+    /// A full notebook resource name under `parent` gives its trailing ID, so a name copied from
+    /// API output works where an ID is expected. A resource name under another project or
+    /// location is an [`Error::Validation`] naming both, rather than a URL with the parent twice
+    /// in it. Anything else is validated as with [`NotebookId::parse`].
+    pub fn resolve(&self, parent: &str) -> Result<Self> {
+        let Ok(name) = NotebookName::parse(&self.0) else {
+            self.validate()?;
+            return Ok(self.clone());
+        };
+        if name.parent() == parent.trim_matches('/') {
+            return Ok(Self(name.notebook_id().to_string()));
+        }
+        Err(Error::validation(format!(
+            "notebook '{}' is a resource name in {}, but the client is configured for {parent}; \
+             pass the bare ID '{}' with the matching project and location",
+            self.0,
+            name.parent(),
+            name.notebook_id()
+        )))
+    }
+
+    /// Convert and resolve under `parent` in one step, for client methods taking
+    /// `impl Into<NotebookId>`.
+    pub(crate) fn checked(id: impl Into<Self>, parent: &str) -> Result<Self> {
+        id.into().resolve(parent)
+    }
+}
+
+impl SourceId {
+    /// Parse an ID, rejecting empty values and values containing `/`.
+    pub fn parse(id: impl Into<String>) -> Result<Self> {
+        let id = Self(id.into());
+        id.validate()?;
+        Ok(id)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        validate_id("source", &self.0)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+
+    /// Convert and validate in one step, for client methods taking `impl Into<SourceId>`.
+    pub(crate) fn checked(id: impl Into<Self>) -> Result<Self> {
+        let id = id.into();
+        id.validate()?;
+        Ok(id)
+    }
+}
+
+fn validate_id(kind: &str, id: &str) -> Result<()> {
+    if id.trim().is_empty() {
+        return Err(Error::validation(format!("{kind} ID must not be empty")));
+    }
+    if id.contains('/') {
+        return Err(Error::validation(format!(
+            "invalid {kind} ID '{id}': pass the bare ID, not a resource name or path"
+        )));
+    }
+    Ok(())
+}
+
+macro_rules! string_conversions {
+    ($id:ident) => {
+        impl From<&str> for $id {
+            fn from(id: &str) -> Self {
+                Self(id.to_string())
+            }
+        }
+
+        impl From<String> for $id {
+            fn from(id: String) -> Self {
+                Self(id)
+            }
+        }
+
+        impl From<&String> for $id {
+            fn from(id: &String) -> Self {
+                Self(id.clone())
+            }
+        }
+
+        impl From<&$id> for $id {
+            fn from(id: &$id) -> Self {
+                id.clone()
+            }
+        }
+
+        impl FromStr for $id {
+            type Err = Error;
+
+            fn from_str(s: &str) -> Result<Self> {
+                Self::parse(s)
+            }
+        }
+
+        impl AsRef<str> for $id {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $id {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+    };
+}
+
+string_conversions!(NotebookId);
+string_conversions!(SourceId);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_accepts_bare_ids() {
+        let notebook = NotebookId::parse("nb-1").unwrap();
+        assert_eq!(notebook.as_str(), "nb-1");
+        assert_eq!(notebook.to_string(), "nb-1");
+        assert_eq!("src_1".parse::<SourceId>().unwrap().into_string(), "src_1");
+    }
+
+    #[test]
+    fn parse_rejects_empty_ids_and_paths() {
+        for input in ["", "   ", "nb1/sources/s1", "projects/123/notebooks/nb1"] {
+            let err = NotebookId::parse(input).unwrap_err();
+            assert!(matches!(err, Error::Validation(_)), "{input}: {err}");
+            assert!(SourceId::parse(input).is_err(), "{input}");
+        }
+        let err = SourceId::parse("a/b").unwrap_err();
+        assert!(err.to_string().contains("invalid source ID 'a/b'"), "{err}");
+    }
+
+    #[test]
+    fn string_conversions_are_unchecked_until_validated() {
+        let owned = String::from("nb1");
+        assert_eq!(NotebookId::from(&owned), NotebookId::from("nb1"));
+        assert_eq!(NotebookId::from(owned), NotebookId::from("nb1"));
+
+        let invalid = SourceId::from("a/b");
+        assert_eq!(invalid.as_str(), "a/b");
+        assert!(invalid.validate().is_err());
+        assert!(SourceId::checked(&invalid).is_err());
+        assert!(NotebookId::checked("", PARENT).is_err());
+    }
+
+    const PARENT: &str = "projects/123/locations/global";
+
+    #[test]
+    fn resolve_extracts_the_id_from_a_name_under_the_parent() {
+        let id = NotebookId::from("projects/123/locations/global/notebooks/nb1");
+
+        assert_eq!(id.resolve(PARENT).unwrap(), NotebookId::from("nb1"));
+    }
+
+    #[test]
+    fn resolve_rejects_a_name_under_another_project() {
+        let id = NotebookId::from("projects/999/locations/global/notebooks/nb1");
+
+        let err = id.resolve(PARENT).unwrap_err();
+
+        assert!(matches!(err, Error::Validation(_)), "{err}");
+        let message = err.to_string();
+        assert!(
+            message.contains("'projects/999/locations/global/notebooks/nb1'")
+                && message.contains("configured for projects/123/locations/global")
+                && message.contains("bare ID 'nb1'"),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn resolve_leaves_plain_ids_alone() {
+        assert_eq!(
+            NotebookId::from("nb-1").resolve(PARENT).unwrap(),
+            NotebookId::from("nb-1")
+        );
+        let err = NotebookId::from("nb1/sources/s1")
+            .resolve(PARENT)
+            .unwrap_err();
+        assert!(err.to_string().contains("pass the bare ID"), "{err}");
+    }
+}
@@ -1,3 +1,10 @@
+pub mod activity;
 pub mod audio;
+pub mod budget;
+mod compat;
+pub mod id;
+pub mod name;
 pub mod notebook;
+pub mod query;
 pub mod source;
+pub mod stats;
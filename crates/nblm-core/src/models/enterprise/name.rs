@@ -0,0 +1,131 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+/// Parsed notebook resource name
+/// (`projects/{project}/locations/{location}/notebooks/{notebook_id}`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NotebookName {
+    project: String,
+    location: String,
+    notebook_id: String,
+}
+
+impl NotebookName {
+    pub fn new(
+        project: impl Into<String>,
+        location: impl Into<String>,
+        notebook_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            project: project.into(),
+            location: location.into(),
+            notebook_id: notebook_id.into(),
+        }
+    }
+
+    pub fn parse(input: &str) -> Result<Self> {
+        let trimmed = input.trim().trim_matches('/');
+        let segments: Vec<&str> = trimmed.split('/').collect();
+        match segments.as_slice() {
+            ["projects", project, "locations", location, "notebooks", notebook_id]
+                if !project.is_empty() && !location.is_empty() && !notebook_id.is_empty() =>
+            {
+                Ok(Self::new(*project, *location, *notebook_id))
+            }
+            _ => Err(Error::validation(format!(
+                "invalid notebook name '{input}': expected projects/PROJECT/locations/LOCATION/notebooks/NOTEBOOK_ID"
+            ))),
+        }
+    }
+
+    pub fn project(&self) -> &str {
+        &self.project
+    }
+
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+
+    pub fn notebook_id(&self) -> &str {
+        &self.notebook_id
+    }
+
+    /// Parent path (`projects/{project}/locations/{location}`) the notebook belongs to.
+    pub fn parent(&self) -> String {
+        format!("projects/{}/locations/{}", self.project, self.location)
+    }
+}
+
+impl fmt::Display for NotebookName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/notebooks/{}", self.parent(), self.notebook_id)
+    }
+}
+
+impl FromStr for NotebookName {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+/// Last segment of a resource name (`.../sources/{source_id}` gives `source_id`).
+///
+/// A bare ID is returned as-is; `None` if the name is empty.
+pub fn resource_id(name: &str) -> Option<&str> {
+    name.trim()
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .filter(|id| !id.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_full_resource_name() {
+        let name = NotebookName::parse("projects/123/locations/global/notebooks/nb1").unwrap();
+        assert_eq!(name.project(), "123");
+        assert_eq!(name.location(), "global");
+        assert_eq!(name.notebook_id(), "nb1");
+        assert_eq!(name.parent(), "projects/123/locations/global");
+        assert_eq!(
+            name.to_string(),
+            "projects/123/locations/global/notebooks/nb1"
+        );
+    }
+
+    #[test]
+    fn rejects_bare_id_and_malformed_names() {
+        for input in [
+            "nb1",
+            "projects/123/locations/global",
+            "projects/123/locations/global/notebooks/",
+            "projects/123/locations/global/notebooks/nb1/sources/s1",
+            "project/123/locations/global/notebooks/nb1",
+        ] {
+            let err = NotebookName::parse(input).unwrap_err();
+            assert!(
+                matches!(err, Error::Validation(_)),
+                "expected validation error for {input}"
+            );
+        }
+    }
+
+    #[test]
+    fn resource_id_takes_last_segment() {
+        assert_eq!(
+            resource_id("projects/123/locations/global/notebooks/nb1/sources/s1"),
+            Some("s1")
+        );
+        assert_eq!(resource_id("sources/s1/"), Some("s1"));
+        assert_eq!(resource_id("s1"), Some("s1"));
+        assert_eq!(resource_id(""), None);
+        assert_eq!(resource_id(" / "), None);
+    }
+}
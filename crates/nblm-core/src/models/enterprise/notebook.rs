@@ -4,16 +4,58 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use super::source::NotebookSource;
+use crate::error::{Error, Result};
+
+/// Partial response mask for [`NblmClient::get_notebook_with_fields`]: the notebook's identity
+/// plus each source's name, ID, title and ingestion status.
+///
+/// [`NblmClient::get_notebook_with_fields`]: crate::NblmClient::get_notebook_with_fields
+pub const NOTEBOOK_FIELDS_MINIMAL: &str =
+    "name,notebookId,title,emoji,sources(name,sourceId,title,settings(status))";
+
+/// Partial response mask for [`NblmClient::list_recently_viewed_with_fields`]: each notebook's
+/// identity, title and emoji, plus the page token.
+///
+/// [`NblmClient::list_recently_viewed_with_fields`]: crate::NblmClient::list_recently_viewed_with_fields
+pub const RECENT_NOTEBOOKS_FIELDS_MINIMAL: &str =
+    "notebooks(name,notebookId,title,emoji),nextPageToken";
+
+/// Partial response mask for [`NblmClient::notebook_source_counts`]: each notebook's identity
+/// and title, only the name of each source, and the page token. The API has no source count
+/// field, so the source names are what is counted.
+///
+/// [`NblmClient::notebook_source_counts`]: crate::NblmClient::notebook_source_counts
+pub const SOURCE_COUNT_FIELDS: &str =
+    "notebooks(name,notebookId,title,sources(name)),nextPageToken";
+
+/// [`SOURCE_COUNT_FIELDS`] for a single notebook, for
+/// [`NblmClient::get_notebook_with_fields`].
+///
+/// [`NblmClient::get_notebook_with_fields`]: crate::NblmClient::get_notebook_with_fields
+pub const NOTEBOOK_SOURCE_COUNT_FIELDS: &str = "name,notebookId,title,sources(name)";
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct Notebook {
     pub name: Option<String>,
+    #[serde(default)]
     pub title: String,
-    #[serde(rename = "notebookId", skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename = "notebookId",
+        alias = "notebook_id",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub notebook_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub emoji: Option<String>,
+    /// Generated summary of the notebook's sources, when the API includes one.
+    #[serde(
+        alias = "description",
+        alias = "notebook_summary",
+        alias = "notebookSummary",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub summary: Option<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub sources: Vec<NotebookSource>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -22,23 +64,28 @@ pub struct Notebook {
     pub extra: HashMap<String, Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A notebook's identity without its sources, for code that handles many notebooks at once.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct NotebookRef {
     pub notebook_id: String,
+    /// Resource name; the bare ID when the notebook came without one, which the API's notebook
+    /// calls accept as well.
     pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub title: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct NotebookMetadata {
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "create_time", skip_serializing_if = "Option::is_none")]
     pub create_time: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "is_shareable", skip_serializing_if = "Option::is_none")]
     pub is_shareable: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "is_shared", skip_serializing_if = "Option::is_none")]
     pub is_shared: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "last_viewed", skip_serializing_if = "Option::is_none")]
     pub last_viewed: Option<String>,
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
@@ -60,4 +107,164 @@ pub struct BatchDeleteNotebooksResponse {
 pub struct ListRecentlyViewedResponse {
     #[serde(default)]
     pub notebooks: Vec<Notebook>,
+    /// Token for the next page; absent on the last page.
+    #[serde(alias = "next_page_token", skip_serializing_if = "Option::is_none")]
+    pub next_page_token: Option<String>,
+}
+
+impl Notebook {
+    /// Notebook ID for follow-up calls: `notebookId`, else the last segment of `name`.
+    ///
+    /// Some responses (and partial responses without `notebookId` in the mask) only carry the
+    /// resource name. A name ending in `/` has no ID.
+    pub fn notebook_id(&self) -> Option<&str> {
+        self.notebook_id.as_deref().or_else(|| {
+            self.name
+                .as_deref()
+                .and_then(|name| name.rsplit('/').next())
+                .filter(|id| !id.is_empty())
+        })
+    }
+}
+
+impl TryFrom<&Notebook> for NotebookRef {
+    type Error = Error;
+
+    /// Fails with [`Error::Validation`] when the notebook has neither a `notebookId` nor a name
+    /// to take one from.
+    fn try_from(notebook: &Notebook) -> Result<Self> {
+        let notebook_id = notebook
+            .notebook_id()
+            .ok_or_else(|| Error::validation("notebook has neither a notebookId nor a name"))?;
+        Ok(Self {
+            notebook_id: notebook_id.to_string(),
+            name: notebook
+                .name
+                .clone()
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| notebook_id.to_string()),
+            title: notebook.title.clone(),
+        })
+    }
+}
+
+impl TryFrom<Notebook> for NotebookRef {
+    type Error = Error;
+
+    fn try_from(notebook: Notebook) -> Result<Self> {
+        Self::try_from(&notebook)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notebook_id_prefers_notebook_id_field() {
+        let notebook = Notebook {
+            name: Some("projects/1/locations/global/notebooks/from-name".to_string()),
+            notebook_id: Some("from-field".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(notebook.notebook_id(), Some("from-field"));
+    }
+
+    #[test]
+    fn notebook_id_falls_back_to_name() {
+        let notebook: Notebook = serde_json::from_str(
+            r#"{"name": "projects/1/locations/global/notebooks/nb1", "title": "T"}"#,
+        )
+        .unwrap();
+        assert!(notebook.notebook_id.is_none());
+        assert_eq!(notebook.notebook_id(), Some("nb1"));
+        assert_eq!(Notebook::default().notebook_id(), None);
+    }
+
+    #[test]
+    fn snake_case_keys_deserialize_into_the_same_fields() {
+        let notebook: Notebook = serde_json::from_str(
+            r#"{"notebook_id": "nb1", "metadata": {"create_time": "t", "is_shared": true}}"#,
+        )
+        .unwrap();
+        assert_eq!(notebook.notebook_id.as_deref(), Some("nb1"));
+        let metadata = notebook.metadata.unwrap();
+        assert_eq!(metadata.create_time.as_deref(), Some("t"));
+        assert_eq!(metadata.is_shared, Some(true));
+        assert!(metadata.extra.is_empty());
+        assert!(notebook.extra.is_empty());
+    }
+
+    #[test]
+    fn summary_accepts_description_and_serializes_as_summary() {
+        for key in [
+            "summary",
+            "description",
+            "notebookSummary",
+            "notebook_summary",
+        ] {
+            let notebook: Notebook =
+                serde_json::from_value(serde_json::json!({ key: "About revenue." })).unwrap();
+            assert_eq!(notebook.summary.as_deref(), Some("About revenue."), "{key}");
+            assert!(notebook.extra.is_empty(), "{key}");
+            assert_eq!(
+                serde_json::to_value(&notebook).unwrap()["summary"],
+                "About revenue."
+            );
+        }
+    }
+
+    #[test]
+    fn notebook_ref_takes_the_id_from_the_field_or_the_name() {
+        let notebook = Notebook {
+            name: Some("projects/1/locations/global/notebooks/nb1".to_string()),
+            title: "Research".to_string(),
+            sources: vec![NotebookSource::default(); 3],
+            ..Default::default()
+        };
+        let reference = NotebookRef::try_from(&notebook).unwrap();
+        assert_eq!(
+            reference,
+            NotebookRef {
+                notebook_id: "nb1".to_string(),
+                name: "projects/1/locations/global/notebooks/nb1".to_string(),
+                title: "Research".to_string(),
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(&reference).unwrap(),
+            serde_json::json!({
+                "notebookId": "nb1",
+                "name": "projects/1/locations/global/notebooks/nb1",
+                "title": "Research"
+            })
+        );
+
+        let bare = Notebook {
+            notebook_id: Some("nb2".to_string()),
+            ..Default::default()
+        };
+        let reference = NotebookRef::try_from(bare).unwrap();
+        assert_eq!(reference.notebook_id, "nb2");
+        assert_eq!(reference.name, "nb2");
+        assert_eq!(
+            serde_json::to_value(&reference).unwrap(),
+            serde_json::json!({"notebookId": "nb2", "name": "nb2"})
+        );
+    }
+
+    #[test]
+    fn notebook_ref_needs_an_id_or_a_name() {
+        for notebook in [
+            Notebook::default(),
+            Notebook {
+                name: Some("projects/1/locations/global/notebooks/".to_string()),
+                title: "Untitled".to_string(),
+                ..Default::default()
+            },
+        ] {
+            let err = NotebookRef::try_from(&notebook).unwrap_err();
+            assert!(matches!(err, Error::Validation(_)), "{err}");
+        }
+    }
 }
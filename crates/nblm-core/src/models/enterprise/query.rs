@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::source::NotebookSourceId;
+
+/// A question to answer from a notebook's sources.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryRequest {
+    pub query: String,
+    /// Restrict the answer to these sources; all sources when `None`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_ids: Option<Vec<String>>,
+}
+
+/// Answer to a [`QueryRequest`].
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryResponse {
+    /// The answer text; `None` when the sources held no answer.
+    #[serde(
+        alias = "answerText",
+        alias = "answer_text",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub answer: Option<String>,
+    /// Passages the answer is based on, in the order the answer refers to them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub citations: Vec<Citation>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl QueryResponse {
+    /// Whether the response carries any answer text.
+    pub fn has_answer(&self) -> bool {
+        self.answer
+            .as_deref()
+            .is_some_and(|answer| !answer.trim().is_empty())
+    }
+}
+
+/// A source passage cited by a [`QueryResponse`].
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Citation {
+    #[serde(alias = "source_id", skip_serializing_if = "Option::is_none")]
+    pub source_id: Option<NotebookSourceId>,
+    /// Title of the cited source.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The cited passage.
+    #[serde(alias = "snippet", skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn has_answer_ignores_blank_text() {
+        let mut response = QueryResponse::default();
+        assert!(!response.has_answer());
+        response.answer = Some("  ".to_string());
+        assert!(!response.has_answer());
+        response.answer = Some("42".to_string());
+        assert!(response.has_answer());
+    }
+}
@@ -3,6 +3,9 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use super::name::resource_id;
+use crate::error::{Error, Result};
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct NotebookSource {
@@ -11,7 +14,7 @@ pub struct NotebookSource {
     pub name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub settings: Option<NotebookSourceSettings>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "source_id", skip_serializing_if = "Option::is_none")]
     pub source_id: Option<NotebookSourceId>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
@@ -19,14 +22,82 @@ pub struct NotebookSource {
     pub extra: HashMap<String, Value>,
 }
 
+/// A source's identity without its metadata, for code that handles many sources at once.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceRef {
+    pub source_id: String,
+    /// Full `projects/.../notebooks/NOTEBOOK_ID/sources/SOURCE_ID` name.
+    pub name: String,
+    pub notebook_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+impl SourceRef {
+    /// Reference to `source` in notebook `notebook_id` under `parent`. Partial responses can
+    /// name sources by bare ID; the full name is built from the notebook's then.
+    pub fn in_notebook(parent: &str, notebook_id: &str, source: &NotebookSource) -> Result<Self> {
+        let source_id = source.source_id().ok_or_else(|| {
+            Error::validation(format!(
+                "source in notebook {notebook_id} has neither a sourceId nor a name"
+            ))
+        })?;
+        let name = if source.name.contains('/') {
+            source.name.clone()
+        } else {
+            format!("{parent}/notebooks/{notebook_id}/sources/{source_id}")
+        };
+        Ok(Self {
+            source_id: source_id.to_string(),
+            name,
+            notebook_id: notebook_id.to_string(),
+            title: source.title.clone(),
+        })
+    }
+}
+
+impl TryFrom<&NotebookSource> for SourceRef {
+    type Error = Error;
+
+    /// Takes the notebook ID from the source's full name; fails with [`Error::Validation`] when
+    /// the name is not `.../notebooks/NOTEBOOK_ID/sources/SOURCE_ID` and no source ID is found.
+    /// Use [`SourceRef::in_notebook`] when the notebook is known.
+    fn try_from(source: &NotebookSource) -> Result<Self> {
+        let segments: Vec<&str> = source.name.trim().trim_matches('/').split('/').collect();
+        let notebook_id = match segments.as_slice() {
+            [.., "notebooks", notebook_id, "sources", _] if !notebook_id.is_empty() => *notebook_id,
+            _ => {
+                return Err(Error::validation(format!(
+                    "source name '{}' does not say which notebook it belongs to",
+                    source.name
+                )))
+            }
+        };
+        // The name is already a full one, so no parent is needed to build it.
+        Self::in_notebook("", notebook_id, source)
+    }
+}
+
+impl TryFrom<NotebookSource> for SourceRef {
+    type Error = Error;
+
+    fn try_from(source: NotebookSource) -> Result<Self> {
+        Self::try_from(&source)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct NotebookSourceMetadata {
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        alias = "source_added_timestamp",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub source_added_timestamp: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "word_count", skip_serializing_if = "Option::is_none")]
     pub word_count: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "youtube_metadata", skip_serializing_if = "Option::is_none")]
     pub youtube_metadata: Option<NotebookSourceYoutubeMetadata>,
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
@@ -35,9 +106,9 @@ pub struct NotebookSourceMetadata {
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct NotebookSourceYoutubeMetadata {
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "channel_name", skip_serializing_if = "Option::is_none")]
     pub channel_name: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "video_id", skip_serializing_if = "Option::is_none")]
     pub video_id: Option<String>,
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
@@ -83,12 +154,34 @@ pub enum UserContent {
 }
 
 impl UserContent {
+    /// Short name of the content kind: `web`, `text`, `drive` or `video`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Web { .. } => "web",
+            Self::Text { .. } => "text",
+            Self::GoogleDrive { .. } => "drive",
+            Self::Video { .. } => "video",
+        }
+    }
+
     pub fn web(url: String, source_name: Option<String>) -> Self {
         Self::Web {
-            web_content: WebContent { url, source_name },
+            web_content: WebContent {
+                url,
+                source_name,
+                fetch_options: None,
+            },
         }
     }
 
+    /// Attach fetch options to a web source; other kinds are returned unchanged.
+    pub fn with_fetch_options(mut self, options: Value) -> Self {
+        if let Self::Web { web_content } = &mut self {
+            web_content.fetch_options = Some(options);
+        }
+        self
+    }
+
     pub fn text(content: String, source_name: Option<String>) -> Self {
         Self::Text {
             text_content: TextContent {
@@ -119,12 +212,35 @@ impl UserContent {
     }
 }
 
+/// Longest source name, in characters, that is sent. The API cuts longer names off at no fixed
+/// point, so they are refused before the request.
+pub const MAX_SOURCE_NAME_CHARS: usize = 500;
+
+/// Trim a source name. A blank name becomes `None`, so the API titles the source itself; one
+/// longer than [`MAX_SOURCE_NAME_CHARS`] fails with [`Error::Validation`](crate::Error).
+pub fn normalize_source_name(name: Option<&str>) -> crate::Result<Option<String>> {
+    let Some(name) = name.map(str::trim).filter(|name| !name.is_empty()) else {
+        return Ok(None);
+    };
+    let chars = name.chars().count();
+    if chars > MAX_SOURCE_NAME_CHARS {
+        return Err(crate::Error::validation(format!(
+            "source name is {chars} characters long (maximum {MAX_SOURCE_NAME_CHARS})"
+        )));
+    }
+    Ok(Some(name.to_string()))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct WebContent {
     pub url: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub source_name: Option<String>,
+    /// Hints for the fetcher (e.g. user agent, wait-for-render), sent verbatim as
+    /// `fetchOptions` so new API options work without a client release.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fetch_options: Option<Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -162,7 +278,7 @@ pub struct BatchCreateSourcesRequest {
 pub struct BatchCreateSourcesResponse {
     #[serde(default)]
     pub sources: Vec<NotebookSource>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "error_count", skip_serializing_if = "Option::is_none")]
     pub error_count: Option<i32>,
 }
 
@@ -182,12 +298,214 @@ pub struct BatchDeleteSourcesResponse {
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct UploadSourceFileResponse {
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(alias = "source_id", skip_serializing_if = "Option::is_none")]
     pub source_id: Option<NotebookSourceId>,
+    /// Full resource name of the created source.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Ingestion state of the created source.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
     #[serde(flatten)]
     pub extra: HashMap<String, Value>,
 }
 
+impl NotebookSource {
+    /// Source ID for follow-up calls: `sourceId.id`, else the last segment of `name`.
+    pub fn source_id(&self) -> Option<&str> {
+        self.source_id
+            .as_ref()
+            .and_then(|id| id.id.as_deref())
+            .or_else(|| resource_id(&self.name))
+    }
+
+    /// Ingestion status reported by the API, e.g. `SOURCE_STATUS_COMPLETE`.
+    pub fn status(&self) -> Option<&str> {
+        self.settings
+            .as_ref()
+            .and_then(|settings| settings.status.as_deref())
+    }
+
+    /// Whether the API reported this source as failed to ingest.
+    pub fn is_failed(&self) -> bool {
+        self.status()
+            .is_some_and(|status| status.ends_with("_ERROR") || status.ends_with("_FAILED"))
+    }
+
+    /// Whether ingestion is over: the source is complete or active, or reports no status.
+    pub fn is_ingested(&self) -> bool {
+        self.status()
+            .is_none_or(|status| status.ends_with("_COMPLETE") || status.ends_with("ACTIVE"))
+    }
+
+    /// The web page or YouTube video this source was ingested from, when the API reports it.
+    ///
+    /// YouTube sources are recognized by `youtubeMetadata.videoId`. Otherwise the first
+    /// `http(s)` value under a key such as `url`, `uri` or `webUrl` is used, searching the
+    /// metadata before the rest of the source; YouTube links found that way count as videos.
+    /// Text, Drive and uploaded sources have no such URL and return `None`.
+    pub fn origin(&self) -> Option<SourceOrigin> {
+        let metadata = self.metadata.as_ref();
+        if let Some(video_id) = metadata
+            .and_then(|metadata| metadata.youtube_metadata.as_ref())
+            .and_then(|youtube| youtube.video_id.as_deref())
+            .filter(|id| !id.is_empty())
+        {
+            return Some(SourceOrigin::Video {
+                url: format!("https://www.youtube.com/watch?v={video_id}"),
+            });
+        }
+        let url = metadata
+            .and_then(|metadata| find_url(&metadata.extra))
+            .or_else(|| find_url(&self.extra))?;
+        Some(if is_youtube_url(&url) {
+            SourceOrigin::Video { url }
+        } else {
+            SourceOrigin::Web { url }
+        })
+    }
+}
+
+/// Where a web or YouTube source was ingested from (see [`NotebookSource::origin`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceOrigin {
+    Web { url: String },
+    Video { url: String },
+}
+
+impl SourceOrigin {
+    pub fn url(&self) -> &str {
+        match self {
+            Self::Web { url } | Self::Video { url } => url,
+        }
+    }
+
+    /// Content that adds the same URL again. YouTube sources cannot be named, so
+    /// `source_name` only applies to web pages.
+    pub fn to_content(&self, source_name: Option<String>) -> UserContent {
+        match self {
+            Self::Web { url } => UserContent::web(url.clone(), source_name),
+            Self::Video { url } => UserContent::video(url.clone()),
+        }
+    }
+}
+
+/// First `http(s)` string under a URL-like key, looking through nested objects in key order.
+pub(crate) fn find_url(fields: &HashMap<String, Value>) -> Option<String> {
+    fn is_url_key(key: &str) -> bool {
+        let key = key.to_ascii_lowercase();
+        key.ends_with("url") || key.ends_with("uri")
+    }
+    fn search<'a>(fields: impl Iterator<Item = (&'a String, &'a Value)>) -> Option<String> {
+        let mut fields: Vec<_> = fields.collect();
+        fields.sort_by_key(|(key, _)| key.as_str());
+        let direct = fields.iter().find_map(|(key, value)| match value {
+            Value::String(url)
+                if is_url_key(key)
+                    && (url.starts_with("https://") || url.starts_with("http://")) =>
+            {
+                Some(url.clone())
+            }
+            _ => None,
+        });
+        direct.or_else(|| {
+            fields.iter().find_map(|(_, value)| match value {
+                Value::Object(map) => search(map.iter()),
+                _ => None,
+            })
+        })
+    }
+    search(fields.iter())
+}
+
+fn is_youtube_url(url: &str) -> bool {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+        .is_some_and(|host| {
+            host == "youtu.be" || host == "youtube.com" || host.ends_with(".youtube.com")
+        })
+}
+
+impl BatchCreateSourcesResponse {
+    /// IDs of the sources that were created, in response order.
+    pub fn created_ids(&self) -> Vec<&str> {
+        self.sources
+            .iter()
+            .filter(|source| !source.is_failed())
+            .filter_map(NotebookSource::source_id)
+            .collect()
+    }
+
+    /// Sources the API reported as failed to ingest.
+    pub fn failed(&self) -> Vec<&NotebookSource> {
+        self.sources
+            .iter()
+            .filter(|source| source.is_failed())
+            .collect()
+    }
+}
+
+/// A source that was created by a batch request, tagged with its index in the request.
+#[derive(Debug, Clone, Serialize)]
+pub struct IngestedSource {
+    pub index: usize,
+    pub source: NotebookSource,
+}
+
+/// A source the API could not ingest, together with the content that was submitted.
+#[derive(Debug, Clone, Serialize)]
+pub struct FailedSource {
+    pub index: usize,
+    pub content: UserContent,
+    /// The source entry returned by the API, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<NotebookSource>,
+}
+
+/// Per-source outcome of a batch source creation.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PartialIngestResult {
+    pub succeeded: Vec<IngestedSource>,
+    pub failed: Vec<FailedSource>,
+}
+
+impl PartialIngestResult {
+    /// Pair submitted contents with the returned sources.
+    ///
+    /// The API returns one source per submitted content, in request order. Contents without a
+    /// matching source entry are treated as failed.
+    pub fn from_response(contents: Vec<UserContent>, response: BatchCreateSourcesResponse) -> Self {
+        let mut sources = response.sources.into_iter();
+        let mut result = Self::default();
+        for (index, content) in contents.into_iter().enumerate() {
+            match sources.next() {
+                Some(source) if !source.is_failed() => {
+                    result.succeeded.push(IngestedSource { index, source });
+                }
+                source => result.failed.push(FailedSource {
+                    index,
+                    content,
+                    source,
+                }),
+            }
+        }
+        result
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// Contents of the failed sources, in request order, ready to be submitted again.
+    pub fn failed_contents(&self) -> Vec<UserContent> {
+        self.failed
+            .iter()
+            .map(|failed| failed.content.clone())
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -412,11 +730,39 @@ mod tests {
     }
 
     // Test 3: skip_serializing_if behavior (None fields are omitted)
+    #[test]
+    fn normalize_source_name_trims_and_drops_blank_names() {
+        assert_eq!(normalize_source_name(None).unwrap(), None);
+        assert_eq!(normalize_source_name(Some("")).unwrap(), None);
+        assert_eq!(normalize_source_name(Some(" \t ")).unwrap(), None);
+        assert_eq!(
+            normalize_source_name(Some("  Notes  ")).unwrap(),
+            Some("Notes".to_string())
+        );
+    }
+
+    #[test]
+    fn normalize_source_name_enforces_the_length_limit() {
+        // Counted in characters, not bytes.
+        let at_limit = "é".repeat(MAX_SOURCE_NAME_CHARS);
+        assert_eq!(
+            normalize_source_name(Some(&format!(" {at_limit} "))).unwrap(),
+            Some(at_limit.clone())
+        );
+
+        let err = normalize_source_name(Some(&format!("{at_limit}x"))).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "validation error: source name is 501 characters long (maximum 500)"
+        );
+    }
+
     #[test]
     fn test_web_content_omits_none_source_name() {
         let content = WebContent {
             url: "https://example.com".to_string(),
             source_name: None,
+            fetch_options: None,
         };
 
         let json = serde_json::to_value(&content).unwrap();
@@ -432,6 +778,7 @@ mod tests {
         let content = WebContent {
             url: "https://example.com".to_string(),
             source_name: Some("My Source".to_string()),
+            fetch_options: None,
         };
 
         let json = serde_json::to_value(&content).unwrap();
@@ -593,6 +940,8 @@ mod tests {
     fn test_upload_source_file_response_omits_none_source_id() {
         let response = UploadSourceFileResponse {
             source_id: None,
+            name: None,
+            state: None,
             extra: HashMap::new(),
         };
 
@@ -621,4 +970,245 @@ mod tests {
         assert!(!json.contains("source_added_timestamp"));
         assert!(!json.contains("word_count"));
     }
+
+    fn source_with_status(name: &str, status: &str) -> NotebookSource {
+        NotebookSource {
+            name: name.to_string(),
+            settings: Some(NotebookSourceSettings {
+                status: Some(status.to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn partial_ingest_result_splits_failed_sources() {
+        let contents = vec![
+            UserContent::web("https://a.example".to_string(), None),
+            UserContent::web("https://b.example".to_string(), None),
+            UserContent::text("body".to_string(), None),
+        ];
+        let response = BatchCreateSourcesResponse {
+            sources: vec![
+                source_with_status("s1", "SOURCE_STATUS_COMPLETE"),
+                source_with_status("s2", "SOURCE_STATUS_ERROR"),
+                source_with_status("s3", "SOURCE_STATUS_PENDING"),
+            ],
+            error_count: Some(1),
+        };
+
+        let result = PartialIngestResult::from_response(contents, response);
+
+        assert!(!result.is_complete());
+        let succeeded: Vec<usize> = result.succeeded.iter().map(|s| s.index).collect();
+        assert_eq!(succeeded, vec![0, 2]);
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.failed[0].index, 1);
+        match &result.failed_contents()[0] {
+            UserContent::Web { web_content } => assert_eq!(web_content.url, "https://b.example"),
+            other => panic!("unexpected content: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn partial_ingest_result_marks_missing_sources_failed() {
+        let contents = vec![
+            UserContent::text("one".to_string(), None),
+            UserContent::text("two".to_string(), None),
+        ];
+        let response = BatchCreateSourcesResponse {
+            sources: vec![source_with_status("s1", "SOURCE_STATUS_COMPLETE")],
+            error_count: Some(1),
+        };
+
+        let result = PartialIngestResult::from_response(contents, response);
+
+        assert_eq!(result.succeeded.len(), 1);
+        assert_eq!(result.failed.len(), 1);
+        assert_eq!(result.failed[0].index, 1);
+        assert!(result.failed[0].source.is_none());
+    }
+
+    #[test]
+    fn source_id_prefers_source_id_field() {
+        let mut source = source_with_status(
+            "projects/1/locations/global/notebooks/nb/sources/from-name",
+            "SOURCE_STATUS_COMPLETE",
+        );
+        assert_eq!(source.source_id(), Some("from-name"));
+
+        source.source_id = Some(NotebookSourceId {
+            id: Some("from-id".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(source.source_id(), Some("from-id"));
+    }
+
+    #[test]
+    fn source_id_handles_bare_and_missing_names() {
+        assert_eq!(
+            source_with_status("s1", "SOURCE_STATUS_COMPLETE").source_id(),
+            Some("s1")
+        );
+        assert_eq!(NotebookSource::default().source_id(), None);
+    }
+
+    #[test]
+    fn batch_response_splits_created_and_failed() {
+        let response = BatchCreateSourcesResponse {
+            sources: vec![
+                source_with_status("notebooks/nb/sources/s1", "SOURCE_STATUS_COMPLETE"),
+                source_with_status("notebooks/nb/sources/s2", "SOURCE_STATUS_ERROR"),
+                source_with_status("", "SOURCE_STATUS_PENDING"),
+                source_with_status("notebooks/nb/sources/s4", "SOURCE_STATUS_PENDING"),
+            ],
+            error_count: Some(1),
+        };
+
+        assert_eq!(response.created_ids(), vec!["s1", "s4"]);
+        let failed: Vec<Option<&str>> = response
+            .failed()
+            .iter()
+            .map(|source| source.source_id())
+            .collect();
+        assert_eq!(failed, vec![Some("s2")]);
+    }
+
+    fn source_from(value: serde_json::Value) -> NotebookSource {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn origin_reads_web_urls_from_metadata() {
+        let source = source_from(serde_json::json!({
+            "name": "projects/1/locations/global/notebooks/nb/sources/s1",
+            "metadata": {"wordCount": 10, "webUrl": "https://example.com/post"},
+            "sourceUrl": "https://example.com/ignored",
+        }));
+        assert_eq!(
+            source.origin(),
+            Some(SourceOrigin::Web {
+                url: "https://example.com/post".to_string()
+            })
+        );
+
+        // Nested objects and the rest of the source are searched when the metadata has none.
+        let nested = source_from(serde_json::json!({
+            "name": "s2",
+            "webMetadata": {"title": "x", "uri": "http://example.org/a"},
+        }));
+        assert_eq!(
+            nested.origin().map(|origin| origin.url().to_string()),
+            Some("http://example.org/a".to_string())
+        );
+    }
+
+    #[test]
+    fn origin_recognizes_youtube_sources() {
+        let by_id = source_from(serde_json::json!({
+            "name": "s1",
+            "metadata": {"youtubeMetadata": {"videoId": "abc123", "channelName": "C"}},
+        }));
+        assert_eq!(
+            by_id.origin(),
+            Some(SourceOrigin::Video {
+                url: "https://www.youtube.com/watch?v=abc123".to_string()
+            })
+        );
+
+        let by_url = source_from(serde_json::json!({
+            "name": "s2",
+            "metadata": {"url": "https://youtu.be/abc123"},
+        }));
+        let origin = by_url.origin().unwrap();
+        assert!(matches!(origin, SourceOrigin::Video { .. }));
+        assert!(matches!(
+            origin.to_content(Some("ignored".to_string())),
+            UserContent::Video { video_content } if video_content.url == "https://youtu.be/abc123"
+        ));
+    }
+
+    #[test]
+    fn origin_is_none_without_a_url() {
+        let text = source_from(serde_json::json!({
+            "name": "s1",
+            "title": "Notes",
+            "metadata": {"wordCount": 3, "label": "https://not-a-url-key.example"},
+            "thumbnailUrl": "data:image/png;base64,AAAA",
+        }));
+        assert_eq!(text.origin(), None);
+    }
+
+    #[test]
+    fn web_origin_keeps_the_source_name() {
+        let origin = SourceOrigin::Web {
+            url: "https://example.com".to_string(),
+        };
+        match origin.to_content(Some("Example".to_string())) {
+            UserContent::Web { web_content } => {
+                assert_eq!(web_content.url, "https://example.com");
+                assert_eq!(web_content.source_name.as_deref(), Some("Example"));
+            }
+            other => panic!("expected web content, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn source_ref_reads_the_notebook_from_the_name() {
+        let source = NotebookSource {
+            name: "projects/1/locations/global/notebooks/nb1/sources/s1".to_string(),
+            title: Some("Report".to_string()),
+            ..source_with_status("", "SOURCE_STATUS_COMPLETE")
+        };
+        let reference = SourceRef::try_from(&source).unwrap();
+        assert_eq!(
+            reference,
+            SourceRef {
+                source_id: "s1".to_string(),
+                name: "projects/1/locations/global/notebooks/nb1/sources/s1".to_string(),
+                notebook_id: "nb1".to_string(),
+                title: Some("Report".to_string()),
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(&reference).unwrap(),
+            serde_json::json!({
+                "sourceId": "s1",
+                "name": "projects/1/locations/global/notebooks/nb1/sources/s1",
+                "notebookId": "nb1",
+                "title": "Report"
+            })
+        );
+    }
+
+    #[test]
+    fn source_ref_in_a_known_notebook_completes_bare_names() {
+        let parent = "projects/1/locations/global";
+        let source = source_with_status("s1", "SOURCE_STATUS_COMPLETE");
+        let reference = SourceRef::in_notebook(parent, "nb1", &source).unwrap();
+        assert_eq!(reference.source_id, "s1");
+        assert_eq!(
+            reference.name,
+            "projects/1/locations/global/notebooks/nb1/sources/s1"
+        );
+        assert_eq!(reference.notebook_id, "nb1");
+        assert_eq!(reference.title, None);
+    }
+
+    #[test]
+    fn source_ref_needs_an_id_and_a_notebook() {
+        let err =
+            SourceRef::try_from(source_with_status("s1", "SOURCE_STATUS_COMPLETE")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "validation error: source name 's1' does not say which notebook it belongs to"
+        );
+        let err = SourceRef::in_notebook("projects/1/locations/global", "nb1", &Default::default())
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "validation error: source in notebook nb1 has neither a sourceId nor a name"
+        );
+    }
 }
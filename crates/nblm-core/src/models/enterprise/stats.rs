@@ -0,0 +1,122 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::notebook::{Notebook, NotebookRef};
+
+/// Number of sources in one notebook, within [`SourceCounts`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NotebookSourceCount {
+    pub notebook_id: String,
+    pub title: String,
+    pub sources: usize,
+}
+
+/// Source counts for a set of notebooks, most sources first.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct SourceCounts {
+    pub notebooks: Vec<NotebookSourceCount>,
+    pub total_sources: usize,
+}
+
+impl SourceCounts {
+    /// Count the sources of each notebook. A notebook that appears more than once (as it can
+    /// across pages of a listing that changes while it is read) is counted once, and notebooks
+    /// without an ID are skipped. Ties are ordered by title, then ID.
+    pub fn from_notebooks<'a>(notebooks: impl IntoIterator<Item = &'a Notebook>) -> Self {
+        Self::from_refs(notebooks.into_iter().filter_map(Self::entry))
+    }
+
+    /// [`SourceCounts::from_notebooks`] over notebooks already reduced by
+    /// [`SourceCounts::entry`], so a caller reading many pages keeps no sources between them.
+    pub fn from_refs(entries: impl IntoIterator<Item = (NotebookRef, usize)>) -> Self {
+        let mut seen = HashSet::new();
+        let mut counts: Vec<NotebookSourceCount> = entries
+            .into_iter()
+            .filter(|(notebook, _)| seen.insert(notebook.notebook_id.clone()))
+            .map(|(notebook, sources)| NotebookSourceCount {
+                notebook_id: notebook.notebook_id,
+                title: notebook.title,
+                sources,
+            })
+            .collect();
+        counts.sort_by(|a, b| {
+            b.sources
+                .cmp(&a.sources)
+                .then_with(|| a.title.cmp(&b.title))
+                .then_with(|| a.notebook_id.cmp(&b.notebook_id))
+        });
+        Self {
+            total_sources: counts.iter().map(|count| count.sources).sum(),
+            notebooks: counts,
+        }
+    }
+
+    /// `notebook`'s reference and number of sources, or `None` when it has no ID.
+    pub fn entry(notebook: &Notebook) -> Option<(NotebookRef, usize)> {
+        NotebookRef::try_from(notebook)
+            .ok()
+            .map(|reference| (reference, notebook.sources.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::enterprise::source::NotebookSource;
+
+    fn notebook(id: &str, title: &str, sources: usize) -> Notebook {
+        Notebook {
+            name: Some(format!("projects/1/locations/global/notebooks/{id}")),
+            title: title.to_string(),
+            sources: (0..sources)
+                .map(|index| NotebookSource {
+                    name: format!("projects/1/locations/global/notebooks/{id}/sources/s{index}"),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn counts_are_deduplicated_and_sorted_by_count_then_title() {
+        let notebooks = [
+            notebook("a", "Alpha", 1),
+            notebook("b", "Beta", 3),
+            notebook("c", "Gamma", 3),
+            notebook("d", "Delta", 0),
+            notebook("b", "Beta", 3),
+            Notebook::default(),
+        ];
+        let counts = SourceCounts::from_notebooks(&notebooks);
+        let order: Vec<(&str, usize)> = counts
+            .notebooks
+            .iter()
+            .map(|count| (count.notebook_id.as_str(), count.sources))
+            .collect();
+        assert_eq!(order, [("b", 3), ("c", 3), ("a", 1), ("d", 0)]);
+        assert_eq!(counts.total_sources, 7);
+    }
+
+    #[test]
+    fn pages_of_ten_thousand_notebooks_reduce_to_refs() {
+        // `from_refs` only ever sees a `NotebookRef` and a count, so no source list outlives
+        // the page it arrived on.
+        let mut entries = Vec::new();
+        for page in 0..20 {
+            let notebooks: Vec<Notebook> = (0..500)
+                .map(|index| notebook(&format!("nb-{page}-{index}"), "T", index % 7))
+                .collect();
+            entries.extend(notebooks.iter().filter_map(SourceCounts::entry));
+        }
+        assert_eq!(entries.len(), 10_000);
+        let counts = SourceCounts::from_refs(entries);
+        assert_eq!(counts.notebooks.len(), 10_000);
+        assert_eq!(
+            counts.total_sources,
+            20 * (0..500).map(|index| index % 7).sum::<usize>()
+        );
+        assert_eq!(counts.notebooks[0].sources, 6);
+    }
+}
@@ -0,0 +1,804 @@
+//! In-memory stand-in for the NotebookLM API, for tests of code built on [`NblmClient`]
+//! (`test-util` feature).
+//!
+//! [`InMemoryBackend`] keeps notebooks, sources and audio overviews in process memory and
+//! [`NblmClient::with_backends_for_testing`] routes the client's typed notebook, source and audio
+//! methods to it, so tests need no mock server and no token. Behaviour is deliberately simple:
+//! creating a notebook stores it, listing returns what is stored (most recently touched first),
+//! adding sources appends them, and deletes remove them. IDs are sequential (`notebook-1`,
+//! `source-1`, ...), every source is ingested at once and an audio overview is complete as soon
+//! as it is created. Unknown IDs fail with the API's 404 error, and field masks are ignored.
+//!
+//! [`InMemoryApi`] serves the same backend over HTTP, for tests that cannot hand a client over,
+//! such as ones that run the CLI binary.
+//!
+//! Methods that do not go through the typed backends ([`NblmClient::raw_request`],
+//! [`NblmClient::download_artifact`]) still send HTTP requests, and notebook queries fail with
+//! [`Error::Unavailable`].
+//!
+//! ```ignore
+//! use nblm_core::models::enterprise::source::UserContent;
+//! use nblm_core::test_support::InMemoryBackend;
+//! use nblm_core::{EnvironmentConfig, NblmClient};
+//!
+//! #[tokio::test]
+//! async fn archive_job_removes_empty_notebooks() {
+//!     let environment = EnvironmentConfig::enterprise("123", "global", "us").unwrap();
+//!     let backend = InMemoryBackend::new(environment);
+//!     backend.insert_notebook("empty", "Nothing here");
+//!     backend.insert_notebook("full", "Research");
+//!     let client = NblmClient::with_backends_for_testing(backend.clone());
+//!     client
+//!         .add_sources("full", vec![UserContent::text("notes".into(), None)])
+//!         .await
+//!         .unwrap();
+//!
+//!     my_crate::archive_empty_notebooks(&client).await.unwrap();
+//!
+//!     assert!(backend.notebook("empty").is_none());
+//!     assert!(backend.notebook("full").is_some());
+//! }
+//! ```
+
+#[doc(hidden)]
+pub mod doctest;
+pub mod http;
+
+pub use self::http::InMemoryApi;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use parking_lot::Mutex;
+use reqwest::StatusCode;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::client::api::backends::{
+    AudioBackend, ClientBackends, NotebooksBackend, SourcesBackend,
+};
+use crate::client::stream::JsonArrayStream;
+use crate::client::{NblmClient, ResponseEnvelope};
+use crate::env::EnvironmentConfig;
+use crate::error::{Error, Result};
+use crate::models::enterprise::{
+    audio::{AudioOverviewRequest, AudioOverviewResponse},
+    name::resource_id,
+    notebook::{
+        BatchDeleteNotebooksRequest, BatchDeleteNotebooksResponse, ListRecentlyViewedResponse,
+        Notebook,
+    },
+    query::{QueryRequest, QueryResponse},
+    source::{
+        BatchCreateSourcesRequest, BatchCreateSourcesResponse, BatchDeleteSourcesRequest,
+        BatchDeleteSourcesResponse, NotebookSource, NotebookSourceId, NotebookSourceSettings,
+        UploadSourceFileResponse, UserContent,
+    },
+};
+
+/// Status given to every source added to an [`InMemoryBackend`].
+pub const SOURCE_COMPLETE: &str = "SOURCE_STATUS_COMPLETE";
+
+/// Status given to every audio overview created in an [`InMemoryBackend`].
+pub const AUDIO_COMPLETE: &str = "AUDIO_OVERVIEW_STATUS_COMPLETE";
+
+/// Notebooks, sources and audio overviews held in memory; see the [module docs](self).
+///
+/// Clones share the same state, so a test can keep one clone to inspect what the client did.
+#[derive(Clone)]
+pub struct InMemoryBackend {
+    environment: EnvironmentConfig,
+    state: Arc<Mutex<State>>,
+}
+
+#[derive(Default)]
+struct State {
+    notebooks: HashMap<String, Notebook>,
+    /// Notebook IDs, most recently created, updated or read first.
+    recent: Vec<String>,
+    audio: HashMap<String, AudioOverviewResponse>,
+    next_notebook: u64,
+    next_source: u64,
+}
+
+impl State {
+    fn touch(&mut self, notebook_id: &str) {
+        self.recent.retain(|id| id != notebook_id);
+        self.recent.insert(0, notebook_id.to_string());
+    }
+
+    fn notebook_mut(&mut self, notebook_id: &str) -> Result<&mut Notebook> {
+        self.notebooks
+            .get_mut(notebook_id)
+            .ok_or_else(|| not_found(format!("Notebook {notebook_id} not found")))
+    }
+}
+
+impl InMemoryBackend {
+    /// An empty backend; resource names are built from `environment`'s parent path.
+    pub fn new(environment: EnvironmentConfig) -> Self {
+        Self {
+            environment,
+            state: Arc::default(),
+        }
+    }
+
+    /// Store a notebook with a chosen ID, as if it had been created earlier.
+    pub fn insert_notebook(&self, notebook_id: &str, title: &str) -> Notebook {
+        let notebook = self.new_notebook(notebook_id, title.to_string(), None);
+        let mut state = self.state.lock();
+        state
+            .notebooks
+            .insert(notebook_id.to_string(), notebook.clone());
+        state.touch(notebook_id);
+        notebook
+    }
+
+    /// Every stored notebook with its sources, most recently touched first.
+    pub fn notebooks(&self) -> Vec<Notebook> {
+        let state = self.state.lock();
+        state
+            .recent
+            .iter()
+            .filter_map(|id| state.notebooks.get(id).cloned())
+            .collect()
+    }
+
+    /// The stored notebook with this ID, with its sources.
+    pub fn notebook(&self, notebook_id: &str) -> Option<Notebook> {
+        self.state.lock().notebooks.get(notebook_id).cloned()
+    }
+
+    /// The notebook's audio overview, if one was created.
+    pub fn audio_overview(&self, notebook_id: &str) -> Option<AudioOverviewResponse> {
+        self.state.lock().audio.get(notebook_id).cloned()
+    }
+
+    fn notebook_name(&self, notebook_id: &str) -> String {
+        format!("{}/notebooks/{notebook_id}", self.environment.parent_path())
+    }
+
+    fn new_notebook(&self, notebook_id: &str, title: String, emoji: Option<String>) -> Notebook {
+        Notebook {
+            name: Some(self.notebook_name(notebook_id)),
+            title,
+            notebook_id: Some(notebook_id.to_string()),
+            emoji,
+            ..Notebook::default()
+        }
+    }
+
+    fn new_source(&self, state: &mut State, notebook_id: &str, title: String) -> NotebookSource {
+        state.next_source += 1;
+        let source_id = format!("source-{}", state.next_source);
+        NotebookSource {
+            name: format!("{}/sources/{source_id}", self.notebook_name(notebook_id)),
+            title: Some(title),
+            source_id: Some(NotebookSourceId {
+                id: Some(source_id),
+                extra: HashMap::new(),
+            }),
+            settings: Some(NotebookSourceSettings {
+                status: Some(SOURCE_COMPLETE.to_string()),
+                extra: HashMap::new(),
+            }),
+            metadata: None,
+            extra: HashMap::new(),
+        }
+    }
+
+    /// One page of [`InMemoryBackend::notebooks`]; page tokens are offsets.
+    fn page(
+        &self,
+        page_size: Option<u32>,
+        page_token: Option<&str>,
+    ) -> Result<ListRecentlyViewedResponse> {
+        let offset = match page_token {
+            Some(token) => token.parse::<usize>().map_err(|_| {
+                Error::http(
+                    StatusCode::BAD_REQUEST,
+                    error_body(400, "Invalid page token", "INVALID_ARGUMENT"),
+                )
+            })?,
+            None => 0,
+        };
+        let notebooks = self.notebooks();
+        let end = match page_size {
+            Some(size) if size > 0 => (offset + size as usize).min(notebooks.len()),
+            _ => notebooks.len(),
+        };
+        let next_page_token = (end < notebooks.len()).then(|| end.to_string());
+        Ok(ListRecentlyViewedResponse {
+            notebooks: notebooks.get(offset..end).unwrap_or_default().to_vec(),
+            next_page_token,
+        })
+    }
+}
+
+impl NblmClient {
+    /// A client whose typed notebook, source and audio calls are served by `backend` instead of
+    /// the API. See [`test_support`](crate::test_support).
+    pub fn with_backends_for_testing(backend: InMemoryBackend) -> Self {
+        let environment = backend.environment.clone();
+        let backend = Arc::new(backend);
+        let token_provider = Arc::new(crate::auth::StaticTokenProvider::new("test-token"));
+        let mut client =
+            Self::new(token_provider, environment).expect("the default HTTP client builds");
+        client.backends = ClientBackends::fixed(
+            Arc::clone(&backend) as Arc<dyn NotebooksBackend>,
+            Arc::clone(&backend) as Arc<dyn SourcesBackend>,
+            backend as Arc<dyn AudioBackend>,
+        );
+        client
+    }
+}
+
+#[async_trait]
+impl NotebooksBackend for InMemoryBackend {
+    async fn create_notebook(&self, title: String, emoji: Option<String>) -> Result<Notebook> {
+        let mut state = self.state.lock();
+        state.next_notebook += 1;
+        let notebook_id = format!("notebook-{}", state.next_notebook);
+        let notebook = self.new_notebook(&notebook_id, title, emoji);
+        state
+            .notebooks
+            .insert(notebook_id.clone(), notebook.clone());
+        state.touch(&notebook_id);
+        Ok(notebook)
+    }
+
+    async fn update_notebook(
+        &self,
+        notebook_id: &str,
+        title: Option<String>,
+        emoji: Option<String>,
+    ) -> Result<Notebook> {
+        let mut state = self.state.lock();
+        let notebook = state.notebook_mut(notebook_id)?;
+        if let Some(title) = title {
+            notebook.title = title;
+        }
+        if let Some(emoji) = emoji {
+            notebook.emoji = Some(emoji);
+        }
+        let notebook = notebook.clone();
+        state.touch(notebook_id);
+        Ok(notebook)
+    }
+
+    async fn get_notebook(&self, notebook_id: &str, _field_mask: Option<&str>) -> Result<Notebook> {
+        let mut state = self.state.lock();
+        let notebook = state.notebook_mut(notebook_id)?.clone();
+        state.touch(notebook_id);
+        Ok(notebook)
+    }
+
+    async fn batch_delete_notebooks(
+        &self,
+        request: BatchDeleteNotebooksRequest,
+    ) -> Result<BatchDeleteNotebooksResponse> {
+        let mut state = self.state.lock();
+        let ids: Vec<String> = request
+            .names
+            .iter()
+            .map(|name| resource_id(name).unwrap_or_default().to_string())
+            .collect();
+        if let Some(missing) = ids.iter().find(|id| !state.notebooks.contains_key(*id)) {
+            return Err(not_found(format!("Notebook {missing} not found")));
+        }
+        for id in &ids {
+            state.notebooks.remove(id);
+            state.audio.remove(id);
+            state.recent.retain(|recent| recent != id);
+        }
+        Ok(BatchDeleteNotebooksResponse::default())
+    }
+
+    async fn delete_notebooks(
+        &self,
+        notebook_names: Vec<String>,
+    ) -> Result<BatchDeleteNotebooksResponse> {
+        for name in notebook_names {
+            self.batch_delete_notebooks(BatchDeleteNotebooksRequest { names: vec![name] })
+                .await?;
+        }
+        Ok(BatchDeleteNotebooksResponse::default())
+    }
+
+    async fn list_recently_viewed(
+        &self,
+        page_size: Option<u32>,
+        page_token: Option<&str>,
+        _field_mask: Option<&str>,
+    ) -> Result<ResponseEnvelope<ListRecentlyViewedResponse>> {
+        envelope(self.page(page_size, page_token)?)
+    }
+
+    async fn stream_recently_viewed_page(
+        &self,
+        page_size: Option<u32>,
+        page_token: Option<&str>,
+    ) -> Result<JsonArrayStream<Notebook>> {
+        let page = self.page(page_size, page_token)?;
+        let mut rest = Map::new();
+        if let Some(token) = page.next_page_token {
+            rest.insert("nextPageToken".to_string(), Value::String(token));
+        }
+        Ok(JsonArrayStream::from_items(page.notebooks, rest))
+    }
+
+    async fn query_notebook(
+        &self,
+        _notebook_id: &str,
+        _request: QueryRequest,
+    ) -> Result<QueryResponse> {
+        Err(Error::Unavailable {
+            feature: "notebook query",
+            detail: "InMemoryBackend does not answer queries".to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl SourcesBackend for InMemoryBackend {
+    async fn batch_create_sources(
+        &self,
+        notebook_id: &str,
+        request: BatchCreateSourcesRequest,
+    ) -> Result<BatchCreateSourcesResponse> {
+        let mut state = self.state.lock();
+        state.notebook_mut(notebook_id)?;
+        let sources: Vec<NotebookSource> = request
+            .user_contents
+            .iter()
+            .map(|content| self.new_source(&mut state, notebook_id, content_title(content)))
+            .collect();
+        state
+            .notebook_mut(notebook_id)?
+            .sources
+            .extend(sources.iter().cloned());
+        state.touch(notebook_id);
+        Ok(BatchCreateSourcesResponse {
+            sources,
+            error_count: Some(0),
+        })
+    }
+
+    async fn add_sources(
+        &self,
+        notebook_id: &str,
+        contents: Vec<UserContent>,
+    ) -> Result<ResponseEnvelope<BatchCreateSourcesResponse>> {
+        let request = BatchCreateSourcesRequest {
+            user_contents: contents,
+        };
+        envelope(self.batch_create_sources(notebook_id, request).await?)
+    }
+
+    async fn batch_delete_sources(
+        &self,
+        notebook_id: &str,
+        request: BatchDeleteSourcesRequest,
+    ) -> Result<BatchDeleteSourcesResponse> {
+        let mut state = self.state.lock();
+        let notebook = state.notebook_mut(notebook_id)?;
+        if let Some(missing) = request
+            .names
+            .iter()
+            .find(|name| !notebook.sources.iter().any(|source| &source.name == *name))
+        {
+            return Err(not_found(format!("Source {missing} not found")));
+        }
+        notebook
+            .sources
+            .retain(|source| !request.names.contains(&source.name));
+        Ok(BatchDeleteSourcesResponse::default())
+    }
+
+    async fn delete_sources(
+        &self,
+        notebook_id: &str,
+        source_names: Vec<String>,
+    ) -> Result<BatchDeleteSourcesResponse> {
+        self.batch_delete_sources(
+            notebook_id,
+            BatchDeleteSourcesRequest {
+                names: source_names,
+            },
+        )
+        .await
+    }
+
+    async fn upload_source_file(
+        &self,
+        notebook_id: &str,
+        file_name: &str,
+        _content_type: &str,
+        data: Vec<u8>,
+    ) -> Result<UploadSourceFileResponse> {
+        if data.is_empty() {
+            return Err(Error::validation("cannot upload empty files"));
+        }
+        let mut state = self.state.lock();
+        state.notebook_mut(notebook_id)?;
+        let source = self.new_source(&mut state, notebook_id, file_name.to_string());
+        state
+            .notebook_mut(notebook_id)?
+            .sources
+            .push(source.clone());
+        state.touch(notebook_id);
+        Ok(UploadSourceFileResponse {
+            source_id: source.source_id,
+            name: Some(source.name),
+            state: Some(SOURCE_COMPLETE.to_string()),
+            extra: HashMap::new(),
+        })
+    }
+
+    async fn get_source(
+        &self,
+        notebook_id: &str,
+        source_id: &str,
+    ) -> Result<ResponseEnvelope<NotebookSource>> {
+        let mut state = self.state.lock();
+        let source = state
+            .notebook_mut(notebook_id)?
+            .sources
+            .iter()
+            .find(|source| source.source_id() == Some(source_id))
+            .cloned()
+            .ok_or_else(|| not_found(format!("Source {source_id} not found")))?;
+        envelope(source)
+    }
+}
+
+#[async_trait]
+impl AudioBackend for InMemoryBackend {
+    async fn create_audio_overview(
+        &self,
+        notebook_id: &str,
+        _request: AudioOverviewRequest,
+    ) -> Result<AudioOverviewResponse> {
+        let mut state = self.state.lock();
+        if state.notebook_mut(notebook_id)?.sources.is_empty() {
+            return Err(Error::http(
+                StatusCode::BAD_REQUEST,
+                error_body(400, "Notebook has no sources", "FAILED_PRECONDITION"),
+            ));
+        }
+        if state.audio.contains_key(notebook_id) {
+            return Err(Error::http(
+                StatusCode::CONFLICT,
+                error_body(409, "Audio overview already exists", "ALREADY_EXISTS"),
+            ));
+        }
+        let overview = AudioOverviewResponse {
+            audio_overview_id: Some("default".to_string()),
+            name: Some(format!(
+                "{}/audioOverviews/default",
+                self.notebook_name(notebook_id)
+            )),
+            status: Some(AUDIO_COMPLETE.to_string()),
+            ..AudioOverviewResponse::default()
+        };
+        state
+            .audio
+            .insert(notebook_id.to_string(), overview.clone());
+        Ok(overview)
+    }
+
+    async fn get_audio_overview(&self, notebook_id: &str) -> Result<AudioOverviewResponse> {
+        let mut state = self.state.lock();
+        state.notebook_mut(notebook_id)?;
+        state
+            .audio
+            .get(notebook_id)
+            .cloned()
+            .ok_or_else(|| not_found(format!("Notebook {notebook_id} has no audio overview")))
+    }
+
+    async fn delete_audio_overview(&self, notebook_id: &str) -> Result<()> {
+        let mut state = self.state.lock();
+        state.notebook_mut(notebook_id)?;
+        state
+            .audio
+            .remove(notebook_id)
+            .map(drop)
+            .ok_or_else(|| not_found(format!("Notebook {notebook_id} has no audio overview")))
+    }
+}
+
+/// Title the API would give a new source: its name, else the URL or document ID.
+fn content_title(content: &UserContent) -> String {
+    match content {
+        UserContent::Web { web_content } => web_content
+            .source_name
+            .clone()
+            .unwrap_or_else(|| web_content.url.clone()),
+        UserContent::Text { text_content } => text_content
+            .source_name
+            .clone()
+            .unwrap_or_else(|| "Pasted text".to_string()),
+        UserContent::GoogleDrive {
+            google_drive_content,
+        } => google_drive_content
+            .source_name
+            .clone()
+            .unwrap_or_else(|| google_drive_content.document_id.clone()),
+        UserContent::Video { video_content } => video_content.url.clone(),
+    }
+}
+
+/// `value` with its JSON serialization as the raw body.
+fn envelope<T: Serialize>(value: T) -> Result<ResponseEnvelope<T>> {
+    let raw = Bytes::from(serde_json::to_vec(&value)?);
+    Ok(ResponseEnvelope::new(value, raw))
+}
+
+fn error_body(code: u16, message: &str, status: &str) -> String {
+    json!({ "error": { "code": code, "message": message, "status": status } }).to_string()
+}
+
+fn not_found(message: String) -> Error {
+    Error::http(
+        StatusCode::NOT_FOUND,
+        error_body(404, &message, "NOT_FOUND"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+
+    use super::*;
+    use crate::models::enterprise::source::PartialIngestResult;
+
+    fn backend() -> InMemoryBackend {
+        InMemoryBackend::new(EnvironmentConfig::enterprise("123", "global", "us").unwrap())
+    }
+
+    fn status(err: &Error) -> Option<StatusCode> {
+        match err {
+            Error::Http { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn created_notebooks_are_listed_most_recent_first() {
+        let backend = backend();
+        let client = NblmClient::with_backends_for_testing(backend.clone());
+
+        let first = client.create_notebook("First").await.unwrap();
+        let second = client
+            .create_notebook_with_emoji("Second", Some("📚".to_string()))
+            .await
+            .unwrap();
+
+        assert_eq!(first.notebook_id.as_deref(), Some("notebook-1"));
+        assert_eq!(
+            first.name.as_deref(),
+            Some("projects/123/locations/global/notebooks/notebook-1")
+        );
+        assert_eq!(second.emoji.as_deref(), Some("📚"));
+        let listed = client.list_recently_viewed(None).await.unwrap();
+        let titles: Vec<_> = listed
+            .notebooks
+            .iter()
+            .map(|nb| nb.title.as_str())
+            .collect();
+        assert_eq!(titles, ["Second", "First"]);
+
+        // Reading a notebook makes it the most recently viewed.
+        client.get_notebook("notebook-1").await.unwrap();
+        let titles: Vec<_> = backend.notebooks().into_iter().map(|nb| nb.title).collect();
+        assert_eq!(titles, ["First", "Second"]);
+    }
+
+    #[tokio::test]
+    async fn listing_pages_and_streams_through_every_notebook() {
+        let backend = backend();
+        for id in ["a", "b", "c"] {
+            backend.insert_notebook(id, &id.to_uppercase());
+        }
+        let client = NblmClient::with_backends_for_testing(backend);
+
+        let page = client.list_recently_viewed(Some(2)).await.unwrap();
+        assert_eq!(page.notebooks.len(), 2);
+        assert_eq!(page.next_page_token.as_deref(), Some("2"));
+        let raw = client.list_recently_viewed_raw(Some(2)).await.unwrap();
+        let raw: Value = serde_json::from_slice(raw.raw()).unwrap();
+        assert_eq!(raw["nextPageToken"], "2");
+
+        let streamed: Vec<_> = client
+            .stream_recently_viewed(Some(2))
+            .map(|notebook| notebook.unwrap().title)
+            .collect()
+            .await;
+        assert_eq!(streamed, ["C", "B", "A"]);
+    }
+
+    #[tokio::test]
+    async fn updates_change_only_the_given_fields() {
+        let backend = backend();
+        backend.insert_notebook("nb1", "Draft");
+        let client = NblmClient::with_backends_for_testing(backend.clone());
+
+        let updated = client
+            .update_notebook("nb1", None, Some("🎧".to_string()))
+            .await
+            .unwrap();
+
+        assert_eq!(updated.title, "Draft");
+        assert_eq!(updated.emoji.as_deref(), Some("🎧"));
+        assert_eq!(
+            backend.notebook("nb1").unwrap().emoji.as_deref(),
+            Some("🎧")
+        );
+    }
+
+    #[tokio::test]
+    async fn sources_are_appended_read_and_deleted() {
+        let backend = backend();
+        backend.insert_notebook("nb1", "Research");
+        let client = NblmClient::with_backends_for_testing(backend.clone());
+
+        let added = client
+            .add_sources(
+                "nb1",
+                vec![
+                    UserContent::web("https://example.com".to_string(), None),
+                    UserContent::text("notes".to_string(), Some("Notes".to_string())),
+                ],
+            )
+            .await
+            .unwrap();
+        let uploaded = client
+            .upload_source_file("nb1", "report.pdf", "application/pdf", b"%PDF".to_vec())
+            .await
+            .unwrap();
+
+        assert_eq!(added.created_ids(), ["source-1", "source-2"]);
+        assert_eq!(
+            uploaded.name.as_deref(),
+            Some("projects/123/locations/global/notebooks/nb1/sources/source-3")
+        );
+        let titles: Vec<_> = backend
+            .notebook("nb1")
+            .unwrap()
+            .sources
+            .into_iter()
+            .map(|source| source.title.unwrap())
+            .collect();
+        assert_eq!(titles, ["https://example.com", "Notes", "report.pdf"]);
+
+        let source = client.get_source("nb1", "source-2").await.unwrap();
+        assert_eq!(source.title.as_deref(), Some("Notes"));
+        assert!(!source.is_failed());
+
+        client
+            .delete_sources("nb1", vec![source.name.clone()])
+            .await
+            .unwrap();
+        let err = client.get_source("nb1", "source-2").await.unwrap_err();
+        assert_eq!(status(&err), Some(StatusCode::NOT_FOUND));
+        assert_eq!(backend.notebook("nb1").unwrap().sources.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn partial_ingest_reports_every_source_as_created() {
+        let backend = backend();
+        backend.insert_notebook("nb1", "Research");
+        let client = NblmClient::with_backends_for_testing(backend);
+
+        let PartialIngestResult { succeeded, failed } = client
+            .add_sources_partial(
+                "nb1",
+                vec![UserContent::video("https://youtu.be/x".to_string())],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(succeeded.len(), 1);
+        assert!(failed.is_empty());
+    }
+
+    #[tokio::test]
+    async fn deleting_notebooks_removes_them_and_unknown_ids_fail() {
+        let backend = backend();
+        backend.insert_notebook("nb1", "One");
+        backend.insert_notebook("nb2", "Two");
+        let client = NblmClient::with_backends_for_testing(backend.clone());
+
+        client
+            .delete_notebooks(vec![
+                "projects/123/locations/global/notebooks/nb1".to_string()
+            ])
+            .await
+            .unwrap();
+        assert!(backend.notebook("nb1").is_none());
+
+        let err = client
+            .delete_notebooks(vec![
+                "projects/123/locations/global/notebooks/nb2".to_string(),
+                "projects/123/locations/global/notebooks/missing".to_string(),
+            ])
+            .await
+            .unwrap_err();
+        assert_eq!(status(&err), Some(StatusCode::NOT_FOUND));
+        assert!(
+            err.to_string().contains("Notebook missing not found"),
+            "{err}"
+        );
+
+        let err = client.get_notebook("nb1").await.unwrap_err();
+        assert_eq!(status(&err), Some(StatusCode::NOT_FOUND));
+    }
+
+    #[tokio::test]
+    async fn audio_overviews_need_sources_and_are_unique() {
+        let backend = backend();
+        backend.insert_notebook("nb1", "Podcast");
+        let client = NblmClient::with_backends_for_testing(backend.clone());
+
+        let err = client
+            .create_audio_overview("nb1", AudioOverviewRequest::default())
+            .await
+            .unwrap_err();
+        assert_eq!(status(&err), Some(StatusCode::BAD_REQUEST));
+
+        client
+            .add_sources("nb1", vec![UserContent::text("t".to_string(), None)])
+            .await
+            .unwrap();
+        let created = client
+            .create_audio_overview("nb1", AudioOverviewRequest::default())
+            .await
+            .unwrap();
+        assert!(created.is_ready());
+        assert!(client.get_audio_overview("nb1").await.unwrap().is_ready());
+        let err = client
+            .create_audio_overview("nb1", AudioOverviewRequest::default())
+            .await
+            .unwrap_err();
+        assert_eq!(status(&err), Some(StatusCode::CONFLICT));
+
+        client.delete_audio_overview("nb1").await.unwrap();
+        assert!(backend.audio_overview("nb1").is_none());
+        let err = client.delete_audio_overview("nb1").await.unwrap_err();
+        assert_eq!(status(&err), Some(StatusCode::NOT_FOUND));
+    }
+
+    #[tokio::test]
+    async fn queries_are_unavailable() {
+        let backend = backend();
+        backend.insert_notebook("nb1", "Research");
+        let client = NblmClient::with_backends_for_testing(backend);
+
+        let err = client
+            .query_notebook(
+                "nb1",
+                QueryRequest {
+                    query: "what?".to_string(),
+                    source_ids: None,
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Unavailable { .. }), "{err}");
+    }
+
+    #[tokio::test]
+    async fn builder_methods_keep_the_in_memory_backends() {
+        let backend = backend();
+        backend.insert_notebook("nb1", "Kept");
+        let client = NblmClient::with_backends_for_testing(backend)
+            .with_retry_config(crate::RetryConfig::disabled())
+            .with_base_url("http://127.0.0.1:9/v1alpha")
+            .unwrap();
+
+        assert_eq!(client.get_notebook("nb1").await.unwrap().title, "Kept");
+    }
+}
@@ -0,0 +1,101 @@
+//! Mock NotebookLM API behind the runnable doc examples.
+//!
+//! Not part of the supported API: the responses are just enough for the examples to run.
+
+use std::sync::Arc;
+
+use serde_json::json;
+use wiremock::matchers::{method, path_regex, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use crate::{EnvironmentConfig, NblmClient, Result, StaticTokenProvider};
+
+const PARENT: &str = "projects/123456/locations/global";
+
+/// A running mock server; keep it alive for as long as the client is used.
+pub struct DocApi {
+    server: MockServer,
+}
+
+impl DocApi {
+    /// Base URL to pass to `with_base_url`.
+    pub fn base_url(&self) -> String {
+        format!("{}/v1alpha", self.server.uri())
+    }
+}
+
+/// Start a mock API answering the calls made in the examples:
+///
+/// - creating a notebook returns `nb-1` titled "Research",
+/// - adding sources and uploading a file each return one source,
+/// - recently viewed notebooks come in two pages (`nb-1`, `nb-2`, then `nb-3`),
+/// - deleting notebooks succeeds.
+pub async fn mock_api() -> DocApi {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path_regex(r"/notebooks$"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(notebook("nb-1", "Research")))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path_regex(r"/sources:batchCreate$"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "sources": [{
+                "name": format!("{PARENT}/notebooks/nb-1/sources/src-1"),
+                "sourceId": { "id": "src-1" },
+                "title": "Example Domain",
+            }]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path_regex(r"/sources:uploadFile$"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "sourceId": { "id": "src-2" },
+            "name": format!("{PARENT}/notebooks/nb-1/sources/src-2"),
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path_regex(r"/notebooks:listRecentlyViewed$"))
+        .and(query_param("pageToken", "page-2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "notebooks": [notebook("nb-3", "Archive")],
+        })))
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path_regex(r"/notebooks:listRecentlyViewed$"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "notebooks": [notebook("nb-1", "Research"), notebook("nb-2", "Reading list")],
+            "nextPageToken": "page-2",
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path_regex(r"/notebooks:batchDelete$"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+        .mount(&server)
+        .await;
+
+    DocApi { server }
+}
+
+/// [`mock_api`] with a client for project `123456` pointed at it, as the examples build it.
+pub async fn client() -> Result<(DocApi, NblmClient)> {
+    let api = mock_api().await;
+    let environment = EnvironmentConfig::enterprise("123456", "global", "us")?;
+    let client = NblmClient::new(Arc::new(StaticTokenProvider::new("token")), environment)?
+        .with_base_url(api.base_url())?;
+    Ok((api, client))
+}
+
+fn notebook(notebook_id: &str, title: &str) -> serde_json::Value {
+    json!({
+        "name": format!("{PARENT}/notebooks/{notebook_id}"),
+        "notebookId": notebook_id,
+        "title": title,
+    })
+}
@@ -0,0 +1,287 @@
+//! [`InMemoryBackend`] behind an HTTP server, for tests that run the real HTTP stack (or a
+//! separate binary, such as the CLI) against stateful fake data.
+//!
+//! [`InMemoryApi`] answers the enterprise REST routes the typed client methods call, each
+//! project and location with its own backend, so tests sharing one server stay apart by using
+//! different project numbers. Requests without an `Authorization` header get a 401; anything
+//! else the backend has no route for gets the API's 404.
+//!
+//! ```ignore
+//! let api = InMemoryApi::new();
+//! let server = api.serve().await;
+//! api.backend("123456", "global").insert_notebook("nb1", "Research");
+//! // point the client at format!("{}/v1alpha", server.uri())
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use futures::executor::block_on;
+use parking_lot::Mutex;
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use wiremock::matchers::any;
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+use super::{error_body, InMemoryBackend};
+use crate::client::api::backends::{AudioBackend, NotebooksBackend, SourcesBackend};
+use crate::env::EnvironmentConfig;
+use crate::error::{Error, Result};
+use crate::models::enterprise::{
+    audio::AudioOverviewRequest,
+    notebook::BatchDeleteNotebooksRequest,
+    source::{BatchCreateSourcesRequest, BatchDeleteSourcesRequest},
+};
+
+/// In-memory NotebookLM API served over HTTP; see the [module docs](self).
+///
+/// Clones share the same backends.
+#[derive(Clone, Default)]
+pub struct InMemoryApi {
+    /// Backends by parent path (`projects/{number}/locations/{location}`).
+    backends: Arc<Mutex<HashMap<String, InMemoryBackend>>>,
+}
+
+impl InMemoryApi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The backend behind `projects/{project_number}/locations/{location}`, created empty on
+    /// first use. Seed it before a request, or inspect it after one.
+    pub fn backend(&self, project_number: &str, location: &str) -> InMemoryBackend {
+        let parent = format!("projects/{project_number}/locations/{location}");
+        self.backends
+            .lock()
+            .entry(parent)
+            .or_insert_with(|| {
+                let environment = EnvironmentConfig::enterprise(project_number, location, "us")
+                    .expect("project number and location form a valid environment");
+                InMemoryBackend::new(environment)
+            })
+            .clone()
+    }
+
+    /// Start a mock server that sends every request to this API. The API base URL is the
+    /// server's URI followed by `/v1alpha`.
+    pub async fn serve(&self) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(any())
+            .respond_with(self.clone())
+            .mount(&server)
+            .await;
+        server
+    }
+
+    fn handle(&self, request: &Request) -> Result<Value> {
+        let path = request.url.path().trim_start_matches('/');
+        let path = path.strip_prefix("upload/").unwrap_or(path);
+        let segments: Vec<&str> = path.split('/').collect();
+        let ["v1alpha", "projects", project, "locations", location, rest @ ..] =
+            segments.as_slice()
+        else {
+            return Err(no_route(request));
+        };
+        let backend = self.backend(project, location);
+        let method = request.method.as_str();
+        let value = match (method, rest) {
+            ("POST", ["notebooks"]) => {
+                let body: Value = body(request)?;
+                let title = body["title"].as_str().unwrap_or_default().to_string();
+                let emoji = body["emoji"].as_str().map(str::to_string);
+                to_value(block_on(backend.create_notebook(title, emoji))?)
+            }
+            ("GET", ["notebooks:listRecentlyViewed"]) => {
+                let page_size = query(request, "pageSize").and_then(|size| size.parse().ok());
+                let page_token = query(request, "pageToken");
+                let page =
+                    block_on(backend.list_recently_viewed(page_size, page_token.as_deref(), None))?;
+                serde_json::from_slice(page.raw())?
+            }
+            ("POST", ["notebooks:batchDelete"]) => {
+                let request: BatchDeleteNotebooksRequest = body(request)?;
+                to_value(block_on(backend.batch_delete_notebooks(request))?)
+            }
+            ("GET", ["notebooks", notebook_id]) => {
+                to_value(block_on(backend.get_notebook(notebook_id, None))?)
+            }
+            ("PATCH", ["notebooks", notebook_id]) => {
+                let body: Value = body(request)?;
+                let title = body["title"].as_str().map(str::to_string);
+                let emoji = body["emoji"].as_str().map(str::to_string);
+                to_value(block_on(backend.update_notebook(
+                    notebook_id,
+                    title,
+                    emoji,
+                ))?)
+            }
+            ("POST", ["notebooks", notebook_query]) if notebook_query.ends_with(":query") => {
+                let notebook_id = notebook_query.trim_end_matches(":query");
+                let request = body(request)?;
+                to_value(block_on(backend.query_notebook(notebook_id, request))?)
+            }
+            ("POST", ["notebooks", notebook_id, "sources:batchCreate"]) => {
+                let request: BatchCreateSourcesRequest = body(request)?;
+                to_value(block_on(
+                    backend.batch_create_sources(notebook_id, request),
+                )?)
+            }
+            ("POST", ["notebooks", notebook_id, "sources:batchDelete"]) => {
+                let request: BatchDeleteSourcesRequest = body(request)?;
+                to_value(block_on(
+                    backend.batch_delete_sources(notebook_id, request),
+                )?)
+            }
+            ("POST", ["notebooks", notebook_id, "sources:uploadFile"]) => {
+                let header = |name: &str| {
+                    request
+                        .headers
+                        .get(name)
+                        .and_then(|value| value.to_str().ok())
+                        .unwrap_or_default()
+                };
+                to_value(block_on(backend.upload_source_file(
+                    notebook_id,
+                    header("x-goog-upload-file-name"),
+                    header("content-type"),
+                    request.body.clone(),
+                ))?)
+            }
+            ("GET", ["notebooks", notebook_id, "sources", source_id]) => {
+                let source = block_on(backend.get_source(notebook_id, source_id))?;
+                serde_json::from_slice(source.raw())?
+            }
+            ("POST", ["notebooks", notebook_id, "audioOverviews"]) => {
+                let request = AudioOverviewRequest::default();
+                to_value(block_on(
+                    backend.create_audio_overview(notebook_id, request),
+                )?)
+            }
+            ("GET", ["notebooks", notebook_id, "audioOverviews", "default"]) => {
+                to_value(block_on(backend.get_audio_overview(notebook_id))?)
+            }
+            ("DELETE", ["notebooks", notebook_id, "audioOverviews", "default"]) => {
+                block_on(backend.delete_audio_overview(notebook_id))?;
+                json!({})
+            }
+            _ => return Err(no_route(request)),
+        };
+        Ok(value)
+    }
+}
+
+impl Respond for InMemoryApi {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        if !request.headers.contains_key("authorization") {
+            return error_response(Error::http(
+                StatusCode::UNAUTHORIZED,
+                error_body(401, "Request is missing credentials", "UNAUTHENTICATED"),
+            ));
+        }
+        match self.handle(request) {
+            Ok(value) => ResponseTemplate::new(200).set_body_json(value),
+            Err(err) => error_response(err),
+        }
+    }
+}
+
+fn error_response(err: Error) -> ResponseTemplate {
+    let (status, body) = match err {
+        Error::Http { status, body, .. } => (status.as_u16(), body),
+        Error::Validation(message) => (400, error_body(400, &message, "INVALID_ARGUMENT")),
+        Error::Json(err) => (400, error_body(400, &err.to_string(), "INVALID_ARGUMENT")),
+        Error::Unavailable { detail, .. } => (501, error_body(501, &detail, "UNIMPLEMENTED")),
+        other => (500, error_body(500, &other.to_string(), "INTERNAL")),
+    };
+    ResponseTemplate::new(status).set_body_raw(body, "application/json")
+}
+
+fn no_route(request: &Request) -> Error {
+    let message = format!("No route for {} {}", request.method, request.url.path());
+    Error::http(
+        StatusCode::NOT_FOUND,
+        error_body(404, &message, "NOT_FOUND"),
+    )
+}
+
+fn body<T: DeserializeOwned>(request: &Request) -> Result<T> {
+    Ok(serde_json::from_slice(&request.body)?)
+}
+
+fn query(request: &Request, name: &str) -> Option<String> {
+    request
+        .url
+        .query_pairs()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+}
+
+fn to_value(value: impl Serialize) -> Value {
+    serde_json::to_value(value).expect("API models serialize to JSON")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::StaticTokenProvider;
+    use crate::models::enterprise::source::UserContent;
+    use crate::NblmClient;
+
+    fn client(server: &MockServer, project_number: &str) -> NblmClient {
+        let provider = Arc::new(StaticTokenProvider::new("test-token"));
+        let env = EnvironmentConfig::enterprise(project_number, "global", "us").unwrap();
+        NblmClient::new(provider, env)
+            .unwrap()
+            .with_base_url(format!("{}/v1alpha", server.uri()))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn client_round_trips_through_the_shim() {
+        let api = InMemoryApi::new();
+        let server = api.serve().await;
+        let client = client(&server, "111");
+
+        let notebook = client
+            .create_notebook("Research".to_string())
+            .await
+            .unwrap();
+        let notebook_id = notebook.notebook_id.unwrap();
+        client
+            .add_sources(
+                &notebook_id,
+                vec![UserContent::web("https://example.com".to_string(), None)],
+            )
+            .await
+            .unwrap();
+        client
+            .upload_source_file(&notebook_id, "notes.txt", "text/plain", b"notes".to_vec())
+            .await
+            .unwrap();
+
+        let listed = client.list_recently_viewed(None).await.unwrap();
+        assert_eq!(listed.notebooks.len(), 1);
+        assert_eq!(listed.notebooks[0].title, "Research");
+        let stored = api.backend("111", "global").notebook(&notebook_id).unwrap();
+        assert_eq!(stored.sources.len(), 2);
+        assert_eq!(stored.sources[1].title.as_deref(), Some("notes.txt"));
+
+        let err = client.get_notebook("missing").await.unwrap_err();
+        assert!(matches!(err, Error::Http { status, .. } if status == StatusCode::NOT_FOUND));
+    }
+
+    #[tokio::test]
+    async fn projects_do_not_share_notebooks() {
+        let api = InMemoryApi::new();
+        let server = api.serve().await;
+        api.backend("111", "global").insert_notebook("nb1", "Mine");
+
+        let listed = client(&server, "222")
+            .list_recently_viewed(None)
+            .await
+            .unwrap();
+        assert!(listed.notebooks.is_empty());
+    }
+}
@@ -0,0 +1,73 @@
+//! Clients built for each `ApiVersion` send requests under that version's prefix and read the
+//! responses through the same typed models (see `models::enterprise::compat`).
+
+mod common;
+
+use std::path::PathBuf;
+
+use nblm_core::{ApiVersion, EnvironmentConfig, NblmClient};
+use serde_json::Value;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const PARENT: &str = "projects/123456/locations/global";
+
+fn fixture(name: &str) -> Value {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/responses")
+        .join(format!("{name}.json"));
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+fn client_for(version: ApiVersion, server: &MockServer) -> NblmClient {
+    let env = EnvironmentConfig::enterprise("123456", "global", "global")
+        .unwrap()
+        .with_api_version(version);
+    common::client_in(env, &format!("{}/{version}", server.uri()))
+}
+
+#[test]
+fn default_base_urls_end_with_the_version() {
+    for version in ApiVersion::ALL {
+        let env = EnvironmentConfig::enterprise("123456", "global", "us")
+            .unwrap()
+            .with_api_version(*version);
+        assert_eq!(
+            env.base_url(),
+            format!("https://us-discoveryengine.googleapis.com/{version}")
+        );
+    }
+}
+
+#[tokio::test]
+async fn requests_and_uploads_use_the_version_prefix() {
+    for version in ApiVersion::ALL {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/{version}/{PARENT}/notebooks/notebook-1")))
+            .respond_with(ResponseTemplate::new(200).set_body_json(fixture("get_notebook")))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path(format!(
+                "/upload/{version}/{PARENT}/notebooks/notebook-1/sources:uploadFile"
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_json(fixture("upload_source_file")))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let client = client_for(*version, &server);
+
+        let notebook = client.get_notebook("notebook-1").await.unwrap();
+        assert_eq!(notebook.sources.len(), 2, "{version}");
+        assert_eq!(notebook.sources[0].source_id(), Some("source-1"));
+
+        let uploaded = client
+            .upload_source_file("notebook-1", "notes.txt", "text/plain", b"notes".to_vec())
+            .await
+            .unwrap();
+        let source_id = uploaded.source_id.as_ref().unwrap();
+        assert_eq!(source_id.id.as_deref(), Some("source-3"), "{version}");
+    }
+}
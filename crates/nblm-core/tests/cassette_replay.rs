@@ -0,0 +1,142 @@
+//! Replays recorded API traffic through the full `NblmClient`, so the typed models are checked
+//! against real payload shapes.
+//!
+//! To re-record a cassette against the live API (this adds a real source to the notebook):
+//!
+//! ```text
+//! NBLM_CASSETTE_RECORD=1 \
+//! NBLM_ACCESS_TOKEN=$(gcloud auth print-access-token) \
+//! NBLM_PROJECT_NUMBER=... NBLM_CASSETTE_NOTEBOOK_ID=... \
+//!   cargo test -p nblm-core --features cassette --test cassette_replay
+//! ```
+//!
+//! The project number and notebook ID are replaced by the placeholders below before anything is
+//! written.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use nblm_core::client::cassette::{CassetteMode, CassetteTransport};
+use nblm_core::models::enterprise::source::UserContent;
+use nblm_core::{EnvTokenProvider, EnvironmentConfig, NblmClient, StaticTokenProvider};
+
+const PROJECT_NUMBER: &str = "123456";
+const NOTEBOOK_ID: &str = "notebook-1";
+
+struct Session {
+    client: NblmClient,
+    cassette: Arc<CassetteTransport>,
+    notebook_id: String,
+}
+
+impl Session {
+    fn start(name: &str) -> Self {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/cassettes")
+            .join(format!("{name}.json"));
+        let cassette = CassetteTransport::from_env(path).expect("load cassette");
+
+        let (project_number, notebook_id) = match cassette.mode() {
+            CassetteMode::Record => (
+                std::env::var("NBLM_PROJECT_NUMBER").expect("NBLM_PROJECT_NUMBER"),
+                std::env::var("NBLM_CASSETTE_NOTEBOOK_ID").unwrap_or_default(),
+            ),
+            CassetteMode::Replay => (PROJECT_NUMBER.to_string(), NOTEBOOK_ID.to_string()),
+        };
+        let cassette = Arc::new(
+            cassette
+                .redact(project_number.clone(), PROJECT_NUMBER)
+                .redact(notebook_id.clone(), NOTEBOOK_ID),
+        );
+
+        let provider: Arc<dyn nblm_core::TokenProvider> = match cassette.mode() {
+            CassetteMode::Record => Arc::new(EnvTokenProvider::new("NBLM_ACCESS_TOKEN")),
+            CassetteMode::Replay => Arc::new(StaticTokenProvider::new("test-token")),
+        };
+        let env = EnvironmentConfig::enterprise(project_number, "global", "global").unwrap();
+        let client = NblmClient::new(provider, env)
+            .unwrap()
+            .with_cassette(Arc::clone(&cassette));
+
+        Self {
+            client,
+            cassette,
+            notebook_id,
+        }
+    }
+
+    fn finish(self) {
+        self.cassette.save().expect("save cassette");
+    }
+}
+
+#[tokio::test]
+async fn list_recently_viewed_replays_recorded_payload() {
+    let session = Session::start("list_recently_viewed");
+
+    let response = session.client.list_recently_viewed(Some(10)).await.unwrap();
+
+    assert_eq!(response.notebooks.len(), 2);
+    let notebook = &response.notebooks[0];
+    assert_eq!(notebook.title, "Quarterly research");
+    assert_eq!(notebook.notebook_id.as_deref(), Some(NOTEBOOK_ID));
+    assert_eq!(
+        notebook.name.as_deref(),
+        Some("projects/123456/locations/global/notebooks/notebook-1")
+    );
+    let metadata = notebook.metadata.as_ref().expect("metadata");
+    assert_eq!(metadata.is_shareable, Some(true));
+    assert_eq!(metadata.is_shared, Some(false));
+    assert_eq!(
+        metadata.last_viewed.as_deref(),
+        Some("2025-10-24T09:12:44.318Z")
+    );
+    // Fields the models do not name are kept rather than dropped.
+    assert_eq!(metadata.extra["userRole"], "PROJECT_ROLE_OWNER");
+    assert_eq!(response.notebooks[1].title, "");
+    assert!(response.next_page_token.is_none());
+
+    session.finish();
+}
+
+#[tokio::test]
+async fn add_sources_replays_recorded_payload() {
+    let session = Session::start("batch_create_sources");
+
+    let response = session
+        .client
+        .add_sources(
+            &session.notebook_id,
+            vec![UserContent::web(
+                "https://example.com/".to_string(),
+                Some("Example Domain".to_string()),
+            )],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.error_count, None);
+    assert_eq!(response.sources.len(), 1);
+    let source = &response.sources[0];
+    assert_eq!(
+        source.name,
+        "projects/123456/locations/global/notebooks/notebook-1/sources/source-1"
+    );
+    assert_eq!(
+        source.source_id.as_ref().and_then(|id| id.id.as_deref()),
+        Some("source-1")
+    );
+    assert_eq!(source.title.as_deref(), Some("Example Domain"));
+    let metadata = source.metadata.as_ref().expect("metadata");
+    assert_eq!(metadata.word_count, Some(30));
+    assert_eq!(metadata.extra["tokenCount"], 41);
+    assert_eq!(
+        source
+            .settings
+            .as_ref()
+            .and_then(|settings| settings.status.as_deref()),
+        Some("SOURCE_STATUS_PENDING")
+    );
+
+    session.finish();
+}
@@ -0,0 +1,33 @@
+//! Fixtures shared by the integration tests. Each test file includes this module with
+//! `mod common;` and uses only part of it.
+#![allow(dead_code)]
+
+use std::sync::Arc;
+
+use nblm_core::{EnvironmentConfig, NblmClient, StaticTokenProvider};
+use wiremock::MockServer;
+
+/// Project `123` in the `global` location, on the `global` endpoint.
+pub fn environment() -> EnvironmentConfig {
+    EnvironmentConfig::enterprise("123", "global", "global").unwrap()
+}
+
+/// A client for `environment` that authenticates with the static token `test-token` and sends
+/// every request to `base_url`.
+pub fn client_in(environment: EnvironmentConfig, base_url: &str) -> NblmClient {
+    let provider = Arc::new(StaticTokenProvider::new("test-token"));
+    NblmClient::new(provider, environment)
+        .unwrap()
+        .with_base_url(base_url)
+        .unwrap()
+}
+
+/// [`client_in`] for [`environment`].
+pub fn client(base_url: &str) -> NblmClient {
+    client_in(environment(), base_url)
+}
+
+/// [`client`] for `server`, serving the `v1alpha` API.
+pub fn mock_client(server: &MockServer) -> NblmClient {
+    client(&format!("{}/v1alpha", server.uri()))
+}
@@ -0,0 +1,51 @@
+//! The programs in `examples/` run against the mock API. `cargo test` also builds them, so an
+//! API change that breaks one fails the build.
+
+#[path = "../examples/create_and_ingest.rs"]
+#[allow(dead_code)]
+mod create_and_ingest;
+
+#[path = "../examples/bulk_cleanup.rs"]
+#[allow(dead_code)]
+mod bulk_cleanup;
+
+use nblm_core::test_support::doctest;
+
+#[tokio::test]
+async fn create_and_ingest_runs_with_config_from_env() {
+    let api = doctest::mock_api().await;
+    std::env::set_var("NBLM_PROJECT_NUMBER", "123456");
+    std::env::set_var("NBLM_ACCESS_TOKEN", "test-token");
+    std::env::set_var("NBLM_BASE_URL", api.base_url());
+
+    let client = create_and_ingest::Config::from_env()
+        .unwrap()
+        .client()
+        .unwrap();
+    let created = create_and_ingest::run(&client, "Research").await.unwrap();
+
+    assert_eq!(created.notebook_id, "nb-1");
+    assert_eq!(created.source_ids, ["src-1"]);
+}
+
+#[tokio::test]
+async fn bulk_cleanup_deletes_only_after_confirmation() {
+    let (_api, client) = doctest::client().await.unwrap();
+
+    let titles: Vec<_> = bulk_cleanup::matching(&client, "Re")
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|notebook| notebook.title)
+        .collect();
+    assert_eq!(titles, ["Research", "Reading list"]);
+
+    let deleted = bulk_cleanup::run(&client, "Re", "n\n".as_bytes())
+        .await
+        .unwrap();
+    assert_eq!(deleted, 0);
+    let deleted = bulk_cleanup::run(&client, "Re", "yes\n".as_bytes())
+        .await
+        .unwrap();
+    assert_eq!(deleted, 2);
+}
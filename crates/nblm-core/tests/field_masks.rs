@@ -0,0 +1,95 @@
+//! Partial responses: the `fields` query parameter and parsing of trimmed payloads.
+
+mod common;
+
+use nblm_core::models::enterprise::notebook::{
+    NOTEBOOK_FIELDS_MINIMAL, RECENT_NOTEBOOKS_FIELDS_MINIMAL,
+};
+use nblm_core::Error;
+use serde_json::json;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const NOTEBOOKS_PATH: &str = "/v1alpha/projects/123/locations/global/notebooks";
+
+#[tokio::test]
+async fn recent_minimal_mask_is_sent_and_trimmed_notebooks_parse() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(format!("{NOTEBOOKS_PATH}:listRecentlyViewed")))
+        .and(query_param("fields", RECENT_NOTEBOOKS_FIELDS_MINIMAL))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "notebooks": [
+                {"name": "projects/123/locations/global/notebooks/nb1", "notebookId": "nb1", "title": "First"},
+                {"notebookId": "nb2"},
+            ],
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = common::mock_client(&server)
+        .list_recently_viewed_with_fields(None, Some(RECENT_NOTEBOOKS_FIELDS_MINIMAL))
+        .await
+        .unwrap();
+
+    assert_eq!(response.notebooks.len(), 2);
+    assert_eq!(response.notebooks[0].title, "First");
+    // A mask without `title` still parses; the title is just empty.
+    assert_eq!(response.notebooks[1].notebook_id.as_deref(), Some("nb2"));
+    assert_eq!(response.notebooks[1].title, "");
+    assert!(response.notebooks[1].metadata.is_none());
+}
+
+#[tokio::test]
+async fn get_notebook_sends_custom_mask_and_omits_it_by_default() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(format!("{NOTEBOOKS_PATH}/nb1")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "notebookId": "nb1",
+            "sources": [{"name": "projects/123/locations/global/notebooks/nb1/sources/s1"}],
+        })))
+        .mount(&server)
+        .await;
+    let client = common::mock_client(&server);
+
+    let notebook = client
+        .get_notebook_with_fields("nb1", Some(NOTEBOOK_FIELDS_MINIMAL))
+        .await
+        .unwrap();
+    assert_eq!(notebook.sources.len(), 1);
+    client.get_notebook("nb1").await.unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    let fields: Vec<Option<String>> = requests
+        .iter()
+        .map(|request| {
+            request
+                .url
+                .query_pairs()
+                .find(|(key, _)| key == "fields")
+                .map(|(_, value)| value.into_owned())
+        })
+        .collect();
+    assert_eq!(fields, [Some(NOTEBOOK_FIELDS_MINIMAL.to_string()), None]);
+}
+
+#[tokio::test]
+async fn blank_mask_is_rejected_before_any_request() {
+    let server = MockServer::start().await;
+    let client = common::mock_client(&server);
+
+    let err = client
+        .list_recently_viewed_with_fields(None, Some("  "))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Validation(_)), "{err}");
+    let err = client
+        .get_notebook_with_fields("nb1", Some(""))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Validation(_)), "{err}");
+
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
@@ -0,0 +1,60 @@
+//! `with_host_failure_cache` against a port nothing listens on.
+
+mod common;
+
+use std::time::Duration;
+
+use nblm_core::{Error, HostFailureConfig, NblmClient, RetryConfig};
+
+/// A local address that refuses connections: bind a port, then release it.
+fn refused_base_url() -> String {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+    format!("http://127.0.0.1:{port}/v1alpha")
+}
+
+fn client(base_url: &str, config: Option<HostFailureConfig>) -> NblmClient {
+    let client =
+        common::client(base_url).with_retry_config(RetryConfig::default().with_max_retries(0));
+    match config {
+        Some(config) => client.with_host_failure_cache(config),
+        None => client,
+    }
+}
+
+#[tokio::test]
+async fn refused_host_fails_fast_after_the_threshold() {
+    let base_url = refused_base_url();
+    let config = HostFailureConfig::default()
+        .with_threshold(2)
+        .with_window(Duration::from_secs(60));
+    let client = client(&base_url, Some(config));
+
+    for _ in 0..2 {
+        let err = client.list_recently_viewed(None).await.unwrap_err();
+        assert!(
+            matches!(&err, Error::Connect { source, .. } if source.is_connect()),
+            "{err:?}"
+        );
+    }
+    let err = client.list_recently_viewed(None).await.unwrap_err();
+    match err {
+        Error::HostUnavailable {
+            failures, retry_in, ..
+        } => {
+            assert_eq!(failures, 2);
+            assert!(retry_in > Duration::from_secs(55), "{retry_in:?}");
+        }
+        other => panic!("expected HostUnavailable, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn without_the_cache_every_request_is_sent() {
+    let client = client(&refused_base_url(), None);
+    for _ in 0..3 {
+        let err = client.list_recently_viewed(None).await.unwrap_err();
+        assert!(matches!(err, Error::Connect { .. }), "{err:?}");
+    }
+}
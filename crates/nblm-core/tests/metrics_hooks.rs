@@ -0,0 +1,128 @@
+//! `MetricsHooks` against the `metrics-util` debugging recorder.
+
+mod common;
+
+use std::time::Duration;
+
+use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+use nblm_core::client::metrics::MetricsHooks;
+use nblm_core::models::enterprise::source::UserContent;
+use nblm_core::RetryConfig;
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const PARENT: &str = "/v1alpha/projects/123/locations/global";
+
+/// `name{label="value",...}` for every metric, with its value.
+fn metrics(recorder: &metrics_util::debugging::Snapshotter) -> Vec<(String, DebugValue)> {
+    let mut metrics: Vec<_> = recorder
+        .snapshot()
+        .into_vec()
+        .into_iter()
+        .map(|(key, _, _, value)| {
+            let key = key.key();
+            let labels: Vec<String> = key
+                .labels()
+                .map(|label| format!("{}={:?}", label.key(), label.value()))
+                .collect();
+            (format!("{}{{{}}}", key.name(), labels.join(",")), value)
+        })
+        .collect();
+    metrics.sort_by(|a, b| a.0.cmp(&b.0));
+    metrics
+}
+
+#[tokio::test]
+async fn requests_durations_and_retries_are_recorded_with_templated_paths() {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    recorder.install().unwrap();
+
+    let server = MockServer::start().await;
+    let recent = format!("{PARENT}/notebooks:listRecentlyViewed");
+    Mock::given(method("GET"))
+        .and(path(recent.as_str()))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(recent.as_str()))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+        .with_priority(2)
+        .mount(&server)
+        .await;
+    for notebook_id in ["nb1", "nb2"] {
+        Mock::given(method("POST"))
+            .and(path(format!(
+                "{PARENT}/notebooks/{notebook_id}/sources:batchCreate"
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "sources": [] })))
+            .mount(&server)
+            .await;
+    }
+
+    let client = common::mock_client(&server)
+        .with_retry_config(
+            RetryConfig::default()
+                .with_min_delay(Duration::from_millis(5))
+                .with_jitter(false),
+        )
+        .with_metrics(MetricsHooks::new());
+
+    client.list_recently_viewed(None).await.unwrap();
+    for notebook_id in ["nb1", "nb2"] {
+        let text = UserContent::text("hello".to_string(), None);
+        client.add_sources(notebook_id, vec![text]).await.unwrap();
+    }
+
+    let metrics = metrics(&snapshotter);
+    let counters: Vec<(&str, u64)> = metrics
+        .iter()
+        .filter_map(|(key, value)| match value {
+            DebugValue::Counter(count) => Some((key.as_str(), *count)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        counters,
+        [
+            (
+                r#"nblm_requests_total{method="GET",path="/notebooks:listRecentlyViewed",status="200"}"#,
+                1
+            ),
+            (
+                r#"nblm_requests_total{method="GET",path="/notebooks:listRecentlyViewed",status="503"}"#,
+                1
+            ),
+            (
+                r#"nblm_requests_total{method="POST",path="/notebooks/{id}/sources:batchCreate",status="200"}"#,
+                2
+            ),
+            ("nblm_retries_total{}", 1),
+        ]
+    );
+
+    let durations: Vec<(&str, usize)> = metrics
+        .iter()
+        .filter_map(|(key, value)| match value {
+            DebugValue::Histogram(samples) => Some((key.as_str(), samples.len())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        durations,
+        [
+            (
+                r#"nblm_request_duration_seconds{method="GET",path="/notebooks:listRecentlyViewed"}"#,
+                2
+            ),
+            (
+                r#"nblm_request_duration_seconds{method="POST",path="/notebooks/{id}/sources:batchCreate"}"#,
+                2
+            ),
+        ]
+    );
+}
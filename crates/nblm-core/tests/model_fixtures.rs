@@ -0,0 +1,406 @@
+//! Checks the typed models against vendored response payloads in `tests/fixtures/responses`.
+//!
+//! Each fixture is served by a mock server and read through the matching `NblmClient` method, so
+//! the wire models and their conversion are covered too. Models that keep unknown fields must
+//! serialize back to the fixture unchanged.
+//!
+//! The fixtures are redacted API responses: the project number is `123456` and notebook, source
+//! and audio overview IDs are `notebook-N`, `source-N` and `audio-overview-N`. To refresh them
+//! from the live API (the `--scratch` part creates and then deletes a notebook):
+//!
+//! ```text
+//! nblm --project-number ... fixtures capture --scratch \
+//!     --dir crates/nblm-core/tests/fixtures/responses
+//! ```
+//!
+//! Review the written files before committing them: titles, URLs and channel names are kept as
+//! returned. `upload_source_file.json` and `get_notebook_with_summary.json` are not captured and
+//! are maintained by hand.
+//!
+//! The `alt_casing_*` fixtures are hand-written variants with the snake_case keys and missing
+//! `notebookId` some endpoints return. They must populate the same typed fields, leaving nothing
+//! in the `extra` maps.
+
+mod common;
+
+use std::path::PathBuf;
+
+use nblm_core::models::enterprise::audio::AudioOverviewRequest;
+use nblm_core::models::enterprise::source::UserContent;
+use nblm_core::{EnvironmentConfig, NblmClient};
+use serde::Serialize;
+use serde_json::Value;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const NOTEBOOKS: &str = "/v1alpha/projects/123456/locations/global/notebooks";
+
+fn fixture(name: &str) -> Value {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/responses")
+        .join(format!("{name}.json"));
+    let content = std::fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("failed to read {}: {err}", path.display()));
+    serde_json::from_str(&content)
+        .unwrap_or_else(|err| panic!("{} is not valid JSON: {err}", path.display()))
+}
+
+/// A client whose only endpoint answers `verb path` with the fixture `name`.
+async fn serve(verb: &str, endpoint: String, name: &str) -> (NblmClient, MockServer) {
+    let server = MockServer::start().await;
+    Mock::given(method(verb))
+        .and(path(endpoint))
+        .respond_with(ResponseTemplate::new(200).set_body_json(fixture(name)))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let env = EnvironmentConfig::enterprise("123456", "global", "global").unwrap();
+    let client = common::client_in(env, &format!("{}/v1alpha", server.uri()));
+    (client, server)
+}
+
+fn assert_round_trips(model: &impl Serialize, expected: &Value) {
+    let actual = serde_json::to_value(model).unwrap();
+    assert_eq!(
+        &actual,
+        expected,
+        "re-serialized model differs from the fixture:\n{}",
+        serde_json::to_string_pretty(&actual).unwrap()
+    );
+}
+
+#[tokio::test]
+async fn notebook_create() {
+    let (client, _server) = serve("POST", NOTEBOOKS.to_string(), "notebook_create").await;
+
+    let notebook = client.create_notebook("Quarterly research").await.unwrap();
+
+    assert_eq!(notebook.title, "Quarterly research");
+    assert_eq!(notebook.notebook_id.as_deref(), Some("notebook-1"));
+    let metadata = notebook.metadata.as_ref().unwrap();
+    assert_eq!(metadata.is_shareable, Some(true));
+    assert_eq!(
+        metadata.create_time.as_deref(),
+        Some("2025-10-24T09:14:02.118Z")
+    );
+    assert_eq!(metadata.extra["userRole"], "PROJECT_ROLE_OWNER");
+    assert_round_trips(&notebook, &fixture("notebook_create"));
+}
+
+#[tokio::test]
+async fn list_recently_viewed_with_metadata() {
+    let (client, _server) = serve(
+        "GET",
+        format!("{NOTEBOOKS}:listRecentlyViewed"),
+        "list_recently_viewed",
+    )
+    .await;
+
+    let response = client.list_recently_viewed(None).await.unwrap();
+
+    assert_eq!(response.notebooks.len(), 3);
+    assert_eq!(response.next_page_token.as_deref(), Some("page-token-1"));
+    let shared = &response.notebooks[1];
+    assert_eq!(shared.title, "Shared reading list");
+    let metadata = shared.metadata.as_ref().unwrap();
+    assert_eq!(metadata.is_shared, Some(true));
+    assert_eq!(
+        metadata.last_viewed.as_deref(),
+        Some("2025-10-22T15:03:19.904Z")
+    );
+    assert_eq!(response.notebooks[2].title, "");
+    assert_round_trips(&response, &fixture("list_recently_viewed"));
+}
+
+#[tokio::test]
+async fn get_notebook_with_sources() {
+    let (client, _server) = serve("GET", format!("{NOTEBOOKS}/notebook-1"), "get_notebook").await;
+
+    let notebook = client.get_notebook("notebook-1").await.unwrap();
+
+    assert_eq!(notebook.sources.len(), 2);
+    let first = &notebook.sources[0];
+    assert_eq!(first.source_id(), Some("source-1"));
+    assert_eq!(first.metadata.as_ref().unwrap().word_count, Some(30));
+    assert_eq!(first.metadata.as_ref().unwrap().extra["tokenCount"], 41);
+    assert!(notebook.sources[1]
+        .metadata
+        .as_ref()
+        .unwrap()
+        .word_count
+        .is_none());
+    assert!(notebook.summary.is_none());
+    assert_round_trips(&notebook, &fixture("get_notebook"));
+}
+
+#[tokio::test]
+async fn get_notebook_with_summary() {
+    let (client, _server) = serve(
+        "GET",
+        format!("{NOTEBOOKS}/notebook-1"),
+        "get_notebook_with_summary",
+    )
+    .await;
+
+    let notebook = client.get_notebook("notebook-1").await.unwrap();
+
+    let summary = notebook.summary.as_deref().unwrap();
+    assert!(summary.starts_with("These sources cover quarterly revenue"));
+    assert!(summary.contains("\n\nThey disagree"));
+    assert!(notebook.extra.is_empty());
+    assert_round_trips(&notebook, &fixture("get_notebook_with_summary"));
+}
+
+#[tokio::test]
+async fn batch_create_sources_with_errors() {
+    let (client, _server) = serve(
+        "POST",
+        format!("{NOTEBOOKS}/notebook-1/sources:batchCreate"),
+        "batch_create_sources_with_errors",
+    )
+    .await;
+
+    let response = client
+        .add_sources(
+            "notebook-1",
+            vec![
+                UserContent::web("https://example.com/".to_string(), None),
+                UserContent::web("https://unreachable.example.invalid/".to_string(), None),
+            ],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.error_count, Some(1));
+    assert_eq!(response.created_ids(), ["source-1"]);
+    let failed = response.failed();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].source_id(), Some("source-2"));
+    assert_round_trips(&response, &fixture("batch_create_sources_with_errors"));
+}
+
+#[tokio::test]
+async fn upload_source_file() {
+    let (client, _server) = serve(
+        "POST",
+        "/upload/v1alpha/projects/123456/locations/global/notebooks/notebook-1/sources:uploadFile"
+            .to_string(),
+        "upload_source_file",
+    )
+    .await;
+
+    let response = client
+        .upload_source_file("notebook-1", "notes.txt", "text/plain", b"notes".to_vec())
+        .await
+        .unwrap();
+
+    let source_id = response.source_id.as_ref().unwrap();
+    assert_eq!(source_id.id.as_deref(), Some("source-3"));
+    assert!(response.name.is_none());
+    assert_round_trips(&response, &fixture("upload_source_file"));
+}
+
+#[tokio::test]
+async fn audio_overview_create() {
+    let (client, _server) = serve(
+        "POST",
+        format!("{NOTEBOOKS}/notebook-1/audioOverviews"),
+        "audio_overview_create",
+    )
+    .await;
+
+    let response = client
+        .create_audio_overview("notebook-1", AudioOverviewRequest::default())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        response.audio_overview_id.as_deref(),
+        Some("audio-overview-1")
+    );
+    assert_eq!(
+        response.status.as_deref(),
+        Some("AUDIO_OVERVIEW_STATUS_IN_PROGRESS")
+    );
+    // The client unwraps the `audioOverview` envelope.
+    assert_round_trips(
+        &response,
+        &fixture("audio_overview_create")["audioOverview"],
+    );
+}
+
+#[tokio::test]
+async fn get_source_with_youtube_metadata() {
+    let (client, _server) = serve(
+        "GET",
+        format!("{NOTEBOOKS}/notebook-1/sources/source-4"),
+        "get_source_youtube",
+    )
+    .await;
+
+    let source = client.get_source("notebook-1", "source-4").await.unwrap();
+
+    assert_eq!(source.source_id(), Some("source-4"));
+    let metadata = source.metadata.as_ref().unwrap();
+    assert_eq!(metadata.word_count, Some(2418));
+    let youtube = metadata.youtube_metadata.as_ref().unwrap();
+    assert_eq!(youtube.channel_name.as_deref(), Some("Example Channel"));
+    assert_eq!(youtube.video_id.as_deref(), Some("video-1"));
+    assert!(!source.is_failed());
+    assert_round_trips(&source, &fixture("get_source_youtube"));
+}
+
+#[tokio::test]
+async fn alt_casing_notebook_without_notebook_id() {
+    let (client, _server) = serve(
+        "GET",
+        format!("{NOTEBOOKS}/notebook-1"),
+        "alt_casing_get_notebook_name_only",
+    )
+    .await;
+
+    let notebook = client.get_notebook("notebook-1").await.unwrap();
+
+    assert!(notebook.notebook_id.is_none());
+    assert_eq!(notebook.notebook_id(), Some("notebook-1"));
+    assert!(notebook.extra.is_empty());
+    let metadata = notebook.metadata.as_ref().unwrap();
+    assert_eq!(metadata.is_shareable, Some(true));
+    assert_eq!(
+        metadata.create_time.as_deref(),
+        Some("2025-10-01T08:00:03.127Z")
+    );
+    assert!(metadata.extra.is_empty());
+
+    let first = &notebook.sources[0];
+    assert_eq!(
+        first.source_id.as_ref().unwrap().id.as_deref(),
+        Some("source-1")
+    );
+    let source_metadata = first.metadata.as_ref().unwrap();
+    assert_eq!(source_metadata.word_count, Some(30));
+    assert!(source_metadata.source_added_timestamp.is_some());
+    assert!(source_metadata.extra.is_empty());
+    // No `sourceId` at all: the ID comes from the name.
+    assert_eq!(notebook.sources[1].source_id(), Some("source-2"));
+}
+
+#[tokio::test]
+async fn alt_casing_list_recently_viewed() {
+    let (client, _server) = serve(
+        "GET",
+        format!("{NOTEBOOKS}:listRecentlyViewed"),
+        "alt_casing_list_recently_viewed",
+    )
+    .await;
+
+    let response = client.list_recently_viewed(None).await.unwrap();
+
+    assert_eq!(response.next_page_token.as_deref(), Some("page-token-1"));
+    let ids: Vec<_> = response
+        .notebooks
+        .iter()
+        .map(|nb| nb.notebook_id())
+        .collect();
+    assert_eq!(ids, [Some("notebook-1"), Some("notebook-2")]);
+    let first = &response.notebooks[0];
+    assert_eq!(first.notebook_id.as_deref(), Some("notebook-1"));
+    assert!(first.extra.is_empty());
+    let metadata = first.metadata.as_ref().unwrap();
+    assert_eq!(metadata.is_shared, Some(true));
+    assert_eq!(
+        metadata.last_viewed.as_deref(),
+        Some("2025-10-24T09:12:44.318Z")
+    );
+    assert!(metadata.extra.is_empty());
+}
+
+#[tokio::test]
+async fn alt_casing_batch_create_sources() {
+    let (client, _server) = serve(
+        "POST",
+        format!("{NOTEBOOKS}/notebook-1/sources:batchCreate"),
+        "alt_casing_batch_create_sources",
+    )
+    .await;
+
+    let response = client
+        .add_sources(
+            "notebook-1",
+            vec![UserContent::web("https://example.com/".to_string(), None)],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.error_count, Some(0));
+    assert_eq!(response.created_ids(), ["source-1"]);
+    assert!(response.sources[0].extra.is_empty());
+}
+
+#[tokio::test]
+async fn alt_casing_upload_source_file() {
+    let (client, _server) = serve(
+        "POST",
+        "/upload/v1alpha/projects/123456/locations/global/notebooks/notebook-1/sources:uploadFile"
+            .to_string(),
+        "alt_casing_upload_source_file",
+    )
+    .await;
+
+    let response = client
+        .upload_source_file("notebook-1", "notes.txt", "text/plain", b"notes".to_vec())
+        .await
+        .unwrap();
+
+    let source_id = response.source_id.as_ref().unwrap();
+    assert_eq!(source_id.id.as_deref(), Some("source-3"));
+    assert!(response.extra.is_empty());
+    // Re-serialized with the canonical casing.
+    assert_round_trips(&response, &fixture("upload_source_file"));
+}
+
+#[tokio::test]
+async fn alt_casing_audio_overview_create() {
+    let (client, _server) = serve(
+        "POST",
+        format!("{NOTEBOOKS}/notebook-1/audioOverviews"),
+        "alt_casing_audio_overview_create",
+    )
+    .await;
+
+    let response = client
+        .create_audio_overview("notebook-1", AudioOverviewRequest::default())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        response.audio_overview_id.as_deref(),
+        Some("audio-overview-1")
+    );
+    assert!(response.generation_options.is_some());
+    assert!(response.extra.is_empty());
+}
+
+#[tokio::test]
+async fn alt_casing_source_with_youtube_metadata() {
+    let (client, _server) = serve(
+        "GET",
+        format!("{NOTEBOOKS}/notebook-1/sources/source-4"),
+        "alt_casing_get_source_youtube",
+    )
+    .await;
+
+    let source = client.get_source("notebook-1", "source-4").await.unwrap();
+
+    assert_eq!(
+        source.source_id.as_ref().unwrap().id.as_deref(),
+        Some("source-4")
+    );
+    let metadata = source.metadata.as_ref().unwrap();
+    assert_eq!(metadata.word_count, Some(2418));
+    let youtube = metadata.youtube_metadata.as_ref().unwrap();
+    assert_eq!(youtube.channel_name.as_deref(), Some("Example Channel"));
+    assert_eq!(youtube.video_id.as_deref(), Some("video-1"));
+    assert!(youtube.extra.is_empty());
+    assert!(metadata.extra.is_empty());
+}
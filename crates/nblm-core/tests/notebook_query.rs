@@ -0,0 +1,131 @@
+//! `notebooks/{id}:query`: answers, citations and projects without the endpoint.
+
+mod common;
+
+use nblm_core::models::enterprise::query::QueryRequest;
+use nblm_core::Error;
+use serde_json::json;
+use wiremock::matchers::{body_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const QUERY_PATH: &str = "/v1alpha/projects/123/locations/global/notebooks/nb1:query";
+
+fn question(source_ids: Option<Vec<&str>>) -> QueryRequest {
+    QueryRequest {
+        query: "What changed in Q3?".to_string(),
+        source_ids: source_ids.map(|ids| ids.into_iter().map(String::from).collect()),
+    }
+}
+
+#[tokio::test]
+async fn cited_answer_is_parsed_with_its_sources() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path(QUERY_PATH))
+        .and(body_json(json!({
+            "query": "What changed in Q3?",
+            "sourceIds": [{ "id": "s1" }],
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "answer": {
+                "answerText": "Revenue grew [1], costs fell [2].",
+                "citations": [
+                    { "sourceId": { "id": "s1" }, "title": "Q3 report", "text": "Revenue grew 12%", "startIndex": 0 },
+                    { "sourceId": { "id": "s1" }, "snippet": "Costs fell 3%" },
+                ],
+            },
+            "queryId": "q-1",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = common::mock_client(&server)
+        .query_notebook("nb1", question(Some(vec!["s1"])))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        response.answer.as_deref(),
+        Some("Revenue grew [1], costs fell [2].")
+    );
+    assert_eq!(response.citations.len(), 2);
+    let first = &response.citations[0];
+    assert_eq!(
+        first.source_id.as_ref().and_then(|id| id.id.as_deref()),
+        Some("s1")
+    );
+    assert_eq!(first.title.as_deref(), Some("Q3 report"));
+    assert_eq!(first.text.as_deref(), Some("Revenue grew 12%"));
+    assert_eq!(first.extra["startIndex"], 0);
+    assert_eq!(response.citations[1].text.as_deref(), Some("Costs fell 3%"));
+    assert_eq!(response.extra["queryId"], "q-1");
+}
+
+#[tokio::test]
+async fn empty_answer_has_no_text_or_citations() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path(QUERY_PATH))
+        .and(body_json(json!({ "query": "What changed in Q3?" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let response = common::mock_client(&server)
+        .query_notebook("nb1", question(None))
+        .await
+        .unwrap();
+
+    assert!(!response.has_answer());
+    assert!(response.citations.is_empty());
+}
+
+#[tokio::test]
+async fn not_found_is_reported_as_unavailable() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path(QUERY_PATH))
+        .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+            "error": { "code": 404, "message": "Method not found.", "status": "NOT_FOUND" }
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let err = common::mock_client(&server)
+        .query_notebook("nb1", question(None))
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        err,
+        Error::Unavailable {
+            feature: "notebook query",
+            ..
+        }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "notebook query is not available for this project or API profile (HTTP 404: Method not found.)"
+    );
+}
+
+#[tokio::test]
+async fn blank_question_is_rejected_before_any_request() {
+    let server = MockServer::start().await;
+    let err = common::mock_client(&server)
+        .query_notebook(
+            "nb1",
+            QueryRequest {
+                query: "  ".to_string(),
+                source_ids: None,
+            },
+        )
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, Error::Validation(_)));
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
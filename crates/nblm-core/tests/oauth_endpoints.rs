@@ -0,0 +1,122 @@
+//! `NBLM_OAUTH_AUTH_ENDPOINT` / `NBLM_OAUTH_TOKEN_ENDPOINT` carried through `OAuthFlow`.
+
+use std::sync::Arc;
+
+use nblm_core::auth::oauth::{OAuthClientConfig, AUTH_ENDPOINT_ENV, TOKEN_ENDPOINT_ENV};
+use nblm_core::{AuthorizeParams, OAuthFlow};
+use serde_json::json;
+use serial_test::serial;
+use wiremock::matchers::{body_string_contains, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Restores the OAuth variables the tests set when dropped.
+struct EnvGuard(Vec<(&'static str, Option<String>)>);
+
+impl EnvGuard {
+    fn set(vars: &[(&'static str, Option<&str>)]) -> Self {
+        let saved = vars
+            .iter()
+            .map(|(key, _)| (*key, std::env::var(key).ok()))
+            .collect();
+        for (key, value) in vars {
+            match value {
+                Some(value) => std::env::set_var(key, value),
+                None => std::env::remove_var(key),
+            }
+        }
+        Self(saved)
+    }
+}
+
+impl Drop for EnvGuard {
+    fn drop(&mut self) {
+        for (key, value) in &self.0 {
+            match value {
+                Some(value) => std::env::set_var(key, value),
+                None => std::env::remove_var(key),
+            }
+        }
+    }
+}
+
+/// A flow built from the environment, with both endpoints pointed at `server`.
+fn flow_for(server: &MockServer) -> (EnvGuard, OAuthFlow) {
+    let guard = EnvGuard::set(&[
+        ("NBLM_OAUTH_CLIENT_ID", Some("sovereign-client")),
+        ("NBLM_OAUTH_CLIENT_SECRET", None),
+        ("NBLM_OAUTH_CLIENT_SECRET_FILE", None),
+        (AUTH_ENDPOINT_ENV, Some(&format!("{}/o/auth", server.uri()))),
+        (
+            TOKEN_ENDPOINT_ENV,
+            Some(&format!("{}/o/token", server.uri())),
+        ),
+    ]);
+    let config = OAuthClientConfig::from_env().unwrap().into_oauth_config();
+    let flow = OAuthFlow::new(config, Arc::new(reqwest::Client::new())).unwrap();
+    (guard, flow)
+}
+
+fn token_response() -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(json!({
+        "access_token": "sovereign-access-token",
+        "refresh_token": "sovereign-refresh-token",
+        "token_type": "Bearer",
+        "expires_in": 3600
+    }))
+}
+
+#[tokio::test]
+#[serial]
+async fn code_exchange_uses_the_overridden_endpoints() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/o/token"))
+        .and(body_string_contains("grant_type=authorization_code"))
+        .and(body_string_contains("client_id=sovereign-client"))
+        .and(body_string_contains("code=auth-code-123"))
+        .and(body_string_contains("code_verifier="))
+        .respond_with(token_response())
+        .expect(1)
+        .mount(&server)
+        .await;
+    let (_env, flow) = flow_for(&server);
+
+    let context = flow.build_authorize_url(&AuthorizeParams {
+        state: None,
+        code_challenge: None,
+        code_challenge_method: None,
+    });
+    assert!(
+        context
+            .url
+            .starts_with(&format!("{}/o/auth?", server.uri())),
+        "{}",
+        context.url
+    );
+
+    let tokens = flow.exchange_code(&context, "auth-code-123").await.unwrap();
+    assert_eq!(tokens.access_token, "sovereign-access-token");
+    assert_eq!(
+        tokens.refresh_token.as_deref(),
+        Some("sovereign-refresh-token")
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn refresh_grants_use_the_overridden_token_endpoint() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/o/token"))
+        .and(body_string_contains("grant_type=refresh_token"))
+        .and(body_string_contains("client_id=sovereign-client"))
+        .and(body_string_contains("refresh_token=stored-refresh-token"))
+        .respond_with(token_response())
+        .expect(1)
+        .mount(&server)
+        .await;
+    let (_env, flow) = flow_for(&server);
+
+    let tokens = flow.refresh("stored-refresh-token").await.unwrap();
+    assert_eq!(tokens.access_token, "sovereign-access-token");
+}
@@ -0,0 +1,114 @@
+//! The `otel` feature against an in-memory OpenTelemetry exporter.
+
+mod common;
+
+use nblm_core::TraceContext;
+use opentelemetry::trace::{SpanId, TracerProvider as _};
+use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider, SpanData};
+use serde_json::json;
+use tracing::subscriber::DefaultGuard;
+use tracing::Instrument;
+use tracing_subscriber::layer::SubscriberExt;
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const EXPLICIT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+/// Route `tracing` spans on this thread into an exporter the test can read back.
+fn install_tracer() -> (InMemorySpanExporter, SdkTracerProvider, DefaultGuard) {
+    let exporter = InMemorySpanExporter::default();
+    let provider = SdkTracerProvider::builder()
+        .with_simple_exporter(exporter.clone())
+        .build();
+    let subscriber = tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+    let guard = tracing::subscriber::set_default(subscriber);
+    (exporter, provider, guard)
+}
+
+async fn server() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"title": "A"})))
+        .mount(&server)
+        .await;
+    server
+}
+
+fn span<'a>(spans: &'a [SpanData], name: &str) -> &'a SpanData {
+    spans
+        .iter()
+        .find(|span| span.name == name)
+        .unwrap_or_else(|| panic!("no '{name}' span in {spans:?}"))
+}
+
+#[tokio::test]
+async fn requests_join_the_current_span_and_are_exported() {
+    let (exporter, provider, _guard) = install_tracer();
+    let server = server().await;
+    let client = common::mock_client(&server);
+
+    client
+        .get_notebook("nb1")
+        .instrument(tracing::info_span!("caller"))
+        .await
+        .unwrap();
+    provider.force_flush().unwrap();
+
+    let spans = exporter.get_finished_spans().unwrap();
+    let caller = span(&spans, "caller");
+    let request = span(&spans, "nblm.request");
+    assert_eq!(request.parent_span_id, caller.span_context.span_id());
+    assert_eq!(
+        request.span_context.trace_id(),
+        caller.span_context.trace_id()
+    );
+    assert!(request.attributes.iter().any(|attribute| {
+        attribute.key.as_str() == "url.template"
+            && attribute.value.as_str()
+                == "/v1alpha/projects/{project}/locations/{location}/notebooks/{notebook}"
+    }));
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(
+        requests[0].headers["traceparent"],
+        format!(
+            "00-{}-{}-01",
+            request.span_context.trace_id(),
+            request.span_context.span_id()
+        )
+    );
+    assert!(!requests[0].headers.contains_key("tracestate"));
+}
+
+#[tokio::test]
+async fn an_explicit_context_wins_over_the_current_span() {
+    let (exporter, provider, _guard) = install_tracer();
+    let server = server().await;
+    let client =
+        common::mock_client(&server).with_trace_context(TraceContext::parse(EXPLICIT).unwrap());
+
+    client
+        .get_notebook("nb1")
+        .instrument(tracing::info_span!("caller"))
+        .await
+        .unwrap();
+    provider.force_flush().unwrap();
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests[0].headers["traceparent"], EXPLICIT);
+    // The request span is still recorded; it just isn't what the server sees.
+    let spans = exporter.get_finished_spans().unwrap();
+    assert_ne!(
+        span(&spans, "nblm.request").span_context.span_id(),
+        SpanId::INVALID
+    );
+}
+
+#[test]
+fn from_span_is_none_without_an_opentelemetry_layer() {
+    let _guard = tracing::subscriber::set_default(tracing_subscriber::registry());
+    let span = tracing::info_span!("caller");
+    assert_eq!(TraceContext::from_span(&span), None);
+}
@@ -0,0 +1,55 @@
+//! Oversized JSON request bodies are refused before anything is sent.
+
+mod common;
+
+use nblm_core::models::enterprise::source::UserContent;
+use nblm_core::{Error, DEFAULT_MAX_BODY_SIZE};
+use serde_json::json;
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn mount_batch_create(server: &MockServer, expected: u64) {
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"sources": []})))
+        .expect(expected)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn oversized_batch_create_fails_without_a_request() {
+    let server = MockServer::start().await;
+    mount_batch_create(&server, 0).await;
+    let client = common::mock_client(&server).with_max_body_size(1024);
+
+    let contents = vec![UserContent::text("x".repeat(2048), Some("Big".to_string()))];
+    let err = client.add_sources("nb1", contents).await.unwrap_err();
+
+    let Error::Validation(message) = &err else {
+        panic!("expected a validation error, got {err:?}");
+    };
+    assert!(message.contains("sources:batchCreate"), "{message}");
+    assert!(
+        message.contains("larger than the 1024-byte limit"),
+        "{message}"
+    );
+    let size: usize = message
+        .split(" is ")
+        .nth(1)
+        .and_then(|rest| rest.split(' ').next())
+        .and_then(|size| size.parse().ok())
+        .unwrap();
+    assert!(size > 2048, "{message}");
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn bodies_within_the_limit_are_sent() {
+    let server = MockServer::start().await;
+    mount_batch_create(&server, 1).await;
+    let client = common::mock_client(&server);
+    assert_eq!(client.max_body_size(), DEFAULT_MAX_BODY_SIZE);
+
+    let contents = vec![UserContent::text("x".repeat(2048), None)];
+    client.add_sources("nb1", contents).await.unwrap();
+}
@@ -0,0 +1,118 @@
+//! `with_request_cache` against wiremock expectations on how often each GET is sent.
+
+mod common;
+
+use nblm_core::{NblmClient, RetryConfig};
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const NAME_PARENT: &str = "projects/123/locations/global";
+const PARENT: &str = "/v1alpha/projects/123/locations/global";
+
+fn client(server: &MockServer) -> NblmClient {
+    common::mock_client(server)
+        .with_retry_config(RetryConfig::default().with_max_retries(0))
+        .with_request_cache()
+}
+
+async fn mock_get(server: &MockServer, path_str: String, body: serde_json::Value, times: u64) {
+    Mock::given(method("GET"))
+        .and(path(path_str))
+        .respond_with(ResponseTemplate::new(200).set_body_json(body))
+        .expect(times)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn repeated_reads_are_sent_once() {
+    let server = MockServer::start().await;
+    let notebook = json!({"name": format!("{NAME_PARENT}/notebooks/nb1"), "title": "One"});
+    mock_get(
+        &server,
+        format!("{PARENT}/notebooks:listRecentlyViewed"),
+        json!({"notebooks": [notebook]}),
+        1,
+    )
+    .await;
+    mock_get(
+        &server,
+        format!("{PARENT}/notebooks/nb1/sources/s1"),
+        json!({"name": format!("{NAME_PARENT}/notebooks/nb1/sources/s1")}),
+        1,
+    )
+    .await;
+    let client = client(&server);
+
+    for _ in 0..3 {
+        let listed = client.list_recently_viewed(None).await.unwrap();
+        assert_eq!(listed.notebooks.len(), 1);
+        let source = client.get_source("nb1", "s1").await.unwrap();
+        assert_eq!(source.source_id(), Some("s1"));
+    }
+    let raw = client.get_source_raw("nb1", "s1").await.unwrap();
+    assert!(!raw.raw().is_empty());
+}
+
+#[tokio::test]
+async fn a_create_in_between_drops_the_cached_listing() {
+    let server = MockServer::start().await;
+    mock_get(
+        &server,
+        format!("{PARENT}/notebooks:listRecentlyViewed"),
+        json!({"notebooks": []}),
+        2,
+    )
+    .await;
+    Mock::given(method("POST"))
+        .and(path(format!("{PARENT}/notebooks")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "name": format!("{NAME_PARENT}/notebooks/nb2"),
+            "title": "Two",
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let client = client(&server);
+
+    client.list_recently_viewed(None).await.unwrap();
+    client.list_recently_viewed(None).await.unwrap();
+    client.create_notebook("Two").await.unwrap();
+    client.list_recently_viewed(None).await.unwrap();
+}
+
+#[tokio::test]
+async fn writes_to_one_notebook_keep_other_notebooks_cached() {
+    let server = MockServer::start().await;
+    for (id, times) in [("nb1", 2), ("nb2", 1)] {
+        mock_get(
+            &server,
+            format!("{PARENT}/notebooks/{id}/sources/s1"),
+            json!({"name": format!("{NAME_PARENT}/notebooks/{id}/sources/s1")}),
+            times,
+        )
+        .await;
+    }
+    Mock::given(method("POST"))
+        .and(path(format!("{PARENT}/notebooks/nb1/sources:batchDelete")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let client = client(&server);
+
+    for id in ["nb1", "nb2"] {
+        client.get_source(id, "s1").await.unwrap();
+    }
+    client
+        .delete_sources(
+            "nb1",
+            vec![format!("{NAME_PARENT}/notebooks/nb1/sources/other")],
+        )
+        .await
+        .unwrap();
+    for id in ["nb1", "nb2"] {
+        client.get_source(id, "s1").await.unwrap();
+    }
+}
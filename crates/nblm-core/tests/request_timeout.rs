@@ -0,0 +1,84 @@
+//! Transport failures come back as `Error::Timeout` or `Error::Connect`, saying what was sent.
+
+mod common;
+
+use std::time::Duration;
+
+use nblm_core::{Error, NblmClient, RetryConfig};
+use serde_json::json;
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn client(base_url: &str, timeout: Duration) -> NblmClient {
+    common::client(base_url)
+        .with_retry_config(RetryConfig::default().with_max_retries(0))
+        .with_timeout(timeout)
+}
+
+#[tokio::test]
+async fn slow_response_is_a_timeout_naming_the_templated_path() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "title": "late" }))
+                .set_delay(Duration::from_secs(5)),
+        )
+        .mount(&server)
+        .await;
+    let client = client(
+        &format!("{}/v1alpha", server.uri()),
+        Duration::from_millis(200),
+    );
+
+    let err = client.get_notebook("nb-123").await.unwrap_err();
+
+    let Error::Timeout {
+        method,
+        path,
+        timeout,
+        elapsed,
+    } = &err
+    else {
+        panic!("expected Timeout, got {err:?}");
+    };
+    assert_eq!(method, "GET");
+    assert_eq!(
+        path,
+        "/v1alpha/projects/{project}/locations/{location}/notebooks/{notebook}"
+    );
+    assert_eq!(*timeout, Duration::from_millis(200));
+    assert!(*elapsed >= Duration::from_millis(150), "{elapsed:?}");
+    let message = err.to_string();
+    assert!(
+        message.starts_with(
+            "GET /v1alpha/projects/{project}/locations/{location}/notebooks/{notebook} timed out after "
+        ),
+        "{message}"
+    );
+    assert!(message.ends_with("(timeout: 200ms)"), "{message}");
+    assert!(!message.contains("nb-123"), "{message}");
+}
+
+#[tokio::test]
+async fn refused_connection_is_a_connect_error_naming_the_host() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    drop(listener);
+    let client = client(
+        &format!("http://127.0.0.1:{port}/v1alpha"),
+        Duration::from_secs(5),
+    );
+
+    let err = client.list_recently_viewed(None).await.unwrap_err();
+
+    match &err {
+        Error::Connect { host, .. } => assert_eq!(host, &format!("127.0.0.1:{port}")),
+        other => panic!("expected Connect, got {other:?}"),
+    }
+    assert!(
+        err.to_string()
+            .starts_with(&format!("could not connect to 127.0.0.1:{port}: ")),
+        "{err}"
+    );
+}
@@ -0,0 +1,148 @@
+//! Response bodies are read up to a size limit and within the request timeout.
+
+mod common;
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use nblm_core::{ClientOptions, Error, DEFAULT_MAX_RESPONSE_SIZE};
+use serde_json::json;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Server answering every connection with `head` and then `rest`, which may never finish.
+async fn raw_server<F, Fut>(head: &'static str, rest: F) -> String
+where
+    F: Fn(tokio::net::TcpStream) -> Fut + Send + Sync + 'static,
+    Fut: std::future::Future<Output = ()> + Send,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let rest = Arc::new(rest);
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let rest = Arc::clone(&rest);
+            tokio::spawn(async move {
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                    match socket.read(&mut buf).await {
+                        Ok(0) | Err(_) => return,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                if socket.write_all(head.as_bytes()).await.is_ok() {
+                    rest(socket).await;
+                }
+            });
+        }
+    });
+    format!("http://{addr}")
+}
+
+fn assert_too_large(err: &Error, limit: usize, path: &str) {
+    match err {
+        Error::ResponseTooLarge { url, limit: actual } => {
+            assert_eq!(*actual, limit);
+            assert!(url.ends_with(path), "{url}");
+        }
+        other => panic!("expected ResponseTooLarge, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn bodies_over_the_limit_fail_with_the_url_and_limit() {
+    let server = MockServer::start().await;
+    let title = "x".repeat(64 * 1024);
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "title": title })))
+        .mount(&server)
+        .await;
+    let client =
+        common::client(&format!("{}/v1alpha", server.uri())).with_max_response_size(8 * 1024);
+
+    let err = client.get_notebook("nb1").await.unwrap_err();
+
+    assert_too_large(&err, 8 * 1024, "/notebooks/nb1");
+    let message = err.to_string();
+    assert!(message.contains("8192-byte limit"), "{message}");
+}
+
+#[tokio::test]
+async fn oversized_error_pages_are_not_buffered_either() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(502).set_body_string("<html>".repeat(10_000)))
+        .mount(&server)
+        .await;
+    let client = common::client(&format!("{}/v1alpha", server.uri())).with_options(ClientOptions {
+        retry: nblm_core::RetryConfig::default().with_max_retries(0),
+        max_response_size: Some(1024),
+        ..ClientOptions::default()
+    });
+
+    let err = client.get_notebook("nb1").await.unwrap_err();
+
+    assert_too_large(&err, 1024, "/notebooks/nb1");
+}
+
+#[tokio::test]
+async fn endless_chunked_bodies_stop_at_the_limit() {
+    let base = raw_server(
+        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ntransfer-encoding: chunked\r\n\r\n",
+        |mut socket| async move {
+            let chunk = format!("{:x}\r\n{}\r\n", 4096, " ".repeat(4096));
+            while socket.write_all(chunk.as_bytes()).await.is_ok() {}
+        },
+    )
+    .await;
+    let client = common::client(&format!("{base}/v1alpha")).with_max_response_size(64 * 1024);
+
+    let err = client.get_notebook("nb1").await.unwrap_err();
+
+    assert_too_large(&err, 64 * 1024, "/notebooks/nb1");
+}
+
+#[tokio::test]
+async fn the_timeout_covers_a_stalled_body() {
+    let base = raw_server(
+        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: 100\r\n\r\n{\"title\":",
+        |socket| async move {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            drop(socket);
+        },
+    )
+    .await;
+    let client = common::client(&format!("{base}/v1alpha"))
+        .with_retry_config(nblm_core::RetryConfig::default().with_max_retries(0))
+        .with_timeout(Duration::from_millis(300));
+
+    let started = Instant::now();
+    let err = client.get_notebook("nb1").await.unwrap_err();
+
+    assert!(
+        matches!(&err, Error::Timeout { method, .. } if method == "GET"),
+        "{err:?}"
+    );
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
+
+#[tokio::test]
+async fn large_responses_within_the_limit_are_read() {
+    let server = MockServer::start().await;
+    let notebooks: Vec<_> = (0..5_000)
+        .map(|i| json!({ "name": format!("projects/123/locations/global/notebooks/nb{i}"), "title": "t".repeat(200) }))
+        .collect();
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "notebooks": notebooks })))
+        .mount(&server)
+        .await;
+    let client = common::client(&format!("{}/v1alpha", server.uri()));
+    assert_eq!(client.max_response_size(), DEFAULT_MAX_RESPONSE_SIZE);
+
+    let listed = client.list_recently_viewed(None).await.unwrap();
+
+    assert_eq!(listed.notebooks.len(), 5_000);
+}
@@ -0,0 +1,65 @@
+//! The 403 `SERVICE_DISABLED` response a project without the Discovery Engine API gets.
+
+mod common;
+
+use std::path::PathBuf;
+
+use nblm_core::{EnvironmentConfig, Error};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn fixture() -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/errors/service_disabled.json");
+    std::fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("failed to read {}: {err}", path.display()))
+}
+
+#[tokio::test]
+async fn service_disabled_names_the_project_and_the_enable_command() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(
+            "/v1alpha/projects/123456/locations/global/notebooks:listRecentlyViewed",
+        ))
+        .respond_with(ResponseTemplate::new(403).set_body_raw(fixture(), "application/json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let env = EnvironmentConfig::enterprise("123456", "global", "global").unwrap();
+    let client = common::client_in(env, &format!("{}/v1alpha", server.uri()));
+
+    let err = client.list_recently_viewed(None).await.unwrap_err();
+
+    let activation =
+        "https://console.developers.google.com/apis/api/discoveryengine.googleapis.com/overview?project=123456";
+    match &err {
+        Error::ServiceDisabled {
+            service,
+            service_title,
+            project,
+            activation_url,
+            body,
+        } => {
+            assert_eq!(service, "discoveryengine.googleapis.com");
+            assert_eq!(service_title, "Discovery Engine API");
+            assert_eq!(project.as_deref(), Some("123456"));
+            assert_eq!(activation_url.as_deref(), Some(activation));
+            assert!(body.contains("PERMISSION_DENIED"));
+        }
+        other => panic!("expected ServiceDisabled, got {other:?}"),
+    }
+    assert_eq!(
+        err.enable_service_command().as_deref(),
+        Some("gcloud services enable discoveryengine.googleapis.com --project 123456")
+    );
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Discovery Engine API is not enabled for project 123456. Enable it with:\n  \
+             gcloud services enable discoveryengine.googleapis.com --project 123456\n\
+             or in the Cloud console: {activation}\n\
+             If you enabled it recently, wait a few minutes and retry."
+        )
+    );
+}
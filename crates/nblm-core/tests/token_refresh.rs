@@ -0,0 +1,71 @@
+//! Concurrent requests that all get a 401 for an expired token share one refresh.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use nblm_core::{EnvironmentConfig, NblmClient, RetryConfig, TokenProvider};
+use serde_json::json;
+use wiremock::matchers::{bearer_token, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const RECENT: &str = "/v1alpha/projects/123/locations/global/notebooks:listRecentlyViewed";
+
+/// Hands out `old` until refreshed, then `new`; each refresh is slow and counted.
+struct CountingProvider {
+    token: Mutex<String>,
+    refreshes: AtomicUsize,
+}
+
+#[async_trait]
+impl TokenProvider for CountingProvider {
+    async fn access_token(&self) -> nblm_core::Result<String> {
+        Ok(self.token.lock().unwrap().clone())
+    }
+
+    async fn refresh_token(&self) -> nblm_core::Result<String> {
+        self.refreshes.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let token = "new".to_string();
+        *self.token.lock().unwrap() = token.clone();
+        Ok(token)
+    }
+}
+
+#[tokio::test]
+async fn concurrent_401s_trigger_a_single_refresh() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(RECENT))
+        .and(bearer_token("old"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(RECENT))
+        .and(bearer_token("new"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"notebooks": []})))
+        .expect(10)
+        .mount(&server)
+        .await;
+
+    let provider = Arc::new(CountingProvider {
+        token: Mutex::new("old".to_string()),
+        refreshes: AtomicUsize::new(0),
+    });
+    let env = EnvironmentConfig::enterprise("123", "global", "global").unwrap();
+    let client = NblmClient::new(provider.clone(), env)
+        .unwrap()
+        .with_retry_config(RetryConfig::default().with_max_retries(0))
+        .with_base_url(format!("{}/v1alpha", server.uri()))
+        .unwrap();
+
+    let results =
+        futures::future::join_all((0..10).map(|_| client.list_recently_viewed(None))).await;
+
+    for result in results {
+        result.unwrap();
+    }
+    assert_eq!(provider.refreshes.load(Ordering::SeqCst), 1);
+}
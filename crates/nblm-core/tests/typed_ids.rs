@@ -0,0 +1,55 @@
+//! Client methods accept both plain strings and the typed ID newtypes.
+
+mod common;
+
+use nblm_core::{Error, NblmClient, NotebookId, SourceId};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const SOURCE_PATH: &str = "/v1alpha/projects/123/locations/global/notebooks/nb1/sources/src1";
+
+async fn client_against(server: &MockServer) -> NblmClient {
+    Mock::given(method("GET"))
+        .and(path(SOURCE_PATH))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "name": "projects/123/locations/global/notebooks/nb1/sources/src1",
+        })))
+        .mount(server)
+        .await;
+    common::mock_client(server)
+}
+
+#[tokio::test]
+async fn string_and_typed_ids_reach_the_same_endpoint() {
+    let server = MockServer::start().await;
+    let client = client_against(&server).await;
+
+    // Existing call sites keep passing strings.
+    let notebook = String::from("nb1");
+    client.get_source("nb1", "src1").await.unwrap();
+    client
+        .get_source(&notebook, "src1".to_string())
+        .await
+        .unwrap();
+
+    // New code can use the typed form; swapping the arguments would not compile.
+    let notebook_id = NotebookId::parse("nb1").unwrap();
+    let source_id = SourceId::parse("src1").unwrap();
+    client.get_source(&notebook_id, &source_id).await.unwrap();
+    client.get_source(notebook_id, source_id).await.unwrap();
+
+    assert_eq!(server.received_requests().await.unwrap().len(), 4);
+}
+
+#[tokio::test]
+async fn invalid_ids_are_rejected_before_any_request() {
+    let server = MockServer::start().await;
+    let client = client_against(&server).await;
+
+    let err = client.get_source("nb1/sources", "src1").await.unwrap_err();
+    assert!(matches!(err, Error::Validation(_)), "{err}");
+    let err = client.get_source("nb1", "").await.unwrap_err();
+    assert!(matches!(err, Error::Validation(_)), "{err}");
+
+    assert!(server.received_requests().await.unwrap().is_empty());
+}
@@ -1,10 +1,11 @@
-use pyo3::exceptions::{PyIOError, PyRuntimeError, PyValueError};
+use async_trait::async_trait;
+use pyo3::exceptions::{PyIOError, PyRuntimeError, PyTypeError, PyValueError};
 use pyo3::prelude::*;
 use reqwest::Client;
 use std::sync::Arc;
 
 use crate::error::PyResult;
-use crate::runtime::block_on_with_runtime;
+use crate::runtime::{block_on_with_runtime, in_token_callback};
 use nblm_core::auth::oauth::{
     FileRefreshTokenStore, OAuthClientConfig, OAuthError, OAuthFlow, RefreshTokenProvider,
     TokenStoreKey,
@@ -66,6 +67,109 @@ impl TokenProvider for EnvTokenProvider {
     }
 }
 
+#[pyclass(module = "nblm", skip_from_py_object)]
+#[derive(Clone)]
+pub struct MetadataTokenProvider {
+    inner: Arc<nblm_core::MetadataTokenProvider>,
+}
+
+#[pymethods]
+impl MetadataTokenProvider {
+    #[new]
+    #[pyo3(signature = (host = None))]
+    pub fn new(host: Option<String>) -> Self {
+        let provider = match host {
+            Some(host) => nblm_core::MetadataTokenProvider::with_host(host),
+            None => nblm_core::MetadataTokenProvider::new(),
+        };
+        Self {
+            inner: Arc::new(provider),
+        }
+    }
+
+    #[getter]
+    pub fn host(&self) -> &str {
+        self.inner.host()
+    }
+}
+
+impl TokenProvider for MetadataTokenProvider {
+    fn get_inner(&self) -> Arc<dyn nblm_core::TokenProvider> {
+        self.inner.clone()
+    }
+}
+
+/// Token provider that calls a Python function for every access token.
+///
+/// The function takes no arguments and returns the token as a `str`. It runs on a worker
+/// thread while the client waits with the GIL released, so it must be synchronous and must
+/// not use an `NblmClient` itself.
+#[pyclass(module = "nblm", skip_from_py_object)]
+#[derive(Clone)]
+pub struct CallbackTokenProvider {
+    inner: Arc<PythonCallback>,
+}
+
+#[pymethods]
+impl CallbackTokenProvider {
+    #[new]
+    pub fn new(callback: Bound<'_, PyAny>) -> PyResult<Self> {
+        if !callback.is_callable() {
+            return Err(PyTypeError::new_err(
+                "CallbackTokenProvider expects a callable returning an access token",
+            ));
+        }
+        Ok(Self {
+            inner: Arc::new(PythonCallback {
+                callback: Arc::new(callback.unbind()),
+            }),
+        })
+    }
+}
+
+impl TokenProvider for CallbackTokenProvider {
+    fn get_inner(&self) -> Arc<dyn nblm_core::TokenProvider> {
+        self.inner.clone()
+    }
+}
+
+struct PythonCallback {
+    callback: Arc<Py<PyAny>>,
+}
+
+#[async_trait]
+impl nblm_core::TokenProvider for PythonCallback {
+    async fn access_token(&self) -> nblm_core::Result<String> {
+        // Taking the GIL may wait on other Python threads, so keep it off the async workers.
+        let callback = Arc::clone(&self.callback);
+        tokio::task::spawn_blocking(move || Python::attach(|py| call_token_callback(py, &callback)))
+            .await
+            .map_err(|err| {
+                CoreError::TokenProvider(format!("token callback did not finish: {err}"))
+            })?
+    }
+}
+
+fn call_token_callback(py: Python<'_>, callback: &Py<PyAny>) -> nblm_core::Result<String> {
+    let token = in_token_callback(|| callback.bind(py).call0())
+        .map_err(|err| CoreError::TokenProvider(err.value(py).to_string()))?;
+    if token.hasattr("__await__").unwrap_or(false) {
+        // Close the coroutine so Python does not warn that it was never awaited.
+        let _ = token.call_method0("close");
+        return Err(CoreError::TokenProvider(
+            "token callback returned an awaitable; pass a synchronous function".to_string(),
+        ));
+    }
+    token.extract::<String>().map_err(|_| {
+        let type_name = token
+            .get_type()
+            .name()
+            .map(|name| name.to_string())
+            .unwrap_or_else(|_| "unknown".to_string());
+        CoreError::TokenProvider(format!("token callback must return str, got {type_name}"))
+    })
+}
+
 #[pyclass(module = "nblm")]
 #[derive(Clone)]
 pub struct UserOAuthProvider {
@@ -137,6 +241,8 @@ pub(crate) enum PyTokenProvider {
     Gcloud(GcloudTokenProvider),
     Env(EnvTokenProvider),
     User(UserOAuthProvider),
+    Metadata(MetadataTokenProvider),
+    Callback(CallbackTokenProvider),
 }
 
 impl PyTokenProvider {
@@ -145,6 +251,8 @@ impl PyTokenProvider {
             PyTokenProvider::Gcloud(p) => p.get_inner(),
             PyTokenProvider::Env(p) => p.get_inner(),
             PyTokenProvider::User(p) => p.get_inner(),
+            PyTokenProvider::Metadata(p) => p.get_inner(),
+            PyTokenProvider::Callback(p) => p.get_inner(),
         }
     }
 }
@@ -162,6 +270,12 @@ impl<'a, 'py> FromPyObject<'a, 'py> for PyTokenProvider {
         if let Ok(p) = ob.extract::<UserOAuthProvider>() {
             return Ok(PyTokenProvider::User(p));
         }
+        if let Ok(p) = ob.extract::<PyRef<'py, MetadataTokenProvider>>() {
+            return Ok(PyTokenProvider::Metadata(p.clone()));
+        }
+        if let Ok(p) = ob.extract::<PyRef<'py, CallbackTokenProvider>>() {
+            return Ok(PyTokenProvider::Callback(p.clone()));
+        }
         Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
             "Expected a TokenProvider instance",
         ))
@@ -1,22 +1,25 @@
 use pyo3::prelude::*;
-use std::fs;
-use std::future::Future;
+use pyo3::types::PyDict;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::auth::{PyTokenProvider, TokenProvider, UserOAuthProvider};
-use crate::error::{map_nblm_error, map_runtime_error, IntoPyResult, PyResult};
+use crate::error::{map_nblm_error, IntoPyResult, NblmValidationError, PyResult};
 use crate::models::{
     AudioOverviewRequest, AudioOverviewResponse, BatchCreateSourcesResponse,
     BatchDeleteNotebooksResponse, BatchDeleteSourcesResponse, GoogleDriveSource,
-    ListRecentlyViewedResponse, Notebook, NotebookSource, TextSource, UploadSourceFileResponse,
-    VideoSource, WebSource,
+    ListRecentlyViewedResponse, Notebook, NotebookSource, NotebookWordBudget, TextSource,
+    UploadSourceFileResponse, VideoSource, WebSource,
 };
+use crate::runtime::block_on_with_runtime;
 use nblm_core::env::{profile_experiment_enabled, PROFILE_EXPERIMENT_FLAG};
 use nblm_core::models::enterprise::source::{
-    GoogleDriveContent, TextContent, UserContent, VideoContent, WebContent,
+    normalize_source_name, GoogleDriveContent, TextContent, UserContent, VideoContent,
+};
+use nblm_core::{
+    client_options_from_env, ApiProfile, EnvironmentConfig, NotebookId, ProfileParams, SourceId,
 };
-use nblm_core::{ApiProfile, EnvironmentConfig, ProfileParams};
 
 #[pyclass(module = "nblm")]
 pub struct NblmClient {
@@ -26,23 +29,45 @@ pub struct NblmClient {
 
 #[pymethods]
 impl NblmClient {
+    /// Retry and timeout settings default to `NBLM_MAX_RETRIES`, `NBLM_RETRY_MIN_DELAY`,
+    /// `NBLM_RETRY_MAX_DELAY` and `NBLM_TIMEOUT`; the keyword arguments (seconds) take precedence.
+    /// `language` is a BCP 47 tag sent as `Accept-Language`.
     #[new]
-    #[pyo3(signature = (token_provider, project_number=None, location = "global".to_string(), endpoint_location = "global".to_string(), profile = "enterprise".to_string()))]
+    #[pyo3(signature = (token_provider, project_number=None, location = "global".to_string(), endpoint_location = "global".to_string(), profile = "enterprise".to_string(), *, max_retries=None, retry_min_delay=None, retry_max_delay=None, timeout=None, language=None))]
+    #[allow(clippy::too_many_arguments)]
     fn new(
         token_provider: PyTokenProvider,
         project_number: Option<String>,
         location: String,
         endpoint_location: String,
         profile: String,
+        max_retries: Option<usize>,
+        retry_min_delay: Option<f64>,
+        retry_max_delay: Option<f64>,
+        timeout: Option<f64>,
+        language: Option<String>,
     ) -> PyResult<Self> {
         let provider = token_provider.get_inner();
         let profile = ApiProfile::parse(&profile).into_py_result()?;
+        let overrides = ClientOverrides {
+            max_retries,
+            retry_min_delay: seconds("retry_min_delay", retry_min_delay)?,
+            retry_max_delay: seconds("retry_max_delay", retry_max_delay)?,
+            timeout: seconds("timeout", timeout)?,
+            language,
+        };
+        if overrides.timeout == Some(Duration::ZERO) {
+            return Err(NblmValidationError::new_err(
+                "timeout must be greater than zero",
+            ));
+        }
         Self::create_with_provider(
             provider,
             project_number,
             location,
             endpoint_location,
             profile,
+            overrides,
         )
     }
 
@@ -72,6 +97,7 @@ impl NblmClient {
             location,
             resolved_endpoint,
             ApiProfile::Enterprise,
+            ClientOverrides::default(),
         )
     }
 
@@ -79,20 +105,84 @@ impl NblmClient {
         "NblmClient()".to_string()
     }
 
+    /// Retry, timeout and base URL settings in effect, after environment and keyword overrides.
+    ///
+    /// Durations are in seconds. Returns a new dict on each access.
+    #[getter]
+    fn effective_config(&self, py: Python) -> PyResult<Py<PyDict>> {
+        let retry = self.inner.retry_config();
+        let config = PyDict::new(py);
+        config.set_item("max_retries", retry.max_retries)?;
+        config.set_item("retry_min_delay", retry.min_delay.as_secs_f64())?;
+        config.set_item("retry_max_delay", retry.max_delay.as_secs_f64())?;
+        config.set_item("timeout", self.inner.timeout().as_secs_f64())?;
+        config.set_item("base_url", self.inner.environment().base_url())?;
+        Ok(config.unbind())
+    }
+
     /// Create a new notebook with the given title.
     ///
     /// Args:
     ///     title: The title of the notebook
+    ///     emoji: Optional emoji shown next to the notebook title: a single emoji, or a
+    ///         shortcode such as ":rocket:" that is replaced by its emoji
     ///
     /// Returns:
     ///     Notebook: The created notebook
     ///
     /// Raises:
+    ///     NblmValidationError: If emoji is not a single emoji or a known shortcode
     ///     NblmError: If the notebook creation fails
-    fn create_notebook(&self, py: Python, title: String) -> PyResult<Notebook> {
+    #[pyo3(signature = (title, emoji = None))]
+    fn create_notebook(
+        &self,
+        py: Python,
+        title: String,
+        emoji: Option<String>,
+    ) -> PyResult<Notebook> {
+        let emoji = emoji_arg(emoji)?;
         let inner = self.inner.clone();
         py.detach(move || {
-            let future = async move { inner.create_notebook(title).await };
+            let future = async move { inner.create_notebook_with_emoji(title, emoji).await };
+            let result = block_on_with_runtime(future)?;
+            Python::attach(|py| Notebook::from_core(py, result))
+        })
+    }
+
+    /// Update a notebook's title and/or emoji.
+    ///
+    /// Only the provided fields are sent to the API (via the PATCH updateMask).
+    ///
+    /// Args:
+    ///     notebook_id: Notebook identifier (notebook resource ID, not full name)
+    ///     title: New title, or None to keep the current one
+    ///     emoji: New emoji (or a shortcode such as ":books:"), or None to keep the current one
+    ///
+    /// Returns:
+    ///     Notebook: The updated notebook
+    ///
+    /// Raises:
+    ///     NblmValidationError: If neither title nor emoji is provided, or emoji is not a
+    ///         single emoji or a known shortcode
+    ///     NblmError: If the request fails
+    #[pyo3(signature = (notebook_id, title = None, emoji = None))]
+    fn update_notebook(
+        &self,
+        py: Python,
+        notebook_id: String,
+        title: Option<String>,
+        emoji: Option<String>,
+    ) -> PyResult<Notebook> {
+        let notebook_id = notebook_id_arg(notebook_id)?;
+        if title.is_none() && emoji.is_none() {
+            return Err(NblmValidationError::new_err(
+                "at least one of title or emoji must be provided",
+            ));
+        }
+        let emoji = emoji_arg(emoji)?;
+        let inner = self.inner.clone();
+        py.detach(move || {
+            let future = async move { inner.update_notebook(&notebook_id, title, emoji).await };
             let result = block_on_with_runtime(future)?;
             Python::attach(|py| Notebook::from_core(py, result))
         })
@@ -122,6 +212,30 @@ impl NblmClient {
         })
     }
 
+    /// Sum the word counts of every source in a notebook.
+    ///
+    /// Args:
+    ///     notebook_id: Notebook identifier (notebook resource ID, not full name)
+    ///
+    /// Returns:
+    ///     NotebookWordBudget: Total word count and per-source breakdown (largest first)
+    ///
+    /// Raises:
+    ///     NblmError: If the request fails
+    fn notebook_word_budget(
+        &self,
+        py: Python,
+        notebook_id: String,
+    ) -> PyResult<NotebookWordBudget> {
+        let notebook_id = notebook_id_arg(notebook_id)?;
+        let inner = self.inner.clone();
+        py.detach(move || {
+            let future = async move { inner.notebook_word_budget(&notebook_id).await };
+            let result = block_on_with_runtime(future)?;
+            Python::attach(|py| NotebookWordBudget::from_core(py, result))
+        })
+    }
+
     /// Delete one or more notebooks.
     ///
     /// Args:
@@ -178,6 +292,7 @@ impl NblmClient {
         drive_sources: Option<Vec<GoogleDriveSource>>,
         video_sources: Option<Vec<VideoSource>>,
     ) -> PyResult<BatchCreateSourcesResponse> {
+        let notebook_id = notebook_id_arg(notebook_id)?;
         let inner = self.inner.clone();
         py.detach(move || {
             let future = async move {
@@ -185,12 +300,8 @@ impl NblmClient {
 
                 if let Some(sources) = web_sources {
                     for source in sources {
-                        contents.push(UserContent::Web {
-                            web_content: WebContent {
-                                url: source.url,
-                                source_name: source.name,
-                            },
-                        });
+                        let source_name = normalize_source_name(source.name.as_deref())?;
+                        contents.push(UserContent::web(source.url, source_name));
                     }
                 }
 
@@ -201,10 +312,11 @@ impl NblmClient {
                                 "text content cannot be empty",
                             ));
                         }
+                        let source_name = normalize_source_name(source.name.as_deref())?;
                         contents.push(UserContent::Text {
                             text_content: TextContent {
                                 content: source.content,
-                                source_name: source.name,
+                                source_name,
                             },
                         });
                     }
@@ -226,14 +338,7 @@ impl NblmClient {
                             ));
                         }
 
-                        let source_name = source.name.as_ref().and_then(|name| {
-                            let trimmed = name.trim();
-                            if trimmed.is_empty() {
-                                None
-                            } else {
-                                Some(trimmed.to_string())
-                            }
-                        });
+                        let source_name = normalize_source_name(source.name.as_deref())?;
 
                         contents.push(UserContent::GoogleDrive {
                             google_drive_content: GoogleDriveContent {
@@ -289,25 +394,11 @@ impl NblmClient {
         content_type: Option<String>,
         display_name: Option<String>,
     ) -> PyResult<UploadSourceFileResponse> {
-        if !path.exists() {
-            return Err(map_nblm_error(nblm_core::Error::validation(format!(
-                "file not found: {}",
-                path.display()
-            ))));
-        }
-        if !path.is_file() {
-            return Err(map_nblm_error(nblm_core::Error::validation(format!(
-                "path is not a file: {}",
-                path.display()
-            ))));
-        }
-
-        let data = fs::read(&path).map_err(PyErr::from)?;
-        if data.is_empty() {
-            return Err(map_nblm_error(nblm_core::Error::validation(
-                "cannot upload empty files",
-            )));
-        }
+        let notebook_id = notebook_id_arg(notebook_id)?;
+        let data = nblm_core::read_upload_file(&path, None).map_err(|err| match err {
+            nblm_core::UploadFileError::Io { source, .. } => PyErr::from(source),
+            err => map_nblm_error(nblm_core::Error::validation(err.to_string())),
+        })?;
 
         let file_name = if let Some(name) = display_name {
             let trimmed = name.trim();
@@ -337,12 +428,7 @@ impl NblmClient {
         let content_type = content_type
             .map(|value| value.trim().to_string())
             .filter(|value| !value.is_empty())
-            .unwrap_or_else(|| {
-                mime_guess::from_path(&path)
-                    .first_or_octet_stream()
-                    .essence_str()
-                    .to_string()
-            });
+            .unwrap_or_else(|| nblm_core::infer_content_type(&path, &Default::default()));
 
         let inner = self.inner.clone();
         py.detach(move || {
@@ -373,6 +459,7 @@ impl NblmClient {
         notebook_id: String,
         source_names: Vec<String>,
     ) -> PyResult<BatchDeleteSourcesResponse> {
+        let notebook_id = notebook_id_arg(notebook_id)?;
         let inner = self.inner.clone();
         py.detach(move || {
             let future = async move { inner.delete_sources(&notebook_id, source_names).await };
@@ -398,6 +485,8 @@ impl NblmClient {
         notebook_id: String,
         source_id: String,
     ) -> PyResult<NotebookSource> {
+        let notebook_id = notebook_id_arg(notebook_id)?;
+        let source_id = source_id_arg(source_id)?;
         let inner = self.inner.clone();
         py.detach(move || {
             let future = async move { inner.get_source(&notebook_id, &source_id).await };
@@ -429,6 +518,7 @@ impl NblmClient {
         notebook_id: String,
         request: Option<AudioOverviewRequest>,
     ) -> PyResult<AudioOverviewResponse> {
+        let notebook_id = notebook_id_arg(notebook_id)?;
         let inner = self.inner.clone();
         let req = request.unwrap_or_default();
         py.detach(move || {
@@ -453,6 +543,7 @@ impl NblmClient {
     /// Raises:
     ///     NblmError: If the request fails
     fn delete_audio_overview(&self, py: Python, notebook_id: String) -> PyResult<()> {
+        let notebook_id = notebook_id_arg(notebook_id)?;
         let inner = self.inner.clone();
         py.detach(move || {
             let future = async move { inner.delete_audio_overview(&notebook_id).await };
@@ -507,20 +598,43 @@ fn build_profile_params(
     })
 }
 
-fn block_on_with_runtime<F, T>(future: F) -> PyResult<T>
-where
-    F: Future<Output = Result<T, nblm_core::Error>> + Send + 'static,
-    T: Send + 'static,
-{
-    if let Ok(handle) = tokio::runtime::Handle::try_current() {
-        return handle.block_on(future).into_py_result();
-    }
+/// Constructor keyword arguments that override the `NBLM_*` retry and timeout variables.
+#[derive(Default)]
+struct ClientOverrides {
+    max_retries: Option<usize>,
+    retry_min_delay: Option<Duration>,
+    retry_max_delay: Option<Duration>,
+    timeout: Option<Duration>,
+    language: Option<String>,
+}
+
+fn seconds(name: &str, value: Option<f64>) -> PyResult<Option<Duration>> {
+    value
+        .map(|secs| {
+            Duration::try_from_secs_f64(secs).map_err(|_| {
+                NblmValidationError::new_err(format!(
+                    "{name} must be a non-negative number of seconds (got {secs})"
+                ))
+            })
+        })
+        .transpose()
+}
+
+/// Check an ID argument up front, so a bad one raises `NblmValidationError` before any request.
+fn notebook_id_arg(id: String) -> PyResult<NotebookId> {
+    NotebookId::parse(id).map_err(map_nblm_error)
+}
 
-    let runtime = tokio::runtime::Builder::new_multi_thread()
-        .enable_all()
-        .build()
-        .map_err(map_runtime_error)?;
-    runtime.block_on(future).into_py_result()
+fn source_id_arg(id: String) -> PyResult<SourceId> {
+    SourceId::parse(id).map_err(map_nblm_error)
+}
+
+fn emoji_arg(emoji: Option<String>) -> PyResult<Option<String>> {
+    emoji
+        .as_deref()
+        .map(nblm_core::normalize_emoji)
+        .transpose()
+        .map_err(map_nblm_error)
 }
 
 impl NblmClient {
@@ -530,12 +644,37 @@ impl NblmClient {
         location: String,
         endpoint_location: String,
         profile: ApiProfile,
+        overrides: ClientOverrides,
     ) -> PyResult<Self> {
         ensure_profile_allowed(profile)?;
         ensure_token_provider_allowed(&provider)?;
         let params = build_profile_params(profile, project_number, location, endpoint_location)?;
         let environment = EnvironmentConfig::from_profile(profile, params).into_py_result()?;
-        let client = nblm_core::NblmClient::new(provider, environment).into_py_result()?;
+        let mut client = nblm_core::NblmClient::new(provider, environment).into_py_result()?;
+        // Same override as the CLI's hidden --base-url flag; lets tests target a mock server.
+        if let Some(base_url) = std::env::var("NBLM_BASE_URL")
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+        {
+            client = client.with_base_url(base_url).into_py_result()?;
+        }
+        let mut options = client_options_from_env().into_py_result()?;
+        if let Some(retries) = overrides.max_retries {
+            options.retry = options.retry.with_max_retries(retries);
+        }
+        if let Some(delay) = overrides.retry_min_delay {
+            options.retry = options.retry.with_min_delay(delay);
+        }
+        if let Some(delay) = overrides.retry_max_delay {
+            options.retry = options.retry.with_max_delay(delay);
+        }
+        if let Some(timeout) = overrides.timeout {
+            options.timeout = Some(timeout);
+        }
+        client = client.with_options(options);
+        if let Some(language) = &overrides.language {
+            client = client.with_accept_language(language).into_py_result()?;
+        }
 
         Ok(Self {
             inner: Arc::new(client),
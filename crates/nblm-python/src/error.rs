@@ -4,11 +4,21 @@ use pyo3::exceptions::PyRuntimeError;
 use pyo3::prelude::*;
 
 create_exception!(nblm, NblmError, PyException);
+create_exception!(nblm, NblmValidationError, NblmError);
+create_exception!(nblm, NblmAuthError, NblmError);
 
 pub type PyResult<T> = Result<T, PyErr>;
 
 pub(crate) fn map_nblm_error(err: nblm_core::Error) -> PyErr {
-    NblmError::new_err(err.to_string())
+    match err {
+        nblm_core::Error::Validation(_) | nblm_core::Error::InvalidEnv { .. } => {
+            NblmValidationError::new_err(err.to_string())
+        }
+        nblm_core::Error::TokenProvider(_) | nblm_core::Error::InteractionRequired { .. } => {
+            NblmAuthError::new_err(err.to_string())
+        }
+        _ => NblmError::new_err(err.to_string()),
+    }
 }
 
 pub(crate) fn map_runtime_error(err: impl std::fmt::Display) -> PyErr {
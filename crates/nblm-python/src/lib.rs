@@ -7,17 +7,18 @@ mod models;
 mod runtime;
 
 pub use auth::{
-    login, EnvTokenProvider, GcloudTokenProvider, TokenProvider, UserOAuthProvider,
-    DEFAULT_ENV_TOKEN_KEY, DEFAULT_GCLOUD_BINARY,
+    login, CallbackTokenProvider, EnvTokenProvider, GcloudTokenProvider, MetadataTokenProvider,
+    TokenProvider, UserOAuthProvider, DEFAULT_ENV_TOKEN_KEY, DEFAULT_GCLOUD_BINARY,
 };
 pub use client::NblmClient;
-pub use error::NblmError;
+pub use error::{NblmAuthError, NblmError, NblmValidationError};
 pub use models::{
     AudioOverviewRequest, AudioOverviewResponse, BatchCreateSourcesResponse,
     BatchDeleteNotebooksResponse, BatchDeleteSourcesResponse, GoogleDriveSource,
     ListRecentlyViewedResponse, Notebook, NotebookMetadata, NotebookSource, NotebookSourceId,
-    NotebookSourceMetadata, NotebookSourceSettings, NotebookSourceYoutubeMetadata, TextSource,
-    UploadSourceFileResponse, VideoSource, WebSource,
+    NotebookSourceMetadata, NotebookSourceSettings, NotebookSourceYoutubeMetadata,
+    NotebookWordBudget, SourceWordCount, TextSource, UploadSourceFileResponse, VideoSource,
+    WebSource,
 };
 
 /// NotebookLM Enterprise API client for Python
@@ -28,6 +29,8 @@ fn nblm(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<GcloudTokenProvider>()?;
     m.add_class::<EnvTokenProvider>()?;
     m.add_class::<UserOAuthProvider>()?;
+    m.add_class::<MetadataTokenProvider>()?;
+    m.add_class::<CallbackTokenProvider>()?;
     m.add_class::<Notebook>()?;
     m.add_class::<NotebookMetadata>()?;
     m.add_class::<NotebookSource>()?;
@@ -44,11 +47,22 @@ fn nblm(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<BatchDeleteSourcesResponse>()?;
     m.add_class::<ListRecentlyViewedResponse>()?;
     m.add_class::<BatchDeleteNotebooksResponse>()?;
+    m.add_class::<NotebookWordBudget>()?;
+    m.add_class::<SourceWordCount>()?;
     m.add_class::<AudioOverviewRequest>()?;
     m.add_class::<AudioOverviewResponse>()?;
     m.add("NblmError", m.py().get_type::<NblmError>())?;
+    m.add(
+        "NblmValidationError",
+        m.py().get_type::<NblmValidationError>(),
+    )?;
+    m.add("NblmAuthError", m.py().get_type::<NblmAuthError>())?;
     m.add("DEFAULT_GCLOUD_BINARY", DEFAULT_GCLOUD_BINARY)?;
     m.add("DEFAULT_ENV_TOKEN_KEY", DEFAULT_ENV_TOKEN_KEY)?;
+    m.add(
+        "DEFAULT_WORD_BUDGET",
+        nblm_core::models::enterprise::budget::DEFAULT_WORD_BUDGET,
+    )?;
 
     Ok(())
 }
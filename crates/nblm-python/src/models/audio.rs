@@ -3,7 +3,9 @@ use pyo3::types::PyDict;
 
 use crate::error::PyResult;
 
-use super::extra_to_pydict;
+use serde_json::json;
+
+use super::{extra_to_pydict, json_value_to_py, py_to_json_value};
 
 /// Request for creating an audio overview.
 ///
@@ -61,6 +63,18 @@ pub struct AudioOverviewResponse {
 
 #[pymethods]
 impl AudioOverviewResponse {
+    /// Convert to plain Python dicts, lists and scalars, recursing into nested models.
+    pub fn to_dict(&self, py: Python) -> PyResult<Py<PyAny>> {
+        let value = json!({
+            "audio_overview_id": self.audio_overview_id,
+            "name": self.name,
+            "status": self.status,
+            "generation_options": py_to_json_value(self.generation_options.bind(py).as_any())?,
+            "extra": py_to_json_value(self.extra.bind(py).as_any())?,
+        });
+        json_value_to_py(py, &value)
+    }
+
     pub fn __repr__(&self) -> String {
         format!(
             "AudioOverviewResponse(audio_overview_id={:?}, name={:?}, status={:?})",
@@ -0,0 +1,118 @@
+use pyo3::prelude::*;
+use pyo3::types::PyList;
+
+use crate::error::PyResult;
+use serde_json::json;
+
+use super::{json_value_to_py, py_to_json_value};
+
+use nblm_core::models::enterprise::budget::DEFAULT_WORD_BUDGET;
+
+#[pyclass(module = "nblm")]
+pub struct SourceWordCount {
+    #[pyo3(get)]
+    pub name: String,
+    #[pyo3(get)]
+    pub title: Option<String>,
+    #[pyo3(get)]
+    pub word_count: Option<u64>,
+}
+
+#[pymethods]
+impl SourceWordCount {
+    /// Convert to plain Python dicts, lists and scalars, recursing into nested models.
+    pub fn to_dict(&self, py: Python) -> PyResult<Py<PyAny>> {
+        let value = json!({
+            "name": self.name,
+            "title": self.title,
+            "word_count": self.word_count,
+        });
+        json_value_to_py(py, &value)
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "SourceWordCount(name={:?}, title={:?}, word_count={:?})",
+            self.name, self.title, self.word_count
+        )
+    }
+
+    pub fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
+
+impl SourceWordCount {
+    pub fn from_core(source: nblm_core::models::enterprise::budget::SourceWordCount) -> Self {
+        Self {
+            name: source.name,
+            title: source.title,
+            word_count: source.word_count,
+        }
+    }
+}
+
+#[pyclass(module = "nblm")]
+pub struct NotebookWordBudget {
+    #[pyo3(get)]
+    pub notebook_id: String,
+    #[pyo3(get)]
+    pub total_words: u64,
+    #[pyo3(get)]
+    pub sources: Py<PyList>,
+    #[pyo3(get)]
+    pub missing_word_counts: usize,
+}
+
+#[pymethods]
+impl NotebookWordBudget {
+    /// Convert to plain Python dicts, lists and scalars, recursing into nested models.
+    pub fn to_dict(&self, py: Python) -> PyResult<Py<PyAny>> {
+        let value = json!({
+            "notebook_id": self.notebook_id,
+            "total_words": self.total_words,
+            "sources": py_to_json_value(self.sources.bind(py).as_any())?,
+            "missing_word_counts": self.missing_word_counts,
+        });
+        json_value_to_py(py, &value)
+    }
+
+    /// Return the number of words above `budget`, or None when the notebook fits.
+    #[pyo3(signature = (budget = DEFAULT_WORD_BUDGET))]
+    pub fn over_budget(&self, budget: u64) -> Option<u64> {
+        self.total_words
+            .checked_sub(budget)
+            .filter(|over| *over > 0)
+    }
+
+    pub fn __repr__(&self, py: Python) -> String {
+        format!(
+            "NotebookWordBudget(notebook_id={:?}, total_words={}, sources={} items)",
+            self.notebook_id,
+            self.total_words,
+            self.sources.bind(py).len()
+        )
+    }
+
+    pub fn __str__(&self, py: Python) -> String {
+        self.__repr__(py)
+    }
+}
+
+impl NotebookWordBudget {
+    pub fn from_core(
+        py: Python,
+        summary: nblm_core::models::enterprise::budget::NotebookWordBudget,
+    ) -> PyResult<Self> {
+        let sources = PyList::empty(py);
+        for source in summary.sources {
+            sources.append(Py::new(py, SourceWordCount::from_core(source))?)?;
+        }
+        Ok(Self {
+            notebook_id: summary.notebook_id,
+            total_words: summary.total_words,
+            sources: sources.unbind(),
+            missing_word_counts: summary.missing_word_counts,
+        })
+    }
+}
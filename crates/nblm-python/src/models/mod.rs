@@ -1,5 +1,6 @@
+use pyo3::exceptions::PyTypeError;
 use pyo3::prelude::*;
-use pyo3::types::{PyBool, PyDict, PyFloat, PyList, PyNone, PyString};
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyNone, PyString, PyTuple};
 use pyo3::IntoPyObject;
 use serde_json::Value;
 use std::collections::HashMap;
@@ -7,12 +8,14 @@ use std::collections::HashMap;
 use crate::error::PyResult;
 
 mod audio;
+mod budget;
 mod notebook;
 mod notebook_source;
 mod responses;
 mod source;
 
 pub use audio::*;
+pub use budget::*;
 pub use notebook::*;
 pub use notebook_source::*;
 pub use responses::*;
@@ -60,3 +63,61 @@ pub(crate) fn extra_to_pydict(py: Python, extra: &HashMap<String, Value>) -> PyR
     }
     Ok(dict.unbind())
 }
+
+/// Convert a Python object back to `serde_json::Value` (the reverse of [`json_value_to_py`]).
+///
+/// Model objects are converted through their `to_dict()` method.
+pub(crate) fn py_to_json_value(value: &Bound<'_, PyAny>) -> PyResult<Value> {
+    if value.is_none() {
+        return Ok(Value::Null);
+    }
+    // bool is a subclass of int, so it has to be checked first.
+    if let Ok(b) = value.cast::<PyBool>() {
+        return Ok(Value::Bool(b.is_true()));
+    }
+    if value.is_instance_of::<PyInt>() {
+        if let Ok(i) = value.extract::<i64>() {
+            return Ok(Value::from(i));
+        }
+        return Ok(Value::from(value.extract::<u64>()?));
+    }
+    if let Ok(f) = value.cast::<PyFloat>() {
+        return Ok(serde_json::Number::from_f64(f.value())
+            .map(Value::Number)
+            .unwrap_or(Value::Null));
+    }
+    if let Ok(s) = value.cast::<PyString>() {
+        return Ok(Value::String(s.to_str()?.to_string()));
+    }
+    if let Ok(list) = value.cast::<PyList>() {
+        return list.iter().map(|item| py_to_json_value(&item)).collect();
+    }
+    if let Ok(tuple) = value.cast::<PyTuple>() {
+        return tuple.iter().map(|item| py_to_json_value(&item)).collect();
+    }
+    if let Ok(dict) = value.cast::<PyDict>() {
+        let mut map = serde_json::Map::new();
+        for (k, v) in dict.iter() {
+            map.insert(k.extract::<String>()?, py_to_json_value(&v)?);
+        }
+        return Ok(Value::Object(map));
+    }
+    if value.hasattr("to_dict")? {
+        return py_to_json_value(&value.call_method0("to_dict")?);
+    }
+    Err(PyTypeError::new_err(format!(
+        "cannot convert {} to a plain value",
+        value.get_type().name()?
+    )))
+}
+
+/// Plain value of an optional nested model, as `to_dict()` returns it.
+pub(crate) fn optional_model_to_json<T>(py: Python, field: &Option<Py<T>>) -> PyResult<Value>
+where
+    T: pyo3::PyClass,
+{
+    match field {
+        Some(value) => py_to_json_value(value.bind(py).as_any()),
+        None => Ok(Value::Null),
+    }
+}
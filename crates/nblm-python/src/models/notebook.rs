@@ -1,9 +1,16 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
 use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyList};
 
 use crate::error::PyResult;
 
-use super::{extra_to_pydict, NotebookSource};
+use serde_json::json;
+
+use super::{
+    extra_to_pydict, json_value_to_py, optional_model_to_json, py_to_json_value, NotebookSource,
+};
 
 #[pyclass(module = "nblm")]
 pub struct NotebookMetadata {
@@ -21,6 +28,18 @@ pub struct NotebookMetadata {
 
 #[pymethods]
 impl NotebookMetadata {
+    /// Convert to plain Python dicts, lists and scalars, recursing into nested models.
+    pub fn to_dict(&self, py: Python) -> PyResult<Py<PyAny>> {
+        let value = json!({
+            "create_time": self.create_time,
+            "is_shareable": self.is_shareable,
+            "is_shared": self.is_shared,
+            "last_viewed": self.last_viewed,
+            "extra": py_to_json_value(self.extra.bind(py).as_any())?,
+        });
+        json_value_to_py(py, &value)
+    }
+
     pub fn __repr__(&self) -> String {
         format!(
             "NotebookMetadata(create_time={:?}, last_viewed={:?})",
@@ -59,6 +78,8 @@ pub struct Notebook {
     #[pyo3(get)]
     pub emoji: Option<String>,
     #[pyo3(get)]
+    pub summary: Option<String>,
+    #[pyo3(get)]
     pub metadata: Option<Py<NotebookMetadata>>,
     #[pyo3(get)]
     pub sources: Py<PyList>,
@@ -68,6 +89,32 @@ pub struct Notebook {
 
 #[pymethods]
 impl Notebook {
+    /// Notebooks are equal when their name, notebook ID and title match.
+    pub fn __eq__(&self, other: PyRef<'_, Self>) -> bool {
+        self.identity() == other.identity()
+    }
+
+    pub fn __hash__(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.identity().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Convert to plain Python dicts, lists and scalars, recursing into nested models.
+    pub fn to_dict(&self, py: Python) -> PyResult<Py<PyAny>> {
+        let value = json!({
+            "name": self.name,
+            "title": self.title,
+            "notebook_id": self.notebook_id,
+            "emoji": self.emoji,
+            "summary": self.summary,
+            "metadata": optional_model_to_json(py, &self.metadata)?,
+            "sources": py_to_json_value(self.sources.bind(py).as_any())?,
+            "extra": py_to_json_value(self.extra.bind(py).as_any())?,
+        });
+        json_value_to_py(py, &value)
+    }
+
     pub fn __repr__(&self, py: Python) -> String {
         let source_count = self.sources.bind(py).len();
         format!(
@@ -82,11 +129,17 @@ impl Notebook {
 }
 
 impl Notebook {
+    fn identity(&self) -> (&Option<String>, &Option<String>, &str) {
+        (&self.name, &self.notebook_id, &self.title)
+    }
+
     pub fn from_core(
         py: Python,
         notebook: nblm_core::models::enterprise::notebook::Notebook,
     ) -> PyResult<Self> {
         let extra = extra_to_pydict(py, &notebook.extra)?;
+        // Filled from the resource name when the response leaves `notebookId` out.
+        let notebook_id = notebook.notebook_id().map(str::to_string);
         let metadata = match notebook.metadata {
             Some(meta) => Some(Py::new(py, NotebookMetadata::from_core(py, meta)?)?),
             None => None,
@@ -99,8 +152,9 @@ impl Notebook {
         Ok(Self {
             name: notebook.name,
             title: notebook.title,
-            notebook_id: notebook.notebook_id,
+            notebook_id,
             emoji: notebook.emoji,
+            summary: notebook.summary,
             metadata,
             sources: sources_list.unbind(),
             extra,
@@ -1,9 +1,14 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
 
 use crate::error::PyResult;
 
-use super::extra_to_pydict;
+use serde_json::json;
+
+use super::{extra_to_pydict, json_value_to_py, optional_model_to_json, py_to_json_value};
 
 #[pyclass(module = "nblm")]
 pub struct NotebookSourceYoutubeMetadata {
@@ -17,6 +22,16 @@ pub struct NotebookSourceYoutubeMetadata {
 
 #[pymethods]
 impl NotebookSourceYoutubeMetadata {
+    /// Convert to plain Python dicts, lists and scalars, recursing into nested models.
+    pub fn to_dict(&self, py: Python) -> PyResult<Py<PyAny>> {
+        let value = json!({
+            "channel_name": self.channel_name,
+            "video_id": self.video_id,
+            "extra": py_to_json_value(self.extra.bind(py).as_any())?,
+        });
+        json_value_to_py(py, &value)
+    }
+
     pub fn __repr__(&self) -> String {
         format!(
             "NotebookSourceYoutubeMetadata(channel_name={:?}, video_id={:?})",
@@ -52,6 +67,15 @@ pub struct NotebookSourceSettings {
 
 #[pymethods]
 impl NotebookSourceSettings {
+    /// Convert to plain Python dicts, lists and scalars, recursing into nested models.
+    pub fn to_dict(&self, py: Python) -> PyResult<Py<PyAny>> {
+        let value = json!({
+            "status": self.status,
+            "extra": py_to_json_value(self.extra.bind(py).as_any())?,
+        });
+        json_value_to_py(py, &value)
+    }
+
     pub fn __repr__(&self) -> String {
         format!("NotebookSourceSettings(status={:?})", self.status)
     }
@@ -83,6 +107,15 @@ pub struct NotebookSourceId {
 
 #[pymethods]
 impl NotebookSourceId {
+    /// Convert to plain Python dicts, lists and scalars, recursing into nested models.
+    pub fn to_dict(&self, py: Python) -> PyResult<Py<PyAny>> {
+        let value = json!({
+            "id": self.id,
+            "extra": py_to_json_value(self.extra.bind(py).as_any())?,
+        });
+        json_value_to_py(py, &value)
+    }
+
     pub fn __repr__(&self) -> String {
         format!("NotebookSourceId(id={:?})", self.id)
     }
@@ -118,6 +151,17 @@ pub struct NotebookSourceMetadata {
 
 #[pymethods]
 impl NotebookSourceMetadata {
+    /// Convert to plain Python dicts, lists and scalars, recursing into nested models.
+    pub fn to_dict(&self, py: Python) -> PyResult<Py<PyAny>> {
+        let value = json!({
+            "source_added_timestamp": self.source_added_timestamp,
+            "word_count": self.word_count,
+            "youtube_metadata": optional_model_to_json(py, &self.youtube_metadata)?,
+            "extra": py_to_json_value(self.extra.bind(py).as_any())?,
+        });
+        json_value_to_py(py, &value)
+    }
+
     pub fn __repr__(&self) -> String {
         format!(
             "NotebookSourceMetadata(source_added_timestamp={:?}, word_count={:?})",
@@ -163,12 +207,40 @@ pub struct NotebookSource {
     pub settings: Option<Py<NotebookSourceSettings>>,
     #[pyo3(get)]
     pub source_id: Option<Py<NotebookSourceId>>,
+    /// Plain source ID: `source_id.id`, else the last segment of `name`.
+    #[pyo3(get)]
+    pub id: Option<String>,
     #[pyo3(get)]
     pub extra: Py<PyDict>,
 }
 
 #[pymethods]
 impl NotebookSource {
+    /// Sources are equal when their resource names match.
+    pub fn __eq__(&self, other: PyRef<'_, Self>) -> bool {
+        self.name == other.name
+    }
+
+    pub fn __hash__(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.name.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Convert to plain Python dicts, lists and scalars, recursing into nested models.
+    pub fn to_dict(&self, py: Python) -> PyResult<Py<PyAny>> {
+        let value = json!({
+            "name": self.name,
+            "title": self.title,
+            "metadata": optional_model_to_json(py, &self.metadata)?,
+            "settings": optional_model_to_json(py, &self.settings)?,
+            "source_id": optional_model_to_json(py, &self.source_id)?,
+            "id": self.id,
+            "extra": py_to_json_value(self.extra.bind(py).as_any())?,
+        });
+        json_value_to_py(py, &value)
+    }
+
     pub fn __repr__(&self, _py: Python) -> String {
         let metadata_present = self.metadata.is_some();
         let settings_present = self.settings.is_some();
@@ -189,6 +261,7 @@ impl NotebookSource {
         py: Python,
         source: nblm_core::models::enterprise::source::NotebookSource,
     ) -> PyResult<Self> {
+        let id = source.source_id().map(str::to_string);
         let metadata = match source.metadata {
             Some(meta) => Some(Py::new(py, NotebookSourceMetadata::from_core(py, meta)?)?),
             None => None,
@@ -210,6 +283,7 @@ impl NotebookSource {
             metadata,
             settings,
             source_id,
+            id,
             extra: extra_to_pydict(py, &source.extra)?,
         })
     }
@@ -3,7 +3,12 @@ use pyo3::types::{PyDict, PyList};
 
 use crate::error::PyResult;
 
-use super::{extra_to_pydict, Notebook, NotebookSource, NotebookSourceId};
+use serde_json::json;
+
+use super::{
+    extra_to_pydict, json_value_to_py, optional_model_to_json, py_to_json_value, Notebook,
+    NotebookSource, NotebookSourceId,
+};
 
 #[pyclass(module = "nblm")]
 pub struct ListRecentlyViewedResponse {
@@ -13,6 +18,14 @@ pub struct ListRecentlyViewedResponse {
 
 #[pymethods]
 impl ListRecentlyViewedResponse {
+    /// Convert to plain Python dicts, lists and scalars, recursing into nested models.
+    pub fn to_dict(&self, py: Python) -> PyResult<Py<PyAny>> {
+        let value = json!({
+            "notebooks": py_to_json_value(self.notebooks.bind(py).as_any())?,
+        });
+        json_value_to_py(py, &value)
+    }
+
     pub fn __repr__(&self, py: Python) -> String {
         let count = self.notebooks.bind(py).len();
         format!("ListRecentlyViewedResponse(notebooks={} items)", count)
@@ -49,6 +62,15 @@ pub struct BatchDeleteNotebooksResponse {
 
 #[pymethods]
 impl BatchDeleteNotebooksResponse {
+    /// Convert to plain Python dicts, lists and scalars, recursing into nested models.
+    pub fn to_dict(&self, py: Python) -> PyResult<Py<PyAny>> {
+        let value = json!({
+            "deleted_notebooks": py_to_json_value(self.deleted_notebooks.bind(py).as_any())?,
+            "failed_notebooks": py_to_json_value(self.failed_notebooks.bind(py).as_any())?,
+        });
+        json_value_to_py(py, &value)
+    }
+
     pub fn __repr__(&self, py: Python) -> String {
         let deleted_count = self.deleted_notebooks.bind(py).len();
         let failed_count = self.failed_notebooks.bind(py).len();
@@ -91,10 +113,23 @@ pub struct BatchCreateSourcesResponse {
     pub sources: Py<PyList>,
     #[pyo3(get)]
     pub error_count: Option<i32>,
+    /// IDs of the sources that were created (failed sources are left out).
+    #[pyo3(get)]
+    pub source_ids: Vec<String>,
 }
 
 #[pymethods]
 impl BatchCreateSourcesResponse {
+    /// Convert to plain Python dicts, lists and scalars, recursing into nested models.
+    pub fn to_dict(&self, py: Python) -> PyResult<Py<PyAny>> {
+        let value = json!({
+            "sources": py_to_json_value(self.sources.bind(py).as_any())?,
+            "error_count": self.error_count,
+            "source_ids": self.source_ids,
+        });
+        json_value_to_py(py, &value)
+    }
+
     pub fn __repr__(&self, py: Python) -> String {
         let count = self.sources.bind(py).len();
         format!(
@@ -113,6 +148,11 @@ impl BatchCreateSourcesResponse {
         py: Python,
         response: nblm_core::models::enterprise::source::BatchCreateSourcesResponse,
     ) -> PyResult<Self> {
+        let source_ids = response
+            .created_ids()
+            .into_iter()
+            .map(str::to_string)
+            .collect();
         let sources_list = PyList::empty(py);
         for source in response.sources {
             let py_source = NotebookSource::from_core(py, source)?;
@@ -121,6 +161,7 @@ impl BatchCreateSourcesResponse {
         Ok(Self {
             sources: sources_list.unbind(),
             error_count: response.error_count,
+            source_ids,
         })
     }
 }
@@ -133,6 +174,14 @@ pub struct BatchDeleteSourcesResponse {
 
 #[pymethods]
 impl BatchDeleteSourcesResponse {
+    /// Convert to plain Python dicts, lists and scalars, recursing into nested models.
+    pub fn to_dict(&self, py: Python) -> PyResult<Py<PyAny>> {
+        let value = json!({
+            "extra": py_to_json_value(self.extra.bind(py).as_any())?,
+        });
+        json_value_to_py(py, &value)
+    }
+
     pub fn __repr__(&self, py: Python) -> String {
         let keys = self.extra.bind(py).len();
         format!("BatchDeleteSourcesResponse(extra_keys={})", keys)
@@ -159,17 +208,32 @@ pub struct UploadSourceFileResponse {
     #[pyo3(get)]
     pub source_id: Option<Py<NotebookSourceId>>,
     #[pyo3(get)]
+    pub name: Option<String>,
+    #[pyo3(get)]
+    pub state: Option<String>,
+    #[pyo3(get)]
     pub extra: Py<PyDict>,
 }
 
 #[pymethods]
 impl UploadSourceFileResponse {
+    /// Convert to plain Python dicts, lists and scalars, recursing into nested models.
+    pub fn to_dict(&self, py: Python) -> PyResult<Py<PyAny>> {
+        let value = json!({
+            "source_id": optional_model_to_json(py, &self.source_id)?,
+            "name": self.name,
+            "state": self.state,
+            "extra": py_to_json_value(self.extra.bind(py).as_any())?,
+        });
+        json_value_to_py(py, &value)
+    }
+
     pub fn __repr__(&self, py: Python) -> String {
         let has_id = self.source_id.is_some();
         let extra_keys = self.extra.bind(py).len();
         format!(
-            "UploadSourceFileResponse(source_id={}, extra_keys={})",
-            has_id, extra_keys
+            "UploadSourceFileResponse(source_id={}, name={:?}, state={:?}, extra_keys={})",
+            has_id, self.name, self.state, extra_keys
         )
     }
 
@@ -189,6 +253,8 @@ impl UploadSourceFileResponse {
         };
         Ok(Self {
             source_id,
+            name: response.name,
+            state: response.state,
             extra: extra_to_pydict(py, &response.extra)?,
         })
     }
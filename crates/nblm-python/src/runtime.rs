@@ -1,20 +1,59 @@
-use crate::error::{map_runtime_error, IntoPyResult, PyResult};
+use crate::error::{map_runtime_error, IntoPyResult, NblmError, PyResult};
+use std::cell::Cell;
 use std::future::Future;
-use tokio::runtime::Handle;
+use std::sync::OnceLock;
+use tokio::runtime::{Handle, Runtime};
 
-/// Block on an async future, creating a Tokio runtime if needed.
+/// One runtime for every client and thread, built on first use.
+static RUNTIME: OnceLock<std::io::Result<Runtime>> = OnceLock::new();
+
+thread_local! {
+    /// Set while a `CallbackTokenProvider` callback runs on this thread.
+    static IN_TOKEN_CALLBACK: Cell<bool> = const { Cell::new(false) };
+}
+
+fn runtime() -> PyResult<&'static Runtime> {
+    RUNTIME
+        .get_or_init(|| {
+            tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .thread_name("nblm-runtime")
+                .build()
+        })
+        .as_ref()
+        .map_err(map_runtime_error)
+}
+
+/// Block on an async future on the shared Tokio runtime.
+///
+/// Callers release the GIL first (`py.detach`), so concurrent calls from several Python threads
+/// run in parallel on the same runtime. Refuses to run inside a token callback: the outer call
+/// is waiting for that callback, so a nested request would queue behind it or recurse into it.
 pub fn block_on_with_runtime<F, T>(future: F) -> PyResult<T>
 where
     F: Future<Output = nblm_core::Result<T>> + Send + 'static,
     T: Send + 'static,
 {
+    if IN_TOKEN_CALLBACK.get() {
+        return Err(NblmError::new_err(
+            "NblmClient cannot be used from inside a CallbackTokenProvider callback; fetch the token without calling the client",
+        ));
+    }
+    // Another library's runtime may already be running this thread.
     if let Ok(handle) = Handle::try_current() {
         return handle.block_on(future).into_py_result();
     }
+    runtime()?.block_on(future).into_py_result()
+}
 
-    let runtime = tokio::runtime::Builder::new_multi_thread()
-        .enable_all()
-        .build()
-        .map_err(map_runtime_error)?;
-    runtime.block_on(future).into_py_result()
+/// Run a token callback with re-entrant client calls disabled on this thread.
+pub(crate) fn in_token_callback<R>(callback: impl FnOnce() -> R) -> R {
+    struct Reset(bool);
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            IN_TOKEN_CALLBACK.set(self.0);
+        }
+    }
+    let _reset = Reset(IN_TOKEN_CALLBACK.replace(true));
+    callback()
 }
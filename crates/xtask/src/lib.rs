@@ -0,0 +1,3 @@
+//! Maintenance tasks for the workspace, run with `cargo run -p xtask -- <task>`.
+
+pub mod public_api;
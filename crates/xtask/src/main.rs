@@ -0,0 +1,64 @@
+use std::process::ExitCode;
+
+use anyhow::{bail, Context, Result};
+use xtask::public_api;
+
+const USAGE: &str = "\
+Usage: cargo run -p xtask -- <task>
+
+Tasks:
+  public-api          Regenerate crates/nblm-core/public-api.txt (needs a nightly toolchain)
+  public-api --check  Fail if the public API differs from crates/nblm-core/public-api.txt";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let result = match args.as_slice() {
+        ["public-api"] => update_public_api(),
+        ["public-api", "--check"] => check_public_api(),
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err:#}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn update_public_api() -> Result<()> {
+    let root = public_api::workspace_root();
+    let api = public_api::current(&root)?;
+    let snapshot = root.join(public_api::SNAPSHOT);
+    let previous = std::fs::read_to_string(&snapshot).unwrap_or_default();
+    std::fs::write(&snapshot, &api)
+        .with_context(|| format!("failed to write {}", snapshot.display()))?;
+    let changes = public_api::diff(&previous, &api);
+    if changes.is_empty() {
+        println!("{} is up to date", public_api::SNAPSHOT);
+    } else {
+        println!("Updated {}:\n{changes}", public_api::SNAPSHOT);
+    }
+    Ok(())
+}
+
+fn check_public_api() -> Result<()> {
+    let root = public_api::workspace_root();
+    let api = public_api::current(&root)?;
+    let snapshot = std::fs::read_to_string(root.join(public_api::SNAPSHOT))
+        .with_context(|| format!("failed to read {}", public_api::SNAPSHOT))?;
+    let changes = public_api::diff(&snapshot, &api);
+    if !changes.is_empty() {
+        bail!(
+            "the public API of {} changed:\n{changes}\n\nRun `cargo run -p xtask -- public-api` and commit {}",
+            public_api::PACKAGE,
+            public_api::SNAPSHOT
+        );
+    }
+    println!("{} matches the public API", public_api::SNAPSHOT);
+    Ok(())
+}
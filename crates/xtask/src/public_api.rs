@@ -0,0 +1,750 @@
+//! Text snapshot of nblm-core's public API, rendered from rustdoc's JSON output.
+//!
+//! Every public item reachable from the crate root gets one line, with its full path and
+//! signature, and the lines are sorted. The snapshot is checked in at [`SNAPSHOT`], so a
+//! change to the public API shows up in review as a diff of that file, and the
+//! `public_api` test fails until the file is regenerated with `cargo run -p xtask -- public-api`.
+//!
+//! rustdoc only writes JSON on nightly, so this needs a nightly toolchain installed
+//! (`rustup toolchain install nightly`); the build itself stays on stable.
+//!
+//! The renderer is written here rather than taken from `cargo-public-api`. That tool (and the
+//! `public-api` library behind it) deserializes rustdoc JSON into `rustdoc-types`, which accepts
+//! exactly one `format_version`. Whenever nightly bumps the format, which happens several times
+//! per release cycle, it stops working until a matching release is out, and CI would have to pin
+//! a nightly date to keep up. Reading the few fields the snapshot needs from untyped JSON
+//! survives those bumps, and keeps the check free of a tool install.
+
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+
+/// Package whose API is tracked.
+pub const PACKAGE: &str = "nblm-core";
+
+/// Snapshot file, relative to the workspace root.
+pub const SNAPSHOT: &str = "crates/nblm-core/public-api.txt";
+
+/// Traits left out of the snapshot: unstable markers that come and go between nightlies.
+const UNSTABLE_TRAITS: &[&str] = &["Freeze", "UnsafeUnpin", "StructuralPartialEq"];
+
+pub fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .ancestors()
+        .nth(2)
+        .expect("xtask lives two levels below the workspace root")
+        .to_path_buf()
+}
+
+pub fn nightly_available() -> bool {
+    Command::new("cargo")
+        .args(["+nightly", "--version"])
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Render the current public API of [`PACKAGE`], with all features enabled.
+pub fn current(root: &Path) -> Result<String> {
+    let json = rustdoc_json(root, PACKAGE)?;
+    let text = std::fs::read_to_string(&json)
+        .with_context(|| format!("failed to read {}", json.display()))?;
+    let krate: Value = serde_json::from_str(&text).context("rustdoc JSON is not valid JSON")?;
+    render(&krate)
+}
+
+/// Build rustdoc JSON for `package` and return the path of the file.
+///
+/// A separate target directory keeps the nightly artifacts away from the stable ones.
+pub fn rustdoc_json(root: &Path, package: &str) -> Result<PathBuf> {
+    let target_dir = root.join("target").join("public-api");
+    let status = Command::new("cargo")
+        .current_dir(root)
+        .args([
+            "+nightly",
+            "rustdoc",
+            "--lib",
+            "--all-features",
+            "--package",
+            package,
+        ])
+        .arg("--target-dir")
+        .arg(&target_dir)
+        .args(["--", "-Z", "unstable-options", "--output-format", "json"])
+        // Lints do not matter for the snapshot; keep the output readable.
+        .env("RUSTDOCFLAGS", "--cap-lints allow")
+        .status()
+        .context("failed to run cargo +nightly rustdoc")?;
+    if !status.success() {
+        bail!("cargo +nightly rustdoc failed for {package} ({status})");
+    }
+    Ok(target_dir
+        .join("doc")
+        .join(format!("{}.json", package.replace('-', "_"))))
+}
+
+/// Lines of `expected` and `actual` that are missing from the other one, as `-`/`+` lines.
+pub fn diff(expected: &str, actual: &str) -> String {
+    let old: BTreeSet<&str> = expected.lines().collect();
+    let new: BTreeSet<&str> = actual.lines().collect();
+    let removed = old.difference(&new).map(|line| format!("-{line}"));
+    let added = new.difference(&old).map(|line| format!("+{line}"));
+    removed.chain(added).collect::<Vec<_>>().join("\n")
+}
+
+/// Render the crate in `krate` (rustdoc JSON) as sorted lines, one per public item.
+pub fn render(krate: &Value) -> Result<String> {
+    let root = id_key(&krate["root"]);
+    let mut renderer = Renderer {
+        index: &krate["index"],
+        paths: &krate["paths"],
+        lines: BTreeSet::new(),
+        visited: HashSet::new(),
+    };
+    let Some(name) = renderer.item(&root)["name"].as_str() else {
+        bail!("rustdoc JSON has no root module");
+    };
+    renderer.module(&root, name);
+    let mut out = renderer.lines.into_iter().collect::<Vec<_>>().join("\n");
+    out.push('\n');
+    Ok(out)
+}
+
+struct Renderer<'a> {
+    index: &'a Value,
+    paths: &'a Value,
+    lines: BTreeSet<String>,
+    /// Modules already rendered under a path, so glob re-exports cannot loop.
+    visited: HashSet<(String, String)>,
+}
+
+impl<'a> Renderer<'a> {
+    fn item(&self, id: &str) -> &'a Value {
+        &self.index[id]
+    }
+
+    fn module(&mut self, id: &str, path: &str) {
+        if !self.visited.insert((id.to_string(), path.to_string())) {
+            return;
+        }
+        let items = self.item(id)["inner"]["module"]["items"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        for child in items {
+            let child = id_key(&child);
+            let item = self.item(&child);
+            if item["visibility"] != "public" {
+                continue;
+            }
+            if let Some(import) = item["inner"].get("use") {
+                self.import(import, path);
+            } else if let Some(name) = item["name"].as_str() {
+                self.define(&child, &format!("{path}::{name}"));
+            }
+        }
+    }
+
+    /// A `pub use`: the target is rendered under the re-exported path when it is local,
+    /// otherwise only the re-export itself is listed.
+    fn import(&mut self, import: &Value, path: &str) {
+        let name = import["name"].as_str().unwrap_or_default();
+        let source = import["source"].as_str().unwrap_or_default();
+        let target = import["id"]
+            .as_u64()
+            .map(|id| id.to_string())
+            .filter(|id| self.index.get(id).is_some());
+        match (target, import["is_glob"].as_bool().unwrap_or(false)) {
+            (Some(target), true) => self.module(&target, path),
+            (Some(target), false) => self.define(&target, &format!("{path}::{name}")),
+            (None, true) => {
+                self.lines.insert(format!("pub use {path}::{source}::*"));
+            }
+            (None, false) => {
+                self.lines
+                    .insert(format!("pub use {path}::{name} = {source}"));
+            }
+        }
+    }
+
+    fn define(&mut self, id: &str, path: &str) {
+        let item = self.item(id);
+        let inner = &item["inner"];
+        let Some((kind, body)) = inner.as_object().and_then(|inner| inner.iter().next()) else {
+            return;
+        };
+        let line = match kind.as_str() {
+            "module" => {
+                self.lines.insert(format!("pub mod {path}"));
+                self.module(id, path);
+                return;
+            }
+            "function" => self.function(path, body, "pub "),
+            "constant" => format!("pub const {path}: {}", self.ty(&body["type"])),
+            "static" => format!(
+                "pub static {}{path}: {}",
+                if body["is_mutable"] == true {
+                    "mut "
+                } else {
+                    ""
+                },
+                self.ty(&body["type"])
+            ),
+            "type_alias" => format!(
+                "pub type {path}{} = {}",
+                self.generic_params(&body["generics"]),
+                self.ty(&body["type"])
+            ),
+            "macro" | "proc_macro" => format!("pub macro {path}!"),
+            "struct" => {
+                self.struct_fields(path, &body["kind"]);
+                self.impls(path, &body["impls"]);
+                format!(
+                    "pub struct {path}{}{}",
+                    self.generic_params(&body["generics"]),
+                    self.where_clause(&body["generics"])
+                )
+            }
+            "union" => {
+                self.struct_fields(path, &serde_json::json!({ "plain": body }));
+                self.impls(path, &body["impls"]);
+                format!("pub union {path}{}", self.generic_params(&body["generics"]))
+            }
+            "enum" => {
+                for variant in body["variants"].as_array().into_iter().flatten() {
+                    self.variant(path, &id_key(variant));
+                }
+                self.impls(path, &body["impls"]);
+                format!(
+                    "pub enum {path}{}{}",
+                    self.generic_params(&body["generics"]),
+                    self.where_clause(&body["generics"])
+                )
+            }
+            "trait" => {
+                for member in body["items"].as_array().into_iter().flatten() {
+                    self.trait_member(path, &id_key(member));
+                }
+                let bounds = self.bounds(&body["bounds"]);
+                format!(
+                    "pub {}trait {path}{}{}{}",
+                    if body["is_unsafe"] == true {
+                        "unsafe "
+                    } else {
+                        ""
+                    },
+                    self.generic_params(&body["generics"]),
+                    if bounds.is_empty() {
+                        String::new()
+                    } else {
+                        format!(": {bounds}")
+                    },
+                    self.where_clause(&body["generics"])
+                )
+            }
+            _ => return,
+        };
+        self.lines.insert(line);
+    }
+
+    fn struct_fields(&mut self, path: &str, kind: &Value) {
+        if let Some(fields) = kind["plain"]["fields"].as_array() {
+            for field in fields {
+                let field = self.item(&id_key(field));
+                if field["visibility"] == "public" {
+                    let name = field["name"].as_str().unwrap_or_default();
+                    let ty = self.ty(&field["inner"]["struct_field"]);
+                    self.lines.insert(format!("pub {path}::{name}: {ty}"));
+                }
+            }
+        }
+        if let Some(fields) = kind["tuple"].as_array() {
+            for (position, field) in fields.iter().enumerate() {
+                let Some(field) = field.as_u64() else {
+                    continue;
+                };
+                let field = self.item(&field.to_string());
+                if field["visibility"] == "public" {
+                    let ty = self.ty(&field["inner"]["struct_field"]);
+                    self.lines.insert(format!("pub {path}::{position}: {ty}"));
+                }
+            }
+        }
+    }
+
+    fn variant(&mut self, path: &str, id: &str) {
+        let variant = self.item(id);
+        let name = variant["name"].as_str().unwrap_or_default();
+        let kind = &variant["inner"]["variant"]["kind"];
+        let fields = if let Some(fields) = kind["tuple"].as_array() {
+            let types: Vec<String> = fields
+                .iter()
+                .map(|field| match field.as_u64() {
+                    Some(field) => self.ty(&self.item(&field.to_string())["inner"]["struct_field"]),
+                    None => "_".to_string(),
+                })
+                .collect();
+            format!("({})", types.join(", "))
+        } else if let Some(fields) = kind["struct"]["fields"].as_array() {
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|field| {
+                    let field = self.item(&id_key(field));
+                    format!(
+                        "{}: {}",
+                        field["name"].as_str().unwrap_or_default(),
+                        self.ty(&field["inner"]["struct_field"])
+                    )
+                })
+                .collect();
+            format!(" {{ {} }}", fields.join(", "))
+        } else {
+            String::new()
+        };
+        self.lines.insert(format!("pub {path}::{name}{fields}"));
+    }
+
+    fn trait_member(&mut self, path: &str, id: &str) {
+        let member = self.item(id);
+        let name = member["name"].as_str().unwrap_or_default();
+        let member_path = format!("{path}::{name}");
+        let line = if let Some(function) = member["inner"].get("function") {
+            self.function(&member_path, function, "")
+        } else if let Some(assoc) = member["inner"].get("assoc_type") {
+            let bounds = self.bounds(&assoc["bounds"]);
+            if bounds.is_empty() {
+                format!("type {member_path}")
+            } else {
+                format!("type {member_path}: {bounds}")
+            }
+        } else if let Some(assoc) = member["inner"].get("assoc_const") {
+            format!("const {member_path}: {}", self.ty(&assoc["type"]))
+        } else {
+            return;
+        };
+        self.lines.insert(line);
+    }
+
+    /// Inherent methods and constants, and every trait implemented other than through a blanket
+    /// impl. Auto traits (`Send`, `Sync`, ...) are included: losing one breaks callers too.
+    /// Unstable marker traits are not, see [`UNSTABLE_TRAITS`].
+    fn impls(&mut self, path: &str, impls: &Value) {
+        for id in impls.as_array().into_iter().flatten() {
+            let block = &self.item(&id_key(id))["inner"]["impl"];
+            let trait_name = block["trait"]["path"].as_str().unwrap_or_default();
+            let trait_name = trait_name.rsplit("::").next().unwrap_or_default();
+            if !block["blanket_impl"].is_null() || UNSTABLE_TRAITS.contains(&trait_name) {
+                continue;
+            }
+            if block["trait"].is_null() {
+                for member in block["items"].as_array().into_iter().flatten() {
+                    let member = self.item(&id_key(member));
+                    if member["visibility"] != "public" {
+                        continue;
+                    }
+                    let name = member["name"].as_str().unwrap_or_default();
+                    let member_path = format!("{path}::{name}");
+                    if let Some(function) = member["inner"].get("function") {
+                        let line = self.function(&member_path, function, "pub ");
+                        self.lines.insert(line);
+                    } else if let Some(constant) = member["inner"].get("assoc_const") {
+                        let line =
+                            format!("pub const {member_path}: {}", self.ty(&constant["type"]));
+                        self.lines.insert(line);
+                    }
+                }
+            } else {
+                let line = format!(
+                    "impl{} {}{} for {}{}",
+                    self.generic_params(&block["generics"]),
+                    if block["is_negative"] == true {
+                        "!"
+                    } else {
+                        ""
+                    },
+                    self.path(&block["trait"]),
+                    self.ty(&block["for"]),
+                    self.where_clause(&block["generics"])
+                );
+                self.lines.insert(line);
+            }
+        }
+    }
+
+    fn function(&self, path: &str, function: &Value, visibility: &str) -> String {
+        let header = &function["header"];
+        let mut qualifiers = String::new();
+        for (flag, keyword) in [
+            ("is_const", "const "),
+            ("is_async", "async "),
+            ("is_unsafe", "unsafe "),
+        ] {
+            if header[flag] == true {
+                qualifiers.push_str(keyword);
+            }
+        }
+        let sig = &function["sig"];
+        let inputs: Vec<String> = sig["inputs"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|input| self.input(&input[0], &input[1]))
+            .collect();
+        let output = match &sig["output"] {
+            Value::Null => String::new(),
+            ty => format!(" -> {}", self.ty(ty)),
+        };
+        format!(
+            "{visibility}{qualifiers}fn {path}{}({}){output}{}",
+            self.generic_params(&function["generics"]),
+            inputs.join(", "),
+            self.where_clause(&function["generics"])
+        )
+    }
+
+    fn input(&self, name: &Value, ty: &Value) -> String {
+        let name = name.as_str().unwrap_or("_");
+        if name == "self" {
+            if ty["generic"] == "Self" {
+                return "self".to_string();
+            }
+            let reference = &ty["borrowed_ref"];
+            if reference["type"]["generic"] == "Self" {
+                return format!("{}self", self.reference(reference));
+            }
+        }
+        format!("{name}: {}", self.ty(ty))
+    }
+
+    fn reference(&self, reference: &Value) -> String {
+        let mut out = "&".to_string();
+        if let Some(lifetime) = reference["lifetime"].as_str() {
+            out.push_str(lifetime);
+            out.push(' ');
+        }
+        if reference["is_mutable"] == true {
+            out.push_str("mut ");
+        }
+        out
+    }
+
+    fn ty(&self, ty: &Value) -> String {
+        let Some((kind, body)) = ty.as_object().and_then(|ty| ty.iter().next()) else {
+            return match ty.as_str() {
+                Some("infer") => "_".to_string(),
+                _ => "?".to_string(),
+            };
+        };
+        match kind.as_str() {
+            "resolved_path" => self.path(body),
+            "generic" | "primitive" => body.as_str().unwrap_or_default().to_string(),
+            "borrowed_ref" => format!("{}{}", self.reference(body), self.ty(&body["type"])),
+            "raw_pointer" => format!(
+                "*{} {}",
+                if body["is_mutable"] == true {
+                    "mut"
+                } else {
+                    "const"
+                },
+                self.ty(&body["type"])
+            ),
+            "slice" => format!("[{}]", self.ty(body)),
+            "array" => format!(
+                "[{}; {}]",
+                self.ty(&body["type"]),
+                body["len"].as_str().unwrap_or("_")
+            ),
+            "tuple" => {
+                let types: Vec<String> = body
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|ty| self.ty(ty))
+                    .collect();
+                if types.len() == 1 {
+                    format!("({},)", types[0])
+                } else {
+                    format!("({})", types.join(", "))
+                }
+            }
+            "impl_trait" => format!("impl {}", self.bounds(body)),
+            "dyn_trait" => {
+                let mut traits: Vec<String> = body["traits"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|poly| self.path(&poly["trait"]))
+                    .collect();
+                if let Some(lifetime) = body["lifetime"].as_str() {
+                    traits.push(lifetime.to_string());
+                }
+                format!("dyn {}", traits.join(" + "))
+            }
+            "function_pointer" => {
+                let inputs: Vec<String> = body["sig"]["inputs"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|input| self.ty(&input[1]))
+                    .collect();
+                let output = match &body["sig"]["output"] {
+                    Value::Null => String::new(),
+                    ty => format!(" -> {}", self.ty(ty)),
+                };
+                format!("fn({}){output}", inputs.join(", "))
+            }
+            "qualified_path" => {
+                let self_type = self.ty(&body["self_type"]);
+                let name = body["name"].as_str().unwrap_or_default();
+                match body["trait"].is_null() {
+                    true => format!("{self_type}::{name}"),
+                    false => format!("<{self_type} as {}>::{name}", self.path(&body["trait"])),
+                }
+            }
+            _ => kind.clone(),
+        }
+    }
+
+    /// A path to a type or trait, fully qualified where rustdoc knows where it is defined.
+    fn path(&self, path: &Value) -> String {
+        let qualified = path["id"]
+            .as_u64()
+            .and_then(|id| self.paths[id.to_string()]["path"].as_array())
+            .map(|segments| {
+                segments
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+                    .join("::")
+            });
+        let name =
+            qualified.unwrap_or_else(|| path["path"].as_str().unwrap_or_default().to_string());
+        format!("{name}{}", self.generic_args(&path["args"]))
+    }
+
+    fn generic_args(&self, args: &Value) -> String {
+        if let Some(angle) = args.get("angle_bracketed") {
+            let mut parts: Vec<String> = angle["args"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|arg| {
+                    if let Some(ty) = arg.get("type") {
+                        self.ty(ty)
+                    } else if let Some(lifetime) = arg["lifetime"].as_str() {
+                        lifetime.to_string()
+                    } else if let Some(constant) = arg.get("const") {
+                        constant["expr"].as_str().unwrap_or("_").to_string()
+                    } else {
+                        "_".to_string()
+                    }
+                })
+                .collect();
+            for constraint in angle["constraints"].as_array().into_iter().flatten() {
+                let name = constraint["name"].as_str().unwrap_or_default();
+                let binding = &constraint["binding"];
+                if let Some(ty) = binding["equality"].get("type") {
+                    parts.push(format!("{name} = {}", self.ty(ty)));
+                } else {
+                    parts.push(format!("{name}: {}", self.bounds(&binding["constraint"])));
+                }
+            }
+            if parts.is_empty() {
+                String::new()
+            } else {
+                format!("<{}>", parts.join(", "))
+            }
+        } else if let Some(parenthesized) = args.get("parenthesized") {
+            let inputs: Vec<String> = parenthesized["inputs"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|ty| self.ty(ty))
+                .collect();
+            let output = match &parenthesized["output"] {
+                Value::Null => String::new(),
+                ty => format!(" -> {}", self.ty(ty)),
+            };
+            format!("({}){output}", inputs.join(", "))
+        } else {
+            String::new()
+        }
+    }
+
+    fn bounds(&self, bounds: &Value) -> String {
+        bounds
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|bound| {
+                if let Some(trait_bound) = bound.get("trait_bound") {
+                    let modifier = match trait_bound["modifier"].as_str() {
+                        Some("maybe") => "?",
+                        Some("maybe_const") => "~const ",
+                        _ => "",
+                    };
+                    Some(format!("{modifier}{}", self.path(&trait_bound["trait"])))
+                } else {
+                    bound["outlives"].as_str().map(str::to_string)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" + ")
+    }
+
+    /// `<T: Bound, 'a>`, leaving out the parameters rustdoc made up for `impl Trait` arguments.
+    fn generic_params(&self, generics: &Value) -> String {
+        let params: Vec<String> = generics["params"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|param| {
+                let name = param["name"].as_str().unwrap_or_default();
+                let kind = &param["kind"];
+                if let Some(ty) = kind.get("type") {
+                    if ty["is_synthetic"] == true {
+                        return None;
+                    }
+                    let bounds = self.bounds(&ty["bounds"]);
+                    Some(if bounds.is_empty() {
+                        name.to_string()
+                    } else {
+                        format!("{name}: {bounds}")
+                    })
+                } else if let Some(constant) = kind.get("const") {
+                    Some(format!("const {name}: {}", self.ty(&constant["type"])))
+                } else {
+                    Some(name.to_string())
+                }
+            })
+            .collect();
+        if params.is_empty() {
+            String::new()
+        } else {
+            format!("<{}>", params.join(", "))
+        }
+    }
+
+    fn where_clause(&self, generics: &Value) -> String {
+        let predicates: Vec<String> = generics["where_predicates"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|predicate| {
+                if let Some(bound) = predicate.get("bound_predicate") {
+                    Some(format!(
+                        "{}: {}",
+                        self.ty(&bound["type"]),
+                        self.bounds(&bound["bounds"])
+                    ))
+                } else if let Some(lifetime) = predicate.get("lifetime_predicate") {
+                    let outlives: Vec<&str> = lifetime["outlives"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(Value::as_str)
+                        .collect();
+                    Some(format!(
+                        "{}: {}",
+                        lifetime["lifetime"].as_str().unwrap_or_default(),
+                        outlives.join(" + ")
+                    ))
+                } else {
+                    predicate.get("eq_predicate").map(|eq| {
+                        format!("{} = {}", self.ty(&eq["lhs"]), self.ty(&eq["rhs"]["type"]))
+                    })
+                }
+            })
+            .collect();
+        if predicates.is_empty() {
+            String::new()
+        } else {
+            format!(" where {}", predicates.join(", "))
+        }
+    }
+}
+
+/// Item IDs are numbers in the JSON but strings as keys of `index` and `paths`.
+fn id_key(id: &Value) -> String {
+    match id {
+        Value::String(id) => id.clone(),
+        id => id.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn string_path() -> Value {
+        json!({ "resolved_path": { "path": "String", "id": 10, "args": null } })
+    }
+
+    /// `pub mod api { pub struct Client { pub name: String } impl Client { pub fn name(&self) -> &str } }`
+    /// plus `pub use api::Client;` at the root.
+    fn sample() -> Value {
+        json!({
+            "root": 1,
+            "paths": { "10": { "path": ["alloc", "string", "String"] } },
+            "index": {
+                "1": { "name": "demo", "visibility": "public", "inner": { "module": { "items": [2, 3] } } },
+                "2": { "name": "api", "visibility": "public", "inner": { "module": { "items": [4, 9] } } },
+                "3": { "name": null, "visibility": "public", "inner": { "use": { "source": "api::Client", "name": "Client", "id": 4, "is_glob": false } } },
+                "4": { "name": "Client", "visibility": "public", "inner": { "struct": {
+                    "kind": { "plain": { "fields": [5, 8] } },
+                    "generics": { "params": [], "where_predicates": [] },
+                    "impls": [6, 11]
+                } } },
+                "5": { "name": "name", "visibility": "public", "inner": { "struct_field": string_path() } },
+                "8": { "name": "secret", "visibility": "default", "inner": { "struct_field": string_path() } },
+                "6": { "name": null, "visibility": "default", "inner": { "impl": {
+                    "trait": null, "blanket_impl": null, "is_negative": false,
+                    "generics": { "params": [], "where_predicates": [] },
+                    "for": { "resolved_path": { "path": "Client", "id": 4, "args": null } },
+                    "items": [7]
+                } } },
+                "7": { "name": "name", "visibility": "public", "inner": { "function": {
+                    "sig": {
+                        "inputs": [["self", { "borrowed_ref": { "lifetime": null, "is_mutable": false, "type": { "generic": "Self" } } }]],
+                        "output": { "borrowed_ref": { "lifetime": null, "is_mutable": false, "type": { "primitive": "str" } } }
+                    },
+                    "generics": { "params": [], "where_predicates": [] },
+                    "header": { "is_const": false, "is_async": false, "is_unsafe": false }
+                } } },
+                "11": { "name": null, "visibility": "default", "inner": { "impl": {
+                    "trait": { "path": "Send", "id": 12, "args": null }, "blanket_impl": null, "is_negative": false,
+                    "generics": { "params": [], "where_predicates": [] },
+                    "for": { "resolved_path": { "path": "Client", "id": 4, "args": null } },
+                    "items": []
+                } } },
+                "9": { "name": "private", "visibility": "crate", "inner": { "function": {} } }
+            }
+        })
+    }
+
+    #[test]
+    fn renders_items_under_their_module_and_reexport_paths() {
+        let rendered = render(&sample()).unwrap();
+
+        assert_eq!(
+            rendered,
+            "impl Send for Client\n\
+             pub demo::Client::name: alloc::string::String\n\
+             pub demo::api::Client::name: alloc::string::String\n\
+             pub fn demo::Client::name(&self) -> &str\n\
+             pub fn demo::api::Client::name(&self) -> &str\n\
+             pub mod demo::api\n\
+             pub struct demo::Client\n\
+             pub struct demo::api::Client\n"
+        );
+    }
+
+    #[test]
+    fn diff_lists_removed_then_added_lines() {
+        assert_eq!(diff("a\nb\n", "b\nc\n"), "-a\n+c");
+        assert_eq!(diff("a\n", "a\n"), "");
+    }
+}
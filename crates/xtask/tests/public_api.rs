@@ -0,0 +1,28 @@
+//! The public API of nblm-core must match the checked-in snapshot.
+
+use xtask::public_api;
+
+/// Ignored by default because it needs a nightly toolchain; the `public-api` CI job runs it with
+/// `cargo make public-api-check`.
+#[test]
+#[ignore = "needs a nightly toolchain; run with `cargo make public-api-check`"]
+fn nblm_core_public_api_matches_snapshot() {
+    assert!(
+        public_api::nightly_available(),
+        "rendering the public API needs a nightly toolchain (rustup toolchain install nightly)"
+    );
+    let root = public_api::workspace_root();
+    let snapshot = std::fs::read_to_string(root.join(public_api::SNAPSHOT))
+        .expect("the public API snapshot is checked in");
+
+    let api = public_api::current(&root).expect("render the public API");
+
+    let changes = public_api::diff(&snapshot, &api);
+    assert!(
+        changes.is_empty(),
+        "the public API of {} changed:\n{changes}\n\n\
+         If this is intended, run `cargo run -p xtask -- public-api` and commit {} with the change.",
+        public_api::PACKAGE,
+        public_api::SNAPSHOT
+    );
+}
@@ -10,17 +10,22 @@ from typing import TYPE_CHECKING
 from .nblm import (
     DEFAULT_ENV_TOKEN_KEY,
     DEFAULT_GCLOUD_BINARY,
+    DEFAULT_WORD_BUDGET,
     AudioOverviewRequest,
     AudioOverviewResponse,
     BatchCreateSourcesResponse,
     BatchDeleteNotebooksResponse,
     BatchDeleteSourcesResponse,
+    CallbackTokenProvider,
     EnvTokenProvider,
     GcloudTokenProvider,
     GoogleDriveSource,
     ListRecentlyViewedResponse,
+    MetadataTokenProvider,
+    NblmAuthError,
     NblmClient,
     NblmError,
+    NblmValidationError,
     Notebook,
     NotebookMetadata,
     NotebookSource,
@@ -28,6 +33,8 @@ from .nblm import (
     NotebookSourceMetadata,
     NotebookSourceSettings,
     NotebookSourceYoutubeMetadata,
+    NotebookWordBudget,
+    SourceWordCount,
     TextSource,
     UploadSourceFileResponse,
     UserOAuthProvider,
@@ -45,17 +52,22 @@ except PackageNotFoundError:
 __all__ = [
     "DEFAULT_ENV_TOKEN_KEY",
     "DEFAULT_GCLOUD_BINARY",
+    "DEFAULT_WORD_BUDGET",
     "AudioOverviewRequest",
     "AudioOverviewResponse",
     "BatchCreateSourcesResponse",
     "BatchDeleteNotebooksResponse",
     "BatchDeleteSourcesResponse",
+    "CallbackTokenProvider",
     "EnvTokenProvider",
     "GcloudTokenProvider",
     "GoogleDriveSource",
     "ListRecentlyViewedResponse",
+    "MetadataTokenProvider",
+    "NblmAuthError",
     "NblmClient",
     "NblmError",
+    "NblmValidationError",
     "Notebook",
     "NotebookMetadata",
     "NotebookSource",
@@ -63,6 +75,8 @@ __all__ = [
     "NotebookSourceMetadata",
     "NotebookSourceSettings",
     "NotebookSourceYoutubeMetadata",
+    "NotebookWordBudget",
+    "SourceWordCount",
     "TextSource",
     "UploadSourceFileResponse",
     "UserOAuthProvider",
@@ -3,14 +3,19 @@
 from ._auth import (
     DEFAULT_ENV_TOKEN_KEY,
     DEFAULT_GCLOUD_BINARY,
+    CallbackTokenProvider,
     EnvTokenProvider,
     GcloudTokenProvider,
+    MetadataTokenProvider,
+    NblmAuthError,
     NblmError,
+    NblmValidationError,
     UserOAuthProvider,
     login,
 )
 from ._client import NblmClient
 from ._models import (
+    DEFAULT_WORD_BUDGET,
     AudioOverviewRequest,
     AudioOverviewResponse,
     BatchCreateSourcesResponse,
@@ -25,6 +30,8 @@ from ._models import (
     NotebookSourceMetadata,
     NotebookSourceSettings,
     NotebookSourceYoutubeMetadata,
+    NotebookWordBudget,
+    SourceWordCount,
     TextSource,
     UploadSourceFileResponse,
     VideoSource,
@@ -36,17 +43,22 @@ __version__: str
 __all__ = [
     "DEFAULT_ENV_TOKEN_KEY",
     "DEFAULT_GCLOUD_BINARY",
+    "DEFAULT_WORD_BUDGET",
     "AudioOverviewRequest",
     "AudioOverviewResponse",
     "BatchCreateSourcesResponse",
     "BatchDeleteNotebooksResponse",
     "BatchDeleteSourcesResponse",
+    "CallbackTokenProvider",
     "EnvTokenProvider",
     "GcloudTokenProvider",
     "GoogleDriveSource",
     "ListRecentlyViewedResponse",
+    "MetadataTokenProvider",
+    "NblmAuthError",
     "NblmClient",
     "NblmError",
+    "NblmValidationError",
     "Notebook",
     "NotebookMetadata",
     "NotebookSource",
@@ -54,6 +66,8 @@ __all__ = [
     "NotebookSourceMetadata",
     "NotebookSourceSettings",
     "NotebookSourceYoutubeMetadata",
+    "NotebookWordBudget",
+    "SourceWordCount",
     "TextSource",
     "UploadSourceFileResponse",
     "UserOAuthProvider",
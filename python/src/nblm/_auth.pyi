@@ -1,5 +1,7 @@
 """Authentication providers for nblm"""
 
+from collections.abc import Callable
+
 DEFAULT_GCLOUD_BINARY: str
 DEFAULT_ENV_TOKEN_KEY: str
 
@@ -15,6 +17,12 @@ def login(drive_access: bool = False, force: bool = False) -> None:
 class NblmError(Exception):
     """Base exception for nblm errors"""
 
+class NblmValidationError(NblmError):
+    """Raised when request arguments are rejected before reaching the API"""
+
+class NblmAuthError(NblmError):
+    """Raised when a token provider cannot supply an access token"""
+
 class GcloudTokenProvider:
     """Token provider that uses gcloud CLI for authentication"""
 
@@ -37,6 +45,37 @@ class EnvTokenProvider:
             key: Environment variable name (default: DEFAULT_ENV_TOKEN_KEY)
         """
 
+class MetadataTokenProvider:
+    """Token provider that reads Application Default Credentials from the GCE/Cloud Run metadata server"""
+
+    def __init__(self, host: str | None = None) -> None:
+        """
+        Create a new MetadataTokenProvider
+
+        Args:
+            host: Metadata server host (default: GCE_METADATA_HOST or metadata.google.internal)
+        """
+
+    @property
+    def host(self) -> str:
+        """Return the metadata server host used for token requests."""
+
+class CallbackTokenProvider:
+    """Token provider that calls a Python function for every access token"""
+
+    def __init__(self, callback: Callable[[], str]) -> None:
+        """
+        Create a new CallbackTokenProvider
+
+        The callback runs on a worker thread while the client waits with the GIL released.
+        It must be synchronous and must not use an NblmClient itself; such calls raise
+        NblmError. When one client is shared across threads the callback may run on several
+        threads at once. Exceptions it raises surface as NblmAuthError.
+
+        Args:
+            callback: Function taking no arguments and returning an access token
+        """
+
 class UserOAuthProvider:
     """Token provider that reuses refresh tokens created via the CLI's user-oauth flow"""
 
@@ -53,4 +92,10 @@ class UserOAuthProvider:
     def endpoint_location(self) -> str:
         """Return the endpoint location associated with the stored token."""
 
-TokenProvider = GcloudTokenProvider | EnvTokenProvider | UserOAuthProvider
+TokenProvider = (
+    GcloudTokenProvider
+    | EnvTokenProvider
+    | UserOAuthProvider
+    | MetadataTokenProvider
+    | CallbackTokenProvider
+)
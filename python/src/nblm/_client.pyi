@@ -1,6 +1,7 @@
 """NblmClient for NotebookLM API operations"""
 
 import os
+from typing import Any
 
 from ._auth import TokenProvider
 from ._models import (
@@ -13,6 +14,7 @@ from ._models import (
     ListRecentlyViewedResponse,
     Notebook,
     NotebookSource,
+    NotebookWordBudget,
     TextSource,
     UploadSourceFileResponse,
     VideoSource,
@@ -29,21 +31,46 @@ class NblmClient:
         location: str = "global",
         endpoint_location: str = "global",
         profile: str = "enterprise",
+        *,
+        max_retries: int | None = None,
+        retry_min_delay: float | None = None,
+        retry_max_delay: float | None = None,
+        timeout: float | None = None,
+        language: str | None = None,
     ) -> None:
         """
         Create a new NblmClient
 
+        Retry and timeout settings not passed here are read from NBLM_MAX_RETRIES,
+        NBLM_RETRY_MIN_DELAY, NBLM_RETRY_MAX_DELAY and NBLM_TIMEOUT (durations such as
+        "500ms" or "30s"), falling back to the built-in defaults.
+
         Args:
             token_provider: Token provider for authentication
             project_number: Google Cloud project number (required for the enterprise profile)
             location: NotebookLM location (default: "global")
             endpoint_location: API endpoint location (default: "global")
             profile: API profile selection (experimental, default: "enterprise")
+            max_retries: Retries after the initial request for 429/5xx responses
+            retry_min_delay: Minimum backoff between retries, in seconds
+            retry_max_delay: Maximum backoff between retries, in seconds
+            timeout: HTTP request timeout, in seconds
+            language: BCP 47 language tag (such as "fr" or "pt-BR") sent as Accept-Language,
+                so API messages come back in that language where supported
 
         Raises:
+            NblmValidationError: If a setting or one of the NBLM_* variables is invalid
             NblmError: If the client cannot be created
         """
 
+    @property
+    def effective_config(self) -> dict[str, Any]:
+        """
+        Settings in effect after environment and keyword overrides, for debugging.
+
+        Keys: max_retries, retry_min_delay, retry_max_delay, timeout (seconds) and base_url.
+        """
+
     @staticmethod
     def with_user_oauth(
         project_number: int | None = ...,
@@ -64,12 +91,13 @@ class NblmClient:
             NblmClient: Configured client that uses the stored refresh token
         """
 
-    def create_notebook(self, title: str) -> Notebook:
+    def create_notebook(self, title: str, emoji: str | None = None) -> Notebook:
         """
         Create a new notebook with the given title
 
         Args:
             title: The title of the notebook
+            emoji: Optional emoji shown next to the notebook title
 
         Returns:
             Notebook: The created notebook
@@ -78,6 +106,30 @@ class NblmClient:
             NblmError: If the notebook creation fails
         """
 
+    def update_notebook(
+        self,
+        notebook_id: str,
+        title: str | None = None,
+        emoji: str | None = None,
+    ) -> Notebook:
+        """
+        Update a notebook's title and/or emoji
+
+        Only the provided fields are sent to the API (via the PATCH updateMask).
+
+        Args:
+            notebook_id: Notebook identifier (notebook resource ID, not full name)
+            title: New title, or None to keep the current one
+            emoji: New emoji, or None to keep the current one
+
+        Returns:
+            Notebook: The updated notebook
+
+        Raises:
+            NblmValidationError: If neither title nor emoji is provided
+            NblmError: If the request fails
+        """
+
     def list_recently_viewed(self, page_size: int | None = None) -> ListRecentlyViewedResponse:
         """
         List recently viewed notebooks
@@ -92,6 +144,20 @@ class NblmClient:
             NblmError: If the request fails
         """
 
+    def notebook_word_budget(self, notebook_id: str) -> NotebookWordBudget:
+        """
+        Sum the word counts of every source in a notebook
+
+        Args:
+            notebook_id: Notebook identifier (notebook resource ID, not full name)
+
+        Returns:
+            NotebookWordBudget: Total word count and per-source breakdown (largest first)
+
+        Raises:
+            NblmError: If the request fails
+        """
+
     def delete_notebooks(self, notebook_names: list[str]) -> BatchDeleteNotebooksResponse:
         """
         Delete one or more notebooks
@@ -158,7 +224,10 @@ class NblmClient:
             UploadSourceFileResponse: Response containing the created source ID
 
         Raises:
-            NblmError: If validation fails or the API request fails
+            NblmValidationError: If the path is missing, not a regular file or empty,
+                or the file changed while it was being read
+            OSError: If the file cannot be opened or read
+            NblmError: If the API request fails
         """
 
     def delete_sources(
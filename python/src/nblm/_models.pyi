@@ -70,18 +70,30 @@ class BatchCreateSourcesResponse:
 
     sources: list[NotebookSource]
     error_count: int | None
+    source_ids: list[str]
+
+    def to_dict(self) -> dict[str, Any]:
+        """Return the fields as plain dicts and lists, ready for ``json.dumps``."""
 
 class BatchDeleteSourcesResponse:
     """Response from deleting sources from a notebook."""
 
     extra: dict[str, Any]
 
+    def to_dict(self) -> dict[str, Any]:
+        """Return the fields as plain dicts and lists, ready for ``json.dumps``."""
+
 class UploadSourceFileResponse:
     """Response from uploading a file source to a notebook."""
 
     source_id: NotebookSourceId | None
+    name: str | None
+    state: str | None
     extra: dict[str, Any]
 
+    def to_dict(self) -> dict[str, Any]:
+        """Return the fields as plain dicts and lists, ready for ``json.dumps``."""
+
 """Data models for nblm"""
 
 class NotebookSourceYoutubeMetadata:
@@ -91,18 +103,27 @@ class NotebookSourceYoutubeMetadata:
     video_id: str | None
     extra: dict[str, Any]
 
+    def to_dict(self) -> dict[str, Any]:
+        """Return the fields as plain dicts and lists, ready for ``json.dumps``."""
+
 class NotebookSourceSettings:
     """Source-level ingestion settings returned by the API."""
 
     status: str | None
     extra: dict[str, Any]
 
+    def to_dict(self) -> dict[str, Any]:
+        """Return the fields as plain dicts and lists, ready for ``json.dumps``."""
+
 class NotebookSourceId:
     """Internal identifier for a notebook source."""
 
     id: str | None
     extra: dict[str, Any]
 
+    def to_dict(self) -> dict[str, Any]:
+        """Return the fields as plain dicts and lists, ready for ``json.dumps``."""
+
 class NotebookSourceMetadata:
     """Timestamps and other attributes describing a notebook source."""
 
@@ -111,6 +132,9 @@ class NotebookSourceMetadata:
     youtube_metadata: NotebookSourceYoutubeMetadata | None
     extra: dict[str, Any]
 
+    def to_dict(self) -> dict[str, Any]:
+        """Return the fields as plain dicts and lists, ready for ``json.dumps``."""
+
 class NotebookSource:
     """A single source that has been added to a notebook."""
 
@@ -119,8 +143,17 @@ class NotebookSource:
     metadata: NotebookSourceMetadata | None
     settings: NotebookSourceSettings | None
     source_id: NotebookSourceId | None
+    id: str | None
     extra: dict[str, Any]
 
+    def to_dict(self) -> dict[str, Any]:
+        """Return the fields as plain dicts and lists, ready for ``json.dumps``."""
+
+    def __eq__(self, other: object) -> bool:
+        """Sources are equal when their resource names match."""
+
+    def __hash__(self) -> int: ...
+
 class NotebookMetadata:
     """Top-level metadata describing a notebook."""
 
@@ -130,6 +163,9 @@ class NotebookMetadata:
     last_viewed: str | None
     extra: dict[str, Any]
 
+    def to_dict(self) -> dict[str, Any]:
+        """Return the fields as plain dicts and lists, ready for ``json.dumps``."""
+
 class Notebook:
     """Represents a NotebookLM notebook with structured fields."""
 
@@ -137,21 +173,62 @@ class Notebook:
     title: str
     notebook_id: str | None
     emoji: str | None
+    summary: str | None
     metadata: NotebookMetadata | None
     sources: list[NotebookSource]
     extra: dict[str, Any]
 
+    def to_dict(self) -> dict[str, Any]:
+        """Return the fields as plain dicts and lists, ready for ``json.dumps``."""
+
+    def __eq__(self, other: object) -> bool:
+        """Notebooks are equal when their name, notebook ID and title match."""
+
+    def __hash__(self) -> int: ...
+
 class ListRecentlyViewedResponse:
     """Response from listing recently viewed notebooks."""
 
     notebooks: list[Notebook]
 
+    def to_dict(self) -> dict[str, Any]:
+        """Return the fields as plain dicts and lists, ready for ``json.dumps``."""
+
+DEFAULT_WORD_BUDGET: int
+
+class SourceWordCount:
+    """Word count of a single source in a notebook budget report."""
+
+    name: str
+    title: str | None
+    word_count: int | None
+
+    def to_dict(self) -> dict[str, Any]:
+        """Return the fields as plain dicts and lists, ready for ``json.dumps``."""
+
+class NotebookWordBudget:
+    """Total word count of a notebook with a per-source breakdown (largest first)."""
+
+    notebook_id: str
+    total_words: int
+    sources: list[SourceWordCount]
+    missing_word_counts: int
+
+    def over_budget(self, budget: int = DEFAULT_WORD_BUDGET) -> int | None:
+        """Return the number of words above ``budget``, or None when the notebook fits."""
+
+    def to_dict(self) -> dict[str, Any]:
+        """Return the fields as plain dicts and lists, ready for ``json.dumps``."""
+
 class BatchDeleteNotebooksResponse:
     """Aggregated results from batch notebook deletion."""
 
     deleted_notebooks: list[str]
     failed_notebooks: list[str]
 
+    def to_dict(self) -> dict[str, Any]:
+        """Return the fields as plain dicts and lists, ready for ``json.dumps``."""
+
 class AudioOverviewRequest:
     """Request for creating an audio overview.
 
@@ -170,3 +247,6 @@ class AudioOverviewResponse:
     status: str | None
     generation_options: Any
     extra: dict[str, Any]
+
+    def to_dict(self) -> dict[str, Any]:
+        """Return the fields as plain dicts and lists, ready for ``json.dumps``."""
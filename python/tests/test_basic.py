@@ -70,6 +70,14 @@ def test_env_token_provider_creation() -> None:
     assert provider_custom is not None
 
 
+def test_metadata_token_provider_creation() -> None:
+    """Test creating a MetadataTokenProvider"""
+    from nblm import MetadataTokenProvider
+
+    provider = MetadataTokenProvider(host="127.0.0.1:8080")
+    assert provider.host == "127.0.0.1:8080"
+
+
 def test_client_creation() -> None:
     """Test creating an NblmClient"""
     from nblm import GcloudTokenProvider, NblmClient
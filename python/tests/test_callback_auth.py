@@ -0,0 +1,114 @@
+"""
+Tests for CallbackTokenProvider against a local mock server
+"""
+
+import json
+import threading
+from collections.abc import Iterator
+from http.server import BaseHTTPRequestHandler, HTTPServer
+from typing import Any
+
+import pytest
+
+from nblm import CallbackTokenProvider, NblmAuthError, NblmClient, NblmError
+
+PROJECT_NUMBER = "123456"
+PARENT = f"projects/{PROJECT_NUMBER}/locations/global"
+
+RECENT = {"notebooks": [{"name": f"{PARENT}/notebooks/nb1", "notebookId": "nb1", "title": "A"}]}
+
+
+class _AuthHandler(BaseHTTPRequestHandler):
+    authorizations: list[str] = []
+    rejected: set[str] = set()
+
+    def do_GET(self) -> None:  # noqa: N802
+        authorization = self.headers.get("Authorization", "")
+        type(self).authorizations.append(authorization)
+        if authorization in type(self).rejected:
+            status, payload = 401, {"error": {"message": "token expired"}}
+        else:
+            status, payload = 200, RECENT
+        body = json.dumps(payload).encode()
+        self.send_response(status)
+        self.send_header("Content-Type", "application/json")
+        self.send_header("Content-Length", str(len(body)))
+        self.end_headers()
+        self.wfile.write(body)
+
+    def log_message(self, format: str, *args: Any) -> None:  # noqa: A002
+        pass
+
+
+@pytest.fixture
+def mock_server(monkeypatch: pytest.MonkeyPatch) -> Iterator[type[_AuthHandler]]:
+    _AuthHandler.authorizations = []
+    _AuthHandler.rejected = set()
+    server = HTTPServer(("127.0.0.1", 0), _AuthHandler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    monkeypatch.setenv("NBLM_BASE_URL", f"http://127.0.0.1:{server.server_port}/v1alpha")
+    monkeypatch.setenv("NBLM_RETRY_MIN_DELAY", "1ms")
+    monkeypatch.setenv("NBLM_RETRY_MAX_DELAY", "5ms")
+    try:
+        yield _AuthHandler
+    finally:
+        server.shutdown()
+        server.server_close()
+
+
+def _client(callback: Any) -> NblmClient:
+    return NblmClient(CallbackTokenProvider(callback), project_number=PROJECT_NUMBER)
+
+
+def test_client_call_uses_callback_token(mock_server: type[_AuthHandler]) -> None:
+    response = _client(lambda: "vault-token").list_recently_viewed()
+
+    assert [notebook.notebook_id for notebook in response.notebooks] == ["nb1"]
+    assert mock_server.authorizations == ["Bearer vault-token"]
+
+
+def test_callback_is_called_again_on_refresh(mock_server: type[_AuthHandler]) -> None:
+    calls = 0
+
+    def next_token() -> str:
+        nonlocal calls
+        calls += 1
+        return f"token-{calls}"
+
+    mock_server.rejected = {"Bearer token-1"}
+    _client(next_token).list_recently_viewed()
+
+    assert calls == 2
+    assert mock_server.authorizations == ["Bearer token-1", "Bearer token-2"]
+
+
+def test_callback_exception_surfaces_as_auth_error(mock_server: type[_AuthHandler]) -> None:
+    def sealed() -> str:
+        raise RuntimeError("vault is sealed")
+
+    with pytest.raises(NblmAuthError, match="vault is sealed"):
+        _client(sealed).list_recently_viewed()
+    assert mock_server.authorizations == []
+
+
+def test_auth_error_is_an_nblm_error() -> None:
+    assert issubclass(NblmAuthError, NblmError)
+
+
+def test_callback_must_return_str(mock_server: type[_AuthHandler]) -> None:
+    with pytest.raises(NblmAuthError, match="must return str, got int"):
+        _client(lambda: 42).list_recently_viewed()
+
+
+def test_async_callback_is_rejected(mock_server: type[_AuthHandler]) -> None:
+    async def fetch() -> str:
+        return "token"
+
+    with pytest.raises(NblmAuthError, match="synchronous function"):
+        _client(fetch).list_recently_viewed()
+
+
+def test_non_callable_is_rejected() -> None:
+    with pytest.raises(TypeError, match="callable"):
+        CallbackTokenProvider("not-a-function")  # type: ignore[arg-type]
@@ -0,0 +1,130 @@
+"""
+Tests for retry and timeout configuration from NBLM_* variables and constructor arguments
+"""
+
+import threading
+from collections.abc import Iterator
+from http.server import BaseHTTPRequestHandler, HTTPServer
+from typing import Any
+
+import pytest
+
+from nblm import EnvTokenProvider, NblmClient, NblmError, NblmValidationError
+
+
+class _RateLimitedHandler(BaseHTTPRequestHandler):
+    requests = 0
+
+    def do_GET(self) -> None:  # noqa: N802
+        type(self).requests += 1
+        payload = b'{"error": {"message": "Too Many Requests"}}'
+        self.send_response(429)
+        self.send_header("Content-Type", "application/json")
+        self.send_header("Content-Length", str(len(payload)))
+        self.end_headers()
+        self.wfile.write(payload)
+
+    def log_message(self, format: str, *args: Any) -> None:  # noqa: A002
+        pass
+
+
+@pytest.fixture
+def rate_limited(monkeypatch: pytest.MonkeyPatch) -> Iterator[type[_RateLimitedHandler]]:
+    _RateLimitedHandler.requests = 0
+    server = HTTPServer(("127.0.0.1", 0), _RateLimitedHandler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    monkeypatch.setenv("NBLM_BASE_URL", f"http://127.0.0.1:{server.server_port}/v1alpha")
+    monkeypatch.setenv("NBLM_ACCESS_TOKEN", "DUMMY_TOKEN")
+    monkeypatch.setenv("NBLM_RETRY_MIN_DELAY", "1ms")
+    monkeypatch.setenv("NBLM_RETRY_MAX_DELAY", "5ms")
+    try:
+        yield _RateLimitedHandler
+    finally:
+        server.shutdown()
+        server.server_close()
+
+
+def _client(**kwargs: Any) -> NblmClient:
+    return NblmClient(EnvTokenProvider(), project_number="123456", **kwargs)
+
+
+def test_env_sets_retry_count(
+    rate_limited: type[_RateLimitedHandler], monkeypatch: pytest.MonkeyPatch
+) -> None:
+    monkeypatch.setenv("NBLM_MAX_RETRIES", "2")
+
+    with pytest.raises(NblmError, match="Too Many Requests"):
+        _client().list_recently_viewed()
+    assert rate_limited.requests == 3
+
+
+def test_constructor_argument_overrides_env(
+    rate_limited: type[_RateLimitedHandler], monkeypatch: pytest.MonkeyPatch
+) -> None:
+    monkeypatch.setenv("NBLM_MAX_RETRIES", "2")
+
+    with pytest.raises(NblmError):
+        _client(max_retries=0).list_recently_viewed()
+    assert rate_limited.requests == 1
+
+
+def test_effective_config_reports_resolved_settings(monkeypatch: pytest.MonkeyPatch) -> None:
+    monkeypatch.setenv("NBLM_ACCESS_TOKEN", "DUMMY_TOKEN")
+    monkeypatch.setenv("NBLM_BASE_URL", "http://127.0.0.1:9/v1alpha")
+    monkeypatch.setenv("NBLM_MAX_RETRIES", "5")
+    monkeypatch.setenv("NBLM_RETRY_MAX_DELAY", "20s")
+    monkeypatch.setenv("NBLM_TIMEOUT", "45s")
+
+    config = _client(retry_min_delay=0.25).effective_config
+    assert config == {
+        "max_retries": 5,
+        "retry_min_delay": 0.25,
+        "retry_max_delay": 20.0,
+        "timeout": 45.0,
+        "base_url": "http://127.0.0.1:9/v1alpha",
+    }
+
+
+class _LanguageHandler(BaseHTTPRequestHandler):
+    accept_language: str | None = None
+
+    def do_GET(self) -> None:  # noqa: N802
+        type(self).accept_language = self.headers.get("Accept-Language")
+        payload = b'{"notebooks": []}'
+        self.send_response(200)
+        self.send_header("Content-Type", "application/json")
+        self.send_header("Content-Length", str(len(payload)))
+        self.end_headers()
+        self.wfile.write(payload)
+
+    def log_message(self, format: str, *args: Any) -> None:  # noqa: A002
+        pass
+
+
+def test_language_is_sent_as_accept_language(monkeypatch: pytest.MonkeyPatch) -> None:
+    server = HTTPServer(("127.0.0.1", 0), _LanguageHandler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    monkeypatch.setenv("NBLM_BASE_URL", f"http://127.0.0.1:{server.server_port}/v1alpha")
+    monkeypatch.setenv("NBLM_ACCESS_TOKEN", "DUMMY_TOKEN")
+    try:
+        _client(language="fr-CA").list_recently_viewed()
+    finally:
+        server.shutdown()
+        server.server_close()
+    assert _LanguageHandler.accept_language == "fr-CA"
+
+
+def test_invalid_language_is_rejected(monkeypatch: pytest.MonkeyPatch) -> None:
+    monkeypatch.setenv("NBLM_ACCESS_TOKEN", "DUMMY_TOKEN")
+
+    with pytest.raises(NblmValidationError, match="invalid language tag"):
+        _client(language="english")
+
+
+def test_invalid_env_value_is_rejected(monkeypatch: pytest.MonkeyPatch) -> None:
+    monkeypatch.setenv("NBLM_MAX_RETRIES", "lots")
+
+    with pytest.raises(NblmValidationError, match="NBLM_MAX_RETRIES"):
+        _client()
@@ -0,0 +1,124 @@
+"""
+Tests for model equality, hashing and to_dict() against a local mock server
+"""
+
+import json
+import threading
+from collections.abc import Iterator
+from http.server import BaseHTTPRequestHandler, HTTPServer
+from typing import Any
+
+import pytest
+
+from nblm import EnvTokenProvider, ListRecentlyViewedResponse, NblmClient
+
+PROJECT_NUMBER = "123456"
+PARENT = f"projects/{PROJECT_NUMBER}/locations/global"
+
+RECENT = {
+    "notebooks": [
+        {
+            "name": f"{PARENT}/notebooks/nb1",
+            "notebookId": "nb1",
+            "title": "Research",
+            "emoji": "📓",
+            "description": "Notes from the conference talk.",
+            "metadata": {"createTime": "2025-01-01T00:00:00Z", "isShared": False},
+            "sources": [
+                {
+                    "name": f"{PARENT}/notebooks/nb1/sources/s1",
+                    "title": "Talk",
+                    "metadata": {
+                        "wordCount": 1200,
+                        "youtubeMetadata": {"channelName": "Channel", "videoId": "vid1"},
+                    },
+                    "settings": {"status": "SOURCE_STATUS_COMPLETE"},
+                    "sourceId": {"id": "s1"},
+                    "unknownField": {"nested": [1, 2.5, None, True]},
+                }
+            ],
+            "futureField": "kept",
+        },
+        {"name": f"{PARENT}/notebooks/nb2", "notebookId": "nb2", "title": "Other"},
+    ]
+}
+
+
+class _RecentHandler(BaseHTTPRequestHandler):
+    def do_GET(self) -> None:  # noqa: N802
+        payload = json.dumps(RECENT).encode()
+        self.send_response(200)
+        self.send_header("Content-Type", "application/json")
+        self.send_header("Content-Length", str(len(payload)))
+        self.end_headers()
+        self.wfile.write(payload)
+
+    def log_message(self, format: str, *args: Any) -> None:  # noqa: A002
+        pass
+
+
+@pytest.fixture
+def mock_server(monkeypatch: pytest.MonkeyPatch) -> Iterator[None]:
+    server = HTTPServer(("127.0.0.1", 0), _RecentHandler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    monkeypatch.setenv("NBLM_BASE_URL", f"http://127.0.0.1:{server.server_port}/v1alpha")
+    monkeypatch.setenv("NBLM_ACCESS_TOKEN", "DUMMY_TOKEN")
+    try:
+        yield
+    finally:
+        server.shutdown()
+        server.server_close()
+
+
+def _recent() -> ListRecentlyViewedResponse:
+    client = NblmClient(EnvTokenProvider(), project_number=PROJECT_NUMBER)
+    return client.list_recently_viewed()
+
+
+def test_notebooks_compare_by_identity(mock_server: None) -> None:
+    first = _recent().notebooks
+    second = _recent().notebooks
+
+    assert first[0] is not second[0]
+    assert first[0] == second[0]
+    assert first[0] != first[1]
+    assert first[0] != "nb1"
+    assert first[0].sources[0] == second[0].sources[0]
+
+
+def test_models_deduplicate_in_sets(mock_server: None) -> None:
+    notebooks = {*_recent().notebooks, *_recent().notebooks}
+    assert {notebook.notebook_id for notebook in notebooks} == {"nb1", "nb2"}
+
+    sources = {*_recent().notebooks[0].sources, *_recent().notebooks[0].sources}
+    assert len(sources) == 1
+
+
+def test_to_dict_returns_plain_structures(mock_server: None) -> None:
+    response = _recent()
+    notebook = response.notebooks[0]
+    data = notebook.to_dict()
+
+    assert data["name"] == notebook.name
+    assert data["notebook_id"] == "nb1"
+    assert data["title"] == "Research"
+    assert data["emoji"] == "📓"
+    assert data["summary"] == "Notes from the conference talk."
+    assert data["metadata"]["create_time"] == "2025-01-01T00:00:00Z"
+    assert data["metadata"]["is_shared"] is False
+    assert data["extra"] == {"futureField": "kept"}
+
+    source = data["sources"][0]
+    assert source["id"] == "s1"
+    assert source["source_id"] == {"id": "s1", "extra": {}}
+    assert source["settings"]["status"] == "SOURCE_STATUS_COMPLETE"
+    assert source["metadata"]["word_count"] == 1200
+    assert source["metadata"]["youtube_metadata"]["video_id"] == "vid1"
+    assert source["extra"] == {"unknownField": {"nested": [1, 2.5, None, True]}}
+
+    # Plain structures only: the result survives a JSON round trip unchanged.
+    assert json.loads(json.dumps(data)) == data
+    assert response.to_dict()["notebooks"][0] == data
+    assert response.to_dict()["notebooks"][1]["metadata"] is None
+    assert response.to_dict()["notebooks"][1]["summary"] is None
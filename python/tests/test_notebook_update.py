@@ -0,0 +1,103 @@
+"""
+Tests for NblmClient.update_notebook against a local mock server
+"""
+
+import json
+import threading
+from collections.abc import Iterator
+from http.server import BaseHTTPRequestHandler, HTTPServer
+from typing import Any
+from urllib.parse import parse_qs, urlparse
+
+import pytest
+
+from nblm import EnvTokenProvider, NblmClient, NblmValidationError
+
+PROJECT_NUMBER = "123456"
+NOTEBOOK_ID = "nb-rename"
+
+
+class _RecordingHandler(BaseHTTPRequestHandler):
+    requests: list[dict[str, Any]] = []
+
+    def do_PATCH(self) -> None:  # noqa: N802
+        length = int(self.headers.get("Content-Length", "0"))
+        body = json.loads(self.rfile.read(length) or b"{}")
+        parsed = urlparse(self.path)
+        self.requests.append(
+            {
+                "path": parsed.path,
+                "query": parse_qs(parsed.query),
+                "body": body,
+            }
+        )
+        response = {
+            "name": f"projects/{PROJECT_NUMBER}/locations/global/notebooks/{NOTEBOOK_ID}",
+            "notebookId": NOTEBOOK_ID,
+            "title": body.get("title", "Original title"),
+            "emoji": body.get("emoji", "📓"),
+        }
+        payload = json.dumps(response).encode()
+        self.send_response(200)
+        self.send_header("Content-Type", "application/json")
+        self.send_header("Content-Length", str(len(payload)))
+        self.end_headers()
+        self.wfile.write(payload)
+
+    def log_message(self, format: str, *args: Any) -> None:  # noqa: A002
+        pass
+
+
+@pytest.fixture
+def mock_server(monkeypatch: pytest.MonkeyPatch) -> Iterator[list[dict[str, Any]]]:
+    _RecordingHandler.requests = []
+    server = HTTPServer(("127.0.0.1", 0), _RecordingHandler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    monkeypatch.setenv("NBLM_BASE_URL", f"http://127.0.0.1:{server.server_port}/v1alpha")
+    monkeypatch.setenv("NBLM_ACCESS_TOKEN", "DUMMY_TOKEN")
+    try:
+        yield _RecordingHandler.requests
+    finally:
+        server.shutdown()
+        server.server_close()
+
+
+def _client() -> NblmClient:
+    return NblmClient(EnvTokenProvider(), project_number=PROJECT_NUMBER)
+
+
+def test_update_notebook_renames_with_title_only_mask(
+    mock_server: list[dict[str, Any]],
+) -> None:
+    notebook = _client().update_notebook(NOTEBOOK_ID, title="Renamed")
+
+    assert notebook.title == "Renamed"
+    assert len(mock_server) == 1
+    request = mock_server[0]
+    assert request["path"] == (
+        f"/v1alpha/projects/{PROJECT_NUMBER}/locations/global/notebooks/{NOTEBOOK_ID}"
+    )
+    assert request["query"]["updateMask"] == ["title"]
+    assert request["body"] == {"title": "Renamed"}
+
+
+def test_update_notebook_emoji_only_mask(mock_server: list[dict[str, Any]]) -> None:
+    notebook = _client().update_notebook(NOTEBOOK_ID, emoji="🚀")
+
+    assert notebook.emoji == "🚀"
+    assert mock_server[0]["query"]["updateMask"] == ["emoji"]
+    assert mock_server[0]["body"] == {"emoji": "🚀"}
+
+
+def test_update_notebook_both_fields_mask(mock_server: list[dict[str, Any]]) -> None:
+    _client().update_notebook(NOTEBOOK_ID, title="Renamed", emoji="🚀")
+
+    assert mock_server[0]["query"]["updateMask"] == ["title,emoji"]
+
+
+def test_update_notebook_requires_a_field(mock_server: list[dict[str, Any]]) -> None:
+    with pytest.raises(NblmValidationError):
+        _client().update_notebook(NOTEBOOK_ID)
+
+    assert mock_server == []
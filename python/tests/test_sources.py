@@ -1,5 +1,10 @@
 """Tests for sources operations bindings."""
 
+import os
+from pathlib import Path
+
+import pytest
+
 import nblm
 
 
@@ -22,3 +27,29 @@ def test_client_sources_methods_exist() -> None:
     assert hasattr(nblm.NblmClient, "delete_sources")
     assert hasattr(nblm.NblmClient, "upload_source_file")
     assert hasattr(nblm.NblmClient, "get_source")
+
+
+def _client(monkeypatch: pytest.MonkeyPatch) -> nblm.NblmClient:
+    monkeypatch.setenv("NBLM_ACCESS_TOKEN", "DUMMY_TOKEN")
+    return nblm.NblmClient(nblm.EnvTokenProvider(), project_number="123456")
+
+
+def test_upload_source_file_rejects_empty_files(
+    monkeypatch: pytest.MonkeyPatch, tmp_path: Path
+) -> None:
+    empty = tmp_path / "empty.txt"
+    empty.write_bytes(b"")
+
+    with pytest.raises(nblm.NblmValidationError, match="cannot upload empty files"):
+        _client(monkeypatch).upload_source_file("nb1", str(empty))
+
+
+@pytest.mark.skipif(not hasattr(os, "mkfifo"), reason="needs named pipes")
+def test_upload_source_file_rejects_fifos_without_blocking(
+    monkeypatch: pytest.MonkeyPatch, tmp_path: Path
+) -> None:
+    fifo = tmp_path / "pipe"
+    os.mkfifo(fifo)
+
+    with pytest.raises(nblm.NblmValidationError, match="is not a file"):
+        _client(monkeypatch).upload_source_file("nb1", str(fifo))
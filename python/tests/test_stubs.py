@@ -0,0 +1,65 @@
+"""
+Check that the committed type stubs match the compiled module
+"""
+
+import ast
+from pathlib import Path
+
+import nblm
+from nblm import nblm as native
+
+STUB_DIR = Path(__file__).resolve().parents[1] / "src" / "nblm"
+STUB_FILES = ["_auth.pyi", "_client.pyi", "_models.pyi"]
+
+
+def _stub_members() -> dict[str, set[str] | None]:
+    """Top-level names declared in the stubs; classes map to their declared members."""
+    members: dict[str, set[str] | None] = {}
+    for file_name in STUB_FILES:
+        tree = ast.parse((STUB_DIR / file_name).read_text())
+        for node in tree.body:
+            if isinstance(node, ast.ClassDef):
+                names: set[str] = set()
+                for item in node.body:
+                    if isinstance(item, ast.FunctionDef):
+                        names.add(item.name)
+                    elif isinstance(item, ast.AnnAssign) and isinstance(item.target, ast.Name):
+                        names.add(item.target.id)
+                members[node.name] = names
+            elif isinstance(node, ast.FunctionDef):
+                members[node.name] = None
+            elif isinstance(node, ast.AnnAssign) and isinstance(node.target, ast.Name):
+                members[node.target.id] = None
+    return members
+
+
+def _runtime_names() -> set[str]:
+    return {name for name in dir(native) if not name.startswith("_")} - {"nblm"}
+
+
+def test_stubs_declare_every_runtime_name() -> None:
+    assert set(_stub_members()) == _runtime_names()
+
+
+def test_package_exports_match_stub_all() -> None:
+    tree = ast.parse((STUB_DIR / "__init__.pyi").read_text())
+    stub_all: list[str] = []
+    for node in tree.body:
+        if isinstance(node, ast.Assign) and any(
+            isinstance(target, ast.Name) and target.id == "__all__" for target in node.targets
+        ):
+            stub_all = ast.literal_eval(node.value)
+    assert sorted(stub_all) == sorted(nblm.__all__)
+
+
+def test_stub_class_members_exist_at_runtime() -> None:
+    for name, declared in _stub_members().items():
+        if declared is None:
+            continue
+        runtime_class = getattr(native, name)
+        missing = {
+            member
+            for member in declared
+            if not member.startswith("__") and not hasattr(runtime_class, member)
+        }
+        assert not missing, f"{name} stub declares members missing at runtime: {missing}"
@@ -0,0 +1,125 @@
+"""
+Tests for sharing NblmClient across Python threads against a local mock server
+"""
+
+import json
+import threading
+import time
+from collections.abc import Iterator
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+from typing import Any
+
+import pytest
+
+from nblm import CallbackTokenProvider, NblmAuthError, NblmClient
+
+PROJECT_NUMBER = "123456"
+PARENT = f"projects/{PROJECT_NUMBER}/locations/global"
+
+RECENT = {"notebooks": [{"name": f"{PARENT}/notebooks/nb1", "notebookId": "nb1", "title": "A"}]}
+
+DELAY = 0.3
+THREADS = 8
+
+
+class _SlowHandler(BaseHTTPRequestHandler):
+    lock = threading.Lock()
+    in_flight = 0
+    max_in_flight = 0
+
+    def do_GET(self) -> None:  # noqa: N802
+        handler = type(self)
+        with handler.lock:
+            handler.in_flight += 1
+            handler.max_in_flight = max(handler.max_in_flight, handler.in_flight)
+        time.sleep(DELAY)
+        with handler.lock:
+            handler.in_flight -= 1
+        body = json.dumps(RECENT).encode()
+        self.send_response(200)
+        self.send_header("Content-Type", "application/json")
+        self.send_header("Content-Length", str(len(body)))
+        self.end_headers()
+        self.wfile.write(body)
+
+    def log_message(self, format: str, *args: Any) -> None:  # noqa: A002
+        pass
+
+
+@pytest.fixture
+def mock_server(monkeypatch: pytest.MonkeyPatch) -> Iterator[type[_SlowHandler]]:
+    _SlowHandler.in_flight = 0
+    _SlowHandler.max_in_flight = 0
+    server = ThreadingHTTPServer(("127.0.0.1", 0), _SlowHandler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    monkeypatch.setenv("NBLM_BASE_URL", f"http://127.0.0.1:{server.server_port}/v1alpha")
+    try:
+        yield _SlowHandler
+    finally:
+        server.shutdown()
+        server.server_close()
+
+
+def _client(callback: Any) -> NblmClient:
+    return NblmClient(CallbackTokenProvider(callback), project_number=PROJECT_NUMBER)
+
+
+def _run_in_threads(target: Any) -> list[Any]:
+    results: list[Any] = [None] * THREADS
+
+    def run(index: int) -> None:
+        try:
+            results[index] = target()
+        except Exception as err:  # noqa: BLE001
+            results[index] = err
+
+    threads = [threading.Thread(target=run, args=(index,)) for index in range(THREADS)]
+    for thread in threads:
+        thread.start()
+    for thread in threads:
+        thread.join()
+    return results
+
+
+def test_shared_client_runs_calls_in_parallel(mock_server: type[_SlowHandler]) -> None:
+    client = _client(lambda: "token")
+
+    started = time.monotonic()
+    results = _run_in_threads(client.list_recently_viewed)
+    elapsed = time.monotonic() - started
+
+    assert all(not isinstance(result, Exception) for result in results), results
+    assert all([nb.notebook_id for nb in result.notebooks] == ["nb1"] for result in results)
+    # Serial calls would take THREADS * DELAY (2.4s).
+    assert elapsed < THREADS * DELAY / 2
+    assert mock_server.max_in_flight > 1
+
+
+def test_callback_is_safe_to_call_from_several_threads(mock_server: type[_SlowHandler]) -> None:
+    lock = threading.Lock()
+    calls = 0
+
+    def token() -> str:
+        nonlocal calls
+        with lock:
+            calls += 1
+        return "token"
+
+    client = _client(token)
+    results = _run_in_threads(client.list_recently_viewed)
+
+    assert all(not isinstance(result, Exception) for result in results), results
+    assert calls >= 1
+
+
+def test_client_call_from_callback_is_refused(mock_server: type[_SlowHandler]) -> None:
+    inner = _client(lambda: "token")
+
+    def reentrant() -> str:
+        inner.list_recently_viewed()
+        return "token"
+
+    with pytest.raises(NblmAuthError, match="inside a CallbackTokenProvider callback"):
+        _client(reentrant).list_recently_viewed()
+    assert mock_server.max_in_flight == 0